@@ -0,0 +1,69 @@
+// Read-Heavy Cache Benchmark
+// Compares SharedCache (RwLock) with the same map behind a Mutex
+//
+// To run this benchmark: cargo bench --bench cache
+
+use std::collections::HashMap;
+use std::hint::black_box;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rustler::concurrent::SharedCache;
+
+const KEYS: u64 = 1_000;
+const LOOKUPS_PER_THREAD: u64 = 200_000;
+
+/// Runs `lookup` from `threads` threads at once, each doing
+/// `LOOKUPS_PER_THREAD` calls, and reports the wall-clock time.
+fn time<F>(label: &str, threads: usize, lookup: F) -> Duration
+where
+    F: Fn(u64) + Send + Sync + 'static,
+{
+    let lookup = Arc::new(lookup);
+    let start = Instant::now();
+    let handles: Vec<_> = (0..threads)
+        .map(|t| {
+            let lookup = Arc::clone(&lookup);
+            thread::spawn(move || {
+                for i in 0..LOOKUPS_PER_THREAD {
+                    lookup(black_box((i * 7 + t as u64) % KEYS));
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    let elapsed = start.elapsed();
+    println!("  {:<22} {:>10.3?}", label, elapsed);
+    elapsed
+}
+
+fn main() {
+    println!("=== Read-Heavy Cache Benchmark ===\n");
+
+    let rwlock_cache = SharedCache::new();
+    let mutex_cache = Arc::new(Mutex::new(HashMap::new()));
+    for key in 0..KEYS {
+        rwlock_cache.insert(key, key * 2);
+        mutex_cache.lock().unwrap().insert(key, key * 2);
+    }
+
+    for threads in [1, 2, 4, 8] {
+        println!("--- {} thread(s), {} lookups each ---", threads, LOOKUPS_PER_THREAD);
+        let cache = rwlock_cache.clone();
+        let rwlock = time("SharedCache (RwLock)", threads, move |key| {
+            black_box(cache.get(&key));
+        });
+        let cache = Arc::clone(&mutex_cache);
+        let mutex = time("Mutex<HashMap>", threads, move |key| {
+            black_box(cache.lock().unwrap().get(&key).copied());
+        });
+        println!("  Mutex / RwLock time: {:.2}x\n", mutex.as_secs_f64() / rwlock.as_secs_f64());
+    }
+
+    println!("An RwLock costs slightly more per call, so with one thread, or on a");
+    println!("single core, the Mutex can come out ahead. The RwLock pulls away once");
+    println!("several cores read at the same time, since the Mutex makes them queue.");
+}
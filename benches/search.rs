@@ -0,0 +1,57 @@
+// Substring Search Benchmark
+// Compares the hand-written KMP and Boyer-Moore searches with str::find
+//
+// To run this benchmark: cargo bench --bench search
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use rustler::text::search::{find_all_bm, find_all_kmp};
+
+/// Finds every (possibly overlapping) match using the standard library.
+fn find_all_std(haystack: &str, needle: &str) -> Vec<usize> {
+    let mut matches = Vec::new();
+    let mut start = 0;
+    while let Some(pos) = haystack[start..].find(needle) {
+        matches.push(start + pos);
+        // Step past the first char of the match so overlaps are found too
+        start += pos + haystack[start + pos..].chars().next().map_or(1, char::len_utf8);
+    }
+    matches
+}
+
+fn time<F: Fn() -> Vec<usize>>(label: &str, iterations: u32, f: F) -> Duration {
+    let start = Instant::now();
+    let mut found = 0;
+    for _ in 0..iterations {
+        found = black_box(f()).len();
+    }
+    let elapsed = start.elapsed() / iterations;
+    println!("  {:<12} {:>10.3?} per search ({} matches)", label, elapsed, found);
+    elapsed
+}
+
+fn main() {
+    println!("=== Substring Search Benchmark ===\n");
+
+    let sentence = "The quick brown fox jumps over the lazy dog while the cat watches. ";
+    let english = sentence.repeat(20_000);
+    let repetitive = "ab".repeat(500_000) + "abc";
+
+    let cases = [
+        ("English text, short needle", english.as_str(), "the"),
+        ("English text, long needle", english.as_str(), "over the lazy dog while"),
+        ("Repetitive text, rare needle", repetitive.as_str(), "ababc"),
+    ];
+
+    for (name, haystack, needle) in cases {
+        println!("--- {} ({} KB, needle {:?}) ---", name, haystack.len() / 1024, needle);
+        time("str::find", 20, || find_all_std(haystack, needle));
+        time("KMP", 20, || find_all_kmp(haystack, needle));
+        time("Boyer-Moore", 20, || find_all_bm(haystack, needle));
+        println!();
+    }
+
+    println!("Boyer-Moore shines with long needles because it can skip ahead;");
+    println!("KMP never backtracks, so it stays steady on repetitive input.");
+}
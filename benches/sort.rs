@@ -0,0 +1,83 @@
+// Sorting Benchmark
+// Compares the hand-written sorts with slice::sort and slice::sort_unstable,
+// and parallel merge sort with its single-threaded version
+//
+// To run this benchmark: cargo bench --bench sort
+
+use std::hint::black_box;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rustler::algorithms::sort::{heap_sort, insertion_sort, merge_sort, parallel_merge_sort, quick_sort};
+
+/// Deterministic pseudo-random numbers (xorshift) so every run sorts the
+/// same input.
+fn random_values(len: usize, mut seed: u64) -> Vec<u64> {
+    (0..len)
+        .map(|_| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed % 1_000_000
+        })
+        .collect()
+}
+
+fn time<F: Fn(&mut [u64])>(label: &str, input: &[u64], iterations: u32, sort: F) -> Duration {
+    let mut total = Duration::ZERO;
+    for _ in 0..iterations {
+        let mut values = input.to_vec();
+        let start = Instant::now();
+        sort(black_box(&mut values));
+        total += start.elapsed();
+        assert!(values.windows(2).all(|w| w[0] <= w[1]), "{} did not sort", label);
+    }
+    let elapsed = total / iterations;
+    println!("  {:<20} {:>10.3?} per sort", label, elapsed);
+    elapsed
+}
+
+fn main() {
+    println!("=== Sorting Benchmark ===\n");
+
+    let random = random_values(50_000, 42);
+    let sorted: Vec<u64> = (0..50_000).collect();
+    let reversed: Vec<u64> = (0..50_000).rev().collect();
+    let small = random_values(2_000, 7);
+
+    let cases = [
+        ("Random", &random),
+        ("Already sorted", &sorted),
+        ("Reversed", &reversed),
+    ];
+
+    for (name, input) in cases {
+        println!("--- {} ({} items) ---", name, input.len());
+        time("slice::sort", input, 10, |v| v.sort());
+        time("slice::sort_unstable", input, 10, |v| v.sort_unstable());
+        time("merge_sort", input, 10, merge_sort);
+        time("quick_sort", input, 10, quick_sort);
+        time("heap_sort", input, 10, heap_sort);
+        println!();
+    }
+
+    // Insertion sort is O(n²), so it only gets the small input
+    println!("--- Random, small ({} items) ---", small.len());
+    time("slice::sort", &small, 10, |v| v.sort());
+    time("insertion_sort", &small, 10, insertion_sort);
+    time("quick_sort", &small, 10, quick_sort);
+    println!();
+
+    // Parallel merge sort only splits large inputs, so give it a big one
+    let large = random_values(1_000_000, 99);
+    let cores = thread::available_parallelism().map_or(1, |n| n.get());
+    println!("--- Random, large ({} items, {} cores) ---", large.len(), cores);
+    let single = time("merge_sort", &large, 3, merge_sort);
+    let parallel = time("parallel_merge_sort", &large, 3, parallel_merge_sort);
+    println!("  speedup: {:.2}x", single.as_secs_f64() / parallel.as_secs_f64());
+    println!();
+
+    println!("The standard library sorts are tuned far beyond these, but");
+    println!("quick sort and heap sort should stay within a small factor.");
+    println!("Parallel merge sort can only beat merge sort with more than one core.");
+}
@@ -0,0 +1,30 @@
+// Compiles the example sources into the library for `rustler search`.
+// Writes $OUT_DIR/example_sources.rs, a table of (file name, source) with
+// an include_str! for every .rs file in examples/, which
+// src/catalog.rs includes.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn main() {
+    let examples = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join("examples");
+    // Watching the directory also catches examples being added or removed
+    println!("cargo:rerun-if-changed={}", examples.display());
+
+    let mut files: Vec<PathBuf> = fs::read_dir(&examples)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "rs"))
+        .collect();
+    files.sort();
+
+    let mut table = String::from("&[\n");
+    for path in files {
+        let name = path.file_stem().unwrap().to_string_lossy();
+        table.push_str(&format!("    ({:?}, include_str!({:?})),\n", name, path));
+    }
+    table.push_str("]\n");
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("example_sources.rs");
+    fs::write(out, table).unwrap();
+}
@@ -17,6 +17,7 @@
 
 /// The main function is the entry point of every Rust program.
 /// When you run a Rust program, execution starts here.
+#[allow(clippy::empty_line_after_doc_comments, clippy::print_literal)]
 fn main() {
     // println! is a macro (notice the exclamation mark)
     // Macros are like functions but they generate code at compile time
@@ -49,7 +49,9 @@ fn main() {
     println!("Byte value (u8): {}", byte_val);
     
     // Floating-point numbers
+    #[allow(clippy::approx_constant)]
     let float32: f32 = 3.14159;          // 32-bit floating point
+    #[allow(clippy::approx_constant)]
     let float64: f64 = 2.718281828459045; // 64-bit floating point (default)
     
     println!("32-bit float: {}", float32);
@@ -102,6 +104,7 @@ fn main() {
     
     // Rust can often infer types automatically
     let inferred_int = 42;        // Rust infers i32
+    #[allow(clippy::approx_constant)]
     let inferred_float = 3.14;    // Rust infers f64
     let inferred_string = "Hello"; // Rust infers &str (string slice)
     
@@ -101,6 +101,7 @@ fn main() {
     
     println!("\n--- Closures with Collections ---");
     
+    #[allow(clippy::useless_vec)]
     let words = vec!["hello", "world", "rust", "programming"];
     
     // Filter words with more than 4 characters
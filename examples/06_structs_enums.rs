@@ -3,6 +3,9 @@
 //
 // To run this example: cargo run --example 06_structs_enums
 
+use rustler::games::StateMachine;
+use rustler::shapes::Point2D;
+
 fn main() {
     println!("=== Structs and Enums in Rust ===\n");
     
@@ -73,10 +76,12 @@ fn main() {
     println!("White: ({}, {}, {})", white.0, white.1, white.2);
     println!("Red: ({}, {}, {})", red.0, red.1, red.2);
     
-    let origin = Point(0, 0);
-    let point = Point(3, 4);
-    println!("Origin: ({}, {})", origin.0, origin.1);
-    println!("Point: ({}, {})", point.0, point.1);
+    // Points use named fields instead, so `x` and `y` can't be mixed up
+    let origin = Point2D::origin();
+    let point = Point2D::new(3.0, 4.0);
+    println!("Origin: ({}, {})", origin.x, origin.y);
+    println!("Point: ({}, {})", point.x, point.y);
+    println!("Distance from origin: {}", origin.distance_to(&point));
     
     // === UNIT STRUCTS ===
     
@@ -254,6 +259,22 @@ fn main() {
     game.resume();
     game.end();
     
+    // The same rules as a transition table. Events the current state has
+    // no row for come back as errors instead of being printed by each method
+    let mut machine = StateMachine::builder(GameState::Menu, 0u32)
+        .on(GameState::Menu, GameEvent::Start).to(GameState::Playing)
+        .on(GameState::Playing, GameEvent::Pause).action(|pauses| *pauses += 1).to(GameState::Paused)
+        .on(GameState::Paused, GameEvent::Resume).to(GameState::Playing)
+        .on(GameState::Playing, GameEvent::End).to(GameState::GameOver)
+        .build();
+    for event in [GameEvent::Start, GameEvent::Pause, GameEvent::Pause, GameEvent::Resume, GameEvent::End] {
+        match machine.fire(event) {
+            Ok(state) => println!("{:?} -> {:?}", event, state),
+            Err(error) => println!("Rejected: {}", error),
+        }
+    }
+    println!("Paused {} time(s)", machine.context());
+    
     println!("\n=== Key Takeaways ===");
     println!("• Structs group related data together");
     println!("• Use impl blocks to define methods and associated functions");
@@ -276,7 +297,6 @@ struct Person {
 
 // Tuple structs
 struct Color(u8, u8, u8);
-struct Point(i32, i32);
 
 // Unit struct
 #[derive(Debug)]
@@ -356,7 +376,7 @@ enum Operation {
 }
 
 // State machine example
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 enum GameState {
     Menu,
     Playing,
@@ -364,6 +384,14 @@ enum GameState {
     GameOver,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum GameEvent {
+    Start,
+    Pause,
+    Resume,
+    End,
+}
+
 struct Game {
     state: GameState,
 }
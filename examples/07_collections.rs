@@ -5,6 +5,10 @@
 
 use std::collections::HashMap;
 
+use rustler::collections::Counter;
+use rustler::text::{csv, pad_center, truncate_with_ellipsis, TextProcessor};
+
+#[allow(clippy::vec_init_then_push)]
 fn main() {
     println!("=== Collections in Rust ===\n");
     
@@ -162,6 +166,16 @@ fn main() {
     let parts: Vec<&str> = text.split(',').collect();
     println!("Split by comma: {:?}", parts);
     
+    // Naive splitting breaks on quoted CSV fields that contain commas;
+    // the csv reader in the rustler library handles quoting properly
+    let csv_line = "Ferris,\"Crab, Rust mascot\",2015";
+    let naive: Vec<&str> = csv_line.split(',').collect();
+    println!("Naive CSV split: {:?}", naive);
+    if let Ok(records) = csv::parse(csv_line) {
+        let fields: Vec<&str> = records[0].iter().collect();
+        println!("Proper CSV parse: {:?}", fields);
+    }
+    
     // String searching
     let contains_rust = text.contains("Rust");
     println!("Contains 'Rust': {}", contains_rust);
@@ -191,6 +205,11 @@ fn main() {
         println!("  {}", byte);
     }
     
+    // Slicing with &text[..9] would panic here because byte 9 falls inside
+    // the crab emoji; char-aware helpers count characters instead
+    println!("Truncated: '{}'", truncate_with_ellipsis(text, 8));
+    println!("Padded:    '{}'", pad_center(text, 14, '*'));
+    
     // === HASHMAPS ===
     
     println!("\n--- HashMaps ---");
@@ -253,28 +272,29 @@ fn main() {
     
     // Word frequency counter
     let text = "the quick brown fox jumps over the lazy dog the fox is quick";
-    let mut word_count = HashMap::new();
-    
-    for word in text.split_whitespace() {
-        let count = word_count.entry(word).or_insert(0);
-        *count += 1;
-    }
+    // Counter wraps the `*map.entry(word).or_insert(0) += 1` idiom
+    let word_count: Counter<&str> = text.split_whitespace().collect();
     
     println!("Word frequencies:");
-    for (word, count) in &word_count {
+    for (word, count) in word_count.most_common(word_count.len()) {
         println!("  {}: {}", word, count);
     }
     
     // Finding most common word
-    let most_common = word_count
-        .iter()
-        .max_by_key(|&(_, count)| count)
-        .map(|(word, count)| (word, count));
-    
-    if let Some((word, count)) = most_common {
+    if let Some((word, count)) = word_count.most_common(1).first() {
         println!("Most common word: '{}' appears {} times", word, count);
     }
     
+    // Totals come from TextProcessor rather than being counted by hand
+    let stats = TextProcessor::new().analyze(text);
+    println!(
+        "{} words, {} characters, {:.0}% of the words distinct",
+        stats.words,
+        stats.chars,
+        stats.unique_word_ratio * 100.0
+    );
+    println!("Longest word: '{}'", stats.longest_word);
+    
     // Group students by grade ranges
     let all_grades = vec![
         ("Alice", 95), ("Bob", 87), ("Charlie", 92),
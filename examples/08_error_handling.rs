@@ -44,15 +44,18 @@ fn main() {
     let maybe_number = Some(10);
     
     // unwrap() - gets the value or panics
+    #[allow(clippy::unnecessary_literal_unwrap)]
     let value = maybe_number.unwrap();
     println!("Unwrapped value: {}", value);
     
     // unwrap_or() - gets the value or returns a default
     let no_value: Option<i32> = None;
+    #[allow(clippy::unnecessary_literal_unwrap)]
     let default_value = no_value.unwrap_or(0);
     println!("Value with default: {}", default_value);
     
     // unwrap_or_else() - gets the value or computes a default
+    #[allow(clippy::unnecessary_literal_unwrap)]
     let computed_default = no_value.unwrap_or_else(|| {
         println!("Computing default value");
         42
@@ -60,6 +63,7 @@ fn main() {
     println!("Computed default: {}", computed_default);
     
     // expect() - like unwrap but with custom panic message
+    #[allow(clippy::unnecessary_literal_unwrap)]
     let expected_value = maybe_number.expect("Expected a number but got None");
     println!("Expected value: {}", expected_value);
     
@@ -226,6 +230,7 @@ fn main() {
     
     println!("\n--- Option and Result Combinations ---");
     
+    #[allow(clippy::useless_vec)]
     let items = vec![
         Some("42"),
         Some("not_a_number"),
@@ -255,6 +260,7 @@ fn main() {
     }
     
     // Pattern 2: Collecting results
+    #[allow(clippy::useless_vec)]
     let strings = vec!["1", "2", "invalid", "4"];
     let results: Result<Vec<i32>, _> = strings
         .iter()
@@ -5,6 +5,10 @@
 
 use std::fmt::Display;
 
+use rustler::collections::Stack;
+use rustler::shapes::{largest_by_area, total_area, Canvas, Circle, Point2D, Rectangle, Shape};
+use rustler::text::TextProcessor;
+
 fn main() {
     println!("=== Traits and Generics in Rust ===\n");
     
@@ -45,13 +49,39 @@ fn main() {
         animal.speak();
     }
     
+    // Trait objects from the rustler library: each shape computes its own
+    // area, and the helpers work on any mix of them
+    let shapes: Vec<Box<dyn Shape>> = vec![
+        Box::new(Circle::new(1.5)),
+        Box::new(Rectangle::new(3.0, 2.0)),
+        Box::new(Rectangle::square(2.5)),
+    ];
+    
+    println!("Shapes:");
+    for shape in &shapes {
+        println!("  {} with area {:.2} and perimeter {:.2}",
+                 shape.name(), shape.area(), shape.perimeter());
+    }
+    println!("Total area: {:.2}", total_area(&shapes));
+    if let Some(largest) = largest_by_area(&shapes) {
+        println!("Largest shape: {} ({:.2})", largest.name(), largest.area());
+    }
+    
+    // The canvas only knows about `&dyn Shape`, yet draws each one correctly
+    let mut canvas = Canvas::new(24, 8);
+    canvas.draw(&Circle::at(Point2D::new(4.0, 4.0), 3.5));
+    canvas.set_brush('+');
+    canvas.draw(&Rectangle::at(Point2D::new(10.0, 1.0), 12.0, 6.0));
+    print!("{}", canvas.render());
+    
     // === DERIVED TRAITS ===
     
     println!("\n--- Derived Traits ---");
     
-    let point1 = Point { x: 3, y: 4 };
-    let point2 = Point { x: 3, y: 4 };
-    let point3 = Point { x: 1, y: 2 };
+    // Point2D derives Debug, Clone, Copy, PartialEq and Default
+    let point1 = Point2D::new(3.0, 4.0);
+    let point2 = Point2D::new(3.0, 4.0);
+    let point3 = Point2D::new(1.0, 2.0);
     
     // Debug trait
     println!("Point1: {:?}", point1);
@@ -60,9 +90,13 @@ fn main() {
     println!("Point1 == Point2: {}", point1 == point2);
     println!("Point1 == Point3: {}", point1 == point3);
     
-    // Clone trait
-    let point4 = point1.clone();
-    println!("Cloned point: {:?}", point4);
+    // Clone and Copy traits: Point2D is Copy, so assignment duplicates it
+    // and point1 stays usable. clone() is for types that aren't Copy
+    let point4 = point1;
+    println!("Copied point: {:?} (point1 is still {:?})", point4, point1);
+    let name = String::from("origin");
+    let name_copy = name.clone();
+    println!("Cloned name: {} (name is still {})", name_copy, name);
     
     // === BASIC GENERICS ===
     
@@ -71,6 +105,7 @@ fn main() {
     // Generic functions
     let int_pair = create_pair(5, 10);
     let string_pair = create_pair("hello".to_string(), "world".to_string());
+    #[allow(clippy::approx_constant)]
     let float_pair = create_pair(3.14, 2.71);
     
     println!("Int pair: {:?}", int_pair);
@@ -103,6 +138,7 @@ fn main() {
     // Display trait bound
     display_item(42);
     display_item("Hello, World!");
+    #[allow(clippy::approx_constant)]
     display_item(3.14159);
     
     // Multiple trait bounds
@@ -153,6 +189,7 @@ fn main() {
     
     println!("\n--- Generic Implementations ---");
     
+    // Stack<T> lives in the rustler library (src/collections/stack.rs)
     let mut stack = Stack::new();
     stack.push(1);
     stack.push(2);
@@ -169,19 +206,25 @@ fn main() {
     string_stack.push("second");
     string_stack.push("third");
     
-    println!("String stack size: {}", string_stack.size());
+    println!("String stack size: {}", string_stack.len());
+    println!("String stack (top first): {}", string_stack);
+    println!("Top of string stack: {:?}", string_stack.peek());
     
     // === OPERATOR OVERLOADING ===
     
     println!("\n--- Operator Overloading ---");
     
-    let p1 = Point { x: 1, y: 2 };
-    let p2 = Point { x: 3, y: 4 };
+    // Point2D implements Add, Sub and Mul<f64> (see src/shapes/point.rs)
+    let p1 = Point2D::new(1.0, 2.0);
+    let p2 = Point2D::new(3.0, 4.0);
     let p3 = p1 + p2; // Using Add trait
     
-    println!("p1: {:?}", p1);
-    println!("p2: {:?}", p2);
-    println!("p1 + p2 = {:?}", p3);
+    println!("p1: {}", p1);
+    println!("p2: {}", p2);
+    println!("p1 + p2 = {}", p3);
+    println!("p2 - p1 = {}", p2 - p1); // Using Sub trait
+    println!("p1 * 2.0 = {}", p1 * 2.0); // Using Mul<f64> trait
+    println!("Midpoint of p1 and p2: {}", p1.midpoint(&p2));
     
     // === DEFAULT IMPLEMENTATIONS ===
     
@@ -260,12 +303,6 @@ struct Bird {
     species: String,
 }
 
-#[derive(Debug, Clone, PartialEq, Copy)]
-struct Point {
-    x: i32,
-    y: i32,
-}
-
 #[derive(Debug)]
 struct Container<T> {
     value: T,
@@ -294,11 +331,6 @@ struct Counter {
     current: usize,
 }
 
-#[derive(Debug)]
-struct Stack<T> {
-    items: Vec<T>,
-}
-
 // === TRAIT IMPLEMENTATIONS ===
 
 impl Animal for Dog {
@@ -331,18 +363,6 @@ impl Animal for Bird {
     }
 }
 
-// Operator overloading using Add trait
-impl std::ops::Add for Point {
-    type Output = Point;
-    
-    fn add(self, other: Point) -> Point {
-        Point {
-            x: self.x + other.x,
-            y: self.y + other.y,
-        }
-    }
-}
-
 // Generic implementation
 impl<T> Container<T> {
     fn new(value: T) -> Container<T> {
@@ -384,7 +404,9 @@ impl Summary for ShoppingList {
 
 impl Summary for Article {
     fn summarize(&self) -> String {
-        format!("{}, by {} ({} chars)", self.title, self.author, self.content.len())
+        // content.len() would count bytes, not characters
+        let stats = TextProcessor::new().analyze(&self.content);
+        format!("{}, by {} ({} words, {} chars)", self.title, self.author, stats.words, stats.chars)
     }
 }
 
@@ -422,26 +444,6 @@ impl Iterator for Counter {
     }
 }
 
-impl<T> Stack<T> {
-    fn new() -> Stack<T> {
-        Stack {
-            items: Vec::new(),
-        }
-    }
-    
-    fn push(&mut self, item: T) {
-        self.items.push(item);
-    }
-    
-    fn pop(&mut self) -> Option<T> {
-        self.items.pop()
-    }
-    
-    fn size(&self) -> usize {
-        self.items.len()
-    }
-}
-
 // === GENERIC FUNCTIONS ===
 
 fn create_pair<T>(first: T, second: T) -> (T, T) {
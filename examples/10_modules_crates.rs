@@ -81,10 +81,15 @@ use std::env;
 use shapes::Circle;
 use shapes::rectangle::Rectangle;
 use math_utils::{add, multiply, divide};
+use rustler::io::TempFile;
+use rustler::log_lite::Logger;
 
 fn main() {
     println!("=== Modules and Crates in Rust ===\n");
     
+    // Problems are logged to stderr; RUSTLER_LOG=error hides the warnings
+    let log = Logger::from_env();
+    
     // === MODULE BASICS ===
     
     println!("--- Module Basics ---");
@@ -130,12 +135,12 @@ fn main() {
     // use shapes::*;
     
     // We can create aliases
-    type Point = (f64, f64);
-    let origin: Point = (0.0, 0.0);
-    let point: Point = (3.0, 4.0);
+    type Point = rustler::shapes::Point2D;
+    let origin = Point::origin();
+    let point = Point::new(3.0, 4.0);
     
-    println!("Origin: {:?}", origin);
-    println!("Point: {:?}", point);
+    println!("Origin: {}", origin);
+    println!("Point: {}", point);
     
     // === STANDARD LIBRARY MODULES ===
     
@@ -159,42 +164,45 @@ fn main() {
     // Environment variables
     match env::var("HOME") {
         Ok(home) => println!("Home directory: {}", home),
-        Err(_) => println!("HOME environment variable not set"),
+        Err(_) => log.warn("env", "HOME environment variable not set"),
     }
     
     // === FILE SYSTEM OPERATIONS ===
     
     println!("\n--- File System Operations ---");
     
-    // Create a temporary file for demonstration
+    // Create a temporary file for demonstration. TempFile picks a unique
+    // name in the system temp directory (not always /tmp, e.g. on Windows)
+    // and deletes the file when it goes out of scope
+    let temp_file = TempFile::with_suffix("modules", ".txt").expect("cannot create a temporary file");
     let content = "Hello from Rust!\nThis is a test file.\nModules are awesome!";
     
-    match fs::write("/tmp/test_file.txt", content) {
+    match fs::write(&temp_file, content) {
         Ok(()) => {
             println!("File written successfully");
             
             // Read the file back
-            match fs::read_to_string("/tmp/test_file.txt") {
+            match fs::read_to_string(&temp_file) {
                 Ok(file_content) => {
                     println!("File contents:");
                     for line in file_content.lines() {
                         println!("  {}", line);
                     }
                 },
-                Err(e) => println!("Error reading file: {}", e),
+                Err(e) => log.error("fs", format!("Error reading file: {}", e)),
             }
             
             // Get file metadata
-            match fs::metadata("/tmp/test_file.txt") {
+            match fs::metadata(&temp_file) {
                 Ok(metadata) => {
                     println!("File size: {} bytes", metadata.len());
                     println!("Is file: {}", metadata.is_file());
                     println!("Is directory: {}", metadata.is_dir());
                 },
-                Err(e) => println!("Error getting metadata: {}", e),
+                Err(e) => log.error("fs", format!("Error getting metadata: {}", e)),
             }
         },
-        Err(e) => println!("Error writing file: {}", e),
+        Err(e) => log.error("fs", format!("Error writing file: {}", e)),
     }
     
     // === PRIVACY AND VISIBILITY ===
@@ -3,6 +3,8 @@
 //
 // To run this example: cargo run --example 11_stdlib_features
 // To run with arguments: cargo run --example 11_stdlib_features -- arg1 arg2 arg3
+// It only asks for input when stdin is a terminal; pass --non-interactive to
+// skip the questions anyway
 
 use std::env;
 use std::fs::{self, File, OpenOptions};
@@ -15,9 +17,22 @@ use std::thread;
 use chrono::{DateTime, Local, Utc};
 use serde::{Deserialize, Serialize};
 
+// Our own library crate
+use rustler::cli::prompt;
+use rustler::concurrent::EventBus;
+use rustler::games::{game, Game, GameEvent};
+use rustler::io::TempDir;
+use rustler::log_lite::Logger;
+use rustler::text::{csv, glob, json, unified_diff};
+
 fn main() {
     println!("=== Standard Library Features ===\n");
     
+    // Failures go to a logger on stderr instead of mixing with the output.
+    // RUSTLER_LOG=error hides the warnings; RUSTLER_LOG=warn,env=error
+    // hides them for the env target only
+    let log = Logger::from_env();
+    
     // === COMMAND LINE ARGUMENTS ===
     
     println!("--- Command Line Arguments ---");
@@ -42,7 +57,7 @@ fn main() {
     // Get environment variables
     match env::var("HOME") {
         Ok(home) => println!("Home directory: {}", home),
-        Err(_) => println!("HOME not set"),
+        Err(_) => log.warn("env", "HOME not set"),
     }
     
     match env::var("PATH") {
@@ -50,14 +65,14 @@ fn main() {
             let paths: Vec<&str> = path.split(':').take(3).collect();
             println!("First 3 PATH entries: {:?}", paths);
         },
-        Err(_) => println!("PATH not set"),
+        Err(_) => log.warn("env", "PATH not set"),
     }
     
     // Set environment variable for this process
     env::set_var("RUST_EXAMPLE", "Hello from Rust!");
     match env::var("RUST_EXAMPLE") {
         Ok(value) => println!("RUST_EXAMPLE: {}", value),
-        Err(_) => println!("Failed to set RUST_EXAMPLE"),
+        Err(_) => log.warn("env", "Failed to set RUST_EXAMPLE"),
     }
     
     // List some environment variables
@@ -70,30 +85,36 @@ fn main() {
     
     println!("\n--- File I/O Basics ---");
     
-    let filename = "/tmp/rust_example.txt";
+    // Every file this example writes goes in one temporary directory with
+    // a unique name, so parallel runs don't collide and it works on any OS.
+    // The directory and its contents are deleted when `workspace` is dropped
+    let workspace = TempDir::new("stdlib").expect("cannot create a temporary directory");
+    println!("Working in {}", workspace.path().display());
+    
+    let filename = workspace.join("rust_example.txt");
     let content = "Hello, File I/O!\nThis is line 2.\nThis is line 3.\n";
     
     // Write to file
-    match fs::write(filename, content) {
+    match fs::write(&filename, content) {
         Ok(()) => println!("File written successfully"),
-        Err(e) => println!("Error writing file: {}", e),
+        Err(e) => log.error("fs", format!("Error writing file: {}", e)),
     }
     
     // Read entire file
-    match fs::read_to_string(filename) {
+    match fs::read_to_string(&filename) {
         Ok(contents) => {
             println!("File contents:");
             for line in contents.lines() {
                 println!("  {}", line);
             }
         },
-        Err(e) => println!("Error reading file: {}", e),
+        Err(e) => log.error("fs", format!("Error reading file: {}", e)),
     }
     
     // Read file as bytes
-    match fs::read(filename) {
+    match fs::read(&filename) {
         Ok(bytes) => println!("File size: {} bytes", bytes.len()),
-        Err(e) => println!("Error reading file as bytes: {}", e),
+        Err(e) => log.error("fs", format!("Error reading file as bytes: {}", e)),
     }
     
     // === ADVANCED FILE I/O ===
@@ -101,10 +122,10 @@ fn main() {
     println!("\n--- Advanced File I/O ---");
     
     // Using File struct with more control
-    let advanced_filename = "/tmp/rust_advanced.txt";
+    let advanced_filename = workspace.join("rust_advanced.txt");
     
     // Write using File and BufWriter
-    match File::create(advanced_filename) {
+    match File::create(&advanced_filename) {
         Ok(file) => {
             let mut writer = BufWriter::new(file);
             writeln!(writer, "Line 1 from BufWriter").unwrap();
@@ -113,38 +134,44 @@ fn main() {
             // BufWriter is automatically flushed when dropped
             println!("Advanced file written with BufWriter");
         },
-        Err(e) => println!("Error creating file: {}", e),
+        Err(e) => log.error("fs", format!("Error creating file: {}", e)),
     }
     
     // Read using BufReader
-    match File::open(advanced_filename) {
+    match File::open(&advanced_filename) {
         Ok(file) => {
             let reader = BufReader::new(file);
             println!("Reading with BufReader:");
             for (i, line) in reader.lines().enumerate() {
                 match line {
                     Ok(content) => println!("  Line {}: {}", i + 1, content),
-                    Err(e) => println!("  Error reading line: {}", e),
+                    Err(e) => log.error("fs", format!("Error reading line: {}", e)),
                 }
             }
         },
-        Err(e) => println!("Error opening file: {}", e),
+        Err(e) => log.error("fs", format!("Error opening file: {}", e)),
     }
     
     // Append to file
-    match OpenOptions::new().create(true).append(true).open(advanced_filename) {
+    let before_append = fs::read_to_string(&advanced_filename).unwrap_or_default();
+    match OpenOptions::new().create(true).append(true).open(&advanced_filename) {
         Ok(mut file) => {
             writeln!(file, "Appended line").unwrap();
             println!("Line appended to file");
         },
-        Err(e) => println!("Error appending to file: {}", e),
+        Err(e) => log.error("fs", format!("Error appending to file: {}", e)),
     }
     
+    // Show what changed using the line-based diff from the rustler library
+    let after_append = fs::read_to_string(&advanced_filename).unwrap_or_default();
+    println!("Changes made by the append:");
+    print!("{}", unified_diff("before", "after", &before_append, &after_append, 1));
+    
     // === FILE METADATA ===
     
     println!("\n--- File Metadata ---");
     
-    match fs::metadata(filename) {
+    match fs::metadata(&filename) {
         Ok(metadata) => {
             println!("File metadata:");
             println!("  Size: {} bytes", metadata.len());
@@ -156,30 +183,30 @@ fn main() {
                 println!("  Modified: {:?}", modified);
             }
         },
-        Err(e) => println!("Error getting metadata: {}", e),
+        Err(e) => log.error("fs", format!("Error getting metadata: {}", e)),
     }
     
     // === DIRECTORY OPERATIONS ===
     
     println!("\n--- Directory Operations ---");
     
-    let dir_path = "/tmp/rust_example_dir";
+    let dir_path = workspace.join("rust_example_dir");
     
     // Create directory
-    match fs::create_dir_all(dir_path) {
-        Ok(()) => println!("Directory created: {}", dir_path),
-        Err(e) => println!("Error creating directory: {}", e),
+    match fs::create_dir_all(&dir_path) {
+        Ok(()) => println!("Directory created: {}", dir_path.display()),
+        Err(e) => log.error("fs", format!("Error creating directory: {}", e)),
     }
     
     // Create files in directory
     for i in 1..=3 {
-        let file_path = format!("{}/file{}.txt", dir_path, i);
+        let file_path = dir_path.join(format!("file{}.txt", i));
         let file_content = format!("This is file number {}\n", i);
         fs::write(&file_path, file_content).unwrap();
     }
     
     // List directory contents
-    match fs::read_dir(dir_path) {
+    match fs::read_dir(&dir_path) {
         Ok(entries) => {
             println!("Directory contents:");
             for entry in entries {
@@ -189,11 +216,23 @@ fn main() {
                         let name = path.file_name().unwrap().to_str().unwrap();
                         println!("  {}", name);
                     },
-                    Err(e) => println!("  Error reading entry: {}", e),
+                    Err(e) => log.error("fs", format!("Error reading entry: {}", e)),
                 }
             }
         },
-        Err(e) => println!("Error reading directory: {}", e),
+        Err(e) => log.error("fs", format!("Error reading directory: {}", e)),
+    }
+    
+    // Filter directory entries with a wildcard pattern
+    let pattern = "file[12].txt";
+    if let Ok(entries) = fs::read_dir(&dir_path) {
+        let mut matching: Vec<String> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .filter(|name| glob::matches(pattern, name))
+            .collect();
+        matching.sort();
+        println!("Files matching '{}': {:?}", pattern, matching);
     }
     
     // === STRING FORMATTING ===
@@ -237,7 +276,7 @@ fn main() {
         Ok(duration) => {
             println!("Seconds since Unix epoch: {}", duration.as_secs());
         },
-        Err(e) => println!("SystemTime before Unix epoch: {}", e),
+        Err(e) => log.error("time", format!("SystemTime before Unix epoch: {}", e)),
     }
     
     // Measuring elapsed time
@@ -265,11 +304,11 @@ fn main() {
             println!("  stderr: {}", String::from_utf8_lossy(&output.stderr));
             println!("  status: {}", output.status);
         },
-        Err(e) => println!("Error executing command: {}", e),
+        Err(e) => log.error("process", format!("Error executing command: {}", e)),
     }
     
-    // List files using ls command
-    match Command::new("ls").arg("-la").arg("/tmp").output() {
+    // List the files this example wrote, using the ls command
+    match Command::new("ls").arg("-la").arg(workspace.path()).output() {
         Ok(output) => {
             if output.status.success() {
                 let stdout = String::from_utf8_lossy(&output.stdout);
@@ -279,7 +318,7 @@ fn main() {
                 }
             }
         },
-        Err(e) => println!("Error listing directory: {}", e),
+        Err(e) => log.error("process", format!("Error listing directory: {}", e)),
     }
     
     // === SERIALIZATION WITH EXTERNAL CRATES ===
@@ -309,42 +348,122 @@ fn main() {
             println!("{}", json);
             
             // Write JSON to file
-            let json_file = "/tmp/person.json";
-            fs::write(json_file, &json).unwrap();
-            println!("JSON written to {}", json_file);
+            let json_file = workspace.join("person.json");
+            fs::write(&json_file, &json).unwrap();
+            println!("JSON written to {}", json_file.display());
             
             // Read and deserialize JSON
-            match fs::read_to_string(json_file) {
+            match fs::read_to_string(&json_file) {
                 Ok(json_content) => {
                     match serde_json::from_str::<Person>(&json_content) {
                         Ok(deserialized_person) => {
                             println!("Deserialized from JSON: {:?}", deserialized_person);
                         },
-                        Err(e) => println!("Error deserializing JSON: {}", e),
+                        Err(e) => log.error("json", format!("Error deserializing JSON: {}", e)),
+                    }
+                    
+                    // The same document through the hand-written parser in
+                    // rustler::text::json, which shows what serde does underneath
+                    match json::Value::parse(&json_content) {
+                        Ok(value) => {
+                            let hobbies = value.get("hobbies").and_then(json::Value::as_array);
+                            println!("Hand-parsed: name = {}, {} hobbies",
+                                     value.get("name").and_then(json::Value::as_str).unwrap_or("?"),
+                                     hobbies.map_or(0, |h| h.len()));
+                        },
+                        Err(e) => log.error("json", format!("Hand-written parser error: {}", e)),
                     }
                 },
-                Err(e) => println!("Error reading JSON file: {}", e),
+                Err(e) => log.error("json", format!("Error reading JSON file: {}", e)),
             }
         },
-        Err(e) => println!("Error serializing to JSON: {}", e),
+        Err(e) => log.error("json", format!("Error serializing to JSON: {}", e)),
+    }
+    
+    // The same derives work for CSV: rustler::text::csv matches header
+    // names to struct fields, so rows never need splitting by hand
+    #[derive(Serialize, Deserialize, Debug)]
+    struct City {
+        name: String,
+        country: String,
+        population: u32,
+    }
+    
+    let cities = "name,country,population\n\"Washington, D.C.\",USA,689545\nLisbon,Portugal,545923\n";
+    match csv::Reader::new(cities.as_bytes()).deserialize::<City>().collect::<Result<Vec<_>, _>>() {
+        Ok(cities) => {
+            for city in &cities {
+                println!("CSV row: {:?}", city);
+            }
+            let mut writer = csv::Writer::new(Vec::new());
+            for city in cities.iter().filter(|city| city.population > 600_000) {
+                writer.serialize(city).unwrap();
+            }
+            print!("Written back as CSV:\n{}", String::from_utf8_lossy(&writer.into_inner()));
+        },
+        Err(e) => log.error("csv", format!("Error reading CSV: {}", e)),
+    }
+    
+    // === SAVING GAME STATE ===
+    
+    println!("\n--- Saving Game State ---");
+    
+    // Game::save writes the state machine's state, the score and the event
+    // history as versioned JSON; Game::load checks the version first
+    let mut game = Game::new();
+    // The game announces what it accepts on an EventBus; this side just
+    // listens, so the game needs no idea how it is being shown
+    let bus = EventBus::new();
+    let announcements = bus.subscribe(game::EVENT_TOPIC, 8);
+    game.publish_to(bus);
+    game.handle(GameEvent::Start).unwrap();
+    game.add_points(120);
+    game.handle(GameEvent::Pause).unwrap();
+    while let Some(event) = announcements.try_recv() {
+        println!("Game event: {:?}", event);
+    }
+    
+    let save_file = workspace.join("rustler_game.json");
+    match game.save(&save_file).and_then(|_| Game::load(&save_file)) {
+        Ok(loaded) => println!("Reloaded {:?}", loaded),
+        Err(e) => log.error("game", format!("Save failed: {}", e)),
     }
     
     // === USER INPUT ===
     
     println!("\n--- User Input ---");
     
-    println!("Enter your name (or press Enter to skip): ");
-    let mut input = String::new();
-    match io::stdin().read_line(&mut input) {
-        Ok(_) => {
-            let input = input.trim();
-            if !input.is_empty() {
-                println!("Hello, {}!", input);
-            } else {
-                println!("No input provided");
-            }
-        },
-        Err(e) => println!("Error reading input: {}", e),
+    // read_line waits until a whole line arrives. Under CI or a script,
+    // stdin may be a pipe that nobody ever writes to, and the example
+    // would hang, so only ask when stdin is a terminal. --non-interactive
+    // skips the questions even then
+    let non_interactive = args.iter().any(|arg| arg == "--non-interactive");
+    if non_interactive || !prompt::is_interactive() {
+        println!("Not asking for input: stdin is not a terminal or --non-interactive was given");
+    } else {
+        println!("Enter your name (or press Enter to skip): ");
+        let mut input = String::new();
+        match io::stdin().read_line(&mut input) {
+            // Ok(0) means end of input, such as Ctrl-D
+            Ok(0) => println!("No input provided"),
+            Ok(_) => {
+                let input = input.trim();
+                if !input.is_empty() {
+                    println!("Hello, {}!", input);
+                } else {
+                    println!("No input provided");
+                }
+            },
+            Err(e) => log.error("stdin", format!("Error reading input: {}", e)),
+        }
+        
+        // The library's prompt helpers wrap that pattern: read_parsed asks
+        // again until the answer parses, and gives None at end of input
+        match prompt::read_parsed::<u8>("Pick a number from 0 to 255: ") {
+            Ok(Some(n)) => println!("{} in binary is {:08b}", n, n),
+            Ok(None) => println!("No number then"),
+            Err(e) => log.error("stdin", format!("Error reading input: {}", e)),
+        }
     }
     
     // === RANDOM NUMBERS (using standard library) ===
@@ -374,18 +493,18 @@ fn main() {
     
     println!("\n--- Cleanup ---");
     
-    // Remove files and directories we created
-    let _ = fs::remove_file(filename);
-    let _ = fs::remove_file(advanced_filename);
-    let _ = fs::remove_file("/tmp/person.json");
-    let _ = fs::remove_dir_all(dir_path);
-    println!("Cleaned up temporary files and directories");
+    // Dropping the TempDir removes everything we created; this would also
+    // happen automatically at the end of main, or if the program panicked
+    let workspace_path = workspace.path().to_path_buf();
+    drop(workspace);
+    println!("Cleaned up temporary files and directories (still there: {})", workspace_path.exists());
     
     println!("\n=== Key Takeaways ===");
     println!("• std::env provides access to environment variables and arguments");
     println!("• std::fs offers comprehensive file and directory operations");
     println!("• std::io provides traits and utilities for input/output operations");
     println!("• BufReader/BufWriter improve performance for many small reads/writes");
+    println!("• Temporary files belong in std::env::temp_dir(); Drop can clean them up (RAII)");
     println!("• std::process allows executing external commands");
     println!("• External crates like serde enable powerful serialization");
     println!("• std::time provides system time and duration measurements");
@@ -4,6 +4,9 @@
 // To run this example: cargo run --example 12_testing
 // To run the tests: cargo test test_in_testing_example
 
+// TextProcessor lives in the rustler library so other examples can share it
+use rustler::text::TextProcessor;
+
 fn main() {
     println!("=== Testing in Rust ===\n");
     
@@ -44,6 +47,10 @@ fn main() {
     println!("  Is palindrome: {}", text_processor.is_palindrome("hello"));
     println!("  Reverse: '{}'", text_processor.reverse(text));
     
+    let paragraph = "Rust is fast. It is also safe. Many people enjoy writing it.";
+    println!("  Readability of '{}':", paragraph);
+    println!("    {}", text_processor.readability(paragraph));
+    
     // Demonstrate geometric calculations
     let rect = Rectangle::new(5.0, 3.0);
     println!("\nRectangle operations:");
@@ -80,7 +87,7 @@ fn main() {
 // === CODE TO BE TESTED ===
 
 /// A simple calculator struct
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct Calculator;
 
 #[derive(Debug, PartialEq)]
@@ -114,44 +121,6 @@ impl Calculator {
     }
 }
 
-/// Text processing utilities
-pub struct TextProcessor;
-
-impl TextProcessor {
-    pub fn new() -> Self {
-        TextProcessor
-    }
-    
-    pub fn count_words(&self, text: &str) -> usize {
-        text.split_whitespace().count()
-    }
-    
-    pub fn is_palindrome(&self, text: &str) -> bool {
-        let cleaned: String = text.chars()
-            .filter(|c| c.is_alphanumeric())
-            .map(|c| c.to_lowercase().to_string())
-            .collect();
-        cleaned == cleaned.chars().rev().collect::<String>()
-    }
-    
-    pub fn reverse(&self, text: &str) -> String {
-        text.chars().rev().collect()
-    }
-    
-    pub fn capitalize_words(&self, text: &str) -> String {
-        text.split_whitespace()
-            .map(|word| {
-                let mut chars = word.chars();
-                match chars.next() {
-                    None => String::new(),
-                    Some(first) => first.to_uppercase().collect::<String>() + &chars.collect::<String>().to_lowercase(),
-                }
-            })
-            .collect::<Vec<_>>()
-            .join(" ")
-    }
-}
-
 /// Rectangle for geometric calculations
 #[derive(Debug)]
 pub struct Rectangle {
@@ -385,7 +354,7 @@ mod test_in_testing_example {
     
     #[test]
     fn test_floating_point_equality() {
-        let a = 0.1 + 0.2;
+        let a: f64 = 0.1 + 0.2;
         let b = 0.3;
         
         // Don't do this - floating point precision issues
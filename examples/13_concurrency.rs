@@ -7,6 +7,13 @@ use std::sync::{Arc, Mutex, mpsc};
 use std::thread;
 use std::time::Duration;
 
+use rustler::collections::Queue;
+use rustler::concurrent::{
+    parallel_map, BoundedBuffer, CancellationToken, EventBus, Metric, OutputOrder, Pipeline, RateLimiter,
+    Scheduler, ThreadPool,
+};
+use rustler::term::ProgressBar;
+
 fn main() {
     println!("=== Concurrency in Rust ===\n");
     
@@ -130,17 +137,34 @@ fn main() {
         println!("  {}", msg);
     }
     
+    // === PIPELINES ===
+    
+    println!("\n--- Pipelines ---");
+    
+    // Pipeline wires several groups of workers together with channels:
+    // each stage's workers share one receiver and send to the next stage
+    let lines = vec!["3 apples", "banana", "12 pears", "7 plums", "no count"];
+    let counts = Pipeline::new(lines)
+        .filter_map(2, |line| line.split_whitespace().next()?.parse::<u32>().ok())
+        .map(3, |count| count * 2)
+        .collect(OutputOrder::Ordered);
+    println!("Doubled counts, in input order: {:?}", counts);
+    
     // === SHARED STATE WITH MUTEX ===
     
     println!("\n--- Shared State with Mutex ---");
     
-    let counter = Arc::new(Mutex::new(0));
+    const THREADS: usize = 5;
+    const INCREMENTS: u64 = 100_000;
+    
+    let counter = Arc::new(Mutex::new(0u64));
     let mut handles = vec![];
+    let start = std::time::Instant::now();
     
-    for i in 0..5 {
+    for i in 0..THREADS {
         let counter = Arc::clone(&counter);
         let handle = thread::spawn(move || {
-            for _ in 0..1000 {
+            for _ in 0..INCREMENTS {
                 let mut num = counter.lock().unwrap();
                 *num += 1;
             }
@@ -153,30 +177,69 @@ fn main() {
     for handle in handles {
         handle.join().unwrap();
     }
+    let mutex_time = start.elapsed();
+    
+    println!("Final counter value: {} (took {:?})", *counter.lock().unwrap(), mutex_time);
+    
+    // A plain counter does not need a lock at all: an atomic integer can be
+    // incremented by many threads at once without losing updates
+    let metric = Arc::new(Metric::new());
+    let start = std::time::Instant::now();
+    let handles: Vec<_> = (0..THREADS)
+        .map(|_| {
+            let metric = Arc::clone(&metric);
+            thread::spawn(move || {
+                for _ in 0..INCREMENTS {
+                    metric.incr();
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    let atomic_time = start.elapsed();
     
-    println!("Final counter value: {}", *counter.lock().unwrap());
+    println!("Atomic metric value: {} (took {:?})", metric.get(), atomic_time);
+    println!(
+        "The Mutex version took {:.1}x as long as the atomic one",
+        mutex_time.as_secs_f64() / atomic_time.as_secs_f64()
+    );
     
     // === WORKER POOL PATTERN ===
     
     println!("\n--- Worker Pool Pattern ---");
     
-    let (job_tx, job_rx) = mpsc::channel();
-    let job_rx = Arc::new(Mutex::new(job_rx));
+    // A job says what to do; the queue holds them in arrival order
+    struct Job {
+        id: u32,
+        work: Duration,
+    }
+    
+    // Every job is queued before the workers start, so a worker that finds
+    // the queue empty knows there is nothing left and can stop
+    let jobs: Queue<Job> = (1..=6)
+        .map(|id| Job { id, work: Duration::from_millis(100 * id as u64) })
+        .collect();
+    println!("Queued {} jobs", jobs.len());
+    let jobs = Arc::new(Mutex::new(jobs));
     
     // Create worker threads
     let mut workers = vec![];
     for id in 0..3 {
-        let rx = Arc::clone(&job_rx);
+        let jobs = Arc::clone(&jobs);
         let worker = thread::spawn(move || {
             loop {
-                let job = rx.lock().unwrap().recv();
+                // The lock is released at the end of this statement, so
+                // other workers can take jobs while this one works
+                let job = jobs.lock().unwrap().dequeue();
                 match job {
-                    Ok(job_id) => {
-                        println!("Worker {} processing job {}", id, job_id);
-                        thread::sleep(Duration::from_millis(500)); // Simulate work
-                        println!("Worker {} completed job {}", id, job_id);
+                    Some(job) => {
+                        println!("Worker {} processing job {}", id, job.id);
+                        thread::sleep(job.work); // Simulate work
+                        println!("Worker {} completed job {}", id, job.id);
                     },
-                    Err(_) => {
+                    None => {
                         println!("Worker {} shutting down", id);
                         break;
                     }
@@ -186,58 +249,73 @@ fn main() {
         workers.push(worker);
     }
     
-    // Send jobs to workers
-    for job_id in 1..=6 {
-        job_tx.send(job_id).unwrap();
-    }
-    
-    // Close the channel to signal workers to shut down
-    drop(job_tx);
-    
     // Wait for all workers to finish
     for worker in workers {
         worker.join().unwrap();
     }
     
+    // The jobs above are fire-and-forget: nothing comes back from them.
+    // The library's ThreadPool packages the same pattern, and `submit`
+    // returns a JobHandle that yields each job's result, or its panic
+    let pool = ThreadPool::new(3);
+    let handles: Vec<_> = (1..=6)
+        .map(|job_id| {
+            pool.submit(move || {
+                if job_id == 4 {
+                    panic!("job {} failed", job_id);
+                }
+                thread::sleep(Duration::from_millis(50));
+                job_id * 10
+            })
+        })
+        .collect();
+    println!("Jobs waiting in the pool: {}", pool.queued());
+    for (job_id, handle) in (1..).zip(handles) {
+        match handle.wait() {
+            Ok(result) => println!("Job {} returned {}", job_id, result),
+            Err(err) => println!("Job {} failed: {}", job_id, err),
+        }
+    }
+    if pool.shutdown(Duration::from_secs(2)) {
+        println!("Pool shut down cleanly");
+    }
+    
     // === CONCURRENT DATA PROCESSING ===
     
     println!("\n--- Concurrent Data Processing ---");
     
     let data = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
-    let data = Arc::new(data);
-    let results = Arc::new(Mutex::new(vec![]));
-    
-    let mut handles = vec![];
     let chunk_size = 3;
     
-    for start in (0..data.len()).step_by(chunk_size) {
-        let data = Arc::clone(&data);
-        let results = Arc::clone(&results);
-        
-        let handle = thread::spawn(move || {
-            let end = std::cmp::min(start + chunk_size, data.len());
-            let chunk_sum: i32 = data[start..end].iter().sum();
-            
-            println!("Thread processing chunk [{}, {}): sum = {}", start, end, chunk_sum);
-            
-            // Add result to shared results vector
-            let mut results = results.lock().unwrap();
-            results.push(chunk_sum);
-        });
-        
-        handles.push(handle);
-    }
-    
-    // Wait for all processing to complete
-    for handle in handles {
-        handle.join().unwrap();
-    }
+    // parallel_map runs each chunk on its own scoped thread and returns the
+    // results in input order, so there is no Arc<Mutex<Vec>> to collect into
+    let chunks: Vec<&[i32]> = data.chunks(chunk_size).collect();
+    let chunk_sums = parallel_map(&chunks, 1, |chunk| {
+        let chunk_sum: i32 = chunk.iter().sum();
+        println!("Thread processing chunk {:?}: sum = {}", chunk, chunk_sum);
+        chunk_sum
+    });
     
-    let final_results = results.lock().unwrap();
-    let total_sum: i32 = final_results.iter().sum();
-    println!("Chunk sums: {:?}", *final_results);
+    let total_sum: i32 = chunk_sums.iter().sum();
+    println!("Chunk sums: {:?}", chunk_sums);
     println!("Total sum: {}", total_sum);
     
+    // Mapping item by item works the same way
+    let squares = parallel_map(&data, chunk_size, |n| n * n);
+    println!("Squares: {:?}", squares);
+    
+    // A ProgressBar's methods take &self and it counts with an atomic, so
+    // every worker can update the same bar without a Mutex around it
+    let work: Vec<u64> = (1..=40).collect();
+    let bar = ProgressBar::new(work.len() as u64).label("Hashing");
+    let hashes = parallel_map(&work, 5, |n| {
+        thread::sleep(Duration::from_millis(50)); // pretend this is slow
+        bar.inc(1);
+        n.wrapping_mul(0x9E37_79B9_7F4A_7C15) >> 48
+    });
+    bar.finish();
+    println!("First hashes: {:?}", &hashes[..4]);
+    
     // === ERROR HANDLING IN THREADS ===
     
     println!("\n--- Error Handling in Threads ---");
@@ -289,8 +367,9 @@ fn main() {
     
     println!("\n--- Producer-Consumer Pattern ---");
     
+    // mpsc::channel is unbounded: a fast producer can pile up any number
+    // of items ahead of a slow consumer
     let (tx, rx) = mpsc::channel();
-    let _buffer_size = 5; // Unused in this simple example, but would be used for bounded channels
     
     // Producer thread
     let producer = thread::spawn(move || {
@@ -315,6 +394,36 @@ fn main() {
     producer.join().unwrap();
     consumer.join().unwrap();
     
+    // A BoundedBuffer holds at most `capacity` items, so the producer is
+    // made to wait (on a Condvar) whenever the consumer falls behind
+    println!("\nWith a bounded buffer of 2:");
+    let buffer = Arc::new(BoundedBuffer::new(2));
+    
+    let producer = {
+        let buffer = Arc::clone(&buffer);
+        thread::spawn(move || {
+            for i in 1..=6 {
+                let item = format!("Item {}", i);
+                println!("Producing: {} ({} waiting)", item, buffer.len());
+                buffer.push(item).unwrap();
+            }
+            // Closing lets the consumer finish once the buffer is drained
+            buffer.close();
+            println!("Producer finished");
+        })
+    };
+    
+    let consumer = thread::spawn(move || {
+        while let Some(item) = buffer.pop() {
+            println!("Consuming: {}", item);
+            thread::sleep(Duration::from_millis(100));
+        }
+        println!("Consumer finished");
+    });
+    
+    producer.join().unwrap();
+    consumer.join().unwrap();
+    
     // === CONCURRENT CALCULATIONS ===
     
     println!("\n--- Concurrent Calculations ---");
@@ -346,6 +455,92 @@ fn main() {
         println!("  fib({}) = {} (took {:?})", num, result, duration);
     }
     
+    // === EVENT BUS ===
+    
+    println!("\n--- Event Bus ---");
+    
+    // The game publishes what happens; a scoreboard and a log listen
+    // without the game knowing about either of them
+    let bus = EventBus::new();
+    let scoreboard = bus.subscribe("game", 8);
+    let log = bus.subscribe_fn("game", 8, |event: String| println!("  [log] {}", event));
+    
+    for event in ["player joined", "coin collected", "coin collected", "player left"] {
+        bus.publish("game", event.to_string());
+    }
+    log.unsubscribe();
+    
+    let coins = std::iter::from_fn(|| scoreboard.try_recv())
+        .filter(|event| event == "coin collected")
+        .count();
+    println!("Scoreboard counted {} coins", coins);
+    
+    // === COOPERATIVE CANCELLATION ===
+    
+    println!("\n--- Cooperative Cancellation ---");
+    
+    // Threads cannot be killed from outside, so long-running loops check a
+    // CancellationToken instead. A server would call cancel() from its
+    // Ctrl+C handler; here the main thread does it after a moment
+    let token = CancellationToken::new();
+    let server = {
+        let token = token.clone();
+        thread::spawn(move || {
+            let mut requests = 0;
+            // Waits between "requests", but wakes as soon as it is cancelled
+            while !token.cancelled_wait(Duration::from_millis(40)) {
+                requests += 1;
+            }
+            println!("Server stopping cleanly after {} requests", requests);
+        })
+    };
+    thread::sleep(Duration::from_millis(150));
+    token.cancel();
+    server.join().unwrap();
+    
+    // === SCHEDULED JOBS ===
+    
+    println!("\n--- Scheduled Jobs ---");
+    
+    // One timer thread runs jobs once after a delay or repeatedly
+    let scheduler = Scheduler::new();
+    let (tick_tx, tick_rx) = mpsc::channel();
+    let heartbeat = scheduler.schedule_every(Duration::from_millis(30), move || {
+        let _ = tick_tx.send("heartbeat");
+    });
+    let (done_tx, done_rx) = mpsc::channel();
+    scheduler.schedule_after(Duration::from_millis(100), move || done_tx.send(()).unwrap());
+    
+    done_rx.recv().unwrap();
+    heartbeat.cancel();
+    println!("{} heartbeats before the one-off job ran", tick_rx.try_iter().count());
+    scheduler.shutdown();
+    
+    // === RATE LIMITING ===
+    
+    println!("\n--- Rate Limiting ---");
+    
+    // A download manager sharing one limiter between its worker threads:
+    // the first three downloads start at once, the rest at 10 per second.
+    // The bar shows the rate settling at about 10 files a second
+    let limiter = Arc::new(RateLimiter::new(10.0, 3));
+    let files = 20;
+    let bar = Arc::new(ProgressBar::new(files).label("Downloading"));
+    let handles: Vec<_> = (1..=files)
+        .map(|_| {
+            let limiter = Arc::clone(&limiter);
+            let bar = Arc::clone(&bar);
+            thread::spawn(move || {
+                limiter.acquire();
+                bar.inc(1);
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    bar.finish();
+    
     println!("\n=== Key Takeaways ===");
     println!("• Use thread::spawn() to create new threads");
     println!("• move closures transfer ownership to threads");
@@ -0,0 +1,328 @@
+// Async/Await Example
+// This example demonstrates futures, async/await, and how an executor drives them
+//
+// To run this example: cargo run --example 14_async_await
+
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rustler::concurrent::block_on;
+
+fn main() {
+    println!("=== Async/Await in Rust ===\n");
+    
+    // === ASYNC FUNCTIONS ===
+    
+    println!("--- Async Functions ---");
+    
+    // Calling an async fn does not run it; it returns a future
+    let future = greet("Ferris");
+    println!("Created a future, nothing has run yet");
+    
+    // An executor polls the future until it finishes. block_on is the
+    // library's tiny executor: it parks the thread while the future waits
+    let greeting = block_on(future);
+    println!("Future finished with: {}", greeting);
+    
+    // .await inside an async block runs futures one after another
+    let total = block_on(async {
+        let a = double(5).await;
+        let b = double(a).await;
+        a + b
+    });
+    println!("double(5) + double(double(5)) = {}", total);
+    
+    // === A HAND-WRITTEN FUTURE ===
+    
+    println!("\n--- A Hand-Written Future ---");
+    
+    // Countdown returns Pending a few times before it is Ready, asking to
+    // be polled again each time
+    let polls = block_on(Countdown { remaining: 3, polls: 0 });
+    println!("Countdown finished after {} polls", polls);
+    
+    // === TIMER FUTURE ===
+    
+    println!("\n--- Timer Future ---");
+    
+    // The timer hands its Waker to a background thread, which calls
+    // wake() when the time is up. Until then the executor sleeps
+    let start = Instant::now();
+    block_on(async {
+        println!("Waiting 200ms...");
+        Timer::new(Duration::from_millis(200)).await;
+        println!("Timer fired after {:?}", start.elapsed());
+    });
+    
+    // === CHANNEL RECEIVE ===
+    
+    println!("\n--- Channel Receive ---");
+    
+    let (sender, receiver) = channel();
+    
+    // A normal thread produces values; the async side awaits each one
+    let producer = thread::spawn(move || {
+        for word in ["async", "without", "tokio"] {
+            thread::sleep(Duration::from_millis(100));
+            println!("  Sending '{}'", word);
+            sender.send(word.to_string());
+        }
+        // Dropping the sender closes the channel
+    });
+    
+    let received = block_on(async {
+        let mut words = Vec::new();
+        while let Some(word) = receiver.recv().await {
+            println!("  Received '{}'", word);
+            words.push(word);
+        }
+        words
+    });
+    producer.join().unwrap();
+    println!("Channel closed; received {:?}", received);
+    
+    // === WAITING ON TWO FUTURES AT ONCE ===
+    
+    println!("\n--- Waiting on Two Futures at Once ---");
+    
+    // Awaiting one after the other takes the sum of both delays
+    let start = Instant::now();
+    block_on(async {
+        Timer::new(Duration::from_millis(150)).await;
+        Timer::new(Duration::from_millis(150)).await;
+    });
+    println!("Sequential: {:?}", start.elapsed());
+    
+    // Polling both in the same future overlaps the waits
+    let start = Instant::now();
+    let (a, b) = block_on(Join::new(
+        async {
+            Timer::new(Duration::from_millis(150)).await;
+            "first"
+        },
+        async {
+            Timer::new(Duration::from_millis(150)).await;
+            "second"
+        },
+    ));
+    println!("Joined: {:?} (got {} and {})", start.elapsed(), a, b);
+    
+    println!("\n=== Key Takeaways ===");
+    println!("• async fn returns a future; nothing runs until it is polled");
+    println!("• An executor polls futures and sleeps until a Waker wakes it");
+    println!("• A future returns Pending after storing the Waker, Ready when done");
+    println!("• .await polls futures one after another inside an async block");
+    println!("• Polling several futures together lets their waits overlap");
+    println!("• Crates like tokio add I/O, timers and many tasks on top of these ideas");
+}
+
+async fn greet(name: &str) -> String {
+    format!("Hello, {}!", name)
+}
+
+async fn double(n: u32) -> u32 {
+    n * 2
+}
+
+/// Pending until it has been polled `remaining` more times.
+struct Countdown {
+    remaining: u32,
+    polls: u32,
+}
+
+impl Future for Countdown {
+    // How many polls it took
+    type Output = u32;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<u32> {
+        self.polls += 1;
+        if self.remaining == 0 {
+            return Poll::Ready(self.polls);
+        }
+        println!("  Countdown polled, {} to go", self.remaining);
+        self.remaining -= 1;
+        // Nothing external will wake us, so ask to be polled again now
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+/// State shared between a timer future and its background thread.
+struct TimerState {
+    fired: bool,
+    waker: Option<Waker>,
+}
+
+/// Becomes ready once `duration` has passed.
+struct Timer {
+    duration: Duration,
+    state: Arc<Mutex<TimerState>>,
+    started: bool,
+}
+
+impl Timer {
+    fn new(duration: Duration) -> Self {
+        Timer {
+            duration,
+            state: Arc::new(Mutex::new(TimerState { fired: false, waker: None })),
+            started: false,
+        }
+    }
+}
+
+impl Future for Timer {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = self.state.lock().unwrap();
+        if state.fired {
+            return Poll::Ready(());
+        }
+        // Always store the latest waker: the future may have moved to a
+        // different task since the last poll
+        state.waker = Some(cx.waker().clone());
+        drop(state);
+
+        // Start the clock on the first poll, since futures are lazy
+        if !self.started {
+            self.started = true;
+            let duration = self.duration;
+            let shared = Arc::clone(&self.state);
+            thread::spawn(move || {
+                thread::sleep(duration);
+                let mut state = shared.lock().unwrap();
+                state.fired = true;
+                if let Some(waker) = state.waker.take() {
+                    waker.wake();
+                }
+            });
+        }
+        Poll::Pending
+    }
+}
+
+/// The inside of an async channel: queued values plus the waker of a
+/// receiver waiting for the next one.
+struct ChannelState<T> {
+    queue: VecDeque<T>,
+    waker: Option<Waker>,
+    closed: bool,
+}
+
+struct Sender<T> {
+    state: Arc<Mutex<ChannelState<T>>>,
+}
+
+struct Receiver<T> {
+    state: Arc<Mutex<ChannelState<T>>>,
+}
+
+fn channel<T>() -> (Sender<T>, Receiver<T>) {
+    let state = Arc::new(Mutex::new(ChannelState {
+        queue: VecDeque::new(),
+        waker: None,
+        closed: false,
+    }));
+    (Sender { state: Arc::clone(&state) }, Receiver { state })
+}
+
+impl<T> Sender<T> {
+    fn send(&self, value: T) {
+        let mut state = self.state.lock().unwrap();
+        state.queue.push_back(value);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let mut state = self.state.lock().unwrap();
+        state.closed = true;
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> Receiver<T> {
+    /// A future for the next value, or `None` once the sender is gone.
+    fn recv(&self) -> Recv<'_, T> {
+        Recv { receiver: self }
+    }
+}
+
+struct Recv<'a, T> {
+    receiver: &'a Receiver<T>,
+}
+
+impl<T> Future for Recv<'_, T> {
+    type Output = Option<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let mut state = self.receiver.state.lock().unwrap();
+        if let Some(value) = state.queue.pop_front() {
+            Poll::Ready(Some(value))
+        } else if state.closed {
+            Poll::Ready(None)
+        } else {
+            state.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+/// Polls two futures together and finishes when both have.
+struct Join<A: Future, B: Future> {
+    a: Pin<Box<A>>,
+    b: Pin<Box<B>>,
+    a_output: Option<A::Output>,
+    b_output: Option<B::Output>,
+}
+
+impl<A: Future, B: Future> Join<A, B> {
+    fn new(a: A, b: B) -> Self {
+        Join {
+            a: Box::pin(a),
+            b: Box::pin(b),
+            a_output: None,
+            b_output: None,
+        }
+    }
+}
+
+// The futures are boxed, so Join itself can be moved freely
+impl<A: Future, B: Future> Unpin for Join<A, B> {}
+
+impl<A: Future, B: Future> Future for Join<A, B> {
+    type Output = (A::Output, B::Output);
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        if this.a_output.is_none() {
+            if let Poll::Ready(output) = this.a.as_mut().poll(cx) {
+                this.a_output = Some(output);
+            }
+        }
+        if this.b_output.is_none() {
+            if let Poll::Ready(output) = this.b.as_mut().poll(cx) {
+                this.b_output = Some(output);
+            }
+        }
+        match (this.a_output.take(), this.b_output.take()) {
+            (Some(a), Some(b)) => Poll::Ready((a, b)),
+            (a, b) => {
+                // Put back whichever finished and keep waiting for the other
+                this.a_output = a;
+                this.b_output = b;
+                Poll::Pending
+            }
+        }
+    }
+}
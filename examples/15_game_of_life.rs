@@ -0,0 +1,72 @@
+// Game of Life Example
+// This example animates Conway's Game of Life in the terminal
+//
+// To run this example: cargo run --example 15_game_of_life
+// To pick a pattern, generation count and frame delay (ms):
+//   cargo run --example 15_game_of_life -- glider 60 80
+
+use std::env;
+use std::io::{self, Write};
+use std::thread;
+use std::time::Duration;
+
+use rustler::games::life::World;
+
+const ROWS: usize = 16;
+const COLS: usize = 32;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let pattern = args.get(1).map(String::as_str).unwrap_or("glider");
+    let generations: u64 = args.get(2).and_then(|n| n.parse().ok()).unwrap_or(40);
+    let delay = Duration::from_millis(args.get(3).and_then(|n| n.parse().ok()).unwrap_or(100));
+
+    // The glider wraps around the edges; the others sit in the middle
+    let mut world = match pattern {
+        "blinker" => World::blinker(ROWS, COLS),
+        "pulsar" => World::from_pattern(ROWS, COLS, 1, 9, PULSAR),
+        "acorn" => World::from_pattern(ROWS, COLS, 6, 12, ".#.....\n...#...\n##..###"),
+        _ => World::glider(ROWS, COLS).with_wrapping(true),
+    };
+
+    let stdout = io::stdout();
+    for _ in 0..=generations {
+        let mut out = stdout.lock();
+        // ANSI escapes: clear the screen and move the cursor to the top left
+        write!(out, "\x1b[2J\x1b[H").unwrap();
+        writeln!(out, "=== Game of Life: {} ===", pattern).unwrap();
+        write!(out, "{}", world).unwrap();
+        writeln!(out, "Generation {:>3}, population {}", world.generation(), world.population()).unwrap();
+        out.flush().unwrap();
+        drop(out);
+
+        if world.population() == 0 {
+            println!("Everything died out.");
+            break;
+        }
+        thread::sleep(delay);
+        world.step();
+    }
+
+    println!("\n=== Key Takeaways ===");
+    println!("• Each generation is computed from the previous one all at once");
+    println!("• Grid2D<bool> stores the board row-major in a single Vec");
+    println!("• rem_euclid wraps negative coordinates for a toroidal world");
+    println!("• Simple local rules produce gliders, oscillators and chaos");
+}
+
+/// A period-3 oscillator.
+const PULSAR: &str = "\
+..###...###..
+.............
+#....#.#....#
+#....#.#....#
+#....#.#....#
+..###...###..
+.............
+..###...###..
+#....#.#....#
+#....#.#....#
+#....#.#....#
+.............
+..###...###..";
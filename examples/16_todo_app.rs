@@ -0,0 +1,136 @@
+// To-Do App Example
+// This example is a small but complete command-line program: a to-do list
+// that remembers its tasks between runs
+//
+// To run this example: cargo run --example 16_todo_app -- <command>
+//   cargo run --example 16_todo_app -- add "Buy milk" --due 2026-10-20
+//   cargo run --example 16_todo_app -- list
+//   cargo run --example 16_todo_app -- done 1
+//   cargo run --example 16_todo_app -- due tomorrow
+//
+// The same commands are available as their own program: cargo run --bin todo
+//
+// Tasks are saved as JSON in your data directory (~/.local/share/rustler on
+// Linux). Set RUSTLER_TODO_FILE to use a different file.
+
+use std::env;
+use std::path::PathBuf;
+use std::process;
+
+use chrono::{Local, NaiveDate};
+use rustler::apps::todo::{Command, Filter, TodoError, TodoList, USAGE};
+
+fn main() {
+    println!("=== Rust To-Do App ===");
+    
+    let args: Vec<String> = env::args().skip(1).collect();
+    let today = Local::now().date_naive();
+    
+    // === PARSING ARGUMENTS ===
+    
+    // The parser lives next to TodoList in the library, so the todo binary
+    // understands exactly the same commands. Each command becomes a
+    // variant of the Command enum, and anything it cannot read is an error
+    // message for the user. With no arguments, show the help and the list
+    let command = if args.is_empty() {
+        println!("{}\n", USAGE);
+        Command::List(Filter::Pending)
+    } else {
+        match Command::parse(&args, today) {
+            Ok(command) => command,
+            Err(message) => {
+                eprintln!("error: {}\n\n{}", message, USAGE);
+                process::exit(2);
+            }
+        }
+    };
+    
+    // === LOADING ===
+    
+    // An environment variable overrides the default location, which is
+    // handy for trying the app without touching your real list
+    let path = match env::var_os("RUSTLER_TODO_FILE").map(PathBuf::from).or_else(TodoList::default_path) {
+        Some(path) => path,
+        None => {
+            eprintln!("error: no data directory found; set RUSTLER_TODO_FILE");
+            process::exit(1);
+        }
+    };
+    
+    let mut todos = match TodoList::load(&path) {
+        Ok(todos) => todos,
+        Err(e) => {
+            eprintln!("error: cannot read {}: {}", path.display(), e);
+            process::exit(1);
+        }
+    };
+    
+    // === RUNNING THE COMMAND ===
+    
+    // Every command returns Result, so one match handles all the failures
+    if let Err(e) = run(command, &mut todos, today) {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+    
+    // === SAVING ===
+    
+    // Saving after every command keeps the file in step with what was shown
+    if let Err(e) = todos.save(&path) {
+        eprintln!("error: cannot save {}: {}", path.display(), e);
+        process::exit(1);
+    }
+    println!("\n({} tasks in {})", todos.len(), path.display());
+    
+    // The takeaways are for the tutorial run, not everyday use
+    if !args.is_empty() {
+        return;
+    }
+    println!("\n=== Key Takeaways ===");
+    println!("• Structs and enums model the data (Task) and the commands");
+    println!("• A Vec keeps tasks in order; ids come from a counter, never reused");
+    println!("• serde turns the whole list into JSON with two derives");
+    println!("• Writing to a temp file and renaming makes saves all-or-nothing");
+}
+
+fn run(command: Command, todos: &mut TodoList, today: NaiveDate) -> Result<(), TodoError> {
+    match command {
+        Command::Add { title, due } => {
+            let id = todos.add(&title, due)?;
+            println!("Added task {}", id);
+        }
+        Command::List(filter) => {
+            let mut shown = 0;
+            for task in todos.list(filter) {
+                let warning = if task.is_overdue(today) { "  <- overdue" } else { "" };
+                println!("{}{}", task, warning);
+                shown += 1;
+            }
+            if shown == 0 {
+                println!("Nothing to show.");
+            }
+        }
+        Command::Done(id) => {
+            let task = todos.complete(id)?;
+            println!("Done: {}", task.title);
+        }
+        Command::Remove(id) => {
+            let task = todos.remove(id)?;
+            println!("Removed: {}", task.title);
+        }
+        Command::Due(date) => {
+            let tasks = todos.due_by(date);
+            for task in &tasks {
+                println!("{}", task);
+            }
+            if tasks.is_empty() {
+                println!("Nothing due by {}.", date);
+            }
+        }
+        Command::Clear => {
+            let removed = todos.clear_done();
+            println!("Cleared {} finished task(s)", removed);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,64 @@
+// Minigrep Example
+// This example is the Book's minigrep project (chapter 12), rebuilt from the
+// library's own pieces: a line reader for files of any size, Boyer-Moore
+// substring search and a directory walker
+//
+// To run this example: cargo run --example 17_minigrep -- [options] <pattern> [path...]
+//   cargo run --example 17_minigrep -- -n Arc examples/13_concurrency.rs
+//   cargo run --example 17_minigrep -- -ri todo src
+//   cargo run --example 17_minigrep -- -c fn examples/01_hello_world.rs examples/06_functions.rs
+//   echo "Hello, grep" | cargo run --example 17_minigrep -- grep
+//
+// Like grep, it exits with 0 when a line matched, 1 when none did and 2 on
+// an error.
+
+use std::env;
+use std::io;
+use std::process;
+
+use rustler::tools::grep::{self, Config, USAGE};
+
+fn main() {
+    // === READING THE ARGUMENTS ===
+
+    // The Book's Config::build takes the pattern and one file name by
+    // position. This Config also understands flags, so the parsing lives
+    // in the library where it is tested, and returns an error value rather
+    // than exiting. Deciding how to report it is up to main
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{}", USAGE);
+        return;
+    }
+    let config = Config::parse(&args).unwrap_or_else(|e| {
+        // Errors go to stderr so they don't end up in redirected output
+        eprintln!("minigrep: {}\n\n{}", e, USAGE);
+        process::exit(2);
+    });
+
+    // === SEARCHING ===
+
+    // run writes to any io::Write. Here it is stdout, locked once so each
+    // line doesn't take the lock again; the tests pass a Vec<u8> instead.
+    // Standard input is only read when no paths were given
+    let mut out = io::stdout().lock();
+    let report = match grep::run(&config, io::stdin(), &mut out) {
+        Ok(report) => report,
+        // Output closed early, as with `| head`: stop quietly
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => process::exit(0),
+        Err(e) => {
+            eprintln!("minigrep: {}", e);
+            process::exit(2);
+        }
+    };
+
+    // A file that can't be read doesn't stop the search; each one is
+    // reported at the end
+    for error in &report.errors {
+        eprintln!("minigrep: {}", error);
+    }
+    if !report.errors.is_empty() {
+        process::exit(2);
+    }
+    process::exit(if report.matches > 0 { 0 } else { 1 });
+}
@@ -0,0 +1,187 @@
+// Mini Shell Example
+// This example is a tiny Unix-style shell: it reads a command line, runs
+// the programs it names as child processes with std::process, and
+// connects `|` pipelines with one thread per pipe
+//
+// To run this example: cargo run --example 18_minishell
+// Then try:
+//   ls -l
+//   echo "Hello, $USER" | tr a-z A-Z
+//   cat Cargo.toml | grep serde | wc -l
+//   cd src
+//   cat nope.txt
+//   echo $?
+//   exit
+//
+// Quoting and $VARIABLE expansion are done by rustler::tools::shell::parse.
+// Lines can also be piped in: echo 'echo hi | rev' | cargo run --example 18_minishell
+
+use std::env;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::PathBuf;
+use std::process::{self, Child, Command, Stdio};
+use std::thread::{self, JoinHandle};
+
+use rustler::tools::shell;
+
+fn main() {
+    // The exit status of the last command, for $? and for `exit` with no code
+    let mut status = 0;
+    // Only show a prompt to a person; piped input runs silently
+    let interactive = io::stdin().is_terminal();
+    let mut lines = io::stdin().lock().lines();
+
+    loop {
+        if interactive {
+            print!("{} $ ", current_dir_name());
+            // print! doesn't flush by itself, and the prompt has no newline
+            io::stdout().flush().unwrap();
+        }
+        let line = match lines.next() {
+            Some(Ok(line)) => line,
+            // Ctrl-D (end of input) leaves like `exit`
+            None => break,
+            Some(Err(e)) => {
+                eprintln!("minishell: {}", e);
+                break;
+            }
+        };
+
+        // === PARSING ===
+
+        // Variables are looked up when the line is parsed, so $? is
+        // whatever the previous line left behind
+        let last = status;
+        let lookup = |name: &str| match name {
+            "?" => Some(last.to_string()),
+            _ => env::var(name).ok(),
+        };
+        let pipeline = match shell::parse(&line, lookup) {
+            Ok(pipeline) if pipeline.is_empty() => continue,
+            Ok(pipeline) => pipeline,
+            Err(e) => {
+                eprintln!("minishell: syntax error: {}", e);
+                status = 2;
+                continue;
+            }
+        };
+
+        // === BUILTINS ===
+
+        // cd and exit can't be separate programs: a child process has its
+        // own working directory, and exiting it would leave us running.
+        // So the shell handles them itself
+        if let [command] = pipeline.as_slice() {
+            match command.program.as_str() {
+                "cd" => {
+                    status = change_dir(&command.args);
+                    continue;
+                }
+                "exit" => {
+                    let code = command.args.first().and_then(|code| code.parse().ok()).unwrap_or(status);
+                    process::exit(code);
+                }
+                _ => {}
+            }
+        }
+
+        // === RUNNING PROGRAMS ===
+
+        status = run_pipeline(&pipeline);
+    }
+    process::exit(status);
+}
+
+/// The last part of the working directory, for the prompt.
+fn current_dir_name() -> String {
+    env::current_dir()
+        .ok()
+        .and_then(|dir| dir.file_name().map(|name| name.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| "/".to_string())
+}
+
+/// `cd <dir>`, or `cd` alone for the home directory. Returns the exit
+/// status, like a program would.
+fn change_dir(args: &[String]) -> i32 {
+    let target = match args.first() {
+        Some(dir) => PathBuf::from(dir),
+        None => match env::var_os("HOME") {
+            Some(home) => PathBuf::from(home),
+            None => {
+                eprintln!("minishell: cd: HOME is not set");
+                return 1;
+            }
+        },
+    };
+    match env::set_current_dir(&target) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("minishell: cd: {}: {}", target.display(), e);
+            1
+        }
+    }
+}
+
+/// Starts every command of the pipeline, then waits for them all. The exit
+/// status is the last command's, as in other shells.
+fn run_pipeline(pipeline: &[shell::Command]) -> i32 {
+    let last = pipeline.len() - 1;
+    let mut children: Vec<Child> = Vec::new();
+    let mut pumps: Vec<JoinHandle<()>> = Vec::new();
+
+    for (i, command) in pipeline.iter().enumerate() {
+        // Every command but the first reads from a pipe we write to, and
+        // every command but the last writes to a pipe we read from. The
+        // first and last share the shell's own stdin and stdout
+        let stdin = if i == 0 { Stdio::inherit() } else { Stdio::piped() };
+        let stdout = if i == last { Stdio::inherit() } else { Stdio::piped() };
+        let spawned = Command::new(&command.program).args(&command.args).stdin(stdin).stdout(stdout).spawn();
+        let mut child = match spawned {
+            Ok(child) => child,
+            Err(e) => {
+                if e.kind() == io::ErrorKind::NotFound {
+                    eprintln!("minishell: {}: command not found", command.program);
+                } else {
+                    eprintln!("minishell: {}: {}", command.program, e);
+                }
+                // Don't leave the earlier commands waiting for input that
+                // will never come
+                for mut child in children {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                }
+                return 127;
+            }
+        };
+
+        // A thread copies the previous command's output into this one's
+        // input. When the previous command exits, the copy ends and the
+        // pipe is dropped, which is how this command sees end of input
+        if let Some(previous) = children.last_mut() {
+            let mut from = previous.stdout.take().expect("stdout is piped");
+            let mut to = child.stdin.take().expect("stdin is piped");
+            pumps.push(thread::spawn(move || {
+                // An early exit downstream (like `head`) breaks the pipe;
+                // that just means the copy is over
+                let _ = io::copy(&mut from, &mut to);
+            }));
+        }
+        children.push(child);
+    }
+
+    let mut status = 0;
+    for mut child in children {
+        status = match child.wait() {
+            // A program killed by a signal has no exit code
+            Ok(exit) => exit.code().unwrap_or(1),
+            Err(e) => {
+                eprintln!("minishell: {}", e);
+                1
+            }
+        };
+    }
+    for pump in pumps {
+        pump.join().unwrap();
+    }
+    status
+}
@@ -0,0 +1,205 @@
+// HTTP Server Example
+// This example is a small web server on rustler::net::http, which speaks
+// HTTP/1.1 by hand on top of std::net::TcpListener. It serves your rustler
+// progress as an HTML dashboard and as JSON, a greeting that reads query
+// parameters, and the example sources as static files
+//
+// To run this example: cargo run --example 19_http_server
+// Then open http://127.0.0.1:7878/ in a browser, or try:
+//   curl -i 'http://127.0.0.1:7878/hello?name=Ferris'
+//   curl http://127.0.0.1:7878/api/progress
+//   curl http://127.0.0.1:7878/source/01_hello_world.rs
+//   curl -i -X POST http://127.0.0.1:7878/
+//
+// Options: --port <n> (default 7878; 0 picks a free one), and --once to
+// make one request by hand, print the exchange and stop instead of serving.
+// Progress is read from RUSTLER_PROGRESS_FILE if set, like `rustler` does.
+
+use std::env;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process;
+
+use rustler::catalog;
+use rustler::net::http::{self, escape_html, Response, Server, Status};
+use rustler::progress::Progress;
+use serde_json::json;
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let once = args.iter().any(|arg| arg == "--once");
+    let port = match args.iter().position(|arg| arg == "--port").map(|i| args.get(i + 1)) {
+        None => 7878,
+        Some(Some(port)) if port.parse::<u16>().is_ok() => port.parse().unwrap(),
+        Some(_) => {
+            eprintln!("--port needs a number from 0 to 65535");
+            process::exit(2);
+        }
+    };
+
+    println!("=== HTTP Server ===\n");
+
+    // === ROUTES ===
+
+    // Each route is an exact path and a closure from Request to Response.
+    // The closures run on the server's worker threads, so they must be
+    // Send + Sync: these ones capture nothing, or clones they own
+    let mut server = Server::new();
+    server.get("/", |_| Response::html(dashboard(&load_progress())));
+    server.get("/api/progress", |_| {
+        let summary = load_progress().summary();
+        let body = json!({
+            "examples_run": summary.examples_run,
+            "examples_total": summary.examples_total,
+            "quizzes_passed": summary.quizzes_passed,
+            "quizzes_total": summary.quizzes_total,
+            "exercises_solved": summary.exercises_solved,
+            "percent": summary.percent(),
+        });
+        Response::json(body.to_string())
+    });
+    // Query parameters arrive decoded: ?name=Ferris%20the%20crab
+    server.get("/hello", |request| match request.query("name") {
+        Some(name) if !name.is_empty() => Response::text(Status::OK, format!("Hello, {}!\n", name)),
+        _ => Response::text(Status::BAD_REQUEST, "add ?name=<your name>\n"),
+    });
+    // Anything under /source/ is read from the examples directory
+    let examples_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples");
+    server.static_files("/source", examples_dir);
+
+    // === LISTENING ===
+
+    // Binding happens here, before any request arrives; port 0 asks the
+    // operating system for any free port
+    let handle = match server.listen(("127.0.0.1", port)) {
+        Ok(handle) => handle,
+        Err(e) => {
+            eprintln!("Could not listen on port {}: {}", port, e);
+            process::exit(1);
+        }
+    };
+    let addr = handle.local_addr();
+    println!("Listening on http://{}/", addr);
+
+    // === A REQUEST BY HAND ===
+
+    // HTTP/1.1 is text: a request line, headers, a blank line. Writing it
+    // to a TcpStream ourselves shows exactly what a browser sends
+    println!("\n--- A request by hand ---");
+    match raw_request(addr, "/hello?name=Ferris%20the%20crab") {
+        Ok(reply) => {
+            for line in reply.lines() {
+                println!("  < {}", line);
+            }
+        }
+        Err(e) => eprintln!("The request failed: {}", e),
+    }
+
+    // === THE SAME WITH THE CLIENT ===
+
+    // http::get writes that request for us and parses the reply, so the
+    // body comes back apart from the status line and headers
+    println!("\n--- With http::get ---");
+    match http::get(&format!("http://{}/api/progress", addr)) {
+        Ok(response) => {
+            println!("  Status: {}", response.status());
+            println!("  Content-Type: {}", response.headers().get("Content-Type").unwrap_or("?"));
+            println!("  Body: {}", String::from_utf8_lossy(response.body()));
+        }
+        Err(e) => eprintln!("The request failed: {}", e),
+    }
+
+    if once {
+        handle.shutdown();
+        println!("\nStopped after one request (--once).");
+        return;
+    }
+
+    // === SERVING ===
+
+    println!("\nServing until Ctrl-C. Try http://{}/ in a browser.", addr);
+    handle.wait();
+}
+
+/// Sends a GET for `target` and returns everything the server sends back.
+/// The server closes the connection after answering, which is how we know
+/// the reply is complete.
+fn raw_request(addr: SocketAddr, target: &str) -> std::io::Result<String> {
+    let mut stream = TcpStream::connect(addr)?;
+    let request = format!("GET {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: rustler-example\r\n\r\n", target, addr);
+    for line in request.lines().filter(|line| !line.is_empty()) {
+        println!("  > {}", line);
+    }
+    stream.write_all(request.as_bytes())?;
+    let mut reply = String::new();
+    stream.read_to_string(&mut reply)?;
+    Ok(reply)
+}
+
+// === THE DASHBOARD ===
+
+fn progress_path() -> Option<PathBuf> {
+    env::var_os("RUSTLER_PROGRESS_FILE").map(PathBuf::from).or_else(Progress::default_path)
+}
+
+/// Progress is read on every request, so reloading the page shows
+/// examples run since the server started.
+fn load_progress() -> Progress {
+    progress_path().and_then(|path| Progress::load(path).ok()).unwrap_or_default()
+}
+
+/// The page, as one string. Everything that comes from outside the program
+/// goes through escape_html so it can't inject markup.
+fn dashboard(progress: &Progress) -> String {
+    let summary = progress.summary();
+    let mut rows = String::new();
+    for example in catalog::examples() {
+        let run = if progress.has_run(example.name) { "✓" } else { "" };
+        let quiz = match progress.quiz(example.name) {
+            Some(record) if record.passed => format!("passed, best {}%", record.best),
+            Some(record) => format!("best {}%", record.best),
+            None => "not taken".to_string(),
+        };
+        rows.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td><a href=\"/source/{}.rs\">{}</a></td><td>{}</td></tr>\n",
+            run,
+            example.number,
+            escape_html(example.name),
+            escape_html(example.title),
+            escape_html(&quiz)
+        ));
+    }
+    format!(
+        "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>Rustler progress</title>
+<style>
+body {{ font-family: sans-serif; max-width: 48em; margin: 2em auto; }}
+table {{ border-collapse: collapse; width: 100%; }}
+td, th {{ border-bottom: 1px solid #ddd; padding: 0.3em; text-align: left; }}
+progress {{ width: 100%; }}
+</style>
+</head>
+<body>
+<h1>Rustler progress</h1>
+<progress value=\"{percent}\" max=\"100\"></progress>
+<p>{percent}% done: {run} of {examples} examples run, {passed} of {quizzes} quizzes passed, {solved} exercises solved.</p>
+<table>
+<tr><th></th><th>#</th><th>Example</th><th>Quiz</th></tr>
+{rows}</table>
+<p><a href=\"/api/progress\">As JSON</a></p>
+</body>
+</html>
+",
+        percent = summary.percent(),
+        run = summary.examples_run,
+        examples = summary.examples_total,
+        passed = summary.quizzes_passed,
+        quizzes = summary.quizzes_total,
+        solved = summary.exercises_solved,
+        rows = rows,
+    )
+}
@@ -0,0 +1,341 @@
+// Chat Server Example
+// This example is a multi-client chat server over plain TCP. Every
+// connection gets a reader thread, which turns the lines a client types
+// into events, and a writer thread, which sends the client the events
+// everyone else publishes. The fan-out between them is a
+// rustler::concurrent::EventBus, so no connection ever waits on another
+//
+// To run this example: cargo run --example 20_chat
+// Then, in two or more other terminals:
+//   nc 127.0.0.1 7879
+// and type. Commands: /nick <name>, /who, /help, /quit (or Ctrl-D).
+//
+// Options: --port <n> (default 7879; 0 picks a free one), and --demo to
+// run two scripted clients against the server, print what they see and
+// stop instead of serving.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use rustler::concurrent::{EventBus, Subscription};
+
+/// The one topic every client publishes to and subscribes to.
+const ROOM: &str = "room";
+/// How many events a client may fall behind by before it misses some.
+const BACKLOG: usize = 64;
+
+// === EVENTS ===
+
+/// Something that happened in the room. Every event goes to every
+/// client's writer, which decides what, if anything, its client sees:
+/// `from` is the id of the client that caused it.
+#[derive(Debug, Clone)]
+enum Event {
+    Joined { from: usize, nick: String },
+    Said { from: usize, nick: String, text: String },
+    Renamed { from: usize, old: String, new: String },
+    /// An answer for one client only, such as the reply to /who
+    Reply { to: usize, text: String },
+    Left { from: usize, nick: String },
+}
+
+impl Event {
+    /// The line client `me` should see, or `None` for nothing.
+    fn render(&self, me: usize) -> Option<String> {
+        match self {
+            Event::Joined { from, .. } if *from == me => None,
+            Event::Joined { nick, .. } => Some(format!("* {} joined", nick)),
+            // Clients already see what they typed
+            Event::Said { from, .. } if *from == me => None,
+            Event::Said { nick, text, .. } => Some(format!("<{}> {}", nick, text)),
+            Event::Renamed { from, new, .. } if *from == me => Some(format!("* You are now {}", new)),
+            Event::Renamed { old, new, .. } => Some(format!("* {} is now {}", old, new)),
+            Event::Reply { to, text } if *to == me => Some(format!("* {}", text)),
+            Event::Reply { .. } => None,
+            Event::Left { from, nick } if *from == me => Some(format!("* Bye, {}!", nick)),
+            Event::Left { nick, .. } => Some(format!("* {} left", nick)),
+        }
+    }
+}
+
+// === THE HUB ===
+
+/// What the connection threads share: the bus, and who is in the room.
+/// Cloning is cheap; the clones share everything.
+#[derive(Clone)]
+struct Hub {
+    bus: EventBus<Event>,
+    nicks: Arc<Mutex<BTreeMap<usize, String>>>,
+    next_id: Arc<AtomicUsize>,
+    /// Whether to log connections on stderr, which --demo turns off
+    log: bool,
+}
+
+impl Hub {
+    fn new(log: bool) -> Self {
+        Hub {
+            bus: EventBus::new(),
+            nicks: Arc::new(Mutex::new(BTreeMap::new())),
+            next_id: Arc::new(AtomicUsize::new(1)),
+            log,
+        }
+    }
+
+    /// Accepts connections until the listener fails.
+    fn serve(&self, listener: TcpListener) {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => self.connect(stream),
+                Err(e) => eprintln!("[server] accept failed: {}", e),
+            }
+        }
+    }
+
+    /// Gives a new connection its id, nickname and two threads.
+    fn connect(&self, stream: TcpStream) {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let nick = format!("guest{}", id);
+        let peer = stream.peer_addr().map_or_else(|_| "?".to_string(), |addr| addr.to_string());
+        // Subscribing before announcing the client means it can't miss
+        // anything said after its welcome
+        let events = self.bus.subscribe(ROOM, BACKLOG);
+        let writer = match stream.try_clone() {
+            Ok(writer) => writer,
+            Err(e) => {
+                eprintln!("[server] could not set up {}: {}", peer, e);
+                return;
+            }
+        };
+        self.nicks.lock().unwrap().insert(id, nick.clone());
+        if self.log {
+            eprintln!("[server] {} connected as {}", peer, nick);
+        }
+
+        let welcome = format!("* Welcome, {}! Type /help for commands.", nick);
+        let hub = self.clone();
+        thread::spawn(move || hub.write_events(id, writer, events, welcome));
+        self.bus.publish(ROOM, Event::Joined { from: id, nick });
+        let hub = self.clone();
+        thread::spawn(move || hub.read_lines(id, stream));
+    }
+
+    // === THE READER ===
+
+    /// Turns what client `id` types into events, until it types /quit,
+    /// closes the connection or the connection breaks. Either way the room
+    /// hears that it left.
+    fn read_lines(&self, id: usize, stream: TcpStream) {
+        for line in BufReader::new(stream).lines() {
+            // A read error is a broken connection, which is another way
+            // of leaving
+            let Ok(line) = line else { break };
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let Some(command) = line.strip_prefix('/') else {
+                let nick = self.nick(id);
+                self.bus.publish(ROOM, Event::Said { from: id, nick, text: line.to_string() });
+                continue;
+            };
+            let (name, arg) = command.split_once(' ').unwrap_or((command, ""));
+            match name {
+                "quit" => break,
+                "nick" => self.rename(id, arg.trim()),
+                "who" => {
+                    let here: Vec<String> = self.nicks.lock().unwrap().values().cloned().collect();
+                    self.reply(id, format!("Here: {}", here.join(", ")));
+                }
+                "help" => self.reply(id, "Commands: /nick <name>, /who, /help, /quit".to_string()),
+                _ => self.reply(id, format!("Unknown command /{}; try /help", name)),
+            }
+        }
+        let nick = self.nicks.lock().unwrap().remove(&id).unwrap_or_default();
+        if self.log {
+            eprintln!("[server] {} left", nick);
+        }
+        self.bus.publish(ROOM, Event::Left { from: id, nick });
+    }
+
+    fn rename(&self, id: usize, new: &str) {
+        let valid = !new.is_empty()
+            && new.len() <= 16
+            && new.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        if !valid {
+            return self.reply(id, "Nicknames are 1 to 16 letters, digits, _ or -".to_string());
+        }
+        // The check and the change happen under one lock, so two clients
+        // can't both take the same name
+        let old = {
+            let mut nicks = self.nicks.lock().unwrap();
+            if nicks.iter().any(|(&other, nick)| other != id && nick == new) {
+                drop(nicks);
+                return self.reply(id, format!("{} is taken", new));
+            }
+            nicks.insert(id, new.to_string()).unwrap_or_default()
+        };
+        self.bus.publish(ROOM, Event::Renamed { from: id, old, new: new.to_string() });
+    }
+
+    fn reply(&self, to: usize, text: String) {
+        self.bus.publish(ROOM, Event::Reply { to, text });
+    }
+
+    fn nick(&self, id: usize) -> String {
+        self.nicks.lock().unwrap().get(&id).cloned().unwrap_or_default()
+    }
+
+    // === THE WRITER ===
+
+    /// Sends client `id` its view of every event, until it leaves or can't
+    /// be written to any more.
+    fn write_events(&self, id: usize, stream: TcpStream, events: Subscription<Event>, welcome: String) {
+        let mut out = io::BufWriter::new(&stream);
+        let mut send = |line: &str| writeln!(out, "{}", line).and_then(|_| out.flush());
+        if send(&welcome).is_err() {
+            return;
+        }
+        // recv only returns None once the bus is gone, which is never
+        // while the hub lives, so the loop ends on our own Left event
+        while let Some(event) = events.recv() {
+            let leaving = matches!(event, Event::Left { from, .. } if from == id);
+            if let Some(line) = event.render(id) {
+                // The client is gone; its reader will notice too
+                if send(&line).is_err() {
+                    break;
+                }
+            }
+            if leaving {
+                break;
+            }
+        }
+        if self.log && events.dropped() > 0 {
+            eprintln!("[server] client {} was too slow and missed {} events", id, events.dropped());
+        }
+        // Closing our side ends the client's read, so `nc` exits after /quit
+        let _ = stream.shutdown(Shutdown::Both);
+    }
+}
+
+// === A SCRIPTED DEMO ===
+
+/// One end of a conversation in --demo: sends lines and prints what comes
+/// back, as the named person would see it.
+struct DemoClient {
+    name: &'static str,
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl DemoClient {
+    fn connect(name: &'static str, addr: SocketAddr) -> io::Result<Self> {
+        let stream = TcpStream::connect(addr)?;
+        // A missing line should fail the demo, not hang it
+        stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+        let mut client = DemoClient {
+            name,
+            reader: BufReader::new(stream.try_clone()?),
+            writer: stream,
+        };
+        client.expect()?;
+        Ok(client)
+    }
+
+    fn send(&mut self, line: &str) -> io::Result<()> {
+        println!("{:>6} types: {}", self.name, line);
+        writeln!(self.writer, "{}", line)
+    }
+
+    /// Reads and prints the next line from the server.
+    fn expect(&mut self) -> io::Result<()> {
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            println!("{:>6} sees:  (the server closed the connection)", self.name);
+        } else {
+            println!("{:>6} sees:  {}", self.name, line.trim_end());
+        }
+        Ok(())
+    }
+}
+
+fn demo(addr: SocketAddr) -> io::Result<()> {
+    let mut ana = DemoClient::connect("ana", addr)?;
+    let mut ben = DemoClient::connect("ben", addr)?;
+    ana.expect()?; // ben joined
+
+    println!("\n--- Nicknames ---");
+    ana.send("/nick ana")?;
+    ana.expect()?;
+    ben.expect()?;
+    ben.send("/nick ana")?;
+    ben.expect()?; // taken
+    ben.send("/nick ben")?;
+    ben.expect()?;
+    ana.expect()?;
+
+    println!("\n--- Talking ---");
+    ana.send("Hi Ben, did you try the HTTP server example?")?;
+    ben.expect()?;
+    ben.send("Yes! This one is plain TCP though.")?;
+    ana.expect()?;
+    ben.send("/who")?;
+    ben.expect()?;
+
+    println!("\n--- Leaving ---");
+    // ben says goodbye properly; ana just disconnects
+    ben.send("/quit")?;
+    ben.expect()?;
+    ben.expect()?;
+    ana.expect()?;
+    drop(ana);
+    Ok(())
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let run_demo = args.iter().any(|arg| arg == "--demo");
+    let port = match args.iter().position(|arg| arg == "--port").map(|i| args.get(i + 1)) {
+        None if run_demo => 0,
+        None => 7879,
+        Some(Some(port)) if port.parse::<u16>().is_ok() => port.parse().unwrap(),
+        Some(_) => {
+            eprintln!("--port needs a number from 0 to 65535");
+            process::exit(2);
+        }
+    };
+
+    println!("=== Chat Server ===\n");
+
+    let listener = match TcpListener::bind(("127.0.0.1", port)) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("Could not listen on port {}: {}", port, e);
+            process::exit(1);
+        }
+    };
+    let addr = listener.local_addr().unwrap();
+    let hub = Hub::new(!run_demo);
+
+    if run_demo {
+        // The server runs on its own thread and ends with the program
+        thread::spawn(move || hub.serve(listener));
+        println!("Two clients chat through a server on {}\n", addr);
+        if let Err(e) = demo(addr) {
+            eprintln!("The demo failed: {}", e);
+            process::exit(1);
+        }
+        println!("\nStopped after the demo (--demo).");
+        return;
+    }
+
+    println!("Listening on {}. Connect with: nc {} {}", addr, addr.ip(), addr.port());
+    println!("Serving until Ctrl-C.");
+    hub.serve(listener);
+}
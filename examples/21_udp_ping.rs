@@ -0,0 +1,162 @@
+// UDP Ping Example
+// This example bounces numbered datagrams off a pong server with
+// std::net::UdpSocket, the way the `ping` command does, and measures the
+// round-trip time of each and how many were lost. The packets are 16
+// bytes, serialized by hand with to_be_bytes and from_be_bytes
+//
+// To run this example: cargo run --example 21_udp_ping
+// The server drops every 4th ping so there is some loss to see. Options:
+//   --count <n>    pings to send (default 8)
+//   --drop <n>     drop every nth ping; 0 drops none (default 4)
+//   --serve <port> only run a pong server, which never drops anything
+//   --to <addr>    ping a server started elsewhere with --serve, such as
+//                  127.0.0.1:7880, instead of starting one here
+
+use std::env;
+use std::net::{SocketAddr, UdpSocket};
+use std::process;
+use std::thread;
+use std::time::Duration;
+
+use rustler::concurrent::CancellationToken;
+use rustler::net::udp::{self, Kind, Packet, Pinger, PACKET_LEN};
+
+/// How long to wait for each pong.
+const TIMEOUT: Duration = Duration::from_millis(300);
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let count = number_option(&args, "--count").unwrap_or(8);
+    let drop_every = number_option(&args, "--drop").unwrap_or(4);
+
+    println!("=== UDP Ping ===\n");
+
+    if let Some(port) = number_option(&args, "--serve") {
+        serve(port);
+        return;
+    }
+
+    // === THE WIRE FORMAT ===
+
+    // A datagram is just bytes, so the packet is laid out by hand: magic,
+    // kind, then the numbers in big-endian ("network") byte order
+    println!("--- A packet on the wire ---");
+    let ping = Packet { kind: Kind::Ping, seq: 258, sent_micros: 1_000_000 };
+    let bytes = ping.to_bytes();
+    println!("{:?}", ping);
+    println!("  = {}", hex(&bytes));
+    println!("  magic \"RP\", kind 0 (ping), seq 258 = 0x00000102, sent 1000000 µs = 0x0f4240");
+    println!("  and back again: {:?}", Packet::from_bytes(&bytes));
+    println!("  a short read:   {:?}", Packet::from_bytes(&bytes[..10]).map_err(|e| e.to_string()));
+
+    // === THE PONG SERVER ===
+
+    let (target, server) = match args.iter().position(|arg| arg == "--to") {
+        Some(i) => match args.get(i + 1).and_then(|addr| addr.parse::<SocketAddr>().ok()) {
+            Some(addr) => (addr, None),
+            None => usage("--to needs an address such as 127.0.0.1:7880"),
+        },
+        None => {
+            let token = CancellationToken::new();
+            let socket = UdpSocket::bind("127.0.0.1:0").unwrap_or_else(|e| fail("could not bind", e));
+            let addr = socket.local_addr().unwrap();
+            let stop = token.clone();
+            let thread = thread::spawn(move || lossy_pongs(&socket, &stop, drop_every));
+            (addr, Some((token, thread)))
+        }
+    };
+
+    // === PINGING ===
+
+    println!("\n--- PING {} ({} bytes per packet) ---", target, PACKET_LEN);
+    let mut pinger = Pinger::connect(target, TIMEOUT).unwrap_or_else(|e| fail("could not open a socket", e));
+    for _ in 0..count {
+        let seq = pinger.next_seq();
+        match pinger.ping() {
+            Ok(Some(rtt)) => println!("{} bytes from {}: seq={} time={:.3} ms", PACKET_LEN, target, seq, ms(rtt)),
+            Ok(None) => println!("seq={} timed out after {} ms", seq, TIMEOUT.as_millis()),
+            // On Linux a ping to a port nobody listens on can come back
+            // as "connection refused", thanks to the connected socket
+            Err(e) => println!("seq={} failed: {}", seq, e),
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+
+    // === STATISTICS ===
+
+    println!("\n--- {} ping statistics ---", target);
+    println!("{}", pinger.stats());
+    if pinger.stats().late > 0 {
+        println!("({} pongs came back after their ping had timed out)", pinger.stats().late);
+    }
+
+    if let Some((token, thread)) = server {
+        token.cancel();
+        let (answered, dropped) = thread.join().unwrap();
+        println!("The server answered {} pings and dropped {} on purpose.", answered, dropped);
+    }
+}
+
+/// Like udp::serve_pongs, but drops every `drop_every`th ping to stand in
+/// for a lossy network. Returns how many pings were answered and dropped.
+fn lossy_pongs(socket: &UdpSocket, token: &CancellationToken, drop_every: u32) -> (u32, u32) {
+    // Wake up now and then to see whether it's time to stop
+    socket.set_read_timeout(Some(Duration::from_millis(50))).unwrap();
+    let mut buf = [0; 64];
+    let (mut answered, mut dropped) = (0, 0);
+    while !token.is_cancelled() {
+        let Ok((len, from)) = socket.recv_from(&mut buf) else { continue };
+        let Ok(packet) = Packet::from_bytes(&buf[..len]) else { continue };
+        if packet.kind != Kind::Ping {
+            continue;
+        }
+        if drop_every > 0 && packet.seq % drop_every == 0 {
+            dropped += 1;
+            continue;
+        }
+        if socket.send_to(&packet.pong().to_bytes(), from).is_ok() {
+            answered += 1;
+        }
+    }
+    (answered, dropped)
+}
+
+/// Runs the library's pong server in the foreground, for --serve.
+fn serve(port: u32) {
+    let port = u16::try_from(port).unwrap_or_else(|_| usage("--serve needs a port from 0 to 65535"));
+    let socket = UdpSocket::bind(("0.0.0.0", port)).unwrap_or_else(|e| fail("could not bind", e));
+    println!("Answering pings on {}. Ping it with --to, stop with Ctrl-C.", socket.local_addr().unwrap());
+    // Never cancelled: the server runs until the process is stopped
+    let token = CancellationToken::new();
+    if let Err(e) = udp::serve_pongs(&socket, &token) {
+        fail("the server stopped", e);
+    }
+}
+
+// === HELPERS ===
+
+fn number_option(args: &[String], name: &str) -> Option<u32> {
+    let i = args.iter().position(|arg| arg == name)?;
+    match args.get(i + 1).and_then(|value| value.parse().ok()) {
+        Some(n) => Some(n),
+        None => usage(&format!("{} needs a number", name)),
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect::<Vec<_>>().join(" ")
+}
+
+fn ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+fn usage(message: &str) -> ! {
+    eprintln!("{}", message);
+    process::exit(2);
+}
+
+fn fail(what: &str, e: std::io::Error) -> ! {
+    eprintln!("{}: {}", what, e);
+    process::exit(1);
+}
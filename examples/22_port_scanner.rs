@@ -0,0 +1,158 @@
+// Port Scanner Example
+// This example is a concurrent TCP port scanner, and a capstone for the
+// concurrency and networking examples: a ThreadPool of workers tries to
+// connect to each port with a timeout, the results come back over an mpsc
+// channel as they finish, and a ProgressBar shows how far the scan is
+//
+// To run this example: cargo run --example 22_port_scanner
+// It starts a few services of its own on 127.0.0.1 so there is something
+// to find, then scans the ports around them. Options:
+//   --host <name>     scan another host (default 127.0.0.1)
+//   --ports <a-b>     scan these ports instead, such as 1-1024
+//   --workers <n>     probes at once (default 64)
+//   --timeout <ms>    how long a silent port gets (default 500)
+//
+// Only scan machines you own or have permission to scan.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::net::TcpListener;
+use std::ops::RangeInclusive;
+use std::process;
+use std::time::{Duration, Instant};
+
+use rustler::net::http::Server;
+use rustler::net::rpc;
+use rustler::term::ProgressBar;
+use rustler::tools::portscan::{PortState, Probe, Scanner};
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let host = option(&args, "--host").unwrap_or("127.0.0.1").to_string();
+    let workers = option(&args, "--workers").map_or(64, |n| parse(n, "--workers"));
+    let timeout = Duration::from_millis(option(&args, "--timeout").map_or(500, |ms| parse(ms, "--timeout")));
+    if workers == 0 {
+        usage("--workers must be at least 1");
+    }
+
+    println!("=== Port Scanner ===\n");
+
+    // === SOMETHING TO FIND ===
+
+    // Three listeners on free ports, kept alive until the end of main:
+    // a web server, a JSON-RPC server, and a bare TcpListener that never
+    // accepts, which still counts as open because the OS completes the
+    // handshake for it
+    let web = Server::new().listen("127.0.0.1:0").unwrap_or_else(|e| fail("could not start the web server", e));
+    let mut methods = rpc::Server::new();
+    methods.register("ping", |_| Ok("pong".into()));
+    let rpc = methods.listen("127.0.0.1:0").unwrap_or_else(|e| fail("could not start the RPC server", e));
+    let bare = TcpListener::bind("127.0.0.1:0").unwrap_or_else(|e| fail("could not bind", e));
+
+    let mut services = BTreeMap::new();
+    services.insert(web.local_addr().port(), "HTTP (net::http)");
+    services.insert(rpc.local_addr().port(), "JSON-RPC (net::rpc)");
+    services.insert(bare.local_addr().unwrap().port(), "a bare TcpListener");
+
+    let ports = match option(&args, "--ports") {
+        Some(range) => parse_range(range).unwrap_or_else(|| usage("--ports needs a range such as 1-1024")),
+        None => {
+            // Free ports can be thousands apart, but closed ports on this
+            // machine refuse at once, so even a wide window is quick
+            let (low, high) = (services.keys().min().unwrap(), services.keys().max().unwrap());
+            low.saturating_sub(5)..=high.saturating_add(5)
+        }
+    };
+    println!("Listening here:");
+    for (port, what) in &services {
+        println!("  {:>5}  {}", port, what);
+    }
+
+    // === SCANNING ===
+
+    // scan() queues one job per port on the pool and returns at once; the
+    // Scan it returns is an iterator over a channel, so this loop runs
+    // while the workers are still probing, and sees results in the order
+    // they finish rather than in port order
+    println!("\nScanning {} ports {}-{} with {} workers...", host, ports.start(), ports.end(), workers);
+    let started = Instant::now();
+    let scan = match Scanner::new().workers(workers).timeout(timeout).scan(&host, ports) {
+        Ok(scan) => scan,
+        Err(e) => fail(&format!("could not resolve {}", host), e),
+    };
+    let bar = ProgressBar::new(scan.total() as u64).label("Probing");
+    let mut found: Vec<Probe> = Vec::new();
+    let mut first_open = Vec::new();
+    for probe in scan {
+        bar.inc(1);
+        if let PortState::Open(_) = probe.state {
+            first_open.push(probe.port);
+        }
+        found.push(probe);
+    }
+    bar.finish();
+    let elapsed = started.elapsed();
+
+    // === THE REPORT ===
+
+    found.sort_by_key(|probe| probe.port);
+    let count = |wanted: fn(&PortState) -> bool| found.iter().filter(|probe| wanted(&probe.state)).count();
+    println!("\nPORT   STATE     CONNECT   SERVICE");
+    for probe in &found {
+        if let PortState::Open(took) = probe.state {
+            let service = services.get(&probe.port).copied().unwrap_or("?");
+            println!("{:<6} open      {:>6.2}ms  {}", probe.port, took.as_secs_f64() * 1000.0, service);
+        }
+    }
+    println!(
+        "\n{} open, {} closed, {} filtered, in {:.2}s",
+        count(|state| matches!(state, PortState::Open(_))),
+        count(|state| *state == PortState::Closed),
+        count(|state| *state == PortState::Filtered),
+        elapsed.as_secs_f64()
+    );
+    println!("The open ports, in the order their probes finished: {:?}", first_open);
+    // A closed port refuses at once; a filtered one costs a whole timeout,
+    // which is what running many probes at once saves on a real network
+    let filtered = count(|state| *state == PortState::Filtered);
+    if filtered > 0 {
+        println!(
+            "One probe at a time would have waited {:.1}s on the filtered ports alone.",
+            timeout.as_secs_f64() * filtered as f64
+        );
+    }
+
+    web.shutdown();
+    rpc.shutdown();
+}
+
+// === OPTIONS ===
+
+fn option<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    let i = args.iter().position(|arg| arg == name)?;
+    match args.get(i + 1) {
+        Some(value) => Some(value),
+        None => usage(&format!("{} needs a value", name)),
+    }
+}
+
+fn parse<T: std::str::FromStr>(value: &str, name: &str) -> T {
+    value.parse().unwrap_or_else(|_| usage(&format!("{} needs a number, not `{}`", name, value)))
+}
+
+/// `80` or `1-1024`.
+fn parse_range(text: &str) -> Option<RangeInclusive<u16>> {
+    let (low, high) = text.split_once('-').unwrap_or((text, text));
+    let (low, high) = (low.trim().parse().ok()?, high.trim().parse().ok()?);
+    (low <= high).then_some(low..=high)
+}
+
+fn usage(message: &str) -> ! {
+    eprintln!("{}", message);
+    process::exit(2);
+}
+
+fn fail(what: &str, e: std::io::Error) -> ! {
+    eprintln!("{}: {}", what, e);
+    process::exit(1);
+}
@@ -0,0 +1,251 @@
+// REST Client Example
+// This example consumes a JSON REST API: it fetches with net::http's
+// Client, deserializes the bodies into serde structs whose optional fields
+// are Option or #[serde(default)], and tells network, HTTP and parse
+// errors apart through the crate's HttpError. A ProgressBar counts the
+// detail requests as they come back
+//
+// To run this example: cargo run --example 23_rest_client
+// It starts a small fixture server on 127.0.0.1 that answers like a crate
+// registry, so it works offline and the data never changes. Option:
+//   --url <url>   fetch any http:// URL that returns JSON and print it
+//                 (there is no TLS, so https:// URLs are refused)
+
+use std::env;
+use std::net::TcpListener;
+use std::process;
+use std::time::Duration;
+
+use rustler::net::http::{Client, HttpError, Response, Server};
+use rustler::term::ProgressBar;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+// === THE MODELS ===
+
+// Only the fields the program uses are declared; serde skips the rest, so
+// the API can add fields without breaking this client
+
+/// GET /api/v1/crates
+#[derive(Debug, Deserialize)]
+struct CrateList {
+    crates: Vec<CrateSummary>,
+    meta: Meta,
+}
+
+#[derive(Debug, Deserialize)]
+struct Meta {
+    total: u64,
+    // null in the JSON and missing altogether both become None
+    next_page: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CrateSummary {
+    name: String,
+    max_version: String,
+    downloads: u64,
+    description: Option<String>,
+    // A missing list is an empty one, rather than an Option to unwrap
+    #[serde(default)]
+    keywords: Vec<String>,
+}
+
+/// GET /api/v1/crates/<name>
+#[derive(Debug, Deserialize)]
+struct CrateResponse {
+    // `crate` is a keyword, so the field needs another name in Rust
+    #[serde(rename = "crate")]
+    info: CrateInfo,
+    #[serde(default)]
+    versions: Vec<Version>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CrateInfo {
+    // The detail has every summary field and a few more
+    #[serde(flatten)]
+    summary: CrateSummary,
+    repository: Option<String>,
+    homepage: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Version {
+    num: String,
+    #[serde(default)]
+    yanked: bool,
+    license: Option<String>,
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let client = Client::new().timeout(Duration::from_secs(5));
+
+    if let Some(i) = args.iter().position(|arg| arg == "--url") {
+        let url = args.get(i + 1).unwrap_or_else(|| usage("--url needs a URL"));
+        // Value takes any JSON at all, for when there is no model yet
+        match client.get_json::<Value>(url) {
+            Ok(value) => println!("{}", serde_json::to_string_pretty(&value).unwrap()),
+            Err(e) => fail(url, &e),
+        }
+        return;
+    }
+
+    println!("=== REST Client ===\n");
+    let fixture = fixture_server().listen("127.0.0.1:0").unwrap_or_else(|e| fail("the fixture server", &e.into()));
+    let base = format!("http://{}/api/v1", fixture.local_addr());
+    println!("Fixture API at {}\n", base);
+
+    // === A LIST ===
+
+    let list: CrateList = match client.get_json(&format!("{}/crates", base)) {
+        Ok(list) => list,
+        Err(e) => fail("the crate list", &e),
+    };
+    println!("{} crates (next page: {}):", list.meta.total, list.meta.next_page.as_deref().unwrap_or("none"));
+    for summary in &list.crates {
+        println!(
+            "  {:<10} {:<8} {:>12} downloads  {}",
+            summary.name,
+            summary.max_version,
+            summary.downloads,
+            summary.description.as_deref().unwrap_or("(no description)")
+        );
+    }
+
+    // === ONE ITEM EACH ===
+
+    // The list says how many requests are coming, so a bar can count them
+    println!();
+    let bar = ProgressBar::new(list.crates.len() as u64).label("Fetching details");
+    let mut details: Vec<CrateResponse> = Vec::new();
+    for summary in &list.crates {
+        match client.get_json(&format!("{}/crates/{}", base, summary.name)) {
+            Ok(detail) => details.push(detail),
+            Err(e) => fail(&summary.name, &e),
+        }
+        bar.inc(1);
+    }
+    bar.finish();
+
+    for detail in &details {
+        let info = &detail.info;
+        println!("\n{} {}", info.summary.name, info.summary.max_version);
+        if !info.summary.keywords.is_empty() {
+            println!("  keywords:   {}", info.summary.keywords.join(", "));
+        }
+        // Option::or picks the first link the API has
+        match info.repository.as_ref().or(info.homepage.as_ref()) {
+            Some(link) => println!("  link:       {}", link),
+            None => println!("  link:       none given"),
+        }
+        let versions: Vec<String> = detail
+            .versions
+            .iter()
+            .map(|v| format!("{}{}", v.num, if v.yanked { " (yanked)" } else { "" }))
+            .collect();
+        println!("  versions:   {}", if versions.is_empty() { "none listed".to_string() } else { versions.join(", ") });
+        let license = detail.versions.first().and_then(|v| v.license.as_deref());
+        println!("  license:    {}", license.unwrap_or("unknown"));
+    }
+
+    // === WHEN THINGS GO WRONG ===
+
+    // Each failure is an HttpError; which variant says whose fault it was
+    println!("\nFour requests that fail, and how:");
+    let closed_port = TcpListener::bind("127.0.0.1:0").and_then(|l| l.local_addr()).map(|addr| addr.port());
+    let failing = [
+        format!("{}/crates/no-such-crate", base),
+        format!("{}/crates/broken", base),
+        format!("http://127.0.0.1:{}/api/v1/crates", closed_port.unwrap_or(1)),
+        "https://crates.io/api/v1/crates".to_string(),
+    ];
+    for url in &failing {
+        match client.get_json::<CrateResponse>(url) {
+            Ok(_) => println!("  {}\n    unexpectedly worked", url),
+            Err(e) => println!("  {}\n    {}: {}", url, kind(&e), e),
+        }
+    }
+
+    fixture.shutdown();
+}
+
+/// The kind of failure, for deciding what to do about it: retrying helps
+/// with the network, not with a URL or a model that is wrong.
+fn kind(e: &HttpError) -> &'static str {
+    match e {
+        HttpError::Io(_) => "network error, worth retrying later",
+        HttpError::Status(status) if status.code() >= 500 => "server error, worth retrying later",
+        HttpError::Status(_) => "HTTP error, the request needs changing",
+        HttpError::Json(_) => "parse error, the model doesn't match the API",
+        HttpError::Malformed(_) | HttpError::TooLarge => "protocol error, the server misbehaved",
+        HttpError::InvalidUrl(_) => "bad URL, this client can't fetch it",
+    }
+}
+
+// === THE FIXTURE SERVER ===
+
+/// A registry-like API with fixed data. Some crates leave optional fields
+/// out or set them to null, and one answers with the wrong shape.
+fn fixture_server() -> Server {
+    let crates = [
+        json!({
+            "name": "serde", "max_version": "1.0.210", "downloads": 412_000_000,
+            "description": "A generic serialization/deserialization framework",
+            "keywords": ["serde", "serialization", "no_std"],
+            "repository": "https://github.com/serde-rs/serde",
+            "homepage": "https://serde.rs",
+            "versions": [{"num": "1.0.210", "license": "MIT OR Apache-2.0"}, {"num": "1.0.209", "yanked": false}],
+        }),
+        json!({
+            "name": "rand", "max_version": "0.8.5", "downloads": 350_000_000,
+            "description": "Random number generators and other randomness functionality.",
+            "keywords": ["random", "rng"], "repository": null,
+            "homepage": "https://rust-random.github.io/book",
+            "versions": [{"num": "0.8.5", "license": "MIT OR Apache-2.0"}, {"num": "0.8.4", "yanked": true}],
+        }),
+        json!({
+            "name": "rustler", "max_version": "0.1.0", "downloads": 42,
+            "description": null,
+        }),
+    ];
+
+    let mut server = Server::new();
+    let mut summaries = Vec::new();
+    for mut info in crates {
+        let name = info["name"].as_str().unwrap().to_string();
+        let fields = info.as_object_mut().unwrap();
+        // Left out rather than sent as null when there are none, which
+        // #[serde(default)] allows and a plain Vec wouldn't
+        let mut body = json!({});
+        if let Some(versions) = fields.remove("versions") {
+            body["versions"] = versions;
+        }
+        let mut summary = fields.clone();
+        summary.retain(|field, _| !["repository", "homepage"].contains(&field.as_str()));
+        summaries.push(summary);
+        body["crate"] = info;
+        let body = body.to_string();
+        server.get(&format!("/api/v1/crates/{}", name), move |_| Response::json(body.clone()));
+    }
+    let list = json!({"crates": summaries, "meta": {"total": summaries.len(), "next_page": null}}).to_string();
+    server.get("/api/v1/crates", move |_| Response::json(list.clone()));
+    // Downloads as a string: valid JSON, but not what the model says
+    server.get("/api/v1/crates/broken", |_| {
+        Response::json(r#"{"crate": {"name": "broken", "max_version": "1.0.0", "downloads": "lots"}}"#)
+    });
+    // Any other path gets the server's own 404 page, which isn't JSON;
+    // get_json checks the status before it parses, so that doesn't matter
+    server
+}
+
+fn usage(message: &str) -> ! {
+    eprintln!("{}", message);
+    process::exit(2);
+}
+
+fn fail(what: &str, e: &HttpError) -> ! {
+    eprintln!("{}: {} ({})", what, e, kind(e));
+    process::exit(1);
+}
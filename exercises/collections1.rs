@@ -0,0 +1,28 @@
+// Exercise: collections1
+// Practises: 07_collections
+//
+// Replace each todo!() so the hidden tests pass, then check your work with
+//   cargo run --bin rustler -- exercise collections1
+// Stuck? `cargo run --bin rustler -- hint collections1` gives a hint; run it
+// again for the next one, and for the solution after the last.
+
+use std::collections::HashMap;
+
+/// How often each word appears in `text`, ignoring case. Words are
+/// separated by whitespace.
+///
+/// Hint: `map.entry(key).or_insert(0)` gives a `&mut` to the count.
+pub fn word_counts(text: &str) -> HashMap<String, usize> {
+    todo!()
+}
+
+/// The most frequent word and its count, or `None` for empty text. On a
+/// tie, pick the word that comes first alphabetically.
+pub fn most_common(text: &str) -> Option<(String, usize)> {
+    todo!()
+}
+
+/// The even numbers from `numbers`, squared, in their original order.
+pub fn even_squares(numbers: &[i32]) -> Vec<i32> {
+    todo!()
+}
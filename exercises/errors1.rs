@@ -0,0 +1,29 @@
+// Exercise: errors1
+// Practises: 08_error_handling
+//
+// Replace each todo!() so the hidden tests pass, then check your work with
+//   cargo run --bin rustler -- exercise errors1
+// Stuck? `cargo run --bin rustler -- hint errors1` gives a hint; run it
+// again for the next one, and for the solution after the last.
+
+use std::num::ParseIntError;
+
+/// Parses a quantity such as `"12"`, allowing spaces around it.
+pub fn parse_quantity(text: &str) -> Result<u32, ParseIntError> {
+    todo!()
+}
+
+/// The total price of `"<quantity> x <price in cents>"`, such as
+/// `"3 x 250"`. Use `?` so that either number failing to parse returns
+/// its error.
+pub fn total_cents(order: &str) -> Result<u32, String> {
+    let (quantity, price) = order.split_once('x').ok_or("expected <quantity> x <price>")?;
+    // TODO: parse both halves with parse_quantity, turning a ParseIntError
+    // into a String with .map_err(|e| e.to_string())
+    todo!()
+}
+
+/// The first number in `items` that parses, or `None`.
+pub fn first_number(items: &[&str]) -> Option<u32> {
+    todo!()
+}
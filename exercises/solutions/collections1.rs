@@ -0,0 +1,35 @@
+// Exercise: collections1
+// Practises: 07_collections
+//
+// Replace each todo!() so the hidden tests pass, then check your work with
+//   cargo run --bin rustler -- exercise collections1
+// Stuck? `cargo run --bin rustler -- hint collections1` gives a hint; run it
+// again for the next one, and for the solution after the last.
+
+use std::collections::HashMap;
+
+/// How often each word appears in `text`, ignoring case. Words are
+/// separated by whitespace.
+///
+/// Hint: `map.entry(key).or_insert(0)` gives a `&mut` to the count.
+pub fn word_counts(text: &str) -> HashMap<String, usize> {
+    let mut counts = HashMap::new();
+    for word in text.split_whitespace() {
+        *counts.entry(word.to_lowercase()).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// The most frequent word and its count, or `None` for empty text. On a
+/// tie, pick the word that comes first alphabetically.
+pub fn most_common(text: &str) -> Option<(String, usize)> {
+    let mut counts: Vec<(String, usize)> = word_counts(text).into_iter().collect();
+    // Highest count first, then alphabetical
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts.into_iter().next()
+}
+
+/// The even numbers from `numbers`, squared, in their original order.
+pub fn even_squares(numbers: &[i32]) -> Vec<i32> {
+    numbers.iter().filter(|n| *n % 2 == 0).map(|n| n * n).collect()
+}
@@ -0,0 +1,24 @@
+// Exercise: functions1
+// Practises: 04_functions
+//
+// Replace each todo!() so the hidden tests pass, then check your work with
+//   cargo run --bin rustler -- exercise functions1
+// Stuck? `cargo run --bin rustler -- hint functions1` gives a hint; run it
+// again for the next one, and for the solution after the last.
+
+/// The area of a `width` by `height` rectangle. Return it as the last
+/// expression, without a semicolon.
+pub fn area(width: u32, height: u32) -> u32 {
+    width * height
+}
+
+/// A closure that adds `amount` to whatever it is given, boxed so it can
+/// be returned. The closure must own `amount`, so it needs `move`.
+pub fn make_adder(amount: i32) -> Box<dyn Fn(i32) -> i32> {
+    Box::new(move |x| x + amount)
+}
+
+/// Calls `f` twice, feeding the first result into the second call.
+pub fn apply_twice(f: impl Fn(i32) -> i32, value: i32) -> i32 {
+    f(f(value))
+}
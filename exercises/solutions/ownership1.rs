@@ -0,0 +1,30 @@
+// Exercise: ownership1
+// Practises: 05_ownership_borrowing
+//
+// Replace each todo!() so the hidden tests pass, then check your work with
+//   cargo run --bin rustler -- exercise ownership1
+// Stuck? `cargo run --bin rustler -- hint ownership1` gives a hint; run it
+// again for the next one, and for the solution after the last.
+//
+// The tests keep using their strings after calling these functions, so the
+// functions must borrow rather than take ownership.
+
+/// The number of characters (not bytes) in `text`.
+pub fn char_count(text: &str) -> usize {
+    text.chars().count()
+}
+
+/// Appends `"!"` to the string in place. The `&mut` lets the function
+/// change a string it does not own.
+pub fn exclaim(text: &mut String) {
+    text.push('!');
+}
+
+/// The first word of `text`, as a slice of it: everything before the first
+/// space, or the whole string if there is none.
+pub fn first_word(text: &str) -> &str {
+    match text.find(' ') {
+        Some(space) => &text[..space],
+        None => text,
+    }
+}
@@ -0,0 +1,48 @@
+// Exercise: structs1
+// Practises: 06_structs_enums
+//
+// Replace each todo!() so the hidden tests pass, then check your work with
+//   cargo run --bin rustler -- exercise structs1
+// Stuck? `cargo run --bin rustler -- hint structs1` gives a hint; run it
+// again for the next one, and for the solution after the last.
+
+#[derive(Debug, PartialEq)]
+pub struct Rectangle {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rectangle {
+    /// An associated function: a square with sides of `size`.
+    pub fn square(size: u32) -> Rectangle {
+        Rectangle {
+            width: size,
+            height: size,
+        }
+    }
+
+    pub fn area(&self) -> u32 {
+        self.width * self.height
+    }
+
+    /// Whether `other` fits entirely inside `self` without rotating it.
+    pub fn can_hold(&self, other: &Rectangle) -> bool {
+        self.width >= other.width && self.height >= other.height
+    }
+}
+
+#[derive(Debug)]
+pub enum Shape {
+    Circle { radius: f64 },
+    Rect(Rectangle),
+    Point,
+}
+
+/// The area of any shape. Use `match`, with one arm per variant.
+pub fn shape_area(shape: &Shape) -> f64 {
+    match shape {
+        Shape::Circle { radius } => std::f64::consts::PI * radius * radius,
+        Shape::Rect(rect) => rect.area() as f64,
+        Shape::Point => 0.0,
+    }
+}
@@ -0,0 +1,31 @@
+// Exercise: variables1
+// Practises: 02_variables_and_types
+//
+// Replace each todo!() so the hidden tests pass, then check your work with
+//   cargo run --bin rustler -- exercise variables1
+// Stuck? `cargo run --bin rustler -- hint variables1` gives a hint; run it
+// again for the next one, and for the solution after the last.
+
+/// Doubles `n` three times by shadowing: each step is a new `let` with the
+/// same name, so no `mut` is needed.
+pub fn double_three_times(n: i32) -> i32 {
+    let n = n * 2;
+    let n = n * 2;
+    let n = n * 2;
+    n
+}
+
+/// Adds up the numbers from 1 to `limit` with a mutable running total.
+pub fn sum_to(limit: u32) -> u32 {
+    let mut total = 0;
+    for i in 1..=limit {
+        total += i;
+    }
+    total
+}
+
+/// Returns the tuple with its two parts swapped.
+pub fn swap(pair: (char, bool)) -> (bool, char) {
+    let (a, b) = pair;
+    (b, a)
+}
@@ -0,0 +1,41 @@
+// Exercise: structs1
+// Practises: 06_structs_enums
+//
+// Replace each todo!() so the hidden tests pass, then check your work with
+//   cargo run --bin rustler -- exercise structs1
+// Stuck? `cargo run --bin rustler -- hint structs1` gives a hint; run it
+// again for the next one, and for the solution after the last.
+
+#[derive(Debug, PartialEq)]
+pub struct Rectangle {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rectangle {
+    /// An associated function: a square with sides of `size`.
+    pub fn square(size: u32) -> Rectangle {
+        todo!()
+    }
+
+    pub fn area(&self) -> u32 {
+        todo!()
+    }
+
+    /// Whether `other` fits entirely inside `self` without rotating it.
+    pub fn can_hold(&self, other: &Rectangle) -> bool {
+        todo!()
+    }
+}
+
+#[derive(Debug)]
+pub enum Shape {
+    Circle { radius: f64 },
+    Rect(Rectangle),
+    Point,
+}
+
+/// The area of any shape. Use `match`, with one arm per variant.
+pub fn shape_area(shape: &Shape) -> f64 {
+    todo!()
+}
@@ -0,0 +1,21 @@
+#[test]
+fn counts_words_ignoring_case() {
+    let counts = word_counts("the cat The hat\nthe");
+    assert_eq!(counts["the"], 3);
+    assert_eq!(counts["cat"], 1);
+    assert_eq!(counts.len(), 3);
+    assert!(word_counts("   ").is_empty());
+}
+
+#[test]
+fn finds_the_most_common_word() {
+    assert_eq!(most_common("a b b c c"), Some(("b".to_string(), 2)));
+    assert_eq!(most_common("Rust rust RUST go"), Some(("rust".to_string(), 3)));
+    assert_eq!(most_common(""), None);
+}
+
+#[test]
+fn squares_the_evens() {
+    assert_eq!(even_squares(&[1, 2, 3, 4, -6]), vec![4, 16, 36]);
+    assert!(even_squares(&[]).is_empty());
+}
@@ -0,0 +1,19 @@
+#[test]
+fn parses_quantities() {
+    assert_eq!(parse_quantity(" 12 "), Ok(12));
+    assert!(parse_quantity("twelve").is_err());
+    assert!(parse_quantity("-1").is_err());
+}
+
+#[test]
+fn totals_an_order() {
+    assert_eq!(total_cents("3 x 250"), Ok(750));
+    assert_eq!(total_cents("3 250"), Err("expected <quantity> x <price>".to_string()));
+    assert_eq!(total_cents("three x 250"), Err("invalid digit found in string".to_string()));
+}
+
+#[test]
+fn finds_the_first_number() {
+    assert_eq!(first_number(&["a", "7", "8"]), Some(7));
+    assert_eq!(first_number(&["a", "b"]), None);
+}
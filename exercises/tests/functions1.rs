@@ -0,0 +1,18 @@
+#[test]
+fn computes_area() {
+    assert_eq!(area(3, 4), 12);
+    assert_eq!(area(0, 9), 0);
+}
+
+#[test]
+fn adder_remembers_its_amount() {
+    let add_five = make_adder(5);
+    assert_eq!(add_five(1), 6);
+    assert_eq!(add_five(-5), 0);
+}
+
+#[test]
+fn applies_twice() {
+    assert_eq!(apply_twice(|x| x * 3, 2), 18);
+    assert_eq!(apply_twice(make_adder(10), 0), 20);
+}
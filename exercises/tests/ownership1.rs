@@ -0,0 +1,22 @@
+#[test]
+fn counts_characters_not_bytes() {
+    let word = String::from("héllo");
+    assert_eq!(char_count(&word), 5);
+    assert_eq!(word.len(), 6);
+}
+
+#[test]
+fn exclaims_in_place() {
+    let mut greeting = String::from("hello");
+    exclaim(&mut greeting);
+    exclaim(&mut greeting);
+    assert_eq!(greeting, "hello!!");
+}
+
+#[test]
+fn finds_first_word() {
+    let sentence = String::from("borrow checker rules");
+    assert_eq!(first_word(&sentence), "borrow");
+    assert_eq!(first_word("single"), "single");
+    assert_eq!(first_word(""), "");
+}
@@ -0,0 +1,22 @@
+#[test]
+fn builds_squares() {
+    assert_eq!(Rectangle::square(3), Rectangle { width: 3, height: 3 });
+}
+
+#[test]
+fn computes_area_and_fit() {
+    let big = Rectangle { width: 8, height: 7 };
+    let small = Rectangle { width: 5, height: 1 };
+    assert_eq!(big.area(), 56);
+    assert!(big.can_hold(&small));
+    assert!(!small.can_hold(&big));
+    assert!(big.can_hold(&Rectangle { width: 8, height: 7 }));
+}
+
+#[test]
+fn matches_every_shape() {
+    let circle = shape_area(&Shape::Circle { radius: 1.0 });
+    assert!((circle - std::f64::consts::PI).abs() < 1e-9);
+    assert_eq!(shape_area(&Shape::Rect(Rectangle::square(4))), 16.0);
+    assert_eq!(shape_area(&Shape::Point), 0.0);
+}
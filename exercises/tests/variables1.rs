@@ -0,0 +1,17 @@
+#[test]
+fn doubles_three_times() {
+    assert_eq!(double_three_times(1), 8);
+    assert_eq!(double_three_times(-5), -40);
+}
+
+#[test]
+fn sums_a_range() {
+    assert_eq!(sum_to(0), 0);
+    assert_eq!(sum_to(4), 10);
+    assert_eq!(sum_to(100), 5050);
+}
+
+#[test]
+fn swaps_a_pair() {
+    assert_eq!(swap(('x', true)), (true, 'x'));
+}
@@ -0,0 +1,10 @@
+//! Algorithms implemented by hand for study.
+//!
+//! These favour clarity over raw speed; the benchmarks in `benches/`
+//! compare them with their standard library counterparts.
+
+pub mod primes;
+pub mod sort;
+
+pub use primes::{is_prime, primes_up_to};
+pub use sort::{heap_sort, insertion_sort, merge_sort, parallel_merge_sort, quick_sort};
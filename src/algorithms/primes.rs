@@ -0,0 +1,73 @@
+//! Prime numbers by the sieve of Eratosthenes.
+//!
+//! The sieve starts by assuming every number is prime, then for each prime
+//! `p` crosses out its multiples from `p * p` upward. Whatever survives is
+//! prime. The crossed-out numbers live in a [`BitSet`], one bit per number.
+
+use crate::collections::BitSet;
+
+/// Every prime up to and including `limit`, in ascending order.
+///
+/// ```
+/// use rustler::algorithms::primes::primes_up_to;
+///
+/// assert_eq!(primes_up_to(20), vec![2, 3, 5, 7, 11, 13, 17, 19]);
+/// ```
+///
+/// # Panics
+///
+/// Panics if `limit` is `usize::MAX`, as the sieve would need one more
+/// number than `usize` can count.
+pub fn primes_up_to(limit: usize) -> Vec<usize> {
+    let size = limit.checked_add(1).expect("limit is too large to sieve");
+    let mut composite = BitSet::with_capacity(size);
+    let mut primes = Vec::new();
+    for n in 2..=limit {
+        if composite.contains(n) {
+            continue;
+        }
+        primes.push(n);
+        // Smaller multiples were already crossed out by smaller primes
+        if let Some(square) = n.checked_mul(n) {
+            for multiple in (square..=limit).step_by(n) {
+                composite.insert(multiple);
+            }
+        }
+    }
+    primes
+}
+
+/// Returns true if `n` is prime, by trial division up to its square root.
+pub fn is_prime(n: usize) -> bool {
+    if n < 2 {
+        return false;
+    }
+    (2..).take_while(|d| d * d <= n).all(|d| !n.is_multiple_of(d))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_small_limits() {
+        assert!(primes_up_to(0).is_empty());
+        assert!(primes_up_to(1).is_empty());
+        assert_eq!(primes_up_to(2), vec![2]);
+        assert_eq!(primes_up_to(30), vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+    }
+
+    #[test]
+    fn test_sieve_agrees_with_trial_division() {
+        let sieved = primes_up_to(5_000);
+        let trial: Vec<usize> = (0..=5_000).filter(|&n| is_prime(n)).collect();
+        assert_eq!(sieved, trial);
+        assert_eq!(sieved.len(), 669);
+    }
+
+    #[test]
+    #[should_panic(expected = "too large to sieve")]
+    fn test_largest_limit_is_rejected() {
+        primes_up_to(usize::MAX);
+    }
+}
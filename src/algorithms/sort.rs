@@ -0,0 +1,414 @@
+//! Classic comparison sorts.
+//!
+//! Every algorithm comes in two forms with the same shape as the standard
+//! library's `sort` and `sort_by`: one for `T: Ord`, and a `_by` variant
+//! taking a comparison closure. They all sort in place and need no `Clone`.
+//!
+//! | Algorithm        | Time (average) | Time (worst) | Stable |
+//! |------------------|----------------|--------------|--------|
+//! | `insertion_sort` | O(n²)          | O(n²)        | yes    |
+//! | `merge_sort`     | O(n log n)     | O(n log n)   | yes    |
+//! | `quick_sort`     | O(n log n)     | O(n²)        | no     |
+//! | `heap_sort`      | O(n log n)     | O(n log n)   | no     |
+//!
+//! [`parallel_merge_sort`] is merge sort with the two halves of each large
+//! split sorted on separate threads.
+
+use std::cmp::Ordering;
+use std::thread;
+
+/// Below this length quick sort hands over to insertion sort, which is
+/// faster on tiny slices.
+const SMALL_SLICE: usize = 16;
+
+/// Below this length parallel merge sort stops spawning threads: sorting a
+/// few thousand items is quicker than starting a thread to do it.
+const PARALLEL_THRESHOLD: usize = 8_192;
+
+/// Sorts by growing a sorted prefix, inserting each new item into place.
+///
+/// Fast for short or nearly-sorted input.
+///
+/// ```
+/// use rustler::algorithms::sort::insertion_sort;
+///
+/// let mut values = [5, 2, 4, 1, 3];
+/// insertion_sort(&mut values);
+/// assert_eq!(values, [1, 2, 3, 4, 5]);
+/// ```
+pub fn insertion_sort<T: Ord>(slice: &mut [T]) {
+    insertion_sort_by(slice, T::cmp);
+}
+
+pub fn insertion_sort_by<T, F>(slice: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    for i in 1..slice.len() {
+        let mut j = i;
+        while j > 0 && compare(&slice[j - 1], &slice[j]) == Ordering::Greater {
+            slice.swap(j - 1, j);
+            j -= 1;
+        }
+    }
+}
+
+/// Sorts by splitting in half, sorting each half, and merging them.
+///
+/// Stable: equal items keep their original order.
+pub fn merge_sort<T: Ord>(slice: &mut [T]) {
+    merge_sort_by(slice, T::cmp);
+}
+
+/// Merge sort normally copies items into a scratch buffer, which would
+/// need `T: Clone`. Instead this sorts a list of *indices* into the slice
+/// and then moves the items into their final places with swaps.
+pub fn merge_sort_by<T, F>(slice: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut order: Vec<usize> = (0..slice.len()).collect();
+    let mut scratch = order.clone();
+    merge_sort_indices(&mut order, &mut scratch, &mut |&a, &b| compare(&slice[a], &slice[b]));
+    apply_permutation(slice, order);
+}
+
+fn merge_sort_indices<F>(order: &mut [usize], scratch: &mut [usize], compare: &mut F)
+where
+    F: FnMut(&usize, &usize) -> Ordering,
+{
+    let len = order.len();
+    if len <= 1 {
+        return;
+    }
+    let mid = len / 2;
+    merge_sort_indices(&mut order[..mid], &mut scratch[..mid], compare);
+    merge_sort_indices(&mut order[mid..], &mut scratch[mid..], compare);
+
+    merge_halves(order, scratch, mid, compare);
+}
+
+/// Merges the sorted runs `order[..mid]` and `order[mid..]` through
+/// `scratch`.
+fn merge_halves<F>(order: &mut [usize], scratch: &mut [usize], mid: usize, compare: &mut F)
+where
+    F: FnMut(&usize, &usize) -> Ordering,
+{
+    let len = order.len();
+    let (mut left, mut right) = (0, mid);
+    for slot in scratch.iter_mut().take(len) {
+        // Taking from the left on ties is what keeps the sort stable
+        let take_left = right == len || (left < mid && compare(&order[left], &order[right]) != Ordering::Greater);
+        if take_left {
+            *slot = order[left];
+            left += 1;
+        } else {
+            *slot = order[right];
+            right += 1;
+        }
+    }
+    order.copy_from_slice(&scratch[..len]);
+}
+
+/// Merge sort that sorts the halves of large slices on separate threads.
+///
+/// Slices are split until they are shorter than a threshold, so a slice of
+/// n items uses roughly n / 8192 threads. Stable, like [`merge_sort`].
+///
+/// ```
+/// use rustler::algorithms::parallel_merge_sort;
+///
+/// let mut values: Vec<u32> = (0..20_000).rev().collect();
+/// parallel_merge_sort(&mut values);
+/// assert!(values.windows(2).all(|pair| pair[0] <= pair[1]));
+/// ```
+pub fn parallel_merge_sort<T: Ord + Sync>(slice: &mut [T]) {
+    parallel_merge_sort_by(slice, T::cmp);
+}
+
+pub fn parallel_merge_sort_by<T, F>(slice: &mut [T], compare: F)
+where
+    T: Sync,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    let mut order: Vec<usize> = (0..slice.len()).collect();
+    let mut scratch = order.clone();
+    {
+        let items: &[T] = slice;
+        let compare = |&a: &usize, &b: &usize| compare(&items[a], &items[b]);
+        parallel_sort_indices(&mut order, &mut scratch, &compare);
+    }
+    apply_permutation(slice, order);
+}
+
+fn parallel_sort_indices<F>(order: &mut [usize], scratch: &mut [usize], compare: &F)
+where
+    F: Fn(&usize, &usize) -> Ordering + Sync,
+{
+    if order.len() < PARALLEL_THRESHOLD {
+        merge_sort_indices(order, scratch, &mut |a, b| compare(a, b));
+        return;
+    }
+    let mid = order.len() / 2;
+    {
+        let (left, right) = order.split_at_mut(mid);
+        let (left_scratch, right_scratch) = scratch.split_at_mut(mid);
+        // Sort the left half on a new thread and the right half on this one
+        thread::scope(|scope| {
+            scope.spawn(|| parallel_sort_indices(left, left_scratch, compare));
+            parallel_sort_indices(right, right_scratch, compare);
+        });
+    }
+    merge_halves(order, scratch, mid, &mut |a, b| compare(a, b));
+}
+
+/// Rearranges `slice` so that position `i` ends up holding the item that
+/// was at `order[i]`, following each cycle of the permutation with swaps.
+fn apply_permutation<T>(slice: &mut [T], mut order: Vec<usize>) {
+    for start in 0..slice.len() {
+        let mut current = start;
+        while order[current] != start {
+            let next = order[current];
+            slice.swap(current, next);
+            order[current] = current;
+            current = next;
+        }
+        order[current] = current;
+    }
+}
+
+/// Sorts by picking a pivot, partitioning smaller items to its left and
+/// larger ones to its right, and recursing on each side.
+///
+/// Uses the median of the first, middle and last items as the pivot so
+/// already-sorted input does not hit the O(n²) worst case.
+pub fn quick_sort<T: Ord>(slice: &mut [T]) {
+    quick_sort_by(slice, T::cmp);
+}
+
+pub fn quick_sort_by<T, F>(slice: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    quick_sort_recursive(slice, &mut compare);
+}
+
+fn quick_sort_recursive<T, F>(mut slice: &mut [T], compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    // Recurse into the smaller side and loop on the larger one, so the
+    // recursion depth stays O(log n)
+    while slice.len() > SMALL_SLICE {
+        let pivot = partition(slice, compare);
+        let (left, right) = slice.split_at_mut(pivot);
+        let right = &mut right[1..];
+        if left.len() < right.len() {
+            quick_sort_recursive(left, compare);
+            slice = right;
+        } else {
+            quick_sort_recursive(right, compare);
+            slice = left;
+        }
+    }
+    insertion_sort_by(slice, compare);
+}
+
+/// Lomuto partition around a median-of-three pivot. Returns the pivot's
+/// final index.
+fn partition<T, F>(slice: &mut [T], compare: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let last = slice.len() - 1;
+    let mid = last / 2;
+    // Order first, middle and last so the median lands in the middle
+    if compare(&slice[mid], &slice[0]) == Ordering::Less {
+        slice.swap(mid, 0);
+    }
+    if compare(&slice[last], &slice[0]) == Ordering::Less {
+        slice.swap(last, 0);
+    }
+    if compare(&slice[last], &slice[mid]) == Ordering::Less {
+        slice.swap(last, mid);
+    }
+    slice.swap(mid, last);
+
+    let mut store = 0;
+    for i in 0..last {
+        if compare(&slice[i], &slice[last]) == Ordering::Less {
+            slice.swap(i, store);
+            store += 1;
+        }
+    }
+    slice.swap(store, last);
+    store
+}
+
+/// Sorts by arranging the slice into a max-heap, then repeatedly swapping
+/// the largest item to the end and restoring the heap in what remains.
+///
+/// O(n log n) in every case and needs no extra memory.
+pub fn heap_sort<T: Ord>(slice: &mut [T]) {
+    heap_sort_by(slice, T::cmp);
+}
+
+pub fn heap_sort_by<T, F>(slice: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    for i in (0..slice.len() / 2).rev() {
+        sift_down(slice, i, &mut compare);
+    }
+    for end in (1..slice.len()).rev() {
+        slice.swap(0, end);
+        sift_down(&mut slice[..end], 0, &mut compare);
+    }
+}
+
+fn sift_down<T, F>(heap: &mut [T], mut index: usize, compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    loop {
+        let left = 2 * index + 1;
+        let right = left + 1;
+        let mut largest = index;
+        if left < heap.len() && compare(&heap[left], &heap[largest]) == Ordering::Greater {
+            largest = left;
+        }
+        if right < heap.len() && compare(&heap[right], &heap[largest]) == Ordering::Greater {
+            largest = right;
+        }
+        if largest == index {
+            return;
+        }
+        heap.swap(index, largest);
+        index = largest;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type SortFn = fn(&mut [i64]);
+
+    const SORTS: [(&str, SortFn); 4] = [
+        ("insertion", insertion_sort),
+        ("merge", merge_sort),
+        ("quick", quick_sort),
+        ("heap", heap_sort),
+    ];
+
+    /// A small xorshift generator so the property tests are repeatable
+    /// without pulling in a random number crate.
+    struct XorShift(u64);
+
+    impl XorShift {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn vec(&mut self, len: usize, range: u64) -> Vec<i64> {
+            (0..len).map(|_| (self.next() % range) as i64 - (range / 2) as i64).collect()
+        }
+    }
+
+    #[test]
+    fn test_matches_std_sort_on_random_input() {
+        let mut rng = XorShift(0x2545_f491_4f6c_dd1d);
+        for round in 0..300 {
+            let len = (rng.next() % 200) as usize;
+            // Alternate between many duplicates and mostly distinct values
+            let range = if round % 2 == 0 { 10 } else { 1_000_000 };
+            let input = rng.vec(len, range);
+            let mut expected = input.clone();
+            expected.sort();
+            for (name, sort) in SORTS {
+                let mut actual = input.clone();
+                sort(&mut actual);
+                assert_eq!(actual, expected, "{} sort failed on {:?}", name, input);
+            }
+        }
+    }
+
+    #[test]
+    fn test_edge_cases() {
+        let cases: [Vec<i64>; 6] = [
+            vec![],
+            vec![1],
+            vec![2, 1],
+            (0..100).collect(),
+            (0..100).rev().collect(),
+            vec![7; 50],
+        ];
+        for input in cases {
+            let mut expected = input.clone();
+            expected.sort();
+            for (name, sort) in SORTS {
+                let mut actual = input.clone();
+                sort(&mut actual);
+                assert_eq!(actual, expected, "{} sort failed on {:?}", name, input);
+            }
+        }
+    }
+
+    #[test]
+    fn test_by_variants() {
+        let mut words = vec!["pear", "fig", "banana", "kiwi"];
+        quick_sort_by(&mut words, |a, b| b.cmp(a));
+        assert_eq!(words, vec!["pear", "kiwi", "fig", "banana"]);
+        heap_sort_by(&mut words, |a, b| a.len().cmp(&b.len()).then(a.cmp(b)));
+        assert_eq!(words, vec!["fig", "kiwi", "pear", "banana"]);
+    }
+
+    #[test]
+    fn test_stable_sorts_keep_ties_in_order() {
+        let mut rng = XorShift(99);
+        let input: Vec<(u64, usize)> = (0..500).map(|i| (rng.next() % 5, i)).collect();
+        let mut expected = input.clone();
+        expected.sort_by_key(|&(key, _)| key);
+
+        let mut merged = input.clone();
+        merge_sort_by(&mut merged, |a, b| a.0.cmp(&b.0));
+        assert_eq!(merged, expected);
+        let mut inserted = input;
+        insertion_sort_by(&mut inserted, |a, b| a.0.cmp(&b.0));
+        assert_eq!(inserted, expected);
+    }
+
+    #[test]
+    fn test_parallel_merge_sort_matches_std_sort() {
+        let mut rng = XorShift(7);
+        for len in [0, 1, 100, PARALLEL_THRESHOLD, 50_000] {
+            let input = rng.vec(len, 1_000);
+            let mut expected = input.clone();
+            expected.sort();
+            let mut actual = input;
+            parallel_merge_sort(&mut actual);
+            assert_eq!(actual, expected, "length {}", len);
+        }
+    }
+
+    #[test]
+    fn test_parallel_merge_sort_is_stable() {
+        let mut rng = XorShift(3);
+        let input: Vec<(u64, usize)> = (0..30_000).map(|i| (rng.next() % 10, i)).collect();
+        let mut expected = input.clone();
+        expected.sort_by_key(|&(key, _)| key);
+        let mut actual = input;
+        parallel_merge_sort_by(&mut actual, |a, b| a.0.cmp(&b.0));
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_sorts_non_clone_items() {
+        let mut owned: Vec<String> = ["b", "c", "a"].iter().map(|s| s.to_string()).collect();
+        merge_sort(&mut owned);
+        assert_eq!(owned, vec!["a", "b", "c"]);
+    }
+}
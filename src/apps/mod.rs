@@ -0,0 +1,5 @@
+//! Small complete applications built from the rest of the library.
+
+pub mod todo;
+
+pub use todo::{Command, Task, TodoError, TodoList, UsageError};
@@ -0,0 +1,479 @@
+//! A to-do list that persists to a JSON file.
+//!
+//! Tasks get increasing ids that are never reused, so `complete 3` always
+//! means the same task even after others are removed.
+//!
+//! [`Command::parse`] reads the command-line interface shared by the
+//! `16_todo_app` example and the `todo` binary.
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::io::dirs::{self, UserDir};
+use crate::store::versioned::{self, VersionError};
+
+/// The format version written by [`TodoList::save`].
+pub const SAVE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Task {
+    pub id: u32,
+    pub title: String,
+    pub done: bool,
+    pub due: Option<NaiveDate>,
+}
+
+impl Task {
+    /// Whether the task is unfinished and its due date is before `today`.
+    pub fn is_overdue(&self, today: NaiveDate) -> bool {
+        !self.done && self.due.is_some_and(|due| due < today)
+    }
+}
+
+impl fmt::Display for Task {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mark = if self.done { 'x' } else { ' ' };
+        write!(f, "[{}] {:>3}  {}", mark, self.id, self.title)?;
+        if let Some(due) = self.due {
+            write!(f, " (due {})", due)?;
+        }
+        Ok(())
+    }
+}
+
+/// Which tasks [`TodoList::list`] returns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Filter {
+    All,
+    Pending,
+    Done,
+}
+
+/// Tasks in the order they were added.
+///
+/// ```
+/// use rustler::apps::todo::{Filter, TodoList};
+///
+/// let mut todos = TodoList::new();
+/// let milk = todos.add("Buy milk", None).unwrap();
+/// todos.add("Write report", "2026-03-01".parse().ok()).unwrap();
+/// todos.complete(milk).unwrap();
+///
+/// let pending: Vec<&str> = todos.list(Filter::Pending).map(|t| t.title.as_str()).collect();
+/// assert_eq!(pending, vec!["Write report"]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TodoList {
+    tasks: Vec<Task>,
+    next_id: u32,
+}
+
+/// What is written to disk: the list plus a version number.
+#[derive(Serialize, Deserialize)]
+struct SaveFile {
+    version: u32,
+    #[serde(flatten)]
+    list: TodoList,
+}
+
+impl TodoList {
+    pub fn new() -> Self {
+        TodoList::default()
+    }
+
+    /// Where the list lives by default: `todo.json` in the user's data
+    /// directory, as found by [`dirs::user_dir`]. `None` if that is
+    /// unknown.
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::user_dir(UserDir::Data).map(|dir| dir.join("todo.json"))
+    }
+
+    /// Reads a list written by [`save`](TodoList::save). A file that does
+    /// not exist yet is an empty list.
+    pub fn load(path: impl AsRef<Path>) -> Result<TodoList, TodoError> {
+        let json = match fs::read_to_string(path) {
+            Ok(json) => json,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(TodoList::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let file: SaveFile = versioned::from_json(&json, SAVE_VERSION)?;
+        Ok(file.list)
+    }
+
+    /// Writes the list as JSON, creating missing parent directories.
+    ///
+    /// The JSON goes to a temporary file that is then renamed over `path`,
+    /// so an interrupted save never leaves a half-written list behind.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), TodoError> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let file = SaveFile {
+            version: SAVE_VERSION,
+            list: self.clone(),
+        };
+        let json = serde_json::to_string_pretty(&file)?;
+        let temp = path.with_extension("json.tmp");
+        fs::write(&temp, json)?;
+        fs::rename(&temp, path)?;
+        Ok(())
+    }
+
+    /// Adds a pending task and returns its id.
+    pub fn add(&mut self, title: &str, due: Option<NaiveDate>) -> Result<u32, TodoError> {
+        let title = title.trim();
+        if title.is_empty() {
+            return Err(TodoError::EmptyTitle);
+        }
+        self.next_id += 1;
+        self.tasks.push(Task {
+            id: self.next_id,
+            title: title.to_string(),
+            done: false,
+            due,
+        });
+        Ok(self.next_id)
+    }
+
+    /// Marks a task as done. Completing a finished task is not an error.
+    pub fn complete(&mut self, id: u32) -> Result<&Task, TodoError> {
+        let task = self.tasks.iter_mut().find(|t| t.id == id).ok_or(TodoError::NotFound(id))?;
+        task.done = true;
+        Ok(task)
+    }
+
+    /// Removes a task and returns it.
+    pub fn remove(&mut self, id: u32) -> Result<Task, TodoError> {
+        let index = self.tasks.iter().position(|t| t.id == id).ok_or(TodoError::NotFound(id))?;
+        Ok(self.tasks.remove(index))
+    }
+
+    /// Removes every finished task and returns how many there were.
+    pub fn clear_done(&mut self) -> usize {
+        let before = self.tasks.len();
+        self.tasks.retain(|t| !t.done);
+        before - self.tasks.len()
+    }
+
+    pub fn get(&self, id: u32) -> Option<&Task> {
+        self.tasks.iter().find(|t| t.id == id)
+    }
+
+    /// The tasks matching `filter`, oldest first.
+    pub fn list(&self, filter: Filter) -> impl Iterator<Item = &Task> {
+        self.tasks.iter().filter(move |t| match filter {
+            Filter::All => true,
+            Filter::Pending => !t.done,
+            Filter::Done => t.done,
+        })
+    }
+
+    /// Unfinished tasks due on or before `date`, soonest first.
+    pub fn due_by(&self, date: NaiveDate) -> Vec<&Task> {
+        let mut tasks: Vec<&Task> = self.tasks.iter().filter(|t| !t.done && t.due.is_some_and(|due| due <= date)).collect();
+        tasks.sort_by_key(|t| t.due);
+        tasks
+    }
+
+    /// Unfinished tasks due before `today`, soonest first.
+    pub fn overdue(&self, today: NaiveDate) -> Vec<&Task> {
+        let mut tasks: Vec<&Task> = self.tasks.iter().filter(|t| t.is_overdue(today)).collect();
+        tasks.sort_by_key(|t| t.due);
+        tasks
+    }
+
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+}
+
+/// What the user asked for on the command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Add { title: String, due: Option<NaiveDate> },
+    List(Filter),
+    Done(u32),
+    Remove(u32),
+    /// Show unfinished tasks due on or before the date.
+    Due(NaiveDate),
+    Clear,
+}
+
+/// The commands [`Command::parse`] understands, for help output.
+pub const USAGE: &str = "\
+Commands:
+  add <title> [--due <date>]   add a task
+  list [all|pending|done]      show tasks (pending by default)
+  done <id>                    mark a task as done
+  rm <id>                      delete a task (or `remove`)
+  due <date>                   unfinished tasks due by a date
+  clear                        delete every finished task
+
+Dates are YYYY-MM-DD, `today` or `tomorrow`.";
+
+impl Command {
+    /// Parses the arguments after the program name. `today` is what
+    /// `today` and `tomorrow` in a date are counted from.
+    ///
+    /// ```
+    /// use rustler::apps::todo::{Command, Filter};
+    ///
+    /// let today = "2026-03-01".parse().unwrap();
+    /// let args = ["add", "Buy", "milk", "--due", "tomorrow"].map(String::from);
+    /// assert_eq!(
+    ///     Command::parse(&args, today).unwrap(),
+    ///     Command::Add { title: "Buy milk".to_string(), due: "2026-03-02".parse().ok() }
+    /// );
+    /// assert_eq!(Command::parse(&["list".to_string()], today).unwrap(), Command::List(Filter::Pending));
+    /// ```
+    pub fn parse(args: &[String], today: NaiveDate) -> Result<Command, UsageError> {
+        let (command, rest) = args.split_first().ok_or_else(|| UsageError("missing command".to_string()))?;
+        match command.as_str() {
+            "add" => {
+                let mut title_words = Vec::new();
+                let mut due = None;
+                let mut words = rest.iter();
+                while let Some(word) = words.next() {
+                    if word == "--due" {
+                        let text = words.next().ok_or_else(|| UsageError("--due needs a date".to_string()))?;
+                        due = Some(parse_date(text, today)?);
+                    } else {
+                        title_words.push(word.as_str());
+                    }
+                }
+                Ok(Command::Add {
+                    title: title_words.join(" "),
+                    due,
+                })
+            }
+            "list" => match rest.first().map(String::as_str) {
+                None | Some("pending") => Ok(Command::List(Filter::Pending)),
+                Some("all") => Ok(Command::List(Filter::All)),
+                Some("done") => Ok(Command::List(Filter::Done)),
+                Some(other) => Err(UsageError(format!("unknown filter `{}`", other))),
+            },
+            "done" => parse_id(rest).map(Command::Done),
+            "rm" | "remove" => parse_id(rest).map(Command::Remove),
+            "due" => {
+                let text = rest.first().ok_or_else(|| UsageError("`due` needs a date".to_string()))?;
+                parse_date(text, today).map(Command::Due)
+            }
+            "clear" => Ok(Command::Clear),
+            other => Err(UsageError(format!("unknown command `{}`", other))),
+        }
+    }
+}
+
+fn parse_id(args: &[String]) -> Result<u32, UsageError> {
+    let text = args.first().ok_or_else(|| UsageError("missing task id".to_string()))?;
+    text.parse().map_err(|_| UsageError(format!("`{}` is not a task id", text)))
+}
+
+/// A date as typed on the command line: `YYYY-MM-DD`, `today` or
+/// `tomorrow`.
+pub fn parse_date(text: &str, today: NaiveDate) -> Result<NaiveDate, UsageError> {
+    match text {
+        "today" => Ok(today),
+        "tomorrow" => today.succ_opt().ok_or_else(|| UsageError("there is no tomorrow".to_string())),
+        _ => NaiveDate::parse_from_str(text, "%Y-%m-%d").map_err(|e| UsageError(format!("bad date `{}`: {}", text, e))),
+    }
+}
+
+/// A command line that [`Command::parse`] could not make sense of; the
+/// message says why.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UsageError(pub String);
+
+impl fmt::Display for UsageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Error for UsageError {}
+
+/// Errors from changing, saving or loading a [`TodoList`].
+#[derive(Debug)]
+pub enum TodoError {
+    /// No task has this id.
+    NotFound(u32),
+    EmptyTitle,
+    Io(io::Error),
+    /// The file is not valid JSON or is missing fields.
+    Format(serde_json::Error),
+    /// A newer build wrote the file, so this one leaves it alone.
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for TodoError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TodoError::NotFound(id) => write!(f, "no task with id {}", id),
+            TodoError::EmptyTitle => write!(f, "a task needs a title"),
+            TodoError::Io(e) => write!(f, "I/O error: {}", e),
+            TodoError::Format(e) => write!(f, "malformed to-do file: {}", e),
+            TodoError::UnsupportedVersion(version) => write!(
+                f,
+                "to-do file version {} is not supported (expected 1 to {})",
+                version, SAVE_VERSION
+            ),
+        }
+    }
+}
+
+impl Error for TodoError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            TodoError::Io(e) => Some(e),
+            TodoError::Format(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for TodoError {
+    fn from(e: io::Error) -> Self {
+        TodoError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for TodoError {
+    fn from(e: serde_json::Error) -> Self {
+        TodoError::Format(e)
+    }
+}
+
+impl From<VersionError> for TodoError {
+    fn from(e: VersionError) -> Self {
+        match e {
+            VersionError::Format(e) => TodoError::Format(e),
+            VersionError::Unsupported(version) => TodoError::UnsupportedVersion(version),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::TempDir;
+
+    fn date(text: &str) -> NaiveDate {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn test_add_complete_remove() {
+        let mut todos = TodoList::new();
+        let a = todos.add("  Water plants ", None).unwrap();
+        let b = todos.add("Pay rent", Some(date("2026-11-01"))).unwrap();
+        assert_eq!((a, b), (1, 2));
+        assert_eq!(todos.get(a).unwrap().title, "Water plants");
+
+        assert!(todos.complete(a).unwrap().done);
+        assert_eq!(todos.list(Filter::Done).count(), 1);
+        assert_eq!(todos.list(Filter::Pending).map(|t| t.id).collect::<Vec<_>>(), vec![b]);
+
+        assert_eq!(todos.remove(a).unwrap().title, "Water plants");
+        assert!(matches!(todos.remove(a), Err(TodoError::NotFound(1))));
+        assert!(matches!(todos.add("   ", None), Err(TodoError::EmptyTitle)));
+        // Ids are never reused
+        assert_eq!(todos.add("Call mum", None).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_overdue_and_clear_done() {
+        let mut todos = TodoList::new();
+        todos.add("Late", Some(date("2026-01-10"))).unwrap();
+        todos.add("Later", Some(date("2026-01-05"))).unwrap();
+        let done = todos.add("Late but done", Some(date("2026-01-01"))).unwrap();
+        todos.add("Due today", Some(date("2026-02-01"))).unwrap();
+        todos.add("Someday", None).unwrap();
+        todos.complete(done).unwrap();
+
+        let overdue: Vec<&str> = todos.overdue(date("2026-02-01")).iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(overdue, vec!["Later", "Late"]);
+        let due: Vec<&str> = todos.due_by(date("2026-02-01")).iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(due, vec!["Later", "Late", "Due today"]);
+        assert_eq!(todos.clear_done(), 1);
+        assert_eq!(todos.len(), 4);
+    }
+
+    #[test]
+    fn test_parse_commands() {
+        let today = date("2026-12-31");
+        let parse = |line: &str| {
+            let args: Vec<String> = line.split_whitespace().map(String::from).collect();
+            Command::parse(&args, today)
+        };
+        assert_eq!(
+            parse("add Pay rent --due 2027-01-05").unwrap(),
+            Command::Add {
+                title: "Pay rent".to_string(),
+                due: Some(date("2027-01-05"))
+            }
+        );
+        assert_eq!(parse("list done").unwrap(), Command::List(Filter::Done));
+        assert_eq!(parse("rm 4").unwrap(), Command::Remove(4));
+        assert_eq!(parse("remove 4").unwrap(), Command::Remove(4));
+        assert_eq!(parse("due tomorrow").unwrap(), Command::Due(date("2027-01-01")));
+        assert_eq!(parse("due today").unwrap(), Command::Due(today));
+
+        assert_eq!(parse("done x").unwrap_err().to_string(), "`x` is not a task id");
+        assert_eq!(parse("list soon").unwrap_err().to_string(), "unknown filter `soon`");
+        assert!(parse("add Thing --due").is_err());
+        assert!(parse("due 2026-13-01").unwrap_err().to_string().starts_with("bad date `2026-13-01`"));
+        assert!(parse("").is_err());
+        assert_eq!(parse("frob").unwrap_err(), UsageError("unknown command `frob`".to_string()));
+    }
+
+    #[test]
+    fn test_display() {
+        let mut todos = TodoList::new();
+        let id = todos.add("Ship it", Some(date("2026-12-24"))).unwrap();
+        assert_eq!(todos.get(id).unwrap().to_string(), "[ ]   1  Ship it (due 2026-12-24)");
+        todos.complete(id).unwrap();
+        assert_eq!(todos.get(id).unwrap().to_string(), "[x]   1  Ship it (due 2026-12-24)");
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = TempDir::new("todo").unwrap();
+        let path = dir.join("nested").join("todo.json");
+        assert!(TodoList::load(&path).unwrap().is_empty());
+
+        let mut todos = TodoList::new();
+        todos.add("Persist me", Some(date("2027-01-01"))).unwrap();
+        let gone = todos.add("Removed", None).unwrap();
+        todos.remove(gone).unwrap();
+        todos.save(&path).unwrap();
+
+        let mut loaded = TodoList::load(&path).unwrap();
+        assert_eq!(loaded, todos);
+        assert_eq!(loaded.add("Next", None).unwrap(), 3);
+        assert!(!path.with_extension("json.tmp").exists());
+    }
+
+    #[test]
+    fn test_bad_files_report_why() {
+        let dir = TempDir::new("todo").unwrap();
+        let path = dir.join("todo.json");
+        fs::write(&path, r#"{"version": 7, "tasks": [], "next_id": 0}"#).unwrap();
+        assert_eq!(
+            TodoList::load(&path).unwrap_err().to_string(),
+            "to-do file version 7 is not supported (expected 1 to 1)"
+        );
+        fs::write(&path, r#"{"version": 1, "tasks": [{"id": 1}], "next_id": 1}"#).unwrap();
+        assert!(matches!(TodoList::load(&path), Err(TodoError::Format(_))));
+    }
+}
@@ -0,0 +1,314 @@
+//! Micro-benchmarks that put two ways of doing the same thing side by
+//! side, for `rustler bench <topic>`.
+//!
+//! Each [`Benchmark`] times its alternatives with [`measure`] and returns a
+//! [`Report`], whose `Display` is a table with the time per run and how
+//! many times slower each alternative is than the fastest:
+//!
+//! | Topic       | Compares                                             |
+//! |-------------|------------------------------------------------------|
+//! | `fibonacci` | naive recursion against a memoized version           |
+//! | `counter`   | a `Mutex<u64>` against an atomic [`Metric`] counter  |
+//! | `vec`       | `Vec::new` and `push` against `Vec::with_capacity`   |
+//!
+//! These are teaching numbers, not rigorous ones: there is no statistics
+//! beyond an average, so run a topic twice before trusting a small
+//! difference. `cargo bench` has the heavier benchmarks.
+//!
+//! ```
+//! use std::time::Duration;
+//!
+//! let benchmark = rustler::bench::find("fib").unwrap();
+//! let report = benchmark.run(Duration::ZERO);
+//! assert_eq!(report.measurements().len(), 2);
+//! assert!(report.to_string().contains("memoized"));
+//! ```
+//!
+//! [`Metric`]: crate::concurrent::Metric
+
+use std::collections::HashMap;
+use std::fmt;
+use std::hint::black_box;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::concurrent::Metric;
+use crate::term::table::{Align, Table};
+
+/// How long each alternative is timed for by default.
+pub const DEFAULT_BUDGET: Duration = Duration::from_millis(300);
+
+/// An upper bound on the runs per alternative, so that something that
+/// takes nanoseconds doesn't loop for billions of runs.
+const MAX_RUNS: u64 = 1 << 24;
+
+/// The timing of one alternative.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Measurement {
+    pub label: String,
+    pub runs: u64,
+    pub total: Duration,
+}
+
+impl Measurement {
+    /// The average time of one run.
+    pub fn per_run(&self) -> Duration {
+        // Durations divide by u32, so average in nanoseconds
+        Duration::from_nanos((self.total.as_nanos() / u128::from(self.runs.max(1))) as u64)
+    }
+}
+
+/// Runs `f` over and over, doubling the number of runs until they take at
+/// least `budget` together, and returns the last timing. `f` runs at least
+/// once, and its result goes through [`black_box`] so the compiler can't
+/// optimize the work away.
+pub fn measure<T>(label: &str, budget: Duration, mut f: impl FnMut() -> T) -> Measurement {
+    let mut runs = 1;
+    loop {
+        let start = Instant::now();
+        for _ in 0..runs {
+            black_box(f());
+        }
+        let total = start.elapsed();
+        if total >= budget || runs >= MAX_RUNS {
+            return Measurement {
+                label: label.to_string(),
+                runs,
+                total,
+            };
+        }
+        runs *= 2;
+    }
+}
+
+/// A comparison `rustler bench` can run.
+#[derive(Debug)]
+pub struct Benchmark {
+    /// What to type after `rustler bench`.
+    pub name: &'static str,
+    pub title: &'static str,
+    /// What the numbers show, printed under the table.
+    pub lesson: &'static str,
+    run: fn(Duration) -> Vec<Measurement>,
+}
+
+impl Benchmark {
+    /// Times every alternative for about `budget` each.
+    pub fn run(&'static self, budget: Duration) -> Report {
+        Report {
+            benchmark: self,
+            measurements: (self.run)(budget),
+        }
+    }
+}
+
+static BENCHMARKS: [Benchmark; 3] = [
+    Benchmark {
+        name: "fibonacci",
+        title: "Naive vs memoized Fibonacci",
+        lesson: "The naive version recomputes the same values exponentially often; \
+                 caching each one makes it linear.",
+        run: bench_fibonacci,
+    },
+    Benchmark {
+        name: "counter",
+        title: "Mutex vs atomic counter",
+        lesson: "Four threads increment a shared counter. An atomic add is a single \
+                 instruction, while every Mutex increment has to lock and unlock.",
+        run: bench_counter,
+    },
+    Benchmark {
+        name: "vec",
+        title: "Vec::push vs Vec::with_capacity",
+        lesson: "A Vec that starts empty reallocates and copies each time it outgrows \
+                 its buffer; with_capacity allocates once.",
+        run: bench_vec,
+    },
+];
+
+/// Every benchmark, in the order `rustler bench` lists them.
+pub fn benchmarks() -> &'static [Benchmark] {
+    &BENCHMARKS
+}
+
+/// The benchmark called `topic`, or the only one whose name starts with
+/// it.
+pub fn find(topic: &str) -> Option<&'static Benchmark> {
+    let topic = topic.to_lowercase();
+    if let Some(exact) = BENCHMARKS.iter().find(|b| b.name == topic) {
+        return Some(exact);
+    }
+    let mut matches = BENCHMARKS.iter().filter(|b| b.name.starts_with(&topic));
+    match (matches.next(), matches.next()) {
+        (Some(only), None) if !topic.is_empty() => Some(only),
+        _ => None,
+    }
+}
+
+/// The timings from one [`Benchmark::run`].
+#[derive(Debug)]
+pub struct Report {
+    benchmark: &'static Benchmark,
+    measurements: Vec<Measurement>,
+}
+
+impl Report {
+    pub fn benchmark(&self) -> &'static Benchmark {
+        self.benchmark
+    }
+
+    pub fn measurements(&self) -> &[Measurement] {
+        &self.measurements
+    }
+
+    /// The alternative with the shortest time per run.
+    pub fn fastest(&self) -> Option<&Measurement> {
+        self.measurements.iter().min_by_key(|m| m.per_run())
+    }
+
+    /// How many times longer `measurement` takes per run than the fastest.
+    pub fn relative(&self, measurement: &Measurement) -> f64 {
+        let fastest = self.fastest().map_or(0, |m| m.per_run().as_nanos());
+        // Something too quick to time at all counts as one nanosecond
+        measurement.per_run().as_nanos().max(1) as f64 / fastest.max(1) as f64
+    }
+}
+
+impl fmt::Display for Report {
+    /// A table of the timings, then the lesson.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut table = Table::new(["Implementation", "Per run", "Runs", "Relative"])
+            .align(1, Align::Right)
+            .align(2, Align::Right)
+            .align(3, Align::Right);
+        for m in &self.measurements {
+            let relative = format!("{:.2}x", self.relative(m));
+            table.add_row([m.label.clone(), format!("{:.2?}", m.per_run()), m.runs.to_string(), relative]);
+        }
+        write!(f, "{}{}", table, self.benchmark.lesson)
+    }
+}
+
+/// Fibonacci by the textbook recursion, taking exponential time.
+pub fn fib_naive(n: u32) -> u64 {
+    match n {
+        0 => 0,
+        1 => 1,
+        _ => fib_naive(n - 1) + fib_naive(n - 2),
+    }
+}
+
+/// Fibonacci by the same recursion, remembering each value in `memo` so
+/// it is only computed once.
+pub fn fib_memo(n: u32, memo: &mut HashMap<u32, u64>) -> u64 {
+    if n < 2 {
+        return u64::from(n);
+    }
+    if let Some(&value) = memo.get(&n) {
+        return value;
+    }
+    let value = fib_memo(n - 1, memo) + fib_memo(n - 2, memo);
+    memo.insert(n, value);
+    value
+}
+
+fn bench_fibonacci(budget: Duration) -> Vec<Measurement> {
+    const N: u32 = 25;
+    vec![
+        measure("naive recursion", budget, || fib_naive(black_box(N))),
+        // A fresh cache each run, or only the first run would do any work
+        measure("memoized", budget, || fib_memo(black_box(N), &mut HashMap::new())),
+    ]
+}
+
+const THREADS: usize = 4;
+const INCREMENTS: u64 = 10_000;
+
+fn bench_counter(budget: Duration) -> Vec<Measurement> {
+    let locked = || {
+        let counter = Mutex::new(0u64);
+        thread::scope(|scope| {
+            for _ in 0..THREADS {
+                scope.spawn(|| (0..INCREMENTS).for_each(|_| *counter.lock().unwrap() += 1));
+            }
+        });
+        counter.into_inner().unwrap()
+    };
+    let atomic = || {
+        let counter = Metric::new();
+        thread::scope(|scope| {
+            for _ in 0..THREADS {
+                scope.spawn(|| (0..INCREMENTS).for_each(|_| counter.incr()));
+            }
+        });
+        counter.get()
+    };
+    vec![measure("Mutex<u64>", budget, locked), measure("AtomicU64 (Metric)", budget, atomic)]
+}
+
+fn bench_vec(budget: Duration) -> Vec<Measurement> {
+    const LEN: usize = 100_000;
+    let grown = || {
+        let mut v = Vec::new();
+        for i in 0..LEN {
+            v.push(black_box(i));
+        }
+        v
+    };
+    let reserved = || {
+        let mut v = Vec::with_capacity(LEN);
+        for i in 0..LEN {
+            v.push(black_box(i));
+        }
+        v
+    };
+    vec![measure("Vec::new + push", budget, grown), measure("Vec::with_capacity", budget, reserved)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_measure_runs_at_least_once() {
+        let mut calls = 0;
+        let m = measure("count", Duration::ZERO, || calls += 1);
+        assert_eq!((m.runs, calls), (1, 1));
+
+        let m = measure("sleep", Duration::from_millis(5), || thread::sleep(Duration::from_millis(1)));
+        assert!(m.runs >= 4 && m.runs.is_power_of_two());
+        assert!(m.total >= Duration::from_millis(5));
+        assert!(m.per_run() >= Duration::from_millis(1));
+    }
+
+    #[test]
+    fn test_fibonacci_versions_agree() {
+        let mut memo = HashMap::new();
+        for n in 0..20 {
+            assert_eq!(fib_naive(n), fib_memo(n, &mut memo));
+        }
+        assert_eq!(fib_memo(90, &mut memo), 2_880_067_194_370_816_120);
+    }
+
+    #[test]
+    fn test_find() {
+        assert_eq!(find("counter").unwrap().name, "counter");
+        assert_eq!(find("FIB").unwrap().name, "fibonacci");
+        assert!(find("").is_none());
+        assert!(find("sort").is_none());
+    }
+
+    #[test]
+    fn test_every_benchmark_runs() {
+        for benchmark in benchmarks() {
+            let report = benchmark.run(Duration::ZERO);
+            assert_eq!(report.measurements().len(), 2, "{}", benchmark.name);
+            let fastest = report.fastest().unwrap();
+            assert_eq!(report.relative(fastest), 1.0);
+            let text = report.to_string();
+            assert!(text.contains("1.00x"), "{}", text);
+            assert!(text.ends_with(benchmark.lesson));
+        }
+    }
+}
@@ -0,0 +1,617 @@
+// The rustler example browser
+// Lists the examples with a line about each, and runs the one you pick by
+// number or by (part of) its name, so there are no file names to remember
+//
+// To run it: cargo run --bin rustler
+//   cargo run --bin rustler -- list
+//   cargo run --bin rustler -- run life
+//   cargo run --bin rustler -- run todo -- list all
+//   cargo run --bin rustler -- explain 05
+//   cargo run --bin rustler -- search Arc
+//   cargo run --bin rustler -- quiz ownership
+//   cargo run --bin rustler -- cards concurrency
+//   cargo run --bin rustler -- exercise variables1
+//   cargo run --bin rustler -- hint variables1
+//   cargo run --bin rustler -- progress
+//   cargo run --release --bin rustler -- bench fibonacci
+//   cargo run --bin rustler -- new example smart_pointers
+//   cargo run --bin rustler -- new exercise closures1 functions
+//
+// Progress is saved as JSON in your config directory (~/.config/rustler on
+// Linux). Set RUSTLER_PROGRESS_FILE to use a different file.
+
+use std::collections::VecDeque;
+use std::env;
+use std::fmt::Display;
+use std::io::{self, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process::{self, Command};
+
+use chrono::{Local, NaiveDate};
+use rustler::bench::{self, Benchmark};
+use rustler::catalog::{self, Example, FindError};
+use rustler::cli::prompt;
+use rustler::exercises::{self, Exercise};
+use rustler::flashcards::{self, Card, Rating};
+use rustler::progress::Progress;
+use rustler::quiz::{self, Question, Score};
+use rustler::scaffold::{self, Created};
+use rustler::term::color::{DIM, ERROR, HEADING, WARNING};
+use rustler::term::table::{Align, Table};
+use rustler::term::{Spinner, Style};
+use rustler::walkthrough;
+
+const USAGE: &str = "\
+Usage: rustler [command]
+
+Commands:
+  (none)                      pick an example from a menu
+  list                        list the examples
+  run <example> [-- args]     run an example, by number or name
+  explain <example>           run an example a step at a time, showing the code
+  search <keyword>            show where the examples mention a keyword
+  quiz [example]              test yourself on an example's topic
+  cards [example]             drill the flashcards for an example's topic
+  exercise [name]             check your answer to an exercise
+  hint <name>                 the next hint for an exercise, then its solution
+  progress [reset]            show what you have done, or start over
+  bench [topic]               time alternative implementations side by side
+  new example <name>          add a numbered example from a template
+  new exercise <name> <topic> add an exercise practising an example's topic
+  help                        show this message";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let code = match args.first().map(String::as_str) {
+        None => browse(),
+        Some("list") => print_list(),
+        Some("run") => match args.get(1) {
+            Some(query) => {
+                let extra = match args.get(2).map(String::as_str) {
+                    Some("--") => &args[3..],
+                    _ => &args[2..],
+                };
+                match catalog::find(query) {
+                    Ok(example) => run(&example, extra, false),
+                    Err(e) => report(&e),
+                }
+            }
+            None => usage_error("`run` needs an example number or name"),
+        },
+        Some("explain") => match args.get(1).map(|query| catalog::find(query)) {
+            Some(Ok(example)) => explain(&example),
+            Some(Err(e)) => report(&e),
+            None => usage_error("`explain` needs an example number or name"),
+        },
+        Some("search") if args.len() > 1 => search(&args[1..].join(" ")),
+        Some("search") => usage_error("`search` needs a keyword"),
+        Some("quiz") => match args.get(1) {
+            Some(query) => match quiz::find(query) {
+                Ok((example, questions)) => take_quiz(&example, questions),
+                Err(e) => report(&e),
+            },
+            None => {
+                print_quizzes();
+                0
+            }
+        },
+        Some("cards") => match args.get(1) {
+            Some(query) => match flashcards::find(query) {
+                Ok((example, cards)) => drill(&example, cards),
+                Err(e) => report(&e),
+            },
+            None => {
+                print_decks();
+                0
+            }
+        },
+        Some("exercise") => match args.get(1) {
+            Some(name) => match exercises::find(name) {
+                Some(exercise) => check_exercise(&exercise),
+                None => {
+                    print_error(format_args!("no exercise is called `{}`; run `rustler exercise` to list them", name));
+                    1
+                }
+            },
+            None => {
+                print_exercises();
+                0
+            }
+        },
+        Some("hint") => match args.get(1) {
+            Some(name) => match exercises::find(name) {
+                Some(exercise) => show_hint(&exercise),
+                None => {
+                    print_error(format_args!("no exercise is called `{}`; run `rustler exercise` to list them", name));
+                    1
+                }
+            },
+            None => usage_error("`hint` needs an exercise name"),
+        },
+        Some("progress") => match args.get(1).map(String::as_str) {
+            None => show_progress(),
+            Some("reset") => reset_progress(),
+            Some(other) => usage_error(&format!("unknown progress command `{}`", other)),
+        },
+        Some("bench") => match args.get(1) {
+            Some(topic) => match bench::find(topic) {
+                Some(benchmark) => run_benchmark(benchmark),
+                None => {
+                    print_error(format_args!("no benchmark is called `{}`; run `rustler bench` to list them", topic));
+                    1
+                }
+            },
+            None => {
+                print_benchmarks();
+                0
+            }
+        },
+        Some("new") => match (args.get(1).map(String::as_str), args.get(2)) {
+            (Some("example"), Some(name)) => scaffolded(scaffold::new_example(root(), name)),
+            (Some("exercise"), Some(name)) => match args.get(3).map(|topic| catalog::find(topic)) {
+                Some(Ok(topic)) => scaffolded(scaffold::new_exercise(root(), name, &topic)),
+                Some(Err(e)) => report(&e),
+                None => usage_error("`new exercise` needs the example whose topic it practises"),
+            },
+            (Some("example" | "exercise"), None) => usage_error("`new` needs a name"),
+            _ => usage_error("`new` makes an `example` or an `exercise`"),
+        },
+        Some("help" | "-h" | "--help") => {
+            println!("{}", USAGE);
+            0
+        }
+        Some(other) => usage_error(&format!("unknown command `{}`", other)),
+    };
+    process::exit(code);
+}
+
+/// Prints the examples one per line. A reader that stops early, such as
+/// `rustler list | head -3`, closes the pipe, which is not an error.
+fn print_list() -> i32 {
+    let mut out = io::stdout().lock();
+    let written = catalog::examples().iter().try_for_each(|example| writeln!(out, "{}", example));
+    match written {
+        Ok(()) => 0,
+        Err(e) if e.kind() == ErrorKind::BrokenPipe => 0,
+        Err(e) => {
+            print_error(format_args!("could not write the list: {}", e));
+            1
+        }
+    }
+}
+
+/// The interactive menu: list, ask, run, repeat until the user quits.
+fn browse() -> i32 {
+    loop {
+        println!("{}", HEADING.paint("=== Rustler examples ==="));
+        print_list();
+        let choice = match prompt::read_line("\nPick an example by number or name (q to quit): ") {
+            Ok(Some(choice)) => choice,
+            Ok(None) => return 0,
+            Err(e) => {
+                print_error(e);
+                return 1;
+            }
+        };
+        match choice.as_str() {
+            "" => continue,
+            "q" | "quit" | "exit" => return 0,
+            _ => match catalog::find(&choice) {
+                Ok(example) => {
+                    run(&example, &[], false);
+                    println!();
+                }
+                Err(e) => {
+                    report(&e);
+                    println!();
+                }
+            },
+        }
+    }
+}
+
+/// Runs an example through Cargo from the crate's own directory, so the
+/// browser works wherever it is started from. With `explain`, the
+/// example's steps pause and show their code. Returns the exit code.
+fn run(example: &Example, args: &[String], explain: bool) -> i32 {
+    println!("{}\n", HEADING.paint(format_args!("--- Running {} ---", example.name)));
+    // Cargo sets CARGO for the programs it runs; fall back to the PATH
+    let cargo = env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let mut command = Command::new(cargo);
+    command
+        .args(["run", "--quiet", "--example", example.name, "--"])
+        .args(args)
+        .current_dir(root());
+    if explain {
+        command.env(walkthrough::EXPLAIN_VAR, "1");
+    }
+    let status = command.status();
+    match status {
+        Ok(status) => {
+            if status.success() {
+                update_progress(|progress| {
+                    progress.mark_example(example.name);
+                });
+            }
+            status.code().unwrap_or(1)
+        }
+        Err(e) => {
+            print_error(format_args!("could not start cargo: {}", e));
+            1
+        }
+    }
+}
+
+/// Runs an example a step at a time, if it marks its sections with
+/// `step!`.
+fn explain(example: &Example) -> i32 {
+    let has_steps = catalog::source(example.name).is_some_and(|source| source.contains("step!("));
+    if !has_steps {
+        print_error(format_args!(
+            "{} has no steps to explain yet; `rustler run {}` runs it in one go",
+            example.name, example.number
+        ));
+        return 1;
+    }
+    run(example, &[], true)
+}
+
+/// Prints each matching line with a line either side, grep style: `:`
+/// after the number marks the match, `-` the context. Exits with 1 if
+/// nothing matched, like grep.
+fn search(keyword: &str) -> i32 {
+    let found = catalog::search(keyword, 1);
+    if found.is_empty() {
+        println!("No example mentions `{}`.", keyword);
+        return 1;
+    }
+    for hit in &found {
+        let location = format!("examples/{}.rs:{}", hit.example.name, hit.line);
+        println!("{} ({})", HEADING.paint(location), hit.kind);
+        for (number, text) in &hit.context {
+            let marker = if *number == hit.line { ':' } else { '-' };
+            println!("{}{} {}", DIM.paint(format_args!("{:>5}", number)), marker, text);
+        }
+        println!();
+    }
+    let mut examples: Vec<&str> = found.iter().map(|hit| hit.example.name).collect();
+    examples.dedup();
+    let count = |n: usize, one: &str, many: &str| format!("{} {}", n, if n == 1 { one } else { many });
+    println!(
+        "{} in {}",
+        count(found.len(), "match", "matches"),
+        count(examples.len(), "example", "examples")
+    );
+    0
+}
+
+fn print_quizzes() {
+    println!("Quizzes (rustler quiz <number or name>):");
+    for example in catalog::examples() {
+        let count = quiz::questions(example.name).map_or(0, <[Question]>::len);
+        println!("{:>2}. {} ({} questions)", example.number, example.title, count);
+    }
+}
+
+/// Asks each question in turn, says whether the answer was right and why,
+/// and ends with the score. Exits with 1 below the pass mark.
+fn take_quiz(example: &Example, questions: &[Question]) -> i32 {
+    println!("{}", HEADING.paint(format_args!("=== Quiz: {} ===", example.title)));
+    println!("Answer with a letter or type the answer; Ctrl-D stops early.\n");
+    let mut score = Score::new();
+    for (number, question) in (1..).zip(questions) {
+        println!("{}. {}", number, question);
+        let response = match prompt::read_line("> ") {
+            Ok(Some(response)) => response,
+            Ok(None) => break,
+            Err(e) => {
+                print_error(e);
+                return 1;
+            }
+        };
+        let correct = question.check(&response);
+        score.record(correct);
+        if correct {
+            println!("✓ Correct. {}\n", question.explanation);
+        } else {
+            println!("✗ The answer is {}. {}\n", question.solution(), question.explanation);
+        }
+    }
+    if score.total() > 0 {
+        update_progress(|progress| progress.record_quiz(example.name, score));
+    }
+    let verdict = if score.passed() { "passed" } else { "keep practising" };
+    println!("Score: {} — {}", score, verdict);
+    if score.passed() {
+        0
+    } else {
+        1
+    }
+}
+
+fn load_progress() -> Progress {
+    progress_path().and_then(|path| Progress::load(path).ok()).unwrap_or_default()
+}
+
+fn today() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+fn print_decks() {
+    let progress = load_progress();
+    let today = today();
+    println!("Flashcards (rustler cards <number or name>):");
+    let mut decks = Table::new(["#", "Topic", "Cards", "Due"]).align(0, Align::Right).align(2, Align::Right).align(3, Align::Right);
+    for example in catalog::examples() {
+        let cards = flashcards::deck(example.name).unwrap_or_default();
+        let due = flashcards::due_cards(cards, today, |term| progress.card_state(example.name, term)).len();
+        let style = if due == 0 { DIM } else { Style::new() };
+        decks.add_styled_row([example.number.to_string(), example.title.to_string(), cards.len().to_string(), due.to_string()], style);
+    }
+    print!("{}", decks);
+}
+
+/// Shows each due card's term, then its definition on Enter, and asks how
+/// well it was remembered. Cards rated "again" go to the back of the queue
+/// until they are known. Ctrl-D stops early; ratings already given are
+/// saved as they are made.
+fn drill(example: &Example, cards: &[Card]) -> i32 {
+    let progress = load_progress();
+    let today = today();
+    let mut queue: VecDeque<&Card> =
+        flashcards::due_cards(cards, today, |term| progress.card_state(example.name, term)).into();
+    println!("{}", HEADING.paint(format_args!("=== Flashcards: {} ===", example.title)));
+    if queue.is_empty() {
+        let next = cards.iter().filter_map(|card| progress.card_state(example.name, &card.term)).map(|s| s.due).min();
+        match next {
+            Some(date) => println!("Nothing is due today; come back on {}.", date),
+            None => println!("This deck is empty."),
+        }
+        return 0;
+    }
+    println!("{} cards due. Press Enter to turn a card over; Ctrl-D stops early.\n", queue.len());
+
+    let mut ratings = [0; 4];
+    while let Some(card) = queue.pop_front() {
+        println!("{}", HEADING.paint(&card.term));
+        match prompt::read_line(&DIM.paint("(Enter) ").to_string()) {
+            Ok(Some(_)) => {}
+            Ok(None) => break,
+            Err(e) => {
+                print_error(e);
+                return 1;
+            }
+        }
+        println!("{}", card.definition);
+        let rating = match prompt::read_parsed::<Rating>("1 again, 2 hard, 3 good, 4 easy > ") {
+            Ok(Some(rating)) => rating,
+            Ok(None) => break,
+            Err(e) => {
+                print_error(e);
+                return 1;
+            }
+        };
+        ratings[rating as usize] += 1;
+        let mut due = today;
+        update_progress(|progress| due = progress.record_card(example.name, &card.term, rating, today).due);
+        if rating == Rating::Again {
+            queue.push_back(card);
+            println!();
+        } else {
+            println!("{}\n", DIM.paint(format_args!("Next due {}", due)));
+        }
+    }
+    let [again, hard, good, easy] = ratings;
+    println!(
+        "Reviewed {} cards: {} again, {} hard, {} good, {} easy.",
+        again + hard + good + easy,
+        again,
+        hard,
+        good,
+        easy
+    );
+    0
+}
+
+fn print_exercises() {
+    let progress = load_progress();
+    println!("Exercises (edit exercises/<name>.rs, then rustler exercise <name>):");
+    for exercise in exercises::exercises() {
+        let mark = if progress.has_solved(exercise.name) { 'x' } else { ' ' };
+        println!("[{}] {}", mark, exercise);
+    }
+}
+
+/// Grades the learner's copy of an exercise and records it once it
+/// passes. Exits with 1 until then.
+fn check_exercise(exercise: &Exercise) -> i32 {
+    println!("{}\n", HEADING.paint(format_args!("--- Checking {} ---", exercise.path().display())));
+    // Compiling takes a few seconds, so show that something is happening
+    let spinner = Spinner::start("Compiling and running the tests");
+    let graded = exercises::grade(exercise);
+    spinner.clear();
+    match graded {
+        Ok(outcome) => {
+            println!("{}", outcome);
+            if !outcome.is_pass() {
+                return 1;
+            }
+            update_progress(|progress| {
+                progress.mark_exercise(exercise.name);
+            });
+            0
+        }
+        Err(e) => {
+            print_error(e);
+            1
+        }
+    }
+}
+
+/// Shows the hint after the ones already taken, and counts it.
+fn show_hint(exercise: &Exercise) -> i32 {
+    let taken = progress_path()
+        .and_then(|path| Progress::load(path).ok())
+        .map_or(0, |progress| progress.hints_taken(exercise.name));
+    match exercises::hint(exercise, taken as usize) {
+        Ok(hint) => {
+            println!("{}", hint);
+            update_progress(|progress| {
+                progress.record_hint(exercise.name);
+            });
+            0
+        }
+        Err(e) => {
+            print_error(e);
+            1
+        }
+    }
+}
+
+/// An environment variable overrides the default location, which is handy
+/// for trying things out without touching your real progress.
+fn progress_path() -> Option<PathBuf> {
+    env::var_os("RUSTLER_PROGRESS_FILE").map(PathBuf::from).or_else(Progress::default_path)
+}
+
+/// Loads, changes and saves the progress file. Progress is a nicety, so a
+/// failure is reported but does not change the command's exit code.
+fn update_progress(change: impl FnOnce(&mut Progress)) {
+    let Some(path) = progress_path() else {
+        return;
+    };
+    let result = Progress::load(&path).and_then(|mut progress| {
+        change(&mut progress);
+        progress.save(&path)
+    });
+    if let Err(e) = result {
+        eprintln!("{} could not update {}: {}", WARNING.paint_stderr("warning:"), path.display(), e);
+    }
+}
+
+fn show_progress() -> i32 {
+    let Some(path) = progress_path() else {
+        print_error("no config directory found; set RUSTLER_PROGRESS_FILE");
+        return 1;
+    };
+    let progress = match Progress::load(&path) {
+        Ok(progress) => progress,
+        Err(e) => {
+            print_error(format_args!("cannot read {}: {}", path.display(), e));
+            return 1;
+        }
+    };
+    println!("{}", HEADING.paint("=== Your progress ==="));
+    let mut examples = Table::new(["", "#", "Example", "Quiz"]).align(0, Align::Center).align(1, Align::Right);
+    for example in catalog::examples() {
+        let mark = if progress.has_run(example.name) { "✓" } else { "" };
+        let quiz = match progress.quiz(example.name) {
+            Some(record) if record.passed => format!("passed, best {}%", record.best),
+            Some(record) => format!("best {}%", record.best),
+            None => "not taken".to_string(),
+        };
+        let style = if progress.has_run(example.name) { Style::new() } else { DIM };
+        examples.add_styled_row([mark.to_string(), example.number.to_string(), example.title.to_string(), quiz], style);
+    }
+    print!("{}", examples);
+    println!();
+    let mut exercises = Table::new(["", "Exercise", "Hints"]).align(0, Align::Center).align(2, Align::Right);
+    for exercise in exercises::exercises() {
+        let solved = progress.has_solved(exercise.name);
+        let hints = match progress.hints_taken(exercise.name) {
+            0 => String::new(),
+            n => n.to_string(),
+        };
+        let style = if solved { Style::new() } else { DIM };
+        exercises.add_styled_row([if solved { "✓" } else { "" }, exercise.name, &hints], style);
+    }
+    print!("{}", exercises);
+    println!("\n{}", progress.summary());
+    0
+}
+
+fn reset_progress() -> i32 {
+    if progress_path().is_none() {
+        println!("No progress is saved on this machine.");
+        return 0;
+    }
+    match prompt::confirm("Forget all progress?", false) {
+        Ok(true) => {}
+        Ok(false) => {
+            println!("Nothing changed.");
+            return 0;
+        }
+        Err(e) => {
+            print_error(e);
+            return 1;
+        }
+    }
+    update_progress(Progress::reset);
+    println!("Progress reset.");
+    0
+}
+
+fn print_benchmarks() {
+    println!("Benchmarks (rustler bench <topic>):");
+    for benchmark in bench::benchmarks() {
+        println!("  {:<10} {}", benchmark.name, benchmark.title);
+    }
+}
+
+fn run_benchmark(benchmark: &'static Benchmark) -> i32 {
+    println!("{}", HEADING.paint(format_args!("=== {} ===", benchmark.title)));
+    if cfg!(debug_assertions) {
+        println!("{}", WARNING.paint("This is a debug build; add --release for realistic numbers."));
+    }
+    println!("Timing each implementation for about {:?}...\n", bench::DEFAULT_BUDGET);
+    println!("{}", benchmark.run(bench::DEFAULT_BUDGET));
+    0
+}
+
+/// The checkout the binary was built from, where new files go.
+fn root() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+}
+
+/// Lists what `rustler new` wrote and what is left to do by hand.
+fn scaffolded(result: Result<Created, scaffold::ScaffoldError>) -> i32 {
+    match result {
+        Ok(created) => {
+            for file in &created.files {
+                println!("created {}", file.display());
+            }
+            for file in &created.updated {
+                println!("updated {}", file.display());
+            }
+            println!("\nNext:");
+            for step in &created.next_steps {
+                println!("  - {}", step);
+            }
+            0
+        }
+        Err(e) => {
+            print_error(e);
+            1
+        }
+    }
+}
+
+fn report(error: &FindError) -> i32 {
+    print_error(error);
+    if let FindError::Ambiguous { candidates, .. } = error {
+        for example in candidates {
+            eprintln!("  {}", example);
+        }
+    }
+    1
+}
+
+/// Prints `message` to stderr after a highlighted `error:`.
+fn print_error(message: impl Display) {
+    eprintln!("{} {}", ERROR.paint_stderr("error:"), message);
+}
+
+fn usage_error(message: &str) -> i32 {
+    print_error(format_args!("{}\n\n{}", message, USAGE));
+    2
+}
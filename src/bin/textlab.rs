@@ -0,0 +1,342 @@
+// TextLab: a playground for the rustler text module
+// Type a command to run it against the current text. Paste some text with
+// `paste` (end with a line holding a single `.`) or read a file with
+// `load`, then try `stats`, `slug`, `reverse` or `help` for the rest.
+// Most commands also take text straight after their name.
+//
+// To run it: cargo run --bin textlab
+// Or run one command against piped text:
+//   cat README.md | cargo run --bin textlab -- stats
+//   echo "Hello, World" | cargo run --bin textlab -- slug
+//   cargo run --bin textlab -- diff old.txt new.txt
+
+use std::collections::HashMap;
+use std::env;
+use std::fmt::Display;
+use std::fs;
+use std::io::{self, BufRead, IsTerminal, Read, Write};
+use std::process;
+
+use rustler::term::color::{ERROR, HEADING};
+use rustler::text::json::Value;
+use rustler::text::numbers::{from_roman, to_roman, to_words};
+use rustler::text::search::find_all_kmp;
+use rustler::text::{glob, pluralize, singularize, split_sentences, truncate_with_ellipsis, unified_diff};
+use rustler::text::{Template, TextProcessor};
+
+/// Every command with its arguments and what it does, for `help` and for
+/// suggesting a command when a name is mistyped.
+const COMMANDS: &[(&str, &str, &str)] = &[
+    ("paste", "", "read text until a line holding a single `.`"),
+    ("load", "<file>", "use a file's contents as the text"),
+    ("show", "", "print the current text"),
+    ("wordcount", "[text]", "count the words"),
+    ("stats", "[text]", "characters, words, sentences and more"),
+    ("readability", "[text]", "Flesch reading ease and grade level"),
+    ("sentences", "[text]", "split into sentences, one per line"),
+    ("reverse", "[text]", "reverse the characters"),
+    ("capitalize", "[text]", "capitalize each word"),
+    ("slug", "[text]", "a lowercase-and-hyphens slug for URLs"),
+    ("palindrome", "[text]", "whether it reads the same backwards"),
+    ("truncate", "<width> [text]", "cut each line to a width, ending with …"),
+    ("find", "<word>", "the line and column of every occurrence"),
+    ("grep", "<pattern>", "lines matching a wildcard pattern like *Rust*"),
+    ("plural", "<word> [count]", "the form of a word that goes with a count"),
+    ("singular", "<word>", "the singular of a word"),
+    ("number", "<n>", "a number in words and as a Roman numeral"),
+    ("roman", "<numeral>", "the value of a Roman numeral"),
+    ("json", "[text]", "parse JSON and pretty-print it"),
+    ("render", "<key=value>...", "fill in the text's {placeholders}"),
+    ("diff", "<file1> <file2>", "a unified diff of two files"),
+    ("help", "", "show this list"),
+    ("quit", "", "leave (so does Ctrl-D)"),
+];
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let mut lab = Lab::default();
+    if args.is_empty() {
+        process::exit(repl(&mut lab));
+    }
+
+    // One command against piped text, like a filter
+    if !io::stdin().is_terminal() {
+        let mut text = String::new();
+        if let Err(e) = io::stdin().read_to_string(&mut text) {
+            print_error(format_args!("cannot read standard input: {}", e));
+            process::exit(1);
+        }
+        lab.text = Some(text);
+    }
+    let code = match lab.run(&args[0], &args[1..].join(" ")) {
+        Ok(output) => {
+            println!("{}", output);
+            0
+        }
+        Err(message) => {
+            print_error(message);
+            1
+        }
+    };
+    process::exit(code);
+}
+
+/// Reads commands until `quit` or the end of input. The prompt is only
+/// shown to a person, so a piped script gives clean output.
+fn repl(lab: &mut Lab) -> i32 {
+    let interactive = io::stdin().is_terminal();
+    if interactive {
+        println!("{}", HEADING.paint("=== TextLab ==="));
+        println!("Paste some text with `paste`, or type `help` for the commands.\n");
+    }
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        if interactive {
+            print!("textlab> ");
+            let _ = io::stdout().flush();
+        }
+        let line = match lines.next() {
+            Some(Ok(line)) => line,
+            Some(Err(e)) => {
+                print_error(e);
+                return 1;
+            }
+            None => return 0,
+        };
+        let line = line.trim();
+        let (command, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        match command {
+            "" => {}
+            "quit" | "exit" | "q" => return 0,
+            "paste" => {
+                if interactive {
+                    println!("Paste your text, then a line holding a single `.`:");
+                }
+                let mut text = String::new();
+                for line in lines.by_ref() {
+                    match line {
+                        Ok(line) if line.trim() == "." => break,
+                        Ok(line) => {
+                            text.push_str(&line);
+                            text.push('\n');
+                        }
+                        Err(e) => {
+                            print_error(e);
+                            return 1;
+                        }
+                    }
+                }
+                let lines = text.lines().count();
+                println!("Got {} {}.", lines, pluralize("line", lines));
+                lab.text = Some(text);
+            }
+            _ => match lab.run(command, rest.trim()) {
+                Ok(output) => println!("{}", output),
+                Err(message) => print_error(message),
+            },
+        }
+    }
+}
+
+/// The text being worked on, if any, and the processor to work on it.
+#[derive(Default)]
+struct Lab {
+    text: Option<String>,
+    processor: TextProcessor,
+}
+
+impl Lab {
+    /// Runs one command; `rest` is everything after its name. The output,
+    /// or a message saying what went wrong, is returned for printing.
+    fn run(&mut self, command: &str, rest: &str) -> Result<String, String> {
+        let processor = &self.processor;
+        match command {
+            "help" => Ok(help()),
+            "load" => {
+                if rest.is_empty() {
+                    return Err("`load` needs a file name".to_string());
+                }
+                let text = fs::read_to_string(rest).map_err(|e| format!("cannot read {}: {}", rest, e))?;
+                let lines = text.lines().count();
+                self.text = Some(text);
+                Ok(format!("Loaded {} ({} {}).", rest, lines, pluralize("line", lines)))
+            }
+            "show" => Ok(self.text("")?.trim_end().to_string()),
+            "wordcount" => {
+                let words = processor.count_words(self.text(rest)?);
+                Ok(format!("{} {}", words, pluralize("word", words)))
+            }
+            "stats" => Ok(processor.analyze(self.text(rest)?).to_string()),
+            "readability" => Ok(processor.readability(self.text(rest)?).to_string()),
+            "sentences" => {
+                let sentences = split_sentences(self.text(rest)?);
+                let numbered: Vec<String> = (1..).zip(&sentences).map(|(n, s)| format!("{:>3}. {}", n, s)).collect();
+                Ok(numbered.join("\n"))
+            }
+            "reverse" => Ok(processor.reverse(self.text(rest)?.trim_end())),
+            "capitalize" => Ok(processor.capitalize_words(self.text(rest)?)),
+            "slug" => Ok(processor.slugify(self.text(rest)?)),
+            "palindrome" => {
+                let text = self.text(rest)?.trim();
+                if processor.is_palindrome(text) {
+                    Ok(format!("Yes: \"{}\" reads the same backwards.", text))
+                } else {
+                    Ok(format!("No: backwards it is \"{}\".", processor.reverse(text)))
+                }
+            }
+            "truncate" => {
+                let (width, text) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+                let width: usize = width
+                    .parse()
+                    .map_err(|_| "`truncate` needs a width first, like `truncate 20`".to_string())?;
+                let lines: Vec<String> = self
+                    .text(text.trim())?
+                    .lines()
+                    .map(|line| truncate_with_ellipsis(line, width))
+                    .collect();
+                Ok(lines.join("\n"))
+            }
+            "find" => {
+                if rest.is_empty() {
+                    return Err("`find` needs something to look for".to_string());
+                }
+                let mut found = Vec::new();
+                for (number, line) in (1..).zip(self.text("")?.lines()) {
+                    for offset in find_all_kmp(line, rest) {
+                        let column = line[..offset].chars().count() + 1;
+                        found.push(format!("{}:{}  {}", number, column, line));
+                    }
+                }
+                if found.is_empty() {
+                    Ok(format!("`{}` does not appear in the text.", rest))
+                } else {
+                    Ok(found.join("\n"))
+                }
+            }
+            "grep" => {
+                if rest.is_empty() {
+                    return Err("`grep` needs a pattern, like `grep *Rust*`".to_string());
+                }
+                let lines: Vec<&str> = self.text("")?.lines().filter(|line| glob::matches(rest, line)).collect();
+                if lines.is_empty() {
+                    Ok(format!("No line matches `{}`; patterns match whole lines, so try `*{}*`.", rest, rest))
+                } else {
+                    Ok(lines.join("\n"))
+                }
+            }
+            "plural" => {
+                let mut words = rest.split_whitespace();
+                let word = words.next().ok_or("`plural` needs a word")?;
+                let count = match words.next() {
+                    Some(count) => count.parse().map_err(|_| format!("`{}` is not a count", count))?,
+                    None => 2,
+                };
+                Ok(format!("{} {}", count, pluralize(word, count)))
+            }
+            "singular" => {
+                let word = rest.split_whitespace().next().ok_or("`singular` needs a word")?;
+                Ok(singularize(word))
+            }
+            "number" => {
+                let n: u64 = rest
+                    .parse()
+                    .map_err(|_| format!("`{}` is not a whole number from 0 up", rest))?;
+                let roman = u32::try_from(n).ok().and_then(|n| to_roman(n).ok());
+                match roman {
+                    Some(roman) => Ok(format!("{}\n{}", to_words(n), roman)),
+                    None => Ok(format!("{}\n(no Roman numeral: only 1 to 3999 have one)", to_words(n))),
+                }
+            }
+            "roman" => from_roman(rest).map(|n| n.to_string()).map_err(|e| e.to_string()),
+            "json" => {
+                let value = Value::parse(self.text(rest)?).map_err(|e| format!("not valid JSON: {}", e))?;
+                Ok(value.to_pretty_string())
+            }
+            "render" => {
+                let template = Template::parse(self.text("")?).map_err(|e| e.to_string())?;
+                let mut values = HashMap::new();
+                for pair in rest.split_whitespace() {
+                    let (key, value) = pair
+                        .split_once('=')
+                        .ok_or_else(|| format!("expected key=value, got `{}`", pair))?;
+                    values.insert(key, value.to_string());
+                }
+                let rendered = template.render(&values).map_err(|e| {
+                    let names: Vec<&str> = template.placeholders().collect();
+                    format!("{} (the placeholders are: {})", e, names.join(", "))
+                })?;
+                Ok(rendered.trim_end().to_string())
+            }
+            "diff" => {
+                let files: Vec<&str> = rest.split_whitespace().collect();
+                let [old, new] = files[..] else {
+                    return Err("`diff` needs two file names".to_string());
+                };
+                let read = |path: &str| fs::read_to_string(path).map_err(|e| format!("cannot read {}: {}", path, e));
+                let diff = unified_diff(old, new, &read(old)?, &read(new)?, 3);
+                if diff.is_empty() {
+                    Ok("The files are the same.".to_string())
+                } else {
+                    Ok(diff.trim_end().to_string())
+                }
+            }
+            _ => Err(unknown_command(command)),
+        }
+    }
+
+    /// Text given after the command, or else the current text.
+    fn text<'a>(&'a self, inline: &'a str) -> Result<&'a str, String> {
+        if !inline.is_empty() {
+            return Ok(inline);
+        }
+        self.text.as_deref().ok_or_else(|| {
+            "there is no text yet: `paste` some, `load` a file, or put it after the command".to_string()
+        })
+    }
+}
+
+fn help() -> String {
+    let lines: Vec<String> = COMMANDS
+        .iter()
+        .map(|(name, args, about)| format!("  {:<28} {}", format!("{} {}", name, args).trim_end(), about))
+        .collect();
+    format!("Commands:\n{}", lines.join("\n"))
+}
+
+/// The error for a command that does not exist, suggesting the nearest
+/// real one when the name looks like a typo.
+fn unknown_command(command: &str) -> String {
+    let nearest = COMMANDS
+        .iter()
+        .map(|(name, _, _)| (edit_distance(command, name), *name))
+        .min()
+        .filter(|(distance, _)| *distance <= 2);
+    match nearest {
+        Some((_, name)) => format!("unknown command `{}`; did you mean `{}`?", command, name),
+        None => format!("unknown command `{}`; type `help` for the list", command),
+    }
+}
+
+/// Levenshtein distance: the fewest single-character insertions, deletions
+/// and substitutions that turn `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    // One row of the table at a time: row[j] is the distance from the
+    // prefix of `a` seen so far to the first j chars of `b`
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitute = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitute.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+fn print_error(message: impl Display) {
+    eprintln!("{} {}", ERROR.paint_stderr("error:"), message);
+}
@@ -0,0 +1,131 @@
+// todo: the to-do list from the 16_todo_app example as an everyday tool
+// Same commands and same file, without the tutorial output, and with
+// overdue tasks highlighted
+//
+// To run it: cargo run --bin todo -- <command>
+//   cargo run --bin todo -- add Renew passport --due 2026-11-30
+//   cargo run --bin todo -- list
+//   cargo run --bin todo -- done 3
+//   cargo run --bin todo -- rm 4
+//   cargo run --bin todo -- due tomorrow
+//
+// Tasks are saved as JSON in your data directory (~/.local/share/rustler on
+// Linux). Set RUSTLER_TODO_FILE to use a different file.
+
+use std::env;
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::process;
+
+use chrono::{Local, NaiveDate};
+use rustler::apps::todo::{Command, Filter, Task, TodoError, TodoList, USAGE};
+use rustler::term::color::{DIM, ERROR, WARNING};
+use rustler::term::table::{Align, Table};
+use rustler::term::Style;
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let today = Local::now().date_naive();
+    let command = match args.first().map(String::as_str) {
+        None => Command::List(Filter::Pending),
+        Some("help" | "-h" | "--help") => {
+            println!("Usage: todo <command>\n\n{}", USAGE);
+            return;
+        }
+        Some(_) => match Command::parse(&args, today) {
+            Ok(command) => command,
+            Err(e) => {
+                print_error(format_args!("{}\n\nUsage: todo <command>\n\n{}", e, USAGE));
+                process::exit(2);
+            }
+        },
+    };
+
+    let Some(path) = env::var_os("RUSTLER_TODO_FILE").map(PathBuf::from).or_else(TodoList::default_path) else {
+        print_error("no data directory found; set RUSTLER_TODO_FILE");
+        process::exit(1);
+    };
+    let mut todos = match TodoList::load(&path) {
+        Ok(todos) => todos,
+        Err(e) => {
+            print_error(format_args!("cannot read {}: {}", path.display(), e));
+            process::exit(1);
+        }
+    };
+
+    let changes = !matches!(command, Command::List(_) | Command::Due(_));
+    if let Err(e) = run(command, &mut todos, today) {
+        print_error(e);
+        process::exit(1);
+    }
+    // Listing never changes the file, so only save after a change
+    if changes {
+        if let Err(e) = todos.save(&path) {
+            print_error(format_args!("cannot save {}: {}", path.display(), e));
+            process::exit(1);
+        }
+    }
+}
+
+fn run(command: Command, todos: &mut TodoList, today: NaiveDate) -> Result<(), TodoError> {
+    match command {
+        Command::Add { title, due } => {
+            let id = todos.add(&title, due)?;
+            println!("Added task {}", id);
+        }
+        Command::List(filter) => {
+            let tasks: Vec<&Task> = todos.list(filter).collect();
+            print_tasks(&tasks, today);
+            if tasks.is_empty() {
+                println!("Nothing to show.");
+            }
+        }
+        Command::Done(id) => {
+            let task = todos.complete(id)?;
+            println!("Done: {}", task.title);
+        }
+        Command::Remove(id) => {
+            let task = todos.remove(id)?;
+            println!("Removed: {}", task.title);
+        }
+        Command::Due(date) => {
+            let tasks = todos.due_by(date);
+            print_tasks(&tasks, today);
+            if tasks.is_empty() {
+                println!("Nothing due by {}.", date);
+            }
+        }
+        Command::Clear => {
+            let removed = todos.clear_done();
+            let noun = if removed == 1 { "task" } else { "tasks" };
+            println!("Cleared {} finished {}", removed, noun);
+        }
+    }
+    Ok(())
+}
+
+/// A table of tasks: finished ones dimmed, overdue ones flagged. Prints
+/// nothing for no tasks, so the caller can say why the list is empty.
+fn print_tasks(tasks: &[&Task], today: NaiveDate) {
+    if tasks.is_empty() {
+        return;
+    }
+    let mut table = Table::new(["ID", "", "Task", "Due"]).align(0, Align::Right).max_width(2, 50);
+    for task in tasks {
+        let mark = if task.done { "✓" } else { "" };
+        let due = task.due.map(|date| date.to_string()).unwrap_or_default();
+        let (style, due) = if task.done {
+            (DIM, due)
+        } else if task.is_overdue(today) {
+            (WARNING, format!("{} overdue", due))
+        } else {
+            (Style::new(), due)
+        };
+        table.add_styled_row([task.id.to_string(), mark.to_string(), task.title.clone(), due], style);
+    }
+    print!("{}", table);
+}
+
+fn print_error(message: impl Display) {
+    eprintln!("{} {}", ERROR.paint_stderr("error:"), message);
+}
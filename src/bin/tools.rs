@@ -0,0 +1,195 @@
+// tools: wc, head, tail and grep in one program, like a tiny busybox
+// The first argument picks the tool; the rest are that tool's arguments.
+// All of them read files a block at a time, so they work on files of any
+// size.
+//
+// To run it: cargo run --bin tools -- <tool> [args...]
+//   cargo run --bin tools -- wc README.md Cargo.toml
+//   cargo run --bin tools -- wc -l src/lib.rs
+//   cat README.md | cargo run --bin tools -- wc -w
+//   cargo run --bin tools -- head -n 5 README.md
+//   cargo run --bin tools -- tail -n 3 examples/index.txt
+//   cargo run --bin tools -- grep -rn TempDir src
+
+use std::env;
+use std::fmt::Display;
+use std::io::{self, Write};
+use std::process;
+
+use rustler::term::color::{ERROR, HEADING};
+use rustler::tools::grep;
+use rustler::tools::textfiles::{self, Counts};
+
+const USAGE: &str = "\
+Usage: tools <tool> [args...]
+
+Tools:
+  wc [-lwc] [file...]           count lines, words and bytes (stdin without files)
+  head [-n <lines>] <file...>   the first lines of each file (10 by default)
+  tail [-n <lines>] <file...>   the last lines of each file (10 by default)
+  grep [options] <pattern> [path...]
+                                lines that contain a pattern; `grep --help` for more";
+
+/// What went wrong: usage errors exit with 2, anything else with 1.
+enum Failure {
+    Usage(String),
+    Io(io::Error),
+}
+
+impl From<io::Error> for Failure {
+    fn from(e: io::Error) -> Self {
+        Failure::Io(e)
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let Some((tool, rest)) = args.split_first() else {
+        println!("{}", USAGE);
+        return;
+    };
+    let result = match tool.as_str() {
+        "wc" => wc(rest),
+        "head" => lines(rest, |file, n| textfiles::head(file, n)),
+        "tail" => lines(rest, |file, n| textfiles::tail(file, n)),
+        "grep" => grep(rest),
+        "help" | "-h" | "--help" => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        other => Err(Failure::Usage(format!("unknown tool `{}`", other))),
+    };
+    match result {
+        Ok(()) => {}
+        // Output closed early, as with `| head`: stop quietly
+        Err(Failure::Io(e)) if e.kind() == io::ErrorKind::BrokenPipe => {}
+        Err(Failure::Io(e)) => {
+            print_error(e);
+            process::exit(1);
+        }
+        Err(Failure::Usage(message)) => {
+            print_error(format_args!("{}\n\n{}", message, USAGE));
+            process::exit(2);
+        }
+    }
+}
+
+fn wc(args: &[String]) -> Result<(), Failure> {
+    let mut columns = (false, false, false);
+    let mut files = Vec::new();
+    for arg in args {
+        let Some(flags) = arg.strip_prefix('-') else {
+            files.push(arg.as_str());
+            continue;
+        };
+        for flag in flags.chars() {
+            match flag {
+                'l' => columns.0 = true,
+                'w' => columns.1 = true,
+                'c' => columns.2 = true,
+                other => return Err(Failure::Usage(format!("unknown wc option `-{}`", other))),
+            }
+        }
+    }
+    // Like wc, no flags means all three columns
+    if columns == (false, false, false) {
+        columns = (true, true, true);
+    }
+    let row = |counts: Counts, name: &str| {
+        let mut row = String::new();
+        for (shown, value) in [(columns.0, counts.lines), (columns.1, counts.words), (columns.2, counts.bytes)] {
+            if shown {
+                row.push_str(&format!("{:>8}", value));
+            }
+        }
+        if !name.is_empty() {
+            row.push(' ');
+            row.push_str(name);
+        }
+        row
+    };
+
+    let mut out = io::stdout().lock();
+    if files.is_empty() {
+        writeln!(out, "{}", row(Counts::from_reader(io::stdin().lock())?, ""))?;
+        return Ok(());
+    }
+    let mut total = Counts::default();
+    let mut failed = false;
+    for file in &files {
+        match textfiles::count(file) {
+            Ok(counts) => {
+                writeln!(out, "{}", row(counts, file))?;
+                total += counts;
+            }
+            // Keep counting the other files, as wc does
+            Err(e) => {
+                print_error(format_args!("{}: {}", file, e));
+                failed = true;
+            }
+        }
+    }
+    if files.len() > 1 {
+        writeln!(out, "{}", row(total, "total"))?;
+    }
+    if failed {
+        drop(out);
+        process::exit(1);
+    }
+    Ok(())
+}
+
+/// `head` and `tail`: `-n` and the files are read the same way for both.
+fn lines(args: &[String], read: fn(&str, usize) -> io::Result<Vec<String>>) -> Result<(), Failure> {
+    let mut n = 10;
+    let mut files = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "-n" {
+            let value = args.next().ok_or_else(|| Failure::Usage("-n needs a number of lines".to_string()))?;
+            n = value.parse().map_err(|_| Failure::Usage(format!("`{}` is not a number of lines", value)))?;
+        } else {
+            files.push(arg.as_str());
+        }
+    }
+    if files.is_empty() {
+        return Err(Failure::Usage("no file given".to_string()));
+    }
+    let mut out = io::stdout().lock();
+    for (i, file) in files.iter().enumerate() {
+        // With several files, each gets a header, as in head and tail
+        if files.len() > 1 {
+            let gap = if i == 0 { "" } else { "\n" };
+            writeln!(out, "{}{}", gap, HEADING.paint(format_args!("==> {} <==", file)))?;
+        }
+        let lines = read(file, n).map_err(|e| io::Error::new(e.kind(), format!("{}: {}", file, e)))?;
+        for line in lines {
+            writeln!(out, "{}", line)?;
+        }
+    }
+    Ok(())
+}
+
+fn grep(args: &[String]) -> Result<(), Failure> {
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{}", grep::USAGE.replacen("minigrep", "tools grep", 1));
+        return Ok(());
+    }
+    let config = grep::Config::parse(args).map_err(|e| Failure::Usage(e.to_string()))?;
+    let report = grep::run(&config, io::stdin(), &mut io::stdout().lock())?;
+    for error in &report.errors {
+        print_error(error);
+    }
+    if !report.errors.is_empty() {
+        process::exit(2);
+    }
+    // grep's convention: 1 means the search worked but found nothing
+    if report.matches == 0 {
+        process::exit(1);
+    }
+    Ok(())
+}
+
+fn print_error(message: impl Display) {
+    eprintln!("{} {}", ERROR.paint_stderr("error:"), message);
+}
@@ -0,0 +1,343 @@
+//! The list of examples, for the `rustler` example browser.
+//!
+//! The list lives in `examples/index.txt`, one example per line, and is
+//! compiled into the library:
+//!
+//! ```text
+//! 03_control_flow | Control Flow | if, match and the three kinds of loop
+//! ```
+//!
+//! [`find`] looks an example up the way a person would type it: by number
+//! (`3` or `03`), by file name, or by any part of its name or title.
+//!
+//! The examples' source is compiled in too, by `build.rs`, so [`search`]
+//! can find every line that mentions a keyword without the `examples/`
+//! directory being around.
+
+use std::fmt;
+
+const INDEX: &str = include_str!("../examples/index.txt");
+
+/// Every file in `examples/` with its source, written out by `build.rs`.
+const SOURCES: &[(&str, &str)] = include!(concat!(env!("OUT_DIR"), "/example_sources.rs"));
+
+/// One runnable file in `examples/`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Example {
+    pub number: u32,
+    /// The file name without `.rs`, as passed to `cargo run --example`.
+    pub name: &'static str,
+    pub title: &'static str,
+    pub summary: &'static str,
+}
+
+impl fmt::Display for Example {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:>2}. {} — {}", self.number, self.title, self.summary)
+    }
+}
+
+/// Every example, in order.
+///
+/// ```
+/// let examples = rustler::catalog::examples();
+/// assert_eq!(examples[0].name, "01_hello_world");
+/// assert_eq!(examples[0].title, "Hello World");
+/// ```
+pub fn examples() -> Vec<Example> {
+    INDEX
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let mut fields = line.splitn(3, '|').map(str::trim);
+            let name = fields.next().unwrap_or_default();
+            let number = name.split('_').next().and_then(|n| n.parse().ok()).unwrap_or(0);
+            Example {
+                number,
+                name,
+                title: fields.next().unwrap_or(name),
+                summary: fields.next().unwrap_or_default(),
+            }
+        })
+        .collect()
+}
+
+/// Finds the example the user meant.
+///
+/// A number or an exact file name picks that example. Otherwise the query
+/// is matched, ignoring case, spaces, `-` and `_`, first as a substring of
+/// each name and title and then as a subsequence of them, so `tdo` finds
+/// the to-do app. A query matching several examples equally well is
+/// [`FindError::Ambiguous`].
+///
+/// ```
+/// use rustler::catalog::{find, FindError};
+///
+/// assert_eq!(find("5").unwrap().name, "05_ownership_borrowing");
+/// assert_eq!(find("life").unwrap().name, "15_game_of_life");
+/// assert!(matches!(find("xyzzy"), Err(FindError::NotFound(_))));
+/// ```
+pub fn find(query: &str) -> Result<Example, FindError> {
+    let all = examples();
+    let query = query.trim();
+    if let Ok(number) = query.parse::<u32>() {
+        return all
+            .into_iter()
+            .find(|e| e.number == number)
+            .ok_or_else(|| FindError::NotFound(query.to_string()));
+    }
+    if let Some(example) = all.iter().find(|e| e.name == query.trim_end_matches(".rs")) {
+        return Ok(*example);
+    }
+
+    let wanted = normalize(query);
+    if wanted.is_empty() {
+        return Err(FindError::NotFound(query.to_string()));
+    }
+    let matches = |test: &dyn Fn(&str) -> bool| -> Vec<Example> {
+        all.iter()
+            .filter(|e| test(&normalize(e.name)) || test(&normalize(e.title)))
+            .copied()
+            .collect()
+    };
+    let mut found = matches(&|text| text.contains(&wanted));
+    if found.is_empty() {
+        found = matches(&|text| is_subsequence(&wanted, text));
+    }
+    match found.len() {
+        0 => Err(FindError::NotFound(query.to_string())),
+        1 => Ok(found[0]),
+        _ => Err(FindError::Ambiguous {
+            query: query.to_string(),
+            candidates: found,
+        }),
+    }
+}
+
+/// Lowercase, with spaces, `-`, `_` and other punctuation removed, so
+/// "game-of-life", "Game of Life" and "game_of_life" are all the same.
+fn normalize(text: &str) -> String {
+    text.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+}
+
+/// Whether the characters of `needle` appear in `haystack` in order.
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut rest = haystack.chars();
+    needle.chars().all(|c| rest.any(|h| h == c))
+}
+
+/// The source code of the example called `name`.
+pub fn source(name: &str) -> Option<&'static str> {
+    SOURCES.iter().find(|(file, _)| *file == name).map(|(_, source)| *source)
+}
+
+/// What is on a line that [`search`] matched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineKind {
+    /// The start of a `fn`, `struct`, `enum`, `trait`, `impl` or similar.
+    Definition,
+    /// A section heading the example prints, like `--- While Loops ---`.
+    Heading,
+    Comment,
+    Code,
+}
+
+impl LineKind {
+    fn of(line: &str) -> LineKind {
+        let line = line.trim_start();
+        if line.starts_with("//") {
+            return LineKind::Comment;
+        }
+        if let Some(text) = line.strip_prefix("println!(\"") {
+            let text = text.trim_start_matches("\\n");
+            if text.starts_with("===") || text.starts_with("---") {
+                return LineKind::Heading;
+            }
+        }
+        let keyword = line
+            .split_whitespace()
+            .find(|word| !word.starts_with("pub") && !matches!(*word, "async" | "unsafe" | "extern"))
+            .unwrap_or_default();
+        let keyword = keyword.split('<').next().unwrap_or_default();
+        match keyword {
+            "fn" | "struct" | "enum" | "trait" | "impl" | "mod" | "type" | "const" | "static" | "union"
+            | "macro_rules!" => LineKind::Definition,
+            _ => LineKind::Code,
+        }
+    }
+}
+
+impl fmt::Display for LineKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            LineKind::Definition => "definition",
+            LineKind::Heading => "heading",
+            LineKind::Comment => "comment",
+            LineKind::Code => "code",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A line of an example that mentions the search term.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Match {
+    pub example: Example,
+    /// The line number, counting from 1.
+    pub line: usize,
+    pub kind: LineKind,
+    /// The matching line and the lines around it, with their numbers.
+    pub context: Vec<(usize, &'static str)>,
+}
+
+/// Every line of every example that contains `keyword`, in order, each
+/// with up to `context` lines either side.
+///
+/// Like ripgrep's smart case, case is ignored unless `keyword` has an
+/// uppercase letter, so `arc` also finds `search` but `Arc` finds only `Arc`.
+///
+/// ```
+/// use rustler::catalog::{search, LineKind};
+///
+/// let found = search("fn calculate_length", 0);
+/// assert_eq!(found[0].example.name, "05_ownership_borrowing");
+/// assert_eq!(found[0].kind, LineKind::Definition);
+/// ```
+pub fn search(keyword: &str, context: usize) -> Vec<Match> {
+    let keyword = keyword.trim();
+    if keyword.is_empty() {
+        return Vec::new();
+    }
+    let ignore_case = !keyword.chars().any(char::is_uppercase);
+    let wanted = if ignore_case { keyword.to_lowercase() } else { keyword.to_string() };
+
+    let mut found = Vec::new();
+    for example in examples() {
+        let Some(source) = source(example.name) else {
+            continue;
+        };
+        let lines: Vec<&str> = source.lines().collect();
+        for (i, line) in lines.iter().enumerate() {
+            let hit = if ignore_case {
+                line.to_lowercase().contains(&wanted)
+            } else {
+                line.contains(&wanted)
+            };
+            if hit {
+                let around = i.saturating_sub(context)..(i + context + 1).min(lines.len());
+                found.push(Match {
+                    example,
+                    line: i + 1,
+                    kind: LineKind::of(line),
+                    context: around.map(|n| (n + 1, lines[n])).collect(),
+                });
+            }
+        }
+    }
+    found
+}
+
+/// Why [`find`] could not pick an example.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FindError {
+    NotFound(String),
+    /// The query fits more than one example equally well.
+    Ambiguous { query: String, candidates: Vec<Example> },
+}
+
+impl fmt::Display for FindError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FindError::NotFound(query) => write!(f, "no example matches `{}`", query),
+            FindError::Ambiguous { query, candidates } => {
+                let names: Vec<&str> = candidates.iter().map(|e| e.name).collect();
+                write!(f, "`{}` could mean {}", query, names.join(", "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for FindError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_index_matches_the_examples_directory() {
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/examples");
+        let mut on_disk: Vec<String> = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "rs"))
+            .map(|path| path.file_stem().unwrap().to_string_lossy().into_owned())
+            .collect();
+        on_disk.sort();
+        let indexed: Vec<String> = examples().iter().map(|e| e.name.to_string()).collect();
+        assert_eq!(indexed, on_disk);
+        let embedded: Vec<&str> = SOURCES.iter().map(|(name, _)| *name).collect();
+        assert_eq!(embedded, indexed);
+    }
+
+    #[test]
+    fn test_numbers_are_sequential_and_described() {
+        for (i, example) in examples().iter().enumerate() {
+            assert_eq!(example.number as usize, i + 1, "{}", example.name);
+            assert!(!example.summary.is_empty(), "{}", example.name);
+        }
+    }
+
+    #[test]
+    fn test_find_by_number_and_name() {
+        assert_eq!(find("03").unwrap().name, "03_control_flow");
+        assert_eq!(find(" 16 ").unwrap().name, "16_todo_app");
+        assert_eq!(find("12_testing.rs").unwrap().name, "12_testing");
+        assert_eq!(find("99"), Err(FindError::NotFound("99".to_string())));
+    }
+
+    #[test]
+    fn test_fuzzy_find() {
+        assert_eq!(find("Game-of-Life").unwrap().name, "15_game_of_life");
+        assert_eq!(find("async").unwrap().name, "14_async_await");
+        assert_eq!(find("tdo").unwrap().name, "16_todo_app");
+        assert_eq!(find("ownrshp").unwrap().name, "05_ownership_borrowing");
+
+        let err = find("and").unwrap_err();
+        assert!(matches!(&err, FindError::Ambiguous { candidates, .. } if candidates.len() > 1));
+        assert!(err.to_string().starts_with("`and` could mean 02_variables_and_types, 05_ownership_borrowing"));
+        assert!(matches!(find("  "), Err(FindError::NotFound(_))));
+    }
+
+    #[test]
+    fn test_search_uses_smart_case_and_context() {
+        let exact = search("Arc", 1);
+        assert!(!exact.is_empty());
+        assert!(exact.iter().all(|m| m.context.iter().any(|(n, text)| *n == m.line && text.contains("Arc"))));
+        let any_case = search("arc", 0);
+        assert!(any_case.len() > exact.len());
+
+        let first = &search("fn main", 2)[0];
+        assert_eq!((first.example.number, first.kind), (1, LineKind::Definition));
+        let numbers: Vec<usize> = first.context.iter().map(|(n, _)| *n).collect();
+        assert_eq!(numbers, (first.line - 2..=first.line + 2).collect::<Vec<_>>());
+
+        assert!(search("  ", 1).is_empty());
+        assert!(search("xyzzy_not_in_any_example", 1).is_empty());
+    }
+
+    #[test]
+    fn test_line_kinds() {
+        assert_eq!(LineKind::of("    /// Doubles it"), LineKind::Comment);
+        assert_eq!(LineKind::of("    println!(\"\\n--- While Loops ---\");"), LineKind::Heading);
+        assert_eq!(LineKind::of("pub(crate) async fn fetch() {"), LineKind::Definition);
+        assert_eq!(LineKind::of("impl<T: Display> Stack<T> {"), LineKind::Definition);
+        assert_eq!(LineKind::of("    let shared = Rc::new(5);"), LineKind::Code);
+        assert_eq!(LineKind::of("    println!(\"{}\", x);"), LineKind::Code);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(find("1").unwrap().to_string(), " 1. Hello World — printing with println!, comments and format strings");
+    }
+}
@@ -0,0 +1,5 @@
+//! Helpers for command-line programs that talk to the person running them.
+
+pub mod prompt;
+
+pub use prompt::{confirm, is_interactive, read_hidden, read_line, read_parsed, Prompter};
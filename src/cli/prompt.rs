@@ -0,0 +1,203 @@
+//! Asking the user for input on the terminal.
+//!
+//! A [`Prompter`] writes a prompt and reads the answer, from any reader and
+//! to any writer, so the same code reads stdin in a program and a byte
+//! string in a test. The free functions do the same on stdin and stdout.
+//!
+//! End of input (Ctrl-D, or the end of a piped file) is always an answer,
+//! never an error or an endless loop: [`read_line`] and [`read_parsed`]
+//! return `None` and [`confirm`] takes its default. Programs that may run
+//! without a person at the keyboard should also check [`is_interactive`]
+//! before asking, because a CI job's stdin may be a pipe that is never
+//! written to or closed, and reading it would wait forever.
+//!
+//! ```
+//! use rustler::cli::prompt::Prompter;
+//!
+//! let mut prompter = Prompter::new(&b"forty\n42\ny\n"[..], Vec::new());
+//! assert_eq!(prompter.read_parsed::<u32>("Age? ").unwrap(), Some(42));
+//! assert!(prompter.confirm("Sure?", false).unwrap());
+//! let transcript = String::from_utf8(prompter.into_output()).unwrap();
+//! assert!(transcript.contains("`forty` is not valid"));
+//! ```
+
+use std::fmt::Display;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::str::FromStr;
+
+/// Whether stdin is a terminal, so that there may be someone to answer.
+pub fn is_interactive() -> bool {
+    io::stdin().is_terminal()
+}
+
+/// Asks questions on `output` and reads answers from `input`.
+#[derive(Debug)]
+pub struct Prompter<R, W> {
+    input: R,
+    output: W,
+}
+
+impl<R: BufRead, W: Write> Prompter<R, W> {
+    pub fn new(input: R, output: W) -> Self {
+        Prompter { input, output }
+    }
+
+    /// The writer back, such as the `Vec<u8>` a test passed in.
+    pub fn into_output(self) -> W {
+        self.output
+    }
+
+    /// Writes `text` without asking anything, such as what to show before
+    /// a question.
+    pub fn say(&mut self, text: impl Display) -> io::Result<()> {
+        write!(self.output, "{}", text)?;
+        self.output.flush()
+    }
+
+    /// Writes `prompt` and reads one line, trimmed, or `None` at end of
+    /// input.
+    pub fn read_line(&mut self, prompt: &str) -> io::Result<Option<String>> {
+        // The prompt has no newline, so say flushes it by hand
+        self.say(prompt)?;
+        let mut line = String::new();
+        if self.input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        Ok(Some(line.trim().to_string()))
+    }
+
+    /// Asks until the answer parses as a `T`, saying what was wrong with
+    /// each one that doesn't. `None` at end of input.
+    pub fn read_parsed<T>(&mut self, prompt: &str) -> io::Result<Option<T>>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        loop {
+            let Some(answer) = self.read_line(prompt)? else {
+                return Ok(None);
+            };
+            match answer.parse() {
+                Ok(value) => return Ok(Some(value)),
+                Err(e) => writeln!(self.output, "`{}` is not valid: {}", answer, e)?,
+            }
+        }
+    }
+
+    /// A yes/no question. `default` is the answer to an empty line and at
+    /// end of input, and is shown capitalized: `[Y/n]` or `[y/N]`.
+    pub fn confirm(&mut self, question: &str, default: bool) -> io::Result<bool> {
+        let choices = if default { "[Y/n]" } else { "[y/N]" };
+        let prompt = format!("{} {} ", question, choices);
+        loop {
+            let Some(answer) = self.read_line(&prompt)? else {
+                return Ok(default);
+            };
+            match answer.to_lowercase().as_str() {
+                "" => return Ok(default),
+                "y" | "yes" => return Ok(true),
+                "n" | "no" => return Ok(false),
+                _ => writeln!(self.output, "Please answer y or n.")?,
+            }
+        }
+    }
+}
+
+/// [`Prompter::read_line`] on stdin and stdout.
+pub fn read_line(prompt: &str) -> io::Result<Option<String>> {
+    Prompter::new(io::stdin().lock(), io::stdout()).read_line(prompt)
+}
+
+/// [`Prompter::read_parsed`] on stdin and stdout.
+pub fn read_parsed<T>(prompt: &str) -> io::Result<Option<T>>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    Prompter::new(io::stdin().lock(), io::stdout()).read_parsed(prompt)
+}
+
+/// [`Prompter::confirm`] on stdin and stdout.
+pub fn confirm(question: &str, default: bool) -> io::Result<bool> {
+    Prompter::new(io::stdin().lock(), io::stdout()).confirm(question, default)
+}
+
+/// Reads a line without showing what is typed, for passwords. Echo is
+/// turned off with `stty`, and back on afterwards even if reading fails.
+/// Where that isn't possible, such as on Windows or when stdin isn't a
+/// terminal, the line is read normally.
+pub fn read_hidden(prompt: &str) -> io::Result<Option<String>> {
+    let hidden = is_interactive() && set_echo(false);
+    let answer = read_line(prompt);
+    if hidden {
+        set_echo(true);
+        // The Enter key wasn't echoed either
+        println!();
+    }
+    answer
+}
+
+/// Turns terminal echo on or off, returning whether it worked.
+#[cfg(unix)]
+fn set_echo(on: bool) -> bool {
+    use std::process::{Command, Stdio};
+
+    // stty acts on the terminal it is given as stdin
+    Command::new("stty")
+        .arg(if on { "echo" } else { "-echo" })
+        .stdin(Stdio::inherit())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+#[cfg(not(unix))]
+fn set_echo(_on: bool) -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prompter(input: &str) -> Prompter<&[u8], Vec<u8>> {
+        Prompter::new(input.as_bytes(), Vec::new())
+    }
+
+    fn transcript(prompter: Prompter<&[u8], Vec<u8>>) -> String {
+        String::from_utf8(prompter.into_output()).unwrap()
+    }
+
+    #[test]
+    fn test_read_line() {
+        let mut p = prompter("  Ferris  \r\n");
+        assert_eq!(p.read_line("Name: ").unwrap(), Some("Ferris".to_string()));
+        assert_eq!(p.read_line("Again: ").unwrap(), None);
+        assert_eq!(transcript(p), "Name: Again: ");
+    }
+
+    #[test]
+    fn test_read_parsed_retries() {
+        let mut p = prompter("\nabc\n-1\n7\n");
+        assert_eq!(p.read_parsed::<u8>("n? ").unwrap(), Some(7));
+        let out = transcript(p);
+        assert_eq!(out.matches("n? ").count(), 4);
+        assert!(out.contains("`abc` is not valid: invalid digit found in string"));
+        assert!(out.contains("`-1` is not valid"));
+
+        let mut p = prompter("nope\n");
+        assert_eq!(p.read_parsed::<f64>("x? ").unwrap(), None);
+    }
+
+    #[test]
+    fn test_confirm() {
+        assert!(prompter("y\n").confirm("Go?", false).unwrap());
+        assert!(prompter("YES\n").confirm("Go?", false).unwrap());
+        assert!(!prompter("n\n").confirm("Go?", true).unwrap());
+        assert!(prompter("\n").confirm("Go?", true).unwrap());
+        assert!(!prompter("").confirm("Go?", false).unwrap());
+
+        let mut p = prompter("maybe\nno\n");
+        assert!(!p.confirm("Go?", true).unwrap());
+        assert_eq!(transcript(p), "Go? [Y/n] Please answer y or n.\nGo? [Y/n] ");
+    }
+}
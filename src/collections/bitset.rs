@@ -0,0 +1,245 @@
+//! A set of small non-negative integers stored as bits.
+//!
+//! Number `n` is present when bit `n % 64` of word `n / 64` is set, so a
+//! set covering 0..1_000_000 takes about 122 KiB instead of the megabytes a
+//! `Vec<bool>` or `HashSet<usize>` would. Set operations work a whole word
+//! (64 numbers) at a time.
+
+use std::fmt;
+
+const BITS: usize = u64::BITS as usize;
+
+/// A growable set of `usize` values backed by `Vec<u64>`.
+///
+/// ```
+/// use rustler::collections::BitSet;
+///
+/// let evens: BitSet = (0..10).step_by(2).collect();
+/// let small: BitSet = (0..5).collect();
+/// assert!(evens.contains(4));
+/// assert_eq!(evens.intersection(&small).iter().collect::<Vec<_>>(), vec![0, 2, 4]);
+/// assert_eq!(small.difference(&evens).count_ones(), 2);
+/// ```
+#[derive(Clone, Default)]
+pub struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    pub fn new() -> Self {
+        BitSet { words: Vec::new() }
+    }
+
+    /// An empty set with room for values below `bits` without growing.
+    pub fn with_capacity(bits: usize) -> Self {
+        BitSet {
+            words: Vec::with_capacity(bits.div_ceil(BITS)),
+        }
+    }
+
+    /// Adds `value`, returning false if it was already present. The set
+    /// grows to fit large values.
+    pub fn insert(&mut self, value: usize) -> bool {
+        let (word, mask) = locate(value);
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        let was_set = self.words[word] & mask != 0;
+        self.words[word] |= mask;
+        !was_set
+    }
+
+    /// Removes `value`, returning true if it was present.
+    pub fn remove(&mut self, value: usize) -> bool {
+        let (word, mask) = locate(value);
+        match self.words.get_mut(word) {
+            Some(bits) if *bits & mask != 0 => {
+                *bits &= !mask;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn contains(&self, value: usize) -> bool {
+        let (word, mask) = locate(value);
+        self.words.get(word).is_some_and(|bits| bits & mask != 0)
+    }
+
+    /// The number of values in the set.
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|bits| bits.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&bits| bits == 0)
+    }
+
+    pub fn clear(&mut self) {
+        self.words.clear();
+    }
+
+    /// Values in either set.
+    pub fn union(&self, other: &BitSet) -> BitSet {
+        let (longer, shorter) = if self.words.len() >= other.words.len() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        let mut words = longer.words.clone();
+        for (bits, other_bits) in words.iter_mut().zip(&shorter.words) {
+            *bits |= other_bits;
+        }
+        BitSet { words }
+    }
+
+    /// Values in both sets.
+    pub fn intersection(&self, other: &BitSet) -> BitSet {
+        let words = self.words.iter().zip(&other.words).map(|(a, b)| a & b).collect();
+        BitSet { words }
+    }
+
+    /// Values in this set but not in `other`.
+    pub fn difference(&self, other: &BitSet) -> BitSet {
+        let words = self
+            .words
+            .iter()
+            .enumerate()
+            .map(|(i, bits)| bits & !other.words.get(i).copied().unwrap_or(0))
+            .collect();
+        BitSet { words }
+    }
+
+    /// Iterates over the values in ascending order.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            words: &self.words,
+            index: 0,
+            current: self.words.first().copied().unwrap_or(0),
+        }
+    }
+}
+
+/// The word index and bit mask for `value`.
+fn locate(value: usize) -> (usize, u64) {
+    (value / BITS, 1 << (value % BITS))
+}
+
+/// Two sets are equal when they hold the same values, however many
+/// trailing empty words either has.
+impl PartialEq for BitSet {
+    fn eq(&self, other: &Self) -> bool {
+        let longest = self.words.len().max(other.words.len());
+        (0..longest).all(|i| self.words.get(i).unwrap_or(&0) == other.words.get(i).unwrap_or(&0))
+    }
+}
+
+impl Eq for BitSet {}
+
+impl FromIterator<usize> for BitSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = BitSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<usize> for BitSet {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+impl fmt::Debug for BitSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/// Yields set bits by repeatedly taking the lowest one from the current
+/// word, skipping empty words entirely.
+pub struct Iter<'a> {
+    words: &'a [u64],
+    index: usize,
+    current: u64,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.current == 0 {
+            self.index += 1;
+            self.current = *self.words.get(self.index)?;
+        }
+        let bit = self.current.trailing_zeros() as usize;
+        // Clear the lowest set bit
+        self.current &= self.current - 1;
+        Some(self.index * BITS + bit)
+    }
+}
+
+impl<'a> IntoIterator for &'a BitSet {
+    type Item = usize;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_remove_contains() {
+        let mut set = BitSet::new();
+        assert!(set.is_empty());
+        assert!(set.insert(3));
+        assert!(!set.insert(3));
+        assert!(set.insert(200));
+        assert!(set.contains(3));
+        assert!(set.contains(200));
+        assert!(!set.contains(64));
+        assert!(!set.contains(10_000));
+        assert_eq!(set.count_ones(), 2);
+        assert!(set.remove(200));
+        assert!(!set.remove(200));
+        assert!(!set.remove(10_000));
+        assert_eq!(set.count_ones(), 1);
+    }
+
+    #[test]
+    fn test_iter_crosses_word_boundaries() {
+        let values = vec![0, 1, 63, 64, 65, 127, 128, 500];
+        let set: BitSet = values.iter().copied().collect();
+        assert_eq!(set.iter().collect::<Vec<_>>(), values);
+        assert_eq!(BitSet::new().iter().next(), None);
+        assert_eq!(format!("{:?}", BitSet::from_iter([5, 1])), "{1, 5}");
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a: BitSet = [1, 2, 3, 100].into_iter().collect();
+        let b: BitSet = [2, 3, 4].into_iter().collect();
+        assert_eq!(a.union(&b), [1, 2, 3, 4, 100].into_iter().collect());
+        assert_eq!(b.union(&a), a.union(&b));
+        assert_eq!(a.intersection(&b), [2, 3].into_iter().collect());
+        assert_eq!(a.difference(&b), [1, 100].into_iter().collect());
+        assert_eq!(b.difference(&a), [4].into_iter().collect());
+    }
+
+    #[test]
+    fn test_equality_ignores_trailing_words() {
+        let mut a: BitSet = [1].into_iter().collect();
+        let b = a.clone();
+        a.insert(1000);
+        a.remove(1000);
+        assert_eq!(a, b);
+        a.clear();
+        assert_eq!(a, BitSet::with_capacity(512));
+    }
+}
@@ -0,0 +1,464 @@
+//! An unbalanced binary search tree, as a map and as a set.
+//!
+//! Every node owns its two subtrees through `Option<Box<Node>>`. Keys in the
+//! left subtree are smaller than the node's key and keys in the right
+//! subtree are larger, so lookups walk a single path from the root.
+//!
+//! The tree does no rebalancing: inserting keys in sorted order builds a
+//! tree that is really a linked list, and operations become O(n) instead
+//! of O(log n). `height()` makes that easy to observe.
+//!
+//! Such a tree can be as deep as it is long, so nothing here recurses
+//! down it: every walk is a loop, and dropping the tree takes nodes off an
+//! explicit stack instead of letting each `Box` drop its children.
+
+use std::cmp::Ordering;
+use std::fmt;
+
+type Tree<K, V> = Option<Box<Node<K, V>>>;
+
+struct Node<K, V> {
+    key: K,
+    value: V,
+    left: Tree<K, V>,
+    right: Tree<K, V>,
+}
+
+/// A sorted map backed by a binary search tree.
+///
+/// ```
+/// use rustler::collections::BstMap;
+///
+/// let mut ages = BstMap::new();
+/// ages.insert("carol", 41);
+/// ages.insert("alice", 30);
+/// ages.insert("bob", 25);
+/// assert_eq!(ages.get(&"bob"), Some(&25));
+/// let names: Vec<_> = ages.keys().collect();
+/// assert_eq!(names, vec![&"alice", &"bob", &"carol"]);
+/// ```
+pub struct BstMap<K, V> {
+    root: Tree<K, V>,
+    len: usize,
+}
+
+impl<K: Ord, V> BstMap<K, V> {
+    pub fn new() -> Self {
+        BstMap { root: None, len: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Inserts a key-value pair, returning the previous value if the key
+    /// was already present.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let mut slot = &mut self.root;
+        while let Some(node) = slot {
+            match key.cmp(&node.key) {
+                Ordering::Less => slot = &mut node.left,
+                Ordering::Greater => slot = &mut node.right,
+                Ordering::Equal => return Some(std::mem::replace(&mut node.value, value)),
+            }
+        }
+        *slot = Some(Box::new(Node {
+            key,
+            value,
+            left: None,
+            right: None,
+        }));
+        self.len += 1;
+        None
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let mut current = self.root.as_deref();
+        while let Some(node) = current {
+            match key.cmp(&node.key) {
+                Ordering::Less => current = node.left.as_deref(),
+                Ordering::Greater => current = node.right.as_deref(),
+                Ordering::Equal => return Some(&node.value),
+            }
+        }
+        None
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let mut current = self.root.as_deref_mut();
+        while let Some(node) = current {
+            match key.cmp(&node.key) {
+                Ordering::Less => current = node.left.as_deref_mut(),
+                Ordering::Greater => current = node.right.as_deref_mut(),
+                Ordering::Equal => return Some(&mut node.value),
+            }
+        }
+        None
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Removes a key, returning its value if it was present.
+    ///
+    /// A node with two children is replaced by its in-order successor, the
+    /// smallest key in its right subtree.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let removed = Self::remove_from(&mut self.root, key);
+        if removed.is_some() {
+            self.len -= 1;
+        }
+        removed
+    }
+
+    fn remove_from(tree: &mut Tree<K, V>, key: &K) -> Option<V> {
+        let mut slot = tree;
+        // Looks before stepping, so that `slot` is free to be reborrowed
+        loop {
+            match key.cmp(&slot.as_ref()?.key) {
+                Ordering::Less => slot = &mut slot.as_mut()?.left,
+                Ordering::Greater => slot = &mut slot.as_mut()?.right,
+                Ordering::Equal => break,
+            }
+        }
+        let mut node = slot.take()?;
+        *slot = match (node.left.take(), node.right.take()) {
+            (None, None) => None,
+            (Some(child), None) | (None, Some(child)) => Some(child),
+            (Some(left), Some(right)) => {
+                let mut rest = Some(right);
+                let mut successor = Self::take_min(&mut rest).expect("a non-empty subtree has a smallest node");
+                successor.left = Some(left);
+                successor.right = rest;
+                Some(successor)
+            }
+        };
+        Some(node.value)
+    }
+
+    /// Detaches the smallest node of a subtree, leaving the rest of the
+    /// subtree in its place.
+    fn take_min(tree: &mut Tree<K, V>) -> Option<Box<Node<K, V>>> {
+        let mut slot = tree;
+        while slot.as_ref()?.left.is_some() {
+            slot = &mut slot.as_mut()?.left;
+        }
+        let mut min = slot.take()?;
+        *slot = min.right.take();
+        Some(min)
+    }
+
+    pub fn first(&self) -> Option<(&K, &V)> {
+        let mut node = self.root.as_deref()?;
+        while let Some(left) = node.left.as_deref() {
+            node = left;
+        }
+        Some((&node.key, &node.value))
+    }
+
+    pub fn last(&self) -> Option<(&K, &V)> {
+        let mut node = self.root.as_deref()?;
+        while let Some(right) = node.right.as_deref() {
+            node = right;
+        }
+        Some((&node.key, &node.value))
+    }
+}
+
+impl<K, V> BstMap<K, V> {
+    /// The number of nodes on the longest path from the root to a leaf;
+    /// 0 for an empty tree.
+    pub fn height(&self) -> usize {
+        let mut height = 0;
+        let mut stack: Vec<(&Node<K, V>, usize)> = self.root.as_deref().map(|root| (root, 1)).into_iter().collect();
+        while let Some((node, depth)) = stack.pop() {
+            height = height.max(depth);
+            for child in [&node.left, &node.right].into_iter().flatten() {
+                stack.push((child, depth + 1));
+            }
+        }
+        height
+    }
+
+    /// Iterates over the entries in ascending key order.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        let mut iter = Iter { stack: Vec::new() };
+        iter.push_left_spine(self.root.as_deref());
+        iter
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &K> + '_ {
+        self.iter().map(|(key, _)| key)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &V> + '_ {
+        self.iter().map(|(_, value)| value)
+    }
+}
+
+impl<K, V> Drop for BstMap<K, V> {
+    fn drop(&mut self) {
+        // Detach each node's children before it drops, so no drop recurses
+        let mut stack: Vec<Box<Node<K, V>>> = self.root.take().into_iter().collect();
+        while let Some(mut node) = stack.pop() {
+            stack.extend(node.left.take());
+            stack.extend(node.right.take());
+        }
+    }
+}
+
+impl<K: Ord, V> Default for BstMap<K, V> {
+    fn default() -> Self {
+        BstMap::new()
+    }
+}
+
+impl<K: Ord, V> FromIterator<(K, V)> for BstMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = BstMap::new();
+        for (key, value) in iter {
+            map.insert(key, value);
+        }
+        map
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for BstMap<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// In-order iterator over a [`BstMap`].
+///
+/// The stack holds the nodes whose left subtrees have been visited but
+/// which have not been yielded yet, so it never grows beyond the tree's
+/// height.
+pub struct Iter<'a, K, V> {
+    stack: Vec<&'a Node<K, V>>,
+}
+
+impl<'a, K, V> Iter<'a, K, V> {
+    fn push_left_spine(&mut self, mut node: Option<&'a Node<K, V>>) {
+        while let Some(current) = node {
+            self.stack.push(current);
+            node = current.left.as_deref();
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        self.push_left_spine(node.right.as_deref());
+        Some((&node.key, &node.value))
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a BstMap<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// A sorted set backed by a binary search tree.
+///
+/// ```
+/// use rustler::collections::BstSet;
+///
+/// let set: BstSet<_> = [5, 1, 3, 1].into_iter().collect();
+/// assert_eq!(set.len(), 3);
+/// assert_eq!(set.iter().collect::<Vec<_>>(), vec![&1, &3, &5]);
+/// ```
+pub struct BstSet<T> {
+    map: BstMap<T, ()>,
+}
+
+impl<T: Ord> BstSet<T> {
+    pub fn new() -> Self {
+        BstSet { map: BstMap::new() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Adds a value, returning false if it was already present.
+    pub fn insert(&mut self, value: T) -> bool {
+        self.map.insert(value, ()).is_none()
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        self.map.contains_key(value)
+    }
+
+    /// Removes a value, returning true if it was present.
+    pub fn remove(&mut self, value: &T) -> bool {
+        self.map.remove(value).is_some()
+    }
+
+    pub fn first(&self) -> Option<&T> {
+        self.map.first().map(|(value, _)| value)
+    }
+
+    pub fn last(&self) -> Option<&T> {
+        self.map.last().map(|(value, _)| value)
+    }
+}
+
+impl<T> BstSet<T> {
+    pub fn height(&self) -> usize {
+        self.map.height()
+    }
+
+    /// Iterates over the values in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        self.map.keys()
+    }
+}
+
+impl<T: Ord> Default for BstSet<T> {
+    fn default() -> Self {
+        BstSet::new()
+    }
+}
+
+impl<T: Ord> FromIterator<T> for BstSet<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut set = BstSet::new();
+        for value in iter {
+            set.insert(value);
+        }
+        set
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for BstSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> BstMap<i32, &'static str> {
+        //        50
+        //      /    \
+        //    30      70
+        //   /  \    /  \
+        //  20  40  60  80
+        [(50, "e"), (30, "c"), (70, "g"), (20, "b"), (40, "d"), (60, "f"), (80, "h")]
+            .into_iter()
+            .collect()
+    }
+
+    #[test]
+    fn test_insert_and_get() {
+        let mut map = sample();
+        assert_eq!(map.len(), 7);
+        assert_eq!(map.get(&40), Some(&"d"));
+        assert_eq!(map.get(&45), None);
+        assert_eq!(map.insert(40, "D"), Some("d"));
+        assert_eq!(map.len(), 7);
+        *map.get_mut(&80).unwrap() = "H";
+        assert_eq!(map.values().copied().collect::<Vec<_>>(), vec!["b", "c", "D", "e", "f", "g", "H"]);
+    }
+
+    #[test]
+    fn test_in_order_iteration() {
+        let map = sample();
+        let keys: Vec<_> = map.keys().copied().collect();
+        assert_eq!(keys, vec![20, 30, 40, 50, 60, 70, 80]);
+        assert_eq!(map.first(), Some((&20, &"b")));
+        assert_eq!(map.last(), Some((&80, &"h")));
+        assert_eq!(format!("{:?}", BstMap::from_iter([(2, 'b'), (1, 'a')])), "{1: 'a', 2: 'b'}");
+    }
+
+    #[test]
+    fn test_remove_leaf_and_single_child() {
+        let mut map = sample();
+        assert_eq!(map.remove(&20), Some("b"));
+        // 30 now has only a right child
+        assert_eq!(map.remove(&30), Some("c"));
+        assert_eq!(map.remove(&30), None);
+        assert_eq!(map.keys().copied().collect::<Vec<_>>(), vec![40, 50, 60, 70, 80]);
+        assert_eq!(map.len(), 5);
+    }
+
+    #[test]
+    fn test_remove_with_two_children() {
+        let mut map = sample();
+        assert_eq!(map.remove(&50), Some("e"));
+        assert_eq!(map.keys().copied().collect::<Vec<_>>(), vec![20, 30, 40, 60, 70, 80]);
+        assert_eq!(map.remove(&70), Some("g"));
+        assert_eq!(map.keys().copied().collect::<Vec<_>>(), vec![20, 30, 40, 60, 80]);
+        for key in [20, 30, 40, 60, 80] {
+            assert!(map.remove(&key).is_some());
+        }
+        assert!(map.is_empty());
+        assert_eq!(map.height(), 0);
+    }
+
+    #[test]
+    fn test_height() {
+        assert_eq!(sample().height(), 3);
+        // Sorted input degenerates into a list
+        let sorted: BstMap<i32, ()> = (0..10).map(|k| (k, ())).collect();
+        assert_eq!(sorted.height(), 10);
+    }
+
+    #[test]
+    fn test_deep_trees_do_not_overflow_the_stack() {
+        // A small stack, so that any recursion down this list-shaped tree
+        // would overflow long before the end
+        let worker = std::thread::Builder::new().stack_size(64 * 1024).spawn(|| {
+            let n = 10_000;
+            let mut map: BstMap<i32, i32> = (0..n).map(|k| (k, k)).collect();
+            assert_eq!(map.height(), n as usize);
+            assert_eq!(map.remove(&(n - 1)), Some(n - 1));
+            assert_eq!(map.remove(&(n / 2)), Some(n / 2));
+            // Putting the middle back gives its successor a left child as
+            // well as a right one, so removing that finds a successor deep
+            // down the list
+            map.insert(n / 2, 0);
+            assert_eq!(map.remove(&(n / 2 + 1)), Some(n / 2 + 1));
+            assert_eq!(map.len(), n as usize - 2);
+            assert!(map.keys().copied().eq((0..n - 1).filter(|&k| k != n / 2 + 1)));
+            let set: BstSet<i32> = (0..n).collect();
+            drop(set);
+        });
+        worker.unwrap().join().unwrap();
+    }
+
+    #[test]
+    fn test_set() {
+        let mut set = BstSet::new();
+        assert!(set.insert("pear"));
+        assert!(set.insert("apple"));
+        assert!(!set.insert("pear"));
+        assert!(set.contains(&"apple"));
+        assert_eq!(set.first(), Some(&"apple"));
+        assert_eq!(set.last(), Some(&"pear"));
+        assert!(set.remove(&"apple"));
+        assert!(!set.remove(&"apple"));
+        assert_eq!(set.len(), 1);
+        assert_eq!(format!("{:?}", set), r#"{"pear"}"#);
+        assert_eq!(set.height(), 1);
+    }
+}
@@ -0,0 +1,208 @@
+//! A multiset that counts how many times each item has been seen.
+//!
+//! Counting with a plain `HashMap` means writing
+//! `*map.entry(item).or_insert(0) += 1` every time; `Counter` wraps that
+//! idiom and adds the queries that usually follow it, like "what are the
+//! k most common items".
+
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Counts of items, like Python's `collections.Counter`.
+///
+/// ```
+/// use rustler::collections::Counter;
+///
+/// let words: Counter<&str> = "the cat saw the dog and the bird".split_whitespace().collect();
+/// assert_eq!(words.count("the"), 3);
+/// assert_eq!(words.count("fish"), 0);
+/// assert_eq!(words.most_common(2), vec![(&"the", 3), (&"and", 1)]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Counter<T: Hash + Eq> {
+    counts: HashMap<T, usize>,
+}
+
+impl<T: Hash + Eq> Counter<T> {
+    pub fn new() -> Self {
+        Counter { counts: HashMap::new() }
+    }
+
+    /// Counts one more `item`, returning its new count.
+    pub fn add(&mut self, item: T) -> usize {
+        self.add_many(item, 1)
+    }
+
+    /// Counts `item` another `n` times, returning its new count. Adding
+    /// zero leaves an item that was never counted out altogether.
+    pub fn add_many(&mut self, item: T, n: usize) -> usize {
+        if n == 0 {
+            return self.count(&item);
+        }
+        let count = self.counts.entry(item).or_insert(0);
+        *count += n;
+        *count
+    }
+
+    /// How many times `item` has been counted; zero if never.
+    pub fn count<Q>(&self, item: &Q) -> usize
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.counts.get(item).copied().unwrap_or(0)
+    }
+
+    /// Forgets `item` entirely, returning the count it had.
+    pub fn remove<Q>(&mut self, item: &Q) -> usize
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.counts.remove(item).unwrap_or(0)
+    }
+
+    /// The number of distinct items.
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// The sum of all counts.
+    pub fn total(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    /// Iterates over `(item, count)` pairs in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&T, usize)> + '_ {
+        self.counts.iter().map(|(item, &count)| (item, count))
+    }
+}
+
+impl<T: Hash + Eq + Ord> Counter<T> {
+    /// The `k` items with the highest counts, highest first. Items with
+    /// equal counts come out in ascending order so the result does not
+    /// depend on hash order.
+    pub fn most_common(&self, k: usize) -> Vec<(&T, usize)> {
+        let mut ranked: Vec<_> = self.iter().collect();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        ranked.truncate(k);
+        ranked
+    }
+}
+
+impl<T: Hash + Eq + Clone> Counter<T> {
+    /// Every item in either counter, with the larger of its two counts.
+    pub fn union(&self, other: &Counter<T>) -> Counter<T> {
+        let mut result = self.clone();
+        for (item, count) in other.iter() {
+            let entry = result.counts.entry(item.clone()).or_insert(0);
+            *entry = (*entry).max(count);
+        }
+        result
+    }
+
+    /// Items in both counters, with the smaller of their two counts.
+    pub fn intersection(&self, other: &Counter<T>) -> Counter<T> {
+        let counts = self
+            .iter()
+            .filter_map(|(item, count)| {
+                let shared = count.min(other.count(item));
+                (shared > 0).then(|| (item.clone(), shared))
+            })
+            .collect();
+        Counter { counts }
+    }
+}
+
+impl<T: Hash + Eq> Default for Counter<T> {
+    fn default() -> Self {
+        Counter::new()
+    }
+}
+
+impl<T: Hash + Eq> FromIterator<T> for Counter<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut counter = Counter::new();
+        counter.extend(iter);
+        counter
+    }
+}
+
+impl<T: Hash + Eq> Extend<T> for Counter<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.add(item);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_and_count() {
+        let mut counter = Counter::new();
+        assert!(counter.is_empty());
+        assert_eq!(counter.add('a'), 1);
+        assert_eq!(counter.add('a'), 2);
+        assert_eq!(counter.add_many('b', 5), 5);
+        assert_eq!(counter.count(&'a'), 2);
+        assert_eq!(counter.count(&'z'), 0);
+        assert_eq!(counter.len(), 2);
+        assert_eq!(counter.total(), 7);
+        assert_eq!(counter.remove(&'b'), 5);
+        assert_eq!(counter.remove(&'b'), 0);
+        assert_eq!(counter.total(), 2);
+    }
+
+    #[test]
+    fn test_adding_zero_records_nothing() {
+        let mut counter = Counter::new();
+        assert_eq!(counter.add_many("ghost", 0), 0);
+        assert!(counter.is_empty());
+        assert!(counter.most_common(5).is_empty());
+        counter.add("seen");
+        assert_eq!(counter.add_many("seen", 0), 1);
+        assert_eq!(counter.len(), 1);
+    }
+
+    #[test]
+    fn test_borrowed_lookup() {
+        let counter: Counter<String> = ["x", "y", "x"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(counter.count("x"), 2);
+    }
+
+    #[test]
+    fn test_most_common_breaks_ties_by_item() {
+        let counter: Counter<char> = "mississippi".chars().collect();
+        assert_eq!(counter.most_common(3), vec![(&'i', 4), (&'s', 4), (&'p', 2)]);
+        assert_eq!(counter.most_common(10).len(), 4);
+        assert!(counter.most_common(0).is_empty());
+    }
+
+    #[test]
+    fn test_union_and_intersection() {
+        let a: Counter<char> = "aaab".chars().collect();
+        let b: Counter<char> = "abbc".chars().collect();
+        let union = a.union(&b);
+        assert_eq!((union.count(&'a'), union.count(&'b'), union.count(&'c')), (3, 2, 1));
+        let both = a.intersection(&b);
+        assert_eq!((both.count(&'a'), both.count(&'b')), (1, 1));
+        assert_eq!(both.len(), 2);
+        assert!(a.intersection(&Counter::new()).is_empty());
+    }
+
+    #[test]
+    fn test_extend() {
+        let mut counter: Counter<u8> = [1, 2].into_iter().collect();
+        counter.extend([2, 2, 3]);
+        assert_eq!(counter.count(&2), 3);
+        assert_eq!(counter.total(), 5);
+    }
+}
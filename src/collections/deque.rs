@@ -0,0 +1,272 @@
+//! A double-ended queue on a growable ring buffer.
+//!
+//! The items live in a fixed-size buffer that is treated as a circle: `head`
+//! is the index of the front item, and the back wraps around to the start
+//! of the buffer when it reaches the end. When the buffer fills up it is
+//! replaced with one twice the size and the items are copied across in
+//! order, so pushes are O(1) amortized.
+
+use std::fmt;
+
+/// Capacity of the first buffer allocated by a push.
+const MIN_CAPACITY: usize = 4;
+
+/// A double-ended queue supporting O(1) pushes and pops at both ends.
+///
+/// ```
+/// use rustler::collections::Deque;
+///
+/// let mut deque = Deque::new();
+/// deque.push_back(2);
+/// deque.push_front(1);
+/// deque.push_back(3);
+/// assert_eq!(deque.pop_front(), Some(1));
+/// assert_eq!(deque.pop_back(), Some(3));
+/// assert_eq!(deque.len(), 1);
+/// ```
+pub struct Deque<T> {
+    /// Empty slots are `None`; the `len` slots starting at `head` (wrapping
+    /// around) are always `Some`.
+    buffer: Vec<Option<T>>,
+    head: usize,
+    len: usize,
+}
+
+impl<T> Deque<T> {
+    /// An empty deque. Nothing is allocated until the first push.
+    pub fn new() -> Self {
+        Deque {
+            buffer: Vec::new(),
+            head: 0,
+            len: 0,
+        }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut deque = Deque::new();
+        deque.buffer.resize_with(capacity, || None);
+        deque
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// How many items fit before the buffer has to grow.
+    pub fn capacity(&self) -> usize {
+        self.buffer.len()
+    }
+
+    pub fn push_back(&mut self, item: T) {
+        self.grow_if_full();
+        let index = self.physical(self.len);
+        self.buffer[index] = Some(item);
+        self.len += 1;
+    }
+
+    pub fn push_front(&mut self, item: T) {
+        self.grow_if_full();
+        self.head = (self.head + self.capacity() - 1) % self.capacity();
+        self.buffer[self.head] = Some(item);
+        self.len += 1;
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        let item = self.buffer[self.head].take();
+        self.head = (self.head + 1) % self.capacity();
+        self.len -= 1;
+        item
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        let index = self.physical(self.len - 1);
+        self.len -= 1;
+        self.buffer[index].take()
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        self.len.checked_sub(1).and_then(|last| self.get(last))
+    }
+
+    /// The item `index` places from the front.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.len {
+            return None;
+        }
+        self.buffer[self.physical(index)].as_ref()
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index >= self.len {
+            return None;
+        }
+        let physical = self.physical(index);
+        self.buffer[physical].as_mut()
+    }
+
+    pub fn clear(&mut self) {
+        while self.pop_front().is_some() {}
+        self.head = 0;
+    }
+
+    /// Iterates from front to back.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> + '_ {
+        (0..self.len).map(move |i| self.buffer[self.physical(i)].as_ref().expect("slot in use"))
+    }
+
+    /// Maps a position counted from the front to an index in the buffer.
+    fn physical(&self, index: usize) -> usize {
+        (self.head + index) % self.capacity()
+    }
+
+    /// Doubles the buffer when it is full, moving the items so the front
+    /// lands at index 0 of the new buffer.
+    fn grow_if_full(&mut self) {
+        if self.len < self.capacity() {
+            return;
+        }
+        let new_capacity = (self.capacity() * 2).max(MIN_CAPACITY);
+        let mut buffer: Vec<Option<T>> = Vec::with_capacity(new_capacity);
+        for i in 0..self.len {
+            let index = self.physical(i);
+            buffer.push(self.buffer[index].take());
+        }
+        buffer.resize_with(new_capacity, || None);
+        self.buffer = buffer;
+        self.head = 0;
+    }
+}
+
+impl<T> Default for Deque<T> {
+    fn default() -> Self {
+        Deque::new()
+    }
+}
+
+impl<T> Extend<T> for Deque<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push_back(item);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for Deque<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut deque = Deque::new();
+        deque.extend(iter);
+        deque
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Deque<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for Deque<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contents(deque: &Deque<i32>) -> Vec<i32> {
+        deque.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_both_ends() {
+        let mut deque = Deque::new();
+        assert_eq!(deque.pop_front(), None);
+        assert_eq!(deque.pop_back(), None);
+        deque.push_back(2);
+        deque.push_back(3);
+        deque.push_front(1);
+        assert_eq!(contents(&deque), vec![1, 2, 3]);
+        assert_eq!(deque.front(), Some(&1));
+        assert_eq!(deque.back(), Some(&3));
+        assert_eq!(deque.pop_back(), Some(3));
+        assert_eq!(deque.pop_front(), Some(1));
+        assert_eq!(deque.pop_front(), Some(2));
+        assert!(deque.is_empty());
+        assert_eq!(deque.back(), None);
+    }
+
+    #[test]
+    fn test_wrap_around() {
+        let mut deque = Deque::with_capacity(4);
+        deque.extend([1, 2, 3]);
+        assert_eq!(deque.pop_front(), Some(1));
+        assert_eq!(deque.pop_front(), Some(2));
+        // The back now wraps past the end of the buffer
+        deque.push_back(4);
+        deque.push_back(5);
+        deque.push_back(6);
+        assert_eq!(deque.capacity(), 4);
+        assert_eq!(contents(&deque), vec![3, 4, 5, 6]);
+        assert_eq!(deque.get(3), Some(&6));
+        assert_eq!(deque.get(4), None);
+        // And the front wraps backwards past index 0
+        let mut front = Deque::with_capacity(4);
+        front.push_front(1);
+        front.push_front(0);
+        assert_eq!(contents(&front), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_reallocation_keeps_order() {
+        let mut deque = Deque::with_capacity(4);
+        deque.extend([1, 2, 3, 4]);
+        deque.pop_front();
+        deque.push_back(5);
+        // Full and wrapped: growing must unwrap the items in order
+        deque.push_front(0);
+        assert_eq!(deque.capacity(), 8);
+        assert_eq!(contents(&deque), vec![0, 2, 3, 4, 5]);
+        for i in 6..100 {
+            deque.push_back(i);
+        }
+        assert_eq!(deque.len(), 99);
+        assert_eq!(deque.back(), Some(&99));
+        assert_eq!(deque.iter().next_back(), Some(&99));
+    }
+
+    #[test]
+    fn test_get_mut_and_clear() {
+        let mut deque: Deque<i32> = (1..=3).collect();
+        *deque.get_mut(1).unwrap() = 20;
+        assert_eq!(contents(&deque), vec![1, 20, 3]);
+        deque.clear();
+        assert!(deque.is_empty());
+        deque.push_back(7);
+        assert_eq!(format!("{:?}", deque), "[7]");
+    }
+
+    #[test]
+    fn test_drops_owned_items() {
+        let mut deque = Deque::new();
+        deque.push_back(String::from("a"));
+        deque.push_front(String::from("b"));
+        assert_eq!(deque.pop_back().as_deref(), Some("a"));
+        assert_eq!(deque, ["b".to_string()].into_iter().collect());
+    }
+}
@@ -0,0 +1,339 @@
+//! A doubly linked list using shared ownership.
+//!
+//! Every node is reachable from two directions, so no single owner can hold
+//! it in a `Box`. Instead nodes live in `Rc<RefCell<_>>`: `Rc` lets several
+//! links share a node and `RefCell` lets us relink it through those shared
+//! references.
+//!
+//! Forward links (`next`, and `head`/`tail` on the list) are strong `Rc`s,
+//! while backward links (`prev`) are `Weak`. If both directions were strong,
+//! each pair of neighbours would keep the other alive and the nodes would
+//! never be freed even after the list was dropped.
+
+use std::cell::{Ref, RefCell, RefMut};
+use std::fmt;
+use std::rc::{Rc, Weak};
+
+type Link<T> = Option<Rc<RefCell<Node<T>>>>;
+
+struct Node<T> {
+    value: T,
+    next: Link<T>,
+    prev: Option<Weak<RefCell<Node<T>>>>,
+}
+
+impl<T> Node<T> {
+    fn new(value: T) -> Rc<RefCell<Self>> {
+        Rc::new(RefCell::new(Node {
+            value,
+            next: None,
+            prev: None,
+        }))
+    }
+}
+
+/// A doubly linked list with O(1) push and pop at both ends.
+///
+/// Peeking returns a `Ref`/`RefMut` guard rather than a plain reference,
+/// because the value sits inside a `RefCell`.
+///
+/// ```
+/// use rustler::collections::DoublyLinkedList;
+///
+/// let mut list = DoublyLinkedList::new();
+/// list.push_back(2);
+/// list.push_front(1);
+/// list.push_back(3);
+/// assert_eq!(*list.peek_front().unwrap(), 1);
+/// assert_eq!(list.pop_back(), Some(3));
+/// assert_eq!(list.pop_front(), Some(1));
+/// ```
+pub struct DoublyLinkedList<T> {
+    head: Link<T>,
+    tail: Link<T>,
+    len: usize,
+}
+
+impl<T> DoublyLinkedList<T> {
+    pub fn new() -> Self {
+        DoublyLinkedList {
+            head: None,
+            tail: None,
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    pub fn push_front(&mut self, value: T) {
+        let node = Node::new(value);
+        match self.head.take() {
+            Some(old_head) => {
+                old_head.borrow_mut().prev = Some(Rc::downgrade(&node));
+                node.borrow_mut().next = Some(old_head);
+            }
+            None => self.tail = Some(Rc::clone(&node)),
+        }
+        self.head = Some(node);
+        self.len += 1;
+    }
+
+    pub fn push_back(&mut self, value: T) {
+        let node = Node::new(value);
+        match self.tail.take() {
+            Some(old_tail) => {
+                node.borrow_mut().prev = Some(Rc::downgrade(&old_tail));
+                old_tail.borrow_mut().next = Some(Rc::clone(&node));
+            }
+            None => self.head = Some(Rc::clone(&node)),
+        }
+        self.tail = Some(node);
+        self.len += 1;
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        let old_head = self.head.take()?;
+        match old_head.borrow_mut().next.take() {
+            Some(new_head) => {
+                new_head.borrow_mut().prev = None;
+                self.head = Some(new_head);
+            }
+            None => self.tail = None,
+        }
+        self.len -= 1;
+        Some(Self::into_value(old_head))
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        let old_tail = self.tail.take()?;
+        let prev = old_tail.borrow_mut().prev.take().and_then(|weak| weak.upgrade());
+        match prev {
+            Some(new_tail) => {
+                new_tail.borrow_mut().next = None;
+                self.tail = Some(new_tail);
+            }
+            None => self.head = None,
+        }
+        self.len -= 1;
+        Some(Self::into_value(old_tail))
+    }
+
+    pub fn peek_front(&self) -> Option<Ref<'_, T>> {
+        self.head.as_ref().map(|node| Ref::map(node.borrow(), |node| &node.value))
+    }
+
+    pub fn peek_back(&self) -> Option<Ref<'_, T>> {
+        self.tail.as_ref().map(|node| Ref::map(node.borrow(), |node| &node.value))
+    }
+
+    pub fn peek_front_mut(&mut self) -> Option<RefMut<'_, T>> {
+        self.head.as_ref().map(|node| RefMut::map(node.borrow_mut(), |node| &mut node.value))
+    }
+
+    pub fn peek_back_mut(&mut self) -> Option<RefMut<'_, T>> {
+        self.tail.as_ref().map(|node| RefMut::map(node.borrow_mut(), |node| &mut node.value))
+    }
+
+    /// Unwraps a node that has been unlinked from the list. By then the
+    /// list held the only strong reference to it.
+    fn into_value(node: Rc<RefCell<Node<T>>>) -> T {
+        match Rc::try_unwrap(node) {
+            Ok(cell) => cell.into_inner().value,
+            Err(_) => unreachable!("an unlinked node has no other owners"),
+        }
+    }
+}
+
+impl<T: Clone> DoublyLinkedList<T> {
+    /// Copies the values out from front to back.
+    pub fn to_vec(&self) -> Vec<T> {
+        let mut values = Vec::with_capacity(self.len);
+        let mut current = self.head.clone();
+        while let Some(node) = current {
+            values.push(node.borrow().value.clone());
+            current = node.borrow().next.clone();
+        }
+        values
+    }
+
+    /// Copies the values out from back to front, following the weak
+    /// `prev` links.
+    pub fn to_vec_rev(&self) -> Vec<T> {
+        let mut values = Vec::with_capacity(self.len);
+        let mut current = self.tail.clone();
+        while let Some(node) = current {
+            values.push(node.borrow().value.clone());
+            current = node.borrow().prev.as_ref().and_then(Weak::upgrade);
+        }
+        values
+    }
+}
+
+impl<T> Default for DoublyLinkedList<T> {
+    fn default() -> Self {
+        DoublyLinkedList::new()
+    }
+}
+
+/// Unlinks the nodes one at a time so they are freed in a loop instead of
+/// through a deep chain of recursive drops.
+impl<T> Drop for DoublyLinkedList<T> {
+    fn drop(&mut self) {
+        self.tail = None;
+        let mut current = self.head.take();
+        while let Some(node) = current {
+            current = node.borrow_mut().next.take();
+        }
+    }
+}
+
+impl<T> FromIterator<T> for DoublyLinkedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = DoublyLinkedList::new();
+        for value in iter {
+            list.push_back(value);
+        }
+        list
+    }
+}
+
+/// Owning iterator that pops from the front, or from the back when
+/// reversed.
+pub struct IntoIter<T>(DoublyLinkedList<T>);
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.0.pop_front()
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.0.pop_back()
+    }
+}
+
+impl<T> IntoIterator for DoublyLinkedList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter(self)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for DoublyLinkedList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut list = f.debug_list();
+        let mut current = self.head.clone();
+        while let Some(node) = current {
+            list.entry(&node.borrow().value);
+            current = node.borrow().next.clone();
+        }
+        list.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    /// Counts how many times it has been dropped.
+    struct DropCounter<'a>(&'a Cell<usize>);
+
+    impl Drop for DropCounter<'_> {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    #[test]
+    fn test_push_and_pop_both_ends() {
+        let mut list = DoublyLinkedList::new();
+        assert_eq!(list.pop_front(), None);
+        assert_eq!(list.pop_back(), None);
+        list.push_back(2);
+        list.push_back(3);
+        list.push_front(1);
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.to_vec(), vec![1, 2, 3]);
+        assert_eq!(list.to_vec_rev(), vec![3, 2, 1]);
+        assert_eq!(list.pop_back(), Some(3));
+        assert_eq!(list.pop_front(), Some(1));
+        assert_eq!(list.pop_back(), Some(2));
+        assert!(list.is_empty());
+        assert!(list.peek_front().is_none());
+        assert!(list.peek_back().is_none());
+    }
+
+    #[test]
+    fn test_peek() {
+        let mut list: DoublyLinkedList<String> = ["a", "b"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(&*list.peek_front().unwrap(), "a");
+        assert_eq!(&*list.peek_back().unwrap(), "b");
+        list.peek_back_mut().unwrap().push('!');
+        list.peek_front_mut().unwrap().push('?');
+        assert_eq!(format!("{:?}", list), r#"["a?", "b!"]"#);
+    }
+
+    #[test]
+    fn test_single_element_links() {
+        let mut list = DoublyLinkedList::new();
+        list.push_front(7);
+        assert_eq!(list.pop_back(), Some(7));
+        list.push_back(8);
+        assert_eq!(list.pop_front(), Some(8));
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn test_double_ended_into_iter() {
+        let list: DoublyLinkedList<i32> = (1..=5).collect();
+        let mut iter = list.into_iter();
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.next_back(), Some(5));
+        assert_eq!(iter.collect::<Vec<_>>(), vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn test_drop_frees_every_node() {
+        let drops = Cell::new(0);
+        {
+            let mut list = DoublyLinkedList::new();
+            for _ in 0..10 {
+                list.push_back(DropCounter(&drops));
+            }
+            list.push_front(DropCounter(&drops));
+            drop(list.pop_back());
+            assert_eq!(drops.get(), 1);
+        }
+        assert_eq!(drops.get(), 11);
+    }
+
+    #[test]
+    fn test_no_strong_cycles() {
+        let mut list = DoublyLinkedList::new();
+        list.push_back(1);
+        list.push_back(2);
+        list.push_back(3);
+        // The middle node is owned only by its predecessor's `next`
+        let middle = list.head.as_ref().unwrap().borrow().next.clone().unwrap();
+        assert_eq!(Rc::strong_count(&middle), 2); // `prev.next` + our clone
+        assert_eq!(Rc::weak_count(&middle), 1); // the tail's `prev`
+        drop(middle);
+        let tail = list.tail.clone().unwrap();
+        drop(list);
+        // Only our handle is left once the list is gone
+        assert_eq!(Rc::strong_count(&tail), 1);
+        assert_eq!(Rc::weak_count(&tail), 0);
+    }
+}
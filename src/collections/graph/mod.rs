@@ -0,0 +1,333 @@
+//! Graphs stored as adjacency lists.
+//!
+//! Nodes carry a value of type `N` and are identified by the [`NodeId`]
+//! returned from [`Graph::add_node`]. Each node keeps a list of the nodes
+//! its edges lead to, along with each edge's weight; in an undirected graph
+//! every edge is recorded on both of its ends.
+
+use std::collections::VecDeque;
+use std::fmt;
+
+pub mod shortest_path;
+pub mod topo;
+
+pub use shortest_path::{a_star, shortest_path, Path};
+pub use topo::{has_cycle, topo_sort, CycleError};
+
+/// Identifies a node within the graph that created it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(usize);
+
+impl NodeId {
+    /// The position of the node in insertion order, starting at 0.
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+impl fmt::Display for NodeId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+/// A directed or undirected graph with a value of type `N` on each node.
+///
+/// ```
+/// use rustler::collections::Graph;
+///
+/// let mut graph = Graph::undirected();
+/// let a = graph.add_node("a");
+/// let b = graph.add_node("b");
+/// let c = graph.add_node("c");
+/// graph.add_edge(a, b);
+/// graph.add_edge(b, c);
+/// assert_eq!(graph.bfs(c), vec![c, b, a]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Graph<N> {
+    nodes: Vec<N>,
+    /// Outgoing edges of each node as `(target, weight)`.
+    adjacency: Vec<Vec<(NodeId, f64)>>,
+    directed: bool,
+    edge_count: usize,
+}
+
+impl<N> Graph<N> {
+    /// A graph whose edges go one way, from `from` to `to`.
+    pub fn directed() -> Self {
+        Graph::with_direction(true)
+    }
+
+    /// A graph whose edges can be followed both ways.
+    pub fn undirected() -> Self {
+        Graph::with_direction(false)
+    }
+
+    fn with_direction(directed: bool) -> Self {
+        Graph {
+            nodes: Vec::new(),
+            adjacency: Vec::new(),
+            directed,
+            edge_count: 0,
+        }
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    pub fn add_node(&mut self, value: N) -> NodeId {
+        self.nodes.push(value);
+        self.adjacency.push(Vec::new());
+        NodeId(self.nodes.len() - 1)
+    }
+
+    /// Connects two nodes with an edge of weight 1. Parallel edges and
+    /// self-loops are allowed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either id does not belong to this graph.
+    pub fn add_edge(&mut self, from: NodeId, to: NodeId) {
+        self.add_weighted_edge(from, to, 1.0);
+    }
+
+    /// Connects two nodes with an edge of the given weight, such as a
+    /// distance or a cost.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either id does not belong to this graph, or if the weight
+    /// is negative or NaN: shortest-path searches rely on weights never
+    /// making a path shorter.
+    pub fn add_weighted_edge(&mut self, from: NodeId, to: NodeId, weight: f64) {
+        assert!(self.contains(from) && self.contains(to), "node id out of range");
+        assert!(weight >= 0.0, "edge weight must be a non-negative number");
+        self.adjacency[from.0].push((to, weight));
+        if !self.directed && from != to {
+            self.adjacency[to.0].push((from, weight));
+        }
+        self.edge_count += 1;
+    }
+
+    pub fn contains(&self, id: NodeId) -> bool {
+        id.0 < self.nodes.len()
+    }
+
+    pub fn node(&self, id: NodeId) -> Option<&N> {
+        self.nodes.get(id.0)
+    }
+
+    pub fn node_mut(&mut self, id: NodeId) -> Option<&mut N> {
+        self.nodes.get_mut(id.0)
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edge_count
+    }
+
+    /// Every node id, in insertion order.
+    pub fn node_ids(&self) -> impl Iterator<Item = NodeId> {
+        (0..self.nodes.len()).map(NodeId)
+    }
+
+    /// The nodes reachable over one edge from `id`, in the order the edges
+    /// were added. Empty for an unknown id.
+    pub fn neighbors(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        self.edges(id).map(|(to, _)| to)
+    }
+
+    /// The edges leaving `id` as `(target, weight)` pairs.
+    pub fn edges(&self, id: NodeId) -> impl Iterator<Item = (NodeId, f64)> + '_ {
+        self.adjacency.get(id.0).into_iter().flatten().copied()
+    }
+
+    /// Breadth-first traversal: the nodes reachable from `start`, nearest
+    /// first.
+    pub fn bfs(&self, start: NodeId) -> Vec<NodeId> {
+        if !self.contains(start) {
+            return Vec::new();
+        }
+        let mut visited = vec![false; self.nodes.len()];
+        let mut order = Vec::new();
+        let mut queue = VecDeque::from([start]);
+        visited[start.0] = true;
+        while let Some(id) = queue.pop_front() {
+            order.push(id);
+            for next in self.neighbors(id) {
+                if !visited[next.0] {
+                    visited[next.0] = true;
+                    queue.push_back(next);
+                }
+            }
+        }
+        order
+    }
+
+    /// Depth-first traversal: the nodes reachable from `start`, following
+    /// each branch as far as it goes before backtracking.
+    ///
+    /// Uses an explicit stack rather than recursion, so deep graphs cannot
+    /// overflow the call stack. Neighbours are explored in the order their
+    /// edges were added.
+    pub fn dfs(&self, start: NodeId) -> Vec<NodeId> {
+        if !self.contains(start) {
+            return Vec::new();
+        }
+        let mut visited = vec![false; self.nodes.len()];
+        let mut order = Vec::new();
+        let mut stack = vec![start];
+        while let Some(id) = stack.pop() {
+            if visited[id.0] {
+                continue;
+            }
+            visited[id.0] = true;
+            order.push(id);
+            // Reversed so the first neighbour is popped first
+            let unvisited: Vec<NodeId> = self.neighbors(id).filter(|next| !visited[next.0]).collect();
+            stack.extend(unvisited.into_iter().rev());
+        }
+        order
+    }
+
+    /// Groups the nodes into connected components, each listed in
+    /// ascending id order, with components ordered by their smallest id.
+    ///
+    /// Edge direction is ignored, so for a directed graph these are the
+    /// weakly connected components.
+    pub fn connected_components(&self) -> Vec<Vec<NodeId>> {
+        // Union-find over node indices
+        let mut parent: Vec<usize> = (0..self.nodes.len()).collect();
+        fn find(parent: &mut [usize], mut i: usize) -> usize {
+            while parent[i] != i {
+                parent[i] = parent[parent[i]];
+                i = parent[i];
+            }
+            i
+        }
+        for (from, edges) in self.adjacency.iter().enumerate() {
+            for (to, _) in edges {
+                let (a, b) = (find(&mut parent, from), find(&mut parent, to.0));
+                if a != b {
+                    parent[a.max(b)] = a.min(b);
+                }
+            }
+        }
+
+        let mut components: Vec<Vec<NodeId>> = Vec::new();
+        let mut component_of_root = vec![usize::MAX; self.nodes.len()];
+        for i in 0..self.nodes.len() {
+            let root = find(&mut parent, i);
+            if component_of_root[root] == usize::MAX {
+                component_of_root[root] = components.len();
+                components.push(Vec::new());
+            }
+            components[component_of_root[root]].push(NodeId(i));
+        }
+        components
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 0 - 1 - 2    5 - 6
+    ///  \     /
+    ///   3 - 4
+    fn sample() -> (Graph<u32>, Vec<NodeId>) {
+        let mut graph = Graph::undirected();
+        let ids: Vec<_> = (0..7).map(|i| graph.add_node(i)).collect();
+        for (a, b) in [(0, 1), (1, 2), (0, 3), (3, 4), (4, 2), (5, 6)] {
+            graph.add_edge(ids[a], ids[b]);
+        }
+        (graph, ids)
+    }
+
+    #[test]
+    fn test_nodes_and_edges() {
+        let (mut graph, ids) = sample();
+        assert_eq!(graph.node_count(), 7);
+        assert_eq!(graph.edge_count(), 6);
+        assert_eq!(graph.node(ids[3]), Some(&3));
+        *graph.node_mut(ids[3]).unwrap() = 30;
+        assert_eq!(graph.node(ids[3]), Some(&30));
+        assert_eq!(graph.neighbors(ids[0]).collect::<Vec<_>>(), vec![ids[1], ids[3]]);
+        assert_eq!(graph.neighbors(ids[2]).collect::<Vec<_>>(), vec![ids[1], ids[4]]);
+        assert_eq!(graph.neighbors(NodeId(99)).count(), 0);
+    }
+
+    #[test]
+    fn test_bfs() {
+        let (graph, ids) = sample();
+        let order: Vec<usize> = graph.bfs(ids[0]).into_iter().map(NodeId::index).collect();
+        assert_eq!(order, vec![0, 1, 3, 2, 4]);
+        assert!(graph.bfs(NodeId(99)).is_empty());
+    }
+
+    #[test]
+    fn test_dfs() {
+        let (graph, ids) = sample();
+        let order: Vec<usize> = graph.dfs(ids[0]).into_iter().map(NodeId::index).collect();
+        assert_eq!(order, vec![0, 1, 2, 4, 3]);
+        assert_eq!(graph.dfs(ids[5]), vec![ids[5], ids[6]]);
+    }
+
+    #[test]
+    fn test_directed_edges_go_one_way() {
+        let mut graph = Graph::directed();
+        let a = graph.add_node('a');
+        let b = graph.add_node('b');
+        let c = graph.add_node('c');
+        graph.add_edge(a, b);
+        graph.add_edge(c, b);
+        assert!(graph.is_directed());
+        assert_eq!(graph.bfs(a), vec![a, b]);
+        assert_eq!(graph.bfs(b), vec![b]);
+        // Weakly connected: direction is ignored
+        assert_eq!(graph.connected_components(), vec![vec![a, b, c]]);
+    }
+
+    #[test]
+    fn test_connected_components() {
+        let (mut graph, ids) = sample();
+        let lonely = graph.add_node(7);
+        let components = graph.connected_components();
+        assert_eq!(components.len(), 3);
+        assert_eq!(components[0], vec![ids[0], ids[1], ids[2], ids[3], ids[4]]);
+        assert_eq!(components[1], vec![ids[5], ids[6]]);
+        assert_eq!(components[2], vec![lonely]);
+    }
+
+    #[test]
+    fn test_weighted_edges() {
+        let mut graph = Graph::undirected();
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        graph.add_weighted_edge(a, b, 2.5);
+        graph.add_edge(b, b);
+        assert_eq!(graph.edges(a).collect::<Vec<_>>(), vec![(b, 2.5)]);
+        assert_eq!(graph.edges(b).collect::<Vec<_>>(), vec![(a, 2.5), (b, 1.0)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "edge weight must be a non-negative number")]
+    fn test_negative_weight_panics() {
+        let mut graph = Graph::directed();
+        let a = graph.add_node(());
+        graph.add_weighted_edge(a, a, -1.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "node id out of range")]
+    fn test_foreign_node_id_panics() {
+        let mut graph: Graph<()> = Graph::directed();
+        let a = graph.add_node(());
+        graph.add_edge(a, NodeId(5));
+    }
+}
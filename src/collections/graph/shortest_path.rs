@@ -0,0 +1,249 @@
+//! Cheapest paths through weighted graphs.
+//!
+//! [`shortest_path`] is Dijkstra's algorithm: it always expands the
+//! unfinished node with the lowest known cost, so the first time it reaches
+//! the goal it has found the cheapest route. [`a_star`] adds a heuristic
+//! estimate of the remaining cost to that priority, which steers the search
+//! towards the goal and usually expands far fewer nodes.
+
+use std::cmp::Ordering;
+
+use super::{Graph, NodeId};
+use crate::collections::BinaryHeap;
+
+/// A route through a graph and its total edge weight.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Path {
+    /// The nodes visited, starting with the start node and ending with the
+    /// goal.
+    pub nodes: Vec<NodeId>,
+    pub cost: f64,
+}
+
+/// A node waiting to be expanded, ordered so the lowest `priority` is the
+/// greatest and comes out of the max-heap first. Priorities are `f64`, so
+/// they are compared with `total_cmp`.
+struct Candidate {
+    priority: f64,
+    /// The cost of reaching `node` when it was queued, to tell a stale
+    /// entry from the current one.
+    cost: f64,
+    node: NodeId,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.total_cmp(&self.priority).then_with(|| other.node.cmp(&self.node))
+    }
+}
+
+/// Finds the cheapest path from `start` to `goal` with Dijkstra's
+/// algorithm.
+///
+/// Returns `None` when the goal cannot be reached or either id is not in
+/// the graph.
+///
+/// ```
+/// use rustler::collections::graph::{shortest_path, Graph};
+///
+/// let mut graph = Graph::directed();
+/// let a = graph.add_node("a");
+/// let b = graph.add_node("b");
+/// let c = graph.add_node("c");
+/// graph.add_weighted_edge(a, c, 10.0);
+/// graph.add_weighted_edge(a, b, 2.0);
+/// graph.add_weighted_edge(b, c, 3.0);
+///
+/// let path = shortest_path(&graph, a, c).unwrap();
+/// assert_eq!(path.nodes, vec![a, b, c]);
+/// assert_eq!(path.cost, 5.0);
+/// ```
+pub fn shortest_path<N>(graph: &Graph<N>, start: NodeId, goal: NodeId) -> Option<Path> {
+    a_star(graph, start, goal, |_| 0.0)
+}
+
+/// Finds the cheapest path from `start` to `goal` with the A* algorithm.
+///
+/// `heuristic(node)` estimates the remaining cost from `node` to the goal.
+/// The result is guaranteed to be the cheapest path only if the estimate
+/// never exceeds the true remaining cost; for example, straight-line
+/// distance when edge weights are distances. A node that turns out to be
+/// cheaper to reach after it was expanded is expanded again, so that
+/// guarantee holds even when the estimate is not consistent from one node
+/// to the next. A heuristic that always returns 0 makes this plain
+/// Dijkstra.
+pub fn a_star<N, H>(graph: &Graph<N>, start: NodeId, goal: NodeId, heuristic: H) -> Option<Path>
+where
+    H: Fn(NodeId) -> f64,
+{
+    if !graph.contains(start) || !graph.contains(goal) {
+        return None;
+    }
+    let count = graph.node_count();
+    let mut cost = vec![f64::INFINITY; count];
+    let mut came_from: Vec<Option<NodeId>> = vec![None; count];
+    let mut open = BinaryHeap::new();
+
+    cost[start.index()] = 0.0;
+    open.push(Candidate {
+        priority: heuristic(start),
+        cost: 0.0,
+        node: start,
+    });
+
+    while let Some(Candidate { cost: queued_cost, node, .. }) = open.pop() {
+        // A node is queued again each time a cheaper route to it turns up,
+        // even after it was expanded; only the entry for its current cost
+        // matters
+        if queued_cost > cost[node.index()] {
+            continue;
+        }
+        if node == goal {
+            return Some(Path {
+                nodes: reconstruct(&came_from, goal),
+                cost: cost[goal.index()],
+            });
+        }
+        for (next, weight) in graph.edges(node) {
+            let new_cost = cost[node.index()] + weight;
+            if new_cost < cost[next.index()] {
+                cost[next.index()] = new_cost;
+                came_from[next.index()] = Some(node);
+                open.push(Candidate {
+                    priority: new_cost + heuristic(next),
+                    cost: new_cost,
+                    node: next,
+                });
+            }
+        }
+    }
+    None
+}
+
+/// Walks the `came_from` links back from the goal and reverses them.
+fn reconstruct(came_from: &[Option<NodeId>], goal: NodeId) -> Vec<NodeId> {
+    let mut nodes = vec![goal];
+    let mut current = goal;
+    while let Some(previous) = came_from[current.index()] {
+        nodes.push(previous);
+        current = previous;
+    }
+    nodes.reverse();
+    nodes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shapes::Point2D;
+
+    #[test]
+    fn test_dijkstra_prefers_cheaper_detour() {
+        let mut graph = Graph::undirected();
+        let ids: Vec<_> = "abcde".chars().map(|c| graph.add_node(c)).collect();
+        let (a, b, c, d, e) = (ids[0], ids[1], ids[2], ids[3], ids[4]);
+        graph.add_weighted_edge(a, b, 4.0);
+        graph.add_weighted_edge(a, c, 1.0);
+        graph.add_weighted_edge(c, b, 2.0);
+        graph.add_weighted_edge(b, d, 1.0);
+        graph.add_weighted_edge(c, d, 5.0);
+        graph.add_weighted_edge(d, e, 3.0);
+
+        let path = shortest_path(&graph, a, e).unwrap();
+        assert_eq!(path.nodes, vec![a, c, b, d, e]);
+        assert_eq!(path.cost, 7.0);
+        // Undirected, so the reverse trip costs the same
+        assert_eq!(shortest_path(&graph, e, a).unwrap().cost, 7.0);
+    }
+
+    #[test]
+    fn test_start_is_goal() {
+        let mut graph: Graph<()> = Graph::directed();
+        let a = graph.add_node(());
+        let path = shortest_path(&graph, a, a).unwrap();
+        assert_eq!(path.nodes, vec![a]);
+        assert_eq!(path.cost, 0.0);
+    }
+
+    #[test]
+    fn test_unreachable() {
+        let mut graph = Graph::directed();
+        let a = graph.add_node(());
+        let b = graph.add_node(());
+        graph.add_edge(b, a);
+        assert_eq!(shortest_path(&graph, a, b), None);
+
+        let other: Graph<()> = Graph::directed();
+        assert_eq!(shortest_path(&other, a, a), None);
+    }
+
+    #[test]
+    fn test_a_star_on_grid() {
+        // A 5x5 grid of points joined to their orthogonal neighbours, with
+        // the middle column blocked except at the top
+        let mut graph = Graph::undirected();
+        let mut ids = Vec::new();
+        for y in 0..5 {
+            for x in 0..5 {
+                ids.push(graph.add_node(Point2D::new(x as f64, y as f64)));
+            }
+        }
+        let at = |x: usize, y: usize| ids[y * 5 + x];
+        let blocked = |x: usize, y: usize| x == 2 && y < 4;
+        for y in 0..5 {
+            for x in 0..5 {
+                if blocked(x, y) {
+                    continue;
+                }
+                if x + 1 < 5 && !blocked(x + 1, y) {
+                    graph.add_edge(at(x, y), at(x + 1, y));
+                }
+                if y + 1 < 5 && !blocked(x, y + 1) {
+                    graph.add_edge(at(x, y), at(x, y + 1));
+                }
+            }
+        }
+
+        let goal = at(4, 0);
+        let goal_point = *graph.node(goal).unwrap();
+        let distance = |id: NodeId| graph.node(id).unwrap().distance_to(&goal_point);
+        let path = a_star(&graph, at(0, 0), goal, distance).unwrap();
+        // Up to the gap in row 4, across, and back down
+        assert_eq!(path.cost, 12.0);
+        assert_eq!(path.nodes.len(), 13);
+        assert!(path.nodes.contains(&at(2, 4)));
+        assert_eq!(shortest_path(&graph, at(0, 0), goal).unwrap().cost, 12.0);
+    }
+
+    #[test]
+    fn test_a_star_reopens_with_an_inconsistent_heuristic() {
+        let mut graph = Graph::directed();
+        let ids: Vec<_> = "sabg".chars().map(|c| graph.add_node(c)).collect();
+        let (s, a, b, g) = (ids[0], ids[1], ids[2], ids[3]);
+        graph.add_weighted_edge(s, a, 4.0);
+        graph.add_weighted_edge(s, b, 1.0);
+        graph.add_weighted_edge(b, a, 1.0);
+        graph.add_weighted_edge(a, g, 4.0);
+
+        // Never more than the true remaining cost, but b's estimate drops by
+        // more than the edge to a, so a is expanded by the dear route first
+        let estimate = |id: NodeId| if id == b { 4.0 } else { 0.0 };
+        let path = a_star(&graph, s, g, estimate).unwrap();
+        assert_eq!(path.nodes, vec![s, b, a, g]);
+        assert_eq!(path.cost, 6.0);
+    }
+}
@@ -0,0 +1,197 @@
+//! Topological ordering of directed graphs.
+//!
+//! A topological order lists every node before the nodes its edges lead
+//! to, like a build order where each task comes after its dependencies.
+//! Such an order exists exactly when the graph has no cycles, so a failed
+//! sort reports one of the cycles it found as ordinary data.
+
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt;
+
+use super::{Graph, NodeId};
+
+/// The graph could not be ordered because it contains a cycle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleError {
+    /// The nodes around one cycle in edge order, starting from the lowest
+    /// id. The last node has an edge back to the first.
+    pub cycle: Vec<NodeId>,
+}
+
+impl fmt::Display for CycleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "graph has a cycle: ")?;
+        for id in &self.cycle {
+            write!(f, "{} -> ", id)?;
+        }
+        match self.cycle.first() {
+            Some(first) => write!(f, "{}", first),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Error for CycleError {}
+
+/// Orders the nodes so every edge points from an earlier node to a later
+/// one, using Kahn's algorithm.
+///
+/// Among nodes that are ready at the same time, lower ids come first, so
+/// the result is deterministic. In an undirected graph every edge works
+/// both ways and forms a cycle of its own, so only edgeless undirected
+/// graphs can be sorted.
+///
+/// ```
+/// use rustler::collections::graph::{topo_sort, Graph};
+///
+/// let mut tasks = Graph::directed();
+/// let fetch = tasks.add_node("fetch");
+/// let build = tasks.add_node("build");
+/// let test = tasks.add_node("test");
+/// tasks.add_edge(build, test);
+/// tasks.add_edge(fetch, build);
+/// assert_eq!(topo_sort(&tasks).unwrap(), vec![fetch, build, test]);
+///
+/// tasks.add_edge(test, fetch);
+/// assert!(topo_sort(&tasks).is_err());
+/// ```
+pub fn topo_sort<N>(graph: &Graph<N>) -> Result<Vec<NodeId>, CycleError> {
+    let mut in_degree = vec![0usize; graph.node_count()];
+    for id in graph.node_ids() {
+        for next in graph.neighbors(id) {
+            in_degree[next.index()] += 1;
+        }
+    }
+
+    let mut ready: VecDeque<NodeId> = graph.node_ids().filter(|id| in_degree[id.index()] == 0).collect();
+    let mut order = Vec::with_capacity(graph.node_count());
+    while let Some(id) = ready.pop_front() {
+        order.push(id);
+        let mut unlocked = Vec::new();
+        for next in graph.neighbors(id) {
+            in_degree[next.index()] -= 1;
+            if in_degree[next.index()] == 0 {
+                unlocked.push(next);
+            }
+        }
+        unlocked.sort();
+        ready.extend(unlocked);
+    }
+
+    if order.len() == graph.node_count() {
+        Ok(order)
+    } else {
+        Err(CycleError {
+            cycle: find_cycle(graph, &in_degree),
+        })
+    }
+}
+
+/// Returns true if the graph contains at least one cycle.
+pub fn has_cycle<N>(graph: &Graph<N>) -> bool {
+    topo_sort(graph).is_err()
+}
+
+/// Picks out one cycle among the nodes Kahn's algorithm could not place
+/// (those left with a non-zero in-degree).
+///
+/// Every leftover node still has a leftover predecessor, so walking
+/// predecessors from any of them must eventually revisit a node; the walk
+/// from that first repeat back to itself is a cycle.
+fn find_cycle<N>(graph: &Graph<N>, in_degree: &[usize]) -> Vec<NodeId> {
+    let leftover = |id: NodeId| in_degree[id.index()] > 0;
+    let mut predecessor: Vec<Option<NodeId>> = vec![None; graph.node_count()];
+    for id in graph.node_ids().filter(|&id| leftover(id)) {
+        for next in graph.neighbors(id).filter(|&next| leftover(next)) {
+            predecessor[next.index()].get_or_insert(id);
+        }
+    }
+
+    let Some(start) = graph.node_ids().find(|&id| leftover(id)) else {
+        return Vec::new();
+    };
+    let mut seen_at = vec![None; graph.node_count()];
+    let mut walk = Vec::new();
+    let mut current = start;
+    while seen_at[current.index()].is_none() {
+        seen_at[current.index()] = Some(walk.len());
+        walk.push(current);
+        current = predecessor[current.index()].expect("leftover nodes have a leftover predecessor");
+    }
+    // The walk followed edges backwards, so reverse it into edge order
+    let mut cycle = walk.split_off(seen_at[current.index()].unwrap_or(0));
+    cycle.reverse();
+    let lowest = cycle.iter().enumerate().min_by_key(|(_, id)| **id).map_or(0, |(i, _)| i);
+    cycle.rotate_left(lowest);
+    cycle
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn directed(count: usize, edges: &[(usize, usize)]) -> (Graph<usize>, Vec<NodeId>) {
+        let mut graph = Graph::directed();
+        let ids: Vec<_> = (0..count).map(|i| graph.add_node(i)).collect();
+        for &(a, b) in edges {
+            graph.add_edge(ids[a], ids[b]);
+        }
+        (graph, ids)
+    }
+
+    fn indices(ids: &[NodeId]) -> Vec<usize> {
+        ids.iter().map(|id| id.index()).collect()
+    }
+
+    #[test]
+    fn test_orders_dependencies_first() {
+        // Getting dressed: underwear before trousers before shoes, socks
+        // before shoes, shirt before tie
+        let (graph, _) = directed(6, &[(0, 1), (1, 3), (2, 3), (4, 5)]);
+        let order = indices(&topo_sort(&graph).unwrap());
+        assert_eq!(order, vec![0, 2, 4, 1, 5, 3]);
+        assert!(!has_cycle(&graph));
+    }
+
+    #[test]
+    fn test_every_edge_points_forward() {
+        let edges = [(5, 2), (5, 0), (4, 0), (4, 1), (2, 3), (3, 1)];
+        let (graph, _) = directed(6, &edges);
+        let order = indices(&topo_sort(&graph).unwrap());
+        let position = |node: usize| order.iter().position(|&n| n == node).unwrap();
+        for (a, b) in edges {
+            assert!(position(a) < position(b), "{} should come before {}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_reports_cycle() {
+        // 0 -> 1 -> 2 -> 3 -> 1, and 3 -> 4 hangs off the cycle
+        let (graph, ids) = directed(5, &[(0, 1), (1, 2), (2, 3), (3, 1), (3, 4)]);
+        let err = topo_sort(&graph).unwrap_err();
+        assert_eq!(indices(&err.cycle), vec![1, 2, 3]);
+        assert_eq!(err.to_string(), "graph has a cycle: #1 -> #2 -> #3 -> #1");
+        assert!(has_cycle(&graph));
+        assert_eq!(graph.node(ids[0]), Some(&0));
+    }
+
+    #[test]
+    fn test_self_loop_is_a_cycle() {
+        let (graph, _) = directed(2, &[(0, 1), (1, 1)]);
+        assert_eq!(indices(&topo_sort(&graph).unwrap_err().cycle), vec![1]);
+    }
+
+    #[test]
+    fn test_empty_and_undirected() {
+        let (empty, _) = directed(0, &[]);
+        assert_eq!(topo_sort(&empty), Ok(Vec::new()));
+
+        let mut undirected = Graph::undirected();
+        let a = undirected.add_node(());
+        let b = undirected.add_node(());
+        assert_eq!(topo_sort(&undirected), Ok(vec![a, b]));
+        undirected.add_edge(a, b);
+        assert_eq!(topo_sort(&undirected).unwrap_err().cycle.len(), 2);
+    }
+}
@@ -0,0 +1,247 @@
+//! A dense two-dimensional container addressed by `(row, col)`.
+//!
+//! Cells are stored row-major in one `Vec`: the cell at `(row, col)` lives
+//! at index `row * cols + col`. Walking a row is a contiguous slice;
+//! walking a column steps through the vector `cols` items at a time.
+//!
+//! [`shapes::Grid`](crate::shapes::Grid) covers the tilemap side (`(x, y)`
+//! coordinates and neighbours); `Grid2D` is the matrix-style building block
+//! for images, cellular automata and mazes.
+
+use std::ops::{Index, IndexMut};
+
+/// A `rows` x `cols` matrix of `T`.
+///
+/// ```
+/// use rustler::collections::Grid2D;
+///
+/// let mut grid = Grid2D::from_fn(2, 3, |row, col| row * 3 + col);
+/// assert_eq!(grid[(1, 2)], 5);
+/// grid[(0, 0)] = 9;
+///
+/// let rows: Vec<&[usize]> = grid.rows().collect();
+/// assert_eq!(rows, vec![&[9, 1, 2][..], &[3, 4, 5][..]]);
+///
+/// let transposed = grid.transpose();
+/// assert_eq!((transposed.row_count(), transposed.col_count()), (3, 2));
+/// assert_eq!(transposed[(2, 1)], 5);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid2D<T> {
+    rows: usize,
+    cols: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid2D<T> {
+    /// A grid with every cell set to `fill`.
+    pub fn new(rows: usize, cols: usize, fill: T) -> Self {
+        Grid2D {
+            rows,
+            cols,
+            cells: vec![fill; rows * cols],
+        }
+    }
+
+    /// Sets every cell to `value`.
+    pub fn fill(&mut self, value: T) {
+        self.cells.fill(value);
+    }
+
+    /// A new grid with rows and columns swapped, so `(r, c)` moves to
+    /// `(c, r)`.
+    pub fn transpose(&self) -> Grid2D<T> {
+        Grid2D::from_fn(self.cols, self.rows, |row, col| self[(col, row)].clone())
+    }
+}
+
+impl<T> Grid2D<T> {
+    /// A grid whose cell at `(row, col)` is `f(row, col)`.
+    pub fn from_fn(rows: usize, cols: usize, mut f: impl FnMut(usize, usize) -> T) -> Self {
+        let cells = (0..rows * cols).map(|i| f(i / cols, i % cols)).collect();
+        Grid2D { rows, cols, cells }
+    }
+
+    /// Builds a grid from rows, or `None` if the rows differ in length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Self> {
+        let row_count = rows.len();
+        let cols = rows.first().map_or(0, Vec::len);
+        if rows.iter().any(|row| row.len() != cols) {
+            return None;
+        }
+        Some(Grid2D {
+            rows: row_count,
+            cols,
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.rows
+    }
+
+    pub fn col_count(&self) -> usize {
+        self.cols
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn in_bounds(&self, row: usize, col: usize) -> bool {
+        row < self.rows && col < self.cols
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        self.in_bounds(row, col).then(|| &self.cells[row * self.cols + col])
+    }
+
+    pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+        if !self.in_bounds(row, col) {
+            return None;
+        }
+        Some(&mut self.cells[row * self.cols + col])
+    }
+
+    /// One row as a slice.
+    pub fn row(&self, row: usize) -> Option<&[T]> {
+        (row < self.rows).then(|| &self.cells[row * self.cols..(row + 1) * self.cols])
+    }
+
+    /// One column, top to bottom.
+    pub fn col(&self, col: usize) -> Option<impl Iterator<Item = &T> + '_> {
+        (col < self.cols).then(|| self.cells.iter().skip(col).step_by(self.cols))
+    }
+
+    /// Iterates over the rows, top to bottom, each as a slice.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> + '_ {
+        (0..self.rows).map(move |row| &self.cells[row * self.cols..(row + 1) * self.cols])
+    }
+
+    /// Iterates over the columns, left to right, each as an iterator
+    /// running top to bottom.
+    pub fn cols(&self) -> impl Iterator<Item = impl Iterator<Item = &T> + '_> + '_ {
+        (0..self.cols).map(move |col| self.cells.iter().skip(col).step_by(self.cols))
+    }
+
+    /// Iterates over every cell as `((row, col), value)`, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> + '_ {
+        let cols = self.cols.max(1);
+        self.cells.iter().enumerate().map(move |(i, cell)| ((i / cols, i % cols), cell))
+    }
+
+    /// Iterates mutably over every cell, row by row.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> + '_ {
+        self.cells.iter_mut()
+    }
+
+    /// A grid of the same shape with `f` applied to every cell.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid2D<U> {
+        Grid2D {
+            rows: self.rows,
+            cols: self.cols,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+/// Indexing by `(row, col)`.
+///
+/// # Panics
+///
+/// Panics if the position is outside the grid. Use [`Grid2D::get`] for a
+/// checked lookup.
+impl<T> Index<(usize, usize)> for Grid2D<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        match self.get(row, col) {
+            Some(cell) => cell,
+            None => panic!("index ({}, {}) out of bounds for a {}x{} grid", row, col, self.rows, self.cols),
+        }
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid2D<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        let (rows, cols) = (self.rows, self.cols);
+        match self.get_mut(row, col) {
+            Some(cell) => cell,
+            None => panic!("index ({}, {}) out of bounds for a {}x{} grid", row, col, rows, cols),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Grid2D<i32> {
+        Grid2D::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6]]).unwrap()
+    }
+
+    #[test]
+    fn test_dimensions_and_access() {
+        let mut grid = Grid2D::new(2, 3, 0);
+        assert_eq!((grid.row_count(), grid.col_count()), (2, 3));
+        assert!(grid.in_bounds(1, 2));
+        assert!(!grid.in_bounds(2, 0));
+        grid[(1, 2)] = 7;
+        assert_eq!(grid.get(1, 2), Some(&7));
+        assert_eq!(grid.get(0, 3), None);
+        *grid.get_mut(0, 0).unwrap() = 1;
+        assert_eq!(grid.row(0), Some(&[1, 0, 0][..]));
+        assert_eq!(grid.row(2), None);
+        grid.fill(4);
+        assert!(grid.iter().all(|(_, &cell)| cell == 4));
+    }
+
+    #[test]
+    fn test_from_rows_rejects_ragged_input() {
+        assert!(Grid2D::from_rows(vec![vec![1, 2], vec![3]]).is_none());
+        let empty = Grid2D::<i32>::from_rows(Vec::new()).unwrap();
+        assert!(empty.is_empty());
+        assert_eq!(empty.rows().count(), 0);
+    }
+
+    #[test]
+    fn test_rows_and_cols() {
+        let grid = sample();
+        let rows: Vec<Vec<i32>> = grid.rows().map(<[i32]>::to_vec).collect();
+        assert_eq!(rows, vec![vec![1, 2, 3], vec![4, 5, 6]]);
+        let cols: Vec<Vec<i32>> = grid.cols().map(|col| col.copied().collect()).collect();
+        assert_eq!(cols, vec![vec![1, 4], vec![2, 5], vec![3, 6]]);
+        assert_eq!(grid.col(1).unwrap().copied().collect::<Vec<_>>(), vec![2, 5]);
+        assert!(grid.col(3).is_none());
+    }
+
+    #[test]
+    fn test_iter_positions() {
+        let grid = sample();
+        let cells: Vec<_> = grid.iter().collect();
+        assert_eq!(cells[0], ((0, 0), &1));
+        assert_eq!(cells[4], ((1, 1), &5));
+        assert_eq!(cells.len(), 6);
+    }
+
+    #[test]
+    fn test_map_and_transpose() {
+        let mut grid = sample();
+        for cell in grid.iter_mut() {
+            *cell *= 10;
+        }
+        let labels = grid.map(|n| n.to_string());
+        assert_eq!(labels[(1, 0)], "40");
+        let transposed = grid.transpose();
+        assert_eq!((transposed.row_count(), transposed.col_count()), (3, 2));
+        assert_eq!(transposed.row(0), Some(&[10, 40][..]));
+        assert_eq!(transposed.transpose(), grid);
+    }
+
+    #[test]
+    #[should_panic(expected = "index (2, 0) out of bounds for a 2x3 grid")]
+    fn test_index_out_of_bounds_panics() {
+        let grid = sample();
+        let _ = grid[(2, 0)];
+    }
+}
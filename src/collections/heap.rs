@@ -0,0 +1,309 @@
+//! Binary heaps stored in a `Vec`.
+//!
+//! The heap is a complete binary tree laid out level by level: the children
+//! of the item at index `i` live at `2i + 1` and `2i + 2`, and its parent
+//! at `(i - 1) / 2`. Every parent is at least as large as its children, so
+//! the largest item is always at index 0.
+//!
+//! Pushing appends at the end and *sifts up*, swapping with the parent
+//! while the new item is larger. Popping moves the last item into the root
+//! and *sifts down*, swapping with the larger child until order is
+//! restored. Both touch one path from root to leaf, so they are O(log n).
+
+use std::cmp::Ordering;
+use std::fmt;
+
+/// A max-heap: `pop` returns the largest item first.
+///
+/// Wrap items in `std::cmp::Reverse` for a min-heap, or use
+/// [`BinaryHeapByKey`] to order by a computed key.
+///
+/// ```
+/// use rustler::collections::BinaryHeap;
+///
+/// let mut heap = BinaryHeap::from_vec(vec![3, 1, 4, 1, 5]);
+/// assert_eq!(heap.peek(), Some(&5));
+/// heap.push(9);
+/// assert_eq!(heap.pop(), Some(9));
+/// assert_eq!(heap.into_sorted_vec(), vec![1, 1, 3, 4, 5]);
+/// ```
+#[derive(Clone)]
+pub struct BinaryHeap<T> {
+    items: Vec<T>,
+}
+
+impl<T: Ord> BinaryHeap<T> {
+    pub fn new() -> Self {
+        BinaryHeap { items: Vec::new() }
+    }
+
+    /// Builds a heap from a vector in O(n) by sifting down every parent,
+    /// starting from the last one. This beats n separate pushes, which
+    /// would be O(n log n).
+    pub fn from_vec(items: Vec<T>) -> Self {
+        let mut heap = BinaryHeap { items };
+        for i in (0..heap.items.len() / 2).rev() {
+            heap.sift_down(i);
+        }
+        heap
+    }
+
+    pub fn push(&mut self, item: T) {
+        self.items.push(item);
+        self.sift_up(self.items.len() - 1);
+    }
+
+    /// Removes and returns the largest item.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.items.is_empty() {
+            return None;
+        }
+        let item = self.items.swap_remove(0);
+        self.sift_down(0);
+        Some(item)
+    }
+
+    /// The largest item, without removing it.
+    pub fn peek(&self) -> Option<&T> {
+        self.items.first()
+    }
+
+    /// Consumes the heap, returning the items in ascending order.
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        let mut sorted = Vec::with_capacity(self.items.len());
+        while let Some(item) = self.pop() {
+            sorted.push(item);
+        }
+        sorted.reverse();
+        sorted
+    }
+
+    fn sift_up(&mut self, index: usize) {
+        sift_up_by(&mut self.items, index, |a, b| a.cmp(b));
+    }
+
+    fn sift_down(&mut self, index: usize) {
+        sift_down_by(&mut self.items, index, |a, b| a.cmp(b));
+    }
+}
+
+impl<T> BinaryHeap<T> {
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+
+    /// Iterates over the items in heap order, which is not sorted.
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        self.items.iter()
+    }
+
+    /// Consumes the heap, returning the items in heap order.
+    pub fn into_vec(self) -> Vec<T> {
+        self.items
+    }
+}
+
+impl<T: Ord> Default for BinaryHeap<T> {
+    fn default() -> Self {
+        BinaryHeap::new()
+    }
+}
+
+impl<T: Ord> FromIterator<T> for BinaryHeap<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        BinaryHeap::from_vec(iter.into_iter().collect())
+    }
+}
+
+impl<T: Ord> Extend<T> for BinaryHeap<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for BinaryHeap<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.items.iter()).finish()
+    }
+}
+
+/// A heap that pops the item with the *smallest* key first, where the key
+/// is computed by a closure.
+///
+/// Useful for priority queues of items that are not `Ord` themselves, such
+/// as tasks ordered by a deadline field.
+///
+/// ```
+/// use rustler::collections::BinaryHeapByKey;
+///
+/// let mut tasks = BinaryHeapByKey::new(|task: &(&str, u32)| task.1);
+/// tasks.push(("write report", 3));
+/// tasks.push(("fix outage", 1));
+/// tasks.push(("lunch", 2));
+/// assert_eq!(tasks.pop(), Some(("fix outage", 1)));
+/// assert_eq!(tasks.pop(), Some(("lunch", 2)));
+/// ```
+pub struct BinaryHeapByKey<T, K, F>
+where
+    F: Fn(&T) -> K,
+{
+    items: Vec<T>,
+    key: F,
+}
+
+impl<T, K, F> BinaryHeapByKey<T, K, F>
+where
+    K: Ord,
+    F: Fn(&T) -> K,
+{
+    pub fn new(key: F) -> Self {
+        BinaryHeapByKey { items: Vec::new(), key }
+    }
+
+    /// Builds a heap from a vector in O(n).
+    pub fn from_vec(items: Vec<T>, key: F) -> Self {
+        let mut heap = BinaryHeapByKey { items, key };
+        for i in (0..heap.items.len() / 2).rev() {
+            heap.sift_down(i);
+        }
+        heap
+    }
+
+    pub fn push(&mut self, item: T) {
+        self.items.push(item);
+        let last = self.items.len() - 1;
+        let key = &self.key;
+        sift_up_by(&mut self.items, last, |a, b| key(b).cmp(&key(a)));
+    }
+
+    /// Removes and returns the item with the smallest key.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.items.is_empty() {
+            return None;
+        }
+        let item = self.items.swap_remove(0);
+        self.sift_down(0);
+        Some(item)
+    }
+
+    /// The item with the smallest key, without removing it.
+    pub fn peek(&self) -> Option<&T> {
+        self.items.first()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    fn sift_down(&mut self, index: usize) {
+        let key = &self.key;
+        sift_down_by(&mut self.items, index, |a, b| key(b).cmp(&key(a)));
+    }
+}
+
+/// Moves `items[index]` up while it compares greater than its parent.
+fn sift_up_by<T>(items: &mut [T], mut index: usize, cmp: impl Fn(&T, &T) -> Ordering) {
+    while index > 0 {
+        let parent = (index - 1) / 2;
+        if cmp(&items[index], &items[parent]) != Ordering::Greater {
+            break;
+        }
+        items.swap(index, parent);
+        index = parent;
+    }
+}
+
+/// Moves `items[index]` down while either child compares greater than it,
+/// always swapping with the greater child.
+fn sift_down_by<T>(items: &mut [T], mut index: usize, cmp: impl Fn(&T, &T) -> Ordering) {
+    loop {
+        let left = 2 * index + 1;
+        let right = left + 1;
+        let mut largest = index;
+        if left < items.len() && cmp(&items[left], &items[largest]) == Ordering::Greater {
+            largest = left;
+        }
+        if right < items.len() && cmp(&items[right], &items[largest]) == Ordering::Greater {
+            largest = right;
+        }
+        if largest == index {
+            return;
+        }
+        items.swap(index, largest);
+        index = largest;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cmp::Reverse;
+
+    /// Checks that every parent is at least as large as its children.
+    fn is_heap<T: Ord>(items: &[T]) -> bool {
+        (1..items.len()).all(|i| items[(i - 1) / 2] >= items[i])
+    }
+
+    #[test]
+    fn test_push_pop_order() {
+        let mut heap = BinaryHeap::new();
+        assert_eq!(heap.pop(), None);
+        for value in [5, 3, 8, 1, 9, 2, 8] {
+            heap.push(value);
+            assert!(is_heap(&heap.items));
+        }
+        assert_eq!(heap.len(), 7);
+        assert_eq!(heap.peek(), Some(&9));
+        let mut popped = Vec::new();
+        while let Some(value) = heap.pop() {
+            assert!(is_heap(&heap.items));
+            popped.push(value);
+        }
+        assert_eq!(popped, vec![9, 8, 8, 5, 3, 2, 1]);
+    }
+
+    #[test]
+    fn test_heapify() {
+        let values: Vec<i32> = (0..100).map(|i| (i * 37) % 101).collect();
+        let heap = BinaryHeap::from_vec(values.clone());
+        assert!(is_heap(&heap.items));
+        let mut expected = values;
+        expected.sort();
+        assert_eq!(heap.into_sorted_vec(), expected);
+        assert!(BinaryHeap::<i32>::from_vec(Vec::new()).is_empty());
+    }
+
+    #[test]
+    fn test_min_heap_with_reverse() {
+        let mut heap: BinaryHeap<_> = [4, 1, 3].into_iter().map(Reverse).collect();
+        heap.extend([Reverse(0)]);
+        assert_eq!(heap.pop(), Some(Reverse(0)));
+        assert_eq!(heap.pop(), Some(Reverse(1)));
+    }
+
+    #[test]
+    fn test_by_key() {
+        let mut heap = BinaryHeapByKey::from_vec(vec!["ccc", "a", "bb", "dddd"], |s: &&str| s.len());
+        assert_eq!(heap.peek(), Some(&"a"));
+        heap.push("");
+        assert_eq!(heap.len(), 5);
+        let order: Vec<_> = std::iter::from_fn(|| heap.pop()).collect();
+        assert_eq!(order, vec!["", "a", "bb", "ccc", "dddd"]);
+        assert!(heap.is_empty());
+    }
+
+}
@@ -0,0 +1,251 @@
+//! A singly linked list built from boxed nodes.
+//!
+//! Each node owns the next one through `Option<Box<Node<T>>>`, so the list
+//! is a chain of unique ownership: dropping the head would drop everything
+//! after it. Most operations are written as loops that move `Option`s
+//! around with `take()` rather than with recursion.
+
+use std::fmt;
+
+type Link<T> = Option<Box<Node<T>>>;
+
+struct Node<T> {
+    value: T,
+    next: Link<T>,
+}
+
+/// A singly linked list with O(1) push and pop at the head.
+///
+/// ```
+/// use rustler::collections::LinkedList;
+///
+/// let mut list = LinkedList::new();
+/// list.push_front(3);
+/// list.push_front(2);
+/// list.push_front(1);
+/// assert_eq!(list.iter().collect::<Vec<_>>(), vec![&1, &2, &3]);
+///
+/// list.reverse();
+/// assert_eq!(list.pop_front(), Some(3));
+/// ```
+pub struct LinkedList<T> {
+    head: Link<T>,
+    len: usize,
+}
+
+impl<T> LinkedList<T> {
+    pub fn new() -> Self {
+        LinkedList { head: None, len: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    pub fn push_front(&mut self, value: T) {
+        let next = self.head.take();
+        self.head = Some(Box::new(Node { value, next }));
+        self.len += 1;
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.head.take().map(|node| {
+            self.head = node.next;
+            self.len -= 1;
+            node.value
+        })
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.head.as_ref().map(|node| &node.value)
+    }
+
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.head.as_mut().map(|node| &mut node.value)
+    }
+
+    /// Reverses the list in place by relinking each node to point at the
+    /// one before it.
+    pub fn reverse(&mut self) {
+        let mut previous: Link<T> = None;
+        let mut current = self.head.take();
+        while let Some(mut node) = current {
+            current = node.next.take();
+            node.next = previous;
+            previous = Some(node);
+        }
+        self.head = previous;
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            next: self.head.as_deref(),
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            next: self.head.as_deref_mut(),
+        }
+    }
+}
+
+impl<T> Default for LinkedList<T> {
+    fn default() -> Self {
+        LinkedList::new()
+    }
+}
+
+/// Drops the nodes one at a time. The default drop would recurse once per
+/// node and can overflow the stack on a long list.
+impl<T> Drop for LinkedList<T> {
+    fn drop(&mut self) {
+        let mut current = self.head.take();
+        while let Some(mut node) = current {
+            current = node.next.take();
+        }
+    }
+}
+
+/// Builds a list in iteration order, so the first item becomes the head.
+impl<T> FromIterator<T> for LinkedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = LinkedList::new();
+        for value in iter {
+            list.push_front(value);
+        }
+        list.reverse();
+        list
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for LinkedList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for LinkedList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+/// Borrowing iterator returned by [`LinkedList::iter`].
+pub struct Iter<'a, T> {
+    next: Option<&'a Node<T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next.map(|node| {
+            self.next = node.next.as_deref();
+            &node.value
+        })
+    }
+}
+
+/// Mutable iterator returned by [`LinkedList::iter_mut`].
+pub struct IterMut<'a, T> {
+    next: Option<&'a mut Node<T>>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next.take().map(|node| {
+            self.next = node.next.as_deref_mut();
+            &mut node.value
+        })
+    }
+}
+
+/// Owning iterator that pops from the front.
+pub struct IntoIter<T>(LinkedList<T>);
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.0.pop_front()
+    }
+}
+
+impl<T> IntoIterator for LinkedList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter(self)
+    }
+}
+
+impl<'a, T> IntoIterator for &'a LinkedList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_and_pop() {
+        let mut list = LinkedList::new();
+        assert!(list.is_empty());
+        assert_eq!(list.pop_front(), None);
+        list.push_front(1);
+        list.push_front(2);
+        assert_eq!(list.len(), 2);
+        assert_eq!(list.front(), Some(&2));
+        assert_eq!(list.pop_front(), Some(2));
+        assert_eq!(list.pop_front(), Some(1));
+        assert_eq!(list.pop_front(), None);
+        assert_eq!(list.len(), 0);
+    }
+
+    #[test]
+    fn test_iterators() {
+        let mut list: LinkedList<i32> = (1..=4).collect();
+        assert_eq!(format!("{:?}", list), "[1, 2, 3, 4]");
+        for value in list.iter_mut() {
+            *value *= 10;
+        }
+        if let Some(front) = list.front_mut() {
+            *front += 1;
+        }
+        let values: Vec<_> = (&list).into_iter().copied().collect();
+        assert_eq!(values, vec![11, 20, 30, 40]);
+        assert_eq!(list.into_iter().sum::<i32>(), 101);
+    }
+
+    #[test]
+    fn test_reverse() {
+        let mut list: LinkedList<_> = "abc".chars().collect();
+        list.reverse();
+        assert_eq!(list.iter().collect::<String>(), "cba");
+        assert_eq!(list.len(), 3);
+
+        let mut empty: LinkedList<i32> = LinkedList::new();
+        empty.reverse();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_long_list_drops_without_overflow() {
+        let list: LinkedList<u32> = (0..200_000).collect();
+        assert_eq!(list.len(), 200_000);
+        drop(list);
+    }
+}
@@ -0,0 +1,33 @@
+//! Data structures built from scratch.
+//!
+//! The standard library already has fast versions of most of these; the
+//! point here is to show how they work and how ownership shapes their
+//! design.
+
+pub mod bitset;
+pub mod bst;
+pub mod counter;
+pub mod deque;
+pub mod doubly_linked_list;
+pub mod graph;
+pub mod grid2d;
+pub mod heap;
+pub mod linked_list;
+pub mod persistent_list;
+pub mod queue;
+pub mod stack;
+pub mod trie;
+
+pub use bitset::BitSet;
+pub use bst::{BstMap, BstSet};
+pub use counter::Counter;
+pub use deque::Deque;
+pub use doubly_linked_list::DoublyLinkedList;
+pub use graph::{Graph, NodeId};
+pub use grid2d::Grid2D;
+pub use heap::{BinaryHeap, BinaryHeapByKey};
+pub use linked_list::LinkedList;
+pub use persistent_list::PersistentList;
+pub use queue::Queue;
+pub use stack::Stack;
+pub use trie::Trie;
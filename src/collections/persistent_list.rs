@@ -0,0 +1,232 @@
+//! An immutable singly linked list with structural sharing.
+//!
+//! Nodes are reference counted and never modified after they are built.
+//! `cons` makes a new node pointing at the existing list instead of copying
+//! it, and `tail` hands back the list after the head, so many lists can
+//! share one suffix in memory. Cloning a list just bumps a reference count.
+//!
+//! This is the list found in functional languages such as Haskell or
+//! Clojure: every "modification" returns a new list and the old one stays
+//! valid.
+
+use std::fmt;
+use std::rc::Rc;
+
+type Link<T> = Option<Rc<Node<T>>>;
+
+struct Node<T> {
+    value: T,
+    next: Link<T>,
+}
+
+/// A persistent (immutable) list with O(1) `cons`, `head`, `tail` and
+/// `clone`.
+///
+/// ```
+/// use rustler::collections::PersistentList;
+///
+/// let empty = PersistentList::new();
+/// let one = empty.cons(1);
+/// let two = one.cons(2);
+///
+/// // `one` is unchanged, and `two` shares its node
+/// assert_eq!(one.iter().collect::<Vec<_>>(), vec![&1]);
+/// assert_eq!(two.iter().collect::<Vec<_>>(), vec![&2, &1]);
+/// assert_eq!(two.tail(), one);
+/// ```
+pub struct PersistentList<T> {
+    head: Link<T>,
+}
+
+impl<T> PersistentList<T> {
+    pub fn new() -> Self {
+        PersistentList { head: None }
+    }
+
+    /// A new list with `value` in front of this one. The existing nodes
+    /// are shared, not copied.
+    pub fn cons(&self, value: T) -> Self {
+        PersistentList {
+            head: Some(Rc::new(Node {
+                value,
+                next: self.head.clone(),
+            })),
+        }
+    }
+
+    /// The first item, if any.
+    pub fn head(&self) -> Option<&T> {
+        self.head.as_ref().map(|node| &node.value)
+    }
+
+    /// The list without its first item; empty if this list is empty.
+    pub fn tail(&self) -> Self {
+        PersistentList {
+            head: self.head.as_ref().and_then(|node| node.next.clone()),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    /// The number of items. O(n), since nodes do not store lengths.
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            next: self.head.as_deref(),
+        }
+    }
+
+    /// True if both lists start at the very same node, meaning they are
+    /// the same list in memory rather than merely equal.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        match (&self.head, &other.head) {
+            (Some(a), Some(b)) => Rc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<T: Clone> PersistentList<T> {
+    /// A new list with the items in reverse order. Nothing can be shared
+    /// here, so every item is cloned.
+    pub fn reversed(&self) -> Self {
+        self.iter().fold(PersistentList::new(), |list, value| list.cons(value.clone()))
+    }
+}
+
+/// Cloning copies one pointer, not the items.
+impl<T> Clone for PersistentList<T> {
+    fn clone(&self) -> Self {
+        PersistentList {
+            head: self.head.clone(),
+        }
+    }
+}
+
+impl<T> Default for PersistentList<T> {
+    fn default() -> Self {
+        PersistentList::new()
+    }
+}
+
+/// Drops nodes in a loop instead of recursively, so long lists cannot
+/// overflow the stack. A node still shared with another list stops the
+/// loop, since that list keeps it alive.
+impl<T> Drop for PersistentList<T> {
+    fn drop(&mut self) {
+        let mut link = self.head.take();
+        while let Some(node) = link {
+            match Rc::try_unwrap(node) {
+                Ok(mut node) => link = node.next.take(),
+                Err(_) => break,
+            }
+        }
+    }
+}
+
+/// Builds a list holding the items in iteration order.
+impl<T> FromIterator<T> for PersistentList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let items: Vec<T> = iter.into_iter().collect();
+        items.into_iter().rev().fold(PersistentList::new(), |list, value| list.cons(value))
+    }
+}
+
+impl<T: PartialEq> PartialEq for PersistentList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr_eq(other) || self.iter().eq(other.iter())
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for PersistentList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+pub struct Iter<'a, T> {
+    next: Option<&'a Node<T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.next.map(|node| {
+            self.next = node.next.as_deref();
+            &node.value
+        })
+    }
+}
+
+impl<'a, T> IntoIterator for &'a PersistentList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cons_head_tail() {
+        let list = PersistentList::new().cons(3).cons(2).cons(1);
+        assert_eq!(list.head(), Some(&1));
+        assert_eq!(list.tail().head(), Some(&2));
+        assert_eq!(list.len(), 3);
+        let empty = list.tail().tail().tail();
+        assert!(empty.is_empty());
+        assert_eq!(empty.head(), None);
+        assert!(empty.tail().is_empty());
+    }
+
+    #[test]
+    fn test_versions_share_structure() {
+        let base: PersistentList<i32> = [2, 3].into_iter().collect();
+        let a = base.cons(1);
+        let b = base.cons(10);
+        assert!(a.tail().ptr_eq(&base));
+        assert!(b.tail().ptr_eq(&base));
+        assert_eq!(a.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(b.iter().copied().collect::<Vec<_>>(), vec![10, 2, 3]);
+        // Dropping one version leaves the shared suffix intact
+        drop(a);
+        assert_eq!(base.iter().copied().collect::<Vec<_>>(), vec![2, 3]);
+    }
+
+    #[test]
+    fn test_clone_is_shallow() {
+        let list: PersistentList<String> = ["a", "b"].iter().map(|s| s.to_string()).collect();
+        let copy = list.clone();
+        assert!(copy.ptr_eq(&list));
+        assert_eq!(copy, list);
+    }
+
+    #[test]
+    fn test_equality_and_reversed() {
+        let list: PersistentList<i32> = (1..=4).collect();
+        let other: PersistentList<i32> = (1..=4).collect();
+        assert!(!list.ptr_eq(&other));
+        assert_eq!(list, other);
+        assert_eq!(format!("{:?}", list.reversed()), "[4, 3, 2, 1]");
+        assert_ne!(list, list.tail());
+    }
+
+    #[test]
+    fn test_long_list_drops_without_overflow() {
+        let list: PersistentList<u32> = (0..200_000).collect();
+        let shared = list.tail();
+        drop(list);
+        assert_eq!(shared.len(), 199_999);
+    }
+}
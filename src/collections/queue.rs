@@ -0,0 +1,161 @@
+//! A first-in, first-out queue.
+
+use std::collections::VecDeque;
+use std::fmt;
+
+/// A FIFO queue: items leave in the order they arrived.
+///
+/// Backed by `VecDeque`, so both ends are O(1).
+///
+/// ```
+/// use rustler::collections::Queue;
+///
+/// let mut jobs = Queue::new();
+/// jobs.enqueue("compile");
+/// jobs.enqueue("test");
+/// assert_eq!(jobs.front(), Some(&"compile"));
+/// assert_eq!(jobs.dequeue(), Some("compile"));
+/// assert_eq!(jobs.len(), 1);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Queue<T> {
+    items: VecDeque<T>,
+}
+
+impl<T> Queue<T> {
+    pub fn new() -> Self {
+        Queue { items: VecDeque::new() }
+    }
+
+    /// An empty queue with room for `capacity` items before reallocating.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Queue {
+            items: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Adds an item to the back of the queue.
+    pub fn enqueue(&mut self, item: T) {
+        self.items.push_back(item);
+    }
+
+    /// Removes and returns the item at the front, or `None` if the queue
+    /// is empty.
+    pub fn dequeue(&mut self) -> Option<T> {
+        self.items.pop_front()
+    }
+
+    /// The next item to be dequeued, without removing it.
+    pub fn front(&self) -> Option<&T> {
+        self.items.front()
+    }
+
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.items.front_mut()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+
+    /// Iterates from the front of the queue to the back.
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        self.items.iter()
+    }
+
+    /// Dequeues every item in order, leaving the queue empty.
+    pub fn drain(&mut self) -> impl Iterator<Item = T> + '_ {
+        self.items.drain(..)
+    }
+}
+
+impl<T> Default for Queue<T> {
+    fn default() -> Self {
+        Queue::new()
+    }
+}
+
+/// Enqueues each item in turn.
+impl<T> Extend<T> for Queue<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.items.extend(iter);
+    }
+}
+
+impl<T> FromIterator<T> for Queue<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Queue {
+            items: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// Consumes the queue, yielding items front first.
+impl<T> IntoIterator for Queue<T> {
+    type Item = T;
+    type IntoIter = std::collections::vec_deque::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.into_iter()
+    }
+}
+
+/// Lists the items front first, e.g. `[1, 2, 3]` after enqueuing 1, 2, 3.
+impl<T: fmt::Display> fmt::Display for Queue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[")?;
+        for (i, item) in self.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", item)?;
+        }
+        write!(f, "]")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fifo_order() {
+        let mut queue = Queue::new();
+        assert_eq!(queue.dequeue(), None);
+        queue.enqueue(1);
+        queue.enqueue(2);
+        queue.enqueue(3);
+        assert_eq!(queue.front(), Some(&1));
+        assert_eq!(queue.dequeue(), Some(1));
+        queue.enqueue(4);
+        assert_eq!(queue.iter().copied().collect::<Vec<_>>(), vec![2, 3, 4]);
+        assert_eq!(queue.len(), 3);
+    }
+
+    #[test]
+    fn test_front_mut() {
+        let mut queue: Queue<String> = Queue::new();
+        queue.enqueue("job".to_string());
+        queue.front_mut().unwrap().push_str("-1");
+        assert_eq!(queue.dequeue().as_deref(), Some("job-1"));
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_collect_and_drain() {
+        let mut queue: Queue<_> = "abc".chars().collect();
+        queue.extend(['d']);
+        assert_eq!(queue.to_string(), "[a, b, c, d]");
+        assert_eq!(queue.drain().collect::<String>(), "abcd");
+        assert!(queue.is_empty());
+        assert_eq!(Queue::from_iter([1, 2]).into_iter().collect::<Vec<_>>(), vec![1, 2]);
+    }
+}
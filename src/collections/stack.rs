@@ -0,0 +1,188 @@
+//! A last-in, first-out stack.
+
+use std::fmt;
+
+/// A LIFO stack backed by a `Vec`, with the top of the stack at the end.
+///
+/// ```
+/// use rustler::collections::Stack;
+///
+/// let mut stack = Stack::new();
+/// stack.push(1);
+/// stack.push(2);
+/// assert_eq!(stack.peek(), Some(&2));
+/// assert_eq!(stack.pop(), Some(2));
+/// assert_eq!(stack.len(), 1);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stack<T> {
+    items: Vec<T>,
+}
+
+impl<T> Stack<T> {
+    pub fn new() -> Self {
+        Stack { items: Vec::new() }
+    }
+
+    /// An empty stack with room for `capacity` items before reallocating.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Stack {
+            items: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.items.capacity()
+    }
+
+    pub fn push(&mut self, item: T) {
+        self.items.push(item);
+    }
+
+    /// Removes and returns the top item, or `None` if the stack is empty.
+    pub fn pop(&mut self) -> Option<T> {
+        self.items.pop()
+    }
+
+    /// The top item, without removing it.
+    pub fn peek(&self) -> Option<&T> {
+        self.items.last()
+    }
+
+    /// A mutable reference to the top item.
+    ///
+    /// ```
+    /// use rustler::collections::Stack;
+    ///
+    /// let mut stack: Stack<i32> = [1, 2].into_iter().collect();
+    /// if let Some(top) = stack.peek_mut() {
+    ///     *top *= 10;
+    /// }
+    /// assert_eq!(stack.pop(), Some(20));
+    /// ```
+    pub fn peek_mut(&mut self) -> Option<&mut T> {
+        self.items.last_mut()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+
+    /// Iterates from the top of the stack to the bottom, in the order
+    /// items would be popped.
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        self.items.iter().rev()
+    }
+
+    /// Pops every item, top first, leaving the stack empty.
+    ///
+    /// ```
+    /// use rustler::collections::Stack;
+    ///
+    /// let mut stack: Stack<_> = "abc".chars().collect();
+    /// let popped: String = stack.drain().collect();
+    /// assert_eq!(popped, "cba");
+    /// assert!(stack.is_empty());
+    /// ```
+    pub fn drain(&mut self) -> impl Iterator<Item = T> + '_ {
+        self.items.drain(..).rev()
+    }
+}
+
+impl<T> Default for Stack<T> {
+    fn default() -> Self {
+        Stack::new()
+    }
+}
+
+/// Pushes each item in turn, so the last one ends up on top.
+impl<T> Extend<T> for Stack<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.items.extend(iter);
+    }
+}
+
+impl<T> FromIterator<T> for Stack<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Stack {
+            items: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// Consumes the stack, yielding items from the top down.
+impl<T> IntoIterator for Stack<T> {
+    type Item = T;
+    type IntoIter = std::iter::Rev<std::vec::IntoIter<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.into_iter().rev()
+    }
+}
+
+/// Lists the items top first, e.g. `[3, 2, 1]` after pushing 1, 2, 3.
+impl<T: fmt::Display> fmt::Display for Stack<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[")?;
+        for (i, item) in self.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", item)?;
+        }
+        write!(f, "]")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_pop_peek() {
+        let mut stack = Stack::new();
+        assert!(stack.is_empty());
+        assert_eq!(stack.pop(), None);
+        assert_eq!(stack.peek(), None);
+        stack.push("a");
+        stack.push("b");
+        assert_eq!(stack.len(), 2);
+        assert_eq!(stack.peek(), Some(&"b"));
+        assert_eq!(stack.pop(), Some("b"));
+        assert_eq!(stack.pop(), Some("a"));
+        assert!(stack.is_empty());
+    }
+
+    #[test]
+    fn test_capacity() {
+        let stack: Stack<u8> = Stack::with_capacity(16);
+        assert!(stack.capacity() >= 16);
+        assert_eq!(stack.len(), 0);
+    }
+
+    #[test]
+    fn test_iteration_order() {
+        let mut stack = Stack::new();
+        stack.extend([1, 2, 3]);
+        let top_down: Vec<_> = stack.iter().copied().collect();
+        assert_eq!(top_down, vec![3, 2, 1]);
+        assert_eq!(stack.clone().into_iter().collect::<Vec<_>>(), vec![3, 2, 1]);
+        assert_eq!(stack.drain().collect::<Vec<_>>(), vec![3, 2, 1]);
+        assert!(stack.is_empty());
+    }
+
+    #[test]
+    fn test_display() {
+        let stack: Stack<i32> = (1..=3).collect();
+        assert_eq!(stack.to_string(), "[3, 2, 1]");
+        assert_eq!(Stack::<i32>::new().to_string(), "[]");
+    }
+}
@@ -0,0 +1,228 @@
+//! A prefix tree of words.
+//!
+//! Each node stands for one prefix and has a child per character that can
+//! follow it. Words sharing a prefix share the nodes for it, so finding all
+//! words that start with some prefix means walking down to that prefix's
+//! node and collecting everything below it.
+
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Default)]
+struct Node {
+    /// Children kept in a `BTreeMap` so they are visited alphabetically.
+    children: BTreeMap<char, Node>,
+    /// True if the path to this node spells a whole word, not just a prefix.
+    is_word: bool,
+}
+
+/// A set of words supporting fast prefix queries.
+///
+/// ```
+/// use rustler::collections::Trie;
+///
+/// let mut contacts = Trie::new();
+/// for name in ["ann", "anna", "annie", "bob"] {
+///     contacts.insert(name);
+/// }
+/// assert!(contacts.contains("anna"));
+/// assert!(!contacts.contains("an"));
+/// assert!(contacts.starts_with("an"));
+/// assert_eq!(contacts.autocomplete("ann", 2), vec!["ann", "anna"]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Trie {
+    root: Node,
+    len: usize,
+}
+
+impl Trie {
+    pub fn new() -> Self {
+        Trie::default()
+    }
+
+    /// The number of distinct words stored.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Adds a word, returning false if it was already present.
+    pub fn insert(&mut self, word: &str) -> bool {
+        let mut node = &mut self.root;
+        for c in word.chars() {
+            node = node.children.entry(c).or_default();
+        }
+        if node.is_word {
+            return false;
+        }
+        node.is_word = true;
+        self.len += 1;
+        true
+    }
+
+    /// Returns true if `word` was inserted as a whole word.
+    pub fn contains(&self, word: &str) -> bool {
+        self.find(word).is_some_and(|node| node.is_word)
+    }
+
+    /// Returns true if any stored word starts with `prefix`.
+    pub fn starts_with(&self, prefix: &str) -> bool {
+        // Removal prunes every other node with no words below it, but the
+        // root stays even when the trie is empty
+        if prefix.is_empty() {
+            return !self.is_empty();
+        }
+        self.find(prefix).is_some()
+    }
+
+    /// Removes a word, returning true if it was present. Nodes left with no
+    /// words below them are pruned.
+    pub fn remove(&mut self, word: &str) -> bool {
+        fn remove_from(node: &mut Node, mut chars: std::str::Chars) -> bool {
+            match chars.next() {
+                None => std::mem::replace(&mut node.is_word, false),
+                Some(c) => {
+                    let Some(child) = node.children.get_mut(&c) else {
+                        return false;
+                    };
+                    let removed = remove_from(child, chars);
+                    if removed && !child.is_word && child.children.is_empty() {
+                        node.children.remove(&c);
+                    }
+                    removed
+                }
+            }
+        }
+        let removed = remove_from(&mut self.root, word.chars());
+        if removed {
+            self.len -= 1;
+        }
+        removed
+    }
+
+    /// Up to `limit` stored words starting with `prefix`, in alphabetical
+    /// order. The prefix itself is included if it is a word.
+    pub fn autocomplete(&self, prefix: &str, limit: usize) -> Vec<String> {
+        let mut results = Vec::new();
+        if let Some(node) = self.find(prefix) {
+            let mut word = prefix.to_string();
+            Self::collect(node, &mut word, limit, &mut results);
+        }
+        results
+    }
+
+    /// Every stored word, in alphabetical order.
+    pub fn words(&self) -> Vec<String> {
+        self.autocomplete("", usize::MAX)
+    }
+
+    fn find(&self, prefix: &str) -> Option<&Node> {
+        let mut node = &self.root;
+        for c in prefix.chars() {
+            node = node.children.get(&c)?;
+        }
+        Some(node)
+    }
+
+    /// Depth-first walk that appends complete words below `node` to `out`,
+    /// reusing `word` as the buffer for the current path.
+    fn collect(node: &Node, word: &mut String, limit: usize, out: &mut Vec<String>) {
+        if out.len() >= limit {
+            return;
+        }
+        if node.is_word {
+            out.push(word.clone());
+        }
+        for (&c, child) in &node.children {
+            word.push(c);
+            Self::collect(child, word, limit, out);
+            word.pop();
+        }
+    }
+}
+
+impl<'a> FromIterator<&'a str> for Trie {
+    fn from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> Self {
+        let mut trie = Trie::new();
+        for word in iter {
+            trie.insert(word);
+        }
+        trie
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_and_contains() {
+        let mut trie = Trie::new();
+        assert!(trie.is_empty());
+        assert!(trie.insert("car"));
+        assert!(trie.insert("cart"));
+        assert!(!trie.insert("car"));
+        assert_eq!(trie.len(), 2);
+        assert!(trie.contains("car"));
+        assert!(trie.contains("cart"));
+        assert!(!trie.contains("ca"));
+        assert!(!trie.contains("carts"));
+    }
+
+    #[test]
+    fn test_starts_with() {
+        let trie: Trie = ["apple", "apply"].into_iter().collect();
+        assert!(trie.starts_with("app"));
+        assert!(trie.starts_with("apple"));
+        assert!(trie.starts_with(""));
+        assert!(!trie.starts_with("b"));
+    }
+
+    #[test]
+    fn test_autocomplete() {
+        let trie: Trie = ["tea", "ten", "ted", "to", "tide", "inn"].into_iter().collect();
+        assert_eq!(trie.autocomplete("te", 10), vec!["tea", "ted", "ten"]);
+        assert_eq!(trie.autocomplete("t", 2), vec!["tea", "ted"]);
+        assert_eq!(trie.autocomplete("to", 10), vec!["to"]);
+        assert!(trie.autocomplete("x", 10).is_empty());
+        assert!(trie.autocomplete("t", 0).is_empty());
+        assert_eq!(trie.words().len(), 6);
+    }
+
+    #[test]
+    fn test_unicode() {
+        let trie: Trie = ["café", "cafés", "naïve"].into_iter().collect();
+        assert!(trie.contains("café"));
+        assert_eq!(trie.autocomplete("caf", 5), vec!["café", "cafés"]);
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut trie: Trie = ["car", "cart", "cat"].into_iter().collect();
+        assert!(trie.remove("car"));
+        assert!(!trie.remove("car"));
+        assert!(!trie.remove("ca"));
+        assert!(trie.contains("cart"));
+        assert!(trie.remove("cart"));
+        // The "car" branch is gone entirely now
+        assert!(!trie.starts_with("car"));
+        assert_eq!(trie.words(), vec!["cat"]);
+        assert_eq!(trie.len(), 1);
+    }
+
+    #[test]
+    fn test_empty_prefix_needs_a_word() {
+        let mut trie = Trie::new();
+        assert!(!trie.starts_with(""));
+        trie.insert("a");
+        assert!(trie.starts_with(""));
+        trie.remove("a");
+        assert!(!trie.starts_with(""));
+        // The empty word is a word too
+        trie.insert("");
+        assert!(trie.starts_with(""));
+    }
+}
@@ -0,0 +1,254 @@
+//! A fixed-capacity queue that blocks producers when full and consumers
+//! when empty.
+//!
+//! This is the textbook use of condition variables. A `Condvar` lets a
+//! thread release a mutex and sleep until another thread signals that the
+//! state it is waiting for might now hold. "Might" matters: a waiting
+//! thread can wake without a signal (a spurious wakeup), or find another
+//! thread got there first, so every wait sits in a loop that re-checks the
+//! condition after waking.
+
+use std::collections::VecDeque;
+use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+
+struct State<T> {
+    items: VecDeque<T>,
+    closed: bool,
+}
+
+/// A bounded multi-producer, multi-consumer queue. Share it with `Arc`.
+///
+/// ```
+/// use rustler::concurrent::BoundedBuffer;
+/// use std::sync::Arc;
+/// use std::thread;
+///
+/// let buffer = Arc::new(BoundedBuffer::new(2));
+/// let producer = {
+///     let buffer = Arc::clone(&buffer);
+///     thread::spawn(move || {
+///         for n in 1..=5 {
+///             // Blocks whenever two items are already waiting
+///             buffer.push(n).unwrap();
+///         }
+///         buffer.close();
+///     })
+/// };
+///
+/// let mut received = Vec::new();
+/// while let Some(n) = buffer.pop() {
+///     received.push(n);
+/// }
+/// producer.join().unwrap();
+/// assert_eq!(received, vec![1, 2, 3, 4, 5]);
+/// ```
+pub struct BoundedBuffer<T> {
+    state: Mutex<State<T>>,
+    capacity: usize,
+    /// Signalled when an item is removed, so a blocked producer can go on.
+    not_full: Condvar,
+    /// Signalled when an item is added, so a blocked consumer can go on.
+    not_empty: Condvar,
+}
+
+impl<T> BoundedBuffer<T> {
+    /// An empty buffer holding at most `capacity` items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "buffer capacity must be at least 1");
+        BoundedBuffer {
+            state: Mutex::new(State {
+                items: VecDeque::with_capacity(capacity),
+                closed: false,
+            }),
+            capacity,
+            not_full: Condvar::new(),
+            not_empty: Condvar::new(),
+        }
+    }
+
+    /// Adds `item`, waiting while the buffer is full. Gives the item back
+    /// as an error if the buffer is closed.
+    pub fn push(&self, item: T) -> Result<(), T> {
+        let mut state = self.lock();
+        // A loop, not an `if`: after waking, the buffer may be full again
+        while state.items.len() == self.capacity && !state.closed {
+            state = self.not_full.wait(state).unwrap_or_else(PoisonError::into_inner);
+        }
+        if state.closed {
+            return Err(item);
+        }
+        state.items.push_back(item);
+        drop(state);
+        self.not_empty.notify_one();
+        Ok(())
+    }
+
+    /// Adds `item` only if there is room right now, otherwise gives it
+    /// back.
+    pub fn try_push(&self, item: T) -> Result<(), T> {
+        let mut state = self.lock();
+        if state.closed || state.items.len() == self.capacity {
+            return Err(item);
+        }
+        state.items.push_back(item);
+        drop(state);
+        self.not_empty.notify_one();
+        Ok(())
+    }
+
+    /// Removes the oldest item, waiting while the buffer is empty. Returns
+    /// `None` once the buffer is closed and drained.
+    pub fn pop(&self) -> Option<T> {
+        let mut state = self.lock();
+        while state.items.is_empty() && !state.closed {
+            state = self.not_empty.wait(state).unwrap_or_else(PoisonError::into_inner);
+        }
+        let item = state.items.pop_front();
+        drop(state);
+        if item.is_some() {
+            self.not_full.notify_one();
+        }
+        item
+    }
+
+    /// Removes the oldest item if there is one, without waiting.
+    pub fn try_pop(&self) -> Option<T> {
+        let item = self.lock().items.pop_front();
+        if item.is_some() {
+            self.not_full.notify_one();
+        }
+        item
+    }
+
+    /// Stops new pushes. Items already queued can still be popped; after
+    /// that `pop` returns `None`. Wakes every waiting thread.
+    pub fn close(&self) {
+        self.lock().closed = true;
+        self.not_full.notify_all();
+        self.not_empty.notify_all();
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.lock().closed
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().items.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn test_try_push_respects_capacity() {
+        let buffer = BoundedBuffer::new(2);
+        assert_eq!(buffer.capacity(), 2);
+        assert_eq!(buffer.try_push(1), Ok(()));
+        assert_eq!(buffer.try_push(2), Ok(()));
+        assert_eq!(buffer.try_push(3), Err(3));
+        assert_eq!(buffer.len(), 2);
+        assert_eq!(buffer.try_pop(), Some(1));
+        assert_eq!(buffer.try_push(3), Ok(()));
+        assert_eq!(buffer.try_pop(), Some(2));
+        assert_eq!(buffer.try_pop(), Some(3));
+        assert_eq!(buffer.try_pop(), None);
+    }
+
+    #[test]
+    fn test_push_blocks_until_there_is_room() {
+        let buffer = Arc::new(BoundedBuffer::new(1));
+        buffer.push("first").unwrap();
+        let pushed = Arc::new(AtomicUsize::new(0));
+        let producer = {
+            let (buffer, pushed) = (Arc::clone(&buffer), Arc::clone(&pushed));
+            thread::spawn(move || {
+                buffer.push("second").unwrap();
+                pushed.store(1, Ordering::SeqCst);
+            })
+        };
+        thread::sleep(Duration::from_millis(30));
+        assert_eq!(pushed.load(Ordering::SeqCst), 0, "push should still be waiting");
+        assert_eq!(buffer.pop(), Some("first"));
+        producer.join().unwrap();
+        assert_eq!(buffer.pop(), Some("second"));
+    }
+
+    #[test]
+    fn test_close_drains_then_ends() {
+        let buffer = BoundedBuffer::new(4);
+        buffer.push(1).unwrap();
+        buffer.close();
+        assert!(buffer.is_closed());
+        assert_eq!(buffer.push(2), Err(2));
+        assert_eq!(buffer.pop(), Some(1));
+        assert_eq!(buffer.pop(), None);
+    }
+
+    #[test]
+    fn test_close_wakes_blocked_consumers() {
+        let buffer = Arc::new(BoundedBuffer::<i32>::new(1));
+        let consumers: Vec<_> = (0..3)
+            .map(|_| {
+                let buffer = Arc::clone(&buffer);
+                thread::spawn(move || buffer.pop())
+            })
+            .collect();
+        thread::sleep(Duration::from_millis(20));
+        buffer.close();
+        for consumer in consumers {
+            assert_eq!(consumer.join().unwrap(), None);
+        }
+    }
+
+    #[test]
+    fn test_many_producers_and_consumers() {
+        let buffer = Arc::new(BoundedBuffer::new(3));
+        let producers: Vec<_> = (0..4)
+            .map(|p| {
+                let buffer = Arc::clone(&buffer);
+                thread::spawn(move || {
+                    for i in 0..250 {
+                        buffer.push(p * 1000 + i).unwrap();
+                    }
+                })
+            })
+            .collect();
+        let consumers: Vec<_> = (0..3)
+            .map(|_| {
+                let buffer = Arc::clone(&buffer);
+                thread::spawn(move || std::iter::from_fn(|| buffer.pop()).collect::<Vec<_>>())
+            })
+            .collect();
+        for producer in producers {
+            producer.join().unwrap();
+        }
+        buffer.close();
+        let mut all: Vec<i32> = consumers.into_iter().flat_map(|c| c.join().unwrap()).collect();
+        all.sort();
+        let mut expected: Vec<i32> = (0..4).flat_map(|p| (0..250).map(move |i| p * 1000 + i)).collect();
+        expected.sort();
+        assert_eq!(all, expected);
+    }
+}
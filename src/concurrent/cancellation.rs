@@ -0,0 +1,229 @@
+//! Cooperative cancellation shared between threads.
+//!
+//! Rust has no way to kill a thread from outside. Instead, long-running
+//! work holds a [`CancellationToken`] and checks it between steps, or
+//! sleeps on it instead of on `thread::sleep` so a cancel wakes it at once.
+//! Whoever wants everything to stop, such as a Ctrl+C handler, calls
+//! `cancel()` on any clone.
+
+use std::fmt;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+type Callback = Box<dyn FnOnce() + Send>;
+
+#[derive(Default)]
+struct State {
+    cancelled: bool,
+    callbacks: Vec<(u64, Callback)>,
+    next_callback: u64,
+}
+
+#[derive(Default)]
+struct Inner {
+    state: Mutex<State>,
+    cancelled: Condvar,
+}
+
+/// A flag that can be set once, from any thread, and waited on.
+///
+/// Clones share the same flag, so cloning is the way to hand it out.
+///
+/// ```
+/// use rustler::concurrent::CancellationToken;
+/// use std::thread;
+/// use std::time::Duration;
+///
+/// let token = CancellationToken::new();
+/// let worker = {
+///     let token = token.clone();
+///     thread::spawn(move || {
+///         let mut ticks = 0;
+///         // Sleeps between ticks, but wakes immediately on cancel
+///         while !token.cancelled_wait(Duration::from_millis(10)) {
+///             ticks += 1;
+///         }
+///         ticks
+///     })
+/// };
+///
+/// thread::sleep(Duration::from_millis(50));
+/// token.cancel();
+/// assert!(worker.join().unwrap() > 0);
+/// ```
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+    inner: Arc<Inner>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        CancellationToken::default()
+    }
+
+    /// Cancels the token, waking every waiter and running the callbacks
+    /// registered with [`on_cancel`](CancellationToken::on_cancel).
+    /// Cancelling again does nothing.
+    pub fn cancel(&self) {
+        let callbacks = {
+            let mut state = self.lock();
+            if state.cancelled {
+                return;
+            }
+            state.cancelled = true;
+            std::mem::take(&mut state.callbacks)
+        };
+        self.inner.cancelled.notify_all();
+        // Run outside the lock so a callback may use the token itself
+        for (_, callback) in callbacks {
+            callback();
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.lock().cancelled
+    }
+
+    /// Blocks until the token is cancelled or `timeout` passes, returning
+    /// true if it was cancelled.
+    pub fn cancelled_wait(&self, timeout: Duration) -> bool {
+        let state = self.lock();
+        let (state, _) = self
+            .inner
+            .cancelled
+            .wait_timeout_while(state, timeout, |state| !state.cancelled)
+            .unwrap_or_else(PoisonError::into_inner);
+        state.cancelled
+    }
+
+    /// Runs `callback` when the token is cancelled, on the thread that
+    /// cancels it. If the token is already cancelled, runs it now.
+    ///
+    /// A long-lived token outlives whatever registered the callback, so
+    /// pass the returned id to [`remove_callback`] once it is no longer
+    /// wanted.
+    ///
+    /// [`remove_callback`]: CancellationToken::remove_callback
+    pub fn on_cancel<F>(&self, callback: F) -> CallbackId
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let mut state = self.lock();
+        let id = state.next_callback;
+        state.next_callback += 1;
+        if state.cancelled {
+            drop(state);
+            callback();
+        } else {
+            state.callbacks.push((id, Box::new(callback)));
+        }
+        CallbackId(id)
+    }
+
+    /// Forgets a callback without running it. Returns false if it has
+    /// already run or been removed.
+    pub fn remove_callback(&self, id: CallbackId) -> bool {
+        let mut state = self.lock();
+        let before = state.callbacks.len();
+        state.callbacks.retain(|(other, _)| *other != id.0);
+        state.callbacks.len() < before
+    }
+
+    #[cfg(test)]
+    fn callback_count(&self) -> usize {
+        self.lock().callbacks.len()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.inner.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// A callback registered with [`CancellationToken::on_cancel`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CallbackId(u64);
+
+impl fmt::Debug for CancellationToken {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CancellationToken").field("cancelled", &self.is_cancelled()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+    use std::time::Instant;
+
+    #[test]
+    fn test_cancel_is_shared_by_clones() {
+        let token = CancellationToken::new();
+        let clone = token.clone();
+        assert!(!clone.is_cancelled());
+        token.cancel();
+        token.cancel();
+        assert!(clone.is_cancelled());
+        assert_eq!(format!("{:?}", clone), "CancellationToken { cancelled: true }");
+    }
+
+    #[test]
+    fn test_cancelled_wait_times_out() {
+        let token = CancellationToken::new();
+        let start = Instant::now();
+        assert!(!token.cancelled_wait(Duration::from_millis(20)));
+        assert!(start.elapsed() >= Duration::from_millis(20));
+    }
+
+    #[test]
+    fn test_cancel_wakes_a_waiter_early() {
+        let token = CancellationToken::new();
+        let waiter = {
+            let token = token.clone();
+            thread::spawn(move || {
+                let start = Instant::now();
+                (token.cancelled_wait(Duration::from_secs(10)), start.elapsed())
+            })
+        };
+        thread::sleep(Duration::from_millis(20));
+        token.cancel();
+        let (cancelled, waited) = waiter.join().unwrap();
+        assert!(cancelled);
+        assert!(waited < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_callbacks_run_once() {
+        let token = CancellationToken::new();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        token.on_cancel(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+        token.cancel();
+        token.cancel();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        // Registering after cancellation runs the callback straight away
+        let counter = Arc::clone(&calls);
+        token.on_cancel(move || {
+            counter.fetch_add(10, Ordering::SeqCst);
+        });
+        assert_eq!(calls.load(Ordering::SeqCst), 11);
+    }
+
+    #[test]
+    fn test_removed_callbacks_never_run() {
+        let token = CancellationToken::new();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let id = token.on_cancel(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+        assert_eq!(token.callback_count(), 1);
+        assert!(token.remove_callback(id));
+        assert!(!token.remove_callback(id));
+        assert_eq!(token.callback_count(), 0);
+        token.cancel();
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+    }
+}
@@ -0,0 +1,306 @@
+//! Publish/subscribe messaging between threads.
+//!
+//! Publishers send events to a named topic without knowing who, if anyone,
+//! is listening. Every subscriber gets its own bounded queue, so a slow
+//! subscriber can never hold up the publisher or the other subscribers:
+//! when its queue is full, new events for it are dropped and counted
+//! instead of waited on.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+struct Subscriber<E> {
+    id: usize,
+    sender: SyncSender<E>,
+    dropped: Arc<AtomicUsize>,
+}
+
+struct Inner<E> {
+    topics: Mutex<HashMap<String, Vec<Subscriber<E>>>>,
+    next_id: AtomicUsize,
+}
+
+/// A set of topics that threads publish events to and subscribe to.
+///
+/// Clones share the same topics, so cloning is the way to hand the bus to
+/// another thread.
+///
+/// ```
+/// use rustler::concurrent::EventBus;
+///
+/// let bus = EventBus::new();
+/// let scores = bus.subscribe("score", 16);
+///
+/// assert_eq!(bus.publish("score", 10), 1);
+/// assert_eq!(bus.publish("score", 25), 1);
+/// // Nobody listens to this topic, so nobody receives it
+/// assert_eq!(bus.publish("chat", 99), 0);
+///
+/// assert_eq!(scores.try_recv(), Some(10));
+/// assert_eq!(scores.try_recv(), Some(25));
+/// assert_eq!(scores.try_recv(), None);
+/// ```
+pub struct EventBus<E> {
+    inner: Arc<Inner<E>>,
+}
+
+impl<E: Clone + Send + 'static> EventBus<E> {
+    pub fn new() -> Self {
+        EventBus {
+            inner: Arc::new(Inner {
+                topics: Mutex::new(HashMap::new()),
+                next_id: AtomicUsize::new(0),
+            }),
+        }
+    }
+
+    /// Subscribes to `topic` through a queue holding at most `capacity`
+    /// undelivered events. Dropping the subscription unsubscribes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn subscribe(&self, topic: &str, capacity: usize) -> Subscription<E> {
+        let (receiver, dropped) = self.add_subscriber(topic, capacity).1;
+        Subscription { receiver, dropped }
+    }
+
+    /// Subscribes `callback` to `topic`. The callback runs on its own
+    /// thread, fed by a queue of at most `capacity` events, so it may take
+    /// as long as it likes without slowing publishers down.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn subscribe_fn<F>(&self, topic: &str, capacity: usize, mut callback: F) -> CallbackSubscription<E>
+    where
+        F: FnMut(E) + Send + 'static,
+    {
+        let (id, (receiver, dropped)) = self.add_subscriber(topic, capacity);
+        // Ends once the bus removes the sender, on unsubscribe or when
+        // every clone of the bus is gone
+        let thread = thread::spawn(move || receiver.iter().for_each(&mut callback));
+        CallbackSubscription {
+            bus: self.clone(),
+            topic: topic.to_string(),
+            id,
+            dropped,
+            thread,
+        }
+    }
+
+    /// Sends `event` to every current subscriber of `topic` without
+    /// waiting, and returns how many received it. Subscribers whose queue
+    /// is full miss the event; subscribers that have gone away are
+    /// removed.
+    pub fn publish(&self, topic: &str, event: E) -> usize {
+        let mut topics = self.lock();
+        let Some(subscribers) = topics.get_mut(topic) else {
+            return 0;
+        };
+        let mut delivered = 0;
+        subscribers.retain(|subscriber| match subscriber.sender.try_send(event.clone()) {
+            Ok(()) => {
+                delivered += 1;
+                true
+            }
+            Err(TrySendError::Full(_)) => {
+                subscriber.dropped.fetch_add(1, Ordering::Relaxed);
+                true
+            }
+            Err(TrySendError::Disconnected(_)) => false,
+        });
+        if subscribers.is_empty() {
+            topics.remove(topic);
+        }
+        delivered
+    }
+
+    /// How many subscribers `topic` has. A subscription dropped since the
+    /// last publish to the topic may still be counted.
+    pub fn subscriber_count(&self, topic: &str) -> usize {
+        self.lock().get(topic).map_or(0, Vec::len)
+    }
+
+    fn add_subscriber(&self, topic: &str, capacity: usize) -> (usize, (Receiver<E>, Arc<AtomicUsize>)) {
+        assert!(capacity > 0, "subscriber capacity must be at least 1");
+        let (sender, receiver) = mpsc::sync_channel(capacity);
+        let dropped = Arc::new(AtomicUsize::new(0));
+        let id = self.inner.next_id.fetch_add(1, Ordering::Relaxed);
+        self.lock().entry(topic.to_string()).or_default().push(Subscriber {
+            id,
+            sender,
+            dropped: Arc::clone(&dropped),
+        });
+        (id, (receiver, dropped))
+    }
+
+    fn remove_subscriber(&self, topic: &str, id: usize) {
+        let mut topics = self.lock();
+        if let Some(subscribers) = topics.get_mut(topic) {
+            subscribers.retain(|subscriber| subscriber.id != id);
+            if subscribers.is_empty() {
+                topics.remove(topic);
+            }
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, Vec<Subscriber<E>>>> {
+        self.inner.topics.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<E: Clone + Send + 'static> Default for EventBus<E> {
+    fn default() -> Self {
+        EventBus::new()
+    }
+}
+
+impl<E> Clone for EventBus<E> {
+    fn clone(&self) -> Self {
+        EventBus {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+/// The receiving end of [`EventBus::subscribe`].
+pub struct Subscription<E> {
+    receiver: Receiver<E>,
+    dropped: Arc<AtomicUsize>,
+}
+
+impl<E> Subscription<E> {
+    /// Waits for the next event. Returns `None` once every clone of the
+    /// bus is gone and the queue is empty.
+    pub fn recv(&self) -> Option<E> {
+        self.receiver.recv().ok()
+    }
+
+    /// The next event if one is queued, without waiting.
+    pub fn try_recv(&self) -> Option<E> {
+        self.receiver.try_recv().ok()
+    }
+
+    /// Waits up to `timeout` for the next event.
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<E> {
+        self.receiver.recv_timeout(timeout).ok()
+    }
+
+    /// How many events were discarded because the queue was full.
+    pub fn dropped(&self) -> usize {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+/// A callback registered with [`EventBus::subscribe_fn`].
+///
+/// Dropping this leaves the callback subscribed for as long as the bus
+/// lives; call [`unsubscribe`](CallbackSubscription::unsubscribe) to stop it.
+pub struct CallbackSubscription<E> {
+    bus: EventBus<E>,
+    topic: String,
+    id: usize,
+    dropped: Arc<AtomicUsize>,
+    thread: JoinHandle<()>,
+}
+
+impl<E: Clone + Send + 'static> CallbackSubscription<E> {
+    /// How many events were discarded because the callback fell behind.
+    pub fn dropped(&self) -> usize {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Removes the callback from the bus and waits for it to finish the
+    /// events already queued for it.
+    ///
+    /// # Panics
+    ///
+    /// Resumes the panic if the callback panicked.
+    pub fn unsubscribe(self) {
+        self.bus.remove_subscriber(&self.topic, self.id);
+        if let Err(payload) = self.thread.join() {
+            std::panic::resume_unwind(payload);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc::channel;
+
+    #[test]
+    fn test_every_subscriber_of_a_topic_receives() {
+        let bus = EventBus::new();
+        let first = bus.subscribe("game", 4);
+        let second = bus.subscribe("game", 4);
+        let other = bus.subscribe("cli", 4);
+        assert_eq!(bus.subscriber_count("game"), 2);
+        assert_eq!(bus.publish("game", "level up"), 2);
+        assert_eq!(first.try_recv(), Some("level up"));
+        assert_eq!(second.try_recv(), Some("level up"));
+        assert_eq!(other.try_recv(), None);
+    }
+
+    #[test]
+    fn test_full_queue_drops_instead_of_blocking() {
+        let bus = EventBus::new();
+        let slow = bus.subscribe("ticks", 2);
+        let fast = bus.subscribe("ticks", 10);
+        for tick in 0..5 {
+            bus.publish("ticks", tick);
+        }
+        assert_eq!(slow.dropped(), 3);
+        assert_eq!(fast.dropped(), 0);
+        assert_eq!(std::iter::from_fn(|| slow.try_recv()).collect::<Vec<_>>(), vec![0, 1]);
+        assert_eq!(std::iter::from_fn(|| fast.try_recv()).count(), 5);
+    }
+
+    #[test]
+    fn test_dropped_subscriptions_are_removed() {
+        let bus = EventBus::new();
+        let kept = bus.subscribe("news", 4);
+        drop(bus.subscribe("news", 4));
+        assert_eq!(bus.publish("news", 1), 1);
+        assert_eq!(bus.subscriber_count("news"), 1);
+        drop(kept);
+        assert_eq!(bus.publish("news", 2), 0);
+        assert_eq!(bus.subscriber_count("news"), 0);
+    }
+
+    #[test]
+    fn test_callbacks_run_on_their_own_thread() {
+        let bus = EventBus::new();
+        let (sender, received) = channel();
+        let subscription = bus.subscribe_fn("scores", 8, move |score: u32| sender.send(score * 2).unwrap());
+        for score in [1, 2, 3] {
+            bus.publish("scores", score);
+        }
+        subscription.unsubscribe();
+        assert_eq!(received.iter().collect::<Vec<_>>(), vec![2, 4, 6]);
+        assert_eq!(bus.publish("scores", 4), 0);
+    }
+
+    #[test]
+    fn test_recv_ends_when_the_bus_is_gone() {
+        let bus = EventBus::new();
+        let subscription = bus.subscribe("topic", 4);
+        let publisher = {
+            let bus = bus.clone();
+            thread::spawn(move || {
+                bus.publish("topic", 'a');
+                bus.publish("topic", 'b');
+            })
+        };
+        publisher.join().unwrap();
+        drop(bus);
+        assert_eq!(subscription.recv(), Some('a'));
+        assert_eq!(subscription.recv_timeout(Duration::from_secs(1)), Some('b'));
+        assert_eq!(subscription.recv(), None);
+    }
+}
@@ -0,0 +1,140 @@
+//! A minimal single-threaded executor for running futures to completion.
+//!
+//! A future does nothing until it is polled. Polling either finishes it
+//! (`Poll::Ready`) or returns `Poll::Pending` after handing the `Waker`
+//! from the `Context` to whatever it is waiting on. When that thing is
+//! ready it calls `wake()`, and the executor polls again.
+//!
+//! Here the waker simply unparks the thread that called [`block_on`], which
+//! parks itself whenever the future is pending. That is the whole executor:
+//! no task queue, no reactor, and no dependency on tokio.
+
+use std::future::Future;
+use std::pin::pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+/// Wakes the executor by unparking its thread.
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Runs `future` on the current thread until it completes, sleeping while
+/// it is waiting to be woken.
+///
+/// ```
+/// use rustler::concurrent::block_on;
+///
+/// async fn add(a: i32, b: i32) -> i32 {
+///     a + b
+/// }
+///
+/// let total = block_on(async {
+///     let first = add(1, 2).await;
+///     add(first, 3).await
+/// });
+/// assert_eq!(total, 6);
+/// ```
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut context = Context::from_waker(&waker);
+    loop {
+        match future.as_mut().poll(&mut context) {
+            Poll::Ready(output) => return output,
+            // A wake that arrived before we park makes park return at once,
+            // so no wake-up is missed. Park may also return spuriously; that
+            // just costs one extra poll.
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::pin::Pin;
+    use std::sync::Mutex;
+    use std::time::{Duration, Instant};
+
+    /// Completes once another thread has slept for `delay`, counting how
+    /// often it was polled.
+    struct Delay {
+        delay: Duration,
+        state: Arc<Mutex<(bool, Option<Waker>)>>,
+        started: bool,
+        polls: usize,
+    }
+
+    impl Delay {
+        fn new(delay: Duration) -> Self {
+            Delay {
+                delay,
+                state: Arc::new(Mutex::new((false, None))),
+                started: false,
+                polls: 0,
+            }
+        }
+    }
+
+    impl Future for Delay {
+        type Output = usize;
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<usize> {
+            self.polls += 1;
+            let mut state = self.state.lock().unwrap();
+            if state.0 {
+                return Poll::Ready(self.polls);
+            }
+            state.1 = Some(cx.waker().clone());
+            drop(state);
+            if !self.started {
+                self.started = true;
+                let (delay, shared) = (self.delay, Arc::clone(&self.state));
+                thread::spawn(move || {
+                    thread::sleep(delay);
+                    let mut state = shared.lock().unwrap();
+                    state.0 = true;
+                    if let Some(waker) = state.1.take() {
+                        waker.wake();
+                    }
+                });
+            }
+            Poll::Pending
+        }
+    }
+
+    #[test]
+    fn test_ready_future() {
+        assert_eq!(block_on(async { "done" }), "done");
+        assert_eq!(block_on(std::future::ready(5)), 5);
+    }
+
+    #[test]
+    fn test_waits_for_wake_from_another_thread() {
+        let start = Instant::now();
+        let polls = block_on(Delay::new(Duration::from_millis(30)));
+        assert!(start.elapsed() >= Duration::from_millis(30));
+        // Parking instead of spinning means only a handful of polls
+        assert!(polls <= 3, "polled {} times", polls);
+    }
+
+    #[test]
+    fn test_awaits_in_sequence() {
+        let polls = block_on(async {
+            let a = Delay::new(Duration::from_millis(5)).await;
+            let b = Delay::new(Duration::from_millis(5)).await;
+            a + b
+        });
+        assert!(polls >= 4);
+    }
+}
@@ -0,0 +1,142 @@
+//! Lock-free counters and a registry for reading them all at once.
+//!
+//! A `Metric` is a single `AtomicU64`. Incrementing it is one atomic
+//! instruction, so threads never wait on each other the way they do with
+//! `Mutex<u64>`. Counters only need each update to be atomic, not to be
+//! ordered with other memory, so `Ordering::Relaxed` is enough.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// A counter that any number of threads can update without locking.
+///
+/// `new` is a `const fn`, so a metric can live in a `static`:
+///
+/// ```
+/// use rustler::concurrent::Metric;
+///
+/// static REQUESTS: Metric = Metric::new();
+///
+/// std::thread::scope(|scope| {
+///     for _ in 0..4 {
+///         scope.spawn(|| (0..100).for_each(|_| REQUESTS.incr()));
+///     }
+/// });
+/// assert_eq!(REQUESTS.get(), 400);
+/// ```
+#[derive(Debug, Default)]
+pub struct Metric {
+    value: AtomicU64,
+}
+
+impl Metric {
+    pub const fn new() -> Self {
+        Metric { value: AtomicU64::new(0) }
+    }
+
+    /// Adds one.
+    pub fn incr(&self) {
+        self.add(1);
+    }
+
+    /// Adds `amount`, wrapping around on overflow.
+    pub fn add(&self, amount: u64) {
+        self.value.fetch_add(amount, Ordering::Relaxed);
+    }
+
+    /// The current value.
+    pub fn get(&self) -> u64 {
+        self.value.load(Ordering::Relaxed)
+    }
+
+    /// Sets the value back to zero, returning what it was.
+    pub fn reset(&self) -> u64 {
+        self.value.swap(0, Ordering::Relaxed)
+    }
+}
+
+/// Named metrics that can be read together.
+///
+/// ```
+/// use rustler::concurrent::MetricRegistry;
+///
+/// let registry = MetricRegistry::new();
+/// registry.metric("cache.hits").add(3);
+/// registry.metric("cache.misses").incr();
+/// registry.metric("cache.hits").incr();
+///
+/// let snapshot = registry.snapshot();
+/// assert_eq!(snapshot["cache.hits"], 4);
+/// assert_eq!(snapshot["cache.misses"], 1);
+/// ```
+#[derive(Debug, Default)]
+pub struct MetricRegistry {
+    metrics: Mutex<BTreeMap<String, Arc<Metric>>>,
+}
+
+impl MetricRegistry {
+    pub fn new() -> Self {
+        MetricRegistry::default()
+    }
+
+    /// The metric called `name`, created at zero the first time it is
+    /// asked for. Keep the returned `Arc` around on hot paths so updates
+    /// skip the registry's lock entirely.
+    pub fn metric(&self, name: &str) -> Arc<Metric> {
+        let mut metrics = self.metrics.lock().unwrap_or_else(PoisonError::into_inner);
+        Arc::clone(metrics.entry(name.to_string()).or_default())
+    }
+
+    /// Every metric's current value, sorted by name.
+    ///
+    /// Each value is read atomically, but updates can land between reads,
+    /// so the snapshot is not one instant across all metrics.
+    pub fn snapshot(&self) -> BTreeMap<String, u64> {
+        let metrics = self.metrics.lock().unwrap_or_else(PoisonError::into_inner);
+        metrics.iter().map(|(name, metric)| (name.clone(), metric.get())).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_incr_add_reset() {
+        let metric = Metric::new();
+        metric.incr();
+        metric.add(10);
+        assert_eq!(metric.get(), 11);
+        assert_eq!(metric.reset(), 11);
+        assert_eq!(metric.get(), 0);
+    }
+
+    #[test]
+    fn test_concurrent_increments_are_not_lost() {
+        let metric = Metric::new();
+        thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    for _ in 0..10_000 {
+                        metric.incr();
+                    }
+                });
+            }
+        });
+        assert_eq!(metric.get(), 80_000);
+    }
+
+    #[test]
+    fn test_registry_shares_metrics_by_name() {
+        let registry = MetricRegistry::new();
+        let hits = registry.metric("hits");
+        hits.add(2);
+        registry.metric("hits").incr();
+        registry.metric("errors");
+        assert_eq!(hits.get(), 3);
+        let snapshot: Vec<_> = registry.snapshot().into_iter().collect();
+        assert_eq!(snapshot, vec![("errors".to_string(), 0), ("hits".to_string(), 3)]);
+    }
+}
@@ -0,0 +1,30 @@
+//! Concurrency building blocks on top of `std::thread` and `std::sync`.
+//!
+//! Everything here uses only the standard library, so the examples can
+//! show how these tools work inside rather than reaching for a crate.
+
+pub mod bounded_buffer;
+pub mod cancellation;
+pub mod event_bus;
+pub mod executor;
+pub mod metric;
+pub mod parallel;
+pub mod pipeline;
+pub mod rate_limiter;
+pub mod scheduler;
+pub mod shared_cache;
+pub mod supervisor;
+pub mod thread_pool;
+
+pub use bounded_buffer::BoundedBuffer;
+pub use cancellation::{CallbackId, CancellationToken};
+pub use event_bus::{CallbackSubscription, EventBus, Subscription};
+pub use executor::block_on;
+pub use metric::{Metric, MetricRegistry};
+pub use parallel::{map_reduce, parallel_map};
+pub use pipeline::{OutputOrder, Pipeline};
+pub use rate_limiter::RateLimiter;
+pub use scheduler::{ScheduledTask, Scheduler};
+pub use shared_cache::SharedCache;
+pub use supervisor::{SupervisedHandle, Supervisor, SupervisorError};
+pub use thread_pool::{JobError, JobHandle, ThreadPool};
@@ -0,0 +1,145 @@
+//! Data-parallel helpers over slices.
+//!
+//! These use `std::thread::scope`, so the worker threads can borrow the
+//! input slice and closure directly: the scope guarantees every thread has
+//! finished before the borrow ends, which removes the need for `Arc`.
+
+use std::thread;
+
+/// Applies `f` to every item, splitting the slice into chunks of
+/// `chunk_size` items that each run on their own thread. The results come
+/// back in the same order as the input.
+///
+/// # Panics
+///
+/// Panics if `chunk_size` is zero, or if `f` panics on any item.
+///
+/// ```
+/// use rustler::concurrent::parallel_map;
+///
+/// let words = ["apple", "kiwi", "banana", "fig"];
+/// let lengths = parallel_map(&words, 2, |word| word.len());
+/// assert_eq!(lengths, vec![5, 4, 6, 3]);
+/// ```
+pub fn parallel_map<T, U, F>(items: &[T], chunk_size: usize, f: F) -> Vec<U>
+where
+    T: Sync,
+    U: Send,
+    F: Fn(&T) -> U + Sync,
+{
+    assert!(chunk_size > 0, "chunk size must be at least 1");
+    let f = &f;
+    thread::scope(|scope| {
+        let handles: Vec<_> = items
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(move || chunk.iter().map(f).collect::<Vec<U>>()))
+            .collect();
+        // Joining in spawn order is what puts the chunks back in order
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap_or_else(|payload| std::panic::resume_unwind(payload)))
+            .collect()
+    })
+}
+
+/// Maps every item with `map_fn` and folds the results together with
+/// `reduce_fn`, splitting the slice evenly across `workers` threads.
+///
+/// Each worker reduces its own share, then the partial results are reduced
+/// in slice order, so `reduce_fn` only has to be associative, not
+/// commutative. Returns `None` for an empty slice.
+///
+/// # Panics
+///
+/// Panics if `workers` is zero, or if either closure panics.
+///
+/// ```
+/// use rustler::concurrent::map_reduce;
+///
+/// let words = ["the", "quick", "brown", "fox"];
+/// let letters = map_reduce(&words, |word| word.len(), |a, b| a + b, 2);
+/// assert_eq!(letters, Some(16));
+/// ```
+pub fn map_reduce<T, M, MapFn, ReduceFn>(data: &[T], map_fn: MapFn, reduce_fn: ReduceFn, workers: usize) -> Option<M>
+where
+    T: Sync,
+    M: Send,
+    MapFn: Fn(&T) -> M + Sync,
+    ReduceFn: Fn(M, M) -> M + Sync,
+{
+    assert!(workers > 0, "map_reduce needs at least one worker");
+    if data.is_empty() {
+        return None;
+    }
+    let chunk_size = data.len().div_ceil(workers);
+    let (map_fn, reduce_fn) = (&map_fn, &reduce_fn);
+    let partials = parallel_map(&data.chunks(chunk_size).collect::<Vec<_>>(), 1, |chunk| {
+        chunk.iter().map(map_fn).reduce(reduce_fn)
+    });
+    partials.into_iter().flatten().reduce(reduce_fn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preserves_order() {
+        let numbers: Vec<u64> = (0..1_000).collect();
+        for chunk_size in [1, 7, 100, 1_000, 5_000] {
+            let squares = parallel_map(&numbers, chunk_size, |n| n * n);
+            let expected: Vec<u64> = numbers.iter().map(|n| n * n).collect();
+            assert_eq!(squares, expected);
+        }
+    }
+
+    #[test]
+    fn test_empty_input() {
+        let empty: [i32; 0] = [];
+        assert!(parallel_map(&empty, 4, |n| n + 1).is_empty());
+    }
+
+    #[test]
+    fn test_borrows_from_caller() {
+        let offset = 10;
+        let chunks: Vec<&[i32]> = [1, 2, 3, 4, 5].chunks(2).collect();
+        let sums = parallel_map(&chunks, 1, |chunk| chunk.iter().sum::<i32>() + offset);
+        assert_eq!(sums, vec![13, 17, 15]);
+    }
+
+    #[test]
+    fn test_map_reduce_matches_sequential_fold() {
+        let numbers: Vec<u64> = (1..=1_000).collect();
+        for workers in [1, 3, 8, 2_000] {
+            assert_eq!(map_reduce(&numbers, |n| n * n, |a, b| a + b, workers), Some(333_833_500));
+        }
+    }
+
+    #[test]
+    fn test_map_reduce_keeps_slice_order() {
+        // String concatenation is associative but not commutative
+        let letters = ['m', 'a', 'p', 'r', 'e', 'd', 'u', 'c', 'e'];
+        let joined = map_reduce(&letters, |c| c.to_string(), |a, b| a + &b, 4);
+        assert_eq!(joined.as_deref(), Some("mapreduce"));
+        let empty: [char; 0] = [];
+        assert_eq!(map_reduce(&empty, |c| c.to_string(), |a, b| a + &b, 4), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one worker")]
+    fn test_map_reduce_zero_workers_panics() {
+        map_reduce(&[1], |n| *n, |a, b| a + b, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "chunk size")]
+    fn test_zero_chunk_size_panics() {
+        parallel_map(&[1], 0, |n| *n);
+    }
+
+    #[test]
+    #[should_panic(expected = "bad item")]
+    fn test_panic_in_closure_propagates() {
+        parallel_map(&[1, 2, 3], 1, |&n| if n == 2 { panic!("bad item") } else { n });
+    }
+}
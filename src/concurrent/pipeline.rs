@@ -0,0 +1,235 @@
+//! Multi-stage processing pipelines connected by channels.
+//!
+//! A source thread feeds items into a channel. Each stage runs its closure
+//! on a group of worker threads that share the stage's input channel (the
+//! way the worker pool in the concurrency example shares its job queue)
+//! and send results on to the next stage. The sink drains the last channel
+//! on the calling thread.
+//!
+//! ```text
+//! source ──▶ map (4 workers) ──▶ filter (2 workers) ──▶ sink
+//! ```
+//!
+//! Every item travels with its position in the source. Items a filter
+//! drops still move on as empty markers, so an ordered sink knows not to
+//! wait for them.
+
+use std::collections::BTreeMap;
+use std::panic;
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread::{self, JoinHandle};
+
+/// An item's position in the source, and the item unless a filter
+/// dropped it.
+type Slot<T> = (usize, Option<T>);
+
+/// The order in which a pipeline's sink sees results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputOrder {
+    /// The same order as the source, holding back results that finish
+    /// early until everything before them has arrived.
+    Ordered,
+    /// Whatever order the last stage finishes them in.
+    Unordered,
+}
+
+/// A chain of concurrent stages, built up one stage at a time and then
+/// run by [`collect`](Pipeline::collect) or [`for_each`](Pipeline::for_each).
+///
+/// ```
+/// use rustler::concurrent::{OutputOrder, Pipeline};
+///
+/// let squares_of_odds = Pipeline::new(1..=10)
+///     .filter(2, |n| n % 2 == 1)
+///     .map(4, |n| n * n)
+///     .collect(OutputOrder::Ordered);
+/// assert_eq!(squares_of_odds, vec![1, 9, 25, 49, 81]);
+/// ```
+pub struct Pipeline<T> {
+    output: Receiver<Slot<T>>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl<T: Send + 'static> Pipeline<T> {
+    /// Starts a pipeline that reads `source` on its own thread.
+    pub fn new<I>(source: I) -> Self
+    where
+        I: IntoIterator<Item = T> + Send + 'static,
+    {
+        let (sender, output) = mpsc::channel();
+        let source = thread::spawn(move || {
+            for (index, item) in source.into_iter().enumerate() {
+                if sender.send((index, Some(item))).is_err() {
+                    break;
+                }
+            }
+        });
+        Pipeline {
+            output,
+            threads: vec![source],
+        }
+    }
+
+    /// Adds a stage applying `f` to every item on `workers` threads.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `workers` is zero.
+    pub fn map<U, F>(self, workers: usize, f: F) -> Pipeline<U>
+    where
+        U: Send + 'static,
+        F: Fn(T) -> U + Send + Sync + 'static,
+    {
+        self.stage(workers, move |item| Some(f(item)))
+    }
+
+    /// Adds a stage keeping only the items for which `keep` returns true,
+    /// on `workers` threads.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `workers` is zero.
+    pub fn filter<F>(self, workers: usize, keep: F) -> Pipeline<T>
+    where
+        F: Fn(&T) -> bool + Send + Sync + 'static,
+    {
+        self.stage(workers, move |item| keep(&item).then_some(item))
+    }
+
+    /// Adds a stage that maps and filters in one step: items for which `f`
+    /// returns `None` are dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `workers` is zero.
+    pub fn filter_map<U, F>(self, workers: usize, f: F) -> Pipeline<U>
+    where
+        U: Send + 'static,
+        F: Fn(T) -> Option<U> + Send + Sync + 'static,
+    {
+        self.stage(workers, f)
+    }
+
+    /// Runs the pipeline, passing each result to `sink` on the calling
+    /// thread in the given order.
+    ///
+    /// If any stage panicked, the panic is resumed here once the remaining
+    /// threads have finished.
+    pub fn for_each<F>(self, order: OutputOrder, mut sink: F)
+    where
+        F: FnMut(T),
+    {
+        let mut pending = BTreeMap::new();
+        let mut next = 0;
+        for (index, item) in self.output.iter() {
+            match order {
+                OutputOrder::Unordered => item.into_iter().for_each(&mut sink),
+                OutputOrder::Ordered => {
+                    pending.insert(index, item);
+                    while let Some(item) = pending.remove(&next) {
+                        item.into_iter().for_each(&mut sink);
+                        next += 1;
+                    }
+                }
+            }
+        }
+
+        let mut first_panic = None;
+        for thread in self.threads {
+            if let Err(payload) = thread.join() {
+                first_panic.get_or_insert(payload);
+            }
+        }
+        if let Some(payload) = first_panic {
+            panic::resume_unwind(payload);
+        }
+    }
+
+    /// Runs the pipeline and gathers the results in the given order.
+    pub fn collect(self, order: OutputOrder) -> Vec<T> {
+        let mut results = Vec::new();
+        self.for_each(order, |item| results.push(item));
+        results
+    }
+
+    fn stage<U, F>(self, workers: usize, f: F) -> Pipeline<U>
+    where
+        U: Send + 'static,
+        F: Fn(T) -> Option<U> + Send + Sync + 'static,
+    {
+        assert!(workers > 0, "a pipeline stage needs at least one worker");
+        let input = Arc::new(Mutex::new(self.output));
+        let f = Arc::new(f);
+        let (sender, output) = mpsc::channel();
+        let mut threads = self.threads;
+        for _ in 0..workers {
+            let input = Arc::clone(&input);
+            let f = Arc::clone(&f);
+            let sender = sender.clone();
+            threads.push(thread::spawn(move || loop {
+                // Hold the lock only while taking the next item
+                let next = input.lock().unwrap_or_else(PoisonError::into_inner).recv();
+                let Ok((index, item)) = next else {
+                    break;
+                };
+                if sender.send((index, item.and_then(&*f))).is_err() {
+                    break;
+                }
+            }));
+        }
+        Pipeline { output, threads }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_ordered_output_matches_source_order() {
+        // Later items finish first, so ordering has real work to do
+        let results = Pipeline::new(0..20u64)
+            .map(4, |n| {
+                thread::sleep(Duration::from_millis(20 - n));
+                n * 10
+            })
+            .collect(OutputOrder::Ordered);
+        assert_eq!(results, (0..20).map(|n| n * 10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_unordered_output_has_every_item() {
+        let mut results = Pipeline::new(0..100).map(3, |n| n + 1).collect(OutputOrder::Unordered);
+        results.sort();
+        assert_eq!(results, (1..=100).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_filter_and_filter_map_stages() {
+        let words = vec!["12", "x", "7", "", "40"];
+        let numbers = Pipeline::new(words)
+            .filter(2, |word| !word.is_empty())
+            .filter_map(2, |word| word.parse::<u32>().ok())
+            .map(1, |n| n * 2)
+            .collect(OutputOrder::Ordered);
+        assert_eq!(numbers, vec![24, 14, 80]);
+    }
+
+    #[test]
+    fn test_for_each_and_empty_source() {
+        let mut seen = Vec::new();
+        Pipeline::new(vec!['a', 'b']).map(2, |c| c.to_ascii_uppercase()).for_each(OutputOrder::Ordered, |c| seen.push(c));
+        assert_eq!(seen, vec!['A', 'B']);
+        assert!(Pipeline::new(Vec::<i32>::new()).map(2, |n| n).collect(OutputOrder::Ordered).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "stage failed")]
+    fn test_stage_panic_reaches_the_caller() {
+        Pipeline::new(0..5)
+            .map(2, |n| if n == 3 { panic!("stage failed") } else { n })
+            .collect(OutputOrder::Unordered);
+    }
+}
@@ -0,0 +1,174 @@
+//! A token-bucket rate limiter.
+//!
+//! The bucket holds up to `burst` tokens and refills continuously at `rate`
+//! tokens per second. Each call takes one token, so short bursts are let
+//! through at full speed while the long-run average stays at `rate`.
+
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// Limits how often something may happen, shared safely between threads.
+///
+/// ```
+/// use rustler::concurrent::RateLimiter;
+///
+/// // Two requests per second, allowing bursts of up to three
+/// let limiter = RateLimiter::new(2.0, 3);
+/// assert!(limiter.try_acquire());
+/// assert!(limiter.try_acquire());
+/// assert!(limiter.try_acquire());
+/// assert!(!limiter.try_acquire());
+/// ```
+pub struct RateLimiter {
+    rate: f64,
+    burst: f64,
+    bucket: Mutex<Bucket>,
+}
+
+impl RateLimiter {
+    /// A limiter refilling `rate` tokens per second, holding at most
+    /// `burst`. It starts full.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rate` is not a positive number or `burst` is zero.
+    pub fn new(rate: f64, burst: u32) -> Self {
+        assert!(rate > 0.0 && rate.is_finite(), "rate must be a positive number");
+        assert!(burst > 0, "burst must be at least 1");
+        RateLimiter {
+            rate,
+            burst: f64::from(burst),
+            bucket: Mutex::new(Bucket {
+                tokens: f64::from(burst),
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    /// Takes a token if one is available, without waiting.
+    pub fn try_acquire(&self) -> bool {
+        let mut bucket = self.refilled();
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Takes a token, sleeping until one is available.
+    pub fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut bucket = self.refilled();
+                if bucket.tokens >= 1.0 {
+                    bucket.tokens -= 1.0;
+                    return;
+                }
+                time_for_one(bucket.tokens, self.rate)
+            };
+            // Sleep without holding the lock; another thread may take the
+            // token first, in which case we go round again
+            thread::sleep(wait);
+        }
+    }
+
+    /// The number of whole tokens available right now.
+    pub fn available(&self) -> u32 {
+        self.refilled().tokens as u32
+    }
+
+    /// Locks the bucket after topping it up for the time since the last
+    /// refill.
+    fn refilled(&self) -> MutexGuard<'_, Bucket> {
+        let mut bucket = self.bucket.lock().unwrap_or_else(PoisonError::into_inner);
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.rate).min(self.burst);
+        bucket.last_refill = now;
+        bucket
+    }
+}
+
+/// How long a bucket holding `tokens` takes to fill up to one. A rate so
+/// slow that the wait doesn't fit in a `Duration` waits as long as one can.
+fn time_for_one(tokens: f64, rate: f64) -> Duration {
+    Duration::try_from_secs_f64((1.0 - tokens) / rate).unwrap_or(Duration::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_burst_then_empty() {
+        let limiter = RateLimiter::new(1.0, 3);
+        assert_eq!(limiter.available(), 3);
+        assert!((0..3).all(|_| limiter.try_acquire()));
+        assert!(!limiter.try_acquire());
+        assert_eq!(limiter.available(), 0);
+    }
+
+    #[test]
+    fn test_refills_over_time() {
+        let limiter = RateLimiter::new(100.0, 1);
+        assert!(limiter.try_acquire());
+        assert!(!limiter.try_acquire());
+        thread::sleep(Duration::from_millis(30));
+        assert!(limiter.try_acquire());
+    }
+
+    #[test]
+    fn test_acquire_waits_for_a_token() {
+        let limiter = RateLimiter::new(50.0, 1);
+        let start = Instant::now();
+        limiter.acquire();
+        limiter.acquire();
+        limiter.acquire();
+        // Two refills at 50 per second take about 40ms
+        assert!(start.elapsed() >= Duration::from_millis(30));
+    }
+
+    #[test]
+    fn test_shared_between_threads() {
+        let limiter = Arc::new(RateLimiter::new(0.01, 5));
+        let granted = Arc::new(AtomicUsize::new(0));
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let limiter = Arc::clone(&limiter);
+                let granted = Arc::clone(&granted);
+                thread::spawn(move || {
+                    for _ in 0..5 {
+                        if limiter.try_acquire() {
+                            granted.fetch_add(1, Ordering::SeqCst);
+                        }
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(granted.load(Ordering::SeqCst), 5);
+    }
+
+    #[test]
+    fn test_time_for_one_saturates() {
+        assert_eq!(time_for_one(0.5, 2.0), Duration::from_millis(250));
+        assert_eq!(time_for_one(0.0, 1e-20), Duration::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "rate must be a positive number")]
+    fn test_rejects_zero_rate() {
+        RateLimiter::new(0.0, 1);
+    }
+}
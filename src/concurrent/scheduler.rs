@@ -0,0 +1,468 @@
+//! Running jobs after a delay or at a fixed interval.
+//!
+//! One timer thread keeps the pending runs in a min-heap ordered by due
+//! time. It sleeps on a `Condvar` until the earliest one is due, or until a
+//! newly scheduled job might be due sooner, then runs the job itself. Jobs
+//! should therefore be short; hand long work to a [`ThreadPool`] from the
+//! job.
+//!
+//! Every scheduled job has its own [`CancellationToken`], and the scheduler
+//! as a whole can be tied to a shared token that stops it.
+//!
+//! [`ThreadPool`]: super::ThreadPool
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use super::{CallbackId, CancellationToken};
+
+enum Job {
+    Once(Box<dyn FnOnce() + Send>),
+    Every(Box<dyn FnMut() + Send>, Duration),
+}
+
+struct Task {
+    job: Job,
+    token: CancellationToken,
+    runs: Arc<AtomicUsize>,
+}
+
+struct State {
+    /// Due times, soonest first. An entry whose task has been cancelled is
+    /// skipped when it comes up.
+    due: BinaryHeap<Reverse<(Instant, u64)>>,
+    tasks: HashMap<u64, Task>,
+    next_id: u64,
+    shutting_down: bool,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    /// Signalled when a job is scheduled or shutdown starts.
+    changed: Condvar,
+    panicked: AtomicUsize,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Stops the timer thread and cancels every job still pending.
+    fn begin_shutdown(&self) {
+        let pending: Vec<Task> = {
+            let mut state = self.lock();
+            state.shutting_down = true;
+            state.due.clear();
+            state.tasks.drain().map(|(_, task)| task).collect()
+        };
+        self.changed.notify_all();
+        // Outside the lock: each cancel runs a callback that takes it
+        for task in pending {
+            task.token.cancel();
+        }
+    }
+}
+
+/// Runs jobs on a timer thread after a delay or repeatedly.
+///
+/// Dropping the scheduler cancels every pending job and waits for a job
+/// that is running to finish.
+///
+/// ```
+/// use rustler::concurrent::Scheduler;
+/// use std::sync::mpsc;
+/// use std::time::Duration;
+///
+/// let scheduler = Scheduler::new();
+/// let (tx, rx) = mpsc::channel();
+///
+/// let later = tx.clone();
+/// scheduler.schedule_after(Duration::from_millis(30), move || later.send("later").unwrap());
+/// scheduler.schedule_after(Duration::from_millis(10), move || tx.send("sooner").unwrap());
+///
+/// assert_eq!(rx.recv().unwrap(), "sooner");
+/// assert_eq!(rx.recv().unwrap(), "later");
+/// ```
+pub struct Scheduler {
+    shared: Arc<Shared>,
+    thread: Option<JoinHandle<()>>,
+    /// The callback that stops the scheduler, removed from the token on
+    /// drop.
+    on_cancel: (CancellationToken, CallbackId),
+}
+
+impl Scheduler {
+    /// Starts a scheduler and its timer thread.
+    pub fn new() -> Self {
+        Scheduler::with_token(CancellationToken::new())
+    }
+
+    /// Starts a scheduler that shuts down when `token` is cancelled, for
+    /// stopping it together with the pools sharing the same token.
+    pub fn with_token(token: CancellationToken) -> Self {
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State {
+                due: BinaryHeap::new(),
+                tasks: HashMap::new(),
+                next_id: 0,
+                shutting_down: false,
+            }),
+            changed: Condvar::new(),
+            panicked: AtomicUsize::new(0),
+        });
+        let thread = {
+            let shared = Arc::clone(&shared);
+            thread::Builder::new()
+                .name("scheduler".to_string())
+                .spawn(move || run_timer(&shared))
+                .expect("failed to spawn the scheduler thread")
+        };
+        let weak = Arc::downgrade(&shared);
+        let id = token.on_cancel(move || {
+            if let Some(shared) = weak.upgrade() {
+                shared.begin_shutdown();
+            }
+        });
+        Scheduler {
+            shared,
+            thread: Some(thread),
+            on_cancel: (token, id),
+        }
+    }
+
+    /// Runs `job` once, `delay` from now.
+    ///
+    /// A delay too long to represent as an [`Instant`] never comes due, so
+    /// the job stays pending until it is cancelled.
+    pub fn schedule_after<F>(&self, delay: Duration, job: F) -> ScheduledTask
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.schedule(delay, Job::Once(Box::new(job)))
+    }
+
+    /// Runs `job` every `period`, starting `period` from now, until the
+    /// returned handle is cancelled.
+    ///
+    /// Runs keep to the original timetable rather than drifting by the
+    /// time each run takes. If a run overruns a whole period, the missed
+    /// runs are skipped rather than made up in a burst.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `period` is zero.
+    pub fn schedule_every<F>(&self, period: Duration, job: F) -> ScheduledTask
+    where
+        F: FnMut() + Send + 'static,
+    {
+        assert!(!period.is_zero(), "a repeating job needs a non-zero period");
+        self.schedule(period, Job::Every(Box::new(job), period))
+    }
+
+    /// Jobs waiting for their next run.
+    pub fn pending(&self) -> usize {
+        self.shared.lock().tasks.len()
+    }
+
+    /// Runs that panicked. A repeating job is cancelled after it panics.
+    pub fn panicked(&self) -> usize {
+        self.shared.panicked.load(Ordering::SeqCst)
+    }
+
+    /// Cancels every pending job and waits for the timer thread to exit.
+    pub fn shutdown(mut self) {
+        self.stop();
+    }
+
+    fn schedule(&self, delay: Duration, job: Job) -> ScheduledTask {
+        let token = CancellationToken::new();
+        let runs = Arc::new(AtomicUsize::new(0));
+        {
+            let mut state = self.shared.lock();
+            if state.shutting_down {
+                // Too late to run; hand back a handle that says so
+                drop(state);
+                token.cancel();
+                return ScheduledTask { token, runs };
+            }
+            let id = state.next_id;
+            state.next_id += 1;
+            if let Some(due_at) = Instant::now().checked_add(delay) {
+                state.due.push(Reverse((due_at, id)));
+            }
+            state.tasks.insert(
+                id,
+                Task {
+                    job,
+                    token: token.clone(),
+                    runs: Arc::clone(&runs),
+                },
+            );
+            // Forget a cancelled job straight away instead of at its due time
+            let weak = Arc::downgrade(&self.shared);
+            drop(state);
+            token.on_cancel(move || {
+                if let Some(shared) = weak.upgrade() {
+                    shared.lock().tasks.remove(&id);
+                }
+            });
+        }
+        self.shared.changed.notify_one();
+        ScheduledTask { token, runs }
+    }
+
+    fn stop(&mut self) {
+        self.shared.begin_shutdown();
+        if let Some(thread) = self.thread.take() {
+            // Jobs run under catch_unwind, so the timer thread never panics
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Default for Scheduler {
+    fn default() -> Self {
+        Scheduler::new()
+    }
+}
+
+impl Drop for Scheduler {
+    fn drop(&mut self) {
+        let (token, id) = &self.on_cancel;
+        token.remove_callback(*id);
+        self.stop();
+    }
+}
+
+/// Sleeps until the soonest job is due, runs it, and reschedules it if it
+/// repeats, until shutdown.
+fn run_timer(shared: &Shared) {
+    loop {
+        let mut state = shared.lock();
+        let (due_at, id) = loop {
+            if state.shutting_down {
+                return;
+            }
+            let now = Instant::now();
+            match state.due.peek() {
+                Some(&Reverse((due_at, id))) if due_at <= now => {
+                    state.due.pop();
+                    break (due_at, id);
+                }
+                Some(&Reverse((due_at, _))) => {
+                    state = shared
+                        .changed
+                        .wait_timeout(state, due_at - now)
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .0;
+                }
+                None => state = shared.changed.wait(state).unwrap_or_else(PoisonError::into_inner),
+            }
+        };
+        // Absent if the job was cancelled while it waited
+        let Some(Task { job, token, runs }) = state.tasks.remove(&id) else {
+            continue;
+        };
+        drop(state);
+
+        // Counted before the run, so whatever the job does to announce
+        // itself cannot be observed ahead of the count
+        runs.fetch_add(1, Ordering::SeqCst);
+        let (outcome, repeat) = match job {
+            Job::Once(job) => (panic::catch_unwind(AssertUnwindSafe(job)), None),
+            Job::Every(mut job, period) => (panic::catch_unwind(AssertUnwindSafe(&mut job)), Some((job, period))),
+        };
+        if outcome.is_err() {
+            shared.panicked.fetch_add(1, Ordering::SeqCst);
+            token.cancel();
+            continue;
+        }
+        let Some((job, period)) = repeat else {
+            continue;
+        };
+
+        let next = next_due(due_at, period, Instant::now());
+        let mut state = shared.lock();
+        // Checked under the lock, so a cancel cannot slip in between the
+        // check and the reinsert and leave the job running forever
+        if state.shutting_down || token.is_cancelled() {
+            continue;
+        }
+        if let Some(next) = next {
+            state.due.push(Reverse((next, id)));
+        }
+        let job = Job::Every(job, period);
+        state.tasks.insert(id, Task { job, token, runs });
+    }
+}
+
+/// The first tick of `period` after `due_at` that is later than `now`, or
+/// `None` if that is too far off to represent as an Instant.
+fn next_due(due_at: Instant, period: Duration, now: Instant) -> Option<Instant> {
+    let ticks = now.saturating_duration_since(due_at).as_nanos() / period.as_nanos() + 1;
+    let offset = u64::try_from(ticks * period.as_nanos()).ok()?;
+    due_at.checked_add(Duration::from_nanos(offset))
+}
+
+/// A handle to a job scheduled on a [`Scheduler`].
+///
+/// Dropping the handle does not cancel the job.
+#[derive(Debug, Clone)]
+pub struct ScheduledTask {
+    token: CancellationToken,
+    runs: Arc<AtomicUsize>,
+}
+
+impl ScheduledTask {
+    /// Stops the job from running again. A run already in progress
+    /// finishes.
+    pub fn cancel(&self) {
+        self.token.cancel();
+    }
+
+    /// Whether the job was cancelled, either directly, by a panic in a
+    /// repeating job, or by the scheduler shutting down.
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// How many times the job has run, counting a run in progress.
+    pub fn runs(&self) -> usize {
+        self.runs.load(Ordering::SeqCst)
+    }
+
+    /// The job's cancellation token, for the job itself to watch or for
+    /// cancelling it from elsewhere.
+    pub fn token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn test_jobs_run_in_due_order() {
+        let scheduler = Scheduler::new();
+        let (tx, rx) = mpsc::channel();
+        for delay in [40, 10, 25] {
+            let tx = tx.clone();
+            scheduler.schedule_after(Duration::from_millis(delay), move || tx.send(delay).unwrap());
+        }
+        drop(tx);
+        let order: Vec<u64> = (0..3).map(|_| rx.recv_timeout(Duration::from_secs(5)).unwrap()).collect();
+        assert_eq!(order, vec![10, 25, 40]);
+    }
+
+    #[test]
+    fn test_schedule_after_waits_for_the_delay() {
+        let scheduler = Scheduler::new();
+        let (tx, rx) = mpsc::channel();
+        let start = Instant::now();
+        let task = scheduler.schedule_after(Duration::from_millis(30), move || tx.send(start.elapsed()).unwrap());
+        assert!(rx.recv().unwrap() >= Duration::from_millis(30));
+        assert_eq!(task.runs(), 1);
+        assert_eq!(scheduler.pending(), 0);
+    }
+
+    #[test]
+    fn test_schedule_every_repeats_until_cancelled() {
+        let scheduler = Scheduler::new();
+        let (tx, rx) = mpsc::channel();
+        let task = scheduler.schedule_every(Duration::from_millis(5), move || {
+            let _ = tx.send(());
+        });
+        for _ in 0..3 {
+            rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        }
+        task.cancel();
+        let runs = task.runs();
+        assert!(runs >= 3);
+        thread::sleep(Duration::from_millis(30));
+        // At most one run can have been in progress when it was cancelled
+        assert!(task.runs() <= runs + 1);
+        assert_eq!(scheduler.pending(), 0);
+    }
+
+    #[test]
+    fn test_cancelled_job_never_runs() {
+        let scheduler = Scheduler::new();
+        let ran = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&ran);
+        let task = scheduler.schedule_after(Duration::from_millis(20), move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+        task.cancel();
+        assert!(task.is_cancelled());
+        assert_eq!(scheduler.pending(), 0);
+        thread::sleep(Duration::from_millis(40));
+        assert_eq!(ran.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_panicking_repeating_job_is_cancelled() {
+        let scheduler = Scheduler::new();
+        let task = scheduler.schedule_every(Duration::from_millis(5), || panic!("tick failed"));
+        let (tx, rx) = mpsc::channel();
+        scheduler.schedule_after(Duration::from_millis(30), move || tx.send(()).unwrap());
+        rx.recv().unwrap();
+        assert_eq!(task.runs(), 1);
+        assert!(task.is_cancelled());
+        assert_eq!(scheduler.panicked(), 1);
+    }
+
+    #[test]
+    fn test_shared_token_shuts_the_scheduler_down() {
+        let token = CancellationToken::new();
+        let scheduler = Scheduler::with_token(token.clone());
+        let task = scheduler.schedule_after(Duration::from_secs(60), || {});
+        token.cancel();
+        assert!(task.is_cancelled());
+        assert_eq!(scheduler.pending(), 0);
+        // Scheduling after shutdown gives back an already cancelled handle
+        assert!(scheduler.schedule_after(Duration::ZERO, || {}).is_cancelled());
+        scheduler.shutdown();
+    }
+
+    #[test]
+    fn test_delay_past_the_end_of_time_is_never_due() {
+        let scheduler = Scheduler::new();
+        let once = scheduler.schedule_after(Duration::MAX, || {});
+        let every = scheduler.schedule_every(Duration::MAX, || {});
+        assert_eq!(scheduler.pending(), 2);
+        thread::sleep(Duration::from_millis(20));
+        assert_eq!((once.runs(), every.runs()), (0, 0));
+        once.cancel();
+        every.cancel();
+        assert_eq!(scheduler.pending(), 0);
+    }
+
+    #[test]
+    fn test_next_due_skips_missed_ticks() {
+        let start = Instant::now();
+        let period = Duration::from_millis(10);
+        assert_eq!(next_due(start, period, start), Some(start + period));
+        assert_eq!(next_due(start, period, start + Duration::from_millis(35)), Some(start + period * 4));
+        // Landing exactly on a tick counts as missing it
+        assert_eq!(next_due(start, period, start + period * 2), Some(start + period * 3));
+        // A tiny period far behind is caught up in one step
+        let tick = Duration::from_nanos(1);
+        let behind = Duration::from_secs(1);
+        assert_eq!(next_due(start, tick, start + behind), Some(start + behind + tick));
+        assert_eq!(next_due(start, Duration::MAX, start), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "non-zero period")]
+    fn test_zero_period_panics() {
+        Scheduler::new().schedule_every(Duration::ZERO, || {});
+    }
+}
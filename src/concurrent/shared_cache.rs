@@ -0,0 +1,228 @@
+//! A thread-safe cache for read-heavy workloads.
+//!
+//! The map sits behind an `RwLock`, which lets any number of readers in at
+//! once and only makes them wait while a writer holds the lock. When most
+//! calls are lookups that hit and several cores are reading at once, that
+//! beats a `Mutex`, which serialises every call; `benches/cache.rs`
+//! measures the difference.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{Arc, PoisonError, RwLock, RwLockWriteGuard};
+use std::time::{Duration, Instant};
+
+struct Entry<V> {
+    value: V,
+    expires_at: Option<Instant>,
+}
+
+impl<V> Entry<V> {
+    /// Only reads the clock for entries that can expire, keeping lookups
+    /// in a TTL-free cache cheap.
+    fn is_live(&self) -> bool {
+        self.expires_at.is_none_or(|deadline| Instant::now() < deadline)
+    }
+}
+
+/// A `HashMap` shared between threads, with optional expiry.
+///
+/// Cloning the cache gives another handle to the same map.
+///
+/// ```
+/// use rustler::concurrent::SharedCache;
+///
+/// let cache = SharedCache::new();
+/// let worker_view = cache.clone();
+///
+/// let page = cache.get_or_insert_with("/index", || "<h1>Home</h1>".to_string());
+/// assert_eq!(page, "<h1>Home</h1>");
+/// // The second lookup is served from the cache, not recomputed
+/// let again = worker_view.get_or_insert_with("/index", || unreachable!());
+/// assert_eq!(again, page);
+/// ```
+pub struct SharedCache<K, V> {
+    entries: Arc<RwLock<HashMap<K, Entry<V>>>>,
+    ttl: Option<Duration>,
+}
+
+impl<K, V> SharedCache<K, V>
+where
+    K: Hash + Eq,
+    V: Clone,
+{
+    /// A cache whose entries never expire.
+    pub fn new() -> Self {
+        SharedCache {
+            entries: Arc::new(RwLock::new(HashMap::new())),
+            ttl: None,
+        }
+    }
+
+    /// A cache whose entries expire `ttl` after they were inserted.
+    pub fn with_ttl(ttl: Duration) -> Self {
+        SharedCache {
+            ttl: Some(ttl),
+            ..SharedCache::new()
+        }
+    }
+
+    /// A clone of the value for `key`, unless it is missing or expired.
+    pub fn get(&self, key: &K) -> Option<V> {
+        let entries = self.entries.read().unwrap_or_else(PoisonError::into_inner);
+        entries
+            .get(key)
+            .filter(|entry| entry.is_live())
+            .map(|entry| entry.value.clone())
+    }
+
+    /// Stores `value`, replacing any earlier value and restarting its TTL.
+    pub fn insert(&self, key: K, value: V) {
+        let entry = self.entry(value);
+        self.write().insert(key, entry);
+    }
+
+    /// The cached value for `key`, or the result of `make` after storing
+    /// it.
+    ///
+    /// The fast path takes only the read lock. On a miss `make` runs under
+    /// the write lock, so concurrent callers asking for the same key wait
+    /// and then find the value instead of computing it again.
+    pub fn get_or_insert_with<F>(&self, key: K, make: F) -> V
+    where
+        F: FnOnce() -> V,
+    {
+        if let Some(value) = self.get(&key) {
+            return value;
+        }
+        let mut entries = self.write();
+        // Another thread may have filled it between our read and write
+        if let Some(entry) = entries.get(&key).filter(|entry| entry.is_live()) {
+            return entry.value.clone();
+        }
+        let value = make();
+        entries.insert(key, self.entry(value.clone()));
+        value
+    }
+
+    /// Removes `key`, returning its value if it was present and live.
+    pub fn remove(&self, key: &K) -> Option<V> {
+        let entry = self.write().remove(key)?;
+        entry.is_live().then_some(entry.value)
+    }
+
+    /// Drops every expired entry, returning how many were removed. Expired
+    /// entries are never returned anyway; this just frees their memory.
+    pub fn purge_expired(&self) -> usize {
+        let mut entries = self.write();
+        let before = entries.len();
+        entries.retain(|_, entry| entry.is_live());
+        before - entries.len()
+    }
+
+    /// The number of stored entries, including expired ones not yet
+    /// purged.
+    pub fn len(&self) -> usize {
+        self.entries.read().unwrap_or_else(PoisonError::into_inner).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn entry(&self, value: V) -> Entry<V> {
+        Entry {
+            value,
+            // A TTL too long to represent as an Instant never runs out
+            expires_at: self.ttl.and_then(|ttl| Instant::now().checked_add(ttl)),
+        }
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, HashMap<K, Entry<V>>> {
+        self.entries.write().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<K, V> Clone for SharedCache<K, V> {
+    fn clone(&self) -> Self {
+        SharedCache {
+            entries: Arc::clone(&self.entries),
+            ttl: self.ttl,
+        }
+    }
+}
+
+impl<K: Hash + Eq, V: Clone> Default for SharedCache<K, V> {
+    fn default() -> Self {
+        SharedCache::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+
+    #[test]
+    fn test_insert_get_remove() {
+        let cache = SharedCache::new();
+        assert!(cache.is_empty());
+        cache.insert("a", 1);
+        cache.insert("a", 2);
+        assert_eq!(cache.get(&"a"), Some(2));
+        assert_eq!(cache.get(&"b"), None);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.remove(&"a"), Some(2));
+        assert_eq!(cache.remove(&"a"), None);
+    }
+
+    #[test]
+    fn test_entries_expire() {
+        let cache = SharedCache::with_ttl(Duration::from_millis(20));
+        cache.insert(1, "short-lived");
+        assert_eq!(cache.get(&1), Some("short-lived"));
+        thread::sleep(Duration::from_millis(40));
+        assert_eq!(cache.get(&1), None);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.purge_expired(), 1);
+        assert!(cache.is_empty());
+        // An expired entry is recomputed
+        assert_eq!(cache.get_or_insert_with(1, || "fresh"), "fresh");
+    }
+
+    #[test]
+    fn test_huge_ttl_never_expires() {
+        let cache = SharedCache::with_ttl(Duration::MAX);
+        cache.insert(1, "forever");
+        assert_eq!(cache.get(&1), Some("forever"));
+        assert_eq!(cache.purge_expired(), 0);
+        assert_eq!(cache.get_or_insert_with(2, || "also forever"), "also forever");
+    }
+
+    #[test]
+    fn test_value_is_computed_once_across_threads() {
+        let cache = SharedCache::new();
+        let calls = AtomicUsize::new(0);
+        thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    let value = cache.get_or_insert_with("config", || {
+                        calls.fetch_add(1, Ordering::SeqCst);
+                        thread::sleep(Duration::from_millis(10));
+                        42
+                    });
+                    assert_eq!(value, 42);
+                });
+            }
+        });
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_clones_share_entries() {
+        let cache = SharedCache::new();
+        let other = cache.clone();
+        thread::spawn(move || other.insert(7, "seven")).join().unwrap();
+        assert_eq!(cache.get(&7), Some("seven"));
+    }
+}
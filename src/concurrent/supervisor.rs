@@ -0,0 +1,276 @@
+//! Restarting threads that panic, in the style of Erlang supervisors.
+//!
+//! A supervised thread runs its closure under `catch_unwind`. If the
+//! closure panics, the supervisor logs it through the global [`log_lite`]
+//! logger, waits, and runs the closure again, doubling the wait each time,
+//! until it returns normally or the restart budget is spent.
+
+use std::error::Error;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use super::thread_pool::panic_message;
+use crate::log_lite;
+
+/// Spawns named threads that are restarted when they panic.
+///
+/// ```
+/// use rustler::concurrent::Supervisor;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use std::sync::Arc;
+/// use std::time::Duration;
+///
+/// let attempts = Arc::new(AtomicUsize::new(0));
+/// let counter = Arc::clone(&attempts);
+/// let supervisor = Supervisor::new().with_backoff(Duration::from_millis(1));
+///
+/// // Fails twice, then succeeds on the third run
+/// let handle = supervisor.spawn("flaky", move || {
+///     if counter.fetch_add(1, Ordering::SeqCst) < 2 {
+///         panic!("not yet");
+///     }
+///     "connected"
+/// });
+/// assert_eq!(handle.join(), Ok("connected"));
+/// assert_eq!(attempts.load(Ordering::SeqCst), 3);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Supervisor {
+    max_restarts: usize,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl Supervisor {
+    /// A supervisor allowing 3 restarts, waiting 100ms before the first
+    /// and doubling up to 5s.
+    pub fn new() -> Self {
+        Supervisor {
+            max_restarts: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+        }
+    }
+
+    /// How many times a thread may be restarted before the supervisor
+    /// gives up on it.
+    pub fn with_max_restarts(mut self, max_restarts: usize) -> Self {
+        self.max_restarts = max_restarts;
+        self
+    }
+
+    /// The wait before the first restart; each later wait doubles it.
+    pub fn with_backoff(mut self, initial: Duration) -> Self {
+        self.initial_backoff = initial;
+        self
+    }
+
+    /// The longest wait between restarts, the first one included.
+    pub fn with_max_backoff(mut self, max: Duration) -> Self {
+        self.max_backoff = max;
+        self
+    }
+
+    /// Runs `work` on a new thread called `name`, restarting it after each
+    /// panic until it returns or the restart budget runs out.
+    pub fn spawn<T, F>(&self, name: &str, work: F) -> SupervisedHandle<T>
+    where
+        T: Send + 'static,
+        F: Fn() -> T + Send + 'static,
+    {
+        let policy = self.clone();
+        let restarts = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&restarts);
+        let thread_name = name.to_string();
+        let handle = thread::Builder::new()
+            .name(name.to_string())
+            .spawn(move || policy.supervise(&thread_name, &counter, work))
+            .expect("failed to spawn a supervised thread");
+        SupervisedHandle { handle, restarts }
+    }
+
+    fn supervise<T>(&self, name: &str, restarts: &AtomicUsize, work: impl Fn() -> T) -> Result<T, SupervisorError> {
+        let mut backoff = self.initial_backoff.min(self.max_backoff);
+        loop {
+            let payload = match panic::catch_unwind(AssertUnwindSafe(&work)) {
+                Ok(value) => return Ok(value),
+                Err(payload) => payload,
+            };
+            let message = panic_message(payload.as_ref());
+            let restarted = restarts.load(Ordering::SeqCst);
+            if restarted >= self.max_restarts {
+                log_lite::global().error(
+                    "supervisor",
+                    format!("{} panicked ({}); giving up after {} restarts", name, message, restarted),
+                );
+                return Err(SupervisorError {
+                    name: name.to_string(),
+                    restarts: restarted,
+                    last_panic: message,
+                });
+            }
+            log_lite::global().warn(
+                "supervisor",
+                format!(
+                    "{} panicked ({}); restart {} of {} in {:?}",
+                    name,
+                    message,
+                    restarted + 1,
+                    self.max_restarts,
+                    backoff
+                ),
+            );
+            thread::sleep(backoff);
+            backoff = self.next_backoff(backoff);
+            restarts.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    /// The wait after `backoff`: twice as long, up to the cap. Doubling
+    /// saturates, so a huge backoff stays at the cap rather than panicking.
+    fn next_backoff(&self, backoff: Duration) -> Duration {
+        backoff.saturating_mul(2).min(self.max_backoff)
+    }
+}
+
+impl Default for Supervisor {
+    fn default() -> Self {
+        Supervisor::new()
+    }
+}
+
+/// A thread started by [`Supervisor::spawn`].
+pub struct SupervisedHandle<T> {
+    handle: JoinHandle<Result<T, SupervisorError>>,
+    restarts: Arc<AtomicUsize>,
+}
+
+impl<T> SupervisedHandle<T> {
+    /// How many times the work has been restarted so far.
+    pub fn restarts(&self) -> usize {
+        self.restarts.load(Ordering::SeqCst)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+
+    /// Waits for the work to return, or for the supervisor to give up.
+    pub fn join(self) -> Result<T, SupervisorError> {
+        // The supervising loop catches every panic from the work itself
+        self.handle.join().unwrap_or_else(|payload| panic::resume_unwind(payload))
+    }
+}
+
+/// A supervised thread kept panicking after its last allowed restart.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SupervisorError {
+    pub name: String,
+    pub restarts: usize,
+    /// The message from the final panic.
+    pub last_panic: String,
+}
+
+impl fmt::Display for SupervisorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} failed after {} restarts: {}",
+            self.name, self.restarts, self.last_panic
+        )
+    }
+}
+
+impl Error for SupervisorError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    fn quick() -> Supervisor {
+        Supervisor::new().with_backoff(Duration::from_millis(1))
+    }
+
+    #[test]
+    fn test_returns_value_without_restarts() {
+        let handle = quick().spawn("calm", || 7);
+        assert_eq!(handle.join(), Ok(7));
+    }
+
+    #[test]
+    fn test_gives_up_after_budget() {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&runs);
+        let handle = quick().with_max_restarts(2).spawn("doomed", move || -> () {
+            counter.fetch_add(1, Ordering::SeqCst);
+            panic!("attempt failed");
+        });
+        let err = handle.join().unwrap_err();
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+        assert_eq!(err.restarts, 2);
+        assert_eq!(err.to_string(), "doomed failed after 2 restarts: attempt failed");
+    }
+
+    #[test]
+    fn test_zero_budget_never_restarts() {
+        let handle = quick().with_max_restarts(0).spawn("once", || -> () { panic!("no retry") });
+        assert_eq!(handle.join().unwrap_err().restarts, 0);
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_the_cap() {
+        let supervisor = Supervisor::new()
+            .with_max_restarts(3)
+            .with_backoff(Duration::from_millis(10))
+            .with_max_backoff(Duration::from_millis(15));
+        let start = Instant::now();
+        let handle = supervisor.spawn("slow", || -> () { panic!("boom") });
+        assert!(handle.join().is_err());
+        // Waits of 10ms, 15ms (capped from 20) and 15ms (capped from 40)
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(40), "took {:?}", elapsed);
+    }
+
+    #[test]
+    fn test_cap_applies_to_the_first_wait() {
+        let supervisor = Supervisor::new()
+            .with_max_restarts(1)
+            .with_backoff(Duration::from_secs(60))
+            .with_max_backoff(Duration::from_millis(1));
+        let start = Instant::now();
+        let handle = supervisor.spawn("capped", || -> () { panic!("boom") });
+        assert!(handle.join().is_err());
+        let elapsed = start.elapsed();
+        assert!(elapsed < Duration::from_secs(10), "took {:?}", elapsed);
+    }
+
+    #[test]
+    fn test_next_backoff_saturates() {
+        let supervisor = Supervisor::new().with_max_backoff(Duration::MAX);
+        assert_eq!(supervisor.next_backoff(Duration::from_millis(5)), Duration::from_millis(10));
+        assert_eq!(supervisor.next_backoff(Duration::MAX), Duration::MAX);
+        let capped = Supervisor::new().with_max_backoff(Duration::from_secs(1));
+        assert_eq!(capped.next_backoff(Duration::MAX), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_restart_count_is_visible() {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&runs);
+        let handle = quick().spawn("flaky", move || {
+            if counter.fetch_add(1, Ordering::SeqCst) == 0 {
+                panic!("first run fails");
+            }
+        });
+        while !handle.is_finished() {
+            thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(handle.restarts(), 1);
+        assert!(handle.join().is_ok());
+    }
+}
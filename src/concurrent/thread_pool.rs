@@ -0,0 +1,602 @@
+//! A fixed-size pool of worker threads pulling jobs from a shared queue.
+//!
+//! Jobs wait in a [`Queue`] behind a `Mutex`; idle workers sleep on a
+//! `Condvar` until a job arrives or the pool shuts down. Each job runs
+//! inside `catch_unwind`, so a panicking job is counted and reported but
+//! the worker that ran it carries on with the next one.
+//!
+//! [`ThreadPool::submit`] additionally hands back a [`JobHandle`] that
+//! receives the job's return value, or the panic, over a one-shot channel.
+//!
+//! Every pool has a [`CancellationToken`]. Cancelling it stops the pool
+//! taking new jobs and discards the queue, and jobs can watch the same
+//! token to stop early. A pool started with a shared token watches a
+//! private child of it, so shutting one pool down leaves its siblings be.
+
+use std::any::Any;
+use std::error::Error;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use super::{CallbackId, CancellationToken};
+use crate::collections::Queue;
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+struct State {
+    jobs: Queue<Job>,
+    /// Set once shutdown starts; workers exit when this is set and the
+    /// queue is empty.
+    shutting_down: bool,
+    /// Workers that have not exited yet.
+    running_workers: usize,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    /// Signalled when a job is queued or shutdown starts.
+    job_available: Condvar,
+    /// Signalled when a worker exits.
+    worker_exited: Condvar,
+    completed: AtomicUsize,
+    panicked: AtomicUsize,
+    /// Workers check this before taking each job, so none starts after a
+    /// cancel even if the queue has not been emptied yet. Private to the
+    /// pool, and cancelled in turn by the token it was started with.
+    token: CancellationToken,
+}
+
+impl Shared {
+    /// Locks the state. Jobs never run while the lock is held, so a
+    /// poisoned lock still holds consistent data and can be used as is.
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Tells the workers to exit once the queue is empty, first emptying
+    /// it if `discard_queued` is set. Returns how many jobs were dropped.
+    fn begin_shutdown(&self, discard_queued: bool) -> usize {
+        let discarded = {
+            let mut state = self.lock();
+            state.shutting_down = true;
+            if discard_queued {
+                state.jobs.drain().count()
+            } else {
+                0
+            }
+        };
+        self.job_available.notify_all();
+        discarded
+    }
+}
+
+/// Runs closures on a fixed number of worker threads.
+///
+/// Dropping the pool behaves like [`ThreadPool::shutdown`] without a time
+/// limit: queued jobs still run, and the drop waits for them.
+///
+/// ```
+/// use rustler::concurrent::ThreadPool;
+/// use std::sync::mpsc;
+/// use std::time::Duration;
+///
+/// let pool = ThreadPool::new(4);
+/// let (tx, rx) = mpsc::channel();
+/// for n in 0..8 {
+///     let tx = tx.clone();
+///     pool.execute(move || tx.send(n * n).unwrap());
+/// }
+/// // A panicking job does not take its worker down with it
+/// pool.execute(|| panic!("bad job"));
+///
+/// assert!(pool.shutdown(Duration::from_secs(5)));
+/// let mut squares: Vec<i32> = rx.try_iter().collect();
+/// squares.sort();
+/// assert_eq!(squares, vec![0, 1, 4, 9, 16, 25, 36, 49]);
+/// ```
+pub struct ThreadPool {
+    shared: Arc<Shared>,
+    workers: Vec<JoinHandle<()>>,
+    /// The callback that passes a cancel of the shared token on to the
+    /// pool's own, removed on drop so a token shared by many short-lived
+    /// pools does not collect a callback for each of them.
+    on_cancel: (CancellationToken, CallbackId),
+}
+
+impl ThreadPool {
+    /// Starts a pool with `size` worker threads.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    pub fn new(size: usize) -> Self {
+        ThreadPool::with_token(size, CancellationToken::new())
+    }
+
+    /// Starts a pool that shuts down like [`shutdown_now`] when `token` is
+    /// cancelled. Sharing one token between several pools and schedulers
+    /// lets a single `cancel()` stop them all, while [`shutdown_now`] on
+    /// one pool stops only that pool.
+    ///
+    /// [`shutdown_now`]: ThreadPool::shutdown_now
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    pub fn with_token(size: usize, token: CancellationToken) -> Self {
+        assert!(size > 0, "a thread pool needs at least one worker");
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State {
+                jobs: Queue::new(),
+                shutting_down: false,
+                running_workers: size,
+            }),
+            job_available: Condvar::new(),
+            worker_exited: Condvar::new(),
+            completed: AtomicUsize::new(0),
+            panicked: AtomicUsize::new(0),
+            token: CancellationToken::new(),
+        });
+        let workers = (0..size)
+            .map(|id| {
+                let shared = Arc::clone(&shared);
+                thread::Builder::new()
+                    .name(format!("pool-worker-{}", id))
+                    .spawn(move || run_worker(&shared))
+                    .expect("failed to spawn a pool worker")
+            })
+            .collect();
+        // A weak reference, so the token does not keep a dropped pool alive
+        let weak = Arc::downgrade(&shared);
+        shared.token.on_cancel(move || {
+            if let Some(shared) = weak.upgrade() {
+                shared.begin_shutdown(true);
+            }
+        });
+        let own = shared.token.clone();
+        let id = token.on_cancel(move || own.cancel());
+        ThreadPool {
+            shared,
+            workers,
+            on_cancel: (token, id),
+        }
+    }
+
+    /// A clone of the pool's own cancellation token, for jobs to watch or
+    /// for another thread to cancel the pool with. It is cancelled along
+    /// with the token the pool was started with, but cancelling it stops
+    /// only this pool.
+    pub fn token(&self) -> CancellationToken {
+        self.shared.token.clone()
+    }
+
+    /// The number of worker threads.
+    pub fn size(&self) -> usize {
+        self.workers.len()
+    }
+
+    /// Queues `job` to run on the next free worker. Once the pool's token
+    /// is cancelled the job is dropped unrun, which a [`JobHandle`] from
+    /// [`submit`](ThreadPool::submit) reports as [`JobError::Cancelled`].
+    pub fn execute<F>(&self, job: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let job: Job = Box::new(job);
+        {
+            let mut state = self.shared.lock();
+            // The workers have left, or are leaving, and would never take it
+            if self.shared.token.is_cancelled() || state.shutting_down {
+                drop(state);
+                drop(job);
+                return;
+            }
+            state.jobs.enqueue(job);
+        }
+        self.shared.job_available.notify_one();
+    }
+
+    /// Queues `job` and returns a handle for collecting its result.
+    ///
+    /// ```
+    /// use rustler::concurrent::{JobError, ThreadPool};
+    ///
+    /// let pool = ThreadPool::new(2);
+    /// let answer = pool.submit(|| 6 * 7);
+    /// let failure = pool.submit(|| -> u32 { panic!("out of cheese") });
+    ///
+    /// assert_eq!(answer.wait(), Ok(42));
+    /// assert_eq!(failure.wait(), Err(JobError::Panicked("out of cheese".to_string())));
+    /// ```
+    pub fn submit<T, F>(&self, job: F) -> JobHandle<T>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel();
+        self.execute(move || match panic::catch_unwind(AssertUnwindSafe(job)) {
+            Ok(value) => {
+                // The caller may have dropped the handle; that is fine
+                let _ = sender.send(Ok(value));
+            }
+            Err(payload) => {
+                let _ = sender.send(Err(JobError::Panicked(panic_message(payload.as_ref()))));
+                // Re-raise so the worker still counts the panic
+                panic::resume_unwind(payload);
+            }
+        });
+        JobHandle { receiver }
+    }
+
+    /// Jobs waiting for a free worker.
+    pub fn queued(&self) -> usize {
+        self.shared.lock().jobs.len()
+    }
+
+    /// Jobs that have finished running, including ones that panicked.
+    pub fn completed(&self) -> usize {
+        self.shared.completed.load(Ordering::SeqCst)
+    }
+
+    /// Jobs that panicked.
+    pub fn panicked(&self) -> usize {
+        self.shared.panicked.load(Ordering::SeqCst)
+    }
+
+    /// Stops accepting work, lets the workers finish every queued job, and
+    /// waits up to `timeout` for them to exit.
+    ///
+    /// Returns false if the time ran out first. The remaining workers are
+    /// left to finish in the background.
+    pub fn shutdown(mut self, timeout: Duration) -> bool {
+        self.shared.begin_shutdown(false);
+        let finished = {
+            let state = self.shared.lock();
+            let (state, _) = self
+                .shared
+                .worker_exited
+                .wait_timeout_while(state, timeout, |state| state.running_workers > 0)
+                .unwrap_or_else(PoisonError::into_inner);
+            state.running_workers == 0
+        };
+        if finished {
+            self.join_workers();
+        } else {
+            // Detach the stragglers so Drop does not wait for them
+            self.workers.clear();
+        }
+        finished
+    }
+
+    /// Discards every queued job, cancels the pool's own token so running
+    /// jobs that watch it can stop early, and waits for the running jobs.
+    /// A token shared through [`with_token`] is left alone. Returns how
+    /// many jobs were discarded.
+    ///
+    /// [`with_token`]: ThreadPool::with_token
+    pub fn shutdown_now(mut self) -> usize {
+        let discarded = self.shared.begin_shutdown(true);
+        self.shared.token.cancel();
+        self.join_workers();
+        discarded
+    }
+
+    fn join_workers(&mut self) {
+        for worker in self.workers.drain(..) {
+            // Jobs run under catch_unwind, so workers themselves never panic
+            let _ = worker.join();
+        }
+    }
+}
+
+impl Drop for ThreadPool {
+    fn drop(&mut self) {
+        let (token, id) = &self.on_cancel;
+        token.remove_callback(*id);
+        self.shared.begin_shutdown(false);
+        self.join_workers();
+    }
+}
+
+/// The result of a job started with [`ThreadPool::submit`].
+#[must_use = "a job's result is lost unless you wait for it"]
+pub struct JobHandle<T> {
+    receiver: Receiver<Result<T, JobError>>,
+}
+
+impl<T> JobHandle<T> {
+    /// Blocks until the job has run, returning its value or why it has
+    /// none.
+    pub fn wait(self) -> Result<T, JobError> {
+        // The sender only disappears without sending if the job was
+        // dropped unrun
+        self.receiver.recv().unwrap_or(Err(JobError::Cancelled))
+    }
+
+    /// Like [`wait`](JobHandle::wait), but gives up after `timeout`,
+    /// returning the handle so the caller can wait again later.
+    pub fn wait_timeout(self, timeout: Duration) -> Result<Result<T, JobError>, JobHandle<T>> {
+        match self.receiver.recv_timeout(timeout) {
+            Ok(result) => Ok(result),
+            Err(mpsc::RecvTimeoutError::Disconnected) => Ok(Err(JobError::Cancelled)),
+            Err(mpsc::RecvTimeoutError::Timeout) => Err(self),
+        }
+    }
+}
+
+/// Why a submitted job produced no value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JobError {
+    /// The job panicked with this message.
+    Panicked(String),
+    /// The job was discarded before it ran, by [`ThreadPool::shutdown_now`]
+    /// or because the pool's token was cancelled.
+    Cancelled,
+}
+
+impl fmt::Display for JobError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            JobError::Panicked(message) => write!(f, "job panicked: {}", message),
+            JobError::Cancelled => write!(f, "job was cancelled before it ran"),
+        }
+    }
+}
+
+impl Error for JobError {}
+
+/// The message passed to `panic!`, if it was a string.
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
+
+fn run_worker(shared: &Shared) {
+    while let Some(job) = next_job(shared) {
+        if panic::catch_unwind(AssertUnwindSafe(job)).is_err() {
+            shared.panicked.fetch_add(1, Ordering::SeqCst);
+        }
+        shared.completed.fetch_add(1, Ordering::SeqCst);
+    }
+    shared.lock().running_workers -= 1;
+    shared.worker_exited.notify_all();
+}
+
+/// Blocks until a job is available, or returns `None` once the pool's
+/// token is cancelled or the pool is shutting down with an empty queue.
+/// The loop guards against spurious wakeups.
+fn next_job(shared: &Shared) -> Option<Job> {
+    let mut state = shared.lock();
+    loop {
+        if shared.token.is_cancelled() {
+            return None;
+        }
+        if let Some(job) = state.jobs.dequeue() {
+            return Some(job);
+        }
+        if state.shutting_down {
+            return None;
+        }
+        state = shared.job_available.wait(state).unwrap_or_else(PoisonError::into_inner);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn test_runs_every_job() {
+        let pool = ThreadPool::new(3);
+        assert_eq!(pool.size(), 3);
+        let counter = Arc::new(AtomicUsize::new(0));
+        for _ in 0..50 {
+            let counter = Arc::clone(&counter);
+            pool.execute(move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+            });
+        }
+        drop(pool);
+        assert_eq!(counter.load(Ordering::SeqCst), 50);
+    }
+
+    #[test]
+    fn test_panicking_job_keeps_worker_alive() {
+        // A single worker, so the later jobs can only run if it survived
+        let pool = ThreadPool::new(1);
+        let (tx, rx) = mpsc::channel();
+        pool.execute(|| panic!("job failure"));
+        for n in 0..3 {
+            let tx = tx.clone();
+            pool.execute(move || tx.send(n).unwrap());
+        }
+        assert_eq!(rx.recv_timeout(Duration::from_secs(5)).unwrap(), 0);
+        assert!(pool.shutdown(Duration::from_secs(5)));
+        assert_eq!(rx.try_iter().collect::<Vec<_>>(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_metrics() {
+        let pool = ThreadPool::new(1);
+        let (started_tx, started_rx) = mpsc::channel();
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+        pool.execute(move || {
+            started_tx.send(()).unwrap();
+            release_rx.recv().unwrap();
+        });
+        started_rx.recv().unwrap();
+        pool.execute(|| {});
+        pool.execute(|| panic!("counted"));
+        assert_eq!(pool.queued(), 2);
+        assert_eq!(pool.completed(), 0);
+        release_tx.send(()).unwrap();
+
+        let shared = Arc::clone(&pool.shared);
+        assert!(pool.shutdown(Duration::from_secs(5)));
+        assert_eq!(shared.completed.load(Ordering::SeqCst), 3);
+        assert_eq!(shared.panicked.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_shutdown_times_out_on_slow_jobs() {
+        let pool = ThreadPool::new(1);
+        pool.execute(|| thread::sleep(Duration::from_millis(300)));
+        assert!(!pool.shutdown(Duration::from_millis(20)));
+    }
+
+    #[test]
+    fn test_shutdown_now_discards_queued_jobs() {
+        let pool = ThreadPool::new(1);
+        let (started_tx, started_rx) = mpsc::channel();
+        pool.execute(move || {
+            started_tx.send(()).unwrap();
+            thread::sleep(Duration::from_millis(50));
+        });
+        started_rx.recv().unwrap();
+        let ran = Arc::new(AtomicUsize::new(0));
+        for _ in 0..4 {
+            let ran = Arc::clone(&ran);
+            pool.execute(move || {
+                ran.fetch_add(1, Ordering::SeqCst);
+            });
+        }
+        assert_eq!(pool.shutdown_now(), 4);
+        assert_eq!(ran.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_submit_returns_values_in_any_order() {
+        let pool = ThreadPool::new(4);
+        let handles: Vec<_> = (0..20u64).map(|n| pool.submit(move || n * 2)).collect();
+        let results: Vec<_> = handles.into_iter().map(|handle| handle.wait().unwrap()).collect();
+        assert_eq!(results, (0..20).map(|n| n * 2).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_submit_reports_panics() {
+        let pool = ThreadPool::new(1);
+        let failed = pool.submit(|| -> () { panic!("disk {} is full", 3) });
+        assert_eq!(failed.wait(), Err(JobError::Panicked("disk 3 is full".to_string())));
+        // The panic still shows up in the pool's metrics
+        assert_eq!(pool.submit(|| ()).wait(), Ok(()));
+        assert_eq!(pool.panicked(), 1);
+    }
+
+    #[test]
+    fn test_discarded_job_is_cancelled() {
+        let pool = ThreadPool::new(1);
+        let (started_tx, started_rx) = mpsc::channel();
+        pool.execute(move || {
+            started_tx.send(()).unwrap();
+            thread::sleep(Duration::from_millis(20));
+        });
+        started_rx.recv().unwrap();
+        let handle = pool.submit(|| 1);
+        pool.shutdown_now();
+        assert_eq!(handle.wait(), Err(JobError::Cancelled));
+    }
+
+    #[test]
+    fn test_wait_timeout_returns_the_handle() {
+        let pool = ThreadPool::new(1);
+        let handle = pool.submit(|| {
+            thread::sleep(Duration::from_millis(100));
+            "done"
+        });
+        let handle = match handle.wait_timeout(Duration::from_millis(1)) {
+            Err(handle) => handle,
+            Ok(_) => panic!("job finished too early"),
+        };
+        assert_eq!(handle.wait(), Ok("done"));
+    }
+
+    #[test]
+    fn test_cancelling_the_token_stops_the_pool() {
+        let token = CancellationToken::new();
+        let pool = ThreadPool::with_token(1, token.clone());
+        let (started_tx, started_rx) = mpsc::channel();
+        // A long job that checks the token and gives up when cancelled
+        let job_token = pool.token();
+        let long_job = pool.submit(move || {
+            started_tx.send(()).unwrap();
+            job_token.cancelled_wait(Duration::from_secs(10))
+        });
+        started_rx.recv().unwrap();
+        let queued = pool.submit(|| "never runs");
+
+        token.cancel();
+        assert_eq!(long_job.wait(), Ok(true));
+        assert_eq!(queued.wait(), Err(JobError::Cancelled));
+        assert!(pool.shutdown(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn test_jobs_after_a_cancel_are_dropped() {
+        let token = CancellationToken::new();
+        let pool = ThreadPool::with_token(2, token.clone());
+        token.cancel();
+        let handle = pool.submit(|| 5);
+        match handle.wait_timeout(Duration::from_secs(2)) {
+            Ok(result) => assert_eq!(result, Err(JobError::Cancelled)),
+            Err(_) => panic!("the job neither ran nor was dropped"),
+        }
+        assert_eq!(pool.queued(), 0);
+    }
+
+    #[test]
+    fn test_dropped_pools_forget_their_callback() {
+        let token = CancellationToken::new();
+        let pool = ThreadPool::with_token(1, token.clone());
+        let id = pool.on_cancel.1;
+        drop(pool);
+        // Already gone, so there was nothing left to remove
+        assert!(!token.remove_callback(id));
+    }
+
+    #[test]
+    fn test_shutdown_now_cancels_running_jobs() {
+        let pool = ThreadPool::new(1);
+        let (started_tx, started_rx) = mpsc::channel();
+        let token = pool.token();
+        let job = pool.submit(move || {
+            started_tx.send(()).unwrap();
+            token.cancelled_wait(Duration::from_secs(10))
+        });
+        started_rx.recv().unwrap();
+        pool.shutdown_now();
+        assert_eq!(job.wait(), Ok(true));
+    }
+
+    #[test]
+    fn test_shutdown_now_leaves_a_shared_token_alone() {
+        let token = CancellationToken::new();
+        let first = ThreadPool::with_token(1, token.clone());
+        let second = ThreadPool::with_token(1, token.clone());
+        first.shutdown_now();
+        assert!(!token.is_cancelled());
+        assert!(!second.token().is_cancelled());
+        assert_eq!(second.submit(|| "still working").wait(), Ok("still working"));
+
+        // The shared token still reaches the surviving pool
+        token.cancel();
+        assert!(second.token().is_cancelled());
+        assert_eq!(second.submit(|| "too late").wait(), Err(JobError::Cancelled));
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one worker")]
+    fn test_zero_workers_panics() {
+        ThreadPool::new(0);
+    }
+}
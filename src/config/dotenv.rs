@@ -0,0 +1,296 @@
+//! `.env` files: `KEY=VALUE` lines that become environment variables.
+//!
+//! ```text
+//! # comments start with #
+//! export DATABASE_HOST=localhost      # `export` is allowed and ignored
+//! DATABASE_URL="postgres://${DATABASE_HOST}:5432/app"
+//! GREETING='kept exactly, ${NOT} expanded'
+//! MOTD="two\nlines"
+//! ```
+//!
+//! Unquoted values are trimmed and end at a ` #` comment. Double-quoted
+//! values understand `\n`, `\t`, `\"`, `\\` and `\$`. Both expand
+//! `${VAR}`, looking first at keys earlier in the file and then at the
+//! environment; an unknown variable expands to nothing. Single-quoted
+//! values are taken literally.
+//!
+//! By default [`load`] leaves variables that are already set alone, so the
+//! real environment wins over the file, which is what lets a deployment
+//! override a developer's `.env`. It wins inside `${VAR}` too, so values
+//! are built from what the variables end up set to. [`load_override`]
+//! lets the file win.
+
+use std::fs;
+use std::path::Path;
+
+use super::ConfigError;
+
+/// Parses `.env` text into its keys and values, in file order, expanding
+/// `${VAR}` from earlier keys and the process environment.
+///
+/// ```
+/// use rustler::config::dotenv;
+///
+/// let vars = dotenv::parse("HOST=example.com\nURL=\"https://${HOST}/api\" # prod\n").unwrap();
+/// assert_eq!(vars[1], ("URL".to_string(), "https://example.com/api".to_string()));
+/// ```
+pub fn parse(text: &str) -> Result<Vec<(String, String)>, ConfigError> {
+    parse_with(text, false, |name| std::env::var(name).ok())
+}
+
+/// Reads the file at `path` and sets each of its variables that is not
+/// already set. Returns the names that were set.
+pub fn load(path: impl AsRef<Path>) -> Result<Vec<String>, ConfigError> {
+    apply(path.as_ref(), false)
+}
+
+/// Like [`load`], but the file's values replace variables that are
+/// already set.
+pub fn load_override(path: impl AsRef<Path>) -> Result<Vec<String>, ConfigError> {
+    apply(path.as_ref(), true)
+}
+
+fn apply(path: &Path, override_existing: bool) -> Result<Vec<String>, ConfigError> {
+    let text = fs::read_to_string(path)?;
+    let vars = to_set(&text, override_existing, |name| std::env::var(name).ok())?;
+    let mut set = Vec::new();
+    for (key, value) in vars {
+        std::env::set_var(&key, value);
+        set.push(key);
+    }
+    Ok(set)
+}
+
+/// The variables in `text` that [`apply`] sets, with `lookup` standing in
+/// for the environment.
+fn to_set(
+    text: &str,
+    override_existing: bool,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Result<Vec<(String, String)>, ConfigError> {
+    // Without an override a variable keeps its environment value, so
+    // expansions must see that value rather than the file's
+    let vars = parse_with(text, !override_existing, &lookup)?;
+    Ok(vars.into_iter().filter(|(key, _)| override_existing || lookup(key).is_none()).collect())
+}
+
+/// [`parse`] with `lookup` standing in for the environment. With
+/// `env_first`, `${VAR}` looks at the environment before the file.
+fn parse_with(
+    text: &str,
+    env_first: bool,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Result<Vec<(String, String)>, ConfigError> {
+    let mut vars: Vec<(String, String)> = Vec::new();
+    for (index, raw) in text.lines().enumerate() {
+        let error = |message: String| ConfigError::Parse {
+            line: index + 1,
+            message,
+        };
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").map_or(line, str::trim_start);
+
+        let (key, rest) = line
+            .split_once('=')
+            .ok_or_else(|| error(format!("expected KEY=VALUE, found `{}`", line)))?;
+        let key = key.trim();
+        if !is_valid_key(key) {
+            return Err(error(format!("`{}` is not a valid variable name", key)));
+        }
+
+        let resolve = |name: &str| {
+            let from_file = || vars.iter().rev().find(|(k, _)| k == name).map(|(_, v)| v.clone());
+            if env_first {
+                lookup(name).or_else(from_file)
+            } else {
+                from_file().or_else(|| lookup(name))
+            }
+        };
+        let rest = rest.trim_start();
+        let value = match rest.chars().next() {
+            Some('\'') => {
+                let (value, after) = rest[1..]
+                    .split_once('\'')
+                    .ok_or_else(|| error("unterminated single quote".to_string()))?;
+                check_trailing(after).map_err(error)?;
+                value.to_string()
+            }
+            Some('"') => {
+                let (value, after) = double_quoted(&rest[1..], &resolve).map_err(error)?;
+                check_trailing(after).map_err(error)?;
+                value
+            }
+            _ => {
+                let value = match rest.find(" #").or_else(|| rest.find("\t#")) {
+                    Some(comment) => &rest[..comment],
+                    None => rest,
+                };
+                expand(value.trim_end(), &resolve).map_err(error)?
+            }
+        };
+        vars.push((key.to_string(), value));
+    }
+    Ok(vars)
+}
+
+fn is_valid_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// After a closing quote only a comment may follow.
+fn check_trailing(after: &str) -> Result<(), String> {
+    let after = after.trim_start();
+    if after.is_empty() || after.starts_with('#') {
+        Ok(())
+    } else {
+        Err(format!("unexpected `{}` after the closing quote", after))
+    }
+}
+
+/// The value of a double-quoted string whose opening quote has been
+/// stripped, and the text after its closing quote.
+fn double_quoted<'a>(text: &'a str, resolve: &dyn Fn(&str) -> Option<String>) -> Result<(String, &'a str), String> {
+    let mut value = String::new();
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        rest = &rest[c.len_utf8()..];
+        match c {
+            '"' => return Ok((value, rest)),
+            '\\' => {
+                let escaped = rest.chars().next().ok_or_else(|| "unterminated double quote".to_string())?;
+                rest = &rest[escaped.len_utf8()..];
+                match escaped {
+                    'n' => value.push('\n'),
+                    't' => value.push('\t'),
+                    '"' | '\\' | '$' => value.push(escaped),
+                    other => return Err(format!("unknown escape `\\{}`", other)),
+                }
+            }
+            '$' if rest.starts_with('{') => {
+                let len = rest.find('}').ok_or_else(|| "unterminated `${`".to_string())?;
+                value.push_str(&resolve(&rest[1..len]).unwrap_or_default());
+                rest = &rest[len + 1..];
+            }
+            c => value.push(c),
+        }
+    }
+    Err("unterminated double quote".to_string())
+}
+
+/// Replaces each `${VAR}` in an unquoted value.
+fn expand(text: &str, resolve: &dyn Fn(&str) -> Option<String>) -> Result<String, String> {
+    let mut value = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("${") {
+        value.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let len = after.find('}').ok_or_else(|| "unterminated `${`".to_string())?;
+        value.push_str(&resolve(&after[..len]).unwrap_or_default());
+        rest = &after[len + 1..];
+    }
+    value.push_str(rest);
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::TempDir;
+
+    fn parse_env(text: &str) -> Vec<(String, String)> {
+        parse_with(text, false, |name| (name == "HOME").then(|| "/home/ada".to_string())).unwrap()
+    }
+
+    fn pairs(vars: &[(String, String)]) -> Vec<(&str, &str)> {
+        vars.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect()
+    }
+
+    #[test]
+    fn test_plain_values_and_comments() {
+        let vars = parse_env("# a comment\n\nexport NAME = demo app  # trailing\nEMPTY=\nURL=http://x/#anchor\n");
+        assert_eq!(
+            pairs(&vars),
+            vec![("NAME", "demo app"), ("EMPTY", ""), ("URL", "http://x/#anchor")]
+        );
+    }
+
+    #[test]
+    fn test_quotes_and_escapes() {
+        let vars = parse_env(concat!(
+            "A=\"  padded # not a comment \"\n",
+            "B='literal ${HOME} \\n'\n",
+            "C=\"line\\nbreak \\\"quoted\\\" \\$HOME\" # comment\n",
+        ));
+        assert_eq!(
+            pairs(&vars),
+            vec![
+                ("A", "  padded # not a comment "),
+                ("B", "literal ${HOME} \\n"),
+                ("C", "line\nbreak \"quoted\" $HOME"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_expansion_prefers_earlier_keys() {
+        let vars = parse_env(concat!(
+            "DIR=${HOME}/app\n",
+            "LOGS=\"${DIR}/logs\"\n",
+            "HOME=/srv\n",
+            "CACHE=${HOME}/cache ${UNSET}end\n",
+        ));
+        assert_eq!(
+            pairs(&vars),
+            vec![("DIR", "/home/ada/app"), ("LOGS", "/home/ada/app/logs"), ("HOME", "/srv"), ("CACHE", "/srv/cache end")]
+        );
+    }
+
+    #[test]
+    fn test_errors_name_the_line() {
+        let cases = [
+            ("OK=1\nno equals sign", "line 2: expected KEY=VALUE, found `no equals sign`"),
+            ("1BAD=x", "line 1: `1BAD` is not a valid variable name"),
+            ("A=\"open", "line 1: unterminated double quote"),
+            ("A='open", "line 1: unterminated single quote"),
+            ("A=\"x\" y", "line 1: unexpected `y` after the closing quote"),
+            ("A=${OOPS", "line 1: unterminated `${`"),
+            ("A=\"\\q\"", "line 1: unknown escape `\\q`"),
+        ];
+        for (text, message) in cases {
+            let err = parse_with(text, false, |_| None).unwrap_err();
+            assert_eq!(err.to_string(), message, "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_load_respects_existing_variables() {
+        let shell = |name: &str| (name == "TAKEN").then(|| "from shell".to_string());
+        let text = "FRESH=from file\nTAKEN=from file\nCOPY=${TAKEN}\n";
+
+        let vars = to_set(text, false, shell).unwrap();
+        assert_eq!(pairs(&vars), [("FRESH", "from file"), ("COPY", "from shell")]);
+        let vars = to_set(text, true, shell).unwrap();
+        assert_eq!(pairs(&vars), [("FRESH", "from file"), ("TAKEN", "from file"), ("COPY", "from file")]);
+
+        let dir = TempDir::new("dotenv").unwrap();
+        assert!(matches!(load(dir.join("missing.env")), Err(ConfigError::Io(_))));
+    }
+
+    #[test]
+    fn test_expansion_uses_the_value_that_is_kept() {
+        let shell = |name: &str| (name == "HOST").then(|| "prod.example.com".to_string());
+        let text = "HOST=localhost\nURL=http://${HOST}/api\n";
+
+        // HOST stays as the shell has it, so URL must be built from that
+        let vars = to_set(text, false, shell).unwrap();
+        assert_eq!(pairs(&vars), [("URL", "http://prod.example.com/api")]);
+        // Overridden, HOST becomes the file's value and URL follows it
+        let vars = to_set(text, true, shell).unwrap();
+        assert_eq!(pairs(&vars), [("HOST", "localhost"), ("URL", "http://localhost/api")]);
+    }
+}
@@ -0,0 +1,336 @@
+//! INI files: `[section]` headers followed by `key = value` lines.
+//!
+//! ```text
+//! ; comments start with ; or #
+//! name = demo            # keys before any header go in the "" section
+//!
+//! [server]
+//! host = localhost
+//! greeting = "  padded, ; not a comment  "
+//! ```
+//!
+//! Values are plain text. Quotes, single or double, keep leading and
+//! trailing spaces and comment characters; inside double quotes `\"`,
+//! `\\`, `\n` and `\r` are escapes. A key that appears twice keeps its
+//! last value, a section that appears twice is merged, and a section with
+//! no keys is still in the result, empty.
+
+use std::collections::HashMap;
+
+use super::ConfigError;
+
+/// Section name to keys and values. Keys outside any section are under
+/// `""`.
+pub type Sections = HashMap<String, HashMap<String, String>>;
+
+/// Parses INI text into its sections.
+///
+/// ```
+/// use rustler::config::ini;
+///
+/// let sections = ini::parse("name = demo\n[db]\nurl = \"postgres://localhost\" ; dev only\n").unwrap();
+/// assert_eq!(sections[""]["name"], "demo");
+/// assert_eq!(sections["db"]["url"], "postgres://localhost");
+/// ```
+pub fn parse(text: &str) -> Result<Sections, ConfigError> {
+    let mut sections = Sections::new();
+    let mut section = String::new();
+    for (index, raw) in text.lines().enumerate() {
+        let error = |message: String| ConfigError::Parse {
+            line: index + 1,
+            message,
+        };
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with([';', '#']) {
+            continue;
+        }
+
+        if let Some(header) = line.strip_prefix('[') {
+            let name = header
+                .strip_suffix(']')
+                .ok_or_else(|| error("section header is missing its closing ]".to_string()))?
+                .trim();
+            if name.is_empty() {
+                return Err(error("section name is empty".to_string()));
+            }
+            section = name.to_string();
+            sections.entry(section.clone()).or_default();
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| error(format!("expected `key = value`, found `{}`", line)))?;
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(error("key is empty".to_string()));
+        }
+        let value = parse_value(value.trim()).map_err(error)?;
+        sections.entry(section.clone()).or_default().insert(key.to_string(), value);
+    }
+    Ok(sections)
+}
+
+/// Writes sections back out as INI text that [`parse`] reads to the same
+/// map. The `""` section comes first; sections and keys are sorted. An
+/// empty `""` section has no header to write, so it is the one thing that
+/// does not come back.
+///
+/// Any value can be written, quoted and escaped as needed, but names are
+/// written as they are, so a key or section name that would read back as
+/// something else, such as a key containing `=`, is
+/// [`ConfigError::Unwritable`].
+///
+/// ```
+/// use rustler::config::ini::{self, Sections};
+///
+/// let mut sections = Sections::new();
+/// sections.entry("app".to_string()).or_default().insert("motd".to_string(), "two\nlines".to_string());
+/// let text = ini::write(&sections).unwrap();
+/// assert_eq!(text, "[app]\nmotd = \"two\\nlines\"\n");
+/// assert_eq!(ini::parse(&text).unwrap(), sections);
+/// ```
+pub fn write(sections: &Sections) -> Result<String, ConfigError> {
+    let mut names: Vec<&String> = sections.keys().collect();
+    names.sort();
+    let mut out = String::new();
+    for name in names {
+        let entries = &sections[name];
+        if !name.is_empty() {
+            check_section_name(name)?;
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            out.push_str(&format!("[{}]\n", name));
+        }
+        let mut keys: Vec<&String> = entries.keys().collect();
+        keys.sort();
+        for key in keys {
+            check_key(key)?;
+            out.push_str(&format!("{} = {}\n", key, quote_if_needed(&entries[key])));
+        }
+    }
+    Ok(out)
+}
+
+/// A key reads back as itself only if it has no line break, nothing that
+/// ends it early or starts a comment, doesn't look like a section header,
+/// and has no spaces for the parser to trim.
+fn check_key(key: &str) -> Result<(), ConfigError> {
+    let problem = if key.is_empty() {
+        Some("is empty")
+    } else if key.contains(['=', ';', '#', '\n', '\r']) {
+        Some("contains `=`, `;`, `#` or a line break")
+    } else if key.starts_with('[') {
+        Some("starts with `[`")
+    } else if key.trim() != key {
+        Some("starts or ends with whitespace")
+    } else {
+        None
+    };
+    match problem {
+        Some(problem) => Err(ConfigError::Unwritable(format!("the INI key {:?}: it {}", key, problem))),
+        None => Ok(()),
+    }
+}
+
+fn check_section_name(name: &str) -> Result<(), ConfigError> {
+    let problem = if name.contains(['\n', '\r']) {
+        Some("contains a line break")
+    } else if name.trim() != name {
+        Some("starts or ends with whitespace")
+    } else {
+        None
+    };
+    match problem {
+        Some(problem) => Err(ConfigError::Unwritable(format!("the INI section {:?}: it {}", name, problem))),
+        None => Ok(()),
+    }
+}
+
+fn parse_value(text: &str) -> Result<String, String> {
+    if let Some(rest) = text.strip_prefix('"') {
+        let mut value = String::new();
+        let mut chars = rest.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => {
+                    check_trailing(chars.as_str())?;
+                    return Ok(value);
+                }
+                '\\' => match chars.next() {
+                    Some(escaped @ ('"' | '\\')) => value.push(escaped),
+                    Some('n') => value.push('\n'),
+                    Some('r') => value.push('\r'),
+                    Some(other) => {
+                        value.push('\\');
+                        value.push(other);
+                    }
+                    None => break,
+                },
+                _ => value.push(c),
+            }
+        }
+        return Err("value is not closed with \"".to_string());
+    }
+    if let Some(rest) = text.strip_prefix('\'') {
+        let (value, trailing) = rest.split_once('\'').ok_or("value is not closed with '")?;
+        check_trailing(trailing)?;
+        return Ok(value.to_string());
+    }
+    Ok(strip_inline_comment(text).trim_end().to_string())
+}
+
+/// After a closing quote only whitespace and a comment may follow.
+fn check_trailing(rest: &str) -> Result<(), String> {
+    let rest = rest.trim();
+    if rest.is_empty() || rest.starts_with([';', '#']) {
+        Ok(())
+    } else {
+        Err(format!("unexpected `{}` after quoted value", rest))
+    }
+}
+
+/// An unquoted value ends at a `;` or `#` that follows whitespace, so
+/// `color = #fff` keeps its hash but `port = 80 ; http` drops the note.
+fn strip_inline_comment(text: &str) -> &str {
+    let mut previous = ' ';
+    for (i, c) in text.char_indices() {
+        if (c == ';' || c == '#') && previous.is_whitespace() && i > 0 {
+            return &text[..i];
+        }
+        previous = c;
+    }
+    text
+}
+
+fn quote_if_needed(value: &str) -> String {
+    let needs_quotes = value.starts_with(char::is_whitespace)
+        || value.ends_with(char::is_whitespace)
+        || value.starts_with(['"', '\''])
+        || value.contains([';', '#', '\n', '\r']);
+    if needs_quotes {
+        let escaped = value
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+            .replace('\r', "\\r");
+        format!("\"{}\"", escaped)
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sections_comments_and_quotes() {
+        let sections = parse(
+            "; top comment\n\
+             title = My App\n\
+             \n\
+             [server]\n\
+             host = localhost   ; inline note\n\
+             color = #ff8800\n\
+             motd = \"  Welcome; \\\"friend\\\"  \"\n\
+             path = 'C:\\temp'\n\
+             empty =\n\
+             [server]\n\
+             host = example.com\n",
+        )
+        .unwrap();
+        assert_eq!(sections[""]["title"], "My App");
+        let server = &sections["server"];
+        assert_eq!(server["host"], "example.com");
+        assert_eq!(server["color"], "#ff8800");
+        assert_eq!(server["motd"], "  Welcome; \"friend\"  ");
+        assert_eq!(server["path"], "C:\\temp");
+        assert_eq!(server["empty"], "");
+        assert_eq!(server.len(), 5);
+    }
+
+    #[test]
+    fn test_errors_name_the_line() {
+        let cases = [
+            ("[open", 1, "closing ]"),
+            ("ok = 1\n[ ]", 2, "section name is empty"),
+            ("\njust words", 2, "expected `key = value`"),
+            ("= value", 1, "key is empty"),
+            ("a = \"unclosed", 1, "not closed"),
+            ("a = 'x' y", 1, "unexpected `y`"),
+        ];
+        for (input, line, expected) in cases {
+            match parse(input) {
+                Err(ConfigError::Parse { line: l, message }) => {
+                    assert_eq!(l, line, "{}", input);
+                    assert!(message.contains(expected), "{}: {}", input, message);
+                }
+                other => panic!("{}: expected a parse error, got {:?}", input, other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_write_round_trips() {
+        let mut sections = Sections::new();
+        sections.entry(String::new()).or_default().insert("name".to_string(), "demo".to_string());
+        let db = sections.entry("db".to_string()).or_default();
+        db.insert("url".to_string(), "postgres://localhost/app".to_string());
+        db.insert("note".to_string(), " has; \"everything\" # \\ ".to_string());
+        db.insert("blank".to_string(), String::new());
+        sections.entry("web".to_string()).or_default().insert("port".to_string(), "8080".to_string());
+
+        let text = write(&sections).unwrap();
+        assert_eq!(
+            text,
+            "name = demo\n\n[db]\nblank = \nnote = \" has; \\\"everything\\\" # \\\\ \"\nurl = postgres://localhost/app\n\n[web]\nport = 8080\n"
+        );
+        assert_eq!(parse(&text).unwrap(), sections);
+    }
+
+    #[test]
+    fn test_empty_sections_round_trip() {
+        let sections = parse("[empty]\n; nothing here\n[full]\nk = v\n").unwrap();
+        assert_eq!(sections.len(), 2);
+        assert!(sections["empty"].is_empty());
+        let text = write(&sections).unwrap();
+        assert_eq!(text, "[empty]\n\n[full]\nk = v\n");
+        assert_eq!(parse(&text).unwrap(), sections);
+
+        let mut top = Sections::new();
+        top.entry(String::new()).or_default();
+        assert_eq!(write(&top).unwrap(), "");
+    }
+
+    #[test]
+    fn test_write_escapes_line_breaks() {
+        let mut sections = Sections::new();
+        let app = sections.entry("app".to_string()).or_default();
+        app.insert("motd".to_string(), "line1\nline2\r\n".to_string());
+        app.insert("path".to_string(), "C:\\new".to_string());
+        app.insert("quoted_path".to_string(), " C:\\new".to_string());
+        let text = write(&sections).unwrap();
+        assert_eq!(text.lines().count(), 4, "{}", text);
+        assert_eq!(parse(&text).unwrap(), sections);
+    }
+
+    #[test]
+    fn test_write_rejects_names_that_would_not_read_back() {
+        for key in ["a=b", "a;b", "a#b", "[a", " a", "a ", "", "a\nb"] {
+            let mut sections = Sections::new();
+            sections.entry(String::new()).or_default().insert(key.to_string(), "1".to_string());
+            assert!(matches!(write(&sections), Err(ConfigError::Unwritable(_))), "{:?}", key);
+        }
+        for name in ["a\nb", " a"] {
+            let mut sections = Sections::new();
+            sections.entry(name.to_string()).or_default().insert("k".to_string(), "1".to_string());
+            assert!(matches!(write(&sections), Err(ConfigError::Unwritable(_))), "{:?}", name);
+        }
+        // Brackets inside a name are fine
+        let mut sections = Sections::new();
+        sections.entry("a]b".to_string()).or_default().insert("k[0]".to_string(), "1".to_string());
+        assert_eq!(parse(&write(&sections).unwrap()).unwrap(), sections);
+    }
+}
@@ -0,0 +1,438 @@
+//! Layered configuration: a file, then a `.env` file, then environment
+//! variable overrides.
+//!
+//! Settings are stored flat under dotted keys, so `port` inside the
+//! `[server]` table of a TOML or INI file and `{"server": {"port": ...}}`
+//! in JSON all become `server.port`. After the file is read, any environment
+//! variable starting with `RUSTLER_` overrides it: a double underscore
+//! separates tables, so `RUSTLER_SERVER__PORT=9000` sets `server.port`.
+//! `RUSTLER_` lines in a [`dotenv`] file next to the config file sit in
+//! between: they override the file, and the real environment overrides them.
+//!
+//! Values from the environment and from INI files are always strings. The
+//! typed getters accept a string where they expect a number or boolean and
+//! parse it, so an override works whatever type the file used.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+pub mod dotenv;
+pub mod ini;
+mod toml;
+
+/// Environment variables with this prefix override file settings.
+pub const ENV_PREFIX: &str = "RUSTLER_";
+
+/// A single setting.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Str(String),
+}
+
+impl Value {
+    /// The name used for this kind of value in error messages.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Bool(_) => "boolean",
+            Value::Int(_) => "integer",
+            Value::Float(_) => "float",
+            Value::Str(_) => "string",
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Int(n) => write!(f, "{}", n),
+            Value::Float(x) => write!(f, "{}", x),
+            Value::Str(s) => write!(f, "{:?}", s),
+        }
+    }
+}
+
+/// Settings read from a file and the environment.
+///
+/// ```
+/// use rustler::config::Config;
+///
+/// let mut config = Config::parse_toml("[server]\nport = 8080\ndebug = false\n").unwrap();
+/// config.merge_env([("RUSTLER_SERVER__DEBUG".to_string(), "yes".to_string())]);
+///
+/// assert_eq!(config.get_int("server.port").unwrap(), 8080);
+/// assert!(config.get_bool("server.debug").unwrap());
+/// assert_eq!(
+///     config.get_str("server.host").unwrap_err().to_string(),
+///     "missing config key `server.host`"
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Config {
+    values: BTreeMap<String, Value>,
+}
+
+impl Config {
+    pub fn new() -> Self {
+        Config::default()
+    }
+
+    /// Reads `path`, choosing the format from its extension (`.toml`,
+    /// `.json` or `.ini`), then applies the `RUSTLER_*` overrides from a
+    /// `.env` file in the same directory, if there is one, and then those
+    /// from the environment.
+    pub fn load(path: impl AsRef<Path>) -> Result<Config, ConfigError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)?;
+        let mut config = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Config::parse_toml(&text)?,
+            Some("json") => Config::parse_json(&text)?,
+            Some("ini") => Config::parse_ini(&text)?,
+            other => return Err(ConfigError::UnsupportedFormat(other.unwrap_or("").to_string())),
+        };
+        let dotenv_path = path.with_file_name(".env");
+        if dotenv_path.is_file() {
+            config.merge_dotenv(&dotenv_path)?;
+        }
+        config.merge_env(std::env::vars());
+        Ok(config)
+    }
+
+    /// Parses the common subset of TOML: tables, dotted keys, strings,
+    /// numbers and booleans. Arrays, inline tables and multi-line strings
+    /// are rejected.
+    pub fn parse_toml(text: &str) -> Result<Config, ConfigError> {
+        Ok(Config {
+            values: toml::parse(text)?,
+        })
+    }
+
+    /// Parses a JSON object, flattening nested objects into dotted keys.
+    /// Nulls are skipped; arrays are rejected.
+    pub fn parse_json(text: &str) -> Result<Config, ConfigError> {
+        let json: serde_json::Value = serde_json::from_str(text)?;
+        let serde_json::Value::Object(object) = json else {
+            return Err(ConfigError::Unsupported("a JSON config must be an object at the top level".to_string()));
+        };
+        let mut config = Config::new();
+        config.flatten_json("", object)?;
+        Ok(config)
+    }
+
+    /// Parses an INI file. Keys in a section become `section.key`, and
+    /// every value is a string.
+    pub fn parse_ini(text: &str) -> Result<Config, ConfigError> {
+        let mut config = Config::new();
+        for (section, entries) in ini::parse(text)? {
+            for (key, value) in entries {
+                let key = if section.is_empty() { key } else { format!("{}.{}", section, key) };
+                config.values.insert(key, Value::Str(value));
+            }
+        }
+        Ok(config)
+    }
+
+    /// Applies the variables starting with [`ENV_PREFIX`] as string
+    /// overrides. Pass `std::env::vars()` for the real environment.
+    pub fn merge_env<I>(&mut self, vars: I)
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        for (name, value) in vars {
+            let Some(rest) = name.strip_prefix(ENV_PREFIX) else {
+                continue;
+            };
+            if rest.is_empty() {
+                continue;
+            }
+            let key = rest.to_ascii_lowercase().replace("__", ".");
+            self.values.insert(key, Value::Str(value));
+        }
+    }
+
+    /// Applies the `RUSTLER_*` variables of a `.env` file as overrides,
+    /// like [`merge_env`](Config::merge_env), without touching the process
+    /// environment.
+    pub fn merge_dotenv(&mut self, path: impl AsRef<Path>) -> Result<(), ConfigError> {
+        let vars = dotenv::parse(&fs::read_to_string(path)?)?;
+        self.merge_env(vars);
+        Ok(())
+    }
+
+    /// Sets `key`, replacing any earlier value.
+    pub fn set(&mut self, key: &str, value: Value) {
+        self.values.insert(key.to_string(), value);
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.values.get(key)
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.values.contains_key(key)
+    }
+
+    /// Every key, in sorted order.
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.values.keys().map(String::as_str)
+    }
+
+    /// A string setting.
+    pub fn get_str(&self, key: &str) -> Result<&str, ConfigError> {
+        match self.require(key)? {
+            Value::Str(s) => Ok(s),
+            other => Err(wrong_type(key, "a string", other)),
+        }
+    }
+
+    /// An integer setting, also accepting a string such as `"8080"`.
+    pub fn get_int(&self, key: &str) -> Result<i64, ConfigError> {
+        match self.require(key)? {
+            Value::Int(n) => Ok(*n),
+            Value::Str(s) => s.trim().parse().map_err(|_| wrong_type(key, "an integer", &Value::Str(s.clone()))),
+            other => Err(wrong_type(key, "an integer", other)),
+        }
+    }
+
+    /// A boolean setting, also accepting the strings `true`/`false`,
+    /// `yes`/`no`, `on`/`off` and `1`/`0`.
+    pub fn get_bool(&self, key: &str) -> Result<bool, ConfigError> {
+        match self.require(key)? {
+            Value::Bool(b) => Ok(*b),
+            Value::Str(s) => match s.trim().to_ascii_lowercase().as_str() {
+                "true" | "yes" | "on" | "1" => Ok(true),
+                "false" | "no" | "off" | "0" => Ok(false),
+                _ => Err(wrong_type(key, "a boolean", &Value::Str(s.clone()))),
+            },
+            other => Err(wrong_type(key, "a boolean", other)),
+        }
+    }
+
+    fn require(&self, key: &str) -> Result<&Value, ConfigError> {
+        self.values.get(key).ok_or_else(|| ConfigError::Missing(key.to_string()))
+    }
+
+    fn flatten_json(&mut self, prefix: &str, object: serde_json::Map<String, serde_json::Value>) -> Result<(), ConfigError> {
+        use serde_json::Value as Json;
+        for (name, json) in object {
+            let key = if prefix.is_empty() { name } else { format!("{}.{}", prefix, name) };
+            let value = match json {
+                Json::Null => continue,
+                Json::Object(inner) => {
+                    self.flatten_json(&key, inner)?;
+                    continue;
+                }
+                Json::Array(_) => return Err(ConfigError::Unsupported(format!("`{}` is an array; arrays are not supported", key))),
+                Json::Bool(b) => Value::Bool(b),
+                Json::Number(n) => match n.as_i64() {
+                    Some(i) => Value::Int(i),
+                    None => Value::Float(n.as_f64().unwrap_or(f64::NAN)),
+                },
+                Json::String(s) => Value::Str(s),
+            };
+            self.values.insert(key, value);
+        }
+        Ok(())
+    }
+}
+
+fn wrong_type(key: &str, expected: &'static str, found: &Value) -> ConfigError {
+    ConfigError::WrongType {
+        key: key.to_string(),
+        expected,
+        found: format!("{} {}", found.type_name(), found),
+    }
+}
+
+/// Errors from loading configuration or reading a setting.
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(io::Error),
+    /// A TOML or INI file could not be parsed.
+    Parse { line: usize, message: String },
+    Json(serde_json::Error),
+    /// The file extension is not one the loader knows.
+    UnsupportedFormat(String),
+    /// The file uses a feature the loader does not handle.
+    Unsupported(String),
+    /// A name that can't be written out in the format, with why.
+    Unwritable(String),
+    Missing(String),
+    WrongType {
+        key: String,
+        expected: &'static str,
+        /// The type and value that was there instead.
+        found: String,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::Io(e) => write!(f, "I/O error: {}", e),
+            ConfigError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+            ConfigError::Json(e) => write!(f, "invalid JSON: {}", e),
+            ConfigError::UnsupportedFormat(ext) => {
+                write!(f, "unsupported config format `.{}` (expected .toml, .json or .ini)", ext)
+            }
+            ConfigError::Unsupported(message) => write!(f, "{}", message),
+            ConfigError::Unwritable(message) => write!(f, "cannot write {}", message),
+            ConfigError::Missing(key) => write!(f, "missing config key `{}`", key),
+            ConfigError::WrongType { key, expected, found } => {
+                write!(f, "config key `{}` should be {}, found {}", key, expected, found)
+            }
+        }
+    }
+}
+
+impl Error for ConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ConfigError::Io(e) => Some(e),
+            ConfigError::Json(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ConfigError {
+    fn from(e: io::Error) -> Self {
+        ConfigError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for ConfigError {
+    fn from(e: serde_json::Error) -> Self {
+        ConfigError::Json(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_json_is_flattened() {
+        let config = Config::parse_json(r#"{"name": "demo", "server": {"port": 80, "tls": {"on": true}}, "ratio": 1.5, "unset": null}"#).unwrap();
+        assert_eq!(config.keys().collect::<Vec<_>>(), vec!["name", "ratio", "server.port", "server.tls.on"]);
+        assert_eq!(config.get_int("server.port").unwrap(), 80);
+        assert!(config.get_bool("server.tls.on").unwrap());
+        assert_eq!(config.get("ratio"), Some(&Value::Float(1.5)));
+    }
+
+    #[test]
+    fn test_json_rejects_arrays_and_non_objects() {
+        let err = Config::parse_json(r#"{"hosts": ["a", "b"]}"#).unwrap_err();
+        assert_eq!(err.to_string(), "`hosts` is an array; arrays are not supported");
+        assert!(matches!(Config::parse_json("[1]"), Err(ConfigError::Unsupported(_))));
+        assert!(matches!(Config::parse_json("{oops"), Err(ConfigError::Json(_))));
+    }
+
+    #[test]
+    fn test_ini_sections_become_dotted_keys() {
+        let config = Config::parse_ini("name = demo
+[server]
+port = 8080
+debug = on
+").unwrap();
+        assert_eq!(config.keys().collect::<Vec<_>>(), vec!["name", "server.debug", "server.port"]);
+        assert_eq!(config.get_int("server.port").unwrap(), 8080);
+        assert!(config.get_bool("server.debug").unwrap());
+        assert_eq!(config.get("name"), Some(&Value::Str("demo".to_string())));
+    }
+
+    #[test]
+    fn test_env_overrides_the_file() {
+        let mut config = Config::parse_toml("[server]\nport = 80\nname = \"a\"").unwrap();
+        config.merge_env(env(&[
+            ("RUSTLER_SERVER__PORT", "9000"),
+            ("RUSTLER_LOG_LEVEL", "debug"),
+            ("HOME", "/root"),
+            ("RUSTLER_", "ignored"),
+        ]));
+        assert_eq!(config.get_int("server.port").unwrap(), 9000);
+        assert_eq!(config.get_str("server.name").unwrap(), "a");
+        assert_eq!(config.get_str("log_level").unwrap(), "debug");
+        assert!(!config.contains("home"));
+        assert_eq!(config.keys().count(), 3);
+    }
+
+    #[test]
+    fn test_typed_getters_explain_mismatches() {
+        let mut config = Config::new();
+        config.set("port", Value::Str("eighty".to_string()));
+        config.set("debug", Value::Int(2));
+        config.set("name", Value::Bool(true));
+        assert_eq!(
+            config.get_int("port").unwrap_err().to_string(),
+            "config key `port` should be an integer, found string \"eighty\""
+        );
+        assert_eq!(
+            config.get_bool("debug").unwrap_err().to_string(),
+            "config key `debug` should be a boolean, found integer 2"
+        );
+        assert_eq!(
+            config.get_str("name").unwrap_err().to_string(),
+            "config key `name` should be a string, found boolean true"
+        );
+        assert!(matches!(config.get_int("missing"), Err(ConfigError::Missing(key)) if key == "missing"));
+    }
+
+    #[test]
+    fn test_load_picks_format_by_extension() {
+        let dir = crate::io::TempDir::new("config").unwrap();
+        let toml_path = dir.join("app.toml");
+        let json_path = dir.join("app.json");
+        let ini_path = dir.join("app.ini");
+        let yaml_path = dir.join("app.yaml");
+        fs::write(&toml_path, "answer = 42").unwrap();
+        fs::write(&json_path, r#"{"answer": 43}"#).unwrap();
+        fs::write(&ini_path, "answer = 45").unwrap();
+        fs::write(&yaml_path, "answer: 44").unwrap();
+
+        let from_toml = Config::load(&toml_path).map(|c| c.get_int("answer").unwrap());
+        let from_json = Config::load(&json_path).map(|c| c.get_int("answer").unwrap());
+        let from_ini = Config::load(&ini_path).map(|c| c.get_int("answer").unwrap());
+        let from_yaml = Config::load(&yaml_path);
+        fs::remove_file(&toml_path).unwrap();
+        assert_eq!(from_toml.unwrap(), 42);
+        assert_eq!(from_json.unwrap(), 43);
+        assert_eq!(from_ini.unwrap(), 45);
+        assert_eq!(
+            from_yaml.unwrap_err().to_string(),
+            "unsupported config format `.yaml` (expected .toml, .json or .ini)"
+        );
+        assert!(matches!(Config::load(&toml_path), Err(ConfigError::Io(_))));
+    }
+
+    #[test]
+    fn test_dotenv_sits_between_file_and_environment() {
+        let dir = crate::io::TempDir::new("config").unwrap();
+        let path = dir.join("app.toml");
+        fs::write(&path, "[server]\nport = 80\nhost = \"file\"\nname = \"file\"\n").unwrap();
+        fs::write(dir.join(".env"), "RUSTLER_SERVER__PORT=8080\nRUSTLER_SERVER__HOST=dotenv\nOTHER=ignored\n").unwrap();
+
+        let mut config = Config::parse_toml(&fs::read_to_string(&path).unwrap()).unwrap();
+        config.merge_dotenv(dir.join(".env")).unwrap();
+        config.merge_env(env(&[("RUSTLER_SERVER__HOST", "shell")]));
+        assert_eq!(config.get_int("server.port").unwrap(), 8080);
+        assert_eq!(config.get_str("server.host").unwrap(), "shell");
+        assert_eq!(config.get_str("server.name").unwrap(), "file");
+        assert!(!config.contains("other"));
+
+        let loaded = Config::load(&path).unwrap();
+        assert_eq!(loaded.get_int("server.port").unwrap(), 8080);
+        assert!(std::env::var_os("RUSTLER_SERVER__PORT").is_none());
+    }
+}
@@ -0,0 +1,248 @@
+//! A parser for the subset of TOML that configuration files usually need.
+//!
+//! Supported: `# comments`, `[table]` and `[dotted.table]` headers, bare,
+//! quoted and dotted keys, and values that are basic `"strings"` (with
+//! escapes), literal `'strings'`, integers, floats and booleans.
+//!
+//! Not supported, and reported as errors rather than misread: arrays,
+//! inline tables, arrays of tables, multi-line strings and dates.
+
+use std::collections::BTreeMap;
+
+use super::{ConfigError, Value};
+
+/// Parses `input` into dotted keys and their values.
+pub(crate) fn parse(input: &str) -> Result<BTreeMap<String, Value>, ConfigError> {
+    let mut values = BTreeMap::new();
+    let mut table = String::new();
+    for (index, raw) in input.lines().enumerate() {
+        let line_number = index + 1;
+        let error = |message: String| ConfigError::Parse {
+            line: line_number,
+            message,
+        };
+        let line = strip_comment(raw).trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(header) = line.strip_prefix('[') {
+            if header.starts_with('[') {
+                return Err(error("arrays of tables ([[...]]) are not supported".to_string()));
+            }
+            let name = header
+                .strip_suffix(']')
+                .ok_or_else(|| error("table header is missing its closing ]".to_string()))?;
+            table = parse_key(name).map_err(error)?;
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| error(format!("expected `key = value`, found `{}`", line)))?;
+        let key = parse_key(key).map_err(error)?;
+        let value = parse_value(value.trim()).map_err(error)?;
+        let full_key = if table.is_empty() { key } else { format!("{}.{}", table, key) };
+        if values.contains_key(&full_key) {
+            return Err(error(format!("key `{}` is defined twice", full_key)));
+        }
+        values.insert(full_key, value);
+    }
+    Ok(values)
+}
+
+/// The line up to any `#` that is not inside a string.
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match quote {
+            Some('"') if escaped => escaped = false,
+            Some('"') if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '#' => return &line[..i],
+            None => {}
+        }
+    }
+    line
+}
+
+/// Turns `a."b c".d` into `a.b c.d`.
+fn parse_key(key: &str) -> Result<String, String> {
+    let mut parts = Vec::new();
+    for part in split_dotted(key.trim())? {
+        let part = part.trim();
+        let name = if let Some(quoted) = part.strip_prefix('"').and_then(|p| p.strip_suffix('"')) {
+            quoted.to_string()
+        } else if let Some(quoted) = part.strip_prefix('\'').and_then(|p| p.strip_suffix('\'')) {
+            quoted.to_string()
+        } else if !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+            part.to_string()
+        } else {
+            return Err(format!("invalid key `{}`", key.trim()));
+        };
+        parts.push(name);
+    }
+    Ok(parts.join("."))
+}
+
+/// Splits on dots that are not inside quotes.
+fn split_dotted(key: &str) -> Result<Vec<&str>, String> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut quote = None;
+    for (i, c) in key.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '.' => {
+                parts.push(&key[start..i]);
+                start = i + 1;
+            }
+            None => {}
+        }
+    }
+    if quote.is_some() {
+        return Err(format!("unterminated quote in key `{}`", key));
+    }
+    parts.push(&key[start..]);
+    Ok(parts)
+}
+
+fn parse_value(text: &str) -> Result<Value, String> {
+    if text.is_empty() {
+        return Err("missing value after `=`".to_string());
+    }
+    if text.starts_with("\"\"\"") || text.starts_with("'''") {
+        return Err("multi-line strings are not supported".to_string());
+    }
+    if let Some(rest) = text.strip_prefix('"') {
+        return parse_basic_string(rest).map(Value::Str);
+    }
+    if let Some(rest) = text.strip_prefix('\'') {
+        return match rest.strip_suffix('\'') {
+            Some(body) if !body.contains('\'') => Ok(Value::Str(body.to_string())),
+            _ => Err("literal string is not closed with '".to_string()),
+        };
+    }
+    match text {
+        "true" => return Ok(Value::Bool(true)),
+        "false" => return Ok(Value::Bool(false)),
+        _ => {}
+    }
+    if text.starts_with('[') {
+        return Err("arrays are not supported".to_string());
+    }
+    if text.starts_with('{') {
+        return Err("inline tables are not supported".to_string());
+    }
+
+    // Underscores may separate digits, as in 1_000_000
+    let digits = text.replace('_', "");
+    if let Ok(n) = digits.parse::<i64>() {
+        return Ok(Value::Int(n));
+    }
+    if digits.contains(['.', 'e', 'E']) {
+        if let Ok(x) = digits.parse::<f64>() {
+            return Ok(Value::Float(x));
+        }
+    }
+    Err(format!("cannot understand value `{}`", text))
+}
+
+/// Parses the rest of a `"..."` string after its opening quote.
+fn parse_basic_string(rest: &str) -> Result<String, String> {
+    let mut out = String::new();
+    let mut chars = rest.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                let trailing = chars.as_str().trim();
+                if !trailing.is_empty() {
+                    return Err(format!("unexpected `{}` after string", trailing));
+                }
+                return Ok(out);
+            }
+            '\\' => {
+                let escaped = match chars.next() {
+                    Some('n') => '\n',
+                    Some('t') => '\t',
+                    Some('r') => '\r',
+                    Some('"') => '"',
+                    Some('\\') => '\\',
+                    Some('u') => {
+                        let hex: String = chars.by_ref().take(4).collect();
+                        u32::from_str_radix(&hex, 16)
+                            .ok()
+                            .and_then(char::from_u32)
+                            .ok_or_else(|| format!("invalid unicode escape \\u{}", hex))?
+                    }
+                    Some(other) => return Err(format!("unknown escape \\{}", other)),
+                    None => return Err("string ends with a lone backslash".to_string()),
+                };
+                out.push(escaped);
+            }
+            _ => out.push(c),
+        }
+    }
+    Err("string is not closed with \"".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tables_and_value_types() {
+        let values = parse(
+            r#"
+            title = "demo"   # a comment
+            [server]
+            host = 'localhost'
+            port = 8_080
+            ratio = 0.75
+            debug = true
+            [server.tls]
+            cert = "C:\\certs\\a.pem"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(values["title"], Value::Str("demo".to_string()));
+        assert_eq!(values["server.host"], Value::Str("localhost".to_string()));
+        assert_eq!(values["server.port"], Value::Int(8080));
+        assert_eq!(values["server.ratio"], Value::Float(0.75));
+        assert_eq!(values["server.debug"], Value::Bool(true));
+        assert_eq!(values["server.tls.cert"], Value::Str("C:\\certs\\a.pem".to_string()));
+    }
+
+    #[test]
+    fn test_quoted_and_dotted_keys() {
+        let values = parse("a.\"b.c\" = 1\n'x y' = \"# not a comment\"").unwrap();
+        assert_eq!(values["a.b.c"], Value::Int(1));
+        assert_eq!(values["x y"], Value::Str("# not a comment".to_string()));
+    }
+
+    #[test]
+    fn test_errors_name_the_line() {
+        let cases = [
+            ("a = 1\na = 2", 2, "defined twice"),
+            ("ports = [1, 2]", 1, "arrays are not supported"),
+            ("\n\nname = \"open", 3, "not closed"),
+            ("[[servers]]", 1, "arrays of tables"),
+            ("just text", 1, "expected `key = value`"),
+            ("x = maybe", 1, "cannot understand"),
+        ];
+        for (input, line, expected) in cases {
+            match parse(input) {
+                Err(ConfigError::Parse { line: l, message }) => {
+                    assert_eq!(l, line, "{}", input);
+                    assert!(message.contains(expected), "{}: {}", input, message);
+                }
+                other => panic!("{}: expected a parse error, got {:?}", input, other),
+            }
+        }
+    }
+}
@@ -0,0 +1,537 @@
+//! Small programs with `todo!()` holes, graded by tests the learner does
+//! not edit, in the style of rustlings.
+//!
+//! Each exercise is a file in `exercises/` that the learner fills in, and
+//! its tests live in `exercises/tests/` under the same name. [`grade`]
+//! glues the two together as a test module, compiles the result with
+//! `rustc --test` in a temporary directory and runs it, so nothing in the
+//! crate itself has to build the learner's half-finished code.
+//!
+//! The list of exercises, in suggested order, is `exercises/index.txt`:
+//!
+//! ```text
+//! ownership1 | 05_ownership_borrowing | borrowing instead of taking ownership
+//! ```
+//!
+//! Each exercise also has hints, compiled in from `exercises/hints.json`,
+//! and a worked answer in `exercises/solutions/`. [`hint`] hands them out
+//! one at a time, ending with the solution as a diff against the learner's
+//! file.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::io::TempDir;
+use crate::text::diff::unified_diff;
+
+const INDEX: &str = include_str!("../exercises/index.txt");
+const HINTS: &str = include_str!("../exercises/hints.json");
+
+/// How long the tests may run before they are taken to be stuck, such as
+/// in a loop that never ends.
+const TIME_LIMIT: Duration = Duration::from_secs(10);
+
+/// One exercise from the index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Exercise {
+    pub name: &'static str,
+    /// The example whose topic it practises.
+    pub topic: &'static str,
+    pub summary: &'static str,
+}
+
+impl Exercise {
+    /// The file the learner edits.
+    pub fn path(&self) -> PathBuf {
+        exercises_dir().join(format!("{}.rs", self.name))
+    }
+
+    /// The tests that grade it.
+    pub fn test_path(&self) -> PathBuf {
+        exercises_dir().join("tests").join(format!("{}.rs", self.name))
+    }
+
+    /// The worked answer, revealed after the last hint.
+    pub fn solution_path(&self) -> PathBuf {
+        exercises_dir().join("solutions").join(format!("{}.rs", self.name))
+    }
+
+    /// The hints, gentlest first.
+    pub fn hints(&self) -> &'static [String] {
+        all_hints().get(self.name).map_or(&[], Vec::as_slice)
+    }
+}
+
+impl fmt::Display for Exercise {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} — {}", self.name, self.summary)
+    }
+}
+
+/// The `exercises/` directory of this checkout. Exercises are edited in
+/// place, so unlike the index they are read from disk, not compiled in.
+pub fn exercises_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("exercises")
+}
+
+/// Every exercise, in suggested order.
+pub fn exercises() -> Vec<Exercise> {
+    INDEX
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let mut fields = line.splitn(3, '|').map(str::trim);
+            Exercise {
+                name: fields.next().unwrap_or_default(),
+                topic: fields.next().unwrap_or_default(),
+                summary: fields.next().unwrap_or_default(),
+            }
+        })
+        .collect()
+}
+
+/// The exercise called `name`.
+pub fn find(name: &str) -> Option<Exercise> {
+    let name = name.trim().trim_end_matches(".rs");
+    exercises().into_iter().find(|e| e.name == name)
+}
+
+fn all_hints() -> &'static BTreeMap<String, Vec<String>> {
+    static HINT_LISTS: OnceLock<BTreeMap<String, Vec<String>>> = OnceLock::new();
+    // Compiled in and checked by the tests, like the quiz questions
+    HINT_LISTS.get_or_init(|| serde_json::from_str(HINTS).expect("hints.json is valid"))
+}
+
+/// The next thing to show a learner who asks for help.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Hint {
+    /// Hint `number` of `total`, counting from 1.
+    Text {
+        number: usize,
+        total: usize,
+        text: &'static str,
+    },
+    /// After the last hint: a unified diff from the learner's file to the
+    /// solution, empty if they already match.
+    Solution(String),
+}
+
+impl fmt::Display for Hint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Hint::Text { number, total, text } => write!(f, "Hint {} of {}: {}", number, total, text),
+            Hint::Solution(diff) if diff.is_empty() => write!(f, "Your file already matches the solution."),
+            Hint::Solution(diff) => write!(f, "No hints left. The solution, as changes to your file:\n\n{}", diff.trim_end()),
+        }
+    }
+}
+
+/// The hint for `exercise` after `taken` have already been shown: the
+/// next one in order, or the solution once they run out.
+///
+/// ```
+/// use rustler::exercises::{find, hint, Hint};
+///
+/// let exercise = find("functions1").unwrap();
+/// assert!(matches!(hint(&exercise, 0).unwrap(), Hint::Text { number: 1, .. }));
+/// assert!(matches!(hint(&exercise, 9).unwrap(), Hint::Solution(_)));
+/// ```
+pub fn hint(exercise: &Exercise, taken: usize) -> Result<Hint, ExerciseError> {
+    let hints = exercise.hints();
+    if let Some(text) = hints.get(taken) {
+        return Ok(Hint::Text {
+            number: taken + 1,
+            total: hints.len(),
+            text,
+        });
+    }
+    let attempt = fs::read_to_string(exercise.path())?;
+    let solution = fs::read_to_string(exercise.solution_path())?;
+    Ok(Hint::Solution(unified_diff(
+        &format!("exercises/{}.rs", exercise.name),
+        &format!("exercises/solutions/{}.rs", exercise.name),
+        &attempt,
+        &solution,
+        3,
+    )))
+}
+
+/// A test that did not pass, and what it printed when it failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure {
+    pub test: String,
+    /// The panic message, such as an `assert_eq!` with its left and right
+    /// values, or `not yet implemented` for a remaining `todo!()`.
+    pub message: String,
+}
+
+/// How an attempt went.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// Every test passed.
+    Passed { tests: usize },
+    /// The code compiled but some tests failed.
+    Failed { passed: usize, failures: Vec<Failure> },
+    /// The code did not compile; holds the compiler's messages.
+    CompileError(String),
+    /// The tests gave no verdict, because the process crashed, exited
+    /// before reporting or ran out of time, or because no test ran; holds
+    /// why.
+    Inconclusive(String),
+}
+
+impl Outcome {
+    pub fn is_pass(&self) -> bool {
+        matches!(self, Outcome::Passed { .. })
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Outcome::Passed { tests } => write!(f, "✓ All {} tests pass", tests),
+            Outcome::Failed { passed, failures } => {
+                write!(f, "✗ {} of {} tests failed", failures.len(), passed + failures.len())?;
+                for failure in failures {
+                    write!(f, "\n\n--- {} ---\n{}", failure.test, failure.message)?;
+                }
+                Ok(())
+            }
+            Outcome::CompileError(messages) => write!(f, "✗ It does not compile yet:\n\n{}", messages),
+            Outcome::Inconclusive(why) => write!(f, "✗ The tests did not finish:\n\n{}", why),
+        }
+    }
+}
+
+/// Compiles `exercise` with its tests and runs them.
+///
+/// An error means grading itself went wrong, for example `rustc` could not
+/// be started; problems in the learner's code are an [`Outcome`].
+pub fn grade(exercise: &Exercise) -> Result<Outcome, ExerciseError> {
+    let source = fs::read_to_string(exercise.path())?;
+    let tests = fs::read_to_string(exercise.test_path())?;
+    grade_source(exercise.name, &source, &tests)
+}
+
+/// [`grade`] for source held in memory. Compiler messages name the file
+/// `exercises/<name>.rs`, with line numbers matching `source`.
+pub fn grade_source(name: &str, source: &str, tests: &str) -> Result<Outcome, ExerciseError> {
+    grade_within(name, source, tests, TIME_LIMIT)
+}
+
+/// [`grade_source`] with the tests stopped after `limit`.
+fn grade_within(name: &str, source: &str, tests: &str, limit: Duration) -> Result<Outcome, ExerciseError> {
+    let dir = TempDir::new("exercise")?;
+    let file_name = format!("{}.rs", name);
+    // The learner's code comes first, so its line numbers are unchanged
+    let combined = format!(
+        "{}\n\n#[cfg(test)]\nmod tests {{\n#![allow(unused_imports)]\nuse super::*;\n\n{}\n}}\n",
+        source, tests
+    );
+    fs::write(dir.join(&file_name), combined)?;
+
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let compiled = Command::new(&rustc)
+        .args(["--edition", "2021", "--test", "-A", "unused", "-o", "exercise"])
+        .arg(&file_name)
+        .current_dir(dir.path())
+        .output()
+        .map_err(|e| ExerciseError::Rustc(rustc.clone(), e))?;
+    if !compiled.status.success() {
+        let messages = String::from_utf8_lossy(&compiled.stderr)
+            .replace(&format!("--> {}", file_name), &format!("--> exercises/{}", file_name));
+        return Ok(Outcome::CompileError(messages.trim_end().to_string()));
+    }
+
+    let mut child = Command::new(dir.join("exercise"))
+        .args(["--test-threads", "1"])
+        // A backtrace would bury the assertion message
+        .env("RUST_BACKTRACE", "0")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    // Drained on their own threads, so a chatty test can't fill a pipe and
+    // stall while we wait for it to exit
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+    let deadline = Instant::now() + limit;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break Some(status);
+        }
+        if Instant::now() >= deadline {
+            // Already exited if this fails, which is just as good
+            let _ = child.kill();
+            child.wait()?;
+            break None;
+        }
+        thread::sleep(Duration::from_millis(20));
+    };
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+
+    let stopped = match status {
+        None => Some(format!(
+            "The tests were still running after {:?}, so they were stopped. Is there a loop that never ends?",
+            limit
+        )),
+        Some(status) if !status.success() => {
+            let stderr = String::from_utf8_lossy(&stderr);
+            // The last lines say why, such as a stack overflow
+            let last: Vec<&str> = stderr.trim_end().lines().rev().take(3).collect();
+            let mut why = format!("The test program stopped with {}.", status);
+            for line in last.iter().rev() {
+                why.push('\n');
+                why.push_str(line);
+            }
+            Some(why)
+        }
+        Some(_) => None,
+    };
+    Ok(parse_test_output(&String::from_utf8_lossy(&stdout), stopped))
+}
+
+/// Reads `pipe` to the end on another thread.
+fn drain(pipe: Option<impl Read + Send + 'static>) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut bytes = Vec::new();
+        if let Some(mut pipe) = pipe {
+            // Whatever arrived before an error is still worth showing
+            let _ = pipe.read_to_end(&mut bytes);
+        }
+        bytes
+    })
+}
+
+/// Reads the libtest report: `test tests::name ... ok` lines, then a
+/// `---- tests::name stdout ----` section per failure, then a list of the
+/// failed tests under `failures:`, then a `test result:` line.
+///
+/// `stopped` holds why the test program failed, if it did. Failed tests
+/// explain that by themselves, but without any, the program crashed or
+/// exited early, and the report can't be trusted to be complete.
+fn parse_test_output(output: &str, stopped: Option<String>) -> Outcome {
+    let mut passed = 0;
+    let mut failures: Vec<Failure> = Vec::new();
+    let mut in_section = false;
+    let mut finished = false;
+    for line in output.lines() {
+        if let Some(test) = line.strip_prefix("---- ").and_then(|l| l.strip_suffix(" stdout ----")) {
+            failures.push(Failure {
+                test: test.trim_start_matches("tests::").to_string(),
+                message: String::new(),
+            });
+            in_section = true;
+        } else if line == "failures:" {
+            in_section = false;
+        } else if in_section {
+            let noise = line.trim().is_empty()
+                || line.starts_with("note: run with `RUST_BACKTRACE")
+                || (line.starts_with("thread '") && line.contains("panicked at"));
+            if let Some(failure) = failures.last_mut().filter(|_| !noise) {
+                if !failure.message.is_empty() {
+                    failure.message.push('\n');
+                }
+                failure.message.push_str(line);
+            }
+        } else if line.starts_with("test ") && line.ends_with(" ... ok") {
+            passed += 1;
+        } else if line.starts_with("test result: ") {
+            finished = true;
+        }
+    }
+    if !failures.is_empty() {
+        Outcome::Failed { passed, failures }
+    } else if let Some(why) = stopped {
+        Outcome::Inconclusive(why)
+    } else if !finished {
+        Outcome::Inconclusive("The test program exited without reporting a result.".to_string())
+    } else if passed == 0 {
+        Outcome::Inconclusive("No tests ran.".to_string())
+    } else {
+        Outcome::Passed { tests: passed }
+    }
+}
+
+/// Errors that stop an exercise from being graded at all.
+#[derive(Debug)]
+pub enum ExerciseError {
+    Io(io::Error),
+    /// The compiler, named by the first field, could not be started.
+    Rustc(String, io::Error),
+}
+
+impl fmt::Display for ExerciseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExerciseError::Io(e) => write!(f, "I/O error: {}", e),
+            ExerciseError::Rustc(rustc, e) => write!(f, "could not run `{}`: {}", rustc, e),
+        }
+    }
+}
+
+impl Error for ExerciseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ExerciseError::Io(e) | ExerciseError::Rustc(_, e) => Some(e),
+        }
+    }
+}
+
+impl From<io::Error> for ExerciseError {
+    fn from(e: io::Error) -> Self {
+        ExerciseError::Io(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog;
+
+    #[test]
+    fn test_index_matches_the_exercises_directory() {
+        let mut on_disk: Vec<String> = fs::read_dir(exercises_dir())
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "rs"))
+            .map(|path| path.file_stem().unwrap().to_string_lossy().into_owned())
+            .collect();
+        on_disk.sort();
+        let mut indexed: Vec<String> = exercises().iter().map(|e| e.name.to_string()).collect();
+        indexed.sort();
+        assert_eq!(indexed, on_disk);
+
+        let examples: Vec<&str> = catalog::examples().iter().map(|e| e.name).collect();
+        for exercise in exercises() {
+            assert!(exercise.test_path().is_file(), "{} has no tests", exercise.name);
+            assert!(examples.contains(&exercise.topic), "{} practises unknown {}", exercise.name, exercise.topic);
+        }
+    }
+
+    #[test]
+    fn test_find() {
+        assert_eq!(find("errors1.rs").unwrap().topic, "08_error_handling");
+        assert_eq!(find("errors9"), None);
+    }
+
+    #[test]
+    fn test_unsolved_exercises_compile_and_fail() {
+        for exercise in exercises() {
+            match grade(&exercise).unwrap() {
+                Outcome::Failed { failures, .. } => {
+                    assert!(failures.iter().any(|f| f.message.contains("not yet implemented")), "{}", exercise.name)
+                }
+                other => panic!("{}: {}", exercise.name, other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_every_exercise_has_hints_and_a_passing_solution() {
+        let mut names: Vec<&str> = exercises().iter().map(|e| e.name).collect();
+        names.sort();
+        let hinted: Vec<&str> = all_hints().keys().map(String::as_str).collect();
+        assert_eq!(hinted, names);
+
+        for exercise in exercises() {
+            assert!(exercise.hints().len() >= 2, "{}", exercise.name);
+            let solution = fs::read_to_string(exercise.solution_path()).unwrap();
+            let tests = fs::read_to_string(exercise.test_path()).unwrap();
+            let outcome = grade_source(exercise.name, &solution, &tests).unwrap();
+            assert!(outcome.is_pass(), "{}: {}", exercise.name, outcome);
+        }
+    }
+
+    #[test]
+    fn test_hints_end_with_the_solution_diff() {
+        let exercise = find("functions1").unwrap();
+        let first = hint(&exercise, 0).unwrap();
+        assert_eq!(first.to_string(), format!("Hint 1 of 2: {}", exercise.hints()[0]));
+        assert!(matches!(hint(&exercise, 1).unwrap(), Hint::Text { number: 2, total: 2, .. }));
+
+        let Hint::Solution(diff) = hint(&exercise, 2).unwrap() else {
+            panic!("expected the solution");
+        };
+        assert!(diff.starts_with("--- exercises/functions1.rs\n+++ exercises/solutions/functions1.rs\n"), "{}", diff);
+        assert!(diff.contains("\n-    todo!()\n+    width * height\n"), "{}", diff);
+        assert_eq!(Hint::Solution(String::new()).to_string(), "Your file already matches the solution.");
+    }
+
+    #[test]
+    fn test_grading_outcomes() {
+        let tests = "#[test]\nfn adds() {\n    assert_eq!(add(2, 2), 4);\n}\n\n#[test]\nfn zero() {\n    assert_eq!(add(0, 0), 0);\n}\n";
+
+        let right = grade_source("add", "pub fn add(a: i32, b: i32) -> i32 { a + b }", tests).unwrap();
+        assert_eq!(right, Outcome::Passed { tests: 2 });
+
+        let wrong = grade_source("add", "pub fn add(a: i32, b: i32) -> i32 { a - b }", tests).unwrap();
+        let Outcome::Failed { passed, failures } = wrong else {
+            panic!("expected a failure");
+        };
+        assert_eq!(passed, 1);
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].test, "adds");
+        assert!(failures[0].message.contains("left: 0\n right: 4"), "{}", failures[0].message);
+
+        let broken = grade_source("add", "pub fn add(a: i32, b: i32) -> i32 { a + }", tests).unwrap();
+        let Outcome::CompileError(messages) = broken else {
+            panic!("expected a compile error");
+        };
+        assert!(messages.contains("--> exercises/add.rs:1"), "{}", messages);
+    }
+
+    #[test]
+    fn test_crashes_and_early_exits_do_not_pass() {
+        let tests = "#[test]\nfn adds() {\n    assert_eq!(add(2, 2), 4);\n}\n";
+
+        let exits = "pub fn add(a: i32, b: i32) -> i32 { std::process::exit(0) }";
+        let outcome = grade_source("add", exits, tests).unwrap();
+        assert_eq!(
+            outcome,
+            Outcome::Inconclusive("The test program exited without reporting a result.".to_string())
+        );
+        assert!(!outcome.is_pass());
+
+        let overflows = "pub fn add(a: i32, b: i32) -> i32 { add(a, b) + 1 }";
+        let Outcome::Inconclusive(why) = grade_source("add", overflows, tests).unwrap() else {
+            panic!("expected the stack overflow to make the run inconclusive");
+        };
+        assert!(why.contains("overflowed its stack"), "{}", why);
+    }
+
+    #[test]
+    fn test_endless_loops_are_stopped() {
+        let tests = "#[test]\nfn adds() {\n    assert_eq!(add(2, 2), 4);\n}\n";
+        let spins = "pub fn add(a: i32, b: i32) -> i32 { loop {} }";
+        let started = Instant::now();
+        let Outcome::Inconclusive(why) = grade_within("add", spins, tests, Duration::from_secs(1)).unwrap() else {
+            panic!("expected the endless loop to make the run inconclusive");
+        };
+        assert!(why.contains("still running after 1s"), "{}", why);
+        // Compiling takes a while too, but nowhere near the default limit
+        assert!(started.elapsed() < TIME_LIMIT);
+    }
+
+    #[test]
+    fn test_parse_test_output_needs_a_result() {
+        let report = "running 1 test\ntest tests::adds ... ok\n\ntest result: ok. 1 passed; 0 failed\n";
+        assert_eq!(parse_test_output(report, None), Outcome::Passed { tests: 1 });
+        assert!(!parse_test_output("running 1 test\ntest tests::adds ... ok\n", None).is_pass());
+        assert_eq!(
+            parse_test_output("running 0 tests\n\ntest result: ok. 0 passed; 0 failed\n", None),
+            Outcome::Inconclusive("No tests ran.".to_string())
+        );
+        let stopped = parse_test_output(report, Some("The test program stopped with signal: 6.".to_string()));
+        assert!(matches!(stopped, Outcome::Inconclusive(_)));
+    }
+}
@@ -0,0 +1,238 @@
+//! Term and definition cards for each example's topic, drilled with a
+//! simple spaced-repetition schedule.
+//!
+//! The cards live in `cards.json` next to this file, keyed by example
+//! name, and are compiled into the library like the quiz questions:
+//!
+//! ```text
+//! {"term": "Arc<T>", "definition": "An atomically reference-counted pointer..."}
+//! ```
+//!
+//! Each card sits in a bucket. A card rated [`Rating::Again`] drops back
+//! to bucket 0 and comes up again the same day; `Hard` keeps its bucket,
+//! `Good` moves it up one and `Easy` two. The bucket sets how many days
+//! pass before the card is due again: see [`INTERVALS`].
+//! `rustler cards <topic>` drills the cards that are due and keeps their
+//! [`CardState`] in the progress file.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use chrono::{Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::catalog::{self, Example, FindError};
+
+const CARDS: &str = include_str!("cards.json");
+
+/// Days until a card in each bucket is due again.
+pub const INTERVALS: [u64; 6] = [1, 2, 4, 8, 16, 32];
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Card {
+    pub term: String,
+    pub definition: String,
+}
+
+/// How well the learner remembered a card.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rating {
+    /// Forgotten: see it again this session.
+    Again,
+    Hard,
+    Good,
+    Easy,
+}
+
+impl FromStr for Rating {
+    type Err = String;
+
+    /// A number from 1 to 4 or the rating's name, in any case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "1" | "again" => Ok(Rating::Again),
+            "2" | "hard" => Ok(Rating::Hard),
+            "3" | "good" => Ok(Rating::Good),
+            "4" | "easy" => Ok(Rating::Easy),
+            _ => Err("expected 1 (again), 2 (hard), 3 (good) or 4 (easy)".to_string()),
+        }
+    }
+}
+
+impl fmt::Display for Rating {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Rating::Again => "again",
+            Rating::Hard => "hard",
+            Rating::Good => "good",
+            Rating::Easy => "easy",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Where a card is in the schedule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CardState {
+    /// An index into [`INTERVALS`].
+    pub bucket: usize,
+    /// The first day the card should be shown again.
+    pub due: NaiveDate,
+}
+
+impl CardState {
+    pub fn is_due(&self, today: NaiveDate) -> bool {
+        self.due <= today
+    }
+}
+
+/// The state of a card after rating it on `today`. `state` is `None` for a
+/// card never seen before, which starts in bucket 0.
+///
+/// ```
+/// use chrono::NaiveDate;
+/// use rustler::flashcards::{schedule, Rating};
+///
+/// let today = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+/// let state = schedule(None, Rating::Good, today);
+/// assert_eq!(state.bucket, 1);
+/// assert_eq!(state.due, NaiveDate::from_ymd_opt(2024, 3, 3).unwrap());
+///
+/// let state = schedule(Some(state), Rating::Again, today);
+/// assert_eq!((state.bucket, state.due), (0, today));
+/// ```
+pub fn schedule(state: Option<CardState>, rating: Rating, today: NaiveDate) -> CardState {
+    let bucket = state.map_or(0, |s| s.bucket);
+    let last = INTERVALS.len() - 1;
+    let bucket = match rating {
+        Rating::Again => return CardState { bucket: 0, due: today },
+        Rating::Hard => bucket.min(last),
+        Rating::Good => (bucket + 1).min(last),
+        Rating::Easy => (bucket + 2).min(last),
+    };
+    let due = today.checked_add_days(Days::new(INTERVALS[bucket])).unwrap_or(NaiveDate::MAX);
+    CardState { bucket, due }
+}
+
+fn decks() -> &'static BTreeMap<String, Vec<Card>> {
+    static DECKS: OnceLock<BTreeMap<String, Vec<Card>>> = OnceLock::new();
+    // Compiled in and checked by the tests, as with the quiz questions
+    DECKS.get_or_init(|| serde_json::from_str(CARDS).expect("cards.json is valid"))
+}
+
+/// Checks a cards file against the examples: every deck belongs to one,
+/// has at least three cards, and never repeats a term. Used on the real
+/// file and on checkouts made by [`crate::scaffold`].
+#[cfg(test)]
+pub(crate) fn assert_valid_decks(examples: &[&str], cards: &str) {
+    let decks: BTreeMap<String, Vec<Card>> = serde_json::from_str(cards).expect("cards.json is valid");
+    for (topic, cards) in &decks {
+        assert!(examples.contains(&topic.as_str()), "{} has cards but no example", topic);
+        assert!(cards.len() >= 3, "{} has too few cards", topic);
+        for card in cards {
+            assert!(!card.term.is_empty() && !card.definition.is_empty());
+        }
+        let mut terms: Vec<&str> = cards.iter().map(|c| c.term.as_str()).collect();
+        terms.sort();
+        terms.dedup();
+        assert_eq!(terms.len(), cards.len(), "{} repeats a term", topic);
+    }
+}
+
+/// The example names that have a deck, in order.
+pub fn topics() -> Vec<&'static str> {
+    decks().keys().map(String::as_str).collect()
+}
+
+/// The cards for the example called `topic`.
+pub fn deck(topic: &str) -> Option<&'static [Card]> {
+    decks().get(topic).map(Vec::as_slice)
+}
+
+/// The deck for the example the user means, by number, name or fuzzy
+/// match.
+pub fn find(query: &str) -> Result<(Example, &'static [Card]), FindError> {
+    let example = catalog::find(query)?;
+    let cards = deck(example.name).ok_or_else(|| FindError::NotFound(query.to_string()))?;
+    Ok((example, cards))
+}
+
+/// The cards in `cards` due on `today`, in deck order. `state` looks up a
+/// card's schedule by term; cards without one are new and always due.
+pub fn due_cards(cards: &[Card], today: NaiveDate, state: impl Fn(&str) -> Option<CardState>) -> Vec<&Card> {
+    cards
+        .iter()
+        .filter(|card| state(&card.term).is_none_or(|s| s.is_due(today)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, d).unwrap()
+    }
+
+    #[test]
+    fn test_every_deck_belongs_to_an_example() {
+        let names: Vec<&str> = catalog::examples().iter().map(|e| e.name).collect();
+        assert_valid_decks(&names, CARDS);
+    }
+
+    #[test]
+    fn test_find() {
+        let (example, cards) = find("13").unwrap();
+        assert_eq!(example.name, "13_concurrency");
+        assert_eq!(cards, deck("13_concurrency").unwrap());
+        assert!(find("no such topic").is_err());
+    }
+
+    #[test]
+    fn test_rating_from_str() {
+        assert_eq!("1".parse(), Ok(Rating::Again));
+        assert_eq!(" Hard ".parse(), Ok(Rating::Hard));
+        assert_eq!("3".parse(), Ok(Rating::Good));
+        assert_eq!("EASY".parse(), Ok(Rating::Easy));
+        assert!("5".parse::<Rating>().is_err());
+        assert_eq!(Rating::Good.to_string(), "good");
+    }
+
+    #[test]
+    fn test_schedule_moves_between_buckets() {
+        let state = schedule(None, Rating::Hard, day(1));
+        assert_eq!(state, CardState { bucket: 0, due: day(2) });
+        let state = schedule(Some(state), Rating::Easy, day(2));
+        assert_eq!(state, CardState { bucket: 2, due: day(6) });
+        let state = schedule(Some(state), Rating::Hard, day(6));
+        assert_eq!(state, CardState { bucket: 2, due: day(10) });
+        let state = schedule(Some(state), Rating::Again, day(10));
+        assert_eq!(state, CardState { bucket: 0, due: day(10) });
+        assert!(state.is_due(day(10)));
+    }
+
+    #[test]
+    fn test_schedule_caps_at_the_last_bucket() {
+        let mut state = None;
+        for _ in 0..5 {
+            state = Some(schedule(state, Rating::Easy, day(1)));
+        }
+        let last = INTERVALS.len() - 1;
+        assert_eq!(state.unwrap().bucket, last);
+        assert_eq!(state.unwrap().due, day(1) + Days::new(INTERVALS[last]));
+    }
+
+    #[test]
+    fn test_due_cards() {
+        let cards = deck("05_ownership_borrowing").unwrap();
+        let mut states = BTreeMap::new();
+        states.insert(cards[0].term.clone(), CardState { bucket: 1, due: day(3) });
+        states.insert(cards[1].term.clone(), CardState { bucket: 0, due: day(1) });
+        let due = due_cards(cards, day(2), |term| states.get(term).copied());
+        assert_eq!(due.len(), cards.len() - 1);
+        assert!(!due.contains(&&cards[0]));
+        assert_eq!(due_cards(cards, day(3), |term| states.get(term).copied()).len(), cards.len());
+    }
+}
@@ -0,0 +1,321 @@
+//! The menu/play/pause game from the structs-and-enums example, run by a
+//! [`StateMachine`] and saved to disk as JSON.
+//!
+//! A save file records a format version next to the data:
+//!
+//! ```text
+//! {
+//!   "version": 1,
+//!   "state": "Paused",
+//!   "score": 120,
+//!   "history": ["Start", "Pause"]
+//! }
+//! ```
+//!
+//! [`store::versioned`](crate::store::versioned) reads the version on its
+//! own first, so a file written by a newer program is rejected with a
+//! clear error instead of half-parsing.
+//!
+//! A game given an [`EventBus`] with [`publish_to`](Game::publish_to)
+//! announces every event it accepts on the [`EVENT_TOPIC`] topic, so a
+//! front end can follow along without the game knowing it exists.
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::{InvalidTransition, StateMachine};
+use crate::concurrent::EventBus;
+use crate::store::versioned::{self, VersionError};
+
+/// The save format this build writes, and the newest it can read.
+pub const SAVE_VERSION: u32 = 1;
+
+/// The [`EventBus`] topic accepted events are published on.
+pub const EVENT_TOPIC: &str = "game";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameState {
+    Menu,
+    Playing,
+    Paused,
+    GameOver,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameEvent {
+    Start,
+    Pause,
+    Resume,
+    End,
+    /// Starts a new round from the game-over screen, clearing the score.
+    Restart,
+}
+
+/// A game session: its current state, score, and every event it accepted.
+///
+/// ```
+/// use rustler::games::game::{Game, GameEvent, GameState};
+///
+/// let mut game = Game::new();
+/// game.handle(GameEvent::Start).unwrap();
+/// assert!(game.add_points(50));
+/// game.handle(GameEvent::Pause).unwrap();
+/// // Points only count while playing
+/// assert!(!game.add_points(10));
+///
+/// assert_eq!(game.state(), GameState::Paused);
+/// assert_eq!(game.score(), 50);
+/// assert!(game.handle(GameEvent::Start).is_err());
+/// assert_eq!(game.history(), &[GameEvent::Start, GameEvent::Pause]);
+/// ```
+pub struct Game {
+    /// The context is the score.
+    machine: StateMachine<GameState, GameEvent, u32>,
+    history: Vec<GameEvent>,
+    events: Option<EventBus<GameEvent>>,
+}
+
+impl Game {
+    /// A new game at the menu with no score.
+    pub fn new() -> Self {
+        Game::resume_from(GameState::Menu, 0, Vec::new())
+    }
+
+    pub fn state(&self) -> GameState {
+        *self.machine.state()
+    }
+
+    pub fn score(&self) -> u32 {
+        *self.machine.context()
+    }
+
+    /// The events the game has accepted, oldest first.
+    pub fn history(&self) -> &[GameEvent] {
+        &self.history
+    }
+
+    /// Publishes every event the game accepts from now on to `bus`, on
+    /// [`EVENT_TOPIC`]. Rejected events are not published.
+    pub fn publish_to(&mut self, bus: EventBus<GameEvent>) {
+        self.events = Some(bus);
+    }
+
+    /// Applies `event`, returning the new state. Events the current state
+    /// does not accept are rejected and leave the game unchanged.
+    pub fn handle(&mut self, event: GameEvent) -> Result<GameState, InvalidTransition<GameState, GameEvent>> {
+        let state = *self.machine.fire(event)?;
+        self.history.push(event);
+        if let Some(bus) = &self.events {
+            bus.publish(EVENT_TOPIC, event);
+        }
+        Ok(state)
+    }
+
+    /// Adds to the score if the game is being played, returning whether
+    /// the points counted.
+    pub fn add_points(&mut self, points: u32) -> bool {
+        if self.state() != GameState::Playing {
+            return false;
+        }
+        let score = self.machine.context_mut();
+        *score = score.saturating_add(points);
+        true
+    }
+
+    /// Writes the game to `path` as JSON, replacing any existing file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SaveError> {
+        let file = SaveFile {
+            version: SAVE_VERSION,
+            state: self.state(),
+            score: self.score(),
+            history: self.history.clone(),
+        };
+        let json = serde_json::to_string_pretty(&file)?;
+        fs::write(path, json)?;
+        Ok(())
+    }
+
+    /// Reads a game written by [`save`](Game::save).
+    pub fn load(path: impl AsRef<Path>) -> Result<Game, SaveError> {
+        let json = fs::read_to_string(path)?;
+        let file: SaveFile = versioned::from_json(&json, SAVE_VERSION)?;
+        Ok(Game::resume_from(file.state, file.score, file.history))
+    }
+
+    fn resume_from(state: GameState, score: u32, history: Vec<GameEvent>) -> Self {
+        use GameEvent::*;
+        use GameState::*;
+        let machine = StateMachine::builder(state, score)
+            .on(Menu, Start).to(Playing)
+            .on(Playing, Pause).to(Paused)
+            .on(Paused, Resume).to(Playing)
+            .on(Playing, End).to(GameOver)
+            .on(Paused, End).to(GameOver)
+            .on(GameOver, Restart).action(|score| *score = 0).to(Playing)
+            .build();
+        Game {
+            machine,
+            history,
+            events: None,
+        }
+    }
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Game::new()
+    }
+}
+
+impl fmt::Debug for Game {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Game")
+            .field("state", &self.state())
+            .field("score", &self.score())
+            .field("history", &self.history)
+            .finish()
+    }
+}
+
+/// The on-disk layout of a saved game.
+#[derive(Serialize, Deserialize)]
+struct SaveFile {
+    version: u32,
+    state: GameState,
+    score: u32,
+    history: Vec<GameEvent>,
+}
+
+/// Errors from saving or loading a game.
+#[derive(Debug)]
+pub enum SaveError {
+    Io(io::Error),
+    /// The file is not valid JSON or is missing fields.
+    Format(serde_json::Error),
+    /// The save came from a newer build, or claims version 0.
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SaveError::Io(e) => write!(f, "I/O error: {}", e),
+            SaveError::Format(e) => write!(f, "malformed save file: {}", e),
+            SaveError::UnsupportedVersion(version) => write!(
+                f,
+                "save file version {} is not supported (expected 1 to {})",
+                version, SAVE_VERSION
+            ),
+        }
+    }
+}
+
+impl Error for SaveError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SaveError::Io(e) => Some(e),
+            SaveError::Format(e) => Some(e),
+            SaveError::UnsupportedVersion(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for SaveError {
+    fn from(e: io::Error) -> Self {
+        SaveError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for SaveError {
+    fn from(e: serde_json::Error) -> Self {
+        SaveError::Format(e)
+    }
+}
+
+impl From<VersionError> for SaveError {
+    fn from(e: VersionError) -> Self {
+        match e {
+            VersionError::Format(e) => SaveError::Format(e),
+            VersionError::Unsupported(version) => SaveError::UnsupportedVersion(version),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::TempFile;
+
+    #[test]
+    fn test_rejected_events_are_not_recorded() {
+        let mut game = Game::new();
+        assert!(game.handle(GameEvent::Pause).is_err());
+        assert_eq!(game.handle(GameEvent::Start), Ok(GameState::Playing));
+        assert_eq!(game.history(), &[GameEvent::Start]);
+    }
+
+    #[test]
+    fn test_accepted_events_are_published() {
+        let bus = EventBus::new();
+        let events = bus.subscribe(EVENT_TOPIC, 8);
+        let mut game = Game::new();
+        game.handle(GameEvent::Start).unwrap();
+        game.publish_to(bus);
+        game.handle(GameEvent::Pause).unwrap();
+        assert!(game.handle(GameEvent::Pause).is_err());
+        game.handle(GameEvent::End).unwrap();
+        assert_eq!(events.try_recv(), Some(GameEvent::Pause));
+        assert_eq!(events.try_recv(), Some(GameEvent::End));
+        assert_eq!(events.try_recv(), None);
+    }
+
+    #[test]
+    fn test_restart_clears_the_score() {
+        let mut game = Game::new();
+        game.handle(GameEvent::Start).unwrap();
+        game.add_points(30);
+        game.handle(GameEvent::End).unwrap();
+        assert_eq!(game.score(), 30);
+        game.handle(GameEvent::Restart).unwrap();
+        assert_eq!((game.state(), game.score()), (GameState::Playing, 0));
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let file = TempFile::new("game").unwrap();
+        let mut game = Game::new();
+        game.handle(GameEvent::Start).unwrap();
+        game.add_points(120);
+        game.handle(GameEvent::Pause).unwrap();
+        game.save(file.path()).unwrap();
+
+        let mut loaded = Game::load(file.path()).unwrap();
+        assert_eq!(loaded.state(), GameState::Paused);
+        assert_eq!(loaded.score(), 120);
+        assert_eq!(loaded.history(), game.history());
+        // The loaded game follows the same rules from where it left off
+        assert_eq!(loaded.handle(GameEvent::Resume), Ok(GameState::Playing));
+    }
+
+    #[test]
+    fn test_newer_version_is_rejected() {
+        let file = TempFile::new("game").unwrap();
+        fs::write(file.path(), r#"{"version": 99, "state": "Menu", "score": 0, "history": [], "lives": 3}"#).unwrap();
+        let err = Game::load(file.path()).unwrap_err();
+        assert!(matches!(err, SaveError::UnsupportedVersion(99)));
+        assert_eq!(err.to_string(), "save file version 99 is not supported (expected 1 to 1)");
+    }
+
+    #[test]
+    fn test_bad_files_report_why() {
+        let file = TempFile::new("game").unwrap();
+        fs::write(file.path(), r#"{"version": 1, "state": "Flying", "score": 0, "history": []}"#).unwrap();
+        assert!(matches!(Game::load(file.path()), Err(SaveError::Format(_))));
+        assert!(matches!(Game::load(file.path().with_extension("missing")), Err(SaveError::Io(_))));
+    }
+}
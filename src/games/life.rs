@@ -0,0 +1,223 @@
+//! Conway's Game of Life on a [`Grid2D<bool>`].
+//!
+//! Every generation, each cell counts its eight live neighbours: a live
+//! cell with two or three survives, a dead cell with exactly three comes
+//! alive, and everything else dies or stays dead. All cells update at
+//! once, so `step` reads the old grid and writes a new one.
+//!
+//! By default the world ends at its edges. With wrapping turned on it is
+//! a torus: the left edge touches the right and the top touches the
+//! bottom, so a glider that flies off one side comes back on the other.
+
+use std::fmt;
+
+use crate::collections::Grid2D;
+
+/// A Game of Life board.
+///
+/// ```
+/// use rustler::games::life::World;
+///
+/// // A blinker flips between a row and a column of three
+/// let mut world = World::blinker(5, 5);
+/// assert_eq!(world.to_string(), ".....\n.....\n.###.\n.....\n.....\n");
+/// world.step();
+/// assert_eq!(world.to_string(), ".....\n..#..\n..#..\n..#..\n.....\n");
+/// assert_eq!(world.generation(), 1);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct World {
+    cells: Grid2D<bool>,
+    wrapping: bool,
+    generation: u64,
+}
+
+impl World {
+    /// An empty world with hard edges.
+    pub fn new(rows: usize, cols: usize) -> Self {
+        World {
+            cells: Grid2D::new(rows, cols, false),
+            wrapping: false,
+            generation: 0,
+        }
+    }
+
+    /// Parses a picture of live (`#`, `O` or `*`) and dead (any other
+    /// character) cells, one line per row, placed with its top-left corner
+    /// at `(top, left)` in an empty `rows` x `cols` world. Parts of the
+    /// pattern outside the world are cut off.
+    pub fn from_pattern(rows: usize, cols: usize, top: usize, left: usize, pattern: &str) -> Self {
+        let mut world = World::new(rows, cols);
+        for (row, line) in pattern.lines().enumerate() {
+            for (col, cell) in line.chars().enumerate() {
+                if matches!(cell, '#' | 'O' | '*') {
+                    world.set(top + row, left + col, true);
+                }
+            }
+        }
+        world
+    }
+
+    /// A horizontal blinker, a period-2 oscillator, in the middle of the
+    /// world.
+    pub fn blinker(rows: usize, cols: usize) -> Self {
+        World::from_pattern(rows, cols, rows / 2, (cols / 2).saturating_sub(1), "###")
+    }
+
+    /// A glider in the top-left corner, heading down and to the right one
+    /// cell every four generations.
+    pub fn glider(rows: usize, cols: usize) -> Self {
+        World::from_pattern(rows, cols, 0, 0, ".#.\n..#\n###")
+    }
+
+    /// Makes the edges wrap around, turning the world into a torus.
+    pub fn with_wrapping(mut self, wrapping: bool) -> Self {
+        self.wrapping = wrapping;
+        self
+    }
+
+    pub fn rows(&self) -> usize {
+        self.cells.row_count()
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cells.col_count()
+    }
+
+    /// How many times [`step`](World::step) has run.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Whether the cell is alive. Cells outside the world are dead.
+    pub fn is_alive(&self, row: usize, col: usize) -> bool {
+        self.cells.get(row, col).copied().unwrap_or(false)
+    }
+
+    /// Brings a cell to life or kills it. Cells outside the world are
+    /// ignored.
+    pub fn set(&mut self, row: usize, col: usize, alive: bool) {
+        if let Some(cell) = self.cells.get_mut(row, col) {
+            *cell = alive;
+        }
+    }
+
+    /// The number of live cells.
+    pub fn population(&self) -> usize {
+        self.cells.iter().filter(|(_, &alive)| alive).count()
+    }
+
+    pub fn grid(&self) -> &Grid2D<bool> {
+        &self.cells
+    }
+
+    /// Advances the world by one generation.
+    pub fn step(&mut self) {
+        let next = Grid2D::from_fn(self.rows(), self.cols(), |row, col| {
+            let neighbours = self.live_neighbours(row, col);
+            matches!((self.cells[(row, col)], neighbours), (true, 2) | (_, 3))
+        });
+        self.cells = next;
+        self.generation += 1;
+    }
+
+    fn live_neighbours(&self, row: usize, col: usize) -> usize {
+        let (rows, cols) = (self.rows() as isize, self.cols() as isize);
+        let mut count = 0;
+        for dr in -1..=1 {
+            for dc in -1..=1 {
+                if (dr, dc) == (0, 0) {
+                    continue;
+                }
+                let (mut r, mut c) = (row as isize + dr, col as isize + dc);
+                if self.wrapping {
+                    r = r.rem_euclid(rows);
+                    c = c.rem_euclid(cols);
+                } else if r < 0 || c < 0 || r >= rows || c >= cols {
+                    continue;
+                }
+                if self.cells[(r as usize, c as usize)] {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+}
+
+/// One line per row, `#` for live cells and `.` for dead ones.
+impl fmt::Display for World {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.cells.rows() {
+            let line: String = row.iter().map(|&alive| if alive { '#' } else { '.' }).collect();
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blinker_has_period_two() {
+        let start = World::blinker(5, 5);
+        let mut world = start.clone();
+        world.step();
+        assert_ne!(world.grid(), start.grid());
+        world.step();
+        assert_eq!(world.grid(), start.grid());
+        assert_eq!(world.population(), 3);
+    }
+
+    #[test]
+    fn test_block_is_still_life() {
+        let mut world = World::from_pattern(4, 4, 1, 1, "##\n##");
+        let before = world.clone();
+        world.step();
+        assert_eq!(world.grid(), before.grid());
+    }
+
+    #[test]
+    fn test_glider_moves_diagonally() {
+        let mut world = World::glider(8, 8);
+        for _ in 0..4 {
+            world.step();
+        }
+        assert_eq!(world.grid(), World::from_pattern(8, 8, 1, 1, ".#.\n..#\n###").grid());
+    }
+
+    #[test]
+    fn test_hard_edges_kill_a_glider() {
+        let mut world = World::glider(5, 5);
+        for _ in 0..20 {
+            world.step();
+        }
+        // It turns into a block stuck in the corner
+        assert_eq!(world.population(), 4);
+        assert!(world.is_alive(4, 4));
+    }
+
+    #[test]
+    fn test_wrapping_glider_returns_home() {
+        // On a 6x6 torus the glider is back where it started after 24 steps
+        let start = World::glider(6, 6).with_wrapping(true);
+        let mut world = start.clone();
+        for _ in 0..24 {
+            world.step();
+            assert_eq!(world.population(), 5);
+        }
+        assert_eq!(world.grid(), start.grid());
+        assert_eq!(world.generation(), 24);
+    }
+
+    #[test]
+    fn test_set_outside_is_ignored() {
+        let mut world = World::new(2, 2);
+        world.set(5, 5, true);
+        world.set(1, 0, true);
+        assert!(!world.is_alive(5, 5));
+        assert_eq!(world.to_string(), "..\n#.\n");
+    }
+}
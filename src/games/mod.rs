@@ -0,0 +1,9 @@
+//! Game logic shared by the examples: state machines and the rules that
+//! run on top of them.
+
+pub mod game;
+pub mod life;
+pub mod state_machine;
+
+pub use game::{Game, GameEvent, GameState, SaveError};
+pub use state_machine::{InvalidTransition, StateMachine, StateMachineBuilder, TransitionBuilder};
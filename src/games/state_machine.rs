@@ -0,0 +1,562 @@
+//! A finite state machine described by a table of transitions.
+//!
+//! The `Game` in the structs-and-enums example hard-codes its transitions
+//! as one method per event, each with its own `match`. Here the same rules
+//! become data: each row says "in this state, on this event, if these
+//! guards pass, run these actions and move to that state". The machine
+//! also owns a context value that guards read and actions update, such as
+//! a score.
+//!
+//! ```text
+//! on(Paused, Resume).guard(|ctx| ...).action(|ctx| ...).to(Playing)
+//! ```
+//!
+//! States can nest. A parent state such as `Playing` groups children such
+//! as `Running` and `Paused`: the machine is always in a leaf state, and
+//! an event the leaf has no transition for bubbles up to its parent, so
+//! `on(Playing, Quit)` covers both children. Entry and exit hooks run as
+//! a transition leaves and enters states, outermost last on the way out
+//! and first on the way in.
+
+use std::error::Error;
+use std::fmt;
+
+type Guard<C> = Box<dyn Fn(&C) -> bool>;
+type Action<C> = Box<dyn FnMut(&mut C)>;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum HookKind {
+    Entry,
+    Exit,
+}
+
+struct Hook<S, C> {
+    state: S,
+    kind: HookKind,
+    action: Action<C>,
+}
+
+struct Transition<S, E, C> {
+    from: S,
+    event: E,
+    to: S,
+    guards: Vec<Guard<C>>,
+    actions: Vec<Action<C>>,
+}
+
+/// A state machine over states `S` and events `E`, carrying a context `C`.
+///
+/// ```
+/// use rustler::games::StateMachine;
+///
+/// #[derive(Debug, Clone, PartialEq)]
+/// enum State { Menu, Playing, Paused }
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Event { Start, Pause, Resume }
+///
+/// let mut machine = StateMachine::builder(State::Menu, 0u32)
+///     .on(State::Menu, Event::Start).action(|starts| *starts += 1).to(State::Playing)
+///     .on(State::Playing, Event::Pause).to(State::Paused)
+///     .on(State::Paused, Event::Resume).to(State::Playing)
+///     .build();
+///
+/// assert_eq!(machine.fire(Event::Start), Ok(&State::Playing));
+/// assert_eq!(machine.fire(Event::Pause), Ok(&State::Paused));
+/// assert_eq!(*machine.context(), 1);
+///
+/// // Paused has no transition for Pause, so the state stays put
+/// let err = machine.fire(Event::Pause).unwrap_err();
+/// assert_eq!(err.to_string(), "no transition from Paused on Pause");
+/// assert_eq!(machine.state(), &State::Paused);
+/// ```
+pub struct StateMachine<S, E, C = ()> {
+    state: S,
+    context: C,
+    transitions: Vec<Transition<S, E, C>>,
+    /// `(child, parent)` pairs, in the order they were declared.
+    parents: Vec<(S, S)>,
+    hooks: Vec<Hook<S, C>>,
+}
+
+impl<S, E, C> StateMachine<S, E, C>
+where
+    S: Clone + PartialEq + fmt::Debug,
+    E: PartialEq + fmt::Debug,
+{
+    /// Starts describing a machine that begins in `initial` with `context`.
+    pub fn builder(initial: S, context: C) -> StateMachineBuilder<S, E, C> {
+        StateMachineBuilder {
+            machine: StateMachine {
+                state: initial,
+                context,
+                transitions: Vec::new(),
+                parents: Vec::new(),
+                hooks: Vec::new(),
+            },
+        }
+    }
+
+    /// The current state. With nested states this is always a leaf.
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    /// Whether the machine is in `state` or in one of its descendants.
+    pub fn is_in(&self, state: &S) -> bool {
+        self.ancestry(&self.state).any(|s| s == state)
+    }
+
+    pub fn context(&self) -> &C {
+        &self.context
+    }
+
+    pub fn context_mut(&mut self) -> &mut C {
+        &mut self.context
+    }
+
+    /// Whether `event` would cause a transition right now.
+    pub fn can_fire(&self, event: &E) -> bool {
+        self.find(event).is_ok()
+    }
+
+    /// Handles `event`: takes the first transition from the current state
+    /// on this event whose guards all pass, runs its actions in order and
+    /// moves to its target state, which is returned.
+    ///
+    /// If the current state has no such transition, its parent's
+    /// transitions are tried, then the grandparent's, and so on. When the
+    /// target has children the machine moves on into its initial child,
+    /// and the leaf it ends up in is returned.
+    ///
+    /// The exit hooks of the states being left run first, innermost first,
+    /// then the transition's actions, then the entry hooks of the states
+    /// being entered, outermost first. A parent shared by the old and new
+    /// states is neither left nor entered.
+    ///
+    /// If there is no transition the state and context are left as they
+    /// were.
+    pub fn fire(&mut self, event: E) -> Result<&S, InvalidTransition<S, E>> {
+        let index = match self.find(&event) {
+            Ok(index) => index,
+            Err(rejected_by_guard) => {
+                return Err(InvalidTransition {
+                    state: self.state.clone(),
+                    event,
+                    rejected_by_guard,
+                })
+            }
+        };
+        let source = self.transitions[index].from.clone();
+        let target = self.transitions[index].to.clone();
+        // The innermost state containing both ends stays active. A
+        // transition to the source itself or to one of its ancestors
+        // leaves and re-enters that state
+        let shared = self
+            .ancestry(&target)
+            .skip(1)
+            .find(|&state| self.ancestry(&source).any(|s| s == state))
+            .cloned();
+
+        let mut leaving = Some(self.state.clone());
+        while let Some(state) = leaving {
+            if Some(&state) == shared.as_ref() {
+                break;
+            }
+            self.run_hooks(HookKind::Exit, &state);
+            leaving = self.parent_of(&state).cloned();
+        }
+
+        for action in &mut self.transitions[index].actions {
+            action(&mut self.context);
+        }
+
+        let mut entering: Vec<S> = self
+            .ancestry(&target)
+            .take_while(|&state| Some(state) != shared.as_ref())
+            .cloned()
+            .collect();
+        entering.reverse();
+        let mut leaf = target;
+        while let Some(child) = self.initial_child(&leaf) {
+            leaf = child.clone();
+            entering.push(leaf.clone());
+        }
+        for state in &entering {
+            self.run_hooks(HookKind::Entry, state);
+        }
+        self.state = leaf;
+        Ok(&self.state)
+    }
+
+    /// The index of the transition `event` would take, or whether a
+    /// matching transition existed but its guards said no.
+    fn find(&self, event: &E) -> Result<usize, bool> {
+        let mut rejected_by_guard = false;
+        // Innermost state first, so a child overrides its parent
+        for state in self.ancestry(&self.state) {
+            for (index, transition) in self.transitions.iter().enumerate() {
+                if transition.from != *state || transition.event != *event {
+                    continue;
+                }
+                if transition.guards.iter().all(|guard| guard(&self.context)) {
+                    return Ok(index);
+                }
+                rejected_by_guard = true;
+            }
+        }
+        Err(rejected_by_guard)
+    }
+
+    fn parent_of(&self, state: &S) -> Option<&S> {
+        self.parents.iter().find(|(child, _)| child == state).map(|(_, parent)| parent)
+    }
+
+    /// The first child declared for `state`, if it has any.
+    fn initial_child(&self, state: &S) -> Option<&S> {
+        self.parents.iter().find(|(_, parent)| parent == state).map(|(child, _)| child)
+    }
+
+    /// `state`, then its parent, grandparent and so on.
+    fn ancestry<'a>(&'a self, state: &'a S) -> impl Iterator<Item = &'a S> + 'a {
+        std::iter::successors(Some(state), move |&state| self.parent_of(state))
+    }
+
+    fn run_hooks(&mut self, kind: HookKind, state: &S) {
+        for hook in &mut self.hooks {
+            if hook.kind == kind && hook.state == *state {
+                (hook.action)(&mut self.context);
+            }
+        }
+    }
+}
+
+impl<S: fmt::Debug, E, C: fmt::Debug> fmt::Debug for StateMachine<S, E, C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("StateMachine")
+            .field("state", &self.state)
+            .field("context", &self.context)
+            .field("transitions", &self.transitions.len())
+            .finish()
+    }
+}
+
+/// Collects the transitions of a [`StateMachine`].
+pub struct StateMachineBuilder<S, E, C> {
+    machine: StateMachine<S, E, C>,
+}
+
+impl<S, E, C> StateMachineBuilder<S, E, C>
+where
+    S: Clone + PartialEq + fmt::Debug,
+    E: PartialEq + fmt::Debug,
+{
+    /// Starts a transition taken when `event` arrives in state `from`.
+    /// Finish it with [`to`](TransitionBuilder::to).
+    ///
+    /// When several transitions share a state and event, they are tried in
+    /// the order they were added.
+    pub fn on(self, from: S, event: E) -> TransitionBuilder<S, E, C> {
+        TransitionBuilder {
+            builder: self,
+            from,
+            event,
+            guards: Vec::new(),
+            actions: Vec::new(),
+        }
+    }
+
+    /// Makes `child` a state nested inside `parent`. The first child
+    /// declared for a parent is its initial state, entered whenever a
+    /// transition targets the parent.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `child` already has a parent, or if `parent` is `child`
+    /// or nested inside it.
+    pub fn substate(mut self, parent: S, child: S) -> Self {
+        assert!(
+            self.machine.parent_of(&child).is_none(),
+            "{:?} already has a parent state",
+            child
+        );
+        assert!(
+            !self.machine.ancestry(&parent).any(|state| *state == child),
+            "making {:?} a child of {:?} would create a cycle",
+            child,
+            parent
+        );
+        self.machine.parents.push((child, parent));
+        self
+    }
+
+    /// Runs `hook` on the context whenever the machine enters `state`.
+    pub fn on_entry<F>(self, state: S, hook: F) -> Self
+    where
+        F: FnMut(&mut C) + 'static,
+    {
+        self.hook(state, HookKind::Entry, Box::new(hook))
+    }
+
+    /// Runs `hook` on the context whenever the machine leaves `state`.
+    pub fn on_exit<F>(self, state: S, hook: F) -> Self
+    where
+        F: FnMut(&mut C) + 'static,
+    {
+        self.hook(state, HookKind::Exit, Box::new(hook))
+    }
+
+    /// Finishes the machine. If the initial state has children, the
+    /// machine starts in its initial leaf; no entry hooks run.
+    pub fn build(mut self) -> StateMachine<S, E, C> {
+        while let Some(child) = self.machine.initial_child(&self.machine.state) {
+            self.machine.state = child.clone();
+        }
+        self.machine
+    }
+
+    fn hook(mut self, state: S, kind: HookKind, action: Action<C>) -> Self {
+        self.machine.hooks.push(Hook { state, kind, action });
+        self
+    }
+}
+
+/// One transition being described; see [`StateMachineBuilder::on`].
+#[must_use = "a transition is only added once `to` is called"]
+pub struct TransitionBuilder<S, E, C> {
+    builder: StateMachineBuilder<S, E, C>,
+    from: S,
+    event: E,
+    guards: Vec<Guard<C>>,
+    actions: Vec<Action<C>>,
+}
+
+impl<S, E, C> TransitionBuilder<S, E, C> {
+    /// Adds a condition on the context that must hold for the transition
+    /// to be taken. Every guard must pass.
+    pub fn guard<F>(mut self, guard: F) -> Self
+    where
+        F: Fn(&C) -> bool + 'static,
+    {
+        self.guards.push(Box::new(guard));
+        self
+    }
+
+    /// Adds a step that runs on the context when the transition is taken,
+    /// after the guards and before the state changes.
+    pub fn action<F>(mut self, action: F) -> Self
+    where
+        F: FnMut(&mut C) + 'static,
+    {
+        self.actions.push(Box::new(action));
+        self
+    }
+
+    /// Sets the state the transition leads to and adds it to the machine.
+    pub fn to(mut self, target: S) -> StateMachineBuilder<S, E, C> {
+        self.builder.machine.transitions.push(Transition {
+            from: self.from,
+            event: self.event,
+            to: target,
+            guards: self.guards,
+            actions: self.actions,
+        });
+        self.builder
+    }
+}
+
+/// An event arrived that the current state has no transition for.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidTransition<S, E> {
+    /// The state the machine was (and still is) in.
+    pub state: S,
+    pub event: E,
+    /// True if a transition matched but one of its guards rejected it.
+    pub rejected_by_guard: bool,
+}
+
+impl<S: fmt::Debug, E: fmt::Debug> fmt::Display for InvalidTransition<S, E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.rejected_by_guard {
+            write!(f, "guard rejected {:?} in {:?}", self.event, self.state)
+        } else {
+            write!(f, "no transition from {:?} on {:?}", self.state, self.event)
+        }
+    }
+}
+
+impl<S: fmt::Debug, E: fmt::Debug> Error for InvalidTransition<S, E> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Door {
+        Open,
+        Closed,
+        Locked,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Action {
+        Open,
+        Close,
+        Lock,
+        Unlock,
+    }
+
+    struct Keys {
+        have_key: bool,
+        log: Vec<&'static str>,
+    }
+
+    fn door() -> StateMachine<Door, Action, Keys> {
+        StateMachine::builder(Door::Closed, Keys { have_key: true, log: Vec::new() })
+            .on(Door::Closed, Action::Open).action(|keys| keys.log.push("creak")).to(Door::Open)
+            .on(Door::Open, Action::Close).action(|keys| keys.log.push("slam")).to(Door::Closed)
+            .on(Door::Closed, Action::Lock).guard(|keys| keys.have_key).to(Door::Locked)
+            .on(Door::Locked, Action::Unlock).guard(|keys| keys.have_key).to(Door::Closed)
+            .build()
+    }
+
+    #[test]
+    fn test_transitions_run_actions_in_order() {
+        let mut machine = door();
+        assert_eq!(machine.fire(Action::Open), Ok(&Door::Open));
+        assert_eq!(machine.fire(Action::Close), Ok(&Door::Closed));
+        assert_eq!(machine.fire(Action::Lock), Ok(&Door::Locked));
+        assert_eq!(machine.context().log, vec!["creak", "slam"]);
+    }
+
+    #[test]
+    fn test_missing_transition_leaves_state_alone() {
+        let mut machine = door();
+        machine.fire(Action::Lock).unwrap();
+        let err = machine.fire(Action::Open).unwrap_err();
+        assert_eq!(
+            err,
+            InvalidTransition {
+                state: Door::Locked,
+                event: Action::Open,
+                rejected_by_guard: false,
+            }
+        );
+        assert_eq!(machine.state(), &Door::Locked);
+        assert!(!machine.can_fire(&Action::Open));
+    }
+
+    #[test]
+    fn test_guard_blocks_transition() {
+        let mut machine = door();
+        machine.fire(Action::Lock).unwrap();
+        machine.context_mut().have_key = false;
+        assert!(!machine.can_fire(&Action::Unlock));
+        let err = machine.fire(Action::Unlock).unwrap_err();
+        assert!(err.rejected_by_guard);
+        assert_eq!(err.to_string(), "guard rejected Unlock in Locked");
+        machine.context_mut().have_key = true;
+        assert_eq!(machine.fire(Action::Unlock), Ok(&Door::Closed));
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Conn {
+        Offline,
+        Online,
+        Idle,
+        Busy,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Net {
+        Connect,
+        Request,
+        Done,
+        Drop,
+    }
+
+    fn connection() -> StateMachine<Conn, Net, Vec<String>> {
+        let log = |text: &'static str| move |log: &mut Vec<String>| log.push(text.to_string());
+        StateMachine::builder(Conn::Offline, Vec::new())
+            .substate(Conn::Online, Conn::Idle)
+            .substate(Conn::Online, Conn::Busy)
+            .on_entry(Conn::Online, log("enter online"))
+            .on_exit(Conn::Online, log("exit online"))
+            .on_entry(Conn::Idle, log("enter idle"))
+            .on_exit(Conn::Idle, log("exit idle"))
+            .on_entry(Conn::Busy, log("enter busy"))
+            .on_exit(Conn::Busy, log("exit busy"))
+            .on(Conn::Offline, Net::Connect).to(Conn::Online)
+            .on(Conn::Idle, Net::Request).to(Conn::Busy)
+            .on(Conn::Busy, Net::Done).to(Conn::Idle)
+            // Handled by the parent, whichever child is active
+            .on(Conn::Online, Net::Drop).action(log("dropped")).to(Conn::Offline)
+            .build()
+    }
+
+    #[test]
+    fn test_entering_a_parent_enters_its_initial_child() {
+        let mut machine = connection();
+        assert_eq!(machine.fire(Net::Connect), Ok(&Conn::Idle));
+        assert!(machine.is_in(&Conn::Online));
+        assert!(!machine.is_in(&Conn::Busy));
+        assert_eq!(machine.context(), &["enter online", "enter idle"]);
+    }
+
+    #[test]
+    fn test_sibling_transition_keeps_the_parent() {
+        let mut machine = connection();
+        machine.fire(Net::Connect).unwrap();
+        machine.context_mut().clear();
+        assert_eq!(machine.fire(Net::Request), Ok(&Conn::Busy));
+        assert_eq!(machine.context(), &["exit idle", "enter busy"]);
+    }
+
+    #[test]
+    fn test_unhandled_events_bubble_to_the_parent() {
+        let mut machine = connection();
+        machine.fire(Net::Connect).unwrap();
+        machine.fire(Net::Request).unwrap();
+        machine.context_mut().clear();
+        assert_eq!(machine.fire(Net::Drop), Ok(&Conn::Offline));
+        assert_eq!(machine.context(), &["exit busy", "exit online", "dropped"]);
+        // Offline has no parent to bubble to
+        assert!(!machine.can_fire(&Net::Drop));
+    }
+
+    #[test]
+    fn test_transition_to_own_parent_reenters_it() {
+        let mut machine = StateMachine::builder("playing", Vec::new())
+            .substate("playing", "running")
+            .on_entry("playing", |log: &mut Vec<&str>| log.push("enter playing"))
+            .on_exit("playing", |log: &mut Vec<&str>| log.push("exit playing"))
+            .on("running", "restart").to("playing")
+            .build();
+        // Building into a parent starts in its initial child
+        assert_eq!(machine.state(), &"running");
+        assert_eq!(machine.fire("restart"), Ok(&"running"));
+        assert_eq!(machine.context(), &["exit playing", "enter playing"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "cycle")]
+    fn test_substate_cycle_panics() {
+        let _ = StateMachine::<_, (), ()>::builder(1, ()).substate(1, 2).substate(2, 1);
+    }
+
+    #[test]
+    fn test_first_passing_guard_wins() {
+        // Scores of 10 or more win, anything less loses
+        let mut machine = StateMachine::builder("playing", 12)
+            .on("playing", "finish").guard(|score| *score >= 10).to("won")
+            .on("playing", "finish").to("lost")
+            .build();
+        assert_eq!(machine.fire("finish"), Ok(&"won"));
+
+        let mut machine = StateMachine::builder("playing", 3)
+            .on("playing", "finish").guard(|score| *score >= 10).to("won")
+            .on("playing", "finish").to("lost")
+            .build();
+        assert_eq!(machine.fire("finish"), Ok(&"lost"));
+    }
+}
@@ -0,0 +1,370 @@
+//! Checksums and fast non-cryptographic hashes.
+//!
+//! | Algorithm | Output        | Typical use                              |
+//! |-----------|---------------|------------------------------------------|
+//! | CRC-32    | `u32`         | detecting corruption (zip, gzip, PNG)    |
+//! | Adler-32  | `u32`         | a cheaper corruption check (zlib)        |
+//! | FNV-1a    | `u32`, `u64`  | hash tables and Bloom filters            |
+//! | DJB2      | `u32`         | the classic string hash from comp.lang.c |
+//!
+//! None of these resist an attacker: anyone can craft two inputs with the
+//! same value. They are for catching accidents and spreading keys, not
+//! for passwords or signatures.
+//!
+//! Each algorithm is a small state type implementing [`Checksum`], so input
+//! can be fed in pieces; the free functions hash a whole slice at once and
+//! [`read`] hashes everything a reader yields without loading it all.
+//!
+//! ```
+//! use rustler::hash_lite::{self, Crc32};
+//!
+//! assert_eq!(hash_lite::crc32(b"123456789"), 0xCBF4_3926);
+//!
+//! let file = std::io::Cursor::new(vec![b'x'; 100_000]);
+//! let streamed = hash_lite::read(Crc32::new(), file).unwrap();
+//! assert_eq!(streamed, hash_lite::crc32(&[b'x'; 100_000]));
+//! ```
+
+use std::hash::Hasher;
+use std::io::{self, Read};
+
+/// A hash or checksum computed incrementally: feeding the input in pieces
+/// gives the same result as feeding it all at once.
+pub trait Checksum {
+    type Output;
+
+    fn update(&mut self, bytes: &[u8]);
+
+    /// The value for everything fed so far. Does not reset the state, so
+    /// more input can follow.
+    fn finish(&self) -> Self::Output;
+}
+
+/// Feeds everything `reader` yields into `checksum`, a buffer at a time,
+/// and returns the result.
+pub fn read<C: Checksum>(mut checksum: C, mut reader: impl Read) -> io::Result<C::Output> {
+    let mut buffer = [0; 8192];
+    loop {
+        match reader.read(&mut buffer) {
+            Ok(0) => return Ok(checksum.finish()),
+            Ok(n) => checksum.update(&buffer[..n]),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// CRC-32 with the IEEE polynomial, reflected, as used by zip, gzip and PNG.
+///
+/// It works a byte at a time through a 256-entry table that is built at
+/// compile time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Crc32(u32);
+
+impl Crc32 {
+    const TABLE: [u32; 256] = {
+        let mut table = [0; 256];
+        let mut i = 0;
+        while i < 256 {
+            let mut crc = i as u32;
+            let mut bit = 0;
+            while bit < 8 {
+                crc = if crc & 1 == 1 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+                bit += 1;
+            }
+            table[i] = crc;
+            i += 1;
+        }
+        table
+    };
+
+    pub fn new() -> Self {
+        Crc32(0xFFFF_FFFF)
+    }
+}
+
+impl Default for Crc32 {
+    fn default() -> Self {
+        Crc32::new()
+    }
+}
+
+impl Checksum for Crc32 {
+    type Output = u32;
+
+    fn update(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 >> 8) ^ Self::TABLE[((self.0 ^ byte as u32) & 0xFF) as usize];
+        }
+    }
+
+    fn finish(&self) -> u32 {
+        !self.0
+    }
+}
+
+/// Adler-32, from zlib: two running sums modulo the largest prime below
+/// 2^16. Faster than CRC-32 but weaker on short inputs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Adler32 {
+    a: u32,
+    b: u32,
+}
+
+impl Adler32 {
+    const MODULUS: u32 = 65_521;
+    /// The most bytes that can be summed before `b` could overflow a `u32`,
+    /// so the modulo only needs taking once per run.
+    const RUN: usize = 5552;
+
+    pub fn new() -> Self {
+        Adler32 { a: 1, b: 0 }
+    }
+}
+
+impl Default for Adler32 {
+    fn default() -> Self {
+        Adler32::new()
+    }
+}
+
+impl Checksum for Adler32 {
+    type Output = u32;
+
+    fn update(&mut self, bytes: &[u8]) {
+        for run in bytes.chunks(Self::RUN) {
+            for &byte in run {
+                self.a += byte as u32;
+                self.b += self.a;
+            }
+            self.a %= Self::MODULUS;
+            self.b %= Self::MODULUS;
+        }
+    }
+
+    fn finish(&self) -> u32 {
+        (self.b << 16) | self.a
+    }
+}
+
+/// 32-bit FNV-1a: xor in a byte, multiply by a prime, repeat.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fnv1a32(u32);
+
+impl Fnv1a32 {
+    const OFFSET_BASIS: u32 = 0x811C_9DC5;
+    const PRIME: u32 = 0x0100_0193;
+
+    pub fn new() -> Self {
+        Fnv1a32(Self::OFFSET_BASIS)
+    }
+}
+
+impl Default for Fnv1a32 {
+    fn default() -> Self {
+        Fnv1a32::new()
+    }
+}
+
+impl Checksum for Fnv1a32 {
+    type Output = u32;
+
+    fn update(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ byte as u32).wrapping_mul(Self::PRIME);
+        }
+    }
+
+    fn finish(&self) -> u32 {
+        self.0
+    }
+}
+
+/// 64-bit FNV-1a. It also implements [`std::hash::Hasher`], so it can
+/// replace the default hasher of a `HashMap` whose keys are short and not
+/// chosen by an attacker:
+///
+/// ```
+/// use std::collections::HashMap;
+/// use std::hash::BuildHasherDefault;
+/// use rustler::hash_lite::Fnv1a64;
+///
+/// let mut counts: HashMap<&str, u32, BuildHasherDefault<Fnv1a64>> = HashMap::default();
+/// *counts.entry("apple").or_default() += 1;
+/// assert_eq!(counts["apple"], 1);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fnv1a64(u64);
+
+impl Fnv1a64 {
+    const OFFSET_BASIS: u64 = 0xCBF2_9CE4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01B3;
+
+    pub fn new() -> Self {
+        Fnv1a64(Self::OFFSET_BASIS)
+    }
+}
+
+impl Default for Fnv1a64 {
+    fn default() -> Self {
+        Fnv1a64::new()
+    }
+}
+
+impl Checksum for Fnv1a64 {
+    type Output = u64;
+
+    fn update(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ byte as u64).wrapping_mul(Self::PRIME);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+impl Hasher for Fnv1a64 {
+    fn write(&mut self, bytes: &[u8]) {
+        Checksum::update(self, bytes);
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Dan Bernstein's DJB2: `hash * 33 + byte`, starting from 5381.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Djb2(u32);
+
+impl Djb2 {
+    pub fn new() -> Self {
+        Djb2(5381)
+    }
+}
+
+impl Default for Djb2 {
+    fn default() -> Self {
+        Djb2::new()
+    }
+}
+
+impl Checksum for Djb2 {
+    type Output = u32;
+
+    fn update(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = self.0.wrapping_mul(33).wrapping_add(byte as u32);
+        }
+    }
+
+    fn finish(&self) -> u32 {
+        self.0
+    }
+}
+
+fn once<C: Checksum>(mut checksum: C, bytes: &[u8]) -> C::Output {
+    checksum.update(bytes);
+    checksum.finish()
+}
+
+pub fn crc32(bytes: &[u8]) -> u32 {
+    once(Crc32::new(), bytes)
+}
+
+pub fn adler32(bytes: &[u8]) -> u32 {
+    once(Adler32::new(), bytes)
+}
+
+pub fn fnv1a_32(bytes: &[u8]) -> u32 {
+    once(Fnv1a32::new(), bytes)
+}
+
+pub fn fnv1a_64(bytes: &[u8]) -> u64 {
+    once(Fnv1a64::new(), bytes)
+}
+
+pub fn djb2(bytes: &[u8]) -> u32 {
+    once(Djb2::new(), bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc32_known_answers() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"a"), 0xE8B7_BE43);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b"The quick brown fox jumps over the lazy dog"), 0x414F_A339);
+    }
+
+    #[test]
+    fn test_adler32_known_answers() {
+        assert_eq!(adler32(b""), 1);
+        assert_eq!(adler32(b"a"), 0x0062_0062);
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+        assert_eq!(adler32(b"123456789"), 0x091E_01DE);
+        // Long enough to need the modulo mid-stream
+        assert_eq!(adler32(&[0xFF; 100_000]), 0x149A_302C);
+    }
+
+    #[test]
+    fn test_fnv1a_known_answers() {
+        assert_eq!(fnv1a_32(b""), 0x811C_9DC5);
+        assert_eq!(fnv1a_32(b"a"), 0xE40C_292C);
+        assert_eq!(fnv1a_32(b"foobar"), 0xBF9C_F968);
+        assert_eq!(fnv1a_64(b""), 0xCBF2_9CE4_8422_2325);
+        assert_eq!(fnv1a_64(b"a"), 0xAF63_DC4C_8601_EC8C);
+        assert_eq!(fnv1a_64(b"foobar"), 0x8594_4171_F739_67E8);
+    }
+
+    #[test]
+    fn test_djb2_known_answers() {
+        assert_eq!(djb2(b""), 5381);
+        assert_eq!(djb2(b"a"), 0x0002_B606);
+        assert_eq!(djb2(b"hello"), 0x0F92_3099);
+        assert_eq!(djb2(b"123456789"), 0x35CD_BB82);
+    }
+
+    #[test]
+    fn test_pieces_match_whole() {
+        let data: Vec<u8> = (0..20_000u32).map(|i| (i * 7 % 256) as u8).collect();
+        for split in [0, 1, 5551, 5552, 5553, 13_000, 20_000] {
+            let (left, right) = data.split_at(split);
+            let mut crc = Crc32::new();
+            let mut adler = Adler32::new();
+            let mut fnv = Fnv1a64::new();
+            let mut djb = Djb2::new();
+            for piece in [left, right] {
+                crc.update(piece);
+                adler.update(piece);
+                Checksum::update(&mut fnv, piece);
+                djb.update(piece);
+            }
+            assert_eq!(crc.finish(), crc32(&data));
+            assert_eq!(adler.finish(), adler32(&data));
+            assert_eq!(Checksum::finish(&fnv), fnv1a_64(&data));
+            assert_eq!(djb.finish(), djb2(&data));
+        }
+    }
+
+    #[test]
+    fn test_read_streams_a_reader() {
+        let data = b"The quick brown fox jumps over the lazy dog".repeat(1000);
+        assert_eq!(read(Crc32::new(), &data[..]).unwrap(), crc32(&data));
+        assert_eq!(read(Adler32::new(), &data[..]).unwrap(), adler32(&data));
+        assert_eq!(read(Fnv1a32::new(), &data[..]).unwrap(), fnv1a_32(&data));
+        assert_eq!(read(Djb2::new(), io::empty()).unwrap(), 5381);
+    }
+
+    #[test]
+    fn test_fnv_as_std_hasher() {
+        let mut hasher = Fnv1a64::new();
+        hasher.write(b"foobar");
+        assert_eq!(Hasher::finish(&hasher), 0x8594_4171_F739_67E8);
+    }
+}
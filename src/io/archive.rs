@@ -0,0 +1,435 @@
+//! A minimal archive format in the spirit of tar: many files in one.
+//!
+//! An archive is a short file header followed by one block per entry and
+//! an end marker. All numbers are little-endian:
+//!
+//! ```text
+//! file header   b"RSAR"  version: u8
+//! entry         kind: u8  path_len: u16  path: [u8]  size: u64  data: [u8; size]  crc32: u32
+//! ...
+//! end           kind = 0xFF
+//! ```
+//!
+//! `kind` is 0 for a file and 1 for a directory, which has no data. Paths
+//! are relative and use `/` on every platform. Tar pads every block to 512
+//! bytes so that tapes could seek; this format does not bother.
+//!
+//! The CRC-32 after each file's data catches corruption when unpacking. It
+//! comes after the data rather than in the header so that [`Writer`] can
+//! stream a file of any size without reading it twice.
+
+use std::error::Error;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Component, Path};
+
+use crate::hash_lite::{crc32, Checksum, Crc32};
+
+const MAGIC: &[u8; 4] = b"RSAR";
+/// The format version written by [`Writer`].
+pub const VERSION: u8 = 1;
+
+const KIND_FILE: u8 = 0;
+const KIND_DIR: u8 = 1;
+const KIND_END: u8 = 0xFF;
+
+/// What an entry holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryKind {
+    File,
+    Dir,
+}
+
+/// One file or directory read from an archive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// Relative path with `/` separators.
+    pub path: String,
+    pub kind: EntryKind,
+    /// The file's contents; empty for a directory.
+    pub data: Vec<u8>,
+}
+
+/// Writes entries to an archive.
+///
+/// ```
+/// use rustler::io::archive::{EntryKind, Reader, Writer};
+///
+/// let mut writer = Writer::new(Vec::new()).unwrap();
+/// writer.add_dir("notes").unwrap();
+/// writer.add_file("notes/todo.txt", &mut "buy milk".as_bytes()).unwrap();
+/// let bytes = writer.finish().unwrap();
+///
+/// let entries: Vec<_> = Reader::new(&bytes[..]).unwrap().collect::<Result<_, _>>().unwrap();
+/// assert_eq!(entries[0].kind, EntryKind::Dir);
+/// assert_eq!(entries[1].path, "notes/todo.txt");
+/// assert_eq!(entries[1].data, b"buy milk");
+/// ```
+#[derive(Debug)]
+pub struct Writer<W: Write> {
+    output: W,
+}
+
+impl<W: Write> Writer<W> {
+    /// Starts an archive by writing the file header.
+    pub fn new(mut output: W) -> Result<Writer<W>, ArchiveError> {
+        output.write_all(MAGIC)?;
+        output.write_all(&[VERSION])?;
+        Ok(Writer { output })
+    }
+
+    /// Adds a directory entry, so that empty directories survive.
+    pub fn add_dir(&mut self, path: &str) -> Result<(), ArchiveError> {
+        self.write_header(KIND_DIR, path, 0)
+    }
+
+    /// Adds a file whose contents are everything `data` yields. The size
+    /// goes in the header before the data, so `data` is read into memory
+    /// first; [`add_path`](Writer::add_path) streams files from disk.
+    pub fn add_file(&mut self, path: &str, data: &mut impl Read) -> Result<(), ArchiveError> {
+        let mut contents = Vec::new();
+        data.read_to_end(&mut contents)?;
+        self.write_header(KIND_FILE, path, contents.len() as u64)?;
+        self.output.write_all(&contents)?;
+        self.output.write_all(&crc32(&contents).to_le_bytes())?;
+        Ok(())
+    }
+
+    /// Adds a file from disk, streaming it in pieces.
+    pub fn add_path(&mut self, path: &str, source: &Path) -> Result<(), ArchiveError> {
+        let mut file = File::open(source)?;
+        let size = file.metadata()?.len();
+        self.write_header(KIND_FILE, path, size)?;
+        let mut crc = Crc32::new();
+        let mut buffer = [0; 8192];
+        let mut remaining = size;
+        while remaining > 0 {
+            let wanted = buffer.len().min(remaining as usize);
+            let read = file.read(&mut buffer[..wanted])?;
+            if read == 0 {
+                return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "file shrank while being archived").into());
+            }
+            crc.update(&buffer[..read]);
+            self.output.write_all(&buffer[..read])?;
+            remaining -= read as u64;
+        }
+        self.output.write_all(&crc.finish().to_le_bytes())?;
+        Ok(())
+    }
+
+    /// Writes the end marker and returns the underlying writer.
+    pub fn finish(mut self) -> Result<W, ArchiveError> {
+        self.output.write_all(&[KIND_END])?;
+        self.output.flush()?;
+        Ok(self.output)
+    }
+
+    fn write_header(&mut self, kind: u8, path: &str, size: u64) -> Result<(), ArchiveError> {
+        check_path(path)?;
+        let len = u16::try_from(path.len()).map_err(|_| ArchiveError::UnsafePath(path.to_string()))?;
+        self.output.write_all(&[kind])?;
+        self.output.write_all(&len.to_le_bytes())?;
+        self.output.write_all(path.as_bytes())?;
+        self.output.write_all(&size.to_le_bytes())?;
+        Ok(())
+    }
+}
+
+/// Reads entries back, checking each file's checksum. Each entry's data is
+/// held in memory, so this suits archives of modest files.
+#[derive(Debug)]
+pub struct Reader<R: Read> {
+    input: R,
+    done: bool,
+}
+
+impl<R: Read> Reader<R> {
+    /// Checks the file header.
+    pub fn new(mut input: R) -> Result<Reader<R>, ArchiveError> {
+        let mut header = [0; 5];
+        read_exact(&mut input, &mut header)?;
+        if &header[..4] != MAGIC {
+            return Err(ArchiveError::NotAnArchive);
+        }
+        if header[4] != VERSION {
+            return Err(ArchiveError::UnsupportedVersion(header[4]));
+        }
+        Ok(Reader { input, done: false })
+    }
+
+    /// The next entry, or `None` after the end marker.
+    pub fn next_entry(&mut self) -> Result<Option<Entry>, ArchiveError> {
+        if self.done {
+            return Ok(None);
+        }
+        let kind = match self.read_array::<1>()?[0] {
+            KIND_END => {
+                self.done = true;
+                return Ok(None);
+            }
+            KIND_FILE => EntryKind::File,
+            KIND_DIR => EntryKind::Dir,
+            other => return Err(ArchiveError::UnknownKind(other)),
+        };
+        let len = u16::from_le_bytes(self.read_array()?) as usize;
+        let mut path = vec![0; len];
+        read_exact(&mut self.input, &mut path)?;
+        let path = String::from_utf8(path).map_err(|e| ArchiveError::UnsafePath(String::from_utf8_lossy(e.as_bytes()).into_owned()))?;
+        check_path(&path)?;
+        let size = u64::from_le_bytes(self.read_array()?);
+
+        let mut data = Vec::new();
+        if kind == EntryKind::File {
+            (&mut self.input).take(size).read_to_end(&mut data)?;
+            if (data.len() as u64) < size {
+                return Err(ArchiveError::Truncated);
+            }
+            let stored = u32::from_le_bytes(self.read_array()?);
+            if crc32(&data) != stored {
+                return Err(ArchiveError::ChecksumMismatch { path });
+            }
+        }
+        Ok(Some(Entry { path, kind, data }))
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N], ArchiveError> {
+        let mut bytes = [0; N];
+        read_exact(&mut self.input, &mut bytes)?;
+        Ok(bytes)
+    }
+}
+
+impl<R: Read> Iterator for Reader<R> {
+    type Item = Result<Entry, ArchiveError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let entry = self.next_entry().transpose();
+        if matches!(entry, Some(Err(_))) {
+            // Past a bad entry the stream position is unknown
+            self.done = true;
+        }
+        entry
+    }
+}
+
+/// Like `read_exact`, but running out of input is [`ArchiveError::Truncated`].
+fn read_exact(input: &mut impl Read, buffer: &mut [u8]) -> Result<(), ArchiveError> {
+    input.read_exact(buffer).map_err(|e| match e.kind() {
+        io::ErrorKind::UnexpectedEof => ArchiveError::Truncated,
+        _ => ArchiveError::Io(e),
+    })
+}
+
+/// Rejects paths that could escape the directory being unpacked into:
+/// absolute paths, `..`, and empty names.
+fn check_path(path: &str) -> Result<(), ArchiveError> {
+    let unsafe_path = || ArchiveError::UnsafePath(path.to_string());
+    if path.is_empty() || path.contains('\\') {
+        return Err(unsafe_path());
+    }
+    for component in Path::new(path).components() {
+        if !matches!(component, Component::Normal(_)) {
+            return Err(unsafe_path());
+        }
+    }
+    Ok(())
+}
+
+/// Packs everything under `dir` into a new archive at `archive`, in sorted
+/// order so the same tree always gives the same bytes. Returns the number
+/// of entries. Symbolic links and other special files are skipped.
+pub fn pack(dir: impl AsRef<Path>, archive: impl AsRef<Path>) -> Result<usize, ArchiveError> {
+    let mut writer = Writer::new(BufWriter::new(File::create(archive)?))?;
+    let count = pack_dir(&mut writer, dir.as_ref(), "")?;
+    writer.finish()?;
+    Ok(count)
+}
+
+fn pack_dir<W: Write>(writer: &mut Writer<W>, dir: &Path, prefix: &str) -> Result<usize, ArchiveError> {
+    let mut children: Vec<_> = fs::read_dir(dir)?.collect::<Result<_, _>>()?;
+    children.sort_by_key(|entry| entry.file_name());
+    let mut count = 0;
+    for child in children {
+        let name = child.file_name();
+        let name = name
+            .to_str()
+            .ok_or_else(|| ArchiveError::UnsafePath(name.to_string_lossy().into_owned()))?;
+        let path = format!("{}{}", prefix, name);
+        let file_type = child.file_type()?;
+        if file_type.is_dir() {
+            writer.add_dir(&path)?;
+            count += 1 + pack_dir(writer, &child.path(), &format!("{}/", path))?;
+        } else if file_type.is_file() {
+            writer.add_path(&path, &child.path())?;
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+/// Unpacks `archive` into `dest`, creating it if needed, and returns the
+/// number of entries. Existing files with the same names are overwritten.
+///
+/// Every path is checked before anything is written for it, so a crafted
+/// archive cannot write outside `dest`.
+pub fn unpack(archive: impl AsRef<Path>, dest: impl AsRef<Path>) -> Result<usize, ArchiveError> {
+    let dest = dest.as_ref();
+    fs::create_dir_all(dest)?;
+    let mut reader = Reader::new(BufReader::new(File::open(archive)?))?;
+    let mut count = 0;
+    while let Some(entry) = reader.next_entry()? {
+        let target = dest.join(&entry.path);
+        match entry.kind {
+            EntryKind::Dir => fs::create_dir_all(&target)?,
+            EntryKind::File => {
+                if let Some(parent) = target.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(&target, &entry.data)?;
+            }
+        }
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// Errors from writing or reading an archive.
+#[derive(Debug)]
+pub enum ArchiveError {
+    Io(io::Error),
+    /// The input does not start with the archive header.
+    NotAnArchive,
+    UnsupportedVersion(u8),
+    /// The input ended in the middle of an entry.
+    Truncated,
+    UnknownKind(u8),
+    /// A file's data does not match its checksum.
+    ChecksumMismatch { path: String },
+    /// A path that is absolute, contains `..`, or is otherwise unusable.
+    UnsafePath(String),
+}
+
+impl fmt::Display for ArchiveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ArchiveError::Io(e) => write!(f, "I/O error: {}", e),
+            ArchiveError::NotAnArchive => write!(f, "not an archive (bad magic bytes)"),
+            ArchiveError::UnsupportedVersion(version) => {
+                write!(f, "archive version {} is not supported (expected {})", version, VERSION)
+            }
+            ArchiveError::Truncated => write!(f, "archive is truncated"),
+            ArchiveError::UnknownKind(kind) => write!(f, "unknown entry kind {:#04x}", kind),
+            ArchiveError::ChecksumMismatch { path } => write!(f, "checksum mismatch for `{}`", path),
+            ArchiveError::UnsafePath(path) => write!(f, "unsafe path `{}` in archive", path),
+        }
+    }
+}
+
+impl Error for ArchiveError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ArchiveError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ArchiveError {
+    fn from(e: io::Error) -> Self {
+        ArchiveError::Io(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::TempDir;
+
+    fn archive_of(entries: &[(&str, Option<&[u8]>)]) -> Vec<u8> {
+        let mut writer = Writer::new(Vec::new()).unwrap();
+        for &(path, data) in entries {
+            match data {
+                Some(mut data) => writer.add_file(path, &mut data).unwrap(),
+                None => writer.add_dir(path).unwrap(),
+            }
+        }
+        writer.finish().unwrap()
+    }
+
+    #[test]
+    fn test_byte_layout() {
+        let bytes = archive_of(&[("a", Some(b"hi"))]);
+        let mut expected = b"RSAR\x01".to_vec();
+        expected.extend([KIND_FILE, 1, 0, b'a']);
+        expected.extend(2u64.to_le_bytes());
+        expected.extend(b"hi");
+        expected.extend(crc32(b"hi").to_le_bytes());
+        expected.push(KIND_END);
+        assert_eq!(bytes, expected);
+    }
+
+    #[test]
+    fn test_pack_and_unpack_a_tree() {
+        let source = TempDir::new("archive-src").unwrap();
+        fs::create_dir_all(source.join("docs/empty")).unwrap();
+        fs::write(source.join("readme.txt"), "top level").unwrap();
+        fs::write(source.join("docs/guide.md"), "# Guide\n").unwrap();
+        let big: Vec<u8> = (0..50_000u32).map(|i| (i % 251) as u8).collect();
+        fs::write(source.join("docs/data.bin"), &big).unwrap();
+
+        let work = TempDir::new("archive-out").unwrap();
+        let archive = work.join("tree.rsar");
+        assert_eq!(pack(&source, &archive).unwrap(), 5);
+        assert_eq!(unpack(&archive, work.join("copy")).unwrap(), 5);
+
+        let copy = work.join("copy");
+        assert_eq!(fs::read_to_string(copy.join("readme.txt")).unwrap(), "top level");
+        assert_eq!(fs::read_to_string(copy.join("docs/guide.md")).unwrap(), "# Guide\n");
+        assert_eq!(fs::read(copy.join("docs/data.bin")).unwrap(), big);
+        assert!(copy.join("docs/empty").is_dir());
+
+        // Sorted traversal makes packing deterministic
+        let again = work.join("again.rsar");
+        pack(&source, &again).unwrap();
+        assert_eq!(fs::read(&archive).unwrap(), fs::read(&again).unwrap());
+    }
+
+    #[test]
+    fn test_corruption_is_detected() {
+        let mut bytes = archive_of(&[("file.txt", Some(b"important data"))]);
+        let data_start = 5 + 1 + 2 + "file.txt".len() + 8;
+        bytes[data_start + 3] ^= 0x01;
+        let err = Reader::new(&bytes[..]).unwrap().next().unwrap().unwrap_err();
+        assert_eq!(err.to_string(), "checksum mismatch for `file.txt`");
+
+        let bytes = archive_of(&[("file.txt", Some(b"important data"))]);
+        let mut reader = Reader::new(&bytes[..bytes.len() - 6]).unwrap();
+        assert!(matches!(reader.next(), Some(Err(ArchiveError::Truncated))));
+        assert!(reader.next().is_none());
+
+        assert!(matches!(Reader::new(&b"PK\x03\x04\x01"[..]), Err(ArchiveError::NotAnArchive)));
+        assert!(matches!(Reader::new(&b"RSAR\x09"[..]), Err(ArchiveError::UnsupportedVersion(9))));
+    }
+
+    #[test]
+    fn test_unsafe_paths_are_rejected() {
+        for path in ["../escape.txt", "/etc/passwd", "a/../../b", "", "dir\\file"] {
+            let mut writer = Writer::new(Vec::new()).unwrap();
+            assert!(matches!(writer.add_dir(path), Err(ArchiveError::UnsafePath(_))), "{}", path);
+        }
+
+        // A hand-made archive smuggling in a parent path never gets written
+        let mut bytes = b"RSAR\x01".to_vec();
+        bytes.extend([KIND_FILE, 5, 0]);
+        bytes.extend(b"../x1");
+        bytes.extend(0u64.to_le_bytes());
+        bytes.extend(crc32(b"").to_le_bytes());
+        bytes.push(KIND_END);
+        let work = TempDir::new("archive-evil").unwrap();
+        fs::write(work.join("evil.rsar"), &bytes).unwrap();
+        let err = unpack(work.join("evil.rsar"), work.join("out")).unwrap_err();
+        assert_eq!(err.to_string(), "unsafe path `../x1` in archive");
+        assert!(!work.join("x1").exists());
+    }
+}
@@ -0,0 +1,288 @@
+//! Reading files far bigger than memory, a piece at a time.
+//!
+//! `fs::read_to_string` on a 20 GB log asks for 20 GB of RAM. The adapters
+//! here hold one buffer at a time whatever the file's size: [`Chunks`]
+//! yields fixed-size blocks of bytes and [`ChunkedLines`] yields lines.
+//! [`process_lines`] puts a line callback and progress reports on top.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::Path;
+
+/// The buffer size used when none is given.
+pub const DEFAULT_CAPACITY: usize = 64 * 1024;
+
+/// Fixed-size blocks of bytes from a reader. Every block is full except
+/// perhaps the last, even when the reader hands back fewer bytes than
+/// asked for, as pipes and sockets often do.
+///
+/// ```
+/// use rustler::io::Chunks;
+///
+/// let blocks: Vec<Vec<u8>> = Chunks::new(&b"abcdefg"[..], 3).collect::<Result<_, _>>().unwrap();
+/// assert_eq!(blocks, vec![b"abc".to_vec(), b"def".to_vec(), b"g".to_vec()]);
+/// ```
+#[derive(Debug)]
+pub struct Chunks<R: Read> {
+    reader: R,
+    size: usize,
+    done: bool,
+}
+
+impl<R: Read> Chunks<R> {
+    /// Blocks of `size` bytes.
+    ///
+    /// # Panics
+    ///
+    /// If `size` is zero.
+    pub fn new(reader: R, size: usize) -> Self {
+        assert!(size > 0, "chunk size must be positive");
+        Chunks { reader, size, done: false }
+    }
+}
+
+impl<R: Read> Iterator for Chunks<R> {
+    type Item = io::Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let mut chunk = vec![0; self.size];
+        let mut filled = 0;
+        while filled < self.size {
+            match self.reader.read(&mut chunk[filled..]) {
+                Ok(0) => {
+                    self.done = true;
+                    break;
+                }
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+        if filled == 0 {
+            return None;
+        }
+        chunk.truncate(filled);
+        Some(Ok(chunk))
+    }
+}
+
+/// Lines from a reader, without their `\n` or `\r\n`.
+///
+/// Unlike [`BufRead::lines`], bytes that are not valid UTF-8 are replaced
+/// with `�` instead of ending the iteration with an error, since one bad
+/// byte in a huge log should not stop the whole run. The number of bytes
+/// consumed so far is available for progress reports.
+///
+/// ```
+/// use rustler::io::ChunkedLines;
+///
+/// let mut lines = ChunkedLines::new(&b"one\r\ntwo\n\xFFthree"[..]);
+/// assert_eq!(lines.next().unwrap().unwrap(), "one");
+/// assert_eq!(lines.bytes_read(), 5);
+/// let rest: Vec<String> = lines.collect::<Result<_, _>>().unwrap();
+/// assert_eq!(rest, vec!["two", "\u{FFFD}three"]);
+/// ```
+#[derive(Debug)]
+pub struct ChunkedLines<R: Read> {
+    reader: BufReader<R>,
+    line: Vec<u8>,
+    bytes_read: u64,
+}
+
+impl<R: Read> ChunkedLines<R> {
+    pub fn new(reader: R) -> Self {
+        ChunkedLines::with_capacity(DEFAULT_CAPACITY, reader)
+    }
+
+    /// Reads from `reader` `capacity` bytes at a time.
+    pub fn with_capacity(capacity: usize, reader: R) -> Self {
+        ChunkedLines {
+            reader: BufReader::with_capacity(capacity, reader),
+            line: Vec::new(),
+            bytes_read: 0,
+        }
+    }
+
+    /// Bytes consumed so far, line endings included.
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes_read
+    }
+}
+
+impl<R: Read> Iterator for ChunkedLines<R> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // One buffer is reused for every line
+        self.line.clear();
+        match self.reader.read_until(b'\n', &mut self.line) {
+            Ok(0) => None,
+            Ok(n) => {
+                self.bytes_read += n as u64;
+                let mut end = self.line.len();
+                if self.line[..end].ends_with(b"\n") {
+                    end -= 1;
+                    if self.line[..end].ends_with(b"\r") {
+                        end -= 1;
+                    }
+                }
+                Some(Ok(String::from_utf8_lossy(&self.line[..end]).into_owned()))
+            }
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+/// How far [`process_lines`] has got.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    pub lines: u64,
+    pub bytes: u64,
+    /// The file's size when processing started.
+    pub total_bytes: u64,
+}
+
+impl Progress {
+    /// Between 0 and 1; an empty file counts as finished.
+    pub fn fraction(&self) -> f64 {
+        if self.total_bytes == 0 {
+            1.0
+        } else {
+            (self.bytes as f64 / self.total_bytes as f64).min(1.0)
+        }
+    }
+}
+
+/// Calls `f` on every line of the file at `path` and returns the final
+/// [`Progress`]. Memory use stays flat however large the file is.
+///
+/// ```
+/// use rustler::io::{process_lines, TempFile};
+///
+/// let file = TempFile::new("docs").unwrap();
+/// std::fs::write(&file, "GET /\nPOST /login\nGET /about\n").unwrap();
+///
+/// let mut gets = 0;
+/// let done = process_lines(&file, |line| {
+///     if line.starts_with("GET") {
+///         gets += 1;
+///     }
+/// })
+/// .unwrap();
+/// assert_eq!((gets, done.lines), (2, 3));
+/// ```
+pub fn process_lines(path: impl AsRef<Path>, f: impl FnMut(&str)) -> io::Result<Progress> {
+    process_lines_with_progress(path, u64::MAX, f, |_| {})
+}
+
+/// Like [`process_lines`], also calling `on_progress` each time another
+/// `every` bytes have been read, and once more at the end.
+pub fn process_lines_with_progress(
+    path: impl AsRef<Path>,
+    every: u64,
+    mut f: impl FnMut(&str),
+    mut on_progress: impl FnMut(&Progress),
+) -> io::Result<Progress> {
+    let file = File::open(path)?;
+    let mut progress = Progress {
+        lines: 0,
+        bytes: 0,
+        total_bytes: file.metadata()?.len(),
+    };
+    let every = every.max(1);
+    let mut next_report = every;
+    let mut lines = ChunkedLines::new(file);
+    while let Some(line) = lines.next() {
+        f(&line?);
+        progress.lines += 1;
+        progress.bytes = lines.bytes_read();
+        if progress.bytes >= next_report {
+            on_progress(&progress);
+            next_report = (progress.bytes / every).saturating_add(1).saturating_mul(every);
+        }
+    }
+    on_progress(&progress);
+    Ok(progress)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::TempFile;
+
+    /// A reader that returns at most `limit` bytes per call, like a pipe.
+    struct Trickle<'a> {
+        data: &'a [u8],
+        limit: usize,
+    }
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = buf.len().min(self.limit).min(self.data.len());
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn test_chunks_are_full_despite_short_reads() {
+        let data: Vec<u8> = (0..=255).collect();
+        let reader = Trickle { data: &data, limit: 7 };
+        let chunks: Vec<Vec<u8>> = Chunks::new(reader, 100).collect::<Result<_, _>>().unwrap();
+        assert_eq!(chunks.iter().map(Vec::len).collect::<Vec<_>>(), vec![100, 100, 56]);
+        assert_eq!(chunks.concat(), data);
+
+        assert_eq!(Chunks::new(io::empty(), 4).count(), 0);
+        assert_eq!(Chunks::new(&b"abcd"[..], 4).count(), 1);
+    }
+
+    #[test]
+    fn test_lines_across_buffer_boundaries() {
+        let text = "short\nthis line is longer than the buffer\r\n\nlast without newline";
+        let reader = Trickle { data: text.as_bytes(), limit: 3 };
+        let mut lines = ChunkedLines::with_capacity(4, reader);
+        let mut seen = Vec::new();
+        for line in lines.by_ref() {
+            seen.push(line.unwrap());
+        }
+        assert_eq!(seen, vec!["short", "this line is longer than the buffer", "", "last without newline"]);
+        assert_eq!(lines.bytes_read(), text.len() as u64);
+    }
+
+    #[test]
+    fn test_process_lines_reports_progress() {
+        let file = TempFile::new("chunked").unwrap();
+        let line = "0123456789abcdefghi\n";
+        std::fs::write(&file, line.repeat(100)).unwrap();
+
+        let mut total_len = 0;
+        let mut reports = Vec::new();
+        let done = process_lines_with_progress(
+            &file,
+            500,
+            |l| total_len += l.len(),
+            |p| reports.push((p.bytes, p.fraction())),
+        )
+        .unwrap();
+
+        assert_eq!(total_len, 1900);
+        assert_eq!(done, Progress { lines: 100, bytes: 2000, total_bytes: 2000 });
+        let bytes: Vec<u64> = reports.iter().map(|r| r.0).collect();
+        assert_eq!(bytes, vec![500, 1000, 1500, 2000, 2000]);
+        assert_eq!(reports.last().unwrap().1, 1.0);
+    }
+
+    #[test]
+    fn test_process_lines_missing_file() {
+        let dir = crate::io::TempDir::new("chunked").unwrap();
+        let err = process_lines(dir.join("missing.log"), |_| {}).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+}
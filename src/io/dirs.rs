@@ -0,0 +1,76 @@
+//! Where per-user files live on each platform.
+//!
+//! | Kind     | Linux and other Unix                        | macOS                            | Windows     |
+//! |----------|---------------------------------------------|----------------------------------|-------------|
+//! | Config   | `$XDG_CONFIG_HOME` or `~/.config`           | `~/Library/Application Support`  | `%APPDATA%` |
+//! | Data     | `$XDG_DATA_HOME` or `~/.local/share`        | `~/Library/Application Support`  | `%APPDATA%` |
+//!
+//! Each is followed by a `rustler` directory, so the crate's files stay
+//! together. An unset or empty variable counts as missing.
+
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+/// Which of the user's directories to look up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserDir {
+    /// Settings and records of what the user has done.
+    Config,
+    /// Data the user made, such as a to-do list.
+    Data,
+}
+
+/// The `rustler` directory inside the user's config or data directory, or
+/// `None` if the environment doesn't say where that is.
+///
+/// ```
+/// use rustler::io::dirs::{user_dir, UserDir};
+///
+/// if let Some(dir) = user_dir(UserDir::Data) {
+///     assert!(dir.ends_with("rustler"));
+/// }
+/// ```
+pub fn user_dir(kind: UserDir) -> Option<PathBuf> {
+    user_dir_with(kind, |name| std::env::var_os(name))
+}
+
+/// [`user_dir`] with `var` standing in for the environment.
+fn user_dir_with(kind: UserDir, var: impl Fn(&str) -> Option<OsString>) -> Option<PathBuf> {
+    let env_dir = |name: &str| var(name).filter(|v| !v.is_empty()).map(PathBuf::from);
+    let base = if cfg!(windows) {
+        env_dir("APPDATA")
+    } else if cfg!(target_os = "macos") {
+        env_dir("HOME").map(|home| home.join("Library/Application Support"))
+    } else {
+        let (xdg, fallback) = match kind {
+            UserDir::Config => ("XDG_CONFIG_HOME", ".config"),
+            UserDir::Data => ("XDG_DATA_HOME", ".local/share"),
+        };
+        env_dir(xdg).or_else(|| env_dir("HOME").map(|home| home.join(fallback)))
+    };
+    base.map(|dir| dir.join("rustler"))
+}
+
+#[cfg(all(test, unix, not(target_os = "macos")))]
+mod tests {
+    use super::*;
+
+    fn env<'a>(vars: &'a [(&str, &str)]) -> impl Fn(&str) -> Option<OsString> + 'a {
+        move |name| vars.iter().find(|(k, _)| *k == name).map(|(_, v)| OsString::from(v))
+    }
+
+    #[test]
+    fn test_xdg_variables_win_over_home() {
+        let vars = [("HOME", "/home/ada"), ("XDG_DATA_HOME", "/data")];
+        assert_eq!(user_dir_with(UserDir::Data, env(&vars)), Some(PathBuf::from("/data/rustler")));
+        assert_eq!(user_dir_with(UserDir::Config, env(&vars)), Some(PathBuf::from("/home/ada/.config/rustler")));
+    }
+
+    #[test]
+    fn test_empty_variables_are_missing() {
+        let vars = [("HOME", "/home/ada"), ("XDG_CONFIG_HOME", "")];
+        assert_eq!(user_dir_with(UserDir::Config, env(&vars)), Some(PathBuf::from("/home/ada/.config/rustler")));
+        assert_eq!(user_dir_with(UserDir::Data, env(&vars)), Some(PathBuf::from("/home/ada/.local/share/rustler")));
+        assert_eq!(user_dir_with(UserDir::Data, env(&[("HOME", "")])), None);
+    }
+}
@@ -0,0 +1,383 @@
+//! An append-only journal of timestamped entries that rotates its file.
+//!
+//! Entries go to the journal file, one per line. Once the file would grow
+//! past the size limit it is renamed to `<name>.1`, the older `<name>.1`
+//! becomes `<name>.2`, and so on; files past the retention limit are
+//! deleted. Reading walks the files from oldest to newest, so entries come
+//! back in the order they were written.
+//!
+//! ```text
+//! journal.log.3   oldest kept
+//! journal.log.2
+//! journal.log.1
+//! journal.log     being written
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Lines, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, FixedOffset, Local, SecondsFormat};
+
+/// Rotate once the journal file would pass this many bytes.
+const DEFAULT_MAX_SIZE: u64 = 1024 * 1024;
+/// Keep this many rotated files besides the current one.
+const DEFAULT_MAX_FILES: usize = 5;
+
+/// One journal line: when it was written and what was said.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub timestamp: DateTime<FixedOffset>,
+    pub message: String,
+}
+
+impl Entry {
+    /// The line for this entry, without a newline. Backslashes and line
+    /// breaks in the message are escaped so every entry is one line.
+    fn to_line(&self) -> String {
+        let mut message = String::with_capacity(self.message.len());
+        for c in self.message.chars() {
+            match c {
+                '\\' => message.push_str("\\\\"),
+                '\n' => message.push_str("\\n"),
+                '\r' => message.push_str("\\r"),
+                _ => message.push(c),
+            }
+        }
+        format!("{} {}", self.timestamp.to_rfc3339_opts(SecondsFormat::Millis, false), message)
+    }
+
+    fn from_line(line: &str) -> Option<Entry> {
+        let (timestamp, escaped) = line.split_once(' ')?;
+        let timestamp = DateTime::parse_from_rfc3339(timestamp).ok()?;
+        let mut message = String::with_capacity(escaped.len());
+        let mut chars = escaped.chars();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                message.push(c);
+                continue;
+            }
+            match chars.next()? {
+                'n' => message.push('\n'),
+                'r' => message.push('\r'),
+                '\\' => message.push('\\'),
+                _ => return None,
+            }
+        }
+        Some(Entry { timestamp, message })
+    }
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.timestamp.format("%Y-%m-%d %H:%M:%S%.3f"), self.message)
+    }
+}
+
+/// An append-only log that rotates by size.
+///
+/// ```
+/// use rustler::io::{Journal, TempDir};
+///
+/// let dir = TempDir::new("journal").unwrap();
+/// let mut journal = Journal::open(dir.join("app.journal")).unwrap().with_max_size(100).with_max_files(2);
+/// for i in 1..=10 {
+///     journal.append(&format!("event {}", i)).unwrap();
+/// }
+///
+/// // The oldest files were pruned; what is left reads back in order
+/// assert_eq!(journal.files().len(), 3);
+/// let messages: Vec<String> = journal.entries().unwrap().map(|e| e.unwrap().message).collect();
+/// assert_eq!(messages, ["event 5", "event 6", "event 7", "event 8", "event 9", "event 10"]);
+/// ```
+#[derive(Debug)]
+pub struct Journal {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    max_size: u64,
+    max_files: usize,
+}
+
+impl Journal {
+    /// Opens the journal at `path` for appending, creating it if needed.
+    /// Existing entries, including rotated files, are kept.
+    pub fn open(path: impl AsRef<Path>) -> Result<Journal, JournalError> {
+        let path = path.as_ref().to_path_buf();
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Journal {
+            path,
+            file,
+            size,
+            max_size: DEFAULT_MAX_SIZE,
+            max_files: DEFAULT_MAX_FILES,
+        })
+    }
+
+    /// Rotates before the current file would grow past `bytes`. An entry
+    /// longer than that still gets written, alone in its file. The default
+    /// is 1 MiB.
+    pub fn with_max_size(mut self, bytes: u64) -> Self {
+        self.max_size = bytes;
+        self
+    }
+
+    /// Keeps at most `count` rotated files; older ones are deleted. The
+    /// default is 5. With 0, rotating simply starts the journal afresh.
+    pub fn with_max_files(mut self, count: usize) -> Self {
+        self.max_files = count;
+        self
+    }
+
+    /// Appends `message` with the current local time.
+    pub fn append(&mut self, message: &str) -> Result<(), JournalError> {
+        self.append_entry(&Entry {
+            timestamp: Local::now().fixed_offset(),
+            message: message.to_string(),
+        })
+    }
+
+    /// Appends an entry with its own timestamp, for example when copying
+    /// entries from elsewhere.
+    pub fn append_entry(&mut self, entry: &Entry) -> Result<(), JournalError> {
+        let mut line = entry.to_line();
+        line.push('\n');
+        let len = line.len() as u64;
+        if self.size > 0 && self.size + len > self.max_size {
+            self.rotate()?;
+        }
+        self.file.write_all(line.as_bytes())?;
+        self.size += len;
+        Ok(())
+    }
+
+    /// Starts a new journal file now, whatever the current size.
+    pub fn rotate(&mut self) -> Result<(), JournalError> {
+        self.file.flush()?;
+        // Anything at or past the limit is pruned, including leftovers from
+        // a time when more files were kept
+        let first_stale = self.max_files.max(1);
+        let mut end = first_stale;
+        while self.rotated_path(end).exists() {
+            end += 1;
+        }
+        for stale in (first_stale..end).rev() {
+            fs::remove_file(self.rotated_path(stale))?;
+        }
+
+        for index in (1..self.max_files).rev() {
+            let from = self.rotated_path(index);
+            if from.exists() {
+                fs::rename(&from, self.rotated_path(index + 1))?;
+            }
+        }
+        if self.max_files > 0 {
+            fs::rename(&self.path, self.rotated_path(1))?;
+        }
+        self.file = OpenOptions::new().create(true).write(true).truncate(true).open(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+
+    /// The journal's files that exist, oldest first, ending with the
+    /// current one.
+    pub fn files(&self) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = (1..)
+            .map(|index| self.rotated_path(index))
+            .take_while(|path| path.exists())
+            .collect();
+        files.reverse();
+        files.push(self.path.clone());
+        files
+    }
+
+    /// Every entry in every file, oldest first.
+    pub fn entries(&mut self) -> Result<Entries, JournalError> {
+        self.file.flush()?;
+        let mut files = self.files();
+        files.reverse();
+        Ok(Entries {
+            files,
+            current: None,
+        })
+    }
+
+    /// Forces written entries onto the disk.
+    pub fn sync(&self) -> Result<(), JournalError> {
+        self.file.sync_data()?;
+        Ok(())
+    }
+
+    /// The path of the rotated file with this number.
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        let mut name = self.path.clone().into_os_string();
+        name.push(format!(".{}", index));
+        PathBuf::from(name)
+    }
+}
+
+/// Iterator returned by [`Journal::entries`].
+#[derive(Debug)]
+pub struct Entries {
+    /// Files still to read, newest first so the next one can be popped.
+    files: Vec<PathBuf>,
+    current: Option<(PathBuf, Lines<BufReader<File>>, usize)>,
+}
+
+impl Iterator for Entries {
+    type Item = Result<Entry, JournalError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((path, lines, line_number)) = &mut self.current {
+                match lines.next() {
+                    Some(Ok(line)) => {
+                        *line_number += 1;
+                        return Some(Entry::from_line(&line).ok_or_else(|| JournalError::Malformed {
+                            path: path.clone(),
+                            line: *line_number,
+                        }));
+                    }
+                    Some(Err(e)) => return Some(Err(e.into())),
+                    None => self.current = None,
+                }
+            }
+            let path = self.files.pop()?;
+            match File::open(&path) {
+                Ok(file) => self.current = Some((path, BufReader::new(file).lines(), 0)),
+                // Rotated away since the list was made
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Some(Err(e.into())),
+            }
+        }
+    }
+}
+
+/// Errors from writing or reading a [`Journal`].
+#[derive(Debug)]
+pub enum JournalError {
+    Io(io::Error),
+    /// A line is not a timestamp followed by a message.
+    Malformed { path: PathBuf, line: usize },
+}
+
+impl fmt::Display for JournalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            JournalError::Io(e) => write!(f, "I/O error: {}", e),
+            JournalError::Malformed { path, line } => {
+                write!(f, "{}:{}: not a journal entry", path.display(), line)
+            }
+        }
+    }
+}
+
+impl Error for JournalError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            JournalError::Io(e) => Some(e),
+            JournalError::Malformed { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for JournalError {
+    fn from(e: io::Error) -> Self {
+        JournalError::Io(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::TempDir;
+
+    fn messages(journal: &mut Journal) -> Vec<String> {
+        journal.entries().unwrap().map(|entry| entry.unwrap().message).collect()
+    }
+
+    fn file_names(journal: &Journal) -> Vec<String> {
+        journal
+            .files()
+            .iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_entries_survive_reopening() {
+        let dir = TempDir::new("journal").unwrap();
+        let path = dir.join("j");
+        Journal::open(&path).unwrap().append("first").unwrap();
+        let mut journal = Journal::open(&path).unwrap();
+        journal.append("second\nwith a line break and a \\").unwrap();
+        assert_eq!(messages(&mut journal), vec!["first", "second\nwith a line break and a \\"]);
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 2);
+    }
+
+    #[test]
+    fn test_rotates_at_the_size_limit() {
+        let dir = TempDir::new("journal").unwrap();
+        let path = dir.join("journal");
+        // Each line is a 29-byte timestamp, a space, 5 bytes and a newline
+        let mut journal = Journal::open(&path).unwrap().with_max_size(80).with_max_files(10);
+        for i in 0..7 {
+            journal.append(&format!("msg {}", i)).unwrap();
+        }
+        assert_eq!(file_names(&journal), vec!["journal.3", "journal.2", "journal.1", "journal"]);
+        for file in journal.files() {
+            assert!(fs::metadata(file).unwrap().len() <= 80);
+        }
+        let expected: Vec<String> = (0..7).map(|i| format!("msg {}", i)).collect();
+        assert_eq!(messages(&mut journal), expected);
+    }
+
+    #[test]
+    fn test_prunes_old_files() {
+        let dir = TempDir::new("journal").unwrap();
+        let path = dir.join("journal");
+        let mut journal = Journal::open(&path).unwrap().with_max_size(1).with_max_files(2);
+        for i in 0..6 {
+            journal.append(&format!("msg {}", i)).unwrap();
+        }
+        assert_eq!(file_names(&journal), vec!["journal.2", "journal.1", "journal"]);
+        assert_eq!(messages(&mut journal), vec!["msg 3", "msg 4", "msg 5"]);
+
+        // Lowering the limit prunes the extra files on the next rotation
+        let mut journal = Journal::open(&path).unwrap().with_max_size(1).with_max_files(0);
+        journal.append("alone").unwrap();
+        assert_eq!(file_names(&journal), vec!["journal"]);
+        assert_eq!(messages(&mut journal), vec!["alone"]);
+    }
+
+    #[test]
+    fn test_timestamps_round_trip() {
+        let dir = TempDir::new("journal").unwrap();
+        let mut journal = Journal::open(dir.join("j")).unwrap();
+        let entry = Entry {
+            timestamp: DateTime::parse_from_rfc3339("2026-05-04T03:02:01.123+02:00").unwrap(),
+            message: "imported".to_string(),
+        };
+        journal.append_entry(&entry).unwrap();
+        let read: Vec<Entry> = journal.entries().unwrap().map(Result::unwrap).collect();
+        assert_eq!(read, vec![entry]);
+        assert_eq!(read[0].to_string(), "2026-05-04 03:02:01.123 imported");
+    }
+
+    #[test]
+    fn test_malformed_lines_are_reported() {
+        let dir = TempDir::new("journal").unwrap();
+        let path = dir.join("j");
+        fs::write(&path, "2026-05-04T03:02:01.123+00:00 fine\nnot a journal line\n").unwrap();
+        let mut journal = Journal::open(&path).unwrap();
+        let results: Vec<_> = journal.entries().unwrap().collect();
+        assert!(results[0].is_ok());
+        match &results[1] {
+            Err(JournalError::Malformed { line, .. }) => assert_eq!(*line, 2),
+            other => panic!("expected a malformed line, got {:?}", other),
+        }
+    }
+}
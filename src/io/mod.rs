@@ -0,0 +1,32 @@
+//! File formats and helpers built on `std::io` and `std::fs`.
+
+pub mod archive;
+pub mod chunked;
+pub mod dirs;
+pub mod journal;
+pub mod temp;
+pub mod walk;
+
+/// CSV lives with the other text formats in [`crate::text::csv`]; it is
+/// here too, next to the rest of the file formats.
+///
+/// ```
+/// use rustler::io::csv::Reader;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Row {
+///     name: String,
+///     age: u32,
+/// }
+///
+/// let mut reader = Reader::new("name,age\nAda,36\n".as_bytes());
+/// let rows: Vec<Row> = reader.deserialize().collect::<Result<_, _>>().unwrap();
+/// assert_eq!((rows[0].name.as_str(), rows[0].age), ("Ada", 36));
+/// ```
+pub use crate::text::csv;
+
+pub use chunked::{process_lines, process_lines_with_progress, ChunkedLines, Chunks, Progress};
+pub use journal::{Entry, Journal, JournalError};
+pub use temp::{TempDir, TempFile};
+pub use walk::{Walk, WalkEntry};
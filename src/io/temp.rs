@@ -0,0 +1,208 @@
+//! Temporary files and directories that delete themselves.
+//!
+//! Paths such as `/tmp/example.txt` do not exist on Windows, and two
+//! programs running at once would overwrite each other's file. These types
+//! create a fresh, uniquely named entry under [`std::env::temp_dir`] and
+//! remove it when they are dropped, even if the code using them panics.
+
+use std::fs::{self, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Counts names handed out by this process, so two temp paths created in
+/// the same instant still differ.
+static NEXT: AtomicU64 = AtomicU64::new(0);
+
+/// Gives up after this many name collisions in a row.
+const ATTEMPTS: u32 = 100;
+
+/// `rustler-<prefix>-<pid>-<nanos>-<n><suffix>` in the system temp dir.
+fn unique_path(prefix: &str, suffix: &str) -> PathBuf {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.subsec_nanos());
+    let n = NEXT.fetch_add(1, Ordering::Relaxed);
+    let name = format!("rustler-{}-{}-{}-{}{}", prefix, std::process::id(), nanos, n, suffix);
+    std::env::temp_dir().join(name)
+}
+
+/// Calls `create` on fresh paths until one does not exist yet.
+fn create_unique(prefix: &str, suffix: &str, create: impl Fn(&Path) -> io::Result<()>) -> io::Result<PathBuf> {
+    for _ in 0..ATTEMPTS {
+        let path = unique_path(prefix, suffix);
+        match create(&path) {
+            Ok(()) => return Ok(path),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Err(io::Error::new(io::ErrorKind::AlreadyExists, "could not find an unused temporary name"))
+}
+
+/// A new empty directory, removed with everything in it when dropped.
+///
+/// ```
+/// use rustler::io::TempDir;
+///
+/// let dir = TempDir::new("docs").unwrap();
+/// std::fs::write(dir.join("notes.txt"), "hello").unwrap();
+/// let path = dir.path().to_path_buf();
+/// assert!(path.join("notes.txt").exists());
+///
+/// drop(dir);
+/// assert!(!path.exists());
+/// ```
+#[derive(Debug)]
+pub struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    /// Creates the directory. `prefix` goes into its name, to tell at a
+    /// glance which program left it behind if deletion ever fails.
+    pub fn new(prefix: &str) -> io::Result<TempDir> {
+        let path = create_unique(prefix, "", |path| fs::create_dir(path))?;
+        Ok(TempDir { path })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// A path inside the directory.
+    pub fn join(&self, name: impl AsRef<Path>) -> PathBuf {
+        self.path.join(name)
+    }
+
+    /// Gives up ownership: the directory is left on disk and its path
+    /// returned.
+    pub fn keep(self) -> PathBuf {
+        let path = self.path.clone();
+        std::mem::forget(self);
+        path
+    }
+}
+
+impl AsRef<Path> for TempDir {
+    fn as_ref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        // Nothing useful can be done with an error while dropping
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+/// A new empty file, removed when dropped.
+///
+/// ```
+/// use rustler::io::TempFile;
+///
+/// let file = TempFile::with_suffix("docs", ".json").unwrap();
+/// std::fs::write(&file, "{}").unwrap();
+/// assert_eq!(file.path().extension().unwrap(), "json");
+/// ```
+#[derive(Debug)]
+pub struct TempFile {
+    path: PathBuf,
+}
+
+impl TempFile {
+    pub fn new(prefix: &str) -> io::Result<TempFile> {
+        TempFile::with_suffix(prefix, "")
+    }
+
+    /// A file whose name ends with `suffix`, for code that looks at the
+    /// extension.
+    pub fn with_suffix(prefix: &str, suffix: &str) -> io::Result<TempFile> {
+        let path = create_unique(prefix, suffix, |path| {
+            OpenOptions::new().write(true).create_new(true).open(path).map(drop)
+        })?;
+        Ok(TempFile { path })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Gives up ownership: the file is left on disk and its path returned.
+    pub fn keep(self) -> PathBuf {
+        let path = self.path.clone();
+        std::mem::forget(self);
+        path
+    }
+}
+
+impl AsRef<Path> for TempFile {
+    fn as_ref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dir_is_removed_with_its_contents() {
+        let dir = TempDir::new("test").unwrap();
+        fs::create_dir(dir.join("sub")).unwrap();
+        fs::write(dir.join("sub").join("file.txt"), "data").unwrap();
+        let path = dir.path().to_path_buf();
+        assert!(path.starts_with(std::env::temp_dir()));
+        drop(dir);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_names_are_unique() {
+        let dirs: Vec<TempDir> = (0..20).map(|_| TempDir::new("unique").unwrap()).collect();
+        let files: Vec<TempFile> = (0..20).map(|_| TempFile::new("unique").unwrap()).collect();
+        let mut paths: Vec<&Path> = dirs.iter().map(TempDir::path).chain(files.iter().map(TempFile::path)).collect();
+        paths.sort();
+        paths.dedup();
+        assert_eq!(paths.len(), 40);
+    }
+
+    #[test]
+    fn test_file_is_created_empty_and_removed() {
+        let file = TempFile::with_suffix("test", ".txt").unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "");
+        assert!(file.path().to_string_lossy().ends_with(".txt"));
+        let path = file.path().to_path_buf();
+        drop(file);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_keep_leaves_the_entry() {
+        let path = TempFile::new("keep").unwrap().keep();
+        assert!(path.exists());
+        fs::remove_file(&path).unwrap();
+
+        let path = TempDir::new("keep").unwrap().keep();
+        assert!(path.is_dir());
+        fs::remove_dir(&path).unwrap();
+    }
+
+    #[test]
+    fn test_removal_happens_during_a_panic() {
+        let path = std::panic::catch_unwind(|| {
+            let dir = TempDir::new("panic").unwrap();
+            std::panic::panic_any(dir.path().to_path_buf());
+        })
+        .unwrap_err()
+        .downcast::<PathBuf>()
+        .unwrap();
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,236 @@
+//! Recursive directory traversal.
+//!
+//! [`Walk`] visits everything under a directory depth first, with the
+//! entries of each directory in name order, so the same tree is always
+//! walked the same way. It keeps a stack of entries still to visit rather
+//! than recursing, and a directory that cannot be read is reported as an
+//! error item without ending the walk.
+//!
+//! ```
+//! use rustler::io::{TempDir, Walk};
+//!
+//! let dir = TempDir::new("walk-doc").unwrap();
+//! std::fs::create_dir(dir.join("src")).unwrap();
+//! std::fs::write(dir.join("src/main.rs"), "fn main() {}").unwrap();
+//! std::fs::write(dir.join("README.md"), "# Hi").unwrap();
+//!
+//! let files: Vec<String> = Walk::new(dir.path())
+//!     .files_only()
+//!     .map(|entry| entry.unwrap().relative_path().display().to_string())
+//!     .collect();
+//! assert_eq!(files, vec!["README.md", "src/main.rs"]);
+//! ```
+
+use std::fs::{self, FileType};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A file or directory found by [`Walk`].
+#[derive(Debug, Clone)]
+pub struct WalkEntry {
+    path: PathBuf,
+    root_len: usize,
+    depth: usize,
+    file_type: FileType,
+}
+
+impl WalkEntry {
+    /// The full path: the walk's root joined with the entry's name.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The path below the walk's root.
+    pub fn relative_path(&self) -> &Path {
+        let mut components = self.path.components();
+        for _ in 0..self.root_len {
+            components.next();
+        }
+        components.as_path()
+    }
+
+    /// 1 for the root's children, 2 for theirs, and so on.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn is_dir(&self) -> bool {
+        self.file_type.is_dir()
+    }
+
+    pub fn is_file(&self) -> bool {
+        self.file_type.is_file()
+    }
+
+    /// Symbolic links are reported but never followed, so a link back up
+    /// the tree cannot make the walk loop forever.
+    pub fn is_symlink(&self) -> bool {
+        self.file_type.is_symlink()
+    }
+}
+
+/// A depth-first walk of a directory tree. The root itself is not yielded.
+#[derive(Debug)]
+pub struct Walk {
+    root: PathBuf,
+    /// Entries still to visit, the next one on top.
+    stack: Vec<io::Result<WalkEntry>>,
+    started: bool,
+    max_depth: Option<usize>,
+    skip_hidden: bool,
+    files_only: bool,
+}
+
+impl Walk {
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        Walk {
+            root: root.as_ref().to_path_buf(),
+            stack: Vec::new(),
+            started: false,
+            max_depth: None,
+            skip_hidden: false,
+            files_only: false,
+        }
+    }
+
+    /// Goes no deeper than `depth`; 1 lists just the root's children.
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Skips names starting with `.`, and everything inside such
+    /// directories, like `.git`.
+    pub fn skip_hidden(mut self) -> Self {
+        self.skip_hidden = true;
+        self
+    }
+
+    /// Yields only regular files. Directories are still descended into.
+    pub fn files_only(mut self) -> Self {
+        self.files_only = true;
+        self
+    }
+
+    /// Pushes the children of `dir` so that the first by name is on top.
+    fn push_children(&mut self, dir: &Path, depth: usize) {
+        if self.max_depth.is_some_and(|max| depth > max) {
+            return;
+        }
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                self.stack.push(Err(with_path(e, dir)));
+                return;
+            }
+        };
+        let root_len = self.root.components().count();
+        let mut children = Vec::new();
+        for entry in entries {
+            let child = entry.and_then(|entry| {
+                Ok(WalkEntry {
+                    path: entry.path(),
+                    root_len,
+                    depth,
+                    file_type: entry.file_type()?,
+                })
+            });
+            match child {
+                Ok(child) if self.skip_hidden && child.path.file_name().is_some_and(|n| n.to_string_lossy().starts_with('.')) => {}
+                Ok(child) => children.push(Ok(child)),
+                Err(e) => children.push(Err(with_path(e, dir))),
+            }
+        }
+        // Errors sort first; they have no name to sort by
+        children.sort_by(|a, b| match (a, b) {
+            (Ok(a), Ok(b)) => a.path.file_name().cmp(&b.path.file_name()),
+            (Err(_), Ok(_)) => std::cmp::Ordering::Less,
+            (Ok(_), Err(_)) => std::cmp::Ordering::Greater,
+            (Err(_), Err(_)) => std::cmp::Ordering::Equal,
+        });
+        self.stack.extend(children.into_iter().rev());
+    }
+}
+
+impl Iterator for Walk {
+    type Item = io::Result<WalkEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.started {
+            self.started = true;
+            let root = self.root.clone();
+            self.push_children(&root, 1);
+        }
+        loop {
+            let entry = match self.stack.pop()? {
+                Ok(entry) => entry,
+                Err(e) => return Some(Err(e)),
+            };
+            if entry.is_dir() {
+                self.push_children(&entry.path.clone(), entry.depth + 1);
+                if self.files_only {
+                    continue;
+                }
+            } else if self.files_only && !entry.is_file() {
+                continue;
+            }
+            return Some(Ok(entry));
+        }
+    }
+}
+
+/// Adds the path to an error, which `read_dir` leaves out.
+fn with_path(e: io::Error, path: &Path) -> io::Error {
+    io::Error::new(e.kind(), format!("{}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::TempDir;
+
+    fn tree() -> TempDir {
+        let dir = TempDir::new("walk").unwrap();
+        for path in ["b/inner/deep.txt", "a.txt", "b/z.txt", ".hidden/secret.txt", "c/.env"] {
+            let path = dir.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "x").unwrap();
+        }
+        dir
+    }
+
+    fn names(walk: Walk) -> Vec<String> {
+        walk.map(|entry| entry.unwrap().relative_path().to_string_lossy().replace('\\', "/")).collect()
+    }
+
+    #[test]
+    fn test_depth_first_in_name_order() {
+        let dir = tree();
+        assert_eq!(
+            names(Walk::new(dir.path())),
+            vec![".hidden", ".hidden/secret.txt", "a.txt", "b", "b/inner", "b/inner/deep.txt", "b/z.txt", "c", "c/.env"]
+        );
+        let depths: Vec<usize> = Walk::new(dir.path()).map(|e| e.unwrap().depth()).collect();
+        assert_eq!(depths, vec![1, 2, 1, 1, 2, 3, 2, 1, 2]);
+    }
+
+    #[test]
+    fn test_options() {
+        let dir = tree();
+        assert_eq!(
+            names(Walk::new(dir.path()).skip_hidden().files_only()),
+            vec!["a.txt", "b/inner/deep.txt", "b/z.txt"]
+        );
+        assert_eq!(names(Walk::new(dir.path()).skip_hidden().max_depth(1)), vec!["a.txt", "b", "c"]);
+    }
+
+    #[test]
+    fn test_missing_root_is_an_error_item() {
+        let dir = TempDir::new("walk").unwrap();
+        let mut walk = Walk::new(dir.join("missing"));
+        let error = walk.next().unwrap().unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+        assert!(error.to_string().contains("missing"));
+        assert!(walk.next().is_none());
+    }
+}
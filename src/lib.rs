@@ -0,0 +1,29 @@
+//! Reusable building blocks for the rustler examples.
+//!
+//! The examples in `examples/` teach one concept at a time; the code that
+//! outgrows a single example lives here so it can be shared and tested.
+
+pub mod algorithms;
+pub mod apps;
+pub mod bench;
+pub mod catalog;
+pub mod cli;
+pub mod collections;
+pub mod concurrent;
+pub mod config;
+pub mod exercises;
+pub mod flashcards;
+pub mod games;
+pub mod hash_lite;
+pub mod io;
+pub mod log_lite;
+pub mod net;
+pub mod progress;
+pub mod quiz;
+pub mod scaffold;
+pub mod shapes;
+pub mod store;
+pub mod term;
+pub mod text;
+pub mod tools;
+pub mod walkthrough;
@@ -0,0 +1,359 @@
+//! A small levelled logger, in the spirit of the `log` and `env_logger`
+//! crates but in one file.
+//!
+//! Every message has a [`Level`] and a target, a short name for the part
+//! of the program it came from. A [`Logger`] drops messages below its
+//! level, formats the rest with a timestamp, and hands the line to each of
+//! its [`Sink`]s.
+//!
+//! The level can be set per target from the `RUSTLER_LOG` environment
+//! variable, with the same syntax as `RUST_LOG`:
+//!
+//! ```text
+//! RUSTLER_LOG=warn                    # warnings and errors only
+//! RUSTLER_LOG=info,supervisor=debug   # info, plus debug for one target
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+
+/// The environment variable read by [`Logger::from_env`].
+pub const ENV_VAR: &str = "RUSTLER_LOG";
+
+/// How important a message is, from least to most.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Level {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Level::Trace => "TRACE",
+            Level::Debug => "DEBUG",
+            Level::Info => "INFO",
+            Level::Warn => "WARN",
+            Level::Error => "ERROR",
+        };
+        // Pads when a width is given, so columns line up
+        f.pad(name)
+    }
+}
+
+impl FromStr for Level {
+    type Err = ParseLevelError;
+
+    /// Parses a level name in any case.
+    fn from_str(s: &str) -> Result<Level, ParseLevelError> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "trace" => Ok(Level::Trace),
+            "debug" => Ok(Level::Debug),
+            "info" => Ok(Level::Info),
+            "warn" | "warning" => Ok(Level::Warn),
+            "error" => Ok(Level::Error),
+            _ => Err(ParseLevelError(s.trim().to_string())),
+        }
+    }
+}
+
+/// A level name that is not one of trace, debug, info, warn or error.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseLevelError(pub String);
+
+impl fmt::Display for ParseLevelError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown log level `{}`", self.0)
+    }
+}
+
+impl Error for ParseLevelError {}
+
+/// Somewhere formatted log lines go.
+pub trait Sink: Send + Sync {
+    /// Writes one line, without its trailing newline.
+    fn write_line(&self, line: &str) -> io::Result<()>;
+}
+
+/// Writes lines to standard error.
+#[derive(Debug, Default)]
+pub struct StderrSink;
+
+impl Sink for StderrSink {
+    fn write_line(&self, line: &str) -> io::Result<()> {
+        writeln!(io::stderr().lock(), "{}", line)
+    }
+}
+
+/// Appends lines to a file.
+#[derive(Debug)]
+pub struct FileSink {
+    file: Mutex<File>,
+}
+
+impl FileSink {
+    /// Opens `path` for appending, creating it if needed.
+    pub fn append(path: impl AsRef<Path>) -> io::Result<FileSink> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(FileSink { file: Mutex::new(file) })
+    }
+}
+
+impl Sink for FileSink {
+    fn write_line(&self, line: &str) -> io::Result<()> {
+        writeln!(self.file.lock().unwrap_or_else(PoisonError::into_inner), "{}", line)
+    }
+}
+
+/// Keeps lines in memory, for tests and for showing logs after the fact.
+/// Clones share the same lines.
+#[derive(Debug, Clone, Default)]
+pub struct MemorySink {
+    lines: Arc<Mutex<Vec<String>>>,
+}
+
+impl MemorySink {
+    pub fn new() -> Self {
+        MemorySink::default()
+    }
+
+    /// The lines written so far.
+    pub fn lines(&self) -> Vec<String> {
+        self.lines.lock().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+}
+
+impl Sink for MemorySink {
+    fn write_line(&self, line: &str) -> io::Result<()> {
+        self.lines.lock().unwrap_or_else(PoisonError::into_inner).push(line.to_string());
+        Ok(())
+    }
+}
+
+/// Filters messages by level and target and sends them to sinks.
+///
+/// ```
+/// use rustler::log_lite::{Level, Logger, MemorySink};
+///
+/// let sink = MemorySink::new();
+/// let logger = Logger::new(Level::Info)
+///     .with_target_level("db", Level::Debug)
+///     .with_timestamps(false)
+///     .with_sink(sink.clone());
+///
+/// logger.debug("http", "dropped: below info");
+/// logger.debug("db", "kept: db logs debug");
+/// logger.warn("http", format!("slow response ({}ms)", 900));
+///
+/// assert_eq!(sink.lines(), vec![
+///     "DEBUG db: kept: db logs debug",
+///     "WARN  http: slow response (900ms)",
+/// ]);
+/// ```
+pub struct Logger {
+    level: Level,
+    /// Overrides of `level` for particular targets.
+    targets: Vec<(String, Level)>,
+    sinks: Vec<Box<dyn Sink>>,
+    timestamps: bool,
+}
+
+impl Logger {
+    /// A logger passing messages at `level` and above. It has no sinks
+    /// until [`with_sink`](Logger::with_sink) adds one.
+    pub fn new(level: Level) -> Self {
+        Logger {
+            level,
+            targets: Vec::new(),
+            sinks: Vec::new(),
+            timestamps: true,
+        }
+    }
+
+    /// A logger writing to stderr, filtered by the `RUSTLER_LOG`
+    /// environment variable. Without it, or if it cannot be parsed, the
+    /// level is `Info`.
+    pub fn from_env() -> Self {
+        let filter = std::env::var(ENV_VAR).unwrap_or_default();
+        Logger::new(Level::Info)
+            .with_filter(&filter)
+            .unwrap_or_else(|_| Logger::new(Level::Info))
+            .with_sink(StderrSink)
+    }
+
+    /// Applies a `RUST_LOG`-style filter: comma-separated entries that are
+    /// either a level for everything or `target=level`.
+    pub fn with_filter(mut self, filter: &str) -> Result<Self, ParseLevelError> {
+        for entry in filter.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+            match entry.split_once('=') {
+                Some((target, level)) => self.targets.push((target.trim().to_string(), level.parse()?)),
+                None => self.level = entry.parse()?,
+            }
+        }
+        Ok(self)
+    }
+
+    /// Uses `level` for messages whose target is `target` or starts with
+    /// `target::`.
+    pub fn with_target_level(mut self, target: &str, level: Level) -> Self {
+        self.targets.push((target.to_string(), level));
+        self
+    }
+
+    pub fn with_sink(mut self, sink: impl Sink + 'static) -> Self {
+        self.sinks.push(Box::new(sink));
+        self
+    }
+
+    /// Whether lines start with the local date and time. On by default.
+    pub fn with_timestamps(mut self, timestamps: bool) -> Self {
+        self.timestamps = timestamps;
+        self
+    }
+
+    /// Whether a message at `level` from `target` would be written.
+    pub fn enabled(&self, level: Level, target: &str) -> bool {
+        // The longest matching target wins, so `net::http` beats `net`
+        let threshold = self
+            .targets
+            .iter()
+            .filter(|(prefix, _)| {
+                target == prefix || target.strip_prefix(prefix.as_str()).is_some_and(|rest| rest.starts_with("::"))
+            })
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map_or(self.level, |&(_, level)| level);
+        level >= threshold
+    }
+
+    /// Formats and writes `message` if its level passes the filter. A sink
+    /// that fails to write is skipped; logging never fails the caller.
+    pub fn log(&self, level: Level, target: &str, message: impl fmt::Display) {
+        if !self.enabled(level, target) {
+            return;
+        }
+        let line = if self.timestamps {
+            let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f");
+            format!("{} {:<5} {}: {}", now, level, target, message)
+        } else {
+            format!("{:<5} {}: {}", level, target, message)
+        };
+        for sink in &self.sinks {
+            let _ = sink.write_line(&line);
+        }
+    }
+
+    pub fn trace(&self, target: &str, message: impl fmt::Display) {
+        self.log(Level::Trace, target, message);
+    }
+
+    pub fn debug(&self, target: &str, message: impl fmt::Display) {
+        self.log(Level::Debug, target, message);
+    }
+
+    pub fn info(&self, target: &str, message: impl fmt::Display) {
+        self.log(Level::Info, target, message);
+    }
+
+    pub fn warn(&self, target: &str, message: impl fmt::Display) {
+        self.log(Level::Warn, target, message);
+    }
+
+    pub fn error(&self, target: &str, message: impl fmt::Display) {
+        self.log(Level::Error, target, message);
+    }
+}
+
+impl fmt::Debug for Logger {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Logger")
+            .field("level", &self.level)
+            .field("targets", &self.targets)
+            .field("sinks", &self.sinks.len())
+            .finish()
+    }
+}
+
+static GLOBAL: OnceLock<Logger> = OnceLock::new();
+
+/// The logger used by library code such as the
+/// [`Supervisor`](crate::concurrent::Supervisor). Unless [`set_global`]
+/// was called first, this is [`Logger::from_env`].
+pub fn global() -> &'static Logger {
+    GLOBAL.get_or_init(Logger::from_env)
+}
+
+/// Installs `logger` as the global logger. This only works before the
+/// global logger is first used; otherwise `logger` is handed back.
+pub fn set_global(logger: Logger) -> Result<(), Logger> {
+    GLOBAL.set(logger)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn capture(logger: Logger) -> (Logger, MemorySink) {
+        let sink = MemorySink::new();
+        (logger.with_timestamps(false).with_sink(sink.clone()), sink)
+    }
+
+    #[test]
+    fn test_levels_below_threshold_are_dropped() {
+        let (logger, sink) = capture(Logger::new(Level::Warn));
+        logger.trace("app", "t");
+        logger.debug("app", "d");
+        logger.info("app", "i");
+        logger.warn("app", "w");
+        logger.error("app", "e");
+        assert_eq!(sink.lines(), vec!["WARN  app: w", "ERROR app: e"]);
+    }
+
+    #[test]
+    fn test_filter_string_sets_target_levels() {
+        let (logger, sink) = capture(Logger::new(Level::Trace).with_filter("error, net=info, net::http=debug").unwrap());
+        logger.info("app", "hidden");
+        logger.info("net", "shown");
+        logger.debug("net::tcp", "hidden");
+        logger.debug("net::http", "shown");
+        logger.debug("network", "hidden: not a net:: target");
+        assert_eq!(sink.lines(), vec!["INFO  net: shown", "DEBUG net::http: shown"]);
+    }
+
+    #[test]
+    fn test_bad_filter_is_an_error() {
+        let err = Logger::new(Level::Info).with_filter("app=loud").unwrap_err();
+        assert_eq!(err.to_string(), "unknown log level `loud`");
+        assert_eq!("Warning".parse(), Ok(Level::Warn));
+    }
+
+    #[test]
+    fn test_timestamps_prefix_each_line() {
+        let sink = MemorySink::new();
+        let logger = Logger::new(Level::Info).with_sink(sink.clone());
+        logger.info("app", "started");
+        let line = &sink.lines()[0];
+        // "2026-01-31 12:34:56.789 INFO  app: started"
+        assert_eq!(line.len(), "2026-01-31 12:34:56.789 ".len() + "INFO  app: started".len());
+        assert!(line.ends_with(" INFO  app: started"), "{}", line);
+        assert_eq!(&line[4..5], "-");
+    }
+
+    #[test]
+    fn test_file_sink_appends() {
+        let file = crate::io::TempFile::new("log").unwrap();
+        for run in ["first", "second"] {
+            let logger = Logger::new(Level::Info).with_timestamps(false).with_sink(FileSink::append(&file).unwrap());
+            logger.info("run", run);
+        }
+        let contents = std::fs::read_to_string(&file).unwrap();
+        assert_eq!(contents, "INFO  run: first\nINFO  run: second\n");
+    }
+}
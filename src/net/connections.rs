@@ -0,0 +1,202 @@
+//! The accept loop shared by servers whose clients stay connected.
+//!
+//! An HTTP connection here answers one request and closes, but a chat,
+//! RPC or key-value client may stay connected and say nothing for as long
+//! as it likes, holding its worker all the while. [`listen`] keeps track
+//! of the connections it is answering so that [`ServerHandle::shutdown`]
+//! can close them, rather than waiting for every client to hang up.
+//!
+//! ```
+//! use std::io::{BufRead, BufReader, Write};
+//! use std::net::TcpStream;
+//! use rustler::net::connections;
+//!
+//! // An echo server: every line comes straight back
+//! let handle = connections::listen("127.0.0.1:0", 2, "echo", |stream| {
+//!     let mut reply = &stream;
+//!     for line in BufReader::new(&stream).lines().map_while(Result::ok) {
+//!         let _ = writeln!(reply, "{}", line);
+//!     }
+//! })
+//! .unwrap();
+//!
+//! let stream = TcpStream::connect(handle.local_addr()).unwrap();
+//! writeln!(&stream, "hello").unwrap();
+//! let mut line = String::new();
+//! BufReader::new(&stream).read_line(&mut line).unwrap();
+//! assert_eq!(line, "hello\n");
+//! // Doesn't wait for the client above to hang up
+//! handle.shutdown();
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread::{self, JoinHandle};
+
+use crate::concurrent::{CancellationToken, ThreadPool};
+
+/// Binds `addr` and hands each connection to `handler` on one of `workers`
+/// threads, until the returned handle is shut down. A connection holds its
+/// worker until `handler` returns; clients beyond `workers` wait their
+/// turn. `name` names the accept thread, for debuggers and panics.
+///
+/// # Panics
+///
+/// Panics if `workers` is zero.
+pub fn listen<F>(addr: impl ToSocketAddrs, workers: usize, name: &str, handler: F) -> io::Result<ServerHandle>
+where
+    F: Fn(TcpStream) + Send + Sync + 'static,
+{
+    let listener = TcpListener::bind(addr)?;
+    let addr = listener.local_addr()?;
+    let token = CancellationToken::new();
+    let open = Arc::new(OpenConnections::default());
+    let pool = ThreadPool::with_token(workers, token.clone());
+    let handler = Arc::new(handler);
+    let (stop, connections) = (token.clone(), Arc::clone(&open));
+    let thread = thread::Builder::new().name(name.to_string()).spawn(move || {
+        for stream in listener.incoming() {
+            if stop.is_cancelled() {
+                break;
+            }
+            // A failed accept is about that one connection
+            let Ok(stream) = stream else { continue };
+            let Some(id) = connections.add(&stream) else { break };
+            let (handler, connections) = (Arc::clone(&handler), Arc::clone(&connections));
+            pool.execute(move || {
+                handler(stream);
+                connections.remove(id);
+            });
+        }
+    })?;
+    Ok(ServerHandle {
+        addr,
+        token,
+        open,
+        thread,
+    })
+}
+
+/// The connections being answered, by id.
+#[derive(Default)]
+struct OpenConnections {
+    state: Mutex<Connections>,
+}
+
+#[derive(Default)]
+struct Connections {
+    streams: HashMap<usize, TcpStream>,
+    next_id: usize,
+    closed: bool,
+}
+
+impl OpenConnections {
+    /// Remembers `stream`, or returns `None` if the server is shutting
+    /// down and the connection shouldn't be answered.
+    fn add(&self, stream: &TcpStream) -> Option<usize> {
+        let mut state = self.lock();
+        if state.closed {
+            return None;
+        }
+        state.next_id += 1;
+        let id = state.next_id;
+        state.streams.insert(id, stream.try_clone().ok()?);
+        Some(id)
+    }
+
+    fn remove(&self, id: usize) {
+        self.lock().streams.remove(&id);
+    }
+
+    /// Shuts every connection down, and refuses any more.
+    fn close_all(&self) {
+        let mut state = self.lock();
+        state.closed = true;
+        for (_, stream) in state.streams.drain() {
+            let _ = stream.shutdown(Shutdown::Both);
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Connections> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// A running server, from [`listen`].
+pub struct ServerHandle {
+    addr: SocketAddr,
+    token: CancellationToken,
+    open: Arc<OpenConnections>,
+    thread: JoinHandle<()>,
+}
+
+impl fmt::Debug for ServerHandle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ServerHandle").field("addr", &self.addr).finish_non_exhaustive()
+    }
+}
+
+impl ServerHandle {
+    /// The address the server is listening on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Blocks until the server stops, which for a server nothing shuts
+    /// down is never.
+    pub fn wait(self) {
+        let _ = self.thread.join();
+    }
+
+    /// Stops accepting connections, closes the open ones and waits for
+    /// the server's threads to finish.
+    pub fn shutdown(self) {
+        self.token.cancel();
+        self.open.close_all();
+        // accept() blocks until someone connects, so connect to wake it
+        let _ = TcpStream::connect(self.addr);
+        self.wait();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_shutdown_closes_idle_connections() {
+        let finished = Arc::new(AtomicUsize::new(0));
+        let count = Arc::clone(&finished);
+        let handle = listen("127.0.0.1:0", 2, "test-accept", move |mut stream| {
+            // Blocks until the client or the shutdown closes the connection
+            let _ = stream.read_to_end(&mut Vec::new());
+            count.fetch_add(1, Ordering::SeqCst);
+        })
+        .unwrap();
+        let quiet: Vec<TcpStream> = (0..2).map(|_| TcpStream::connect(handle.local_addr()).unwrap()).collect();
+        // Wait until both are being answered, or shutdown would discard
+        // them from the queue instead
+        while handle.open.lock().streams.len() < 2 {
+            thread::yield_now();
+        }
+        handle.shutdown();
+        assert_eq!(finished.load(Ordering::SeqCst), 2);
+        drop(quiet);
+    }
+
+    #[test]
+    fn test_connections_after_shutdown_are_refused() {
+        let open = OpenConnections::default();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let stream = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let id = open.add(&stream).unwrap();
+        open.remove(id);
+        open.close_all();
+        assert_eq!(open.add(&stream), None);
+    }
+}
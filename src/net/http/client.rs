@@ -0,0 +1,287 @@
+//! Fetching `http://` URLs over a plain `TcpStream`.
+//!
+//! [`get`] parses the URL, resolves the host (DNS names and IP addresses
+//! alike, through `ToSocketAddrs`), connects to the first address that
+//! answers, writes a GET request and reads the [`Response`]. There is no
+//! TLS, so `https://` URLs are refused rather than sent in the clear, and
+//! redirects are returned as they are for the caller to follow.
+//! [`get_json`] also checks the status and deserializes the body.
+//!
+//! ```no_run
+//! let response = rustler::net::http::get("http://example.com/").unwrap();
+//! println!("{} with {} bytes", response.status(), response.body().len());
+//! ```
+
+use std::fmt;
+use std::io::{BufReader, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+
+use super::{HttpError, Method, Response};
+
+/// A parsed `http://` URL.
+///
+/// ```
+/// use rustler::net::http::Url;
+///
+/// let url = Url::parse("http://localhost:8080/search?q=rust#top").unwrap();
+/// assert_eq!((url.host.as_str(), url.port), ("localhost", 8080));
+/// assert_eq!(url.target, "/search?q=rust");
+/// assert!(Url::parse("https://example.com/").is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Url {
+    /// A name or an IP address; IPv6 addresses keep their brackets.
+    pub host: String,
+    pub port: u16,
+    /// The path and query, as sent in the request line. Always starts
+    /// with `/`.
+    pub target: String,
+}
+
+impl Url {
+    pub fn parse(url: &str) -> Result<Url, HttpError> {
+        let invalid = |why: &str| HttpError::InvalidUrl(format!("{} in `{}`", why, url));
+        let (scheme, rest) = url.split_once("://").ok_or_else(|| invalid("no scheme"))?;
+        if !scheme.eq_ignore_ascii_case("http") {
+            return Err(invalid("only http:// is supported"));
+        }
+        // The fragment is for the browser and never sent
+        let rest = rest.split('#').next().unwrap_or("");
+        let split = rest.find(['/', '?']).unwrap_or(rest.len());
+        let (authority, target) = rest.split_at(split);
+        if authority.contains('@') {
+            return Err(invalid("user names and passwords are not supported"));
+        }
+        // A colon after the last `]` separates the port from an IPv6 host
+        let port_colon = authority.rfind(':').filter(|&i| authority.rfind(']').is_none_or(|j| i > j));
+        let (host, port) = match port_colon {
+            Some(i) => {
+                let port = authority[i + 1..].parse().map_err(|_| invalid("bad port"))?;
+                (&authority[..i], port)
+            }
+            None => (authority, 80),
+        };
+        if host.is_empty() {
+            return Err(invalid("no host"));
+        }
+        let target = if target.starts_with('/') {
+            target.to_string()
+        } else {
+            format!("/{}", target)
+        };
+        Ok(Url {
+            host: host.to_string(),
+            port,
+            target,
+        })
+    }
+
+    /// The value for the `Host` header: the host, and the port unless it
+    /// is the default.
+    pub fn authority(&self) -> String {
+        if self.port == 80 {
+            self.host.clone()
+        } else {
+            format!("{}:{}", self.host, self.port)
+        }
+    }
+}
+
+impl fmt::Display for Url {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "http://{}{}", self.authority(), self.target)
+    }
+}
+
+/// Settings for requests; [`get`] uses the defaults.
+#[derive(Debug, Clone)]
+pub struct Client {
+    timeout: Duration,
+    user_agent: String,
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Client::new()
+    }
+}
+
+impl Client {
+    /// A client with a 30-second timeout.
+    pub fn new() -> Self {
+        Client {
+            timeout: Duration::from_secs(30),
+            user_agent: format!("rustler/{}", env!("CARGO_PKG_VERSION")),
+        }
+    }
+
+    /// How long connecting, and each read or write after that, may take.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = user_agent.to_string();
+        self
+    }
+
+    /// Fetches `url` and returns the response, whatever its status: a 404
+    /// is a successful exchange, so check [`Response::status`].
+    pub fn get(&self, url: &str) -> Result<Response, HttpError> {
+        self.send(Method::Get, &Url::parse(url)?)
+    }
+
+    /// Fetches `url` and deserializes its JSON body. A status other than a
+    /// success is an [`HttpError::Status`], and a body that doesn't match
+    /// `T` an [`HttpError::Json`].
+    pub fn get_json<T: DeserializeOwned>(&self, url: &str) -> Result<T, HttpError> {
+        let response = self.get(url)?;
+        if !response.status().is_success() {
+            return Err(HttpError::Status(response.status()));
+        }
+        Ok(serde_json::from_slice(response.body())?)
+    }
+
+    fn send(&self, method: Method, url: &Url) -> Result<Response, HttpError> {
+        let stream = self.connect(url)?;
+        stream.set_read_timeout(Some(self.timeout))?;
+        stream.set_write_timeout(Some(self.timeout))?;
+        let request = format!(
+            "{} {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: {}\r\nAccept: */*\r\nConnection: close\r\n\r\n",
+            method,
+            url.target,
+            url.authority(),
+            self.user_agent
+        );
+        (&stream).write_all(request.as_bytes())?;
+        Response::read_from(BufReader::new(&stream), method == Method::Head)
+    }
+
+    /// Connects to the first of the host's addresses that answers, so a
+    /// name with both an IPv6 and an IPv4 address works when only one of
+    /// them is reachable.
+    fn connect(&self, url: &Url) -> Result<TcpStream, HttpError> {
+        // ToSocketAddrs wants IPv6 hosts without their brackets
+        let host = url.host.trim_start_matches('[').trim_end_matches(']');
+        let mut last_error = None;
+        for addr in (host, url.port).to_socket_addrs()? {
+            match TcpStream::connect_timeout(&addr, self.timeout) {
+                Ok(stream) => return Ok(stream),
+                Err(e) => last_error = Some(e),
+            }
+        }
+        Err(HttpError::Io(last_error.unwrap_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::NotFound, format!("{} has no addresses", url.host))
+        })))
+    }
+}
+
+/// Fetches `url` with a default [`Client`].
+pub fn get(url: &str) -> Result<Response, HttpError> {
+    Client::new().get(url)
+}
+
+/// Fetches `url` with a default [`Client`] and deserializes its JSON body.
+pub fn get_json<T: DeserializeOwned>(url: &str) -> Result<T, HttpError> {
+    Client::new().get_json(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::http::{Server, Status};
+    use std::io::Read;
+    use std::net::TcpListener;
+    use std::thread;
+
+    #[test]
+    fn test_parse_urls() {
+        let url = Url::parse("http://example.com").unwrap();
+        assert_eq!(url, Url { host: "example.com".to_string(), port: 80, target: "/".to_string() });
+        assert_eq!(url.to_string(), "http://example.com/");
+
+        let url = Url::parse("HTTP://[::1]:8080?x=1").unwrap();
+        assert_eq!((url.host.as_str(), url.port, url.target.as_str()), ("[::1]", 8080, "/?x=1"));
+        assert_eq!(url.authority(), "[::1]:8080");
+        assert_eq!(Url::parse("http://[::1]/a").unwrap().port, 80);
+
+        for bad in ["example.com", "ftp://x/", "http://", "http://:80/", "http://x:port/", "http://a@b/", "http://x:70000/"] {
+            assert!(matches!(Url::parse(bad), Err(HttpError::InvalidUrl(_))), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_get_from_our_server() {
+        let mut server = Server::new();
+        server.get("/greet", |request| {
+            let agent = request.header("User-Agent").unwrap_or("").to_string();
+            Response::text(Status::OK, format!("hi {} from {}", request.query("to").unwrap_or("?"), agent))
+        });
+        let handle = server.listen("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", handle.local_addr());
+
+        let response = get(&format!("{}/greet?to=you", base)).unwrap();
+        assert_eq!(response.status(), Status::OK);
+        assert_eq!(response.body_text().unwrap(), format!("hi you from rustler/{}", env!("CARGO_PKG_VERSION")));
+
+        let response = Client::new().user_agent("test").get(&format!("{}/missing", base)).unwrap();
+        assert_eq!(response.status(), Status::NOT_FOUND);
+        handle.shutdown();
+    }
+
+    #[test]
+    fn test_get_chunked_from_a_raw_server() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 1024];
+            let n = stream.read(&mut request).unwrap();
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n4\r\nWiki\r\n5\r\npedia\r\n0\r\n\r\n")
+                .unwrap();
+            String::from_utf8_lossy(&request[..n]).into_owned()
+        });
+        let response = get(&format!("http://{}/wiki", addr)).unwrap();
+        assert_eq!(response.body_text().unwrap(), "Wikipedia");
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("GET /wiki HTTP/1.1\r\n"), "{}", request);
+        assert!(request.contains(&format!("\r\nHost: {}\r\n", addr)));
+        assert!(request.ends_with("Connection: close\r\n\r\n"));
+    }
+
+    #[test]
+    fn test_get_json() {
+        #[derive(Debug, PartialEq, serde::Deserialize)]
+        struct Crab {
+            name: String,
+            legs: Option<u8>,
+        }
+        let mut server = Server::new();
+        server.get("/ferris", |_| Response::json(r#"{"name": "Ferris", "claws": 2}"#));
+        server.get("/broken", |_| Response::json(r#"{"name": 7}"#));
+        let handle = server.listen("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", handle.local_addr());
+
+        let crab: Crab = get_json(&format!("{}/ferris", base)).unwrap();
+        assert_eq!(crab, Crab { name: "Ferris".to_string(), legs: None });
+        let result = get_json::<Crab>(&format!("{}/broken", base));
+        assert!(matches!(result, Err(HttpError::Json(_))));
+        let result = get_json::<Crab>(&format!("{}/missing", base));
+        assert!(matches!(result, Err(HttpError::Status(Status::NOT_FOUND))));
+        handle.shutdown();
+    }
+
+    #[test]
+    fn test_connection_refused() {
+        // Bind and drop to find a port that nothing is listening on
+        let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let result = Client::new().timeout(Duration::from_secs(2)).get(&format!("http://127.0.0.1:{}/", port));
+        assert!(matches!(result, Err(HttpError::Io(_))));
+    }
+}
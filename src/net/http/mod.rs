@@ -0,0 +1,726 @@
+//! HTTP/1.1 messages, a [`Server`] that routes requests to closures, and a
+//! client that [`get`]s URLs.
+//!
+//! Only as much of HTTP as the examples need is here, on std's sockets
+//! alone: one request per connection (both sides send
+//! `Connection: close`), request bodies sized by `Content-Length`,
+//! response bodies sized by `Content-Length`, chunked or ended by closing
+//! the connection, and no TLS, so only `http://` URLs.
+//!
+//! ```
+//! use rustler::net::http::{Request, Response, Server, Status};
+//!
+//! let mut server = Server::new();
+//! server.get("/hello", |request| {
+//!     let name = request.query("name").unwrap_or("world");
+//!     Response::text(Status::OK, format!("Hello, {}!", name))
+//! });
+//!
+//! let raw = "GET /hello?name=Ferris%20the%20crab HTTP/1.1\r\nHost: localhost\r\n\r\n";
+//! let request = Request::read_from(raw.as_bytes()).unwrap();
+//! let response = server.handle(&request);
+//! assert_eq!(response.status(), Status::OK);
+//! assert_eq!(response.body(), b"Hello, Ferris the crab!");
+//! ```
+
+pub mod client;
+pub mod server;
+
+pub use client::{get, get_json, Client, Url};
+pub use server::{Server, ServerHandle};
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead, Read, Write};
+
+/// The longest request line or header line accepted, in bytes.
+const MAX_LINE: u64 = 8 * 1024;
+
+/// The most headers accepted in one request.
+const MAX_HEADERS: usize = 100;
+
+/// The largest request body accepted, in bytes.
+pub const MAX_BODY: usize = 1024 * 1024;
+
+/// A request method. Methods this enum doesn't name are kept as `Other`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Method {
+    Get,
+    Head,
+    Post,
+    Put,
+    Delete,
+    Patch,
+    Options,
+    Other(String),
+}
+
+impl Method {
+    /// The method named `name`. Method names are case-sensitive, so `get`
+    /// is `Other("get")`.
+    pub fn parse(name: &str) -> Method {
+        match name {
+            "GET" => Method::Get,
+            "HEAD" => Method::Head,
+            "POST" => Method::Post,
+            "PUT" => Method::Put,
+            "DELETE" => Method::Delete,
+            "PATCH" => Method::Patch,
+            "OPTIONS" => Method::Options,
+            other => Method::Other(other.to_string()),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Method::Get => "GET",
+            Method::Head => "HEAD",
+            Method::Post => "POST",
+            Method::Put => "PUT",
+            Method::Delete => "DELETE",
+            Method::Patch => "PATCH",
+            Method::Options => "OPTIONS",
+            Method::Other(name) => name,
+        }
+    }
+}
+
+impl fmt::Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// A response status code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Status(pub u16);
+
+impl Status {
+    pub const OK: Status = Status(200);
+    pub const CREATED: Status = Status(201);
+    pub const NO_CONTENT: Status = Status(204);
+    pub const MOVED_PERMANENTLY: Status = Status(301);
+    pub const FOUND: Status = Status(302);
+    pub const NOT_MODIFIED: Status = Status(304);
+    pub const BAD_REQUEST: Status = Status(400);
+    pub const FORBIDDEN: Status = Status(403);
+    pub const NOT_FOUND: Status = Status(404);
+    pub const METHOD_NOT_ALLOWED: Status = Status(405);
+    pub const PAYLOAD_TOO_LARGE: Status = Status(413);
+    pub const INTERNAL_SERVER_ERROR: Status = Status(500);
+    pub const NOT_IMPLEMENTED: Status = Status(501);
+    pub const SERVICE_UNAVAILABLE: Status = Status(503);
+
+    pub fn code(self) -> u16 {
+        self.0
+    }
+
+    /// The standard reason phrase, or "" for codes this type doesn't name.
+    pub fn reason(self) -> &'static str {
+        match self.0 {
+            200 => "OK",
+            201 => "Created",
+            204 => "No Content",
+            301 => "Moved Permanently",
+            302 => "Found",
+            304 => "Not Modified",
+            400 => "Bad Request",
+            403 => "Forbidden",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            413 => "Payload Too Large",
+            500 => "Internal Server Error",
+            501 => "Not Implemented",
+            503 => "Service Unavailable",
+            _ => "",
+        }
+    }
+
+    /// Whether the code is in the 2xx range.
+    pub fn is_success(self) -> bool {
+        (200..300).contains(&self.0)
+    }
+}
+
+impl fmt::Display for Status {
+    /// The code and reason, as in a status line: `404 Not Found`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.0, self.reason())
+    }
+}
+
+/// Header fields in the order they arrived. Names are compared ignoring
+/// case, as HTTP requires.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Headers {
+    fields: Vec<(String, String)>,
+}
+
+impl Headers {
+    pub fn new() -> Self {
+        Headers::default()
+    }
+
+    /// The first value of the header called `name`.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(field, _)| field.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Sets `name` to `value`, replacing any values it had.
+    pub fn insert(&mut self, name: &str, value: impl Into<String>) {
+        self.remove(name);
+        self.append(name, value);
+    }
+
+    /// Adds a value for `name`, keeping any it already had.
+    pub fn append(&mut self, name: &str, value: impl Into<String>) {
+        self.fields.push((name.to_string(), value.into()));
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.fields.retain(|(field, _)| !field.eq_ignore_ascii_case(name));
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.fields.iter().map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+}
+
+/// A request, as read by the server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    pub method: Method,
+    /// The path with percent escapes decoded, such as `/files/a b.txt`.
+    pub path: String,
+    /// The query parameters, decoded. A name given more than once keeps
+    /// its last value.
+    pub query: BTreeMap<String, String>,
+    pub headers: Headers,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    /// A request for `target`, a path with an optional `?query`, with no
+    /// headers or body.
+    pub fn new(method: Method, target: &str) -> Self {
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        Request {
+            method,
+            path: percent_decode(path, false),
+            query: parse_query(query),
+            headers: Headers::new(),
+            body: Vec::new(),
+        }
+    }
+
+    /// Reads one request: the request line, the headers up to the blank
+    /// line, and a body of `Content-Length` bytes if there is one.
+    pub fn read_from<R: BufRead>(mut reader: R) -> Result<Request, HttpError> {
+        let line = read_line(&mut reader)?.ok_or_else(|| HttpError::Malformed("empty request".to_string()))?;
+        let mut parts = line.split_whitespace();
+        let (Some(method), Some(target), Some(version), None) = (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(HttpError::Malformed(format!("bad request line `{}`", line)));
+        };
+        if !version.starts_with("HTTP/1.") {
+            return Err(HttpError::Malformed(format!("unsupported version `{}`", version)));
+        }
+        let mut request = Request::new(Method::parse(method), target);
+        request.headers = read_headers(&mut reader)?;
+
+        if request.headers.get("Transfer-Encoding").is_some() {
+            return Err(HttpError::Malformed("request bodies must have a Content-Length".to_string()));
+        }
+        let length = content_length(&request.headers)?.unwrap_or(0);
+        if length > MAX_BODY {
+            return Err(HttpError::TooLarge);
+        }
+        request.body = vec![0; length];
+        reader.read_exact(&mut request.body)?;
+        Ok(request)
+    }
+
+    /// The query parameter called `name`.
+    pub fn query(&self, name: &str) -> Option<&str> {
+        self.query.get(name).map(String::as_str)
+    }
+
+    /// The first value of the header called `name`, ignoring case.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name)
+    }
+}
+
+/// A response, built by a handler and written by the server.
+///
+/// ```
+/// use rustler::net::http::{Response, Status};
+///
+/// let response = Response::text(Status::NOT_FOUND, "no such page");
+/// let mut raw = Vec::new();
+/// response.write_to(&mut raw).unwrap();
+/// let raw = String::from_utf8(raw).unwrap();
+/// assert!(raw.starts_with("HTTP/1.1 404 Not Found\r\n"));
+/// assert!(raw.contains("Content-Length: 12\r\n"));
+/// assert!(raw.ends_with("\r\n\r\nno such page"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    status: Status,
+    headers: Headers,
+    body: Vec<u8>,
+}
+
+impl Response {
+    /// An empty response with this status.
+    pub fn new(status: Status) -> Self {
+        Response {
+            status,
+            headers: Headers::new(),
+            body: Vec::new(),
+        }
+    }
+
+    /// A plain-text response.
+    pub fn text(status: Status, body: impl Into<String>) -> Self {
+        Response::new(status)
+            .with_header("Content-Type", "text/plain; charset=utf-8")
+            .with_body(body.into())
+    }
+
+    /// A 200 response with an HTML page.
+    pub fn html(body: impl Into<String>) -> Self {
+        Response::new(Status::OK)
+            .with_header("Content-Type", "text/html; charset=utf-8")
+            .with_body(body.into())
+    }
+
+    /// A 200 response with a JSON document.
+    pub fn json(body: impl Into<String>) -> Self {
+        Response::new(Status::OK)
+            .with_header("Content-Type", "application/json")
+            .with_body(body.into())
+    }
+
+    /// Sets a header, replacing any value it had.
+    pub fn with_header(mut self, name: &str, value: impl Into<String>) -> Self {
+        self.headers.insert(name, value);
+        self
+    }
+
+    pub fn with_body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    pub fn status(&self) -> Status {
+        self.status
+    }
+
+    pub fn headers(&self) -> &Headers {
+        &self.headers
+    }
+
+    pub fn body(&self) -> &[u8] {
+        &self.body
+    }
+
+    /// The body as text, if it is UTF-8.
+    pub fn body_text(&self) -> Result<&str, std::str::Utf8Error> {
+        std::str::from_utf8(&self.body)
+    }
+
+    /// Reads a response: the status line, the headers, and the body. The
+    /// body is chunked if `Transfer-Encoding` says so, else as long as
+    /// `Content-Length`, else everything up to the end of the stream.
+    /// `head` says the request was a `HEAD`, whose response has no body
+    /// whatever its headers say.
+    pub fn read_from<R: BufRead>(mut reader: R, head: bool) -> Result<Response, HttpError> {
+        let line = read_line(&mut reader)?.ok_or_else(|| HttpError::Malformed("empty response".to_string()))?;
+        let mut parts = line.splitn(3, ' ');
+        let (Some(version), Some(code)) = (parts.next(), parts.next()) else {
+            return Err(HttpError::Malformed(format!("bad status line `{}`", line)));
+        };
+        let code = code
+            .parse()
+            .ok()
+            .filter(|code| (100..600).contains(code) && version.starts_with("HTTP/1."))
+            .ok_or_else(|| HttpError::Malformed(format!("bad status line `{}`", line)))?;
+        let mut response = Response::new(Status(code));
+        response.headers = read_headers(&mut reader)?;
+
+        // 1xx, 204 and 304 responses never have a body
+        if head || code < 200 || code == 204 || code == 304 {
+            return Ok(response);
+        }
+        let chunked = response
+            .headers
+            .get("Transfer-Encoding")
+            .is_some_and(|coding| coding.eq_ignore_ascii_case("chunked"));
+        if chunked {
+            response.body = read_chunked(&mut reader)?;
+        } else if let Some(length) = content_length(&response.headers)? {
+            // take() rather than a buffer of `length`, so a server that
+            // promises a huge body can't make us allocate it up front
+            reader.by_ref().take(length as u64).read_to_end(&mut response.body)?;
+            if response.body.len() < length {
+                return Err(HttpError::Io(io::ErrorKind::UnexpectedEof.into()));
+            }
+        } else {
+            reader.read_to_end(&mut response.body)?;
+        }
+        Ok(response)
+    }
+
+    /// Writes the status line, the headers with `Content-Length` and
+    /// `Connection: close` added, and the body.
+    pub fn write_to<W: Write>(&self, mut out: W) -> io::Result<()> {
+        let mut head = format!("HTTP/1.1 {}\r\n", self.status);
+        let mut headers = self.headers.clone();
+        headers.insert("Content-Length", self.body.len().to_string());
+        headers.insert("Connection", "close");
+        for (name, value) in headers.iter() {
+            head.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        head.push_str("\r\n");
+        // One write for the head, so it doesn't go out a header at a time
+        out.write_all(head.as_bytes())?;
+        out.write_all(&self.body)?;
+        out.flush()
+    }
+}
+
+/// Errors from reading HTTP messages.
+#[derive(Debug)]
+pub enum HttpError {
+    Io(io::Error),
+    /// The message broke the protocol, or used a part of it not supported
+    /// here.
+    Malformed(String),
+    /// The body is larger than [`MAX_BODY`].
+    TooLarge,
+    /// The URL isn't an `http://` URL this client can fetch.
+    InvalidUrl(String),
+    /// The server answered with this status rather than a success.
+    Status(Status),
+    /// The body wasn't the JSON the caller expected.
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for HttpError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HttpError::Io(e) => write!(f, "I/O error: {}", e),
+            HttpError::Malformed(message) => write!(f, "malformed HTTP message: {}", message),
+            HttpError::TooLarge => write!(f, "the body is larger than {} bytes", MAX_BODY),
+            HttpError::InvalidUrl(message) => write!(f, "invalid URL: {}", message),
+            HttpError::Status(status) => write!(f, "the server answered {}", status),
+            HttpError::Json(e) => write!(f, "unexpected JSON: {}", e),
+        }
+    }
+}
+
+impl Error for HttpError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            HttpError::Io(e) => Some(e),
+            HttpError::Json(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for HttpError {
+    fn from(e: io::Error) -> Self {
+        HttpError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for HttpError {
+    fn from(e: serde_json::Error) -> Self {
+        HttpError::Json(e)
+    }
+}
+
+/// Reads one CRLF- or LF-terminated line without its ending, or `None` at
+/// end of input. Lines longer than [`MAX_LINE`] are an error rather than
+/// an unbounded allocation.
+fn read_line<R: BufRead>(reader: &mut R) -> Result<Option<String>, HttpError> {
+    let mut line = Vec::new();
+    reader.by_ref().take(MAX_LINE).read_until(b'\n', &mut line)?;
+    if line.is_empty() {
+        return Ok(None);
+    }
+    if line.pop() != Some(b'\n') {
+        return Err(HttpError::Malformed("line too long or cut short".to_string()));
+    }
+    if line.last() == Some(&b'\r') {
+        line.pop();
+    }
+    String::from_utf8(line)
+        .map(Some)
+        .map_err(|_| HttpError::Malformed("line is not UTF-8".to_string()))
+}
+
+/// Reads `Name: value` lines up to the blank line that ends the head.
+fn read_headers<R: BufRead>(reader: &mut R) -> Result<Headers, HttpError> {
+    let mut headers = Headers::new();
+    loop {
+        let line = read_line(reader)?.ok_or_else(|| HttpError::Malformed("headers cut short".to_string()))?;
+        if line.is_empty() {
+            return Ok(headers);
+        }
+        if headers.len() == MAX_HEADERS {
+            return Err(HttpError::Malformed("too many headers".to_string()));
+        }
+        let (name, value) = line
+            .split_once(':')
+            .ok_or_else(|| HttpError::Malformed(format!("bad header `{}`", line)))?;
+        headers.append(name.trim(), value.trim());
+    }
+}
+
+/// Reads a chunked body: chunks of a hex size line and that many bytes,
+/// ending with a chunk of size 0 and optional trailer headers.
+fn read_chunked<R: BufRead>(reader: &mut R) -> Result<Vec<u8>, HttpError> {
+    let mut body = Vec::new();
+    loop {
+        let line = read_line(reader)?.ok_or_else(|| HttpError::Malformed("chunked body cut short".to_string()))?;
+        // Chunk extensions after `;` carry nothing we need
+        let size = line.split(';').next().unwrap_or("").trim();
+        let size = usize::from_str_radix(size, 16)
+            .map_err(|_| HttpError::Malformed(format!("bad chunk size `{}`", line)))?;
+        if size == 0 {
+            read_headers(reader)?;
+            return Ok(body);
+        }
+        let start = body.len();
+        reader.by_ref().take(size as u64).read_to_end(&mut body)?;
+        if body.len() - start < size {
+            return Err(HttpError::Io(io::ErrorKind::UnexpectedEof.into()));
+        }
+        // Each chunk's data is followed by its own CRLF
+        if read_line(reader)?.is_none_or(|rest| !rest.is_empty()) {
+            return Err(HttpError::Malformed("chunk longer than its size".to_string()));
+        }
+    }
+}
+
+fn content_length(headers: &Headers) -> Result<Option<usize>, HttpError> {
+    headers
+        .get("Content-Length")
+        .map(|value| {
+            value
+                .parse()
+                .map_err(|_| HttpError::Malformed(format!("bad Content-Length `{}`", value)))
+        })
+        .transpose()
+}
+
+/// Splits `a=1&b=two` into decoded pairs; a name without `=` has an empty
+/// value.
+fn parse_query(query: &str) -> BTreeMap<String, String> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(name, true), percent_decode(value, true))
+        })
+        .collect()
+}
+
+/// Decodes `%XX` escapes, and `+` as a space in query strings. Invalid
+/// escapes are kept as they are, and invalid UTF-8 becomes `�`.
+fn percent_decode(text: &str, plus_as_space: bool) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = || std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok();
+        match bytes[i] {
+            b'%' => match hex().and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                Some(byte) => {
+                    decoded.push(byte);
+                    i += 2;
+                }
+                None => decoded.push(b'%'),
+            },
+            b'+' if plus_as_space => decoded.push(b' '),
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Replaces the characters that are special in HTML with entities, so
+/// text can go into a page as text.
+///
+/// ```
+/// use rustler::net::http::escape_html;
+///
+/// assert_eq!(escape_html("<b>Tom & \"Jerry\"</b>"), "&lt;b&gt;Tom &amp; &quot;Jerry&quot;&lt;/b&gt;");
+/// ```
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(raw: &str) -> Result<Request, HttpError> {
+        Request::read_from(raw.as_bytes())
+    }
+
+    #[test]
+    fn test_read_request() {
+        let raw = "POST /notes/a%20b?tag=rust&q=x+y&flag HTTP/1.1\r\n\
+                   Host: localhost\r\n\
+                   content-length: 5\r\n\
+                   X-Empty:\r\n\
+                   \r\n\
+                   hello and more";
+        let request = parse(raw).unwrap();
+        assert_eq!(request.method, Method::Post);
+        assert_eq!(request.path, "/notes/a b");
+        assert_eq!(request.query("tag"), Some("rust"));
+        assert_eq!(request.query("q"), Some("x y"));
+        assert_eq!(request.query("flag"), Some(""));
+        assert_eq!(request.header("Content-Length"), Some("5"));
+        assert_eq!(request.header("x-empty"), Some(""));
+        assert_eq!(request.body, b"hello");
+    }
+
+    #[test]
+    fn test_bare_newlines_are_accepted() {
+        let request = parse("GET / HTTP/1.0\nHost: x\n\n").unwrap();
+        assert_eq!(request.method, Method::Get);
+        assert_eq!(request.path, "/");
+        assert!(request.query.is_empty());
+    }
+
+    #[test]
+    fn test_malformed_requests() {
+        let malformed = |raw: &str| matches!(parse(raw), Err(HttpError::Malformed(_)));
+        assert!(malformed(""));
+        assert!(malformed("GET /\r\n\r\n"));
+        assert!(malformed("GET / HTTP/2\r\n\r\n"));
+        assert!(malformed("GET / HTTP/1.1\r\nNo colon\r\n\r\n"));
+        assert!(malformed("GET / HTTP/1.1\r\nHost: x\r\n"));
+        assert!(malformed("POST / HTTP/1.1\r\nContent-Length: lots\r\n\r\n"));
+        assert!(malformed("POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n"));
+        let long = format!("GET /{} HTTP/1.1\r\n\r\n", "a".repeat(MAX_LINE as usize));
+        assert!(malformed(&long));
+
+        let huge = format!("POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n", MAX_BODY + 1);
+        assert!(matches!(parse(&huge), Err(HttpError::TooLarge)));
+        // The body is shorter than promised
+        assert!(matches!(
+            parse("POST / HTTP/1.1\r\nContent-Length: 10\r\n\r\nshort"),
+            Err(HttpError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!(percent_decode("caf%C3%A9", false), "café");
+        assert_eq!(percent_decode("a+b", false), "a+b");
+        assert_eq!(percent_decode("a+b", true), "a b");
+        assert_eq!(percent_decode("100%", false), "100%");
+        assert_eq!(percent_decode("%zz%4", false), "%zz%4");
+        assert_eq!(percent_decode("%FF", false), "\u{FFFD}");
+    }
+
+    #[test]
+    fn test_write_response() {
+        let response = Response::json(r#"{"ok":true}"#).with_header("X-Id", "7").with_header("x-id", "8");
+        let mut raw = Vec::new();
+        response.write_to(&mut raw).unwrap();
+        assert_eq!(
+            String::from_utf8(raw).unwrap(),
+            "HTTP/1.1 200 OK\r\n\
+             Content-Type: application/json\r\n\
+             x-id: 8\r\n\
+             Content-Length: 11\r\n\
+             Connection: close\r\n\
+             \r\n\
+             {\"ok\":true}"
+        );
+    }
+
+    fn response(raw: &str) -> Result<Response, HttpError> {
+        Response::read_from(raw.as_bytes(), false)
+    }
+
+    #[test]
+    fn test_read_response_by_length_and_to_end() {
+        let r = response("HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nhello, and the rest").unwrap();
+        assert_eq!((r.status(), r.body_text().unwrap()), (Status::OK, "hello"));
+        let r = response("HTTP/1.0 404 Not Found\r\nServer: x\r\n\r\nall of\nthis").unwrap();
+        assert_eq!((r.status(), r.body()), (Status::NOT_FOUND, &b"all of\nthis"[..]));
+        assert_eq!(r.headers().get("server"), Some("x"));
+        // A status without a reason phrase
+        assert_eq!(response("HTTP/1.1 299\r\n\r\n").unwrap().status(), Status(299));
+        let r = Response::read_from(&b"HTTP/1.1 200 OK\r\nContent-Length: 9\r\n\r\n"[..], true).unwrap();
+        assert!(r.body().is_empty());
+        assert!(response("HTTP/1.1 204 No Content\r\n\r\nignored").unwrap().body().is_empty());
+    }
+
+    #[test]
+    fn test_read_chunked_response() {
+        let raw = "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n\
+                   5\r\nhello\r\n7;name=x\r\n, world\r\n0\r\nTrailer: yes\r\n\r\n";
+        assert_eq!(response(raw).unwrap().body_text(), Ok("hello, world"));
+        let cut = "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nhel";
+        assert!(matches!(response(cut), Err(HttpError::Io(_))));
+        let long = "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n2\r\nhello\r\n0\r\n\r\n";
+        assert!(matches!(response(long), Err(HttpError::Malformed(_))));
+        let bad = "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\nzz\r\n";
+        assert!(matches!(response(bad), Err(HttpError::Malformed(_))));
+    }
+
+    #[test]
+    fn test_malformed_responses() {
+        for raw in ["", "HTTP/1.1\r\n\r\n", "HTTP/1.1 abc OK\r\n\r\n", "SPDY/3 200 OK\r\n\r\n", "HTTP/1.1 700 ?\r\n\r\n"] {
+            assert!(matches!(response(raw), Err(HttpError::Malformed(_))), "{:?}", raw);
+        }
+        let short = "HTTP/1.1 200 OK\r\nContent-Length: 10\r\n\r\nshort";
+        assert!(matches!(response(short), Err(HttpError::Io(_))));
+    }
+
+    #[test]
+    fn test_status_and_method() {
+        assert_eq!(Status::METHOD_NOT_ALLOWED.to_string(), "405 Method Not Allowed");
+        assert_eq!(Status(299).to_string(), "299 ");
+        assert!(Status::NO_CONTENT.is_success());
+        assert!(!Status::FOUND.is_success());
+        assert_eq!(Method::parse("DELETE"), Method::Delete);
+        assert_eq!(Method::parse("get"), Method::Other("get".to_string()));
+        assert_eq!(Method::Other("BREW".to_string()).to_string(), "BREW");
+    }
+}
@@ -0,0 +1,364 @@
+//! Routing requests to handlers, serving files, and the accept loop.
+//!
+//! A [`Server`] is a table of routes, each an exact path and a method
+//! mapped to a closure, plus directories of static files under a URL
+//! prefix. [`Server::handle`] answers one [`Request`] without any
+//! networking, which is how the routes are tested; [`Server::listen`]
+//! accepts connections on a background thread and answers each on a
+//! [`ThreadPool`] worker, so a slow client doesn't hold up the others.
+//!
+//! ```no_run
+//! use rustler::net::http::{Response, Server};
+//!
+//! let mut server = Server::new();
+//! server.get("/", |_| Response::html("<h1>Hello</h1>"));
+//! server.static_files("/files", "public");
+//! let handle = server.listen("127.0.0.1:7878").unwrap();
+//! println!("Listening on http://{}", handle.local_addr());
+//! handle.wait();
+//! ```
+
+use std::fs;
+use std::io::{self, BufReader};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use super::{escape_html, HttpError, Method, Request, Response, Status};
+use crate::concurrent::{CancellationToken, ThreadPool};
+
+/// How long a connection may take to send its request.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+type Handler = Arc<dyn Fn(&Request) -> Response + Send + Sync>;
+
+struct Route {
+    method: Method,
+    path: String,
+    handler: Handler,
+}
+
+/// Routes and static directories; see the [module documentation](self).
+pub struct Server {
+    routes: Vec<Route>,
+    static_dirs: Vec<(String, PathBuf)>,
+    workers: usize,
+}
+
+impl Default for Server {
+    fn default() -> Self {
+        Server::new()
+    }
+}
+
+impl Server {
+    /// A server with no routes, answering on four worker threads.
+    pub fn new() -> Self {
+        Server {
+            routes: Vec::new(),
+            static_dirs: Vec::new(),
+            workers: 4,
+        }
+    }
+
+    /// How many connections are answered at once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `workers` is zero.
+    pub fn workers(mut self, workers: usize) -> Self {
+        assert!(workers > 0, "a server needs at least one worker");
+        self.workers = workers;
+        self
+    }
+
+    /// Answers `method` requests for exactly `path` with `handler`. The
+    /// query string isn't part of the path, so `/search?q=x` matches
+    /// `/search`. Routes added first win.
+    pub fn route<F>(&mut self, method: Method, path: &str, handler: F) -> &mut Self
+    where
+        F: Fn(&Request) -> Response + Send + Sync + 'static,
+    {
+        self.routes.push(Route {
+            method,
+            path: path.to_string(),
+            handler: Arc::new(handler),
+        });
+        self
+    }
+
+    pub fn get<F>(&mut self, path: &str, handler: F) -> &mut Self
+    where
+        F: Fn(&Request) -> Response + Send + Sync + 'static,
+    {
+        self.route(Method::Get, path, handler)
+    }
+
+    pub fn post<F>(&mut self, path: &str, handler: F) -> &mut Self
+    where
+        F: Fn(&Request) -> Response + Send + Sync + 'static,
+    {
+        self.route(Method::Post, path, handler)
+    }
+
+    /// Serves the files in `dir` for `GET` requests under `prefix`, so
+    /// with a prefix of `/static`, `/static/css/site.css` is
+    /// `dir/css/site.css`. A directory serves its `index.html`. Paths that
+    /// would leave `dir`, such as ones with `..`, are not found.
+    pub fn static_files(&mut self, prefix: &str, dir: impl Into<PathBuf>) -> &mut Self {
+        self.static_dirs.push((prefix.trim_end_matches('/').to_string(), dir.into()));
+        self
+    }
+
+    /// The response to `request`: from the first route that matches, or a
+    /// static file, or `405 Method Not Allowed` if only the method is
+    /// wrong, or `404 Not Found`. A handler that panics gets a `500`.
+    pub fn handle(&self, request: &Request) -> Response {
+        let on_path: Vec<&Route> = self.routes.iter().filter(|route| route.path == request.path).collect();
+        if let Some(route) = on_path.iter().find(|route| route.method == request.method) {
+            let handler = &route.handler;
+            return panic::catch_unwind(AssertUnwindSafe(|| handler(request))).unwrap_or_else(|_| {
+                Response::text(Status::INTERNAL_SERVER_ERROR, "the handler for this page failed")
+            });
+        }
+        if request.method == Method::Get {
+            for (prefix, dir) in &self.static_dirs {
+                if let Some(response) = serve_file(prefix, dir, &request.path) {
+                    return response;
+                }
+            }
+        }
+        if !on_path.is_empty() {
+            let allowed: Vec<&str> = on_path.iter().map(|route| route.method.as_str()).collect();
+            return Response::text(Status::METHOD_NOT_ALLOWED, format!("{} is not allowed here", request.method))
+                .with_header("Allow", allowed.join(", "));
+        }
+        Response::new(Status::NOT_FOUND)
+            .with_header("Content-Type", "text/html; charset=utf-8")
+            .with_body(format!("<h1>Not Found</h1><p>Nothing is at {}.</p>", escape_html(&request.path)))
+    }
+
+    /// Reads one request from `stream` and writes the answer. Requests
+    /// that can't be read get a `400` or `413`; a connection that fails
+    /// midway is dropped.
+    pub fn serve_connection(&self, stream: TcpStream) {
+        // A client that connects and says nothing mustn't tie up a worker
+        let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+        let response = match Request::read_from(BufReader::new(&stream)) {
+            Ok(request) => self.handle(&request),
+            Err(HttpError::Malformed(message)) => Response::text(Status::BAD_REQUEST, message),
+            Err(HttpError::TooLarge) => Response::text(Status::PAYLOAD_TOO_LARGE, HttpError::TooLarge.to_string()),
+            // The client went away; the other errors only come from fetching
+            Err(_) => return,
+        };
+        // The client may already have gone; there is no one to tell
+        let _ = response.write_to(&stream);
+    }
+
+    /// Binds `addr` and answers connections on a background thread until
+    /// the returned handle is shut down. Port 0 picks a free port; ask the
+    /// handle which.
+    pub fn listen(self, addr: impl ToSocketAddrs) -> io::Result<ServerHandle> {
+        let listener = TcpListener::bind(addr)?;
+        let addr = listener.local_addr()?;
+        let token = CancellationToken::new();
+        let pool = ThreadPool::with_token(self.workers, token.clone());
+        let server = Arc::new(self);
+        let stop = token.clone();
+        let thread = thread::Builder::new().name("http-accept".to_string()).spawn(move || {
+            for stream in listener.incoming() {
+                if stop.is_cancelled() {
+                    break;
+                }
+                // A failed accept is about that one connection
+                let Ok(stream) = stream else { continue };
+                let server = Arc::clone(&server);
+                pool.execute(move || server.serve_connection(stream));
+            }
+        })?;
+        Ok(ServerHandle {
+            addr,
+            token,
+            thread,
+        })
+    }
+}
+
+/// A running server, from [`Server::listen`].
+#[derive(Debug)]
+pub struct ServerHandle {
+    addr: SocketAddr,
+    token: CancellationToken,
+    thread: JoinHandle<()>,
+}
+
+impl ServerHandle {
+    /// The address the server is listening on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Blocks until the server stops, which for a server nothing shuts
+    /// down is never.
+    pub fn wait(self) {
+        let _ = self.thread.join();
+    }
+
+    /// Stops accepting connections and waits for the accept loop to end.
+    /// Requests already being answered are finished first; connections
+    /// still waiting for a worker are closed unanswered.
+    pub fn shutdown(self) {
+        self.token.cancel();
+        // accept() blocks until someone connects, so connect to wake it
+        let _ = TcpStream::connect(self.addr);
+        self.wait();
+    }
+}
+
+/// The file under `dir` that `path` names below `prefix`, if there is one.
+fn serve_file(prefix: &str, dir: &Path, path: &str) -> Option<Response> {
+    let rest = path.strip_prefix(prefix)?;
+    if !(rest.is_empty() || rest.starts_with('/')) {
+        // `/static2` is not under `/static`
+        return None;
+    }
+    let relative = Path::new(rest.trim_start_matches('/'));
+    if relative.components().any(|part| !matches!(part, Component::Normal(_))) {
+        return None;
+    }
+    let mut file = dir.join(relative);
+    if file.is_dir() {
+        file.push("index.html");
+    }
+    let body = fs::read(&file).ok()?;
+    Some(
+        Response::new(Status::OK)
+            .with_header("Content-Type", content_type(&file))
+            .with_body(body),
+    )
+}
+
+/// The media type for a file, by its extension.
+fn content_type(path: &Path) -> &'static str {
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+    match extension.as_str() {
+        "html" | "htm" => "text/html; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "js" => "text/javascript; charset=utf-8",
+        "json" => "application/json",
+        "txt" | "md" | "rs" | "toml" => "text/plain; charset=utf-8",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "ico" => "image/x-icon",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::TempDir;
+    use std::io::{Read, Write};
+
+    fn server() -> Server {
+        let mut server = Server::new();
+        server
+            .get("/hello", |request| {
+                Response::text(Status::OK, format!("Hello, {}!", request.query("name").unwrap_or("world")))
+            })
+            .post("/echo", |request| Response::new(Status::CREATED).with_body(request.body.clone()))
+            .get("/boom", |_| panic!("handler bug"));
+        server
+    }
+
+    fn get(server: &Server, target: &str) -> Response {
+        server.handle(&Request::new(Method::Get, target))
+    }
+
+    #[test]
+    fn test_routes_and_query() {
+        let server = server();
+        let response = get(&server, "/hello?name=Ferris");
+        assert_eq!(response.status(), Status::OK);
+        assert_eq!(response.body(), b"Hello, Ferris!");
+        assert_eq!(get(&server, "/hello").body(), b"Hello, world!");
+
+        let mut post = Request::new(Method::Post, "/echo");
+        post.body = b"ping".to_vec();
+        let response = server.handle(&post);
+        assert_eq!((response.status(), response.body()), (Status::CREATED, &b"ping"[..]));
+    }
+
+    #[test]
+    fn test_not_found_and_wrong_method() {
+        let server = server();
+        let response = get(&server, "/<nope>");
+        assert_eq!(response.status(), Status::NOT_FOUND);
+        assert!(String::from_utf8_lossy(response.body()).contains("&lt;nope&gt;"));
+
+        let response = server.handle(&Request::new(Method::Delete, "/echo"));
+        assert_eq!(response.status(), Status::METHOD_NOT_ALLOWED);
+        assert_eq!(response.headers().get("allow"), Some("POST"));
+    }
+
+    #[test]
+    fn test_panicking_handler_is_a_server_error() {
+        let response = get(&server(), "/boom");
+        assert_eq!(response.status(), Status::INTERNAL_SERVER_ERROR);
+    }
+
+    #[test]
+    fn test_static_files() {
+        let dir = TempDir::new("http-static").unwrap();
+        fs::create_dir(dir.join("docs")).unwrap();
+        fs::write(dir.join("docs").join("index.html"), "<p>docs</p>").unwrap();
+        fs::write(dir.join("notes.txt"), "plain").unwrap();
+        let mut server = server();
+        server.static_files("/static/", dir.path());
+
+        let response = get(&server, "/static/notes.txt");
+        assert_eq!(response.body(), b"plain");
+        assert_eq!(response.headers().get("Content-Type"), Some("text/plain; charset=utf-8"));
+        assert_eq!(get(&server, "/static/docs").body(), b"<p>docs</p>");
+        assert_eq!(get(&server, "/static/docs/index.html").body(), b"<p>docs</p>");
+
+        for missing in ["/static/nope.txt", "/static/../notes.txt", "/static/%2E%2E/x", "/staticnotes.txt"] {
+            assert_eq!(get(&server, missing).status(), Status::NOT_FOUND, "{}", missing);
+        }
+        // Only GET reads files
+        let response = server.handle(&Request::new(Method::Post, "/static/notes.txt"));
+        assert_eq!(response.status(), Status::NOT_FOUND);
+    }
+
+    fn exchange(addr: SocketAddr, raw: &str) -> String {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.write_all(raw.as_bytes()).unwrap();
+        let mut reply = String::new();
+        stream.read_to_string(&mut reply).unwrap();
+        reply
+    }
+
+    #[test]
+    fn test_listen_over_tcp() {
+        let handle = server().workers(2).listen("127.0.0.1:0").unwrap();
+        let addr = handle.local_addr();
+
+        let reply = exchange(addr, "GET /hello?name=net HTTP/1.1\r\nHost: localhost\r\n\r\n");
+        assert!(reply.starts_with("HTTP/1.1 200 OK\r\n"), "{}", reply);
+        assert!(reply.ends_with("\r\n\r\nHello, net!"));
+
+        let reply = exchange(addr, "NONSENSE\r\n\r\n");
+        assert!(reply.starts_with("HTTP/1.1 400 Bad Request\r\n"), "{}", reply);
+
+        handle.shutdown();
+        // Nothing answers after shutdown
+        let refused = TcpStream::connect(addr)
+            .and_then(|mut s| s.write_all(b"GET /hello HTTP/1.1\r\n\r\n").and_then(|_| s.read(&mut [0; 16])));
+        assert!(matches!(refused, Err(_) | Ok(0)));
+    }
+}
@@ -0,0 +1,513 @@
+//! A key-value server speaking a tiny Redis-like text protocol, backed by
+//! a [`KvStore`] so that what clients set survives a restart.
+//!
+//! A client sends one command per line and gets one reply line back:
+//!
+//! ```text
+//! > SET greeting hello, world      < +OK
+//! > GET greeting                   < $hello, world
+//! > DEL greeting                   < :1
+//! > GET greeting                   < _
+//! > FLY away                       < -ERR unknown command `FLY`
+//! ```
+//!
+//! Command names are case-insensitive, a key is one word, and a value is
+//! the rest of the line, spaces and all. The first character of a reply
+//! says what it is, as in Redis: `+` a status, `-` an error, `:` a
+//! number, `$` a value and `_` no value. `nc` or `telnet` make a fine
+//! client; [`Client`] is one for programs.
+
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use super::connections::{self, ServerHandle};
+use crate::store::KvStore;
+
+/// The longest line either side accepts, newline included.
+pub const MAX_LINE: usize = 64 * 1024;
+
+// === THE PROTOCOL ===
+
+/// A request line.
+///
+/// ```
+/// use rustler::net::kvserver::Command;
+///
+/// let command = Command::parse("set motto  keep it simple").unwrap();
+/// assert_eq!(command, Command::Set("motto".to_string(), " keep it simple".to_string()));
+/// assert_eq!(Command::parse("GET").unwrap_err().to_string(), "usage: GET key");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Get(String),
+    Set(String, String),
+    Del(String),
+    Ping,
+    /// Ends the connection.
+    Quit,
+}
+
+impl Command {
+    /// Parses one line, without its line ending. Only the single space
+    /// after the key separates it from a `SET` value, so the value keeps
+    /// any further spaces.
+    pub fn parse(line: &str) -> Result<Command, ProtocolError> {
+        let line = line.trim_start();
+        let (name, rest) = line.split_once(' ').unwrap_or((line, ""));
+        if name.is_empty() {
+            return Err(ProtocolError::Empty);
+        }
+        let one_key = |usage| match rest.split_whitespace().collect::<Vec<_>>()[..] {
+            [key] => Ok(key.to_string()),
+            _ => Err(ProtocolError::Usage(usage)),
+        };
+        match name.to_ascii_uppercase().as_str() {
+            "GET" => one_key("GET key").map(Command::Get),
+            "DEL" => one_key("DEL key").map(Command::Del),
+            "SET" => {
+                let rest = rest.trim_start();
+                match rest.split_once(' ') {
+                    Some((key, value)) if !key.is_empty() => Ok(Command::Set(key.to_string(), value.to_string())),
+                    _ => Err(ProtocolError::Usage("SET key value")),
+                }
+            }
+            "PING" if rest.trim().is_empty() => Ok(Command::Ping),
+            "QUIT" if rest.trim().is_empty() => Ok(Command::Quit),
+            "PING" => Err(ProtocolError::Usage("PING")),
+            "QUIT" => Err(ProtocolError::Usage("QUIT")),
+            _ => Err(ProtocolError::UnknownCommand(name.to_string())),
+        }
+    }
+}
+
+/// The line that sends the command, without its newline.
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Command::Get(key) => write!(f, "GET {}", key),
+            Command::Set(key, value) => write!(f, "SET {} {}", key, value),
+            Command::Del(key) => write!(f, "DEL {}", key),
+            Command::Ping => write!(f, "PING"),
+            Command::Quit => write!(f, "QUIT"),
+        }
+    }
+}
+
+/// A reply line.
+///
+/// ```
+/// use rustler::net::kvserver::Reply;
+///
+/// assert_eq!(Reply::parse("$Ferris").unwrap(), Reply::Value("Ferris".to_string()));
+/// assert_eq!(Reply::Integer(1).to_string(), ":1");
+/// assert_eq!(Reply::Nil.to_string(), "_");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reply {
+    /// `+OK`, `+PONG`
+    Status(String),
+    /// `-ERR ...`, with the text after `-`
+    Error(String),
+    /// `:1`, how many keys a `DEL` removed
+    Integer(i64),
+    /// `$...`, a value, which may be empty
+    Value(String),
+    /// `_`, for a key with no value
+    Nil,
+}
+
+impl Reply {
+    pub fn parse(line: &str) -> Result<Reply, ProtocolError> {
+        let bad = || ProtocolError::BadReply(line.to_string());
+        // Every prefix is one byte, so a reply that starts with anything
+        // wider has no rest and falls through to the error
+        let rest = line.get(1..).unwrap_or("");
+        match line.chars().next() {
+            Some('+') => Ok(Reply::Status(rest.to_string())),
+            Some('-') => Ok(Reply::Error(rest.to_string())),
+            Some(':') => rest.parse().map(Reply::Integer).map_err(|_| bad()),
+            Some('$') => Ok(Reply::Value(rest.to_string())),
+            Some('_') if rest.is_empty() => Ok(Reply::Nil),
+            _ => Err(bad()),
+        }
+    }
+
+    fn ok() -> Reply {
+        Reply::Status("OK".to_string())
+    }
+}
+
+/// The line that sends the reply, without its newline.
+impl fmt::Display for Reply {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Reply::Status(status) => write!(f, "+{}", status),
+            Reply::Error(message) => write!(f, "-{}", message),
+            Reply::Integer(n) => write!(f, ":{}", n),
+            Reply::Value(value) => write!(f, "${}", value),
+            Reply::Nil => write!(f, "_"),
+        }
+    }
+}
+
+/// A line that breaks the protocol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProtocolError {
+    Empty,
+    UnknownCommand(String),
+    /// The right command with the wrong arguments; holds its usage.
+    Usage(&'static str),
+    /// A key that is empty or contains whitespace, or a value with a
+    /// line break, which a line can't carry.
+    Unsendable(String),
+    BadReply(String),
+    LineTooLong,
+}
+
+impl fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProtocolError::Empty => write!(f, "empty command"),
+            ProtocolError::UnknownCommand(name) => write!(f, "unknown command `{}`", name),
+            ProtocolError::Usage(usage) => write!(f, "usage: {}", usage),
+            ProtocolError::Unsendable(why) => write!(f, "cannot send {}", why),
+            ProtocolError::BadReply(line) => write!(f, "not a reply: {:?}", line),
+            ProtocolError::LineTooLong => write!(f, "line longer than {} bytes", MAX_LINE),
+        }
+    }
+}
+
+impl Error for ProtocolError {}
+
+/// Reads one line, without its line ending. `None` at end of input.
+fn read_line<R: BufRead>(reader: &mut R) -> io::Result<Option<Result<String, ProtocolError>>> {
+    let mut line = String::new();
+    let n = reader.take(MAX_LINE as u64).read_line(&mut line)?;
+    if n == 0 {
+        return Ok(None);
+    }
+    if !line.ends_with('\n') && n == MAX_LINE {
+        return Ok(Some(Err(ProtocolError::LineTooLong)));
+    }
+    let len = line.trim_end_matches(['\r', '\n']).len();
+    line.truncate(len);
+    Ok(Some(Ok(line)))
+}
+
+// === THE SERVER ===
+
+/// Answers commands from a [`KvStore`] shared by every connection.
+///
+/// ```
+/// use rustler::io::TempFile;
+/// use rustler::net::kvserver::{Client, KvServer};
+/// use rustler::store::KvStore;
+///
+/// let log = TempFile::new("kvserver-doc").unwrap();
+/// let handle = KvServer::new(KvStore::open(&log).unwrap()).listen("127.0.0.1:0").unwrap();
+///
+/// let mut client = Client::connect(handle.local_addr()).unwrap();
+/// client.set("crab", "Ferris").unwrap();
+/// assert_eq!(client.get("crab").unwrap(), Some("Ferris".to_string()));
+/// assert!(client.del("crab").unwrap());
+/// assert_eq!(client.get("crab").unwrap(), None);
+/// handle.shutdown();
+/// ```
+#[derive(Debug, Clone)]
+pub struct KvServer {
+    store: Arc<Mutex<KvStore>>,
+    workers: usize,
+}
+
+impl KvServer {
+    /// A server for `store`, answering up to eight clients at once.
+    pub fn new(store: KvStore) -> Self {
+        KvServer {
+            store: Arc::new(Mutex::new(store)),
+            workers: 8,
+        }
+    }
+
+    /// How many clients are answered at once; more wait their turn.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `workers` is zero.
+    pub fn workers(mut self, workers: usize) -> Self {
+        assert!(workers > 0, "a server needs at least one worker");
+        self.workers = workers;
+        self
+    }
+
+    /// Runs one command against the store. `QUIT` is answered here with
+    /// `+OK`; closing the connection is up to the caller.
+    pub fn execute(&self, command: &Command) -> Reply {
+        // The store is consistent after a panic elsewhere: every change is
+        // one appended line
+        let mut store = self.store.lock().unwrap_or_else(PoisonError::into_inner);
+        let result = match command {
+            Command::Get(key) => store.get(key).map(|value| value.map_or(Reply::Nil, Reply::Value)),
+            Command::Set(key, value) => store.set(key, value).map(|()| Reply::ok()),
+            Command::Del(key) => store.remove(key).map(|removed| Reply::Integer(removed.into())),
+            Command::Ping => Ok(Reply::Status("PONG".to_string())),
+            Command::Quit => Ok(Reply::ok()),
+        };
+        result.unwrap_or_else(|e| Reply::Error(format!("ERR {}", e)))
+    }
+
+    /// Answers the commands on `stream` until the client sends `QUIT` or
+    /// hangs up. A bad line gets an error reply and the connection goes on,
+    /// except for an overlong one, after which it can't be trusted.
+    pub fn serve_connection(&self, stream: TcpStream) -> io::Result<()> {
+        let mut reader = BufReader::new(&stream);
+        let mut writer = &stream;
+        while let Some(line) = read_line(&mut reader)? {
+            let (reply, done) = match line.and_then(|line| Command::parse(&line)) {
+                Ok(command) => (self.execute(&command), command == Command::Quit),
+                Err(e) => (Reply::Error(format!("ERR {}", e)), e == ProtocolError::LineTooLong),
+            };
+            writeln!(writer, "{}", reply)?;
+            if done {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Binds `addr` and answers clients on a background thread until the
+    /// returned handle is shut down.
+    pub fn listen(self, addr: impl ToSocketAddrs) -> io::Result<ServerHandle> {
+        let workers = self.workers;
+        connections::listen(addr, workers, "kv-accept", move |stream| {
+            // A client that goes away only ends its own connection
+            let _ = self.serve_connection(stream);
+        })
+    }
+}
+
+// === THE CLIENT ===
+
+/// Errors from a [`Client`].
+#[derive(Debug)]
+pub enum ClientError {
+    Io(io::Error),
+    Protocol(ProtocolError),
+    /// The server answered with an error, whose text this is.
+    Server(String),
+    /// The server closed the connection instead of answering.
+    Closed,
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ClientError::Io(e) => write!(f, "I/O error: {}", e),
+            ClientError::Protocol(e) => write!(f, "protocol error: {}", e),
+            ClientError::Server(message) => write!(f, "the server answered: {}", message),
+            ClientError::Closed => write!(f, "the server closed the connection"),
+        }
+    }
+}
+
+impl Error for ClientError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ClientError::Io(e) => Some(e),
+            ClientError::Protocol(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ClientError {
+    fn from(e: io::Error) -> Self {
+        ClientError::Io(e)
+    }
+}
+
+impl From<ProtocolError> for ClientError {
+    fn from(e: ProtocolError) -> Self {
+        ClientError::Protocol(e)
+    }
+}
+
+/// One connection to a [`KvServer`].
+#[derive(Debug)]
+pub struct Client {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl Client {
+    pub fn connect(addr: impl ToSocketAddrs) -> Result<Client, ClientError> {
+        let stream = TcpStream::connect(addr)?;
+        Ok(Client {
+            reader: BufReader::new(stream.try_clone()?),
+            writer: stream,
+        })
+    }
+
+    pub fn get(&mut self, key: &str) -> Result<Option<String>, ClientError> {
+        check_key(key)?;
+        match self.send(&Command::Get(key.to_string()))? {
+            Reply::Value(value) => Ok(Some(value)),
+            Reply::Nil => Ok(None),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), ClientError> {
+        check_key(key)?;
+        if value.contains(['\r', '\n']) {
+            return Err(ProtocolError::Unsendable("a value with a line break".to_string()).into());
+        }
+        match self.send(&Command::Set(key.to_string(), value.to_string()))? {
+            Reply::Status(_) => Ok(()),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    /// Removes `key`, returning whether it was there.
+    pub fn del(&mut self, key: &str) -> Result<bool, ClientError> {
+        check_key(key)?;
+        match self.send(&Command::Del(key.to_string()))? {
+            Reply::Integer(n) => Ok(n > 0),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    pub fn ping(&mut self) -> Result<(), ClientError> {
+        match self.send(&Command::Ping)? {
+            Reply::Status(_) => Ok(()),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    /// Sends any command and returns the reply as it is; an error reply
+    /// is a `Reply::Error` here, not an `Err`.
+    pub fn send(&mut self, command: &Command) -> Result<Reply, ClientError> {
+        writeln!(self.writer, "{}", command)?;
+        let line = read_line(&mut self.reader)?.ok_or(ClientError::Closed)??;
+        Ok(Reply::parse(&line)?)
+    }
+}
+
+fn check_key(key: &str) -> Result<(), ProtocolError> {
+    if key.is_empty() || key.contains(char::is_whitespace) {
+        return Err(ProtocolError::Unsendable(format!("the key {:?}", key)));
+    }
+    Ok(())
+}
+
+/// An error reply, or one of the wrong kind for the command.
+fn unexpected(reply: Reply) -> ClientError {
+    match reply {
+        Reply::Error(message) => ClientError::Server(message),
+        other => ClientError::Protocol(ProtocolError::BadReply(other.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::TempDir;
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(Command::parse("GET lang"), Ok(Command::Get("lang".to_string())));
+        assert_eq!(Command::parse("  del   lang "), Ok(Command::Del("lang".to_string())));
+        assert_eq!(Command::parse("Set lang Rust 2024"), Ok(Command::Set("lang".to_string(), "Rust 2024".to_string())));
+        assert_eq!(Command::parse("SET  empty "), Ok(Command::Set("empty".to_string(), String::new())));
+        assert_eq!(Command::parse("ping"), Ok(Command::Ping));
+        assert_eq!(Command::parse("QUIT"), Ok(Command::Quit));
+
+        assert_eq!(Command::parse(""), Err(ProtocolError::Empty));
+        assert_eq!(Command::parse("   "), Err(ProtocolError::Empty));
+        assert_eq!(Command::parse("FLY away"), Err(ProtocolError::UnknownCommand("FLY".to_string())));
+        assert_eq!(Command::parse("GET"), Err(ProtocolError::Usage("GET key")));
+        assert_eq!(Command::parse("GET a b"), Err(ProtocolError::Usage("GET key")));
+        assert_eq!(Command::parse("SET lang"), Err(ProtocolError::Usage("SET key value")));
+        assert_eq!(Command::parse("PING me"), Err(ProtocolError::Usage("PING")));
+
+        for command in [Command::Get("k".to_string()), Command::Set("k".to_string(), "a b".to_string()), Command::Quit] {
+            assert_eq!(Command::parse(&command.to_string()), Ok(command));
+        }
+    }
+
+    #[test]
+    fn test_parse_replies() {
+        for reply in [
+            Reply::Status("OK".to_string()),
+            Reply::Error("ERR no".to_string()),
+            Reply::Integer(-3),
+            Reply::Value(String::new()),
+            Reply::Value("with spaces".to_string()),
+            Reply::Nil,
+        ] {
+            assert_eq!(Reply::parse(&reply.to_string()), Ok(reply));
+        }
+        for bad in ["", "OK", ":one", "_x"] {
+            assert_eq!(Reply::parse(bad), Err(ProtocolError::BadReply(bad.to_string())));
+        }
+    }
+
+    #[test]
+    fn test_execute() {
+        let dir = TempDir::new("kvserver-execute").unwrap();
+        let server = KvServer::new(KvStore::open(dir.join("kv.log")).unwrap());
+        let run = |line: &str| server.execute(&Command::parse(line).unwrap()).to_string();
+        assert_eq!(run("GET a"), "_");
+        assert_eq!(run("SET a 1 2 3"), "+OK");
+        assert_eq!(run("GET a"), "$1 2 3");
+        assert_eq!(run("DEL a"), ":1");
+        assert_eq!(run("DEL a"), ":0");
+        assert_eq!(run("PING"), "+PONG");
+    }
+
+    #[test]
+    fn test_over_tcp_with_bad_lines_and_restart() {
+        let dir = TempDir::new("kvserver-tcp").unwrap();
+        let log = dir.join("kv.log");
+        let handle = KvServer::new(KvStore::open(&log).unwrap()).listen("127.0.0.1:0").unwrap();
+
+        // A raw connection sees error replies and carries on
+        let stream = TcpStream::connect(handle.local_addr()).unwrap();
+        write!(&stream, "FLY\r\nGET\nSET kept across restarts\n\nQUIT\nPING\n").unwrap();
+        let replies: Vec<String> = BufReader::new(&stream).lines().map(Result::unwrap).collect();
+        assert_eq!(
+            replies,
+            [
+                "-ERR unknown command `FLY`",
+                "-ERR usage: GET key",
+                "+OK",
+                "-ERR empty command",
+                "+OK",
+            ]
+        );
+
+        let mut client = Client::connect(handle.local_addr()).unwrap();
+        client.ping().unwrap();
+        assert!(matches!(client.set("two words", "x"), Err(ClientError::Protocol(_))));
+        assert!(matches!(client.set("k", "line\nbreak"), Err(ClientError::Protocol(_))));
+        assert!(!client.del("missing").unwrap());
+        handle.shutdown();
+        assert!(matches!(client.ping(), Err(ClientError::Closed | ClientError::Io(_))));
+
+        // The values were in the store's log all along
+        let handle = KvServer::new(KvStore::open(&log).unwrap()).listen("127.0.0.1:0").unwrap();
+        let mut client = Client::connect(handle.local_addr()).unwrap();
+        assert_eq!(client.get("kept").unwrap(), Some("across restarts".to_string()));
+        handle.shutdown();
+    }
+
+    #[test]
+    fn test_overlong_line_ends_the_connection() {
+        let mut input = vec![b'x'; MAX_LINE + 10];
+        input.extend(b"\nPING\n");
+        let mut reader = &input[..];
+        assert_eq!(read_line(&mut reader).unwrap(), Some(Err(ProtocolError::LineTooLong)));
+    }
+}
@@ -0,0 +1,8 @@
+//! Networking on std's sockets alone, with no async runtime or HTTP
+//! crate: the protocols are written out by hand so they can be read.
+
+pub mod connections;
+pub mod http;
+pub mod kvserver;
+pub mod rpc;
+pub mod udp;
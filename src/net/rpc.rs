@@ -0,0 +1,717 @@
+//! JSON-RPC 2.0 over TCP, with the crate's own JSON parser.
+//!
+//! A client sends requests such as
+//! `{"jsonrpc":"2.0","method":"add","params":[2,3],"id":1}` and the
+//! server answers `{"jsonrpc":"2.0","result":5,"id":1}`, or an `error`
+//! object with a numeric code in place of `result`. A request without an
+//! `id` is a notification, which gets no answer at all, and an array of
+//! requests is a batch, answered with an array.
+//!
+//! TCP is a stream of bytes, not of messages, so each JSON text goes in a
+//! frame: its length in bytes as a big-endian `u32`, then the bytes. The
+//! reader always knows how much to read, however the bytes are split into
+//! packets on the way.
+//!
+//! ```
+//! use rustler::net::rpc::{param, Client, Fault, Server};
+//! use rustler::text::json::Value;
+//!
+//! let mut server = Server::new();
+//! server.register("add", |params| {
+//!     let a = param(params, 0, "a")?.as_f64().ok_or_else(|| Fault::invalid_params("a must be a number"))?;
+//!     let b = param(params, 1, "b")?.as_f64().ok_or_else(|| Fault::invalid_params("b must be a number"))?;
+//!     Ok(Value::from(a + b))
+//! });
+//! let handle = server.listen("127.0.0.1:0").unwrap();
+//!
+//! let mut client = Client::connect(handle.local_addr()).unwrap();
+//! let sum = client.call("add", Value::parse("[2, 3]").unwrap()).unwrap();
+//! assert_eq!(sum, Value::from(5.0));
+//! assert!(client.call("divide", Value::Null).is_err());
+//! handle.shutdown();
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::connections::{self, ServerHandle};
+use crate::text::json::{ParseError, Value};
+
+/// The largest frame either side accepts.
+pub const MAX_FRAME: usize = 1024 * 1024;
+
+// === FRAMES ===
+
+/// Writes `payload` as one frame and flushes.
+pub fn write_frame<W: Write>(mut writer: W, payload: &[u8]) -> Result<(), RpcError> {
+    if payload.len() > MAX_FRAME {
+        return Err(RpcError::FrameTooLarge(payload.len()));
+    }
+    writer.write_all(&(payload.len() as u32).to_be_bytes())?;
+    writer.write_all(payload)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reads one frame. Returns `None` if the reader ends before a frame
+/// starts, which is how the other side says it is done; ending in the
+/// middle of a frame is an error.
+///
+/// ```
+/// use rustler::net::rpc::{read_frame, write_frame};
+///
+/// let mut wire = Vec::new();
+/// write_frame(&mut wire, b"{}").unwrap();
+/// assert_eq!(wire, b"\x00\x00\x00\x02{}");
+///
+/// let mut reader = &wire[..];
+/// assert_eq!(read_frame(&mut reader).unwrap(), Some(b"{}".to_vec()));
+/// assert_eq!(read_frame(&mut reader).unwrap(), None);
+/// ```
+pub fn read_frame<R: Read>(mut reader: R) -> Result<Option<Vec<u8>>, RpcError> {
+    let mut header = [0; 4];
+    let mut filled = 0;
+    while filled < header.len() {
+        match reader.read(&mut header[filled..]) {
+            Ok(0) if filled == 0 => return Ok(None),
+            Ok(0) => return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into()),
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    let len = u32::from_be_bytes(header) as usize;
+    if len > MAX_FRAME {
+        return Err(RpcError::FrameTooLarge(len));
+    }
+    let mut payload = vec![0; len];
+    reader.read_exact(&mut payload)?;
+    Ok(Some(payload))
+}
+
+// === ERRORS ===
+
+/// The error codes JSON-RPC defines, and the range it leaves to servers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    /// The message was not JSON.
+    ParseError,
+    /// JSON, but not a request.
+    InvalidRequest,
+    MethodNotFound,
+    InvalidParams,
+    /// The method failed; here, it panicked.
+    InternalError,
+    /// Any other code, for the application's own errors.
+    Server(i64),
+}
+
+impl ErrorCode {
+    pub fn code(self) -> i64 {
+        match self {
+            ErrorCode::ParseError => -32700,
+            ErrorCode::InvalidRequest => -32600,
+            ErrorCode::MethodNotFound => -32601,
+            ErrorCode::InvalidParams => -32602,
+            ErrorCode::InternalError => -32603,
+            ErrorCode::Server(code) => code,
+        }
+    }
+
+    pub fn from_code(code: i64) -> ErrorCode {
+        match code {
+            -32700 => ErrorCode::ParseError,
+            -32600 => ErrorCode::InvalidRequest,
+            -32601 => ErrorCode::MethodNotFound,
+            -32602 => ErrorCode::InvalidParams,
+            -32603 => ErrorCode::InternalError,
+            code => ErrorCode::Server(code),
+        }
+    }
+}
+
+/// An `error` object: what a method returns instead of a result.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fault {
+    pub code: ErrorCode,
+    pub message: String,
+    /// Anything more the server wants to say, such as which field was bad.
+    pub data: Option<Value>,
+}
+
+impl Fault {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Fault {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+
+    /// The fault for parameters of the wrong shape or type.
+    pub fn invalid_params(message: impl Into<String>) -> Self {
+        Fault::new(ErrorCode::InvalidParams, message)
+    }
+
+    pub fn with_data(mut self, data: Value) -> Self {
+        self.data = Some(data);
+        self
+    }
+
+    pub fn to_value(&self) -> Value {
+        let mut fields = vec![
+            ("code", Value::from(self.code.code())),
+            ("message", Value::from(self.message.as_str())),
+        ];
+        if let Some(data) = &self.data {
+            fields.push(("data", data.clone()));
+        }
+        object(fields)
+    }
+
+    /// Reads an `error` object; `None` if it lacks a numeric `code` or a
+    /// string `message`.
+    pub fn from_value(value: &Value) -> Option<Fault> {
+        Some(Fault {
+            code: ErrorCode::from_code(value.get("code")?.as_i64()?),
+            message: value.get("message")?.as_str()?.to_string(),
+            data: value.get("data").cloned(),
+        })
+    }
+}
+
+impl fmt::Display for Fault {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} (error {})", self.message, self.code.code())
+    }
+}
+
+impl Error for Fault {}
+
+/// Everything a call can fail with.
+#[derive(Debug)]
+pub enum RpcError {
+    Io(io::Error),
+    /// A frame's length is over [`MAX_FRAME`].
+    FrameTooLarge(usize),
+    /// A frame that is not valid JSON.
+    Json(ParseError),
+    /// Valid JSON that breaks the protocol, such as an answer to the
+    /// wrong request.
+    Protocol(String),
+    /// The server answered with an error.
+    Fault(Fault),
+}
+
+impl fmt::Display for RpcError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RpcError::Io(e) => write!(f, "I/O error: {}", e),
+            RpcError::FrameTooLarge(len) => write!(f, "a frame of {} bytes is over the {} byte limit", len, MAX_FRAME),
+            RpcError::Json(e) => write!(f, "invalid JSON: {}", e),
+            RpcError::Protocol(message) => write!(f, "protocol error: {}", message),
+            RpcError::Fault(fault) => write!(f, "the server answered: {}", fault),
+        }
+    }
+}
+
+impl Error for RpcError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            RpcError::Io(e) => Some(e),
+            RpcError::Json(e) => Some(e),
+            RpcError::Fault(fault) => Some(fault),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for RpcError {
+    fn from(e: io::Error) -> Self {
+        RpcError::Io(e)
+    }
+}
+
+impl From<ParseError> for RpcError {
+    fn from(e: ParseError) -> Self {
+        RpcError::Json(e)
+    }
+}
+
+// === MESSAGES ===
+
+/// A call, or with no `id`, a notification.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Request {
+    /// A number or a string chosen by the client, echoed in the answer.
+    pub id: Option<Value>,
+    pub method: String,
+    /// An array or an object; `Null` when there are none.
+    pub params: Value,
+}
+
+impl Request {
+    pub fn to_value(&self) -> Value {
+        let mut fields = vec![("jsonrpc", Value::from("2.0")), ("method", Value::from(self.method.as_str()))];
+        if !self.params.is_null() {
+            fields.push(("params", self.params.clone()));
+        }
+        if let Some(id) = &self.id {
+            fields.push(("id", id.clone()));
+        }
+        object(fields)
+    }
+
+    /// Checks that `value` is a well-formed request. The fault is the one
+    /// to answer with.
+    pub fn from_value(value: &Value) -> Result<Request, Fault> {
+        let invalid = |why: &str| Fault::new(ErrorCode::InvalidRequest, why);
+        if value.as_object().is_none() {
+            return Err(invalid("a request must be an object"));
+        }
+        if value.get("jsonrpc").and_then(Value::as_str) != Some("2.0") {
+            return Err(invalid("\"jsonrpc\" must be \"2.0\""));
+        }
+        let method = value
+            .get("method")
+            .and_then(Value::as_str)
+            .ok_or_else(|| invalid("\"method\" must be a string"))?;
+        let params = match value.get("params") {
+            None => Value::Null,
+            Some(params @ (Value::Array(_) | Value::Object(_))) => params.clone(),
+            Some(_) => return Err(invalid("\"params\" must be an array or an object")),
+        };
+        let id = match value.get("id") {
+            None => None,
+            Some(id @ (Value::Null | Value::Number(_) | Value::String(_))) => Some(id.clone()),
+            Some(_) => return Err(invalid("\"id\" must be a number, a string or null")),
+        };
+        Ok(Request {
+            id,
+            method: method.to_string(),
+            params,
+        })
+    }
+}
+
+/// The answer to a call.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+    /// The request's id, or `Null` if the request was too broken to have
+    /// one.
+    pub id: Value,
+    pub result: Result<Value, Fault>,
+}
+
+impl Response {
+    pub fn to_value(&self) -> Value {
+        let body = match &self.result {
+            Ok(result) => ("result", result.clone()),
+            Err(fault) => ("error", fault.to_value()),
+        };
+        object(vec![("jsonrpc", Value::from("2.0")), body, ("id", self.id.clone())])
+    }
+
+    pub fn from_value(value: &Value) -> Result<Response, RpcError> {
+        let broken = |why: &str| RpcError::Protocol(format!("{} in {}", why, value));
+        if value.get("jsonrpc").and_then(Value::as_str) != Some("2.0") {
+            return Err(broken("no \"jsonrpc\": \"2.0\""));
+        }
+        let id = value.get("id").cloned().ok_or_else(|| broken("no \"id\""))?;
+        let result = match (value.get("result"), value.get("error")) {
+            (Some(result), None) => Ok(result.clone()),
+            (None, Some(error)) => Err(Fault::from_value(error).ok_or_else(|| broken("a malformed \"error\""))?),
+            _ => return Err(broken("not exactly one of \"result\" and \"error\"")),
+        };
+        Ok(Response { id, result })
+    }
+}
+
+/// Parameter `name`, looked up by `position` when the params are an
+/// array and by `name` when they are an object, for use in methods.
+pub fn param<'a>(params: &'a Value, position: usize, name: &str) -> Result<&'a Value, Fault> {
+    let found = match params {
+        Value::Array(items) => items.get(position),
+        Value::Object(fields) => fields.get(name),
+        _ => None,
+    };
+    found.ok_or_else(|| Fault::invalid_params(format!("missing parameter `{}`", name)))
+}
+
+fn object(fields: Vec<(&str, Value)>) -> Value {
+    Value::Object(fields.into_iter().map(|(key, value)| (key.to_string(), value)).collect::<BTreeMap<_, _>>())
+}
+
+// === THE SERVER ===
+
+type Method = Arc<dyn Fn(&Value) -> Result<Value, Fault> + Send + Sync>;
+
+/// Methods by name, and the loop that answers connections.
+pub struct Server {
+    methods: HashMap<String, Method>,
+    workers: usize,
+}
+
+impl Default for Server {
+    fn default() -> Self {
+        Server::new()
+    }
+}
+
+impl Server {
+    /// A server with no methods, answering up to eight connections at once.
+    pub fn new() -> Self {
+        Server {
+            methods: HashMap::new(),
+            workers: 8,
+        }
+    }
+
+    /// How many connections are answered at once. A connection holds its
+    /// worker until the client hangs up; more clients wait their turn.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `workers` is zero.
+    pub fn workers(mut self, workers: usize) -> Self {
+        assert!(workers > 0, "a server needs at least one worker");
+        self.workers = workers;
+        self
+    }
+
+    /// Answers calls to `method` with `handler`, which gets the params.
+    /// Registering a name again replaces its handler.
+    pub fn register<F>(&mut self, method: &str, handler: F) -> &mut Self
+    where
+        F: Fn(&Value) -> Result<Value, Fault> + Send + Sync + 'static,
+    {
+        self.methods.insert(method.to_string(), Arc::new(handler));
+        self
+    }
+
+    /// The answer to one frame's worth of JSON text, or `None` when there
+    /// is nothing to send back because it held only notifications.
+    ///
+    /// ```
+    /// use rustler::net::rpc::Server;
+    /// use rustler::text::json::Value;
+    ///
+    /// let mut server = Server::new();
+    /// server.register("ping", |_| Ok(Value::from("pong")));
+    /// let answer = server.handle_text(r#"{"jsonrpc": "2.0", "method": "ping", "id": 7}"#);
+    /// assert_eq!(answer.as_deref(), Some(r#"{"id":7,"jsonrpc":"2.0","result":"pong"}"#));
+    /// assert_eq!(server.handle_text(r#"{"jsonrpc": "2.0", "method": "ping"}"#), None);
+    /// ```
+    pub fn handle_text(&self, text: &str) -> Option<String> {
+        let answer = match Value::parse(text) {
+            Err(e) => Some(Response {
+                id: Value::Null,
+                result: Err(Fault::new(ErrorCode::ParseError, e.to_string())),
+            }
+            .to_value()),
+            Ok(Value::Array(batch)) if batch.is_empty() => Some(
+                Response {
+                    id: Value::Null,
+                    result: Err(Fault::new(ErrorCode::InvalidRequest, "an empty batch")),
+                }
+                .to_value(),
+            ),
+            Ok(Value::Array(batch)) => {
+                let answers: Vec<Value> = batch.iter().filter_map(|request| self.handle_value(request)).collect();
+                (!answers.is_empty()).then_some(Value::Array(answers))
+            }
+            Ok(request) => self.handle_value(&request),
+        };
+        answer.map(|answer| answer.to_string())
+    }
+
+    /// The answer to one request, if it wants one.
+    fn handle_value(&self, value: &Value) -> Option<Value> {
+        let request = match Request::from_value(value) {
+            Ok(request) => request,
+            Err(fault) => {
+                // Answer with the id if it can be made out at all
+                let id = value.get("id").cloned().unwrap_or(Value::Null);
+                return Some(Response { id, result: Err(fault) }.to_value());
+            }
+        };
+        let result = match self.methods.get(&request.method) {
+            Some(method) => panic::catch_unwind(AssertUnwindSafe(|| method(&request.params))).unwrap_or_else(|_| {
+                Err(Fault::new(ErrorCode::InternalError, format!("method `{}` failed", request.method)))
+            }),
+            None => Err(Fault::new(
+                ErrorCode::MethodNotFound,
+                format!("no method named `{}`", request.method),
+            )),
+        };
+        // Notifications are never answered, not even with an error
+        let id = request.id?;
+        Some(Response { id, result }.to_value())
+    }
+
+    /// Answers frames from `stream` until the client hangs up. A frame
+    /// over the size limit is answered with an error and ends the
+    /// connection, since what follows can't be trusted to be a frame.
+    pub fn serve_connection(&self, stream: TcpStream) -> Result<(), RpcError> {
+        let mut reader = BufReader::new(&stream);
+        let mut writer = BufWriter::new(&stream);
+        loop {
+            let frame = match read_frame(&mut reader) {
+                Ok(Some(frame)) => frame,
+                Ok(None) => return Ok(()),
+                Err(RpcError::FrameTooLarge(len)) => {
+                    let fault = Fault::new(ErrorCode::InvalidRequest, RpcError::FrameTooLarge(len).to_string());
+                    let answer = Response { id: Value::Null, result: Err(fault) }.to_value();
+                    write_frame(&mut writer, answer.to_string().as_bytes())?;
+                    return Err(RpcError::FrameTooLarge(len));
+                }
+                Err(e) => return Err(e),
+            };
+            let answer = match String::from_utf8(frame) {
+                Ok(text) => self.handle_text(&text),
+                Err(_) => {
+                    let fault = Fault::new(ErrorCode::ParseError, "the message is not UTF-8");
+                    Some(Response { id: Value::Null, result: Err(fault) }.to_value().to_string())
+                }
+            };
+            if let Some(answer) = answer {
+                write_frame(&mut writer, answer.as_bytes())?;
+            }
+        }
+    }
+
+    /// Binds `addr` and answers connections on a background thread until
+    /// the returned handle is shut down.
+    pub fn listen(self, addr: impl ToSocketAddrs) -> io::Result<ServerHandle> {
+        let workers = self.workers;
+        let server = Arc::new(self);
+        connections::listen(addr, workers, "rpc-accept", move |stream| {
+            // A client that breaks the protocol only loses its own
+            // connection
+            let _ = server.serve_connection(stream);
+        })
+    }
+}
+
+// === THE CLIENT ===
+
+/// One connection to a server, making one call at a time.
+#[derive(Debug)]
+pub struct Client {
+    reader: BufReader<TcpStream>,
+    writer: BufWriter<TcpStream>,
+    next_id: i64,
+}
+
+impl Client {
+    pub fn connect(addr: impl ToSocketAddrs) -> Result<Client, RpcError> {
+        let stream = TcpStream::connect(addr)?;
+        Ok(Client {
+            reader: BufReader::new(stream.try_clone()?),
+            writer: BufWriter::new(stream),
+            next_id: 1,
+        })
+    }
+
+    /// How long to wait for an answer before a call fails; `None`, the
+    /// default, waits forever.
+    pub fn set_timeout(&self, timeout: Option<Duration>) -> Result<(), RpcError> {
+        self.reader.get_ref().set_read_timeout(timeout)?;
+        Ok(())
+    }
+
+    /// Calls `method` and waits for its result. An error answer comes back
+    /// as [`RpcError::Fault`].
+    pub fn call(&mut self, method: &str, params: Value) -> Result<Value, RpcError> {
+        let id = self.next_id;
+        self.next_id += 1;
+        let request = Request {
+            id: Some(Value::from(id)),
+            method: method.to_string(),
+            params,
+        };
+        write_frame(&mut self.writer, request.to_value().to_string().as_bytes())?;
+        let frame = read_frame(&mut self.reader)?
+            .ok_or_else(|| RpcError::Protocol("the server hung up without answering".to_string()))?;
+        let text = String::from_utf8(frame).map_err(|_| RpcError::Protocol("an answer that is not UTF-8".to_string()))?;
+        let response = Response::from_value(&Value::parse(&text)?)?;
+        if response.id != Value::from(id) {
+            return Err(RpcError::Protocol(format!("expected the answer to call {}, got {}", id, response.id)));
+        }
+        response.result.map_err(RpcError::Fault)
+    }
+
+    /// Sends a notification: the method runs, but nothing is sent back,
+    /// so there is no way to know when or whether it succeeded.
+    pub fn notify(&mut self, method: &str, params: Value) -> Result<(), RpcError> {
+        let request = Request {
+            id: None,
+            method: method.to_string(),
+            params,
+        };
+        write_frame(&mut self.writer, request.to_value().to_string().as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn json(text: &str) -> Value {
+        Value::parse(text).unwrap()
+    }
+
+    fn calculator() -> Server {
+        let mut server = Server::new();
+        server.register("subtract", |params| {
+            let number = |position, name| {
+                param(params, position, name)?
+                    .as_f64()
+                    .ok_or_else(|| Fault::invalid_params(format!("`{}` must be a number", name)))
+            };
+            Ok(Value::from(number(0, "minuend")? - number(1, "subtrahend")?))
+        });
+        server.register("crash", |_| panic!("deliberately"));
+        server.register("teapot", |_| {
+            Err(Fault::new(ErrorCode::Server(418), "I'm a teapot").with_data(Value::from("short and stout")))
+        });
+        server
+    }
+
+    /// The answer to `request` as a value, for comparing without caring
+    /// about key order or spacing.
+    fn answer(server: &Server, request: &str) -> Option<Value> {
+        server.handle_text(request).map(|text| json(&text))
+    }
+
+    #[test]
+    fn test_calls_with_positional_and_named_params() {
+        let server = calculator();
+        assert_eq!(
+            answer(&server, r#"{"jsonrpc": "2.0", "method": "subtract", "params": [42, 23], "id": 1}"#),
+            Some(json(r#"{"jsonrpc": "2.0", "result": 19, "id": 1}"#))
+        );
+        assert_eq!(
+            answer(
+                &server,
+                r#"{"jsonrpc": "2.0", "method": "subtract", "params": {"subtrahend": 23, "minuend": 42}, "id": "a"}"#
+            ),
+            Some(json(r#"{"jsonrpc": "2.0", "result": 19, "id": "a"}"#))
+        );
+        assert_eq!(answer(&server, r#"{"jsonrpc": "2.0", "method": "subtract", "params": [1, 2]}"#), None);
+    }
+
+    #[test]
+    fn test_error_codes() {
+        let server = calculator();
+        let code = |request: &str| {
+            let answer = answer(&server, request).unwrap();
+            answer.get("error").and_then(|error| error.get("code")).and_then(Value::as_i64)
+        };
+        assert_eq!(code("{not json"), Some(-32700));
+        assert_eq!(code(r#"{"method": "subtract", "id": 1}"#), Some(-32600));
+        assert_eq!(code(r#"{"jsonrpc": "2.0", "method": 1, "id": 1}"#), Some(-32600));
+        assert_eq!(code(r#"{"jsonrpc": "2.0", "method": "x", "params": 3, "id": 1}"#), Some(-32600));
+        assert_eq!(code("[]"), Some(-32600));
+        assert_eq!(code(r#"{"jsonrpc": "2.0", "method": "divide", "id": 1}"#), Some(-32601));
+        assert_eq!(code(r#"{"jsonrpc": "2.0", "method": "subtract", "params": [1], "id": 1}"#), Some(-32602));
+        assert_eq!(code(r#"{"jsonrpc": "2.0", "method": "subtract", "params": ["a", 1], "id": 1}"#), Some(-32602));
+        assert_eq!(code(r#"{"jsonrpc": "2.0", "method": "crash", "id": 1}"#), Some(-32603));
+
+        let teapot = answer(&server, r#"{"jsonrpc": "2.0", "method": "teapot", "id": 9}"#).unwrap();
+        let fault = Fault::from_value(teapot.get("error").unwrap()).unwrap();
+        assert_eq!(fault.code, ErrorCode::Server(418));
+        assert_eq!(fault.data, Some(Value::from("short and stout")));
+        assert_eq!(fault.to_string(), "I'm a teapot (error 418)");
+    }
+
+    #[test]
+    fn test_batches() {
+        let server = calculator();
+        let batch = r#"[
+            {"jsonrpc": "2.0", "method": "subtract", "params": [3, 1], "id": 1},
+            {"jsonrpc": "2.0", "method": "subtract", "params": [3, 1]},
+            {"foo": "boo"},
+            {"jsonrpc": "2.0", "method": "nope", "id": "5"}
+        ]"#;
+        let answers = answer(&server, batch).unwrap();
+        let answers = answers.as_array().unwrap();
+        assert_eq!(answers.len(), 3);
+        assert_eq!(answers[0].get("result"), Some(&Value::from(2.0)));
+        assert_eq!(answers[1].get("id"), Some(&Value::Null));
+        assert_eq!(answers[2].get("id"), Some(&Value::from("5")));
+
+        let notifications = r#"[{"jsonrpc": "2.0", "method": "a"}, {"jsonrpc": "2.0", "method": "b"}]"#;
+        assert_eq!(server.handle_text(notifications), None);
+    }
+
+    #[test]
+    fn test_messages_round_trip() {
+        let request = Request {
+            id: Some(Value::from(3.0)),
+            method: "sum".to_string(),
+            params: json("[1, 2]"),
+        };
+        assert_eq!(Request::from_value(&request.to_value()), Ok(request));
+        let response = Response {
+            id: Value::from("x"),
+            result: Err(Fault::invalid_params("no")),
+        };
+        assert_eq!(Response::from_value(&response.to_value()).unwrap(), response);
+        assert!(Response::from_value(&json(r#"{"jsonrpc": "2.0", "id": 1}"#)).is_err());
+        assert!(Response::from_value(&json(r#"{"jsonrpc": "2.0", "result": 1, "error": {}, "id": 1}"#)).is_err());
+    }
+
+    #[test]
+    fn test_frames() {
+        let mut wire = Vec::new();
+        write_frame(&mut wire, b"one").unwrap();
+        write_frame(&mut wire, b"").unwrap();
+        let mut reader = &wire[..];
+        assert_eq!(read_frame(&mut reader).unwrap(), Some(b"one".to_vec()));
+        assert_eq!(read_frame(&mut reader).unwrap(), Some(Vec::new()));
+        assert_eq!(read_frame(&mut reader).unwrap(), None);
+
+        assert!(matches!(read_frame(&b"\x00\x00"[..]), Err(RpcError::Io(_))));
+        assert!(matches!(read_frame(&b"\x00\x00\x00\x05abc"[..]), Err(RpcError::Io(_))));
+        assert!(matches!(read_frame(&b"\xff\xff\xff\xff"[..]), Err(RpcError::FrameTooLarge(_))));
+        assert!(matches!(write_frame(Vec::new(), &vec![0; MAX_FRAME + 1]), Err(RpcError::FrameTooLarge(_))));
+    }
+
+    #[test]
+    fn test_client_and_server_over_tcp() {
+        let notified = Arc::new(AtomicUsize::new(0));
+        let mut server = calculator().workers(2);
+        let count = Arc::clone(&notified);
+        server.register("log", move |_| {
+            count.fetch_add(1, Ordering::SeqCst);
+            Ok(Value::Null)
+        });
+        let handle = server.listen("127.0.0.1:0").unwrap();
+
+        let mut client = Client::connect(handle.local_addr()).unwrap();
+        client.set_timeout(Some(Duration::from_secs(5))).unwrap();
+        client.notify("log", Value::Null).unwrap();
+        assert_eq!(client.call("subtract", json("[10, 4]")).unwrap(), Value::from(6.0));
+        // The notification was read before the call that followed it
+        assert_eq!(notified.load(Ordering::SeqCst), 1);
+        match client.call("teapot", Value::Null) {
+            Err(RpcError::Fault(fault)) => assert_eq!(fault.code, ErrorCode::Server(418)),
+            other => panic!("expected a fault, got {:?}", other),
+        }
+
+        // Another client is answered while the first stays connected, and
+        // shutting down doesn't wait for either to hang up
+        let mut other = Client::connect(handle.local_addr()).unwrap();
+        assert_eq!(other.call("subtract", json("[1, 1]")).unwrap(), Value::from(0.0));
+        handle.shutdown();
+        assert!(client.call("subtract", json("[1, 1]")).is_err());
+    }
+}
@@ -0,0 +1,414 @@
+//! Ping and pong over UDP: sequence numbers, round-trip times and loss.
+//!
+//! UDP delivers datagrams whole or not at all, in any order, perhaps
+//! twice. A [`Pinger`] sends numbered pings and waits a limited time for
+//! the pong with the same number, so a lost datagram costs one timeout and
+//! a late one is recognised and ignored. [`serve_pongs`] is the other end.
+//!
+//! Every datagram is one 16-byte [`Packet`]. Numbers are big-endian,
+//! the usual "network byte order":
+//!
+//! ```text
+//! magic b"RP"  kind: u8 (0 ping, 1 pong)  0: u8  seq: u32  sent_micros: u64
+//! ```
+//!
+//! `sent_micros` is the sender's clock, in microseconds since its pinger
+//! started. The pong echoes it back unchanged, so the sender can work out
+//! the round trip from the pong alone.
+
+use std::error::Error;
+use std::fmt;
+use std::io::{self, ErrorKind};
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::time::{Duration, Instant};
+
+use crate::concurrent::CancellationToken;
+
+const MAGIC: &[u8; 2] = b"RP";
+/// The size of every packet on the wire.
+pub const PACKET_LEN: usize = 16;
+
+const KIND_PING: u8 = 0;
+const KIND_PONG: u8 = 1;
+
+/// How often [`serve_pongs`] checks whether it has been cancelled.
+const POLL: Duration = Duration::from_millis(50);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Ping,
+    Pong,
+}
+
+/// One ping or pong.
+///
+/// ```
+/// use rustler::net::udp::{Kind, Packet};
+///
+/// let ping = Packet { kind: Kind::Ping, seq: 7, sent_micros: 1_500 };
+/// let bytes = ping.to_bytes();
+/// assert_eq!(&bytes[..4], b"RP\x00\x00");
+/// assert_eq!(&bytes[4..8], &[0, 0, 0, 7]);
+/// assert_eq!(Packet::from_bytes(&bytes), Ok(ping));
+/// assert_eq!(ping.pong().kind, Kind::Pong);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Packet {
+    pub kind: Kind,
+    pub seq: u32,
+    pub sent_micros: u64,
+}
+
+impl Packet {
+    pub fn to_bytes(&self) -> [u8; PACKET_LEN] {
+        let mut bytes = [0; PACKET_LEN];
+        bytes[..2].copy_from_slice(MAGIC);
+        bytes[2] = match self.kind {
+            Kind::Ping => KIND_PING,
+            Kind::Pong => KIND_PONG,
+        };
+        bytes[4..8].copy_from_slice(&self.seq.to_be_bytes());
+        bytes[8..].copy_from_slice(&self.sent_micros.to_be_bytes());
+        bytes
+    }
+
+    /// Decodes a datagram. Anything but exactly [`PACKET_LEN`] bytes with
+    /// the right magic and a known kind is rejected, since a UDP port
+    /// receives whatever anyone sends it.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Packet, PacketError> {
+        let bytes: &[u8; PACKET_LEN] = bytes.try_into().map_err(|_| PacketError::WrongLength(bytes.len()))?;
+        if &bytes[..2] != MAGIC {
+            return Err(PacketError::BadMagic);
+        }
+        let kind = match bytes[2] {
+            KIND_PING => Kind::Ping,
+            KIND_PONG => Kind::Pong,
+            other => return Err(PacketError::UnknownKind(other)),
+        };
+        // The slices have the right lengths, so the conversions can't fail
+        let seq = u32::from_be_bytes(bytes[4..8].try_into().unwrap());
+        let sent_micros = u64::from_be_bytes(bytes[8..].try_into().unwrap());
+        Ok(Packet { kind, seq, sent_micros })
+    }
+
+    /// The answer to this ping: the same number and timestamp.
+    pub fn pong(&self) -> Packet {
+        Packet {
+            kind: Kind::Pong,
+            ..*self
+        }
+    }
+}
+
+/// Why a datagram is not a [`Packet`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PacketError {
+    WrongLength(usize),
+    BadMagic,
+    UnknownKind(u8),
+}
+
+impl fmt::Display for PacketError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PacketError::WrongLength(len) => write!(f, "expected {} bytes, got {}", PACKET_LEN, len),
+            PacketError::BadMagic => write!(f, "not a ping packet (bad magic bytes)"),
+            PacketError::UnknownKind(kind) => write!(f, "unknown packet kind {:#04x}", kind),
+        }
+    }
+}
+
+impl Error for PacketError {}
+
+/// Answers every ping that arrives on `socket` with a pong, until `token`
+/// is cancelled. Datagrams that aren't pings are ignored. Returns how
+/// many pings were answered.
+pub fn serve_pongs(socket: &UdpSocket, token: &CancellationToken) -> io::Result<u64> {
+    // Waking up now and then is how a blocking receive notices the token
+    socket.set_read_timeout(Some(POLL))?;
+    let mut answered = 0;
+    // One more than a packet, so an oversized datagram isn't cut down to
+    // a valid-looking one
+    let mut buf = [0; PACKET_LEN + 1];
+    while !token.is_cancelled() {
+        let (len, from) = match socket.recv_from(&mut buf) {
+            Ok(received) => received,
+            Err(e) if is_timeout(&e) => continue,
+            Err(e) => return Err(e),
+        };
+        if let Ok(packet @ Packet { kind: Kind::Ping, .. }) = Packet::from_bytes(&buf[..len]) {
+            socket.send_to(&packet.pong().to_bytes(), from)?;
+            answered += 1;
+        }
+    }
+    Ok(answered)
+}
+
+/// Sends numbered pings to one peer and keeps [`PingStats`].
+///
+/// ```
+/// use std::net::UdpSocket;
+/// use std::thread;
+/// use std::time::Duration;
+/// use rustler::concurrent::CancellationToken;
+/// use rustler::net::udp::{serve_pongs, Pinger};
+///
+/// let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+/// let addr = server.local_addr().unwrap();
+/// let token = CancellationToken::new();
+/// let stop = token.clone();
+/// let ponger = thread::spawn(move || serve_pongs(&server, &stop));
+///
+/// let mut pinger = Pinger::connect(addr, Duration::from_secs(1)).unwrap();
+/// assert!(pinger.ping().unwrap().is_some());
+/// assert_eq!(pinger.stats().received, 1);
+///
+/// token.cancel();
+/// assert_eq!(ponger.join().unwrap().unwrap(), 1);
+/// ```
+#[derive(Debug)]
+pub struct Pinger {
+    socket: UdpSocket,
+    timeout: Duration,
+    start: Instant,
+    next_seq: u32,
+    stats: PingStats,
+}
+
+impl Pinger {
+    /// Binds a local port and aims it at `peer`, waiting up to `timeout`
+    /// for each pong.
+    pub fn connect(peer: impl ToSocketAddrs, timeout: Duration) -> io::Result<Pinger> {
+        let peer = peer
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| io::Error::new(ErrorKind::NotFound, "the address did not resolve"))?;
+        let local: SocketAddr = if peer.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" }.parse().unwrap();
+        let socket = UdpSocket::bind(local)?;
+        // A connected UDP socket only sends to, and only receives from,
+        // that one peer
+        socket.connect(peer)?;
+        Ok(Pinger::new(socket, timeout))
+    }
+
+    /// Uses a socket that is already connected to its peer.
+    pub fn new(socket: UdpSocket, timeout: Duration) -> Pinger {
+        Pinger {
+            socket,
+            timeout,
+            start: Instant::now(),
+            next_seq: 1,
+            stats: PingStats::default(),
+        }
+    }
+
+    /// The sequence number of the next ping, counting from 1.
+    pub fn next_seq(&self) -> u32 {
+        self.next_seq
+    }
+
+    /// Sends the next ping and waits for its pong. Returns the round-trip
+    /// time, or `None` if the pong didn't come within the timeout. Pongs
+    /// for earlier pings that turn up meanwhile are counted as late.
+    pub fn ping(&mut self) -> io::Result<Option<Duration>> {
+        let seq = self.next_seq;
+        self.next_seq = self.next_seq.wrapping_add(1);
+        let ping = Packet {
+            kind: Kind::Ping,
+            seq,
+            sent_micros: self.micros(),
+        };
+        self.socket.send(&ping.to_bytes())?;
+        self.stats.sent += 1;
+
+        let deadline = Instant::now() + self.timeout;
+        let mut buf = [0; PACKET_LEN + 1];
+        loop {
+            let left = deadline.saturating_duration_since(Instant::now());
+            // A zero read timeout is an error, not "don't wait"
+            if left.is_zero() {
+                return Ok(None);
+            }
+            self.socket.set_read_timeout(Some(left))?;
+            let len = match self.socket.recv(&mut buf) {
+                Ok(len) => len,
+                Err(e) if is_timeout(&e) => return Ok(None),
+                Err(e) => return Err(e),
+            };
+            match Packet::from_bytes(&buf[..len]) {
+                Ok(Packet { kind: Kind::Pong, seq: got, sent_micros }) if got == seq => {
+                    let rtt = Duration::from_micros(self.micros().saturating_sub(sent_micros));
+                    self.stats.record(rtt);
+                    return Ok(Some(rtt));
+                }
+                Ok(Packet { kind: Kind::Pong, .. }) => self.stats.late += 1,
+                // Noise on the port; keep waiting
+                _ => {}
+            }
+        }
+    }
+
+    pub fn stats(&self) -> &PingStats {
+        &self.stats
+    }
+
+    fn micros(&self) -> u64 {
+        self.start.elapsed().as_micros() as u64
+    }
+}
+
+/// Counts and round-trip times, summarised like `ping` does.
+///
+/// ```
+/// use std::time::Duration;
+/// use rustler::net::udp::PingStats;
+///
+/// let mut stats = PingStats::default();
+/// stats.sent = 4;
+/// stats.record(Duration::from_millis(2));
+/// stats.record(Duration::from_millis(4));
+/// stats.record(Duration::from_millis(3));
+/// assert_eq!(stats.loss_percent(), 25.0);
+/// assert_eq!(stats.avg(), Some(Duration::from_millis(3)));
+/// assert_eq!(
+///     stats.to_string(),
+///     "4 sent, 3 received, 25.0% loss, round trip min/avg/max 2.000/3.000/4.000 ms"
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PingStats {
+    pub sent: u32,
+    pub received: u32,
+    /// Pongs that arrived after their ping had timed out.
+    pub late: u32,
+    pub min: Option<Duration>,
+    pub max: Option<Duration>,
+    total: Duration,
+}
+
+impl PingStats {
+    /// Counts a pong that came back after `rtt`. Sending is counted
+    /// separately, in `sent`.
+    pub fn record(&mut self, rtt: Duration) {
+        self.received += 1;
+        self.total += rtt;
+        self.min = Some(self.min.map_or(rtt, |min| min.min(rtt)));
+        self.max = Some(self.max.map_or(rtt, |max| max.max(rtt)));
+    }
+
+    /// The mean round trip, or `None` before any pong.
+    pub fn avg(&self) -> Option<Duration> {
+        (self.received > 0).then(|| self.total / self.received)
+    }
+
+    /// The share of pings that got no pong in time, from 0 to 100.
+    pub fn loss_percent(&self) -> f64 {
+        if self.sent == 0 {
+            return 0.0;
+        }
+        f64::from(self.sent.saturating_sub(self.received)) * 100.0 / f64::from(self.sent)
+    }
+}
+
+impl fmt::Display for PingStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} sent, {} received, {:.1}% loss", self.sent, self.received, self.loss_percent())?;
+        if let (Some(min), Some(avg), Some(max)) = (self.min, self.avg(), self.max) {
+            let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+            write!(f, ", round trip min/avg/max {:.3}/{:.3}/{:.3} ms", ms(min), ms(avg), ms(max))?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether a receive gave up because of its timeout. Unix says
+/// `WouldBlock` and Windows `TimedOut`.
+fn is_timeout(e: &io::Error) -> bool {
+    matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_packet_round_trip_and_rejects() {
+        let pong = Packet {
+            kind: Kind::Pong,
+            seq: u32::MAX,
+            sent_micros: 0x0102_0304_0506_0708,
+        };
+        let bytes = pong.to_bytes();
+        assert_eq!(bytes, *b"RP\x01\x00\xff\xff\xff\xff\x01\x02\x03\x04\x05\x06\x07\x08");
+        assert_eq!(Packet::from_bytes(&bytes), Ok(pong));
+
+        assert_eq!(Packet::from_bytes(&bytes[..15]), Err(PacketError::WrongLength(15)));
+        assert_eq!(Packet::from_bytes(&[0; 17]), Err(PacketError::WrongLength(17)));
+        let mut bad = bytes;
+        bad[0] = b'X';
+        assert_eq!(Packet::from_bytes(&bad), Err(PacketError::BadMagic));
+        bad = bytes;
+        bad[2] = 9;
+        assert_eq!(Packet::from_bytes(&bad), Err(PacketError::UnknownKind(9)));
+    }
+
+    #[test]
+    fn test_stats_without_pongs() {
+        let mut stats = PingStats::default();
+        assert_eq!(stats.loss_percent(), 0.0);
+        stats.sent = 3;
+        assert_eq!(stats.avg(), None);
+        assert_eq!(stats.to_string(), "3 sent, 0 received, 100.0% loss");
+    }
+
+    #[test]
+    fn test_lost_and_late_pongs() {
+        // A peer that sits on the first ping and answers it only after
+        // the second arrives, then answers the second at once
+        let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let addr = peer.local_addr().unwrap();
+        let slow = thread::spawn(move || {
+            let mut buf = [0; PACKET_LEN];
+            let (_, from) = peer.recv_from(&mut buf).unwrap();
+            let first = Packet::from_bytes(&buf).unwrap();
+            peer.recv_from(&mut buf).unwrap();
+            let second = Packet::from_bytes(&buf).unwrap();
+            peer.send_to(b"noise", from).unwrap();
+            peer.send_to(&first.pong().to_bytes(), from).unwrap();
+            peer.send_to(&second.pong().to_bytes(), from).unwrap();
+        });
+
+        let mut pinger = Pinger::connect(addr, Duration::from_millis(100)).unwrap();
+        assert_eq!(pinger.ping().unwrap(), None);
+        pinger.timeout = Duration::from_secs(5);
+        assert!(pinger.ping().unwrap().is_some());
+        slow.join().unwrap();
+
+        let stats = pinger.stats();
+        assert_eq!((stats.sent, stats.received, stats.late), (2, 1, 1));
+        assert_eq!(stats.loss_percent(), 50.0);
+        assert_eq!(pinger.next_seq(), 3);
+    }
+
+    #[test]
+    fn test_serve_pongs_ignores_junk() {
+        let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let addr = server.local_addr().unwrap();
+        let token = CancellationToken::new();
+        let stop = token.clone();
+        let ponger = thread::spawn(move || serve_pongs(&server, &stop).unwrap());
+
+        let client = UdpSocket::bind("127.0.0.1:0").unwrap();
+        client.connect(addr).unwrap();
+        client.send(b"hello").unwrap();
+        let pong = Packet { kind: Kind::Pong, seq: 1, sent_micros: 0 };
+        client.send(&pong.to_bytes()).unwrap();
+        let mut pinger = Pinger::new(client, Duration::from_secs(5));
+        for _ in 0..3 {
+            assert!(pinger.ping().unwrap().is_some());
+        }
+        token.cancel();
+        assert_eq!(ponger.join().unwrap(), 3);
+    }
+}
@@ -0,0 +1,381 @@
+//! What a learner has done so far: examples run, quizzes taken,
+//! exercises solved, hints asked for and where each flashcard is in its
+//! schedule, kept in a JSON file between sessions.
+//!
+//! The `rustler` binary updates the file as it goes, and
+//! `rustler progress` prints a [`Summary`]. Hint counts are kept per
+//! exercise, so an instructor reading the file can see where a learner
+//! got stuck.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::catalog;
+use crate::flashcards::{self, CardState, Rating};
+use crate::io::dirs::{self, UserDir};
+use crate::quiz::{self, Score};
+use crate::store::versioned::{self, VersionError};
+
+/// The format version written by [`Progress::save`].
+pub const SAVE_VERSION: u32 = 1;
+
+/// The best attempt at one quiz.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuizRecord {
+    /// The highest score, in percent.
+    pub best: u32,
+    pub attempts: u32,
+    /// Whether any attempt reached the pass mark.
+    pub passed: bool,
+}
+
+/// Everything the learner has completed.
+///
+/// ```
+/// use rustler::progress::Progress;
+/// use rustler::quiz::Score;
+///
+/// let mut progress = Progress::new();
+/// progress.mark_example("01_hello_world");
+/// let mut score = Score::new();
+/// score.record(true);
+/// progress.record_quiz("01_hello_world", score);
+///
+/// let summary = progress.summary();
+/// assert_eq!(summary.examples_run, 1);
+/// assert_eq!(summary.quizzes_passed, 1);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Progress {
+    /// Names of examples run to completion.
+    #[serde(default)]
+    examples: BTreeSet<String>,
+    /// Quiz results by example name.
+    #[serde(default)]
+    quizzes: BTreeMap<String, QuizRecord>,
+    /// Names of exercises whose tests pass.
+    #[serde(default)]
+    exercises: BTreeSet<String>,
+    /// How many hints each exercise has needed.
+    #[serde(default)]
+    hints: BTreeMap<String, u32>,
+    /// Flashcard schedules by example name, then by term.
+    #[serde(default)]
+    cards: BTreeMap<String, BTreeMap<String, CardState>>,
+}
+
+/// What is written to disk: the progress plus a version number.
+#[derive(Serialize, Deserialize)]
+struct SaveFile {
+    version: u32,
+    #[serde(flatten)]
+    progress: Progress,
+}
+
+impl Progress {
+    pub fn new() -> Self {
+        Progress::default()
+    }
+
+    /// Where progress lives by default: `progress.json` in the user's
+    /// config directory, as found by [`dirs::user_dir`]. `None` if that is
+    /// unknown.
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::user_dir(UserDir::Config).map(|dir| dir.join("progress.json"))
+    }
+
+    /// Reads progress written by [`save`](Progress::save). A file that does
+    /// not exist yet means nothing has been done.
+    pub fn load(path: impl AsRef<Path>) -> Result<Progress, ProgressError> {
+        let json = match fs::read_to_string(path) {
+            Ok(json) => json,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Progress::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let file: SaveFile = versioned::from_json(&json, SAVE_VERSION)?;
+        Ok(file.progress)
+    }
+
+    /// Writes the progress as JSON, creating missing parent directories.
+    /// Like the to-do list, it is written to a temporary file and renamed
+    /// into place.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ProgressError> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let file = SaveFile {
+            version: SAVE_VERSION,
+            progress: self.clone(),
+        };
+        let json = serde_json::to_string_pretty(&file)?;
+        let temp = path.with_extension("json.tmp");
+        fs::write(&temp, json)?;
+        fs::rename(&temp, path)?;
+        Ok(())
+    }
+
+    /// Records that an example was run. Returns `false` if it already was.
+    pub fn mark_example(&mut self, name: &str) -> bool {
+        self.examples.insert(name.to_string())
+    }
+
+    /// Records an attempt at a quiz, keeping the best score.
+    pub fn record_quiz(&mut self, topic: &str, score: Score) {
+        let record = self.quizzes.entry(topic.to_string()).or_insert(QuizRecord {
+            best: 0,
+            attempts: 0,
+            passed: false,
+        });
+        record.attempts += 1;
+        record.best = record.best.max(score.percent());
+        record.passed |= score.passed();
+    }
+
+    /// Records that an exercise was solved. Returns `false` if it already
+    /// was.
+    pub fn mark_exercise(&mut self, name: &str) -> bool {
+        self.exercises.insert(name.to_string())
+    }
+
+    /// Records that a hint for an exercise was shown, and returns how many
+    /// have been shown for it in all.
+    pub fn record_hint(&mut self, exercise: &str) -> u32 {
+        let taken = self.hints.entry(exercise.to_string()).or_insert(0);
+        *taken += 1;
+        *taken
+    }
+
+    /// Reschedules a flashcard after the learner rated it on `today`, and
+    /// returns its new state.
+    pub fn record_card(&mut self, topic: &str, term: &str, rating: Rating, today: NaiveDate) -> CardState {
+        let deck = self.cards.entry(topic.to_string()).or_default();
+        let state = flashcards::schedule(deck.get(term).copied(), rating, today);
+        deck.insert(term.to_string(), state);
+        state
+    }
+
+    pub fn has_run(&self, example: &str) -> bool {
+        self.examples.contains(example)
+    }
+
+    pub fn quiz(&self, topic: &str) -> Option<&QuizRecord> {
+        self.quizzes.get(topic)
+    }
+
+    pub fn has_solved(&self, exercise: &str) -> bool {
+        self.exercises.contains(exercise)
+    }
+
+    pub fn hints_taken(&self, exercise: &str) -> u32 {
+        self.hints.get(exercise).copied().unwrap_or(0)
+    }
+
+    /// Where a flashcard is in its schedule; `None` if it was never rated.
+    pub fn card_state(&self, topic: &str, term: &str) -> Option<CardState> {
+        self.cards.get(topic)?.get(term).copied()
+    }
+
+    /// Forgets everything.
+    pub fn reset(&mut self) {
+        *self = Progress::new();
+    }
+
+    /// Counts against what there is to do.
+    pub fn summary(&self) -> Summary {
+        Summary {
+            examples_run: self.examples.len(),
+            examples_total: catalog::examples().len(),
+            quizzes_passed: self.quizzes.values().filter(|q| q.passed).count(),
+            quizzes_total: quiz::topics().len(),
+            exercises_solved: self.exercises.len(),
+            hints_taken: self.hints.values().sum(),
+        }
+    }
+}
+
+/// Completion counts, shown by `rustler progress`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Summary {
+    pub examples_run: usize,
+    pub examples_total: usize,
+    pub quizzes_passed: usize,
+    pub quizzes_total: usize,
+    pub exercises_solved: usize,
+    pub hints_taken: u32,
+}
+
+impl Summary {
+    /// Examples and quizzes done, in percent, rounded down.
+    pub fn percent(&self) -> usize {
+        let total = self.examples_total + self.quizzes_total;
+        ((self.examples_run + self.quizzes_passed) * 100).checked_div(total).unwrap_or(0)
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Examples run:     {}/{}", self.examples_run, self.examples_total)?;
+        writeln!(f, "Quizzes passed:   {}/{}", self.quizzes_passed, self.quizzes_total)?;
+        writeln!(f, "Exercises solved: {}", self.exercises_solved)?;
+        writeln!(f, "Hints taken:      {}", self.hints_taken)?;
+        write!(f, "Overall:          {}%", self.percent())
+    }
+}
+
+/// Errors from saving or loading [`Progress`].
+#[derive(Debug)]
+pub enum ProgressError {
+    Io(io::Error),
+    /// The file is not valid JSON or is missing fields.
+    Format(serde_json::Error),
+    /// The file holds a format version newer than this build knows.
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for ProgressError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProgressError::Io(e) => write!(f, "I/O error: {}", e),
+            ProgressError::Format(e) => write!(f, "malformed progress file: {}", e),
+            ProgressError::UnsupportedVersion(version) => write!(
+                f,
+                "progress file version {} is not supported (expected 1 to {})",
+                version, SAVE_VERSION
+            ),
+        }
+    }
+}
+
+impl Error for ProgressError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ProgressError::Io(e) => Some(e),
+            ProgressError::Format(e) => Some(e),
+            ProgressError::UnsupportedVersion(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for ProgressError {
+    fn from(e: io::Error) -> Self {
+        ProgressError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for ProgressError {
+    fn from(e: serde_json::Error) -> Self {
+        ProgressError::Format(e)
+    }
+}
+
+impl From<VersionError> for ProgressError {
+    fn from(e: VersionError) -> Self {
+        match e {
+            VersionError::Format(e) => ProgressError::Format(e),
+            VersionError::Unsupported(version) => ProgressError::UnsupportedVersion(version),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::TempDir;
+
+    fn score(correct: u32, total: u32) -> Score {
+        let mut score = Score::new();
+        for i in 0..total {
+            score.record(i < correct);
+        }
+        score
+    }
+
+    #[test]
+    fn test_quiz_keeps_the_best_attempt() {
+        let mut progress = Progress::new();
+        progress.record_quiz("03_control_flow", score(3, 3));
+        progress.record_quiz("03_control_flow", score(1, 3));
+        assert_eq!(
+            progress.quiz("03_control_flow"),
+            Some(&QuizRecord {
+                best: 100,
+                attempts: 2,
+                passed: true
+            })
+        );
+        progress.record_quiz("04_functions", score(1, 3));
+        assert!(!progress.quiz("04_functions").unwrap().passed);
+        assert_eq!(progress.summary().quizzes_passed, 1);
+    }
+
+    #[test]
+    fn test_marks_and_summary() {
+        let mut progress = Progress::new();
+        assert!(progress.mark_example("01_hello_world"));
+        assert!(!progress.mark_example("01_hello_world"));
+        assert!(progress.mark_exercise("variables1"));
+        assert!(progress.has_run("01_hello_world"));
+        assert!(progress.has_solved("variables1"));
+        assert_eq!(progress.hints_taken("structs1"), 0);
+        assert_eq!(progress.record_hint("structs1"), 1);
+        assert_eq!(progress.record_hint("structs1"), 2);
+        progress.record_hint("errors1");
+
+        let summary = progress.summary();
+        assert_eq!(summary.examples_run, 1);
+        assert_eq!(summary.exercises_solved, 1);
+        assert_eq!(summary.hints_taken, 3);
+        assert_eq!(summary.examples_total, catalog::examples().len());
+        assert_eq!(summary.percent(), 100 / (summary.examples_total + summary.quizzes_total));
+        assert!(summary.to_string().starts_with("Examples run:     1/"));
+
+        progress.reset();
+        assert_eq!(progress, Progress::new());
+    }
+
+    #[test]
+    fn test_cards_are_rescheduled() {
+        let mut progress = Progress::new();
+        let today = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+        assert_eq!(progress.card_state("13_concurrency", "Arc<T>"), None);
+        let state = progress.record_card("13_concurrency", "Arc<T>", Rating::Easy, today);
+        assert_eq!(state.bucket, 2);
+        let state = progress.record_card("13_concurrency", "Arc<T>", Rating::Good, state.due);
+        assert_eq!(progress.card_state("13_concurrency", "Arc<T>"), Some(state));
+        assert_eq!(state.bucket, 3);
+        assert_eq!(progress.card_state("12_testing", "Arc<T>"), None);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = TempDir::new("progress").unwrap();
+        let path = dir.join("rustler").join("progress.json");
+        assert_eq!(Progress::load(&path).unwrap(), Progress::new());
+
+        let mut progress = Progress::new();
+        progress.mark_example("02_variables_and_types");
+        progress.record_quiz("02_variables_and_types", score(2, 3));
+        progress.record_hint("ownership1");
+        let today = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+        progress.record_card("05_ownership_borrowing", "Move", Rating::Good, today);
+        progress.save(&path).unwrap();
+        assert_eq!(Progress::load(&path).unwrap(), progress);
+
+        fs::write(&path, r#"{"version": 2}"#).unwrap();
+        assert_eq!(
+            Progress::load(&path).unwrap_err().to_string(),
+            "progress file version 2 is not supported (expected 1 to 1)"
+        );
+        // Sections missing from older files default to empty
+        fs::write(&path, r#"{"version": 1, "examples": ["01_hello_world"]}"#).unwrap();
+        assert!(Progress::load(&path).unwrap().has_run("01_hello_world"));
+    }
+}
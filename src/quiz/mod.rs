@@ -0,0 +1,274 @@
+//! Short quizzes on each example's topic, for checking comprehension.
+//!
+//! The questions live in `questions.json` next to this file, keyed by
+//! example name, and are compiled into the library. A question is either
+//! multiple choice:
+//!
+//! ```text
+//! {"prompt": "...", "choices": ["a", "b", "c"], "answer": 1, "explanation": "..."}
+//! ```
+//!
+//! or fill-in, with every accepted answer listed:
+//!
+//! ```text
+//! {"prompt": "...", "answers": ["FnOnce"], "explanation": "..."}
+//! ```
+//!
+//! A [`Score`] tallies the answers; `rustler quiz <topic>` asks the
+//! questions one at a time.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::OnceLock;
+
+use serde::Deserialize;
+
+use crate::catalog::{self, Example, FindError};
+
+const QUESTIONS: &str = include_str!("questions.json");
+
+/// The share of correct answers, in percent, needed to pass.
+pub const PASS_MARK: u32 = 70;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Question {
+    pub prompt: String,
+    #[serde(flatten)]
+    pub kind: Kind,
+    /// Shown after answering, right or wrong.
+    pub explanation: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum Kind {
+    /// `answer` is the index of the right choice.
+    Choice { choices: Vec<String>, answer: usize },
+    FillIn { answers: Vec<String> },
+}
+
+impl Question {
+    /// Whether `response` is right.
+    ///
+    /// A multiple-choice response may be the choice's letter (`b`), its
+    /// number counting from 1 (`2`) or its text. Fill-in responses are
+    /// compared ignoring case, surrounding backticks and repeated spaces.
+    ///
+    /// ```
+    /// let question = &rustler::quiz::questions("05_ownership_borrowing").unwrap()[0];
+    /// assert!(question.check("b"));
+    /// assert!(question.check("2"));
+    /// assert!(!question.check("a"));
+    /// ```
+    pub fn check(&self, response: &str) -> bool {
+        let response = normalize(response);
+        match &self.kind {
+            Kind::Choice { choices, answer } => {
+                let letter = (b'a' + *answer as u8) as char;
+                response == letter.to_string()
+                    || response == (answer + 1).to_string()
+                    || choices.get(*answer).is_some_and(|choice| normalize(choice) == response)
+            }
+            Kind::FillIn { answers } => answers.iter().any(|answer| normalize(answer) == response),
+        }
+    }
+
+    /// The right answer as it should be shown to the learner.
+    pub fn solution(&self) -> String {
+        match &self.kind {
+            Kind::Choice { choices, answer } => {
+                format!("{}) {}", (b'a' + *answer as u8) as char, choices[*answer])
+            }
+            Kind::FillIn { answers } => answers[0].clone(),
+        }
+    }
+}
+
+impl fmt::Display for Question {
+    /// The prompt, followed by lettered choices for multiple choice.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.prompt)?;
+        if let Kind::Choice { choices, .. } = &self.kind {
+            for (letter, choice) in (b'a'..).zip(choices) {
+                write!(f, "\n  {}) {}", letter as char, choice)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn normalize(text: &str) -> String {
+    let text = text.trim().trim_matches('`');
+    text.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+fn banks() -> &'static BTreeMap<String, Vec<Question>> {
+    static BANKS: OnceLock<BTreeMap<String, Vec<Question>>> = OnceLock::new();
+    // The file is compiled in and checked by the tests, so a parse error
+    // here is a bug in the build rather than bad user input
+    BANKS.get_or_init(|| serde_json::from_str(QUESTIONS).expect("questions.json is valid"))
+}
+
+/// Checks a questions file against the examples it should cover: every
+/// example needs at least three questions, each answerable and explained.
+/// Used on the real file and on checkouts made by [`crate::scaffold`].
+#[cfg(test)]
+pub(crate) fn assert_valid_banks(examples: &[&str], questions: &str) {
+    let banks: BTreeMap<String, Vec<Question>> = serde_json::from_str(questions).expect("questions.json is valid");
+    assert_eq!(banks.keys().map(String::as_str).collect::<Vec<_>>(), examples);
+    for (topic, questions) in &banks {
+        assert!(questions.len() >= 3, "{}", topic);
+        for question in questions {
+            match &question.kind {
+                Kind::Choice { choices, answer } => assert!(*answer < choices.len(), "{}", question.prompt),
+                Kind::FillIn { answers } => assert!(!answers.is_empty(), "{}", question.prompt),
+            }
+            assert!(!question.explanation.is_empty(), "{}", question.prompt);
+        }
+    }
+}
+
+/// The example names that have a quiz, in order.
+pub fn topics() -> Vec<&'static str> {
+    banks().keys().map(String::as_str).collect()
+}
+
+/// The questions for the example called `topic`.
+pub fn questions(topic: &str) -> Option<&'static [Question]> {
+    banks().get(topic).map(Vec::as_slice)
+}
+
+/// The quiz for the example the user means, looked up the way the
+/// example browser does: by number, name or fuzzy match.
+pub fn find(query: &str) -> Result<(Example, &'static [Question]), FindError> {
+    let example = catalog::find(query)?;
+    let questions = questions(example.name).ok_or_else(|| FindError::NotFound(query.to_string()))?;
+    Ok((example, questions))
+}
+
+/// Running totals for one quiz.
+///
+/// ```
+/// use rustler::quiz::Score;
+///
+/// let mut score = Score::new();
+/// for correct in [true, true, false, true] {
+///     score.record(correct);
+/// }
+/// assert_eq!(score.to_string(), "3/4 (75%)");
+/// assert!(score.passed());
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Score {
+    correct: u32,
+    total: u32,
+}
+
+impl Score {
+    pub fn new() -> Self {
+        Score::default()
+    }
+
+    pub fn record(&mut self, correct: bool) {
+        self.total += 1;
+        if correct {
+            self.correct += 1;
+        }
+    }
+
+    pub fn correct(&self) -> u32 {
+        self.correct
+    }
+
+    pub fn total(&self) -> u32 {
+        self.total
+    }
+
+    /// Rounded down; 0 before any answers.
+    pub fn percent(&self) -> u32 {
+        (self.correct * 100).checked_div(self.total).unwrap_or(0)
+    }
+
+    /// Whether the score reaches [`PASS_MARK`].
+    pub fn passed(&self) -> bool {
+        self.total > 0 && self.percent() >= PASS_MARK
+    }
+}
+
+impl fmt::Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{} ({}%)", self.correct, self.total, self.percent())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn choice() -> Question {
+        Question {
+            prompt: "Which type shares ownership between threads?".to_string(),
+            kind: Kind::Choice {
+                choices: vec!["Rc".to_string(), "Arc".to_string()],
+                answer: 1,
+            },
+            explanation: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_every_example_has_a_valid_quiz() {
+        let names: Vec<&str> = catalog::examples().iter().map(|e| e.name).collect();
+        assert_eq!(topics(), names);
+        assert_valid_banks(&names, QUESTIONS);
+    }
+
+    #[test]
+    fn test_multiple_choice_accepts_letter_number_or_text() {
+        let question = choice();
+        for response in ["b", "B", " 2 ", "arc", "Arc"] {
+            assert!(question.check(response), "{}", response);
+        }
+        for response in ["a", "1", "Rc", "", "c"] {
+            assert!(!question.check(response), "{}", response);
+        }
+        assert_eq!(question.solution(), "b) Arc");
+        assert_eq!(question.to_string(), "Which type shares ownership between threads?\n  a) Rc\n  b) Arc");
+    }
+
+    #[test]
+    fn test_fill_in_is_lenient_about_formatting() {
+        let question = Question {
+            prompt: "The string slice type?".to_string(),
+            kind: Kind::FillIn {
+                answers: vec!["&str".to_string(), "& str".to_string()],
+            },
+            explanation: String::new(),
+        };
+        assert!(question.check("`&str`"));
+        assert!(question.check("&   str"));
+        assert!(!question.check("String"));
+        assert_eq!(question.solution(), "&str");
+    }
+
+    #[test]
+    fn test_find_by_example() {
+        let (example, questions) = find("ownership").unwrap();
+        assert_eq!(example.name, "05_ownership_borrowing");
+        assert_eq!(questions.len(), 3);
+        assert!(matches!(find("xyzzy"), Err(FindError::NotFound(_))));
+    }
+
+    #[test]
+    fn test_score() {
+        let mut score = Score::new();
+        assert_eq!(score.percent(), 0);
+        assert!(!score.passed());
+        score.record(true);
+        score.record(false);
+        score.record(false);
+        assert_eq!((score.correct(), score.total()), (1, 3));
+        assert_eq!(score.to_string(), "1/3 (33%)");
+        assert!(!score.passed());
+    }
+}
@@ -0,0 +1,400 @@
+//! Templates for new examples and exercises, used by `rustler new`.
+//!
+//! Each function writes the new files into a checkout and appends an entry
+//! to the matching index, so the browser and the exercise runner list it
+//! after the next build. Existing files are never overwritten.
+//!
+//! The templates compile and pass their own tests as generated, and the
+//! data files the tests check, such as the quiz questions and exercise
+//! hints, get placeholder entries, so the crate stays green while the
+//! placeholders are filled in. What is left to write by hand is listed in
+//! [`Created::next_steps`].
+
+use std::error::Error;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::catalog::Example;
+
+/// What a scaffolding command wrote.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Created {
+    /// The new files, relative to the checkout.
+    pub files: Vec<PathBuf>,
+    /// The index or data files an entry was added to.
+    pub updated: Vec<PathBuf>,
+    /// What is left for the contributor to do by hand.
+    pub next_steps: Vec<String>,
+}
+
+/// Adds `examples/NN_<name>.rs` under `root`, numbered after the last
+/// example in `examples/index.txt`, lists it there, and gives it three
+/// placeholder questions in `src/quiz/questions.json`.
+///
+/// `name` is lowercase words joined by `_`; the title is made from it, so
+/// `smart_pointers` becomes "Smart Pointers".
+pub fn new_example(root: &Path, name: &str) -> Result<Created, ScaffoldError> {
+    check_name(name)?;
+    let index_path = Path::new("examples").join("index.txt");
+    let index = fs::read_to_string(root.join(&index_path))?;
+    let names: Vec<&str> = index_names(&index).collect();
+    if names.iter().any(|existing| existing.split_once('_').map(|(_, rest)| rest) == Some(name)) {
+        return Err(ScaffoldError::Exists(name.to_string()));
+    }
+    let number = names
+        .iter()
+        .filter_map(|existing| existing.split('_').next()?.parse::<u32>().ok())
+        .max()
+        .unwrap_or(0)
+        + 1;
+    let file = format!("{:02}_{}", number, name);
+    let title = title_case(name);
+    let questions_path = Path::new("src").join("quiz").join("questions.json");
+    let questions = fs::read_to_string(root.join(&questions_path))?;
+    let questions = add_questions(&questions, &file, &title).ok_or_else(|| not_an_object(&questions_path))?;
+
+    let path = Path::new("examples").join(format!("{}.rs", file));
+    create_file(root, &path, &example_template(&file, &title))?;
+    append_line(root, &index_path, &format!("{} | {} | TODO: one-line summary", file, title))?;
+    // Every example needs a quiz, or the quiz tests fail
+    fs::write(root.join(&questions_path), questions)?;
+
+    Ok(Created {
+        files: vec![path.clone()],
+        next_steps: vec![
+            format!("write the example in {}", path.display()),
+            format!("replace the TODO summary in {}", index_path.display()),
+            format!("replace the TODO questions for {} in {}", file, questions_path.display()),
+            "describe it in README.md".to_string(),
+        ],
+        updated: vec![index_path, questions_path],
+    })
+}
+
+/// Adds an exercise called `name` that practises `topic`: the learner's
+/// file, its hidden tests and a solution under `exercises/`, an entry in
+/// `exercises/index.txt` and placeholder hints in `exercises/hints.json`.
+pub fn new_exercise(root: &Path, name: &str, topic: &Example) -> Result<Created, ScaffoldError> {
+    check_name(name)?;
+    let index_path = Path::new("exercises").join("index.txt");
+    let hints_path = Path::new("exercises").join("hints.json");
+    let index = fs::read_to_string(root.join(&index_path))?;
+    if index_names(&index).any(|existing| existing == name) {
+        return Err(ScaffoldError::Exists(name.to_string()));
+    }
+    let hints = fs::read_to_string(root.join(&hints_path))?;
+    let hints = add_hints(&hints, name).ok_or_else(|| not_an_object(&hints_path))?;
+
+    let file_name = format!("{}.rs", name);
+    let stub = exercise_template(name, topic.name);
+    let paths = [
+        Path::new("exercises").join(&file_name),
+        Path::new("exercises").join("tests").join(&file_name),
+        Path::new("exercises").join("solutions").join(&file_name),
+    ];
+    create_file(root, &paths[0], &stub)?;
+    create_file(root, &paths[1], "#[test]\nfn answers() {\n    assert_eq!(answer(), 42);\n}\n")?;
+    create_file(root, &paths[2], &stub.replace("    todo!()", "    42"))?;
+    append_line(root, &index_path, &format!("{} | {} | TODO: one-line summary", name, topic.name))?;
+    fs::write(root.join(&hints_path), hints)?;
+
+    Ok(Created {
+        next_steps: vec![
+            format!("write the exercise in {}, with a todo!() for each gap", paths[0].display()),
+            format!("test it in {} and solve it in {}", paths[1].display(), paths[2].display()),
+            format!("replace the TODO summary in {}", index_path.display()),
+            format!("replace the TODO hints in {}", hints_path.display()),
+        ],
+        files: paths.to_vec(),
+        updated: vec![index_path, hints_path],
+    })
+}
+
+/// Lowercase ASCII letters, digits and `_`, starting with a letter, so the
+/// name works as a file name, an identifier and a `cargo run --example`
+/// argument.
+fn check_name(name: &str) -> Result<(), ScaffoldError> {
+    let valid = name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(ScaffoldError::InvalidName(name.to_string()))
+    }
+}
+
+/// The first field of each entry in an index file.
+fn index_names(index: &str) -> impl Iterator<Item = &str> {
+    index
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| line.split('|').next().unwrap_or_default().trim())
+}
+
+fn title_case(name: &str) -> String {
+    name.split('_')
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map_or(String::new(), |first| first.to_ascii_uppercase().to_string() + chars.as_str())
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn example_template(file: &str, title: &str) -> String {
+    format!(
+        "// {title} Example
+// TODO: say what this example demonstrates
+//
+// To run this example: cargo run --example {file}
+
+fn main() {{
+    println!(\"=== {title} ===\\n\");
+    println!(\"{{}}\", greeting());
+}}
+
+// TODO: replace with the code this example demonstrates
+fn greeting() -> &'static str {{
+    \"Hello from {file}!\"
+}}
+
+#[cfg(test)]
+mod tests {{
+    use super::*;
+
+    #[test]
+    fn test_greeting() {{
+        assert!(greeting().starts_with(\"Hello\"));
+    }}
+}}
+"
+    )
+}
+
+fn exercise_template(name: &str, topic: &str) -> String {
+    format!(
+        "// Exercise: {name}
+// Practises: {topic}
+//
+// Replace each todo!() so the hidden tests pass, then check your work with
+//   cargo run --bin rustler -- exercise {name}
+// Stuck? `cargo run --bin rustler -- hint {name}` gives a hint; run it
+// again for the next one, and for the solution after the last.
+
+/// TODO: describe what to write. For now the answer is 42.
+pub fn answer() -> u32 {{
+    todo!()
+}}
+"
+    )
+}
+
+/// `hints` with two placeholder hints for `name`. `None` if `hints` is not
+/// a JSON object.
+fn add_hints(hints: &str, name: &str) -> Option<String> {
+    let items = [
+        quote("TODO: a nudge in the right direction"),
+        quote("TODO: a more direct hint, short of the answer"),
+    ];
+    add_entry(hints, name, &items)
+}
+
+/// `questions` with three placeholder questions for the example `file`,
+/// valid enough for the quiz tests. `None` if `questions` is not a JSON
+/// object.
+fn add_questions(questions: &str, file: &str, title: &str) -> Option<String> {
+    let items: Vec<String> = (1..=3)
+        .map(|n| {
+            format!(
+                "{{\n      \"prompt\": {},\n      \"choices\": [{}, {}],\n      \"answer\": 0,\n      \"explanation\": {}\n    }}",
+                quote(&format!("TODO: question {} about {}", n, title)),
+                quote("TODO: the right answer"),
+                quote("TODO: a wrong answer"),
+                quote("TODO: why the right answer is right")
+            )
+        })
+        .collect();
+    add_entry(questions, file, &items)
+}
+
+/// `json` with an array of `items`, already written out as JSON, added
+/// under `name` before the closing brace, keeping the rest of the file as
+/// it was. `None` if `json` is not a JSON object.
+fn add_entry(json: &str, name: &str, items: &[String]) -> Option<String> {
+    serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(json).ok()?;
+    let body = json[..json.rfind('}')?].trim_end();
+    let separator = if body.ends_with('{') { "" } else { "," };
+    Some(format!("{}{}\n  {}: [\n    {}\n  ]\n}}\n", body, separator, quote(name), items.join(",\n    ")))
+}
+
+fn quote(text: &str) -> String {
+    serde_json::to_string(text).unwrap_or_default()
+}
+
+fn not_an_object(path: &Path) -> ScaffoldError {
+    ScaffoldError::Io(io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("{} is not a JSON object", path.display()),
+    ))
+}
+
+/// Writes a new file, creating its directory, and fails if it exists.
+fn create_file(root: &Path, path: &Path, contents: &str) -> Result<(), ScaffoldError> {
+    let full = root.join(path);
+    if let Some(parent) = full.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().write(true).create_new(true).open(&full).map_err(|e| {
+        if e.kind() == io::ErrorKind::AlreadyExists {
+            ScaffoldError::Exists(path.display().to_string())
+        } else {
+            ScaffoldError::Io(e)
+        }
+    })?;
+    file.write_all(contents.as_bytes())?;
+    Ok(())
+}
+
+/// Appends `line` to an index file, first ending its last line if needed.
+fn append_line(root: &Path, path: &Path, line: &str) -> io::Result<()> {
+    let full = root.join(path);
+    let ends_with_newline = fs::read(&full)?.last().is_none_or(|&b| b == b'\n');
+    let mut file = OpenOptions::new().append(true).open(&full)?;
+    if !ends_with_newline {
+        writeln!(file)?;
+    }
+    writeln!(file, "{}", line)
+}
+
+/// Errors from [`new_example`] and [`new_exercise`].
+#[derive(Debug)]
+pub enum ScaffoldError {
+    Io(io::Error),
+    /// The name is not lowercase words, digits and `_`.
+    InvalidName(String),
+    /// An example, exercise or file by that name is already there.
+    Exists(String),
+}
+
+impl fmt::Display for ScaffoldError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ScaffoldError::Io(e) => write!(f, "I/O error: {}", e),
+            ScaffoldError::InvalidName(name) => write!(
+                f,
+                "`{}` is not a valid name: use lowercase letters, digits and _, starting with a letter",
+                name
+            ),
+            ScaffoldError::Exists(name) => write!(f, "`{}` already exists", name),
+        }
+    }
+}
+
+impl Error for ScaffoldError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ScaffoldError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ScaffoldError {
+    fn from(e: io::Error) -> Self {
+        ScaffoldError::Io(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog;
+    use crate::exercises::{grade_source, Outcome};
+    use crate::io::TempDir;
+
+    /// A checkout with copies of the real index files.
+    fn checkout() -> TempDir {
+        let dir = TempDir::new("scaffold").unwrap();
+        let real = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let files = [
+            "examples/index.txt",
+            "exercises/index.txt",
+            "exercises/hints.json",
+            "src/quiz/questions.json",
+            "src/flashcards/cards.json",
+        ];
+        for path in files {
+            fs::create_dir_all(dir.join(path).parent().unwrap()).unwrap();
+            fs::copy(real.join(path), dir.join(path)).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_new_example_is_numbered_and_indexed() {
+        let dir = checkout();
+        let next = catalog::examples().len() + 1;
+        let created = new_example(dir.path(), "smart_pointers").unwrap();
+        let file = format!("{:02}_smart_pointers", next);
+        assert_eq!(created.files, vec![Path::new("examples").join(format!("{}.rs", file))]);
+
+        let source = fs::read_to_string(dir.join(&created.files[0])).unwrap();
+        assert!(source.starts_with("// Smart Pointers Example\n"), "{}", source);
+        assert!(source.contains(&format!("cargo run --example {}\n", file)));
+        let index = fs::read_to_string(dir.join("examples/index.txt")).unwrap();
+        assert!(index.ends_with(&format!("\n{} | Smart Pointers | TODO: one-line summary\n", file)));
+        assert_eq!(created.updated, vec![Path::new("examples/index.txt"), Path::new("src/quiz/questions.json")]);
+
+        assert!(matches!(new_example(dir.path(), "smart_pointers"), Err(ScaffoldError::Exists(_))));
+        assert!(matches!(new_example(dir.path(), "Smart-Pointers"), Err(ScaffoldError::InvalidName(_))));
+        assert!(matches!(new_example(dir.path(), "17_x"), Err(ScaffoldError::InvalidName(_))));
+    }
+
+    #[test]
+    fn test_new_exercise_is_gradable() {
+        let dir = checkout();
+        let topic = catalog::find("functions").unwrap();
+        let created = new_exercise(dir.path(), "closures1", &topic).unwrap();
+        assert_eq!(created.files.len(), 3);
+
+        let read = |path: &Path| fs::read_to_string(dir.join(path)).unwrap();
+        let (stub, tests, solution) = (read(&created.files[0]), read(&created.files[1]), read(&created.files[2]));
+        assert!(matches!(grade_source("closures1", &stub, &tests).unwrap(), Outcome::Failed { .. }));
+        assert!(grade_source("closures1", &solution, &tests).unwrap().is_pass());
+
+        let index = read(Path::new("exercises/index.txt"));
+        assert!(index.ends_with(&format!("closures1 | {} | TODO: one-line summary\n", topic.name)));
+        let hints: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_str(&read(Path::new("exercises/hints.json"))).unwrap();
+        assert_eq!(hints["closures1"].as_array().unwrap().len(), 2);
+        assert!(hints.contains_key("variables1"));
+
+        assert!(matches!(new_exercise(dir.path(), "errors1", &topic), Err(ScaffoldError::Exists(_))));
+    }
+
+    #[test]
+    fn test_new_example_keeps_the_quiz_and_cards_valid() {
+        let dir = checkout();
+        new_example(dir.path(), "smart_pointers").unwrap();
+        let read = |path: &str| fs::read_to_string(dir.join(path)).unwrap();
+        let index = read("examples/index.txt");
+        let names: Vec<&str> = index_names(&index).collect();
+        // The same checks the quiz and flashcard tests make on the real files
+        crate::quiz::assert_valid_banks(&names, &read("src/quiz/questions.json"));
+        crate::flashcards::assert_valid_decks(&names, &read("src/flashcards/cards.json"));
+    }
+
+    #[test]
+    fn test_add_hints() {
+        assert_eq!(
+            add_hints("{}\n", "a").unwrap(),
+            "{\n  \"a\": [\n    \"TODO: a nudge in the right direction\",\n    \"TODO: a more direct hint, short of the answer\"\n  ]\n}\n"
+        );
+        assert_eq!(add_hints("[]", "a"), None);
+        assert_eq!(title_case("game_of_life"), "Game Of Life");
+    }
+}
@@ -0,0 +1,239 @@
+//! ASCII-art rendering of shapes for the terminal.
+//!
+//! A [`Canvas`] is a grid of characters where each cell covers a 1x1 square
+//! of the plane. The bottom-left cell covers `(0, 0)` to `(1, 1)`, and y
+//! grows upwards as it does in the rest of the geometry module.
+
+use super::{Point2D, Segment, Shape};
+
+/// A character grid that shapes and segments are drawn onto.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Canvas {
+    width: usize,
+    height: usize,
+    cells: Vec<char>,
+    brush: char,
+}
+
+impl Canvas {
+    /// A blank canvas `width` cells wide and `height` cells tall.
+    pub fn new(width: usize, height: usize) -> Self {
+        Canvas {
+            width,
+            height,
+            cells: vec![' '; width * height],
+            brush: '#',
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Sets the character used by later draw calls (`#` by default).
+    pub fn set_brush(&mut self, brush: char) {
+        self.brush = brush;
+    }
+
+    /// The character in a cell, with row 0 at the top.
+    pub fn get(&self, col: usize, row: usize) -> Option<char> {
+        (col < self.width && row < self.height).then(|| self.cells[row * self.width + col])
+    }
+
+    /// Fills every cell whose center lies inside `shape`.
+    pub fn draw(&mut self, shape: &dyn Shape) {
+        let bounds = shape.bounds();
+        let cols = self.span(bounds.min.x, bounds.max.x, self.width);
+        let rows = self.span(bounds.min.y, bounds.max.y, self.height);
+        for y in rows {
+            for x in cols.clone() {
+                let center = Point2D::new(x as f64 + 0.5, y as f64 + 0.5);
+                if shape.contains_point(&center) {
+                    self.plot(x as i64, y as i64);
+                }
+            }
+        }
+    }
+
+    /// Draws a line using Bresenham's algorithm.
+    ///
+    /// Parts of the line that fall outside the canvas are clipped before
+    /// it is stepped through, so far-away endpoints cost no more than near
+    /// ones. Segments with non-finite coordinates are not drawn.
+    pub fn draw_segment(&mut self, segment: &Segment) {
+        let Some((start, end)) = self.clip(segment) else {
+            return;
+        };
+        let (mut x, mut y) = (start.x.floor() as i64, start.y.floor() as i64);
+        let (x1, y1) = (end.x.floor() as i64, end.y.floor() as i64);
+        let dx = (x1 - x).abs();
+        let dy = -(y1 - y).abs();
+        let step_x = if x < x1 { 1 } else { -1 };
+        let step_y = if y < y1 { 1 } else { -1 };
+        let mut error = dx + dy;
+        loop {
+            self.plot(x, y);
+            if x == x1 && y == y1 {
+                break;
+            }
+            let doubled = 2 * error;
+            if doubled >= dy {
+                error += dy;
+                x += step_x;
+            }
+            if doubled <= dx {
+                error += dx;
+                y += step_y;
+            }
+        }
+    }
+
+    /// Clears every cell back to a space.
+    pub fn clear(&mut self) {
+        self.cells.fill(' ');
+    }
+
+    /// The canvas as text, one line per row with the top row first.
+    pub fn render(&self) -> String {
+        let mut out = String::with_capacity((self.width + 1) * self.height);
+        for row in self.cells.chunks(self.width.max(1)).take(self.height) {
+            out.extend(row);
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Cell indices along one axis overlapping `min..=max`, clamped to the canvas.
+    fn span(&self, min: f64, max: f64, size: usize) -> std::ops::Range<usize> {
+        let start = min.floor().max(0.0) as usize;
+        let end = (max.ceil().max(0.0) as usize).min(size);
+        start.min(end)..end
+    }
+
+    /// The part of `segment` inside the canvas, by Liang–Barsky: each edge
+    /// of the canvas narrows the range of `t` in `start + t * (end - start)`
+    /// that stays on its side, and nothing is left if the range empties.
+    /// A clipped end is worked out from the edge that cut it rather than
+    /// from `t`, which is too coarse to tell cells apart for far-away ends.
+    fn clip(&self, segment: &Segment) -> Option<(Point2D, Point2D)> {
+        let (start, end) = (segment.start, segment.end);
+        if ![start.x, start.y, end.x, end.y].iter().all(|v| v.is_finite()) {
+            return None;
+        }
+        let (dx, dy) = (end.x - start.x, end.y - start.y);
+        let on_x = |x: f64| Point2D::new(x, start.y + (x - start.x) * dy / dx);
+        let on_y = |y: f64| Point2D::new(start.x + (y - start.y) * dx / dy, y);
+        let (width, height) = (self.width as f64, self.height as f64);
+        let edges = [
+            (-dx, start.x, on_x(0.0)),
+            (dx, width - start.x, on_x(width)),
+            (-dy, start.y, on_y(0.0)),
+            (dy, height - start.y, on_y(height)),
+        ];
+        let (mut t0, mut t1) = (0.0_f64, 1.0_f64);
+        let (mut enter, mut exit) = (start, end);
+        for (p, q, cut) in edges {
+            if p == 0.0 {
+                // Parallel to this edge, so either wholly inside it or not
+                if q < 0.0 {
+                    return None;
+                }
+                continue;
+            }
+            let t = q / p;
+            if p < 0.0 && t > t0 {
+                (t0, enter) = (t, cut);
+            } else if p > 0.0 && t < t1 {
+                (t1, exit) = (t, cut);
+            }
+            if t0 > t1 {
+                return None;
+            }
+        }
+        Some((enter, exit))
+    }
+
+    /// Sets the cell containing plane coordinates `(x, y)`, ignoring
+    /// anything off the canvas.
+    fn plot(&mut self, x: i64, y: i64) {
+        if x < 0 || y < 0 || x >= self.width as i64 || y >= self.height as i64 {
+            return;
+        }
+        let row = self.height - 1 - y as usize;
+        self.cells[row * self.width + x as usize] = self.brush;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shapes::{Circle, Rectangle};
+
+    #[test]
+    fn test_blank_canvas() {
+        let canvas = Canvas::new(3, 2);
+        assert_eq!(canvas.render(), "   \n   \n");
+        assert_eq!(canvas.get(3, 0), None);
+    }
+
+    #[test]
+    fn test_draw_rectangle() {
+        let mut canvas = Canvas::new(5, 4);
+        canvas.draw(&Rectangle::at(Point2D::new(1.0, 1.0), 3.0, 2.0));
+        assert_eq!(canvas.render(), "     \n ### \n ### \n     \n");
+    }
+
+    #[test]
+    fn test_draw_circle() {
+        let mut canvas = Canvas::new(7, 7);
+        canvas.draw(&Circle::at(Point2D::new(3.5, 3.5), 2.5));
+        let expected = [
+            "       ",
+            "  ###  ",
+            " ##### ",
+            " ##### ",
+            " ##### ",
+            "  ###  ",
+            "       ",
+        ];
+        assert_eq!(canvas.render(), expected.join("\n") + "\n");
+    }
+
+    #[test]
+    fn test_draw_segment_and_brush() {
+        let mut canvas = Canvas::new(4, 4);
+        canvas.set_brush('*');
+        canvas.draw_segment(&Segment::new(Point2D::new(0.0, 0.0), Point2D::new(3.5, 3.5)));
+        assert_eq!(canvas.render(), "   *\n  * \n *  \n*   \n");
+    }
+
+    #[test]
+    fn test_clipping() {
+        let mut canvas = Canvas::new(3, 3);
+        canvas.draw(&Circle::at(Point2D::new(-10.0, -10.0), 2.0));
+        canvas.draw_segment(&Segment::new(Point2D::new(-5.0, 1.0), Point2D::new(10.0, 1.0)));
+        assert_eq!(canvas.render(), "   \n###\n   \n");
+        canvas.clear();
+        assert_eq!(canvas.render(), "   \n   \n   \n");
+    }
+
+    #[test]
+    fn test_far_away_endpoints_are_clipped_first() {
+        let mut canvas = Canvas::new(3, 3);
+        // Stepping through every cell of these would take hours
+        canvas.draw_segment(&Segment::new(Point2D::new(-1e15, -1e15), Point2D::new(1e15, 1e15)));
+        assert_eq!(canvas.render(), "  #\n # \n#  \n");
+        canvas.clear();
+        canvas.draw_segment(&Segment::new(Point2D::new(1e18, 2.5), Point2D::new(-1e18, 2.5)));
+        assert_eq!(canvas.render(), "###\n   \n   \n");
+        canvas.clear();
+        // Wholly outside, and not a number at all
+        canvas.draw_segment(&Segment::new(Point2D::new(-1e15, 5.0), Point2D::new(1e15, 1e15)));
+        canvas.draw_segment(&Segment::new(Point2D::new(f64::NAN, 1.0), Point2D::new(2.0, 1.0)));
+        assert_eq!(canvas.render(), "   \n   \n   \n");
+    }
+}
@@ -0,0 +1,123 @@
+//! Axis-aligned bounding boxes.
+
+use super::Point2D;
+
+/// The smallest axis-aligned rectangle enclosing some geometry, stored as
+/// its minimum and maximum corners.
+///
+/// A box may be flat (zero width or height), for example the bounds of a
+/// single point or a horizontal line.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    pub min: Point2D,
+    pub max: Point2D,
+}
+
+impl BoundingBox {
+    /// Builds a box from two opposite corners given in any order.
+    pub fn new(a: Point2D, b: Point2D) -> Self {
+        BoundingBox {
+            min: Point2D::new(a.x.min(b.x), a.y.min(b.y)),
+            max: Point2D::new(a.x.max(b.x), a.y.max(b.y)),
+        }
+    }
+
+    /// The smallest box containing every point, or `None` for an empty slice.
+    pub fn from_points(points: &[Point2D]) -> Option<Self> {
+        let (first, rest) = points.split_first()?;
+        let start = BoundingBox::new(*first, *first);
+        Some(rest.iter().fold(start, |bounds, p| bounds.union(&BoundingBox::new(*p, *p))))
+    }
+
+    pub fn width(&self) -> f64 {
+        self.max.x - self.min.x
+    }
+
+    pub fn height(&self) -> f64 {
+        self.max.y - self.min.y
+    }
+
+    pub fn center(&self) -> Point2D {
+        self.min.midpoint(&self.max)
+    }
+
+    /// The smallest box containing both boxes.
+    pub fn union(&self, other: &BoundingBox) -> BoundingBox {
+        BoundingBox {
+            min: Point2D::new(self.min.x.min(other.min.x), self.min.y.min(other.min.y)),
+            max: Point2D::new(self.max.x.max(other.max.x), self.max.y.max(other.max.y)),
+        }
+    }
+
+    /// The overlapping region, or `None` if the boxes do not touch.
+    ///
+    /// Boxes that only share an edge or a corner intersect in a flat box.
+    pub fn intersection(&self, other: &BoundingBox) -> Option<BoundingBox> {
+        let min = Point2D::new(self.min.x.max(other.min.x), self.min.y.max(other.min.y));
+        let max = Point2D::new(self.max.x.min(other.max.x), self.max.y.min(other.max.y));
+        if min.x > max.x || min.y > max.y {
+            return None;
+        }
+        Some(BoundingBox { min, max })
+    }
+
+    /// Returns true if `point` lies inside the box or on its edge.
+    pub fn contains(&self, point: &Point2D) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x) && (self.min.y..=self.max.y).contains(&point.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bbox(x1: f64, y1: f64, x2: f64, y2: f64) -> BoundingBox {
+        BoundingBox::new(Point2D::new(x1, y1), Point2D::new(x2, y2))
+    }
+
+    #[test]
+    fn test_new_orders_corners() {
+        let b = bbox(3.0, 1.0, -1.0, 4.0);
+        assert_eq!(b.min, Point2D::new(-1.0, 1.0));
+        assert_eq!(b.max, Point2D::new(3.0, 4.0));
+        assert_eq!(b.width(), 4.0);
+        assert_eq!(b.height(), 3.0);
+        assert_eq!(b.center(), Point2D::new(1.0, 2.5));
+    }
+
+    #[test]
+    fn test_from_points() {
+        let points = [Point2D::new(1.0, 5.0), Point2D::new(-2.0, 0.0), Point2D::new(4.0, 2.0)];
+        assert_eq!(BoundingBox::from_points(&points), Some(bbox(-2.0, 0.0, 4.0, 5.0)));
+        let single = BoundingBox::from_points(&[Point2D::new(1.0, 1.0)]).unwrap();
+        assert_eq!(single.width(), 0.0);
+        assert!(BoundingBox::from_points(&[]).is_none());
+    }
+
+    #[test]
+    fn test_union() {
+        let a = bbox(0.0, 0.0, 1.0, 1.0);
+        let b = bbox(2.0, -1.0, 3.0, 0.5);
+        assert_eq!(a.union(&b), bbox(0.0, -1.0, 3.0, 1.0));
+        assert_eq!(a.union(&a), a);
+    }
+
+    #[test]
+    fn test_intersection() {
+        let a = bbox(0.0, 0.0, 2.0, 2.0);
+        let b = bbox(1.0, 1.0, 3.0, 3.0);
+        assert_eq!(a.intersection(&b), Some(bbox(1.0, 1.0, 2.0, 2.0)));
+        assert_eq!(a.intersection(&bbox(5.0, 5.0, 6.0, 6.0)), None);
+        // Touching edges give a flat box
+        let touching = a.intersection(&bbox(2.0, 0.0, 4.0, 2.0)).unwrap();
+        assert_eq!(touching.width(), 0.0);
+    }
+
+    #[test]
+    fn test_contains() {
+        let b = bbox(0.0, 0.0, 2.0, 1.0);
+        assert!(b.contains(&Point2D::new(1.0, 0.5)));
+        assert!(b.contains(&Point2D::new(2.0, 1.0)));
+        assert!(!b.contains(&Point2D::new(2.1, 0.5)));
+    }
+}
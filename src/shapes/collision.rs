@@ -0,0 +1,93 @@
+//! Overlap tests between shapes.
+//!
+//! Shapes that only touch along an edge or at a single point count as
+//! intersecting, which is what simple games and physics usually want.
+
+use super::{Circle, Point2D, Rectangle, Shape};
+
+/// Shapes that can test for overlap with a shape of type `T`.
+///
+/// Implemented for every pair of circles and rectangles, in both orders,
+/// so `a.intersects(&b)` works whichever kinds `a` and `b` are.
+pub trait Intersects<T> {
+    fn intersects(&self, other: &T) -> bool;
+}
+
+impl Intersects<Circle> for Circle {
+    /// Two circles overlap when their centers are no further apart than
+    /// the sum of their radii.
+    fn intersects(&self, other: &Circle) -> bool {
+        self.center.distance_to(&other.center) <= self.radius + other.radius
+    }
+}
+
+impl Intersects<Rectangle> for Rectangle {
+    /// Axis-aligned bounding box test: the rectangles overlap unless one
+    /// lies entirely to the left, right, above or below the other.
+    fn intersects(&self, other: &Rectangle) -> bool {
+        let (a_max, b_max) = (self.max_corner(), other.max_corner());
+        self.origin.x <= b_max.x
+            && other.origin.x <= a_max.x
+            && self.origin.y <= b_max.y
+            && other.origin.y <= a_max.y
+    }
+}
+
+impl Intersects<Rectangle> for Circle {
+    /// Finds the point of the rectangle closest to the circle's center and
+    /// checks whether it lies within the radius.
+    fn intersects(&self, other: &Rectangle) -> bool {
+        let max = other.max_corner();
+        let closest = Point2D::new(
+            self.center.x.clamp(other.origin.x, max.x),
+            self.center.y.clamp(other.origin.y, max.y),
+        );
+        self.contains_point(&closest)
+    }
+}
+
+impl Intersects<Circle> for Rectangle {
+    fn intersects(&self, other: &Circle) -> bool {
+        other.intersects(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_circle_circle() {
+        let a = Circle::at(Point2D::new(0.0, 0.0), 1.0);
+        assert!(a.intersects(&Circle::at(Point2D::new(1.5, 0.0), 1.0)));
+        assert!(a.intersects(&Circle::at(Point2D::new(2.0, 0.0), 1.0)));
+        assert!(!a.intersects(&Circle::at(Point2D::new(3.0, 0.0), 1.0)));
+        // One circle entirely inside another
+        assert!(a.intersects(&Circle::at(Point2D::new(0.1, 0.1), 0.2)));
+    }
+
+    #[test]
+    fn test_rect_rect() {
+        let a = Rectangle::new(2.0, 2.0);
+        assert!(a.intersects(&Rectangle::at(Point2D::new(1.0, 1.0), 2.0, 2.0)));
+        assert!(a.intersects(&Rectangle::at(Point2D::new(2.0, 0.0), 1.0, 1.0)));
+        assert!(!a.intersects(&Rectangle::at(Point2D::new(2.5, 0.0), 1.0, 1.0)));
+        assert!(!a.intersects(&Rectangle::at(Point2D::new(0.0, -3.0), 1.0, 1.0)));
+        assert!(a.intersects(&Rectangle::at(Point2D::new(0.5, 0.5), 0.5, 0.5)));
+    }
+
+    #[test]
+    fn test_circle_rect() {
+        let rect = Rectangle::new(4.0, 2.0);
+        // Overlapping an edge
+        let edge = Circle::at(Point2D::new(2.0, 2.5), 1.0);
+        assert!(edge.intersects(&rect));
+        assert!(rect.intersects(&edge));
+        // Near a corner but outside it
+        let corner = Circle::at(Point2D::new(5.0, 3.0), 1.0);
+        assert!(!corner.intersects(&rect));
+        assert!(!rect.intersects(&corner));
+        // Center inside the rectangle
+        assert!(Circle::at(Point2D::new(1.0, 1.0), 0.1).intersects(&rect));
+    }
+}
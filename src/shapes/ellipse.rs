@@ -0,0 +1,128 @@
+//! Axis-aligned ellipses.
+
+use std::f64::consts::PI;
+
+use super::{BoundingBox, Point2D, Shape};
+
+/// An ellipse whose axes run parallel to x and y.
+///
+/// `semi_axis_x` and `semi_axis_y` are the distances from the center to the
+/// edge along each axis; when they are equal the ellipse is a circle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ellipse {
+    pub center: Point2D,
+    pub semi_axis_x: f64,
+    pub semi_axis_y: f64,
+}
+
+impl Ellipse {
+    /// An ellipse centered on the origin.
+    pub fn new(semi_axis_x: f64, semi_axis_y: f64) -> Self {
+        Ellipse::at(Point2D::origin(), semi_axis_x, semi_axis_y)
+    }
+
+    pub fn at(center: Point2D, semi_axis_x: f64, semi_axis_y: f64) -> Self {
+        Ellipse { center, semi_axis_x, semi_axis_y }
+    }
+
+    pub fn is_circle(&self) -> bool {
+        (self.semi_axis_x - self.semi_axis_y).abs() < f64::EPSILON
+    }
+
+    /// How stretched the ellipse is: 0 for a circle, approaching 1 as it
+    /// flattens.
+    pub fn eccentricity(&self) -> f64 {
+        let major = self.semi_axis_x.max(self.semi_axis_y);
+        let minor = self.semi_axis_x.min(self.semi_axis_y);
+        if major == 0.0 {
+            return 0.0;
+        }
+        (1.0 - (minor * minor) / (major * major)).sqrt()
+    }
+}
+
+impl Shape for Ellipse {
+    fn area(&self) -> f64 {
+        PI * self.semi_axis_x * self.semi_axis_y
+    }
+
+    /// Ramanujan's approximation; there is no closed form for the exact
+    /// perimeter. It is exact for circles and within 0.5% even for very
+    /// flat ellipses.
+    fn perimeter(&self) -> f64 {
+        let (a, b) = (self.semi_axis_x, self.semi_axis_y);
+        PI * (3.0 * (a + b) - ((3.0 * a + b) * (a + 3.0 * b)).sqrt())
+    }
+
+    fn name(&self) -> &str {
+        if self.is_circle() {
+            "circle"
+        } else {
+            "ellipse"
+        }
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        let corner = Point2D::new(self.semi_axis_x, self.semi_axis_y);
+        BoundingBox::new(self.center - corner, self.center + corner)
+    }
+
+    fn contains_point(&self, point: &Point2D) -> bool {
+        if self.semi_axis_x == 0.0 || self.semi_axis_y == 0.0 {
+            return false;
+        }
+        let dx = (point.x - self.center.x) / self.semi_axis_x;
+        let dy = (point.y - self.center.y) / self.semi_axis_y;
+        dx * dx + dy * dy <= 1.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shapes::Circle;
+
+    #[test]
+    fn test_area() {
+        let ellipse = Ellipse::new(3.0, 2.0);
+        assert!((ellipse.area() - 6.0 * PI).abs() < 1e-9);
+        assert_eq!(ellipse.name(), "ellipse");
+    }
+
+    #[test]
+    fn test_circle_matches_circle() {
+        let ellipse = Ellipse::new(2.0, 2.0);
+        let circle = Circle::new(2.0);
+        assert!(ellipse.is_circle());
+        assert_eq!(ellipse.name(), "circle");
+        assert!((ellipse.perimeter() - circle.perimeter()).abs() < 1e-9);
+        assert!((ellipse.area() - circle.area()).abs() < 1e-9);
+        assert_eq!(ellipse.eccentricity(), 0.0);
+    }
+
+    #[test]
+    fn test_ramanujan_perimeter() {
+        // Reference value for semi-axes 10 and 5 from the exact elliptic
+        // integral
+        let ellipse = Ellipse::new(10.0, 5.0);
+        assert!((ellipse.perimeter() - 48.442_241_102_738_38).abs() < 1e-3);
+        assert_eq!(Ellipse::new(5.0, 10.0).perimeter(), ellipse.perimeter());
+    }
+
+    #[test]
+    fn test_contains_point() {
+        let ellipse = Ellipse::at(Point2D::new(1.0, 1.0), 4.0, 1.0);
+        assert!(ellipse.contains_point(&Point2D::new(1.0, 1.0)));
+        assert!(ellipse.contains_point(&Point2D::new(5.0, 1.0)));
+        assert!(ellipse.contains_point(&Point2D::new(3.0, 1.5)));
+        assert!(!ellipse.contains_point(&Point2D::new(1.0, 2.5)));
+        assert!(!ellipse.contains_point(&Point2D::new(4.0, 1.8)));
+    }
+
+    #[test]
+    fn test_bounds_and_eccentricity() {
+        let ellipse = Ellipse::at(Point2D::new(1.0, -1.0), 5.0, 3.0);
+        assert_eq!(ellipse.bounds(), BoundingBox::new(Point2D::new(-4.0, -4.0), Point2D::new(6.0, 2.0)));
+        assert!((ellipse.eccentricity() - 0.8).abs() < 1e-9);
+    }
+}
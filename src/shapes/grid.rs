@@ -0,0 +1,202 @@
+//! Rectangular grids of cells, such as tilemaps and cellular automata.
+//!
+//! Cells are addressed as `(x, y)` with `x` counting columns from the left
+//! and `y` counting rows from the top, the way text and tilemaps are laid
+//! out.
+
+use std::fmt;
+
+/// Offsets to the four orthogonal neighbours: up, right, down, left.
+const ORTHOGONAL: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Offsets to all eight surrounding cells, clockwise from the top-left.
+const SURROUNDING: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+];
+
+/// A `width` x `height` grid storing one `T` per cell in row-major order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    /// A grid with every cell set to `fill`.
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// Sets every cell to `value`.
+    pub fn fill(&mut self, value: T) {
+        self.cells.fill(value);
+    }
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from rows, or `None` if the rows differ in length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Self> {
+        let height = rows.len();
+        let width = rows.first().map_or(0, Vec::len);
+        if rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+        Some(Grid {
+            width,
+            height,
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn in_bounds(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        self.in_bounds(x, y).then(|| &self.cells[y * self.width + x])
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        if !self.in_bounds(x, y) {
+            return None;
+        }
+        Some(&mut self.cells[y * self.width + x])
+    }
+
+    /// Replaces a cell, returning the old value, or `None` (leaving the
+    /// grid unchanged) if `(x, y)` is outside the grid.
+    pub fn set(&mut self, x: usize, y: usize, value: T) -> Option<T> {
+        self.get_mut(x, y).map(|cell| std::mem::replace(cell, value))
+    }
+
+    /// One row as a slice.
+    pub fn row(&self, y: usize) -> Option<&[T]> {
+        (y < self.height).then(|| &self.cells[y * self.width..(y + 1) * self.width])
+    }
+
+    /// Iterates over every cell as `((x, y), value)`, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> + '_ {
+        let width = self.width.max(1);
+        self.cells.iter().enumerate().map(move |(i, cell)| ((i % width, i / width), cell))
+    }
+
+    /// The in-bounds cells directly above, right of, below and left of
+    /// `(x, y)`.
+    pub fn neighbors4(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.offsets(x, y, &ORTHOGONAL)
+    }
+
+    /// The in-bounds cells among the eight surrounding `(x, y)`, including
+    /// diagonals.
+    pub fn neighbors8(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.offsets(x, y, &SURROUNDING)
+    }
+
+    fn offsets(
+        &self,
+        x: usize,
+        y: usize,
+        offsets: &'static [(isize, isize)],
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        offsets.iter().filter_map(move |&(dx, dy)| {
+            let nx = x.checked_add_signed(dx)?;
+            let ny = y.checked_add_signed(dy)?;
+            self.in_bounds(nx, ny).then_some((nx, ny))
+        })
+    }
+}
+
+impl Grid<char> {
+    /// Parses a grid from lines of text, or `None` if the lines differ in
+    /// length.
+    pub fn from_text(text: &str) -> Option<Self> {
+        Grid::from_rows(text.lines().map(|line| line.chars().collect()).collect())
+    }
+}
+
+/// Character grids print one row per line.
+impl fmt::Display for Grid<char> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for y in 0..self.height {
+            let line: String = self.row(y).unwrap_or_default().iter().collect();
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_and_set() {
+        let mut grid = Grid::new(3, 2, 0);
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid.set(2, 1, 7), Some(0));
+        assert_eq!(grid.get(2, 1), Some(&7));
+        assert_eq!(grid.get(3, 0), None);
+        assert_eq!(grid.set(0, 2, 1), None);
+        *grid.get_mut(0, 0).unwrap() = 5;
+        assert_eq!(grid.row(0), Some(&[5, 0, 0][..]));
+    }
+
+    #[test]
+    fn test_from_rows() {
+        let grid = Grid::from_rows(vec![vec![1, 2], vec![3, 4]]).unwrap();
+        assert_eq!(grid.get(1, 0), Some(&2));
+        assert_eq!(grid.get(0, 1), Some(&3));
+        assert!(Grid::from_rows(vec![vec![1, 2], vec![3]]).is_none());
+        let cells: Vec<_> = grid.iter().collect();
+        assert_eq!(cells[3], ((1, 1), &4));
+    }
+
+    #[test]
+    fn test_neighbors4() {
+        let grid = Grid::new(3, 3, ());
+        let center: Vec<_> = grid.neighbors4(1, 1).collect();
+        assert_eq!(center, vec![(1, 0), (2, 1), (1, 2), (0, 1)]);
+        let corner: Vec<_> = grid.neighbors4(0, 0).collect();
+        assert_eq!(corner, vec![(1, 0), (0, 1)]);
+    }
+
+    #[test]
+    fn test_neighbors8() {
+        let grid = Grid::new(3, 3, ());
+        assert_eq!(grid.neighbors8(1, 1).count(), 8);
+        let corner: Vec<_> = grid.neighbors8(2, 2).collect();
+        assert_eq!(corner, vec![(1, 1), (2, 1), (1, 2)]);
+        assert_eq!(Grid::new(1, 1, ()).neighbors8(0, 0).count(), 0);
+    }
+
+    #[test]
+    fn test_char_grid_round_trip() {
+        let text = "#.#\n...\n";
+        let mut grid = Grid::from_text(text).unwrap();
+        assert_eq!(grid.to_string(), text);
+        grid.fill('.');
+        assert_eq!(grid.to_string(), "...\n...\n");
+        assert!(Grid::from_text("##\n#\n").is_none());
+    }
+}
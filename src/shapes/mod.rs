@@ -0,0 +1,239 @@
+//! Two-dimensional geometry, plus the 3D solids in [`solid`].
+//!
+//! Every shape implements [`Shape`], so different kinds of shapes can be
+//! stored together as `Box<dyn Shape>` trait objects.
+
+use std::f64::consts::PI;
+
+pub mod ascii;
+pub mod bounds;
+pub mod collision;
+pub mod ellipse;
+pub mod grid;
+pub mod point;
+pub mod polygon;
+pub mod segment;
+pub mod solid;
+pub mod triangle;
+pub mod units;
+
+pub use ascii::Canvas;
+pub use bounds::BoundingBox;
+pub use collision::Intersects;
+pub use ellipse::Ellipse;
+pub use grid::Grid;
+pub use point::Point2D;
+pub use polygon::Polygon;
+pub use segment::Segment;
+pub use solid::{Cone, Cuboid, Cylinder, Solid, Sphere};
+pub use triangle::{AngleKind, SideKind, Triangle, TriangleError};
+pub use units::{Feet, Length, Meters};
+
+/// Common behaviour for all 2D shapes.
+pub trait Shape {
+    fn area(&self) -> f64;
+    fn perimeter(&self) -> f64;
+    /// A short human-readable name such as "circle".
+    fn name(&self) -> &str;
+    /// The smallest axis-aligned box enclosing the shape.
+    fn bounds(&self) -> BoundingBox;
+
+    /// Returns true if `point` lies inside the shape or on its edge.
+    ///
+    /// The default only checks the bounding box; shapes override it with
+    /// an exact test.
+    fn contains_point(&self, point: &Point2D) -> bool {
+        self.bounds().contains(point)
+    }
+}
+
+/// A circle described by its center and radius.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Circle {
+    pub center: Point2D,
+    pub radius: f64,
+}
+
+impl Circle {
+    /// A circle centered on the origin.
+    pub fn new(radius: f64) -> Self {
+        Circle::at(Point2D::origin(), radius)
+    }
+
+    pub fn at(center: Point2D, radius: f64) -> Self {
+        Circle { center, radius }
+    }
+
+    pub fn circumference(&self) -> f64 {
+        2.0 * PI * self.radius
+    }
+}
+
+impl Shape for Circle {
+    fn area(&self) -> f64 {
+        PI * self.radius * self.radius
+    }
+
+    fn perimeter(&self) -> f64 {
+        self.circumference()
+    }
+
+    fn name(&self) -> &str {
+        "circle"
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        let corner = Point2D::new(self.radius, self.radius);
+        BoundingBox::new(self.center - corner, self.center + corner)
+    }
+
+    fn contains_point(&self, point: &Point2D) -> bool {
+        self.center.distance_to(point) <= self.radius
+    }
+}
+
+/// An axis-aligned rectangle. `origin` is the corner with the smallest x
+/// and y; the rectangle extends `width` along x and `height` along y.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rectangle {
+    pub origin: Point2D,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Rectangle {
+    /// A rectangle with its corner on the origin.
+    pub fn new(width: f64, height: f64) -> Self {
+        Rectangle::at(Point2D::origin(), width, height)
+    }
+
+    pub fn at(origin: Point2D, width: f64, height: f64) -> Self {
+        Rectangle { origin, width, height }
+    }
+
+    pub fn square(size: f64) -> Self {
+        Rectangle::new(size, size)
+    }
+
+    /// The corner opposite `origin`, with the largest x and y.
+    pub fn max_corner(&self) -> Point2D {
+        Point2D::new(self.origin.x + self.width, self.origin.y + self.height)
+    }
+
+    pub fn is_square(&self) -> bool {
+        (self.width - self.height).abs() < f64::EPSILON
+    }
+
+    /// Returns true if `other` fits inside this rectangle without rotating.
+    pub fn can_hold(&self, other: &Rectangle) -> bool {
+        self.width >= other.width && self.height >= other.height
+    }
+}
+
+impl Shape for Rectangle {
+    fn area(&self) -> f64 {
+        self.width * self.height
+    }
+
+    fn perimeter(&self) -> f64 {
+        2.0 * (self.width + self.height)
+    }
+
+    fn name(&self) -> &str {
+        if self.is_square() {
+            "square"
+        } else {
+            "rectangle"
+        }
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        BoundingBox::new(self.origin, self.max_corner())
+    }
+
+    fn contains_point(&self, point: &Point2D) -> bool {
+        self.bounds().contains(point)
+    }
+}
+
+/// Sums the areas of a mixed collection of shapes.
+pub fn total_area(shapes: &[Box<dyn Shape>]) -> f64 {
+    shapes.iter().map(|shape| shape.area()).sum()
+}
+
+/// Returns the shape with the largest area, or `None` for an empty slice.
+///
+/// If several shapes tie, the last one wins.
+pub fn largest_by_area(shapes: &[Box<dyn Shape>]) -> Option<&dyn Shape> {
+    shapes
+        .iter()
+        .max_by(|a, b| a.area().total_cmp(&b.area()))
+        .map(|shape| shape.as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f64 = 1e-9;
+
+    #[test]
+    fn test_circle() {
+        let circle = Circle::new(2.0);
+        assert!((circle.area() - 4.0 * PI).abs() < EPSILON);
+        assert!((circle.perimeter() - 4.0 * PI).abs() < EPSILON);
+        assert_eq!(circle.name(), "circle");
+        assert_eq!(circle.center, Point2D::origin());
+        let bounds = Circle::at(Point2D::new(1.0, 2.0), 1.5).bounds();
+        assert_eq!(bounds, BoundingBox::new(Point2D::new(-0.5, 0.5), Point2D::new(2.5, 3.5)));
+    }
+
+    #[test]
+    fn test_contains_point() {
+        let circle = Circle::at(Point2D::new(1.0, 1.0), 2.0);
+        assert!(circle.contains_point(&Point2D::new(1.0, 1.0)));
+        assert!(circle.contains_point(&Point2D::new(3.0, 1.0)));
+        assert!(!circle.contains_point(&Point2D::new(3.0, 3.0)));
+
+        let rect = Rectangle::at(Point2D::new(-1.0, 0.0), 2.0, 1.0);
+        assert!(rect.contains_point(&Point2D::new(0.0, 0.5)));
+        assert!(rect.contains_point(&Point2D::new(1.0, 1.0)));
+        assert!(!rect.contains_point(&Point2D::new(1.5, 0.5)));
+        assert!(!rect.contains_point(&Point2D::new(0.0, -0.1)));
+    }
+
+    #[test]
+    fn test_rectangle() {
+        let rect = Rectangle::new(5.0, 3.0);
+        assert_eq!(rect.area(), 15.0);
+        assert_eq!(rect.perimeter(), 16.0);
+        assert_eq!(rect.name(), "rectangle");
+        assert_eq!(Rectangle::square(4.0).name(), "square");
+        assert!(rect.can_hold(&Rectangle::new(2.0, 1.0)));
+        assert!(!rect.can_hold(&Rectangle::square(4.0)));
+        assert_eq!(rect.bounds(), BoundingBox::new(Point2D::origin(), Point2D::new(5.0, 3.0)));
+    }
+
+    #[test]
+    fn test_total_area_of_mixed_shapes() {
+        let shapes: Vec<Box<dyn Shape>> = vec![
+            Box::new(Rectangle::new(2.0, 3.0)),
+            Box::new(Circle::new(1.0)),
+            Box::new(Rectangle::square(2.0)),
+        ];
+        assert!((total_area(&shapes) - (6.0 + PI + 4.0)).abs() < EPSILON);
+        assert_eq!(total_area(&[]), 0.0);
+    }
+
+    #[test]
+    fn test_largest_by_area() {
+        let shapes: Vec<Box<dyn Shape>> = vec![
+            Box::new(Rectangle::new(1.0, 1.0)),
+            Box::new(Circle::new(3.0)),
+            Box::new(Rectangle::new(5.0, 5.0)),
+        ];
+        let largest = largest_by_area(&shapes).unwrap();
+        assert_eq!(largest.name(), "circle");
+        assert!(largest_by_area(&[]).is_none());
+    }
+}
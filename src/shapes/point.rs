@@ -0,0 +1,101 @@
+//! Points in the plane.
+//!
+//! [`Point2D`] doubles as a 2D vector: adding or subtracting points works
+//! component-wise, and multiplying by an `f64` scales both coordinates.
+
+use std::fmt;
+use std::ops::{Add, Mul, Sub};
+
+/// A point (or position) in 2D space.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Point2D {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Point2D {
+    pub fn new(x: f64, y: f64) -> Self {
+        Point2D { x, y }
+    }
+
+    /// The point at (0, 0).
+    pub fn origin() -> Self {
+        Point2D::default()
+    }
+
+    /// Euclidean distance to another point.
+    pub fn distance_to(&self, other: &Point2D) -> f64 {
+        (self.x - other.x).hypot(self.y - other.y)
+    }
+
+    /// The point halfway between this point and `other`.
+    pub fn midpoint(&self, other: &Point2D) -> Point2D {
+        (*self + *other) * 0.5
+    }
+}
+
+impl Add for Point2D {
+    type Output = Point2D;
+
+    fn add(self, other: Point2D) -> Point2D {
+        Point2D::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Point2D {
+    type Output = Point2D;
+
+    fn sub(self, other: Point2D) -> Point2D {
+        Point2D::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Mul<f64> for Point2D {
+    type Output = Point2D;
+
+    fn mul(self, factor: f64) -> Point2D {
+        Point2D::new(self.x * factor, self.y * factor)
+    }
+}
+
+impl fmt::Display for Point2D {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distance() {
+        let a = Point2D::new(0.0, 0.0);
+        let b = Point2D::new(3.0, 4.0);
+        assert_eq!(a.distance_to(&b), 5.0);
+        assert_eq!(b.distance_to(&a), 5.0);
+        assert_eq!(a, Point2D::origin());
+    }
+
+    #[test]
+    fn test_operators() {
+        let a = Point2D::new(1.0, 2.0);
+        let b = Point2D::new(3.0, 5.0);
+        assert_eq!(a + b, Point2D::new(4.0, 7.0));
+        assert_eq!(b - a, Point2D::new(2.0, 3.0));
+        assert_eq!(a * 3.0, Point2D::new(3.0, 6.0));
+    }
+
+    #[test]
+    fn test_midpoint() {
+        let a = Point2D::new(-2.0, 0.0);
+        let b = Point2D::new(4.0, 6.0);
+        assert_eq!(a.midpoint(&b), Point2D::new(1.0, 3.0));
+        assert_eq!(a.midpoint(&a), a);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Point2D::new(1.5, -2.0).to_string(), "(1.5, -2)");
+    }
+}
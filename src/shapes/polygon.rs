@@ -0,0 +1,227 @@
+//! Simple polygons given by their vertices.
+
+use super::{BoundingBox, Point2D, Segment, Shape};
+
+/// How close a point must be to an edge to count as lying on it.
+const EDGE_TOLERANCE: f64 = 1e-9;
+
+/// A closed polygon whose edges join consecutive vertices, with the last
+/// vertex connected back to the first.
+///
+/// The vertices may be listed clockwise or counter-clockwise. Edges are
+/// assumed not to cross each other.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polygon {
+    vertices: Vec<Point2D>,
+}
+
+impl Polygon {
+    /// Creates a polygon, or `None` if fewer than three vertices are given.
+    pub fn new(vertices: Vec<Point2D>) -> Option<Self> {
+        if vertices.len() < 3 {
+            return None;
+        }
+        Some(Polygon { vertices })
+    }
+
+    pub fn vertices(&self) -> &[Point2D] {
+        &self.vertices
+    }
+
+    /// Iterates over the edges as `(start, end)` pairs, including the
+    /// closing edge from the last vertex back to the first.
+    pub fn edges(&self) -> impl Iterator<Item = (Point2D, Point2D)> + '_ {
+        let n = self.vertices.len();
+        (0..n).map(move |i| (self.vertices[i], self.vertices[(i + 1) % n]))
+    }
+
+    /// The shoelace formula: positive for counter-clockwise vertices and
+    /// negative for clockwise ones.
+    pub fn signed_area(&self) -> f64 {
+        self.edges().map(|(a, b)| a.x * b.y - b.x * a.y).sum::<f64>() / 2.0
+    }
+
+    /// The center of mass of the polygon's area.
+    ///
+    /// Falls back to the average of the vertices when the area is zero.
+    pub fn centroid(&self) -> Point2D {
+        let area = self.signed_area();
+        if area.abs() < f64::EPSILON {
+            let n = self.vertices.len() as f64;
+            let (sx, sy) = self.vertices.iter().fold((0.0, 0.0), |(sx, sy), p| (sx + p.x, sy + p.y));
+            return Point2D::new(sx / n, sy / n);
+        }
+        let (mut cx, mut cy) = (0.0, 0.0);
+        for (a, b) in self.edges() {
+            let cross = a.x * b.y - b.x * a.y;
+            cx += (a.x + b.x) * cross;
+            cy += (a.y + b.y) * cross;
+        }
+        Point2D::new(cx / (6.0 * area), cy / (6.0 * area))
+    }
+
+    /// Returns true if every interior angle is at most 180 degrees.
+    ///
+    /// Walks the edges checking that every turn goes the same way; straight
+    /// (collinear) vertices are allowed.
+    pub fn is_convex(&self) -> bool {
+        let n = self.vertices.len();
+        let mut direction = 0.0;
+        for i in 0..n {
+            let a = self.vertices[i];
+            let b = self.vertices[(i + 1) % n];
+            let c = self.vertices[(i + 2) % n];
+            let cross = (b.x - a.x) * (c.y - b.y) - (b.y - a.y) * (c.x - b.x);
+            if cross.abs() < f64::EPSILON {
+                continue;
+            }
+            if direction == 0.0 {
+                direction = cross.signum();
+            } else if cross.signum() != direction {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Returns true if `point` is inside the polygon or on its boundary.
+    ///
+    /// Casts a ray from the point towards positive x and counts how many
+    /// edges it crosses: an odd count means inside. Each edge includes its
+    /// lower endpoint but not its upper one, so a ray passing exactly
+    /// through a vertex is counted once, and horizontal edges are skipped.
+    /// Points on an edge are checked separately because the parity test
+    /// gives an arbitrary answer for them.
+    pub fn contains(&self, point: &Point2D) -> bool {
+        if self.edges().any(|(a, b)| Segment::new(a, b).distance_to_point(point) <= EDGE_TOLERANCE) {
+            return true;
+        }
+        let mut inside = false;
+        for (a, b) in self.edges() {
+            if (a.y > point.y) != (b.y > point.y) {
+                let crossing_x = a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x);
+                if point.x < crossing_x {
+                    inside = !inside;
+                }
+            }
+        }
+        inside
+    }
+}
+
+impl Shape for Polygon {
+    fn area(&self) -> f64 {
+        self.signed_area().abs()
+    }
+
+    fn perimeter(&self) -> f64 {
+        self.edges().map(|(a, b)| a.distance_to(&b)).sum()
+    }
+
+    fn name(&self) -> &str {
+        "polygon"
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        BoundingBox::from_points(&self.vertices).expect("a polygon has at least three vertices")
+    }
+
+    fn contains_point(&self, point: &Point2D) -> bool {
+        self.contains(point)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn polygon(points: &[(f64, f64)]) -> Polygon {
+        Polygon::new(points.iter().map(|&(x, y)| Point2D::new(x, y)).collect()).unwrap()
+    }
+
+    #[test]
+    fn test_needs_three_vertices() {
+        assert!(Polygon::new(vec![Point2D::origin(), Point2D::new(1.0, 0.0)]).is_none());
+    }
+
+    #[test]
+    fn test_square_area_perimeter_centroid() {
+        let square = polygon(&[(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)]);
+        assert_eq!(square.area(), 4.0);
+        assert_eq!(square.perimeter(), 8.0);
+        assert_eq!(square.centroid(), Point2D::new(1.0, 1.0));
+        assert_eq!(square.bounds(), BoundingBox::new(Point2D::origin(), Point2D::new(2.0, 2.0)));
+        assert!(square.is_convex());
+    }
+
+    #[test]
+    fn test_winding_order() {
+        let ccw = polygon(&[(0.0, 0.0), (4.0, 0.0), (0.0, 3.0)]);
+        let cw = polygon(&[(0.0, 0.0), (0.0, 3.0), (4.0, 0.0)]);
+        assert_eq!(ccw.signed_area(), 6.0);
+        assert_eq!(cw.signed_area(), -6.0);
+        assert_eq!(ccw.area(), cw.area());
+        assert_eq!(ccw.perimeter(), 12.0);
+        assert_eq!(cw.centroid(), ccw.centroid());
+    }
+
+    #[test]
+    fn test_concave_polygon() {
+        // An L shape: a 2x2 square with the top-right 1x1 corner removed
+        let l_shape = polygon(&[(0.0, 0.0), (2.0, 0.0), (2.0, 1.0), (1.0, 1.0), (1.0, 2.0), (0.0, 2.0)]);
+        assert_eq!(l_shape.area(), 3.0);
+        assert!(!l_shape.is_convex());
+        let centroid = l_shape.centroid();
+        assert!((centroid.x - 5.0 / 6.0).abs() < 1e-9);
+        assert!((centroid.y - 5.0 / 6.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_collinear_vertices_stay_convex() {
+        let with_midpoint = polygon(&[(0.0, 0.0), (1.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)]);
+        assert!(with_midpoint.is_convex());
+        assert_eq!(with_midpoint.area(), 4.0);
+    }
+
+    #[test]
+    fn test_contains_concave() {
+        let l_shape = polygon(&[(0.0, 0.0), (2.0, 0.0), (2.0, 1.0), (1.0, 1.0), (1.0, 2.0), (0.0, 2.0)]);
+        assert!(l_shape.contains(&Point2D::new(0.5, 0.5)));
+        assert!(l_shape.contains(&Point2D::new(0.5, 1.5)));
+        assert!(l_shape.contains(&Point2D::new(1.5, 0.5)));
+        // The missing top-right corner
+        assert!(!l_shape.contains(&Point2D::new(1.5, 1.5)));
+        assert!(!l_shape.contains(&Point2D::new(-0.5, 0.5)));
+        assert!(l_shape.contains_point(&Point2D::new(0.5, 0.5)));
+    }
+
+    #[test]
+    fn test_contains_boundary() {
+        let square = polygon(&[(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)]);
+        assert!(square.contains(&Point2D::new(0.0, 0.0)));
+        assert!(square.contains(&Point2D::new(2.0, 1.0)));
+        assert!(square.contains(&Point2D::new(1.0, 2.0)));
+        assert!(!square.contains(&Point2D::new(2.0, 2.5)));
+    }
+
+    #[test]
+    fn test_ray_through_vertex() {
+        // A diamond: rays from points level with the left and right
+        // vertices pass exactly through them
+        let diamond = polygon(&[(2.0, 0.0), (4.0, 2.0), (2.0, 4.0), (0.0, 2.0)]);
+        assert!(diamond.contains(&Point2D::new(1.0, 2.0)));
+        assert!(!diamond.contains(&Point2D::new(-1.0, 2.0)));
+        assert!(!diamond.contains(&Point2D::new(5.0, 2.0)));
+        // A zig-zag bottom edge with a vertex level with the test point
+        let zigzag = polygon(&[(0.0, 0.0), (2.0, 1.0), (4.0, 0.0), (4.0, 3.0), (0.0, 3.0)]);
+        assert!(!zigzag.contains(&Point2D::new(-1.0, 1.0)));
+        assert!(zigzag.contains(&Point2D::new(3.0, 1.0)));
+    }
+
+    #[test]
+    fn test_degenerate_centroid() {
+        let flat = polygon(&[(0.0, 0.0), (1.0, 0.0), (2.0, 0.0)]);
+        assert_eq!(flat.area(), 0.0);
+        assert_eq!(flat.centroid(), Point2D::new(1.0, 0.0));
+    }
+}
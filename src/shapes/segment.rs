@@ -0,0 +1,154 @@
+//! Straight line segments between two points.
+
+use super::Point2D;
+
+/// Tolerance for treating cross products as zero (parallel lines).
+const EPSILON: f64 = 1e-12;
+
+fn cross(a: Point2D, b: Point2D) -> f64 {
+    a.x * b.y - a.y * b.x
+}
+
+fn dot(a: Point2D, b: Point2D) -> f64 {
+    a.x * b.x + a.y * b.y
+}
+
+/// The part of a line between `start` and `end`, both included.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Segment {
+    pub start: Point2D,
+    pub end: Point2D,
+}
+
+impl Segment {
+    pub fn new(start: Point2D, end: Point2D) -> Self {
+        Segment { start, end }
+    }
+
+    pub fn length(&self) -> f64 {
+        self.start.distance_to(&self.end)
+    }
+
+    pub fn midpoint(&self) -> Point2D {
+        self.start.midpoint(&self.end)
+    }
+
+    /// The point on the segment nearest to `point`.
+    pub fn closest_point(&self, point: &Point2D) -> Point2D {
+        let direction = self.end - self.start;
+        let length_squared = dot(direction, direction);
+        if length_squared == 0.0 {
+            return self.start;
+        }
+        let t = (dot(*point - self.start, direction) / length_squared).clamp(0.0, 1.0);
+        self.start + direction * t
+    }
+
+    /// Shortest distance from `point` to any point on the segment.
+    pub fn distance_to_point(&self, point: &Point2D) -> f64 {
+        self.closest_point(point).distance_to(point)
+    }
+
+    /// Where this segment meets `other`, or `None` if they do not touch.
+    ///
+    /// Segments that touch only at an endpoint count as intersecting. When
+    /// two collinear segments overlap along a stretch, the start of the
+    /// shared stretch (measured along `self`) is returned.
+    pub fn intersects(&self, other: &Segment) -> Option<Point2D> {
+        let r = self.end - self.start;
+        let s = other.end - other.start;
+        let offset = other.start - self.start;
+        let denominator = cross(r, s);
+
+        if denominator.abs() < EPSILON {
+            if cross(offset, r).abs() >= EPSILON {
+                return None; // parallel, on different lines
+            }
+            return self.collinear_overlap(other);
+        }
+
+        let t = cross(offset, s) / denominator;
+        let u = cross(offset, r) / denominator;
+        if (0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u) {
+            Some(self.start + r * t)
+        } else {
+            None
+        }
+    }
+
+    /// Overlap of two segments known to lie on the same line.
+    fn collinear_overlap(&self, other: &Segment) -> Option<Point2D> {
+        let r = self.end - self.start;
+        let length_squared = dot(r, r);
+        if length_squared == 0.0 {
+            // `self` is a single point
+            return (other.distance_to_point(&self.start) < EPSILON).then_some(self.start);
+        }
+        // Project the other segment onto `self` as parameters along r
+        let t0 = dot(other.start - self.start, r) / length_squared;
+        let t1 = dot(other.end - self.start, r) / length_squared;
+        let low = t0.min(t1).max(0.0);
+        let high = t0.max(t1).min(1.0);
+        (low <= high).then(|| self.start + r * low)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seg(x1: f64, y1: f64, x2: f64, y2: f64) -> Segment {
+        Segment::new(Point2D::new(x1, y1), Point2D::new(x2, y2))
+    }
+
+    #[test]
+    fn test_length_and_midpoint() {
+        let s = seg(0.0, 0.0, 3.0, 4.0);
+        assert_eq!(s.length(), 5.0);
+        assert_eq!(s.midpoint(), Point2D::new(1.5, 2.0));
+    }
+
+    #[test]
+    fn test_crossing_segments() {
+        let a = seg(0.0, 0.0, 2.0, 2.0);
+        let b = seg(0.0, 2.0, 2.0, 0.0);
+        assert_eq!(a.intersects(&b), Some(Point2D::new(1.0, 1.0)));
+        assert_eq!(b.intersects(&a), Some(Point2D::new(1.0, 1.0)));
+    }
+
+    #[test]
+    fn test_non_crossing_segments() {
+        // The lines cross, but beyond the end of the second segment
+        assert_eq!(seg(0.0, 0.0, 2.0, 2.0).intersects(&seg(3.0, 0.0, 2.0, 0.9)), None);
+        // Parallel lines
+        assert_eq!(seg(0.0, 0.0, 2.0, 0.0).intersects(&seg(0.0, 1.0, 2.0, 1.0)), None);
+    }
+
+    #[test]
+    fn test_touching_at_endpoint() {
+        let a = seg(0.0, 0.0, 1.0, 0.0);
+        let b = seg(1.0, 0.0, 1.0, 5.0);
+        assert_eq!(a.intersects(&b), Some(Point2D::new(1.0, 0.0)));
+    }
+
+    #[test]
+    fn test_collinear_segments() {
+        let a = seg(0.0, 0.0, 4.0, 0.0);
+        assert_eq!(a.intersects(&seg(2.0, 0.0, 6.0, 0.0)), Some(Point2D::new(2.0, 0.0)));
+        assert_eq!(a.intersects(&seg(6.0, 0.0, -1.0, 0.0)), Some(Point2D::new(0.0, 0.0)));
+        assert_eq!(a.intersects(&seg(5.0, 0.0, 6.0, 0.0)), None);
+        let point = seg(1.0, 0.0, 1.0, 0.0);
+        assert_eq!(point.intersects(&a), Some(Point2D::new(1.0, 0.0)));
+    }
+
+    #[test]
+    fn test_distance_to_point() {
+        let s = seg(0.0, 0.0, 4.0, 0.0);
+        assert_eq!(s.distance_to_point(&Point2D::new(2.0, 3.0)), 3.0);
+        // Beyond the ends the nearest point is an endpoint
+        assert_eq!(s.distance_to_point(&Point2D::new(7.0, 4.0)), 5.0);
+        assert_eq!(s.distance_to_point(&Point2D::new(-3.0, -4.0)), 5.0);
+        assert_eq!(s.distance_to_point(&Point2D::new(1.0, 0.0)), 0.0);
+        assert_eq!(seg(1.0, 1.0, 1.0, 1.0).distance_to_point(&Point2D::new(4.0, 5.0)), 5.0);
+    }
+}
@@ -0,0 +1,197 @@
+//! Three-dimensional solids.
+//!
+//! [`Solid`] is the 3D counterpart of [`Shape`](super::Shape): every solid
+//! can report its volume and surface area, and mixed solids can be stored
+//! together as `Box<dyn Solid>`.
+
+use std::f64::consts::PI;
+
+/// Common behaviour for all 3D solids.
+pub trait Solid {
+    fn volume(&self) -> f64;
+    fn surface_area(&self) -> f64;
+    /// A short human-readable name such as "sphere".
+    fn name(&self) -> &str;
+}
+
+/// A sphere with the given radius.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sphere {
+    pub radius: f64,
+}
+
+impl Sphere {
+    pub fn new(radius: f64) -> Self {
+        Sphere { radius }
+    }
+}
+
+impl Solid for Sphere {
+    fn volume(&self) -> f64 {
+        4.0 / 3.0 * PI * self.radius.powi(3)
+    }
+
+    fn surface_area(&self) -> f64 {
+        4.0 * PI * self.radius * self.radius
+    }
+
+    fn name(&self) -> &str {
+        "sphere"
+    }
+}
+
+/// A rectangular box with the given width, height and depth.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cuboid {
+    pub width: f64,
+    pub height: f64,
+    pub depth: f64,
+}
+
+impl Cuboid {
+    pub fn new(width: f64, height: f64, depth: f64) -> Self {
+        Cuboid { width, height, depth }
+    }
+
+    pub fn cube(size: f64) -> Self {
+        Cuboid::new(size, size, size)
+    }
+
+    pub fn is_cube(&self) -> bool {
+        (self.width - self.height).abs() < f64::EPSILON && (self.height - self.depth).abs() < f64::EPSILON
+    }
+}
+
+impl Solid for Cuboid {
+    fn volume(&self) -> f64 {
+        self.width * self.height * self.depth
+    }
+
+    fn surface_area(&self) -> f64 {
+        2.0 * (self.width * self.height + self.height * self.depth + self.depth * self.width)
+    }
+
+    fn name(&self) -> &str {
+        if self.is_cube() {
+            "cube"
+        } else {
+            "cuboid"
+        }
+    }
+}
+
+/// A right circular cylinder.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cylinder {
+    pub radius: f64,
+    pub height: f64,
+}
+
+impl Cylinder {
+    pub fn new(radius: f64, height: f64) -> Self {
+        Cylinder { radius, height }
+    }
+}
+
+impl Solid for Cylinder {
+    fn volume(&self) -> f64 {
+        PI * self.radius * self.radius * self.height
+    }
+
+    /// Both circular ends plus the curved side.
+    fn surface_area(&self) -> f64 {
+        2.0 * PI * self.radius * (self.radius + self.height)
+    }
+
+    fn name(&self) -> &str {
+        "cylinder"
+    }
+}
+
+/// A right circular cone standing on its base.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cone {
+    pub radius: f64,
+    pub height: f64,
+}
+
+impl Cone {
+    pub fn new(radius: f64, height: f64) -> Self {
+        Cone { radius, height }
+    }
+
+    /// Distance from the tip to the edge of the base.
+    pub fn slant_height(&self) -> f64 {
+        self.radius.hypot(self.height)
+    }
+}
+
+impl Solid for Cone {
+    fn volume(&self) -> f64 {
+        PI * self.radius * self.radius * self.height / 3.0
+    }
+
+    /// The base plus the curved side.
+    fn surface_area(&self) -> f64 {
+        PI * self.radius * (self.radius + self.slant_height())
+    }
+
+    fn name(&self) -> &str {
+        "cone"
+    }
+}
+
+/// Sums the volumes of a mixed collection of solids.
+pub fn total_volume(solids: &[Box<dyn Solid>]) -> f64 {
+    solids.iter().map(|solid| solid.volume()).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f64 = 1e-9;
+
+    #[test]
+    fn test_sphere() {
+        let sphere = Sphere::new(3.0);
+        assert!((sphere.volume() - 36.0 * PI).abs() < EPSILON);
+        assert!((sphere.surface_area() - 36.0 * PI).abs() < EPSILON);
+        assert_eq!(sphere.name(), "sphere");
+    }
+
+    #[test]
+    fn test_cuboid() {
+        let cuboid = Cuboid::new(2.0, 3.0, 4.0);
+        assert_eq!(cuboid.volume(), 24.0);
+        assert_eq!(cuboid.surface_area(), 52.0);
+        assert_eq!(cuboid.name(), "cuboid");
+        assert_eq!(Cuboid::cube(2.0).name(), "cube");
+        assert_eq!(Cuboid::cube(2.0).surface_area(), 24.0);
+    }
+
+    #[test]
+    fn test_cylinder() {
+        let cylinder = Cylinder::new(1.0, 2.0);
+        assert!((cylinder.volume() - 2.0 * PI).abs() < EPSILON);
+        assert!((cylinder.surface_area() - 6.0 * PI).abs() < EPSILON);
+    }
+
+    #[test]
+    fn test_cone() {
+        let cone = Cone::new(3.0, 4.0);
+        assert_eq!(cone.slant_height(), 5.0);
+        assert!((cone.volume() - 12.0 * PI).abs() < EPSILON);
+        assert!((cone.surface_area() - 24.0 * PI).abs() < EPSILON);
+        // A cone holds a third of the cylinder with the same base and height
+        let cylinder = Cylinder::new(3.0, 4.0);
+        assert!((cylinder.volume() / cone.volume() - 3.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn test_total_volume() {
+        let solids: Vec<Box<dyn Solid>> = vec![Box::new(Cuboid::cube(2.0)), Box::new(Cylinder::new(1.0, 1.0))];
+        assert!((total_volume(&solids) - (8.0 + PI)).abs() < EPSILON);
+        assert_eq!(total_volume(&[]), 0.0);
+    }
+}
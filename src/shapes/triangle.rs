@@ -0,0 +1,273 @@
+//! Triangles built from side lengths or vertices.
+
+use std::error::Error;
+use std::fmt;
+
+use super::{BoundingBox, Point2D, Shape};
+
+/// Relative tolerance used when comparing side lengths.
+const TOLERANCE: f64 = 1e-9;
+
+fn approx_eq(a: f64, b: f64) -> bool {
+    (a - b).abs() <= TOLERANCE * a.abs().max(b.abs()).max(1.0)
+}
+
+/// Why three lengths or points do not form a triangle.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TriangleError {
+    /// A side was zero, negative, or not a finite number.
+    InvalidSide(f64),
+    /// One side is at least as long as the other two together, so the
+    /// "triangle" would be flat or could not close.
+    InequalityViolated { a: f64, b: f64, c: f64 },
+}
+
+impl fmt::Display for TriangleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TriangleError::InvalidSide(side) => write!(f, "side length {} must be positive", side),
+            TriangleError::InequalityViolated { a, b, c } => {
+                write!(f, "sides {}, {} and {} violate the triangle inequality", a, b, c)
+            }
+        }
+    }
+}
+
+impl Error for TriangleError {}
+
+/// Classification by how many sides are equal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SideKind {
+    Equilateral,
+    Isosceles,
+    Scalene,
+}
+
+/// Classification by the largest angle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AngleKind {
+    Acute,
+    Right,
+    Obtuse,
+}
+
+/// A triangle with side lengths `a`, `b` and `c`.
+///
+/// Side `a` joins the first and second vertices, `b` the second and third,
+/// and `c` the third and first.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Triangle {
+    a: f64,
+    b: f64,
+    c: f64,
+    vertices: [Point2D; 3],
+}
+
+impl Triangle {
+    /// Builds a triangle from three side lengths.
+    ///
+    /// The triangle is placed with its first vertex on the origin and side
+    /// `a` along the positive x axis.
+    pub fn from_sides(a: f64, b: f64, c: f64) -> Result<Self, TriangleError> {
+        Triangle::check_sides(a, b, c)?;
+        let x = (a * a + c * c - b * b) / (2.0 * a);
+        let y = (c * c - x * x).max(0.0).sqrt();
+        let vertices = [Point2D::origin(), Point2D::new(a, 0.0), Point2D::new(x, y)];
+        Ok(Triangle { a, b, c, vertices })
+    }
+
+    /// Builds a triangle from its three corners.
+    ///
+    /// Fails if two points coincide or all three lie on one line.
+    pub fn from_points(p1: Point2D, p2: Point2D, p3: Point2D) -> Result<Self, TriangleError> {
+        let (a, b, c) = (p1.distance_to(&p2), p2.distance_to(&p3), p3.distance_to(&p1));
+        Triangle::check_sides(a, b, c)?;
+        Ok(Triangle { a, b, c, vertices: [p1, p2, p3] })
+    }
+
+    fn check_sides(a: f64, b: f64, c: f64) -> Result<(), TriangleError> {
+        for side in [a, b, c] {
+            if !side.is_finite() || side <= 0.0 {
+                return Err(TriangleError::InvalidSide(side));
+            }
+        }
+        // Each side must be strictly shorter than the sum of the other two
+        let mut sorted = [a, b, c];
+        sorted.sort_by(f64::total_cmp);
+        if sorted[2] >= sorted[0] + sorted[1] || approx_eq(sorted[2], sorted[0] + sorted[1]) {
+            return Err(TriangleError::InequalityViolated { a, b, c });
+        }
+        Ok(())
+    }
+
+    pub fn sides(&self) -> (f64, f64, f64) {
+        (self.a, self.b, self.c)
+    }
+
+    pub fn vertices(&self) -> [Point2D; 3] {
+        self.vertices
+    }
+
+    pub fn side_kind(&self) -> SideKind {
+        let ab = approx_eq(self.a, self.b);
+        let bc = approx_eq(self.b, self.c);
+        let ca = approx_eq(self.c, self.a);
+        if ab && bc {
+            SideKind::Equilateral
+        } else if ab || bc || ca {
+            SideKind::Isosceles
+        } else {
+            SideKind::Scalene
+        }
+    }
+
+    /// Compares the square of the longest side with the sum of the squares
+    /// of the other two (the converse of Pythagoras' theorem).
+    pub fn angle_kind(&self) -> AngleKind {
+        let mut sorted = [self.a, self.b, self.c];
+        sorted.sort_by(f64::total_cmp);
+        let longest = sorted[2] * sorted[2];
+        let others = sorted[0] * sorted[0] + sorted[1] * sorted[1];
+        if approx_eq(longest, others) {
+            AngleKind::Right
+        } else if longest > others {
+            AngleKind::Obtuse
+        } else {
+            AngleKind::Acute
+        }
+    }
+}
+
+impl Shape for Triangle {
+    /// Heron's formula: area from the side lengths and the semi-perimeter.
+    fn area(&self) -> f64 {
+        let s = self.perimeter() / 2.0;
+        (s * (s - self.a) * (s - self.b) * (s - self.c)).max(0.0).sqrt()
+    }
+
+    fn perimeter(&self) -> f64 {
+        self.a + self.b + self.c
+    }
+
+    fn name(&self) -> &str {
+        "triangle"
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        BoundingBox::from_points(&self.vertices).expect("a triangle has three vertices")
+    }
+
+    /// The point is inside when it lies on the same side of all three
+    /// edges (or on one of them).
+    fn contains_point(&self, point: &Point2D) -> bool {
+        let [p1, p2, p3] = self.vertices;
+        let side = |a: Point2D, b: Point2D| (b.x - a.x) * (point.y - a.y) - (b.y - a.y) * (point.x - a.x);
+        let (d1, d2, d3) = (side(p1, p2), side(p2, p3), side(p3, p1));
+        let has_negative = d1 < 0.0 || d2 < 0.0 || d3 < 0.0;
+        let has_positive = d1 > 0.0 || d2 > 0.0 || d3 > 0.0;
+        !(has_negative && has_positive)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heron_area() {
+        let triangle = Triangle::from_sides(3.0, 4.0, 5.0).unwrap();
+        assert!((triangle.area() - 6.0).abs() < 1e-9);
+        assert_eq!(triangle.perimeter(), 12.0);
+
+        let equilateral = Triangle::from_sides(2.0, 2.0, 2.0).unwrap();
+        assert!((equilateral.area() - 3f64.sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_from_points() {
+        let triangle = Triangle::from_points(
+            Point2D::new(0.0, 0.0),
+            Point2D::new(4.0, 0.0),
+            Point2D::new(0.0, 3.0),
+        )
+        .unwrap();
+        assert!((triangle.area() - 6.0).abs() < 1e-9);
+        assert_eq!(triangle.angle_kind(), AngleKind::Right);
+    }
+
+    #[test]
+    fn test_bounds() {
+        let placed = Triangle::from_sides(3.0, 4.0, 5.0).unwrap();
+        let bounds = placed.bounds();
+        assert_eq!(bounds.min, Point2D::origin());
+        assert!((bounds.max.x - 3.0).abs() < 1e-9);
+        assert!((bounds.max.y - 4.0).abs() < 1e-9);
+        assert!((placed.vertices()[1].distance_to(&placed.vertices()[2]) - 4.0).abs() < 1e-9);
+
+        let triangle = Triangle::from_points(
+            Point2D::new(-1.0, 2.0),
+            Point2D::new(3.0, 0.0),
+            Point2D::new(1.0, 5.0),
+        )
+        .unwrap();
+        assert_eq!(triangle.bounds(), BoundingBox::new(Point2D::new(-1.0, 0.0), Point2D::new(3.0, 5.0)));
+    }
+
+    #[test]
+    fn test_contains_point() {
+        let triangle = Triangle::from_points(
+            Point2D::new(0.0, 0.0),
+            Point2D::new(4.0, 0.0),
+            Point2D::new(0.0, 4.0),
+        )
+        .unwrap();
+        assert!(triangle.contains_point(&Point2D::new(1.0, 1.0)));
+        assert!(triangle.contains_point(&Point2D::new(2.0, 2.0)));
+        assert!(!triangle.contains_point(&Point2D::new(3.0, 3.0)));
+        assert!(!triangle.contains_point(&Point2D::new(-0.1, 1.0)));
+    }
+
+    #[test]
+    fn test_invalid_triangles() {
+        assert_eq!(Triangle::from_sides(0.0, 1.0, 1.0), Err(TriangleError::InvalidSide(0.0)));
+        assert_eq!(Triangle::from_sides(-1.0, 1.0, 1.0), Err(TriangleError::InvalidSide(-1.0)));
+        assert!(matches!(Triangle::from_sides(f64::NAN, 1.0, 1.0), Err(TriangleError::InvalidSide(_))));
+        assert_eq!(
+            Triangle::from_sides(1.0, 2.0, 10.0),
+            Err(TriangleError::InequalityViolated { a: 1.0, b: 2.0, c: 10.0 })
+        );
+        // Degenerate: the three sides lie flat
+        assert!(Triangle::from_sides(1.0, 2.0, 3.0).is_err());
+        // Collinear points
+        let collinear = Triangle::from_points(
+            Point2D::new(0.0, 0.0),
+            Point2D::new(1.0, 1.0),
+            Point2D::new(2.0, 2.0),
+        );
+        assert!(matches!(collinear, Err(TriangleError::InequalityViolated { .. })));
+    }
+
+    #[test]
+    fn test_side_classification() {
+        assert_eq!(Triangle::from_sides(3.0, 3.0, 3.0).unwrap().side_kind(), SideKind::Equilateral);
+        assert_eq!(Triangle::from_sides(5.0, 5.0, 8.0).unwrap().side_kind(), SideKind::Isosceles);
+        assert_eq!(Triangle::from_sides(8.0, 5.0, 5.0).unwrap().side_kind(), SideKind::Isosceles);
+        assert_eq!(Triangle::from_sides(4.0, 5.0, 6.0).unwrap().side_kind(), SideKind::Scalene);
+    }
+
+    #[test]
+    fn test_angle_classification() {
+        assert_eq!(Triangle::from_sides(5.0, 3.0, 4.0).unwrap().angle_kind(), AngleKind::Right);
+        assert_eq!(Triangle::from_sides(4.0, 5.0, 6.0).unwrap().angle_kind(), AngleKind::Acute);
+        assert_eq!(Triangle::from_sides(2.0, 3.0, 4.0).unwrap().angle_kind(), AngleKind::Obtuse);
+        let right_isosceles = Triangle::from_sides(1.0, 1.0, 2f64.sqrt()).unwrap();
+        assert_eq!(right_isosceles.angle_kind(), AngleKind::Right);
+        assert_eq!(right_isosceles.side_kind(), SideKind::Isosceles);
+    }
+
+    #[test]
+    fn test_error_message() {
+        let err = Triangle::from_sides(1.0, 1.0, 5.0).unwrap_err();
+        assert_eq!(err.to_string(), "sides 1, 1 and 5 violate the triangle inequality");
+    }
+}
@@ -0,0 +1,159 @@
+//! Typed lengths.
+//!
+//! [`Meters`] and [`Feet`] wrap an `f64` so the compiler can tell them
+//! apart: adding meters to feet is a type error instead of a silent bug.
+//! Convert explicitly with `From`/`Into` when you need to mix them.
+//!
+//! ```compile_fail
+//! use rustler::shapes::units::{Feet, Meters};
+//!
+//! let total = Meters(2.0) + Feet(3.0); // error: mismatched types
+//! ```
+
+use std::fmt;
+use std::ops::{Add, Mul, Sub};
+
+use super::Rectangle;
+
+/// Feet in one meter.
+pub const FEET_PER_METER: f64 = 3.280_839_895;
+
+/// A length that can be converted to meters.
+///
+/// Shape constructors that take a `Length` work in meters internally.
+pub trait Length: Copy {
+    fn to_meters(self) -> Meters;
+}
+
+/// A length in meters.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Meters(pub f64);
+
+/// A length in feet.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Feet(pub f64);
+
+// Both units support the same arithmetic, so the impls are generated once
+// for each of them.
+macro_rules! length_unit {
+    ($unit:ident, $suffix:literal) => {
+        impl Add for $unit {
+            type Output = $unit;
+
+            fn add(self, other: $unit) -> $unit {
+                $unit(self.0 + other.0)
+            }
+        }
+
+        impl Sub for $unit {
+            type Output = $unit;
+
+            fn sub(self, other: $unit) -> $unit {
+                $unit(self.0 - other.0)
+            }
+        }
+
+        impl Mul<f64> for $unit {
+            type Output = $unit;
+
+            fn mul(self, factor: f64) -> $unit {
+                $unit(self.0 * factor)
+            }
+        }
+
+        impl fmt::Display for $unit {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{} {}", self.0, $suffix)
+            }
+        }
+    };
+}
+
+length_unit!(Meters, "m");
+length_unit!(Feet, "ft");
+
+impl From<Feet> for Meters {
+    fn from(feet: Feet) -> Meters {
+        Meters(feet.0 / FEET_PER_METER)
+    }
+}
+
+impl From<Meters> for Feet {
+    fn from(meters: Meters) -> Feet {
+        Feet(meters.0 * FEET_PER_METER)
+    }
+}
+
+impl Length for Meters {
+    fn to_meters(self) -> Meters {
+        self
+    }
+}
+
+impl Length for Feet {
+    fn to_meters(self) -> Meters {
+        self.into()
+    }
+}
+
+impl Rectangle {
+    /// A rectangle measured in a single unit, stored in meters.
+    ///
+    /// Both sides share the type `L`, so passing meters for one side and
+    /// feet for the other does not compile.
+    pub fn with_lengths<L: Length>(width: L, height: L) -> Self {
+        Rectangle::new(width.to_meters().0, height.to_meters().0)
+    }
+
+    /// The width as a typed length.
+    pub fn width_in_meters(&self) -> Meters {
+        Meters(self.width)
+    }
+
+    /// The height as a typed length.
+    pub fn height_in_meters(&self) -> Meters {
+        Meters(self.height)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shapes::Shape;
+
+    #[test]
+    fn test_arithmetic() {
+        assert_eq!(Meters(2.0) + Meters(3.0), Meters(5.0));
+        assert_eq!(Feet(10.0) - Feet(4.0), Feet(6.0));
+        assert_eq!(Meters(1.5) * 2.0, Meters(3.0));
+        assert!(Feet(1.0) < Feet(2.0));
+    }
+
+    #[test]
+    fn test_conversion() {
+        let feet: Feet = Meters(1.0).into();
+        assert!((feet.0 - 3.280_839_895).abs() < 1e-9);
+        let meters = Meters::from(Feet(3.280_839_895));
+        assert!((meters.0 - 1.0).abs() < 1e-9);
+        // Mixing units needs an explicit conversion
+        let total = Meters(1.0) + Feet(10.0).into();
+        assert!((total.0 - 4.048).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Meters(2.5).to_string(), "2.5 m");
+        assert_eq!(Feet(3.0).to_string(), "3 ft");
+    }
+
+    #[test]
+    fn test_rectangle_with_lengths() {
+        let metric = Rectangle::with_lengths(Meters(2.0), Meters(3.0));
+        assert_eq!(metric.area(), 6.0);
+        assert_eq!(metric.width_in_meters(), Meters(2.0));
+
+        let imperial = Rectangle::with_lengths(Feet(10.0), Feet(10.0));
+        assert!((imperial.area() - 9.290_304).abs() < 1e-6);
+        assert!(imperial.is_square());
+    }
+}
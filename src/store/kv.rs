@@ -0,0 +1,443 @@
+//! A persistent key-value store in the style of Bitcask.
+//!
+//! Every change is appended to a log file as one JSON line, and nothing in
+//! the file is ever overwritten:
+//!
+//! ```text
+//! {"op":"set","key":"lang","value":"rust"}
+//! {"op":"set","key":"lang","value":"Rust"}
+//! {"op":"remove","key":"lang"}
+//! ```
+//!
+//! An in-memory index maps each live key to where its latest `set` line
+//! starts, so a read is one seek. Opening a store replays the log to
+//! rebuild the index. Lines superseded by later ones are dead weight; once
+//! they add up past a threshold, the store compacts by writing the live
+//! entries to a fresh file and swapping it in.
+//!
+//! If the program dies halfway through appending, the log ends in a
+//! partial line. Opening the store again cuts that line off, which loses
+//! only the write that was interrupted.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// Compact once this many bytes of the log are superseded entries.
+const DEFAULT_COMPACTION_THRESHOLD: u64 = 1024 * 1024;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+enum Entry {
+    Set { key: String, value: String },
+    Remove { key: String },
+}
+
+/// Where a key's latest value sits in the log.
+#[derive(Debug, Clone, Copy)]
+struct Location {
+    offset: u64,
+    /// Length of the whole line, newline included.
+    len: u64,
+}
+
+/// A string-to-string map stored in an append-only log file.
+///
+/// ```
+/// use rustler::io::TempFile;
+/// use rustler::store::KvStore;
+///
+/// let log = TempFile::new("kv").unwrap();
+/// {
+///     let mut store = KvStore::open(&log).unwrap();
+///     store.set("language", "Rust").unwrap();
+///     store.set("mascot", "Ferris").unwrap();
+///     store.remove("mascot").unwrap();
+/// }
+///
+/// // Reopening replays the log
+/// let mut store = KvStore::open(&log).unwrap();
+/// assert_eq!(store.get("language").unwrap(), Some("Rust".to_string()));
+/// assert_eq!(store.get("mascot").unwrap(), None);
+/// ```
+#[derive(Debug)]
+pub struct KvStore {
+    path: PathBuf,
+    file: File,
+    index: HashMap<String, Location>,
+    /// Bytes of the log taken up by entries a later entry made obsolete.
+    stale_bytes: u64,
+    compaction_threshold: u64,
+}
+
+impl KvStore {
+    /// Opens the store at `path`, creating the file if it does not exist.
+    ///
+    /// A partial line at the end of the log, left by an interrupted write,
+    /// is removed. A bad line anywhere else is reported as
+    /// [`KvError::Corrupt`].
+    pub fn open(path: impl AsRef<Path>) -> Result<KvStore, KvError> {
+        let path = path.as_ref().to_path_buf();
+        let mut file = OpenOptions::new().read(true).append(true).create(true).open(&path)?;
+        let mut index = HashMap::new();
+        let mut stale_bytes = 0;
+        let mut offset = 0;
+
+        let mut reader = BufReader::new(&mut file);
+        // Bytes rather than a String, so that a write torn in the middle of
+        // a multi-byte character is a bad line like any other
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            let len = reader.read_until(b'\n', &mut line)? as u64;
+            if len == 0 {
+                break;
+            }
+            let entry = if line.ends_with(b"\n") {
+                serde_json::from_slice::<Entry>(&line).ok()
+            } else {
+                None
+            };
+            let Some(entry) = entry else {
+                // Only the last line may be broken: that is a torn write
+                if reader.fill_buf()?.is_empty() {
+                    drop(reader);
+                    file.set_len(offset)?;
+                    break;
+                }
+                return Err(KvError::Corrupt { offset });
+            };
+            match entry {
+                Entry::Set { key, .. } => {
+                    if let Some(old) = index.insert(key, Location { offset, len }) {
+                        stale_bytes += old.len;
+                    }
+                }
+                Entry::Remove { key } => {
+                    // The remove line itself is also dead once applied
+                    stale_bytes += len + index.remove(&key).map_or(0, |old| old.len);
+                }
+            }
+            offset += len;
+        }
+
+        Ok(KvStore {
+            path,
+            file,
+            index,
+            stale_bytes,
+            compaction_threshold: DEFAULT_COMPACTION_THRESHOLD,
+        })
+    }
+
+    /// Compacts automatically once `bytes` of the log are superseded
+    /// entries. The default is 1 MiB.
+    pub fn with_compaction_threshold(mut self, bytes: u64) -> Self {
+        self.compaction_threshold = bytes;
+        self
+    }
+
+    /// The value stored under `key`.
+    pub fn get(&mut self, key: &str) -> Result<Option<String>, KvError> {
+        let Some(&location) = self.index.get(key) else {
+            return Ok(None);
+        };
+        self.file.seek(SeekFrom::Start(location.offset))?;
+        let mut line = Vec::new();
+        (&mut self.file).take(location.len).read_to_end(&mut line)?;
+        match serde_json::from_slice(&line) {
+            Ok(Entry::Set { value, .. }) => Ok(Some(value)),
+            _ => Err(KvError::Corrupt {
+                offset: location.offset,
+            }),
+        }
+    }
+
+    /// Stores `value` under `key`, replacing any earlier value.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), KvError> {
+        let location = self.append(&Entry::Set {
+            key: key.to_string(),
+            value: value.to_string(),
+        })?;
+        if let Some(old) = self.index.insert(key.to_string(), location) {
+            self.stale_bytes += old.len;
+        }
+        self.maybe_compact()
+    }
+
+    /// Removes `key`, returning whether it was there.
+    pub fn remove(&mut self, key: &str) -> Result<bool, KvError> {
+        let Some(old) = self.index.remove(key) else {
+            return Ok(false);
+        };
+        let location = self.append(&Entry::Remove { key: key.to_string() })?;
+        self.stale_bytes += old.len + location.len;
+        self.maybe_compact()?;
+        Ok(true)
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.index.contains_key(key)
+    }
+
+    /// The number of keys.
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    /// Every key, in no particular order.
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.index.keys().map(String::as_str)
+    }
+
+    /// The size of the log file in bytes.
+    pub fn log_size(&self) -> Result<u64, KvError> {
+        Ok(self.file.metadata()?.len())
+    }
+
+    /// Bytes of the log that compaction would reclaim.
+    pub fn stale_bytes(&self) -> u64 {
+        self.stale_bytes
+    }
+
+    /// Forces everything written so far onto the disk.
+    pub fn sync(&self) -> Result<(), KvError> {
+        self.file.sync_data()?;
+        Ok(())
+    }
+
+    /// Rewrites the log with only the live entries.
+    ///
+    /// The new log is written beside the old one and then renamed over it,
+    /// so a crash during compaction leaves the old log untouched.
+    pub fn compact(&mut self) -> Result<(), KvError> {
+        let mut compact_path = self.path.clone().into_os_string();
+        compact_path.push(".compact");
+        let compact_path = PathBuf::from(compact_path);
+
+        let mut keys: Vec<String> = self.index.keys().cloned().collect();
+        keys.sort();
+        let mut new_index = HashMap::with_capacity(keys.len());
+        {
+            let mut out = io::BufWriter::new(File::create(&compact_path)?);
+            let mut offset = 0;
+            for key in keys {
+                let value = self.get(&key)?.expect("indexed keys have values");
+                let line = encode(&Entry::Set { key: key.clone(), value })?;
+                out.write_all(line.as_bytes())?;
+                let len = line.len() as u64;
+                new_index.insert(key, Location { offset, len });
+                offset += len;
+            }
+            out.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+        }
+        fs::rename(&compact_path, &self.path)?;
+
+        self.file = OpenOptions::new().read(true).append(true).open(&self.path)?;
+        self.index = new_index;
+        self.stale_bytes = 0;
+        Ok(())
+    }
+
+    fn maybe_compact(&mut self) -> Result<(), KvError> {
+        if self.stale_bytes >= self.compaction_threshold {
+            self.compact()?;
+        }
+        Ok(())
+    }
+
+    /// Writes `entry` at the end of the log in a single write call.
+    fn append(&mut self, entry: &Entry) -> Result<Location, KvError> {
+        let line = encode(entry)?;
+        let offset = self.file.seek(SeekFrom::End(0))?;
+        self.file.write_all(line.as_bytes())?;
+        Ok(Location {
+            offset,
+            len: line.len() as u64,
+        })
+    }
+}
+
+fn encode(entry: &Entry) -> Result<String, KvError> {
+    let mut line = serde_json::to_string(entry)?;
+    line.push('\n');
+    Ok(line)
+}
+
+/// Errors from a [`KvStore`].
+#[derive(Debug)]
+pub enum KvError {
+    Io(io::Error),
+    Serde(serde_json::Error),
+    /// The log holds an unreadable entry at this byte offset, somewhere
+    /// other than its end.
+    Corrupt { offset: u64 },
+}
+
+impl fmt::Display for KvError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KvError::Io(e) => write!(f, "I/O error: {}", e),
+            KvError::Serde(e) => write!(f, "cannot encode entry: {}", e),
+            KvError::Corrupt { offset } => write!(f, "corrupt log entry at byte {}", offset),
+        }
+    }
+}
+
+impl Error for KvError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            KvError::Io(e) => Some(e),
+            KvError::Serde(e) => Some(e),
+            KvError::Corrupt { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for KvError {
+    fn from(e: io::Error) -> Self {
+        KvError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for KvError {
+    fn from(e: serde_json::Error) -> Self {
+        KvError::Serde(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::TempFile;
+
+    #[test]
+    fn test_set_get_remove() {
+        let log = TempFile::new("kv").unwrap();
+        let mut store = KvStore::open(log.path()).unwrap();
+        assert!(store.is_empty());
+        store.set("a", "1").unwrap();
+        store.set("b", "two\nlines").unwrap();
+        store.set("a", "one").unwrap();
+        assert_eq!(store.get("a").unwrap().as_deref(), Some("one"));
+        assert_eq!(store.get("b").unwrap().as_deref(), Some("two\nlines"));
+        assert!(store.remove("a").unwrap());
+        assert!(!store.remove("a").unwrap());
+        assert_eq!(store.get("a").unwrap(), None);
+        assert_eq!(store.len(), 1);
+    }
+
+    #[test]
+    fn test_reopen_rebuilds_the_index() {
+        let log = TempFile::new("kv").unwrap();
+        {
+            let mut store = KvStore::open(log.path()).unwrap();
+            for i in 0..50 {
+                store.set(&format!("key{}", i % 10), &i.to_string()).unwrap();
+            }
+            store.remove("key3").unwrap();
+        }
+        let mut store = KvStore::open(log.path()).unwrap();
+        assert_eq!(store.len(), 9);
+        assert_eq!(store.get("key7").unwrap().as_deref(), Some("47"));
+        assert!(!store.contains_key("key3"));
+        assert!(store.stale_bytes() > 0);
+    }
+
+    #[test]
+    fn test_torn_final_write_is_discarded() {
+        let log = TempFile::new("kv").unwrap();
+        {
+            let mut store = KvStore::open(log.path()).unwrap();
+            store.set("kept", "yes").unwrap();
+        }
+        let intact = fs::metadata(log.path()).unwrap().len();
+        // Simulate a crash partway through appending the next entry
+        let mut file = OpenOptions::new().append(true).open(log.path()).unwrap();
+        file.write_all(br#"{"op":"set","key":"lost","va"#).unwrap();
+        drop(file);
+
+        let mut store = KvStore::open(log.path()).unwrap();
+        assert_eq!(fs::metadata(log.path()).unwrap().len(), intact);
+        assert_eq!(store.get("kept").unwrap().as_deref(), Some("yes"));
+        assert!(!store.contains_key("lost"));
+        // New writes land on a clean line boundary
+        store.set("after", "crash").unwrap();
+        drop(store);
+        let mut store = KvStore::open(log.path()).unwrap();
+        assert_eq!(store.get("after").unwrap().as_deref(), Some("crash"));
+    }
+
+    #[test]
+    fn test_write_torn_inside_a_character_is_discarded() {
+        let log = TempFile::new("kv").unwrap();
+        {
+            let mut store = KvStore::open(log.path()).unwrap();
+            store.set("kept", "yes").unwrap();
+        }
+        let intact = fs::metadata(log.path()).unwrap().len();
+        let entry = r#"{"op":"set","key":"crab","value":"🦀"}"#;
+        // Stop one byte into the four-byte crab
+        let torn = &entry.as_bytes()[..entry.find('🦀').unwrap() + 1];
+        let mut file = OpenOptions::new().append(true).open(log.path()).unwrap();
+        file.write_all(torn).unwrap();
+        drop(file);
+
+        let mut store = KvStore::open(log.path()).unwrap();
+        assert_eq!(fs::metadata(log.path()).unwrap().len(), intact);
+        assert_eq!(store.get("kept").unwrap().as_deref(), Some("yes"));
+        assert!(!store.contains_key("crab"));
+    }
+
+    #[test]
+    fn test_corruption_before_the_end_is_an_error() {
+        let log = TempFile::new("kv").unwrap();
+        let good = r#"{"op":"set","key":"a","value":"1"}"#;
+        fs::write(log.path(), format!("{}\nGARBAGE\n{}\n", good, good)).unwrap();
+        match KvStore::open(log.path()) {
+            Err(KvError::Corrupt { offset }) => assert_eq!(offset, good.len() as u64 + 1),
+            other => panic!("expected corruption, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_compaction_keeps_live_entries_only() {
+        let log = TempFile::new("kv").unwrap();
+        let mut store = KvStore::open(log.path()).unwrap();
+        for i in 0..100 {
+            store.set("counter", &i.to_string()).unwrap();
+        }
+        store.set("other", "x").unwrap();
+        store.remove("other").unwrap();
+        let before = store.log_size().unwrap();
+        store.compact().unwrap();
+        assert!(store.log_size().unwrap() < before / 20);
+        assert_eq!(store.stale_bytes(), 0);
+        assert_eq!(store.get("counter").unwrap().as_deref(), Some("99"));
+        drop(store);
+        let mut store = KvStore::open(log.path()).unwrap();
+        assert_eq!(store.keys().collect::<Vec<_>>(), vec!["counter"]);
+        assert_eq!(store.get("counter").unwrap().as_deref(), Some("99"));
+    }
+
+    #[test]
+    fn test_compacts_automatically_past_the_threshold() {
+        let log = TempFile::new("kv").unwrap();
+        let mut store = KvStore::open(log.path()).unwrap().with_compaction_threshold(500);
+        for i in 0..200 {
+            store.set("k", &i.to_string()).unwrap();
+            assert!(store.stale_bytes() < 500);
+        }
+        assert!(store.log_size().unwrap() < 1_000);
+        assert_eq!(store.get("k").unwrap().as_deref(), Some("199"));
+    }
+}
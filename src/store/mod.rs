@@ -0,0 +1,11 @@
+//! Data that outlives the program: stores backed by files on disk.
+
+pub mod kv;
+pub mod record_file;
+pub mod ttl_cache;
+pub mod versioned;
+
+pub use kv::{KvError, KvStore};
+pub use record_file::{RecordError, RecordFile};
+pub use ttl_cache::{CacheError, TtlCache};
+pub use versioned::VersionError;
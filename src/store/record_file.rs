@@ -0,0 +1,482 @@
+//! A flat-file table: one record per line, found by id through an index.
+//!
+//! Each line starts with a one-byte status, then the record's id, a tab
+//! (shown here as `→`) and its fields as JSON:
+//!
+//! ```text
+//! +1→{"name":"Ferris","age":9}
+//! -2→{"name":"Corro","age":3}
+//! +3→{"name":"Corro","age":4}
+//! ```
+//!
+//! Unlike [`KvStore`](super::KvStore), which only ever appends, a record
+//! file edits itself in place: deleting a record overwrites its `+` with a
+//! `-`, a tombstone. Updating appends the new version and tombstones the
+//! old line, so a scan of the file never meets two live copies of a
+//! record. Tombstoned lines are wasted space until [`compact`] rewrites
+//! the file without them.
+//!
+//! Ids are never reused, so when the record with the highest id is gone,
+//! compaction leaves a tombstone with no fields, such as `-7→`, to say
+//! which ids have been handed out.
+//!
+//! [`compact`]: RecordFile::compact
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+const LIVE: u8 = b'+';
+const DEAD: u8 = b'-';
+
+/// Where a live record's line is.
+#[derive(Debug, Clone, Copy)]
+struct Slot {
+    offset: u64,
+    /// Length of the line, newline included.
+    len: u64,
+}
+
+/// Records of type `T` stored one per line, each under a numeric id.
+///
+/// ```
+/// use rustler::io::TempFile;
+/// use rustler::store::RecordFile;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Book {
+///     title: String,
+///     year: u16,
+/// }
+///
+/// let file = TempFile::new("books").unwrap();
+/// let mut books = RecordFile::<Book>::open(&file).unwrap();
+/// let id = books.insert(&Book { title: "The Rust Book".to_string(), year: 2018 }).unwrap();
+/// books.update(id, &Book { title: "The Rust Book".to_string(), year: 2023 }).unwrap();
+/// assert_eq!(books.get(id).unwrap().unwrap().year, 2023);
+///
+/// books.delete(id).unwrap();
+/// assert!(books.is_empty());
+/// ```
+#[derive(Debug)]
+pub struct RecordFile<T> {
+    path: PathBuf,
+    file: File,
+    index: HashMap<u64, Slot>,
+    next_id: u64,
+    /// Bytes taken up by tombstoned lines.
+    dead_bytes: u64,
+    _record: PhantomData<fn() -> T>,
+}
+
+impl<T: Serialize + DeserializeOwned> RecordFile<T> {
+    /// Opens the file at `path`, creating it if needed, and indexes its
+    /// live records.
+    ///
+    /// A partial line at the end, left by an interrupted write, is cut off.
+    /// If an update was interrupted after appending the new version but
+    /// before tombstoning the old one, the newer line wins.
+    pub fn open(path: impl AsRef<Path>) -> Result<RecordFile<T>, RecordError> {
+        let path = path.as_ref().to_path_buf();
+        let file = OpenOptions::new().read(true).write(true).create(true).truncate(false).open(&path)?;
+        let mut records = RecordFile {
+            path,
+            file,
+            index: HashMap::new(),
+            next_id: 1,
+            dead_bytes: 0,
+            _record: PhantomData,
+        };
+        records.build_index()?;
+        Ok(records)
+    }
+
+    fn build_index(&mut self) -> Result<(), RecordError> {
+        let mut reader = BufReader::new(&mut self.file);
+        // Bytes rather than a String, so that a write torn in the middle of
+        // a multi-byte character is cut off like any other
+        let mut line = Vec::new();
+        let mut offset = 0;
+        let mut line_number = 0;
+        let mut superseded = Vec::new();
+        loop {
+            line.clear();
+            let len = reader.read_until(b'\n', &mut line)? as u64;
+            if len == 0 {
+                break;
+            }
+            line_number += 1;
+            if !line.ends_with(b"\n") {
+                // A torn final write; drop it
+                drop(reader);
+                self.file.set_len(offset)?;
+                break;
+            }
+            let corrupt = || RecordError::Corrupt { line: line_number };
+            let (status, id) = parse_header(&line).ok_or_else(corrupt)?;
+            // The last possible id would leave none for the next insert
+            self.next_id = self.next_id.max(id.checked_add(1).ok_or_else(corrupt)?);
+            if status == LIVE {
+                if let Some(older) = self.index.insert(id, Slot { offset, len }) {
+                    superseded.push(older);
+                }
+            } else if line != high_water_mark(id).as_bytes() {
+                self.dead_bytes += len;
+            }
+            offset += len;
+        }
+        // Finish any update that stopped halfway
+        for slot in superseded {
+            self.tombstone(slot)?;
+        }
+        Ok(())
+    }
+
+    /// Appends a record under a new id and returns the id. Ids are never
+    /// reused, even after the record is deleted.
+    pub fn insert(&mut self, record: &T) -> Result<u64, RecordError> {
+        let id = self.next_id;
+        let slot = self.append(id, record)?;
+        self.index.insert(id, slot);
+        self.next_id += 1;
+        Ok(id)
+    }
+
+    /// The record stored under `id`, read with a single seek.
+    pub fn get(&mut self, id: u64) -> Result<Option<T>, RecordError> {
+        let Some(&slot) = self.index.get(&id) else {
+            return Ok(None);
+        };
+        self.read(slot).map(Some)
+    }
+
+    /// Replaces the record under `id`, keeping its id.
+    pub fn update(&mut self, id: u64, record: &T) -> Result<(), RecordError> {
+        let old = *self.index.get(&id).ok_or(RecordError::NotFound(id))?;
+        let slot = self.append(id, record)?;
+        self.tombstone(old)?;
+        self.index.insert(id, slot);
+        Ok(())
+    }
+
+    /// Tombstones the record under `id`.
+    pub fn delete(&mut self, id: u64) -> Result<(), RecordError> {
+        let slot = self.index.remove(&id).ok_or(RecordError::NotFound(id))?;
+        self.tombstone(slot)
+    }
+
+    pub fn contains(&self, id: u64) -> bool {
+        self.index.contains_key(&id)
+    }
+
+    /// The number of live records.
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    /// The ids of live records, in increasing order.
+    pub fn ids(&self) -> Vec<u64> {
+        let mut ids: Vec<u64> = self.index.keys().copied().collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    /// Every live record with its id, in id order.
+    pub fn records(&mut self) -> Result<Vec<(u64, T)>, RecordError> {
+        self.ids().into_iter().map(|id| Ok((id, self.read(self.index[&id])?))).collect()
+    }
+
+    /// Bytes of the file that [`compact`](RecordFile::compact) would free.
+    pub fn dead_bytes(&self) -> u64 {
+        self.dead_bytes
+    }
+
+    /// Rewrites the file with only the live records, in id order.
+    ///
+    /// The new file is written beside the old one and renamed over it, so
+    /// a crash part way through leaves the old file as it was.
+    pub fn compact(&mut self) -> Result<(), RecordError> {
+        let mut compact_path = self.path.clone().into_os_string();
+        compact_path.push(".compact");
+        let compact_path = PathBuf::from(compact_path);
+
+        let mut new_index = HashMap::with_capacity(self.index.len());
+        {
+            let mut out = BufWriter::new(File::create(&compact_path)?);
+            let mut offset = 0;
+            let ids = self.ids();
+            for &id in &ids {
+                let line = self.read_line(self.index[&id])?;
+                out.write_all(&line)?;
+                let len = line.len() as u64;
+                new_index.insert(id, Slot { offset, len });
+                offset += len;
+            }
+            // Without the line for the last id handed out, reopening would
+            // hand it out again
+            let last_id = self.next_id - 1;
+            if last_id > 0 && ids.last() != Some(&last_id) {
+                out.write_all(high_water_mark(last_id).as_bytes())?;
+            }
+            out.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+        }
+        fs::rename(&compact_path, &self.path)?;
+
+        self.file = OpenOptions::new().read(true).write(true).open(&self.path)?;
+        self.index = new_index;
+        self.dead_bytes = 0;
+        Ok(())
+    }
+
+    fn append(&mut self, id: u64, record: &T) -> Result<Slot, RecordError> {
+        let json = serde_json::to_string(record)?;
+        let line = format!("{}{}\t{}\n", LIVE as char, id, json);
+        let offset = self.file.seek(SeekFrom::End(0))?;
+        self.file.write_all(line.as_bytes())?;
+        Ok(Slot {
+            offset,
+            len: line.len() as u64,
+        })
+    }
+
+    /// Overwrites the status byte of the line at `slot`.
+    fn tombstone(&mut self, slot: Slot) -> Result<(), RecordError> {
+        self.file.seek(SeekFrom::Start(slot.offset))?;
+        self.file.write_all(&[DEAD])?;
+        self.dead_bytes += slot.len;
+        Ok(())
+    }
+
+    fn read_line(&mut self, slot: Slot) -> Result<Vec<u8>, RecordError> {
+        self.file.seek(SeekFrom::Start(slot.offset))?;
+        let mut line = Vec::new();
+        (&mut self.file).take(slot.len).read_to_end(&mut line)?;
+        Ok(line)
+    }
+
+    fn read(&mut self, slot: Slot) -> Result<T, RecordError> {
+        let line = self.read_line(slot)?;
+        let tab = line.iter().position(|&b| b == b'\t').ok_or(RecordError::Corrupt { line: 0 })?;
+        Ok(serde_json::from_slice(&line[tab + 1..])?)
+    }
+}
+
+/// The status byte and id at the start of a line.
+fn parse_header(line: &[u8]) -> Option<(u8, u64)> {
+    let (&status, rest) = line.split_first()?;
+    if status != LIVE && status != DEAD {
+        return None;
+    }
+    let tab = rest.iter().position(|&b| b == b'\t')?;
+    let id = std::str::from_utf8(&rest[..tab]).ok()?;
+    Some((status, id.parse().ok()?))
+}
+
+/// The tombstone that [`RecordFile::compact`] keeps for the highest id
+/// handed out, when that record is gone. It isn't counted as dead bytes,
+/// since compacting again would only write it back.
+fn high_water_mark(id: u64) -> String {
+    format!("{}{}\t\n", DEAD as char, id)
+}
+
+/// Errors from a [`RecordFile`].
+#[derive(Debug)]
+pub enum RecordError {
+    Io(io::Error),
+    /// A record could not be converted to or from JSON.
+    Json(serde_json::Error),
+    /// A line does not start with a status and an id. Lines are numbered
+    /// from 1; 0 means a line found through the index.
+    Corrupt { line: usize },
+    NotFound(u64),
+}
+
+impl fmt::Display for RecordError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecordError::Io(e) => write!(f, "I/O error: {}", e),
+            RecordError::Json(e) => write!(f, "bad record: {}", e),
+            RecordError::Corrupt { line } => write!(f, "corrupt record on line {}", line),
+            RecordError::NotFound(id) => write!(f, "no record with id {}", id),
+        }
+    }
+}
+
+impl Error for RecordError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            RecordError::Io(e) => Some(e),
+            RecordError::Json(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for RecordError {
+    fn from(e: io::Error) -> Self {
+        RecordError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for RecordError {
+    fn from(e: serde_json::Error) -> Self {
+        RecordError::Json(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::TempFile;
+    use serde::Deserialize;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Player {
+        name: String,
+        score: u32,
+    }
+
+    fn player(name: &str, score: u32) -> Player {
+        Player {
+            name: name.to_string(),
+            score,
+        }
+    }
+
+    #[test]
+    fn test_insert_get_update_delete() {
+        let file = TempFile::new("records").unwrap();
+        let mut players = RecordFile::open(file.path()).unwrap();
+        let ada = players.insert(&player("Ada", 10)).unwrap();
+        let bob = players.insert(&player("Bob", 20)).unwrap();
+        assert_eq!((ada, bob), (1, 2));
+
+        players.update(ada, &player("Ada", 15)).unwrap();
+        assert_eq!(players.get(ada).unwrap(), Some(player("Ada", 15)));
+        players.delete(bob).unwrap();
+        assert_eq!(players.get(bob).unwrap(), None);
+        assert!(matches!(players.update(bob, &player("Bob", 0)), Err(RecordError::NotFound(2))));
+        assert!(matches!(players.delete(bob), Err(RecordError::NotFound(2))));
+        assert_eq!(players.records().unwrap(), vec![(1, player("Ada", 15))]);
+
+        // The old line for Ada and Bob's only line are tombstoned in place
+        let text = fs::read_to_string(file.path()).unwrap();
+        let statuses: Vec<&str> = text.lines().map(|line| &line[..2]).collect();
+        assert_eq!(statuses, vec!["-1", "-2", "+1"]);
+    }
+
+    #[test]
+    fn test_reopen_rebuilds_the_index() {
+        let file = TempFile::new("records").unwrap();
+        {
+            let mut players = RecordFile::open(file.path()).unwrap();
+            for i in 0..5 {
+                players.insert(&player(&format!("p{}", i), i)).unwrap();
+            }
+            players.delete(5).unwrap();
+            players.update(2, &player("p1", 100)).unwrap();
+        }
+        let mut players = RecordFile::<Player>::open(file.path()).unwrap();
+        assert_eq!(players.ids(), vec![1, 2, 3, 4]);
+        assert_eq!(players.get(2).unwrap().unwrap().score, 100);
+        assert!(players.dead_bytes() > 0);
+        // Ids keep counting past deleted ones
+        assert_eq!(players.insert(&player("new", 0)).unwrap(), 6);
+    }
+
+    #[test]
+    fn test_recovers_from_interrupted_writes() {
+        let file = TempFile::new("records").unwrap();
+        fs::write(
+            file.path(),
+            "+1\t{\"name\":\"old\",\"score\":1}\n+1\t{\"name\":\"new\",\"score\":2}\n+2\t{\"name\":\"torn",
+        )
+        .unwrap();
+        let mut players = RecordFile::<Player>::open(file.path()).unwrap();
+        assert_eq!(players.records().unwrap(), vec![(1, player("new", 2))]);
+        assert!(fs::read_to_string(file.path()).unwrap().starts_with("-1\t"));
+        assert_eq!(players.insert(&player("after", 3)).unwrap(), 2);
+
+        fs::write(file.path(), "+1\t{}\nnonsense\n").unwrap();
+        assert!(matches!(RecordFile::<Player>::open(file.path()), Err(RecordError::Corrupt { line: 2 })));
+        fs::write(file.path(), format!("+1\t{{}}\n+{}\t{{}}\n", u64::MAX)).unwrap();
+        assert!(matches!(RecordFile::<Player>::open(file.path()), Err(RecordError::Corrupt { line: 2 })));
+    }
+
+    #[test]
+    fn test_deleted_ids_stay_used_after_compaction() {
+        let file = TempFile::new("records").unwrap();
+        let mut players = RecordFile::open(file.path()).unwrap();
+        players.insert(&player("Ada", 1)).unwrap();
+        let bob = players.insert(&player("Bob", 2)).unwrap();
+        players.delete(bob).unwrap();
+        players.compact().unwrap();
+        assert_eq!(players.dead_bytes(), 0);
+        drop(players);
+
+        let mut players = RecordFile::<Player>::open(file.path()).unwrap();
+        assert_eq!(players.ids(), vec![1]);
+        assert_eq!(players.dead_bytes(), 0);
+        assert_eq!(players.insert(&player("Cy", 3)).unwrap(), 3);
+
+        // With every record gone, the mark is the whole file
+        for id in players.ids() {
+            players.delete(id).unwrap();
+        }
+        players.compact().unwrap();
+        assert_eq!(fs::read_to_string(file.path()).unwrap(), "-3\t\n");
+        drop(players);
+        let mut players = RecordFile::<Player>::open(file.path()).unwrap();
+        assert_eq!(players.insert(&player("Di", 4)).unwrap(), 4);
+    }
+
+    #[test]
+    fn test_write_torn_inside_a_character_is_cut_off() {
+        let file = TempFile::new("records").unwrap();
+        let line = "+2\t{\"name\":\"🦀\",\"score\":1}\n";
+        let torn = &line.as_bytes()[..line.find('🦀').unwrap() + 2];
+        let mut contents = b"+1\t{\"name\":\"Ada\",\"score\":1}\n".to_vec();
+        let intact = contents.len() as u64;
+        contents.extend_from_slice(torn);
+        fs::write(file.path(), contents).unwrap();
+
+        let mut players = RecordFile::<Player>::open(file.path()).unwrap();
+        assert_eq!(fs::metadata(file.path()).unwrap().len(), intact);
+        assert_eq!(players.records().unwrap(), vec![(1, player("Ada", 1))]);
+    }
+
+    #[test]
+    fn test_compaction_drops_tombstones() {
+        let file = TempFile::new("records").unwrap();
+        let mut players = RecordFile::open(file.path()).unwrap();
+        for round in 0..20 {
+            players.insert(&player("temp", round)).unwrap();
+        }
+        for id in 1..=19 {
+            players.delete(id).unwrap();
+        }
+        players.update(20, &player("kept", 99)).unwrap();
+        let before = fs::metadata(file.path()).unwrap().len();
+        players.compact().unwrap();
+        assert_eq!(players.dead_bytes(), 0);
+        assert!(fs::metadata(file.path()).unwrap().len() < before / 10);
+        assert_eq!(players.get(20).unwrap(), Some(player("kept", 99)));
+
+        drop(players);
+        let mut players = RecordFile::<Player>::open(file.path()).unwrap();
+        assert_eq!(players.records().unwrap(), vec![(20, player("kept", 99))]);
+        assert_eq!(players.insert(&player("next", 0)).unwrap(), 21);
+    }
+}
@@ -0,0 +1,387 @@
+//! A cache whose entries expire, kept on disk between runs.
+//!
+//! [`SharedCache`](crate::concurrent::SharedCache) forgets everything when
+//! the program exits. A `TtlCache` writes a JSON snapshot of its live
+//! entries when it is dropped and reads it back on [`open`], so a command
+//! line tool run twice in a minute can skip the second download.
+//!
+//! Expiry times are wall-clock milliseconds since the Unix epoch, not
+//! `Instant`s, because an `Instant` means nothing to the next process.
+//! Expired entries are removed lazily, when a read finds them, or all at
+//! once by [`purge_expired`]; they are never written to the snapshot.
+//!
+//! [`open`]: TtlCache::open
+//! [`purge_expired`]: TtlCache::purge_expired
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::hash::Hash;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use super::versioned::{self, VersionError};
+
+/// The snapshot format version written by [`TtlCache::save`].
+pub const SAVE_VERSION: u32 = 1;
+
+#[derive(Debug)]
+struct Slot<V> {
+    value: V,
+    /// Milliseconds since the Unix epoch.
+    expires_at: u64,
+}
+
+/// What is written to disk.
+#[derive(Serialize, Deserialize)]
+struct Snapshot<E> {
+    version: u32,
+    entries: Vec<E>,
+}
+
+/// One entry in a snapshot. A list of these rather than a JSON object,
+/// because object keys must be strings and `K` need not be.
+#[derive(Serialize, Deserialize)]
+struct Stored<K, V> {
+    key: K,
+    value: V,
+    expires_at: u64,
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis() as u64)
+}
+
+/// A map from `K` to `V` whose entries expire, persisted to a JSON file.
+///
+/// ```
+/// use std::time::Duration;
+/// use rustler::io::TempFile;
+/// use rustler::store::TtlCache;
+///
+/// let file = TempFile::with_suffix("docs", ".json").unwrap();
+/// {
+///     let mut cache = TtlCache::open(&file, Duration::from_secs(60)).unwrap();
+///     cache.insert("https://example.com".to_string(), "<html>...</html>".to_string());
+/// } // dropped: the snapshot is written
+///
+/// let mut cache = TtlCache::<String, String>::open(&file, Duration::from_secs(60)).unwrap();
+/// assert_eq!(cache.get(&"https://example.com".to_string()).unwrap(), "<html>...</html>");
+/// ```
+#[derive(Debug)]
+pub struct TtlCache<K, V>
+where
+    K: Hash + Eq + Serialize + DeserializeOwned,
+    V: Serialize + DeserializeOwned,
+{
+    path: PathBuf,
+    ttl: Duration,
+    entries: HashMap<K, Slot<V>>,
+    /// Whether the entries differ from the snapshot on disk.
+    dirty: bool,
+}
+
+impl<K, V> TtlCache<K, V>
+where
+    K: Hash + Eq + Serialize + DeserializeOwned,
+    V: Serialize + DeserializeOwned,
+{
+    /// Loads the snapshot at `path`, or starts empty if there is none.
+    /// Entries inserted with [`insert`](TtlCache::insert) live for `ttl`.
+    pub fn open(path: impl AsRef<Path>, ttl: Duration) -> Result<Self, CacheError> {
+        let path = path.as_ref().to_path_buf();
+        let mut cache = TtlCache {
+            path,
+            ttl,
+            entries: HashMap::new(),
+            dirty: false,
+        };
+        let json = match fs::read_to_string(&cache.path) {
+            Ok(json) => json,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(cache),
+            Err(e) => return Err(e.into()),
+        };
+        // An empty file is what TempFile and `touch` leave behind
+        if json.trim().is_empty() {
+            return Ok(cache);
+        }
+        let snapshot: Snapshot<Stored<K, V>> = versioned::from_json(&json, SAVE_VERSION)?;
+        let now = now_millis();
+        for stored in snapshot.entries {
+            if stored.expires_at > now {
+                let slot = Slot {
+                    value: stored.value,
+                    expires_at: stored.expires_at,
+                };
+                cache.entries.insert(stored.key, slot);
+            } else {
+                // Expired while the program was not running
+                cache.dirty = true;
+            }
+        }
+        Ok(cache)
+    }
+
+    /// The value for `key`, unless it is missing or expired. An expired
+    /// entry is removed.
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        let expired = self.entries.get(key)?.expires_at <= now_millis();
+        if expired {
+            self.entries.remove(key);
+            self.dirty = true;
+            return None;
+        }
+        self.entries.get(key).map(|slot| &slot.value)
+    }
+
+    /// How long `key` has left, or `None` if it is missing or expired.
+    pub fn time_to_live(&self, key: &K) -> Option<Duration> {
+        let remaining = self.entries.get(key)?.expires_at.checked_sub(now_millis())?;
+        (remaining > 0).then(|| Duration::from_millis(remaining))
+    }
+
+    /// Stores `value` for the cache's TTL, replacing any earlier value.
+    pub fn insert(&mut self, key: K, value: V) {
+        self.insert_with_ttl(key, value, self.ttl);
+    }
+
+    /// Stores `value` for `ttl` instead of the cache's default.
+    pub fn insert_with_ttl(&mut self, key: K, value: V, ttl: Duration) {
+        // A TTL too long to count in milliseconds never runs out
+        let ttl = u64::try_from(ttl.as_millis()).unwrap_or(u64::MAX);
+        let expires_at = now_millis().saturating_add(ttl);
+        self.entries.insert(key, Slot { value, expires_at });
+        self.dirty = true;
+    }
+
+    /// Removes `key`, returning its value if it was present and live.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let slot = self.entries.remove(key)?;
+        self.dirty = true;
+        (slot.expires_at > now_millis()).then_some(slot.value)
+    }
+
+    /// Drops every expired entry, returning how many were removed.
+    pub fn purge_expired(&mut self) -> usize {
+        let now = now_millis();
+        let before = self.entries.len();
+        self.entries.retain(|_, slot| slot.expires_at > now);
+        let removed = before - self.entries.len();
+        if removed > 0 {
+            self.dirty = true;
+        }
+        removed
+    }
+
+    /// The number of stored entries, including expired ones not yet
+    /// removed.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Writes the live entries to the snapshot file now, rather than
+    /// waiting for the cache to be dropped.
+    ///
+    /// The JSON goes to a temporary file that is then renamed over the
+    /// snapshot, so a crash mid-save leaves the previous one intact.
+    pub fn save(&mut self) -> Result<(), CacheError> {
+        let now = now_millis();
+        let entries: Vec<Stored<&K, &V>> = self
+            .entries
+            .iter()
+            .filter(|(_, slot)| slot.expires_at > now)
+            .map(|(key, slot)| Stored {
+                key,
+                value: &slot.value,
+                expires_at: slot.expires_at,
+            })
+            .collect();
+        let snapshot = Snapshot {
+            version: SAVE_VERSION,
+            entries,
+        };
+        let json = serde_json::to_string(&snapshot)?;
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let temp = self.path.with_extension("tmp");
+        fs::write(&temp, json)?;
+        fs::rename(&temp, &self.path)?;
+        self.dirty = false;
+        Ok(())
+    }
+}
+
+impl<K, V> Drop for TtlCache<K, V>
+where
+    K: Hash + Eq + Serialize + DeserializeOwned,
+    V: Serialize + DeserializeOwned,
+{
+    /// Saves if anything changed. Errors cannot be reported from `drop`;
+    /// call [`save`](TtlCache::save) first to see them.
+    fn drop(&mut self) {
+        if self.dirty {
+            let _ = self.save();
+        }
+    }
+}
+
+/// Errors from loading or saving a [`TtlCache`].
+#[derive(Debug)]
+pub enum CacheError {
+    Io(io::Error),
+    /// The snapshot is not valid JSON or does not match `K` and `V`.
+    Format(serde_json::Error),
+    /// The snapshot is from a newer build; delete it to start afresh.
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for CacheError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CacheError::Io(e) => write!(f, "I/O error: {}", e),
+            CacheError::Format(e) => write!(f, "malformed cache snapshot: {}", e),
+            CacheError::UnsupportedVersion(version) => write!(
+                f,
+                "cache snapshot version {} is not supported (expected 1 to {})",
+                version, SAVE_VERSION
+            ),
+        }
+    }
+}
+
+impl Error for CacheError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CacheError::Io(e) => Some(e),
+            CacheError::Format(e) => Some(e),
+            CacheError::UnsupportedVersion(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for CacheError {
+    fn from(e: io::Error) -> Self {
+        CacheError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for CacheError {
+    fn from(e: serde_json::Error) -> Self {
+        CacheError::Format(e)
+    }
+}
+
+impl From<VersionError> for CacheError {
+    fn from(e: VersionError) -> Self {
+        match e {
+            VersionError::Format(e) => CacheError::Format(e),
+            VersionError::Unsupported(version) => CacheError::UnsupportedVersion(version),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::TempDir;
+    use std::thread;
+
+    const MINUTE: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn test_insert_get_remove() {
+        let dir = TempDir::new("ttl").unwrap();
+        let mut cache = TtlCache::open(dir.join("cache.json"), MINUTE).unwrap();
+        assert!(cache.is_empty());
+        cache.insert(1u32, "one".to_string());
+        cache.insert(1, "uno".to_string());
+        assert_eq!(cache.get(&1).map(String::as_str), Some("uno"));
+        assert_eq!(cache.get(&2), None);
+        assert!(cache.time_to_live(&1).unwrap() > Duration::from_secs(59));
+        assert_eq!(cache.remove(&1).as_deref(), Some("uno"));
+        assert_eq!(cache.remove(&1), None);
+    }
+
+    #[test]
+    fn test_expired_entries_are_evicted_on_read() {
+        let dir = TempDir::new("ttl").unwrap();
+        let mut cache = TtlCache::open(dir.join("cache.json"), Duration::from_millis(20)).unwrap();
+        cache.insert("short".to_string(), 1);
+        cache.insert_with_ttl("long".to_string(), 2, MINUTE);
+        thread::sleep(Duration::from_millis(40));
+
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.time_to_live(&"short".to_string()), None);
+        assert_eq!(cache.get(&"short".to_string()), None);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.get(&"long".to_string()), Some(&2));
+        assert_eq!(cache.purge_expired(), 0);
+    }
+
+    #[test]
+    fn test_huge_ttl_never_expires() {
+        let dir = TempDir::new("ttl").unwrap();
+        let path = dir.join("cache.json");
+        {
+            let mut cache = TtlCache::open(&path, Duration::MAX).unwrap();
+            cache.insert(1u8, "forever".to_string());
+            assert_eq!(cache.get(&1).map(String::as_str), Some("forever"));
+            assert_eq!(cache.purge_expired(), 0);
+        }
+        let mut cache = TtlCache::<u8, String>::open(&path, Duration::MAX).unwrap();
+        assert_eq!(cache.get(&1).map(String::as_str), Some("forever"));
+    }
+
+    #[test]
+    fn test_snapshot_survives_a_restart() {
+        let dir = TempDir::new("ttl").unwrap();
+        let path = dir.join("nested").join("cache.json");
+        {
+            let mut cache = TtlCache::open(&path, MINUTE).unwrap();
+            cache.insert((1, 2), vec!["a".to_string()]);
+            cache.insert_with_ttl((3, 4), vec![], Duration::from_millis(10));
+            thread::sleep(Duration::from_millis(20));
+        }
+        let mut cache = TtlCache::<(i32, i32), Vec<String>>::open(&path, MINUTE).unwrap();
+        // The expired entry was never written
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.get(&(1, 2)).unwrap(), &["a"]);
+        assert!(!path.with_extension("tmp").exists());
+    }
+
+    #[test]
+    fn test_unchanged_cache_is_not_written() {
+        let dir = TempDir::new("ttl").unwrap();
+        let path = dir.join("cache.json");
+        let mut cache = TtlCache::<String, u8>::open(&path, MINUTE).unwrap();
+        assert_eq!(cache.get(&"missing".to_string()), None);
+        drop(cache);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_bad_snapshots_report_why() {
+        let dir = TempDir::new("ttl").unwrap();
+        let path = dir.join("cache.json");
+        fs::write(&path, r#"{"version": 9, "entries": []}"#).unwrap();
+        let err = TtlCache::<String, u8>::open(&path, MINUTE).unwrap_err();
+        assert_eq!(err.to_string(), "cache snapshot version 9 is not supported (expected 1 to 1)");
+
+        fs::write(&path, r#"{"version": 1, "entries": [{"key": "a", "value": "not a number", "expires_at": 0}]}"#).unwrap();
+        assert!(matches!(TtlCache::<String, u8>::open(&path, MINUTE), Err(CacheError::Format(_))));
+    }
+}
@@ -0,0 +1,115 @@
+//! JSON files that record their format version next to the data.
+//!
+//! A file written by a newer program may have moved or renamed fields, so
+//! the `version` field is read on its own first. A file from the future is
+//! then rejected with a clear error instead of half-parsing or, worse,
+//! parsing into something subtly wrong.
+//!
+//! ```text
+//! {
+//!   "version": 1,
+//!   ...
+//! }
+//! ```
+
+use std::error::Error;
+use std::fmt;
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+/// Just enough of a file to learn its version.
+#[derive(Deserialize)]
+struct VersionProbe {
+    version: u32,
+}
+
+/// Parses `json` as a `T` once its `version` is known to be between 1 and
+/// `newest`, the latest format this build writes.
+///
+/// ```
+/// use rustler::store::versioned::{self, VersionError};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Save {
+///     score: u32,
+/// }
+///
+/// let save: Save = versioned::from_json(r#"{"version": 1, "score": 7}"#, 1).unwrap();
+/// assert_eq!(save.score, 7);
+/// let newer = versioned::from_json::<Save>(r#"{"version": 2, "score": 7}"#, 1);
+/// assert!(matches!(newer, Err(VersionError::Unsupported(2))));
+/// ```
+pub fn from_json<T: DeserializeOwned>(json: &str, newest: u32) -> Result<T, VersionError> {
+    let probe: VersionProbe = serde_json::from_str(json)?;
+    if probe.version == 0 || probe.version > newest {
+        return Err(VersionError::Unsupported(probe.version));
+    }
+    Ok(serde_json::from_str(json)?)
+}
+
+/// Why [`from_json`] could not read a file.
+#[derive(Debug)]
+pub enum VersionError {
+    /// The file is not valid JSON, has no version, or does not match `T`.
+    Format(serde_json::Error),
+    /// The file was written in a format version this build cannot read.
+    Unsupported(u32),
+}
+
+impl fmt::Display for VersionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VersionError::Format(e) => write!(f, "malformed file: {}", e),
+            VersionError::Unsupported(version) => write!(f, "format version {} is not supported", version),
+        }
+    }
+}
+
+impl Error for VersionError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            VersionError::Format(e) => Some(e),
+            VersionError::Unsupported(_) => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for VersionError {
+    fn from(e: serde_json::Error) -> Self {
+        VersionError::Format(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Save {
+        name: String,
+    }
+
+    #[test]
+    fn test_versions_in_range_are_read() {
+        for version in [1, 3] {
+            let json = format!(r#"{{"version": {}, "name": "ferris"}}"#, version);
+            assert_eq!(from_json::<Save>(&json, 3).unwrap(), Save { name: "ferris".to_string() });
+        }
+    }
+
+    #[test]
+    fn test_unreadable_files() {
+        let unsupported = |json: &str| match from_json::<Save>(json, 1) {
+            Err(VersionError::Unsupported(version)) => Some(version),
+            _ => None,
+        };
+        assert_eq!(unsupported(r#"{"version": 0, "name": "x"}"#), Some(0));
+        // Checked before the rest, which a newer format may have changed
+        assert_eq!(unsupported(r#"{"version": 2, "title": "x"}"#), Some(2));
+        for bad in ["", r#"{"name": "x"}"#, r#"{"version": 1}"#, r#"{"version": "1", "name": "x"}"#] {
+            assert!(matches!(from_json::<Save>(bad, 1), Err(VersionError::Format(_))), "{}", bad);
+        }
+    }
+}
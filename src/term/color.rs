@@ -0,0 +1,296 @@
+//! ANSI colors and text styles for terminal output.
+//!
+//! A [`Style`] is built up from colors and attributes and then wraps a
+//! value with [`Style::paint`], giving a [`Colored`] that prints the value
+//! between escape codes. Whether the codes are written is decided once per
+//! stream: never when `NO_COLOR` is set or `TERM` is `dumb`, always when
+//! `CLICOLOR_FORCE` is set, and otherwise only when the stream is a
+//! terminal, so piping output to a file or `grep` keeps it plain.
+//!
+//! ```
+//! use rustler::term::color::{Color, Style};
+//!
+//! let warning = Style::new().fg(Color::Yellow).bold();
+//! let text = warning.paint("careful").enabled(true).to_string();
+//! assert_eq!(text, "\x1b[1;33mcareful\x1b[0m");
+//! assert_eq!(warning.paint("careful").enabled(false).to_string(), "careful");
+//! ```
+
+use std::env;
+use std::ffi::OsString;
+use std::fmt::{self, Display};
+use std::io::{self, IsTerminal};
+use std::sync::OnceLock;
+
+/// A terminal color: one of the eight standard colors, their bright
+/// variants, an entry in the 256-color palette or a 24-bit RGB value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite,
+    Fixed(u8),
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    /// The SGR parameters for this color, as foreground (`base` 30) or
+    /// background (`base` 40).
+    fn code(self, base: u8) -> String {
+        let standard = |n: u8| (base + n).to_string();
+        let bright = |n: u8| (base + 60 + n).to_string();
+        match self {
+            Color::Black => standard(0),
+            Color::Red => standard(1),
+            Color::Green => standard(2),
+            Color::Yellow => standard(3),
+            Color::Blue => standard(4),
+            Color::Magenta => standard(5),
+            Color::Cyan => standard(6),
+            Color::White => standard(7),
+            Color::BrightBlack => bright(0),
+            Color::BrightRed => bright(1),
+            Color::BrightGreen => bright(2),
+            Color::BrightYellow => bright(3),
+            Color::BrightBlue => bright(4),
+            Color::BrightMagenta => bright(5),
+            Color::BrightCyan => bright(6),
+            Color::BrightWhite => bright(7),
+            Color::Fixed(n) => format!("{};5;{}", base + 8, n),
+            Color::Rgb(r, g, b) => format!("{};2;{};{};{}", base + 8, r, g, b),
+        }
+    }
+}
+
+/// Colors and attributes to apply to some text. The builder methods are
+/// `const`, so styles can be constants.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Style {
+    fg: Option<Color>,
+    bg: Option<Color>,
+    bold: bool,
+    dim: bool,
+    italic: bool,
+    underline: bool,
+}
+
+/// The escape sequence that resets all styles.
+pub const RESET: &str = "\x1b[0m";
+
+/// Section headings.
+pub const HEADING: Style = Style::new().fg(Color::Cyan).bold();
+/// The `error:` in front of error messages.
+pub const ERROR: Style = Style::new().fg(Color::Red).bold();
+/// The `warning:` in front of warnings.
+pub const WARNING: Style = Style::new().fg(Color::Yellow).bold();
+/// Things that went well.
+pub const SUCCESS: Style = Style::new().fg(Color::Green);
+/// Secondary details such as line numbers.
+pub const DIM: Style = Style::new().dim();
+
+impl Style {
+    /// No colors or attributes: painted text prints unchanged.
+    pub const fn new() -> Self {
+        Style {
+            fg: None,
+            bg: None,
+            bold: false,
+            dim: false,
+            italic: false,
+            underline: false,
+        }
+    }
+
+    pub const fn fg(mut self, color: Color) -> Self {
+        self.fg = Some(color);
+        self
+    }
+
+    pub const fn bg(mut self, color: Color) -> Self {
+        self.bg = Some(color);
+        self
+    }
+
+    pub const fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    pub const fn dim(mut self) -> Self {
+        self.dim = true;
+        self
+    }
+
+    pub const fn italic(mut self) -> Self {
+        self.italic = true;
+        self
+    }
+
+    pub const fn underline(mut self) -> Self {
+        self.underline = true;
+        self
+    }
+
+    pub fn is_plain(&self) -> bool {
+        *self == Style::new()
+    }
+
+    /// The escape sequence that turns this style on, such as `\x1b[1;31m`,
+    /// or nothing for a plain style.
+    pub fn prefix(&self) -> String {
+        let mut codes = Vec::new();
+        for (on, code) in [(self.bold, "1"), (self.dim, "2"), (self.italic, "3"), (self.underline, "4")] {
+            if on {
+                codes.push(code.to_string());
+            }
+        }
+        codes.extend(self.fg.map(|color| color.code(30)));
+        codes.extend(self.bg.map(|color| color.code(40)));
+        if codes.is_empty() {
+            String::new()
+        } else {
+            format!("\x1b[{}m", codes.join(";"))
+        }
+    }
+
+    /// Wraps `value` for printing to standard output, colored if stdout
+    /// takes color.
+    pub fn paint<T: Display>(self, value: T) -> Colored<T> {
+        Colored {
+            value,
+            style: self,
+            enabled: enabled(Stream::Stdout),
+        }
+    }
+
+    /// [`paint`](Style::paint) for standard error.
+    pub fn paint_stderr<T: Display>(self, value: T) -> Colored<T> {
+        Colored {
+            value,
+            style: self,
+            enabled: enabled(Stream::Stderr),
+        }
+    }
+}
+
+/// A value that displays in a [`Style`]. Width, fill and precision given
+/// in the format string apply to the value inside the escape codes, so
+/// `{:<10}` still lines up columns.
+#[derive(Debug, Clone, Copy)]
+pub struct Colored<T> {
+    value: T,
+    style: Style,
+    enabled: bool,
+}
+
+impl<T> Colored<T> {
+    /// Overrides the detected setting, to force color on or off.
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+}
+
+impl<T: Display> Display for Colored<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if !self.enabled || self.style.is_plain() {
+            return self.value.fmt(f);
+        }
+        f.write_str(&self.style.prefix())?;
+        self.value.fmt(f)?;
+        f.write_str(RESET)
+    }
+}
+
+/// An output stream whose color support can be checked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+/// Whether to write escape codes to `stream`. Checked once per stream and
+/// remembered.
+pub fn enabled(stream: Stream) -> bool {
+    static STDOUT: OnceLock<bool> = OnceLock::new();
+    static STDERR: OnceLock<bool> = OnceLock::new();
+    let (cell, is_terminal): (_, fn() -> bool) = match stream {
+        Stream::Stdout => (&STDOUT, || io::stdout().is_terminal()),
+        Stream::Stderr => (&STDERR, || io::stderr().is_terminal()),
+    };
+    *cell.get_or_init(|| {
+        decide(
+            env::var_os("NO_COLOR"),
+            env::var_os("CLICOLOR_FORCE"),
+            env::var_os("TERM"),
+            is_terminal(),
+        )
+    })
+}
+
+/// The detection rules, apart from the environment. Following
+/// no-color.org, `NO_COLOR` counts when it is set to anything non-empty;
+/// `CLICOLOR_FORCE=0` means not to force.
+fn decide(no_color: Option<OsString>, force: Option<OsString>, term: Option<OsString>, is_terminal: bool) -> bool {
+    if no_color.is_some_and(|value| !value.is_empty()) {
+        return false;
+    }
+    if force.is_some_and(|value| !value.is_empty() && value != "0") {
+        return true;
+    }
+    is_terminal && term.is_none_or(|term| term != "dumb")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefix_codes() {
+        assert_eq!(Style::new().prefix(), "");
+        assert_eq!(Style::new().fg(Color::Red).prefix(), "\x1b[31m");
+        assert_eq!(Style::new().bg(Color::BrightBlue).prefix(), "\x1b[104m");
+        assert_eq!(
+            Style::new().bold().underline().fg(Color::Fixed(208)).bg(Color::Rgb(1, 2, 3)).prefix(),
+            "\x1b[1;4;38;5;208;48;2;1;2;3m"
+        );
+        assert_eq!(HEADING.prefix(), "\x1b[1;36m");
+        assert_eq!(DIM.prefix(), "\x1b[2m");
+    }
+
+    #[test]
+    fn test_colored_keeps_padding_inside_the_codes() {
+        let cell = SUCCESS.paint("ok").enabled(true);
+        assert_eq!(format!("[{:<4}]", cell), "[\x1b[32mok  \x1b[0m]");
+        assert_eq!(format!("[{:>4}]", cell.enabled(false)), "[  ok]");
+        assert_eq!(Style::new().paint(7).enabled(true).to_string(), "7");
+    }
+
+    #[test]
+    fn test_detection_rules() {
+        let some = |s: &str| Some(OsString::from(s));
+        assert!(decide(None, None, some("xterm-256color"), true));
+        assert!(decide(None, None, None, true));
+        assert!(!decide(None, None, some("xterm"), false));
+        assert!(!decide(None, None, some("dumb"), true));
+        assert!(!decide(some("1"), None, some("xterm"), true));
+        assert!(!decide(some("0"), None, some("xterm"), true));
+        assert!(!decide(some("1"), some("1"), None, true));
+        assert!(decide(some(""), None, None, true));
+        assert!(decide(None, some("1"), None, false));
+        assert!(!decide(None, some("0"), None, false));
+    }
+}
@@ -0,0 +1,9 @@
+//! Helpers for programs that talk to a terminal.
+
+pub mod color;
+pub mod progress;
+pub mod table;
+
+pub use color::{Color, Colored, Style};
+pub use progress::{ProgressBar, Spinner};
+pub use table::Table;
@@ -0,0 +1,389 @@
+//! Progress bars and spinners that redraw a single terminal line.
+//!
+//! A [`ProgressBar`] is for work of known size: it shows how much is done,
+//! the rate and an estimate of the time left. A [`Spinner`] is for work
+//! whose size isn't known, like waiting on a compiler. Both return to the
+//! start of the line with `\r` and draw over it.
+//!
+//! That only makes sense on a terminal. When stdout is redirected to a file
+//! or a pipe, they draw nothing while running and print one plain line when
+//! they finish, so logs don't fill up with carriage returns.
+//!
+//! ```
+//! use rustler::term::ProgressBar;
+//!
+//! let bar = ProgressBar::new(3).label("copying").interactive(false);
+//! for _ in 0..3 {
+//!     bar.inc(1);
+//! }
+//! assert_eq!(bar.position(), 3);
+//! bar.finish(); // prints "copying: 3/3 in 0.0s"
+//! ```
+
+use std::io::{self, IsTerminal, Write};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// The least time between redraws, so a fast loop doesn't spend its time
+/// writing to the terminal.
+const REDRAW_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How often a spinner moves on to its next frame.
+const SPIN_INTERVAL: Duration = Duration::from_millis(80);
+
+const SPINNER_FRAMES: [char; 4] = ['|', '/', '-', '\\'];
+
+/// Clears from the cursor to the end of the line, in case the new line is
+/// shorter than the last.
+const CLEAR_LINE: &str = "\x1b[K";
+
+/// A bar for work with a known total. Updates take `&self`, so threads can
+/// share one bar, such as the workers of a parallel map.
+#[derive(Debug)]
+pub struct ProgressBar {
+    total: u64,
+    position: AtomicU64,
+    label: String,
+    width: usize,
+    interactive: bool,
+    start: Instant,
+    /// When the bar was last drawn; the lock also keeps two threads from
+    /// drawing at once.
+    drawn: Mutex<Option<Instant>>,
+    finished: AtomicBool,
+}
+
+impl ProgressBar {
+    /// A bar from 0 to `total`, drawn only when stdout is a terminal.
+    pub fn new(total: u64) -> Self {
+        ProgressBar {
+            total,
+            position: AtomicU64::new(0),
+            label: String::new(),
+            width: 30,
+            interactive: io::stdout().is_terminal(),
+            start: Instant::now(),
+            drawn: Mutex::new(None),
+            finished: AtomicBool::new(false),
+        }
+    }
+
+    /// Text shown before the bar.
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = label.into();
+        self
+    }
+
+    /// The width of the bar itself in characters, without the counts.
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Overrides the terminal check, to force redrawing on or off.
+    pub fn interactive(mut self, interactive: bool) -> Self {
+        self.interactive = interactive;
+        self
+    }
+
+    pub fn inc(&self, n: u64) {
+        self.position.fetch_add(n, Ordering::Relaxed);
+        self.draw(false);
+    }
+
+    pub fn set_position(&self, position: u64) {
+        self.position.store(position, Ordering::Relaxed);
+        self.draw(false);
+    }
+
+    pub fn position(&self) -> u64 {
+        self.position.load(Ordering::Relaxed)
+    }
+
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// How much is done, from 0.0 to 1.0. A total of 0 counts as done.
+    pub fn fraction(&self) -> f64 {
+        fraction(self.position(), self.total)
+    }
+
+    /// Items per second since the bar was created.
+    pub fn rate(&self) -> f64 {
+        rate(self.position(), self.start.elapsed())
+    }
+
+    /// The time left at the rate so far, or `None` before anything is done
+    /// or when the estimate is too long to hold in a `Duration`.
+    pub fn eta(&self) -> Option<Duration> {
+        eta(self.position(), self.total, self.start.elapsed())
+    }
+
+    /// Draws the bar a last time and moves to the next line, or prints the
+    /// summary line when stdout isn't a terminal. Later calls, and the one
+    /// made on drop, do nothing.
+    pub fn finish(&self) {
+        if self.finished.swap(true, Ordering::Relaxed) {
+            return;
+        }
+        let elapsed = self.start.elapsed();
+        if self.interactive {
+            self.draw(true);
+            // Also reached from drop, where a panic while unwinding aborts
+            let _ = writeln!(io::stdout());
+        } else {
+            let label = if self.label.is_empty() { "done".to_string() } else { self.label.clone() };
+            let line = format!("{}: {}/{} in {:.1}s", label, self.position(), self.total, elapsed.as_secs_f64());
+            let _ = writeln!(io::stdout(), "{}", line);
+        }
+    }
+
+    fn draw(&self, force: bool) {
+        if !self.interactive || self.finished.load(Ordering::Relaxed) && !force {
+            return;
+        }
+        let mut drawn = self.drawn.lock().unwrap();
+        let now = Instant::now();
+        if !force && drawn.is_some_and(|last| now - last < REDRAW_INTERVAL) {
+            return;
+        }
+        *drawn = Some(now);
+        let line = self.render(self.position(), now - self.start);
+        let mut out = io::stdout().lock();
+        // A closed stdout isn't worth failing the work over
+        let _ = write!(out, "\r{}{}", line, CLEAR_LINE).and_then(|_| out.flush());
+    }
+
+    /// The bar's line, such as `label [=====>    ]  5/10  50%  2.0/s  ETA 0:03`.
+    fn render(&self, position: u64, elapsed: Duration) -> String {
+        let position = position.min(self.total);
+        let fraction = fraction(position, self.total);
+        let filled = (fraction * self.width as f64) as usize;
+        let mut bar = "=".repeat(filled);
+        if filled < self.width {
+            bar.push(if position > 0 { '>' } else { ' ' });
+            bar.push_str(&" ".repeat(self.width - filled - 1));
+        }
+        let eta = match eta(position, self.total, elapsed) {
+            Some(eta) => clock(eta),
+            None => "-:--".to_string(),
+        };
+        let digits = self.total.to_string().len();
+        let label = if self.label.is_empty() { String::new() } else { format!("{} ", self.label) };
+        format!(
+            "{}[{}] {:>digits$}/{}  {:>3}%  {}/s  ETA {}",
+            label,
+            bar,
+            position,
+            self.total,
+            (fraction * 100.0) as u32,
+            format_rate(rate(position, elapsed)),
+            eta,
+        )
+    }
+}
+
+impl Drop for ProgressBar {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+fn fraction(position: u64, total: u64) -> f64 {
+    if total == 0 {
+        1.0
+    } else {
+        position.min(total) as f64 / total as f64
+    }
+}
+
+fn rate(position: u64, elapsed: Duration) -> f64 {
+    let secs = elapsed.as_secs_f64();
+    if secs == 0.0 {
+        0.0
+    } else {
+        position as f64 / secs
+    }
+}
+
+fn eta(position: u64, total: u64, elapsed: Duration) -> Option<Duration> {
+    if position >= total {
+        return Some(Duration::ZERO);
+    }
+    let rate = rate(position, elapsed);
+    // A rate slow enough against a big enough total overflows a Duration
+    if rate > 0.0 {
+        Duration::try_from_secs_f64((total - position) as f64 / rate).ok()
+    } else {
+        None
+    }
+}
+
+/// One decimal below 10 per second, whole numbers above.
+fn format_rate(rate: f64) -> String {
+    if rate < 10.0 {
+        format!("{:.1}", rate)
+    } else {
+        format!("{:.0}", rate)
+    }
+}
+
+/// `m:ss`, or `h:mm:ss` from an hour up.
+fn clock(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+    } else {
+        format!("{}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+/// A spinner for work of unknown length, turning on a background thread
+/// until [`finish`](Spinner::finish).
+///
+/// ```no_run
+/// use rustler::term::Spinner;
+///
+/// let spinner = Spinner::start("Compiling");
+/// // ... slow work ...
+/// spinner.finish("Compiled");
+/// ```
+#[derive(Debug)]
+pub struct Spinner {
+    message: Arc<Mutex<String>>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Spinner {
+    /// Starts spinning next to `message`. When stdout isn't a terminal the
+    /// message is printed once instead.
+    pub fn start(message: impl Into<String>) -> Self {
+        Spinner::start_with(message, io::stdout().is_terminal())
+    }
+
+    /// [`start`](Spinner::start) with the terminal check overridden.
+    pub fn start_with(message: impl Into<String>, interactive: bool) -> Self {
+        let message = Arc::new(Mutex::new(message.into()));
+        let stop = Arc::new(AtomicBool::new(false));
+        if !interactive {
+            let _ = writeln!(io::stdout(), "{}...", message.lock().unwrap());
+            return Spinner { message, stop, thread: None };
+        }
+        let thread = {
+            let message = Arc::clone(&message);
+            let stop = Arc::clone(&stop);
+            thread::spawn(move || {
+                let start = Instant::now();
+                let mut tick = 0;
+                while !stop.load(Ordering::Relaxed) {
+                    let line = spinner_line(tick, &message.lock().unwrap(), start.elapsed());
+                    let mut out = io::stdout().lock();
+                    let _ = write!(out, "\r{}{}", line, CLEAR_LINE).and_then(|_| out.flush());
+                    drop(out);
+                    tick += 1;
+                    // Woken early by finish
+                    thread::park_timeout(SPIN_INTERVAL);
+                }
+            })
+        };
+        Spinner {
+            message,
+            stop,
+            thread: Some(thread),
+        }
+    }
+
+    /// Changes the message next to the spinner, to say which step the
+    /// work is on.
+    pub fn set_message(&self, message: impl Into<String>) {
+        *self.message.lock().unwrap() = message.into();
+    }
+
+    /// Stops the spinner and replaces its line with `message`.
+    pub fn finish(mut self, message: impl std::fmt::Display) {
+        self.stop_thread();
+        let _ = writeln!(io::stdout(), "{}", message);
+    }
+
+    /// Stops the spinner and clears its line, leaving nothing behind.
+    pub fn clear(mut self) {
+        self.stop_thread();
+    }
+
+    fn stop_thread(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            thread.thread().unpark();
+            let _ = thread.join();
+            let mut out = io::stdout().lock();
+            let _ = write!(out, "\r{}", CLEAR_LINE).and_then(|_| out.flush());
+        }
+    }
+}
+
+impl Drop for Spinner {
+    fn drop(&mut self) {
+        self.stop_thread();
+    }
+}
+
+/// A spinner frame, such as `/ Compiling (3s)`.
+fn spinner_line(tick: usize, message: &str, elapsed: Duration) -> String {
+    format!("{} {} ({}s)", SPINNER_FRAMES[tick % SPINNER_FRAMES.len()], message, elapsed.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let bar = ProgressBar::new(10).width(10).interactive(false);
+        assert_eq!(bar.render(0, Duration::ZERO), "[          ]  0/10    0%  0.0/s  ETA -:--");
+        assert_eq!(bar.render(5, Duration::from_secs(2)), "[=====>    ]  5/10   50%  2.5/s  ETA 0:02");
+        let bar = bar.label("copy");
+        assert_eq!(bar.render(10, Duration::from_secs(1)), "copy [==========] 10/10  100%  10/s  ETA 0:00");
+        // Overshooting is shown as done
+        assert_eq!(bar.render(12, Duration::from_secs(1)), bar.render(10, Duration::from_secs(1)));
+        // An estimate past Duration::MAX is shown as unknown rather than panicking
+        let huge = ProgressBar::new(u64::MAX).width(1).interactive(false);
+        assert!(huge.render(1, Duration::from_secs(2)).ends_with("ETA -:--"));
+    }
+
+    #[test]
+    fn test_rate_and_eta() {
+        assert_eq!(rate(30, Duration::from_secs(3)), 10.0);
+        assert_eq!(rate(30, Duration::ZERO), 0.0);
+        assert_eq!(eta(25, 100, Duration::from_secs(5)), Some(Duration::from_secs(15)));
+        assert_eq!(eta(0, 100, Duration::from_secs(5)), None);
+        assert_eq!(eta(100, 100, Duration::from_secs(5)), Some(Duration::ZERO));
+        assert_eq!(eta(1, u64::MAX, Duration::from_secs(2)), None);
+        assert_eq!(clock(Duration::from_secs(75)), "1:15");
+        assert_eq!(clock(Duration::from_secs(3723)), "1:02:03");
+        assert_eq!(format_rate(2.345), "2.3");
+        assert_eq!(format_rate(123.6), "124");
+    }
+
+    #[test]
+    fn test_shared_between_threads() {
+        let bar = ProgressBar::new(400).interactive(false);
+        thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| (0..100).for_each(|_| bar.inc(1)));
+            }
+        });
+        assert_eq!(bar.position(), 400);
+        assert_eq!(bar.fraction(), 1.0);
+        assert_eq!(ProgressBar::new(0).interactive(false).fraction(), 1.0);
+    }
+
+    #[test]
+    fn test_spinner_frames() {
+        assert_eq!(spinner_line(0, "Working", Duration::from_millis(2500)), "| Working (2s)");
+        assert_eq!(spinner_line(5, "Working", Duration::ZERO), "/ Working (0s)");
+    }
+}
@@ -0,0 +1,331 @@
+//! Tables of text with columns sized to fit.
+//!
+//! A [`Table`] has a header row and any number of rows. Each column is as
+//! wide as its widest cell, measured in terminal columns with
+//! [`display_width`], so CJK text and emoji line up. Columns can be
+//! aligned, capped at a width (longer cells end in `…`), and the whole
+//! table drawn with box-drawing lines, ASCII, or no borders at all.
+//!
+//! ```
+//! use rustler::term::table::{Align, Border, Table};
+//!
+//! let mut table = Table::new(["Name", "Age"]).border(Border::Ascii).align(1, Align::Right);
+//! table.add_row(["Ferris", "8"]);
+//! table.add_row(["Corro", "12"]);
+//! assert_eq!(table.to_string(), "\
+//! +--------+-----+
+//! | Name   | Age |
+//! +--------+-----+
+//! | Ferris |   8 |
+//! | Corro  |  12 |
+//! +--------+-----+
+//! ");
+//! ```
+
+use std::fmt::{self, Display};
+
+use super::color::Style;
+use crate::text::{display_width, truncate_to_width};
+
+/// Where a cell's text goes when the column is wider than it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Align {
+    #[default]
+    Left,
+    Right,
+    Center,
+}
+
+/// How the table's edges and the line under the header are drawn.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Border {
+    /// `┌─┬─┐` box-drawing lines.
+    #[default]
+    Box,
+    /// `+-+-+` for terminals and fonts without box-drawing characters.
+    Ascii,
+    /// No frame: columns two spaces apart and a rule under the header.
+    None,
+}
+
+/// The characters for one border style: the left, middle and right piece
+/// of the top, header rule and bottom lines, then the horizontal and
+/// vertical lines.
+struct Lines {
+    top: [&'static str; 3],
+    rule: [&'static str; 3],
+    bottom: [&'static str; 3],
+    horizontal: &'static str,
+    vertical: &'static str,
+}
+
+const BOX: Lines = Lines {
+    top: ["┌", "┬", "┐"],
+    rule: ["├", "┼", "┤"],
+    bottom: ["└", "┴", "┘"],
+    horizontal: "─",
+    vertical: "│",
+};
+
+const ASCII: Lines = Lines {
+    top: ["+", "+", "+"],
+    rule: ["+", "+", "+"],
+    bottom: ["+", "+", "+"],
+    horizontal: "-",
+    vertical: "|",
+};
+
+#[derive(Debug, Clone)]
+struct Row {
+    cells: Vec<String>,
+    style: Style,
+}
+
+/// A table of text, rendered by its `Display` impl.
+#[derive(Debug, Clone)]
+pub struct Table {
+    headers: Vec<String>,
+    rows: Vec<Row>,
+    align: Vec<Align>,
+    max_width: Vec<Option<usize>>,
+    border: Border,
+    header_style: Style,
+}
+
+impl Table {
+    /// A table with these column headings and box-drawing borders.
+    pub fn new<I>(headers: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        Table {
+            headers: headers.into_iter().map(Into::into).collect(),
+            rows: Vec::new(),
+            align: Vec::new(),
+            max_width: Vec::new(),
+            border: Border::Box,
+            header_style: Style::new().bold(),
+        }
+    }
+
+    pub fn border(mut self, border: Border) -> Self {
+        self.border = border;
+        self
+    }
+
+    /// Aligns column `column`, counting from 0. Columns are left-aligned
+    /// unless set otherwise; headings follow their column.
+    pub fn align(mut self, column: usize, align: Align) -> Self {
+        set(&mut self.align, column, align);
+        self
+    }
+
+    /// Caps column `column` at `width` terminal columns, truncating longer
+    /// cells with `…`.
+    pub fn max_width(mut self, column: usize, width: usize) -> Self {
+        set(&mut self.max_width, column, Some(width));
+        self
+    }
+
+    /// The style of the header row; bold unless set otherwise.
+    pub fn header_style(mut self, style: Style) -> Self {
+        self.header_style = style;
+        self
+    }
+
+    /// Adds a row. A row shorter than the headers is filled out with empty
+    /// cells, and one longer adds columns with empty headings.
+    pub fn add_row<I>(&mut self, cells: I) -> &mut Self
+    where
+        I: IntoIterator,
+        I::Item: Display,
+    {
+        self.add_styled_row(cells, Style::new())
+    }
+
+    /// [`add_row`](Table::add_row) with the row's text painted in `style`,
+    /// such as dimmed for finished items. The borders stay plain.
+    pub fn add_styled_row<I>(&mut self, cells: I, style: Style) -> &mut Self
+    where
+        I: IntoIterator,
+        I::Item: Display,
+    {
+        let cells = cells.into_iter().map(|cell| cell.to_string()).collect();
+        self.rows.push(Row { cells, style });
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    fn columns(&self) -> usize {
+        self.rows.iter().map(|row| row.cells.len()).chain([self.headers.len()]).max().unwrap_or(0)
+    }
+
+    /// A cell's text after truncation, or "" past the end of a short row.
+    fn cell(&self, cells: &[String], column: usize) -> String {
+        let text = cells.get(column).map(String::as_str).unwrap_or("");
+        // Tabs and newlines would break the layout
+        let text = text.replace(['\t', '\n', '\r'], " ");
+        match self.max_width.get(column).copied().flatten() {
+            Some(max) => truncate_to_width(&text, max),
+            None => text,
+        }
+    }
+
+    fn widths(&self) -> Vec<usize> {
+        (0..self.columns())
+            .map(|column| {
+                self.rows
+                    .iter()
+                    .map(|row| &row.cells)
+                    .chain([&self.headers])
+                    .map(|cells| display_width(&self.cell(cells, column)))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect()
+    }
+
+    fn write_row(&self, f: &mut fmt::Formatter, cells: &[String], style: Style, widths: &[usize]) -> fmt::Result {
+        let padded: Vec<String> = widths
+            .iter()
+            .enumerate()
+            .map(|(column, &width)| {
+                let text = self.cell(cells, column);
+                let align = self.align.get(column).copied().unwrap_or_default();
+                style.paint(pad(&text, width, align)).to_string()
+            })
+            .collect();
+        match self.border {
+            Border::None => writeln!(f, "{}", padded.join("  ").trim_end()),
+            Border::Box | Border::Ascii => {
+                let v = self.lines().vertical;
+                writeln!(f, "{} {} {}", v, padded.join(&format!(" {} ", v)), v)
+            }
+        }
+    }
+
+    fn lines(&self) -> &'static Lines {
+        match self.border {
+            Border::Ascii => &ASCII,
+            _ => &BOX,
+        }
+    }
+}
+
+impl Display for Table {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let widths = self.widths();
+        let framed = self.border != Border::None;
+        let lines = self.lines();
+        let line = |[left, middle, right]: [&str; 3]| {
+            let segments: Vec<String> = widths.iter().map(|&w| lines.horizontal.repeat(w + 2)).collect();
+            format!("{}{}{}", left, segments.join(middle), right)
+        };
+
+        if framed {
+            writeln!(f, "{}", line(lines.top))?;
+        }
+        self.write_row(f, &self.headers, self.header_style, &widths)?;
+        if framed {
+            writeln!(f, "{}", line(lines.rule))?;
+        } else {
+            let rules: Vec<String> = widths.iter().map(|&w| "-".repeat(w)).collect();
+            writeln!(f, "{}", rules.join("  "))?;
+        }
+        for row in &self.rows {
+            self.write_row(f, &row.cells, row.style, &widths)?;
+        }
+        if framed {
+            writeln!(f, "{}", line(lines.bottom))?;
+        }
+        Ok(())
+    }
+}
+
+/// Sets `values[index]`, growing `values` with defaults to reach it.
+fn set<T: Default + Clone>(values: &mut Vec<T>, index: usize, value: T) {
+    if values.len() <= index {
+        values.resize(index + 1, T::default());
+    }
+    values[index] = value;
+}
+
+/// Pads `text` with spaces to `width` terminal columns.
+fn pad(text: &str, width: usize, align: Align) -> String {
+    let padding = width.saturating_sub(display_width(text));
+    let (left, right) = match align {
+        Align::Left => (0, padding),
+        Align::Right => (padding, 0),
+        Align::Center => (padding / 2, padding - padding / 2),
+    };
+    format!("{}{}{}", " ".repeat(left), text, " ".repeat(right))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_box_borders_and_alignment() {
+        let mut table = Table::new(["Item", "Qty", "Note"]).align(1, Align::Right).align(2, Align::Center);
+        table.add_row(["apple", "3", "ok"]);
+        table.add_row(["kiwi", "12", "ripe"]);
+        assert_eq!(
+            table.to_string(),
+            "\
+┌───────┬─────┬──────┐
+│ Item  │ Qty │ Note │
+├───────┼─────┼──────┤
+│ apple │   3 │  ok  │
+│ kiwi  │  12 │ ripe │
+└───────┴─────┴──────┘
+"
+        );
+    }
+
+    #[test]
+    fn test_wide_characters_line_up() {
+        let mut table = Table::new(["Word", "Meaning"]).border(Border::Ascii);
+        table.add_row(["日本", "Japan"]);
+        table.add_row(["🦀", "crab"]);
+        table.add_row(["cafe\u{301}", "café"]);
+        let text = table.to_string();
+        // Every line is the same number of columns wide
+        let widths: Vec<usize> = text.lines().map(display_width).collect();
+        assert!(widths.iter().all(|&w| w == widths[0]), "{}", text);
+        assert!(text.contains("| 日本 | Japan   |"));
+        assert!(text.contains("| 🦀   | crab    |"));
+    }
+
+    #[test]
+    fn test_truncation_and_ragged_rows() {
+        let mut table = Table::new(["Title"]).border(Border::None).max_width(0, 8);
+        table.add_row(["A rather long title"]);
+        table.add_row(["Short", "extra"]);
+        table.add_row(Vec::<String>::new());
+        assert_eq!(table.len(), 3);
+        assert_eq!(table.to_string(), "Title\n--------  -----\nA rathe…\nShort     extra\n\n");
+    }
+
+    #[test]
+    fn test_cells_with_line_breaks_stay_on_one_line() {
+        let mut table = Table::new(["a"]).border(Border::None);
+        table.add_row(["one\ntwo"]);
+        assert_eq!(table.to_string(), "a\n-------\none two\n");
+    }
+
+    #[test]
+    fn test_empty_table() {
+        let table = Table::new(["Only", "Headers"]).border(Border::Ascii);
+        assert!(table.is_empty());
+        assert_eq!(table.to_string(), "+------+---------+\n| Only | Headers |\n+------+---------+\n+------+---------+\n");
+    }
+}
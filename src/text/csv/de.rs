@@ -0,0 +1,143 @@
+//! Turns a record into a serde value, using the header row for field names.
+//!
+//! A row looks like a map from column name to field, so it can fill a
+//! struct or a `HashMap`. Each field is text and gets parsed into whatever
+//! type the struct asks for; an empty field is `None` for an `Option`.
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use serde::de::value::StrDeserializer;
+use serde::de::{self, DeserializeSeed, IntoDeserializer, MapAccess, Visitor};
+use serde::forward_to_deserialize_any;
+
+use super::{Record, SerdeMessage};
+
+pub(super) struct RowDeserializer<'a> {
+    pub(super) headers: &'a Record,
+    pub(super) record: &'a Record,
+}
+
+impl<'de> de::Deserializer<'de> for RowDeserializer<'_> {
+    type Error = SerdeMessage;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeMessage> {
+        visitor.visit_map(Fields {
+            pairs: self.headers.iter().zip(self.record.iter()),
+            current: None,
+        })
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+struct Fields<'a, I> {
+    pairs: I,
+    /// The column whose key was just handed out and whose value comes next.
+    current: Option<(&'a str, &'a str)>,
+}
+
+impl<'de, 'a, I: Iterator<Item = (&'a str, &'a str)>> MapAccess<'de> for Fields<'a, I> {
+    type Error = SerdeMessage;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, SerdeMessage> {
+        let Some((column, field)) = self.pairs.next() else {
+            return Ok(None);
+        };
+        self.current = Some((column, field));
+        let key: StrDeserializer<SerdeMessage> = column.into_deserializer();
+        seed.deserialize(key).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, SerdeMessage> {
+        let (column, field) = self.current.take().expect("serde asks for a key before its value");
+        seed.deserialize(FieldDeserializer { column, field })
+    }
+}
+
+struct FieldDeserializer<'a> {
+    column: &'a str,
+    field: &'a str,
+}
+
+impl FieldDeserializer<'_> {
+    fn parse<T: FromStr>(&self) -> Result<T, SerdeMessage>
+    where
+        T::Err: Display,
+    {
+        self.field.trim().parse().map_err(|e| {
+            SerdeMessage(format!(
+                "column `{}`: cannot read {:?} as {}: {}",
+                self.column,
+                self.field,
+                std::any::type_name::<T>(),
+                e
+            ))
+        })
+    }
+}
+
+macro_rules! parse_as {
+    ($($method:ident => $visit:ident($ty:ty)),* $(,)?) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeMessage> {
+                visitor.$visit(self.parse::<$ty>()?)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for FieldDeserializer<'_> {
+    type Error = SerdeMessage;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeMessage> {
+        visitor.visit_str(self.field)
+    }
+
+    parse_as! {
+        deserialize_bool => visit_bool(bool),
+        deserialize_i8 => visit_i8(i8),
+        deserialize_i16 => visit_i16(i16),
+        deserialize_i32 => visit_i32(i32),
+        deserialize_i64 => visit_i64(i64),
+        deserialize_u8 => visit_u8(u8),
+        deserialize_u16 => visit_u16(u16),
+        deserialize_u32 => visit_u32(u32),
+        deserialize_u64 => visit_u64(u64),
+        deserialize_f32 => visit_f32(f32),
+        deserialize_f64 => visit_f64(f64),
+        deserialize_char => visit_char(char),
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeMessage> {
+        if self.field.is_empty() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, SerdeMessage> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    /// Unit variants only: the field holds the variant's name.
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SerdeMessage> {
+        let variant: StrDeserializer<SerdeMessage> = self.field.trim().into_deserializer();
+        visitor.visit_enum(variant)
+    }
+
+    forward_to_deserialize_any! {
+        i128 u128 str string bytes byte_buf unit unit_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
@@ -0,0 +1,646 @@
+//! CSV reading and writing.
+//!
+//! Follows RFC 4180: fields may be wrapped in double quotes, and a quoted
+//! field can contain the delimiter, line breaks, and doubled quotes (`""`).
+//! Splitting lines on `,` gets all of those cases wrong.
+//!
+//! With a header row, [`Reader::deserialize`] fills structs by matching
+//! column names to field names, and [`Writer::serialize`] writes structs
+//! back out, so data files never need picking apart by hand.
+
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::marker::PhantomData;
+use std::ops::Index;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+mod de;
+mod ser;
+
+/// One row of a CSV file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Record {
+    fields: Vec<String>,
+}
+
+impl Record {
+    pub fn new(fields: Vec<String>) -> Self {
+        Record { fields }
+    }
+
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.fields.get(index).map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.fields.iter().map(String::as_str)
+    }
+
+    pub fn into_fields(self) -> Vec<String> {
+        self.fields
+    }
+}
+
+impl Index<usize> for Record {
+    type Output = str;
+
+    fn index(&self, index: usize) -> &str {
+        &self.fields[index]
+    }
+}
+
+impl From<Vec<String>> for Record {
+    fn from(fields: Vec<String>) -> Self {
+        Record::new(fields)
+    }
+}
+
+impl From<Vec<&str>> for Record {
+    fn from(fields: Vec<&str>) -> Self {
+        Record::new(fields.into_iter().map(String::from).collect())
+    }
+}
+
+/// Errors from reading CSV data.
+#[derive(Debug)]
+pub enum CsvError {
+    Io(io::Error),
+    /// A quoted field was still open at the end of the input.
+    UnterminatedQuote { line: usize },
+    /// A quote appeared in the middle of an unquoted field, or a closing
+    /// quote was followed by something other than a delimiter.
+    UnexpectedQuote { line: usize },
+    /// A record could not be turned into the requested type, for example
+    /// because a field does not parse as a number or a column is missing.
+    Deserialize { line: usize, message: String },
+    /// A value has no CSV form, such as a struct with a `Vec` field.
+    Serialize(String),
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CsvError::Io(e) => write!(f, "I/O error: {}", e),
+            CsvError::UnterminatedQuote { line } => {
+                write!(f, "quoted field starting on line {} is never closed", line)
+            }
+            CsvError::UnexpectedQuote { line } => write!(f, "unexpected quote on line {}", line),
+            CsvError::Deserialize { line, message } => write!(f, "record on line {}: {}", line, message),
+            CsvError::Serialize(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl Error for CsvError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CsvError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for CsvError {
+    fn from(e: io::Error) -> Self {
+        CsvError::Io(e)
+    }
+}
+
+/// The message carried by serde errors while mapping records, before the
+/// reader or writer wraps it in a [`CsvError`].
+#[derive(Debug)]
+struct SerdeMessage(String);
+
+impl fmt::Display for SerdeMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Error for SerdeMessage {}
+
+impl serde::de::Error for SerdeMessage {
+    fn custom<T: fmt::Display>(message: T) -> Self {
+        SerdeMessage(message.to_string())
+    }
+}
+
+impl serde::ser::Error for SerdeMessage {
+    fn custom<T: fmt::Display>(message: T) -> Self {
+        SerdeMessage(message.to_string())
+    }
+}
+
+/// Reads [`Record`]s from any [`Read`] source.
+///
+/// ```
+/// use rustler::text::csv::Reader;
+///
+/// let data = "name,quote\nFerris,\"Hello, world\"\n";
+/// let records = Reader::new(data.as_bytes()).read_all().unwrap();
+/// assert_eq!(records[1].get(1), Some("Hello, world"));
+/// ```
+///
+/// Typed rows come from [`deserialize`](Reader::deserialize):
+///
+/// ```
+/// use rustler::text::csv::Reader;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Crate {
+///     name: String,
+///     downloads: u64,
+///     license: Option<String>,
+/// }
+///
+/// let data = "downloads,name,license\n412000000,serde,MIT\n9000,rustler,\n";
+/// let crates: Vec<Crate> = Reader::new(data.as_bytes()).deserialize().collect::<Result<_, _>>().unwrap();
+/// assert_eq!(crates[0].name, "serde");
+/// assert_eq!(crates[0].downloads, 412_000_000);
+/// assert_eq!(crates[1].license, None);
+/// ```
+pub struct Reader<R> {
+    input: BufReader<R>,
+    delimiter: char,
+    line: usize,
+    /// The line the last record started on.
+    record_line: usize,
+    headers: Option<Record>,
+    buffer: String,
+}
+
+impl<R: Read> Reader<R> {
+    pub fn new(input: R) -> Self {
+        Reader {
+            input: BufReader::new(input),
+            delimiter: ',',
+            line: 0,
+            record_line: 0,
+            headers: None,
+            buffer: String::new(),
+        }
+    }
+
+    /// Uses a different field separator, such as `;` or `\t`.
+    pub fn with_delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Reads the next record, or `None` at the end of the input.
+    ///
+    /// Blank lines between records are skipped.
+    pub fn read_record(&mut self) -> Result<Option<Record>, CsvError> {
+        loop {
+            if !self.next_line()? {
+                return Ok(None);
+            }
+            if !self.buffer.trim_end_matches(['\r', '\n']).is_empty() {
+                break;
+            }
+        }
+
+        let start_line = self.line;
+        self.record_line = start_line;
+        let mut fields = Vec::new();
+        let mut field = String::new();
+        let mut in_quotes = false;
+        // Set after a closing quote, when only a delimiter or line end may follow
+        let mut after_quotes = false;
+
+        loop {
+            let line = std::mem::take(&mut self.buffer);
+            let mut chars = line.chars().peekable();
+            while let Some(c) = chars.next() {
+                if in_quotes {
+                    if c == '"' {
+                        if chars.peek() == Some(&'"') {
+                            chars.next();
+                            field.push('"');
+                        } else {
+                            in_quotes = false;
+                            after_quotes = true;
+                        }
+                    } else {
+                        field.push(c);
+                    }
+                } else if c == self.delimiter {
+                    fields.push(std::mem::take(&mut field));
+                    after_quotes = false;
+                } else if c == '\n' || (c == '\r' && chars.peek() == Some(&'\n')) {
+                    // The line ending is handled once the loop finishes
+                } else if after_quotes {
+                    return Err(CsvError::UnexpectedQuote { line: self.line });
+                } else if c == '"' {
+                    if !field.is_empty() {
+                        return Err(CsvError::UnexpectedQuote { line: self.line });
+                    }
+                    in_quotes = true;
+                } else {
+                    field.push(c);
+                }
+            }
+
+            if !in_quotes {
+                break;
+            }
+            // The quoted field continues on the next line
+            if !self.next_line()? {
+                return Err(CsvError::UnterminatedQuote { line: start_line });
+            }
+        }
+
+        fields.push(field);
+        Ok(Some(Record::new(fields)))
+    }
+
+    /// Reads every remaining record.
+    pub fn read_all(&mut self) -> Result<Vec<Record>, CsvError> {
+        self.records().collect()
+    }
+
+    /// Iterates over the remaining records.
+    pub fn records(&mut self) -> Records<'_, R> {
+        Records { reader: self }
+    }
+
+    /// The header row. The first call reads the next record as the headers;
+    /// an empty input has no columns.
+    pub fn headers(&mut self) -> Result<&Record, CsvError> {
+        if self.headers.is_none() {
+            let headers = self.read_record()?.unwrap_or_default();
+            self.headers = Some(headers);
+        }
+        Ok(self.headers.get_or_insert_with(Record::default))
+    }
+
+    /// Iterates over the remaining records as `T`s, matching each field to
+    /// the column with the same name in the header row.
+    ///
+    /// Fields are parsed into the type `T` asks for. An empty field is
+    /// `None` for an `Option`, and an enum field holds a variant name.
+    /// Columns `T` does not mention are ignored.
+    pub fn deserialize<T: DeserializeOwned>(&mut self) -> DeserializeRecords<'_, R, T> {
+        DeserializeRecords {
+            reader: self,
+            _type: PhantomData,
+        }
+    }
+
+    fn next_line(&mut self) -> io::Result<bool> {
+        self.buffer.clear();
+        let read = self.input.read_line(&mut self.buffer)?;
+        if read > 0 {
+            self.line += 1;
+        }
+        Ok(read > 0)
+    }
+}
+
+/// Iterator returned by [`Reader::records`].
+pub struct Records<'a, R> {
+    reader: &'a mut Reader<R>,
+}
+
+impl<R: Read> Iterator for Records<'_, R> {
+    type Item = Result<Record, CsvError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.reader.read_record().transpose()
+    }
+}
+
+/// Iterator returned by [`Reader::deserialize`].
+pub struct DeserializeRecords<'a, R, T> {
+    reader: &'a mut Reader<R>,
+    _type: PhantomData<fn() -> T>,
+}
+
+impl<R: Read, T: DeserializeOwned> Iterator for DeserializeRecords<'_, R, T> {
+    type Item = Result<T, CsvError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Err(e) = self.reader.headers() {
+            return Some(Err(e));
+        }
+        let record = match self.reader.read_record() {
+            Ok(record) => record?,
+            Err(e) => return Some(Err(e)),
+        };
+        let headers = self.reader.headers.as_ref().expect("headers were read above");
+        let row = de::RowDeserializer {
+            headers,
+            record: &record,
+        };
+        Some(T::deserialize(row).map_err(|e| CsvError::Deserialize {
+            line: self.reader.record_line,
+            message: e.0,
+        }))
+    }
+}
+
+/// Writes records to any [`Write`] sink, quoting fields only when needed.
+///
+/// ```
+/// use rustler::text::csv::Writer;
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Score {
+///     player: String,
+///     points: u32,
+/// }
+///
+/// let mut writer = Writer::new(Vec::new());
+/// writer.serialize(&Score { player: "Ferris, the crab".to_string(), points: 42 }).unwrap();
+/// writer.serialize(&Score { player: "Corro".to_string(), points: 7 }).unwrap();
+/// let text = String::from_utf8(writer.into_inner()).unwrap();
+/// assert_eq!(text, "player,points\n\"Ferris, the crab\",42\nCorro,7\n");
+/// ```
+pub struct Writer<W: Write> {
+    output: W,
+    delimiter: char,
+    wrote_headers: bool,
+}
+
+impl<W: Write> Writer<W> {
+    pub fn new(output: W) -> Self {
+        Writer {
+            output,
+            delimiter: ',',
+            wrote_headers: false,
+        }
+    }
+
+    /// Uses a different field separator, such as `;` or `\t`.
+    pub fn with_delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Writes one record followed by a newline.
+    pub fn write_record<I, S>(&mut self, fields: I) -> io::Result<()>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut line = String::new();
+        for (i, field) in fields.into_iter().enumerate() {
+            if i > 0 {
+                line.push(self.delimiter);
+            }
+            line.push_str(&escape_field(field.as_ref(), self.delimiter));
+        }
+        line.push('\n');
+        self.output.write_all(line.as_bytes())
+    }
+
+    /// Writes a struct as one record. The first call also writes a header
+    /// row of the struct's field names.
+    ///
+    /// `None` is written as an empty field and a unit enum variant as its
+    /// name, matching what [`Reader::deserialize`] expects.
+    pub fn serialize<T: Serialize>(&mut self, record: &T) -> Result<(), CsvError> {
+        let fields = match record.serialize(ser::CellSerializer) {
+            Ok(ser::Cell::Row(fields)) => fields,
+            Ok(ser::Cell::Text(_)) => return Err(CsvError::Serialize("only structs can be written as CSV records".to_string())),
+            Err(e) => return Err(CsvError::Serialize(e.0)),
+        };
+        if !self.wrote_headers {
+            self.write_record(fields.iter().map(|(name, _)| name))?;
+            self.wrote_headers = true;
+        }
+        self.write_record(fields.iter().map(|(_, value)| value))?;
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.output.flush()
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.output
+    }
+}
+
+/// Quotes a field if it contains the delimiter, a quote, a line break, or
+/// leading/trailing whitespace; embedded quotes are doubled.
+pub fn escape_field(field: &str, delimiter: char) -> String {
+    let needs_quotes = field.contains([delimiter, '"', '\n', '\r'])
+        || field.starts_with(char::is_whitespace)
+        || field.ends_with(char::is_whitespace);
+    if needs_quotes {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Parses a whole CSV document held in memory.
+pub fn parse(text: &str) -> Result<Vec<Record>, CsvError> {
+    Reader::new(text.as_bytes()).read_all()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use std::collections::HashMap;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Role {
+        Admin,
+        Guest,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct User {
+        name: String,
+        age: u32,
+        score: f64,
+        active: bool,
+        role: Role,
+        email: Option<String>,
+    }
+
+    fn fields(record: &Record) -> Vec<&str> {
+        record.iter().collect()
+    }
+
+    #[test]
+    fn test_simple_rows() {
+        let records = parse("a,b,c\n1,2,3\n").unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(fields(&records[0]), vec!["a", "b", "c"]);
+        assert_eq!(fields(&records[1]), vec!["1", "2", "3"]);
+        assert_eq!(&records[1][2], "3");
+    }
+
+    #[test]
+    fn test_quoted_fields() {
+        let records = parse("name,motto\n\"Smith, John\",\"He said \"\"hi\"\"\"\n").unwrap();
+        assert_eq!(fields(&records[1]), vec!["Smith, John", "He said \"hi\""]);
+    }
+
+    #[test]
+    fn test_embedded_newline() {
+        let records = parse("id,note\n1,\"line one\nline two\"\n2,plain\n").unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[1].get(1), Some("line one\nline two"));
+        assert_eq!(records[2].get(1), Some("plain"));
+    }
+
+    #[test]
+    fn test_empty_fields_crlf_and_blank_lines() {
+        let records = parse("a,,c\r\n\r\n,\r\nlast").unwrap();
+        assert_eq!(fields(&records[0]), vec!["a", "", "c"]);
+        assert_eq!(fields(&records[1]), vec!["", ""]);
+        assert_eq!(fields(&records[2]), vec!["last"]);
+    }
+
+    #[test]
+    fn test_custom_delimiter() {
+        let records = Reader::new("a;\"b;c\"\n".as_bytes()).with_delimiter(';').read_all().unwrap();
+        assert_eq!(fields(&records[0]), vec!["a", "b;c"]);
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        assert!(matches!(parse("a,\"open\nstill open"), Err(CsvError::UnterminatedQuote { line: 1 })));
+        assert!(matches!(parse("ok\nab\"c\n"), Err(CsvError::UnexpectedQuote { line: 2 })));
+        assert!(matches!(parse("\"quoted\"x\n"), Err(CsvError::UnexpectedQuote { line: 1 })));
+    }
+
+    #[test]
+    fn test_writer_escapes_fields() {
+        let mut writer = Writer::new(Vec::new());
+        writer.write_record(["plain", "with,comma", "with \"quote\""]).unwrap();
+        writer.write_record(vec![String::from("multi\nline"), String::from(" padded ")]).unwrap();
+        let output = String::from_utf8(writer.into_inner()).unwrap();
+        assert_eq!(
+            output,
+            "plain,\"with,comma\",\"with \"\"quote\"\"\"\n\"multi\nline\",\" padded \"\n"
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let rows = vec![
+            vec!["id", "text"],
+            vec!["1", "commas, \"quotes\", and\nnewlines"],
+            vec!["2", ""],
+        ];
+        let mut writer = Writer::new(Vec::new());
+        for row in &rows {
+            writer.write_record(row).unwrap();
+        }
+        let text = String::from_utf8(writer.into_inner()).unwrap();
+        let parsed: Vec<Record> = parse(&text).unwrap();
+        let expected: Vec<Record> = rows.into_iter().map(Record::from).collect();
+        assert_eq!(parsed, expected);
+    }
+
+    #[test]
+    fn test_deserialize_maps_columns_by_name() {
+        let data = "age,name,role,active,score,email,notes\n36,\"Lovelace, Ada\",Admin,true,9.5,ada@example.com,ignored\n7, Ferris ,Guest,false,0,,\n";
+        let users: Vec<User> = Reader::new(data.as_bytes()).deserialize().collect::<Result<_, _>>().unwrap();
+        assert_eq!(
+            users[0],
+            User {
+                name: "Lovelace, Ada".to_string(),
+                age: 36,
+                score: 9.5,
+                active: true,
+                role: Role::Admin,
+                email: Some("ada@example.com".to_string()),
+            }
+        );
+        // Strings keep their spaces; numbers are trimmed before parsing
+        assert_eq!(users[1].name, " Ferris ");
+        assert_eq!(users[1].email, None);
+    }
+
+    #[test]
+    fn test_deserialize_into_a_map() {
+        let mut reader = Reader::new("x,y\n1,2\n".as_bytes());
+        let rows: Vec<HashMap<String, i32>> = reader.deserialize().collect::<Result<_, _>>().unwrap();
+        assert_eq!(rows[0]["y"], 2);
+        assert_eq!(fields(reader.headers().unwrap()), vec!["x", "y"]);
+    }
+
+    #[test]
+    fn test_deserialize_errors_name_the_line_and_column() {
+        // A missing Option column is fine; a missing required one is not
+        let data = "name,age,active,role\nAda,36,true,Admin\n";
+        match Reader::new(data.as_bytes()).deserialize::<User>().next().unwrap() {
+            Err(CsvError::Deserialize { line, message }) => {
+                assert_eq!(line, 2);
+                assert_eq!(message, "missing field `score`");
+            }
+            other => panic!("expected a deserialize error, got {:?}", other),
+        }
+
+        let data = "name,age,score,active,role\nAda,36,1,true,Admin\n\nBob,old,1,true,Admin\n";
+        let mut reader = Reader::new(data.as_bytes());
+        let mut rows = reader.deserialize::<User>();
+        assert_eq!(rows.next().unwrap().unwrap().email, None);
+        let err = rows.next().unwrap().unwrap_err();
+        assert_eq!(err.to_string(), "record on line 4: column `age`: cannot read \"old\" as u32: invalid digit found in string");
+    }
+
+    #[test]
+    fn test_serialize_round_trip() {
+        let users = vec![
+            User {
+                name: "Grace \"Amazing\" Hopper".to_string(),
+                age: 85,
+                score: 99.25,
+                active: false,
+                role: Role::Guest,
+                email: None,
+            },
+            User {
+                name: "Ferris".to_string(),
+                age: 9,
+                score: 3.0,
+                active: true,
+                role: Role::Admin,
+                email: Some("ferris@rust-lang.org".to_string()),
+            },
+        ];
+        let mut writer = Writer::new(Vec::new());
+        for user in &users {
+            writer.serialize(user).unwrap();
+        }
+        let text = String::from_utf8(writer.into_inner()).unwrap();
+        assert!(text.starts_with("name,age,score,active,role,email\n\"Grace \"\"Amazing\"\" Hopper\",85,99.25,false,Guest,\n"));
+        let read: Vec<User> = Reader::new(text.as_bytes()).deserialize().collect::<Result<_, _>>().unwrap();
+        assert_eq!(read, users);
+    }
+
+    #[test]
+    fn test_serialize_rejects_values_without_a_csv_form() {
+        #[derive(Serialize)]
+        struct Tagged {
+            tags: Vec<String>,
+        }
+        let mut writer = Writer::new(Vec::new());
+        let err = writer.serialize(&Tagged { tags: vec![] }).unwrap_err();
+        assert_eq!(err.to_string(), "a sequence cannot be written as CSV");
+        assert!(writer.serialize(&42).is_err());
+        assert!(writer.into_inner().is_empty());
+    }
+}
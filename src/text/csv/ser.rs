@@ -0,0 +1,156 @@
+//! Turns a serde value into a row: a struct becomes its field names and
+//! their values as text. Anything that does not fit in one CSV field, such
+//! as a nested struct or a `Vec`, is an error.
+
+use serde::ser::{self, Impossible, Serialize, SerializeStruct};
+
+use super::SerdeMessage;
+
+/// What one value serialized to.
+pub(super) enum Cell {
+    Text(String),
+    Row(Vec<(&'static str, String)>),
+}
+
+pub(super) struct CellSerializer;
+
+fn unsupported(what: &str) -> SerdeMessage {
+    SerdeMessage(format!("{} cannot be written as CSV", what))
+}
+
+macro_rules! as_text {
+    ($($method:ident($ty:ty)),* $(,)?) => {
+        $(
+            fn $method(self, value: $ty) -> Result<Cell, SerdeMessage> {
+                Ok(Cell::Text(value.to_string()))
+            }
+        )*
+    };
+}
+
+impl ser::Serializer for CellSerializer {
+    type Ok = Cell;
+    type Error = SerdeMessage;
+    type SerializeSeq = Impossible<Cell, SerdeMessage>;
+    type SerializeTuple = Impossible<Cell, SerdeMessage>;
+    type SerializeTupleStruct = Impossible<Cell, SerdeMessage>;
+    type SerializeTupleVariant = Impossible<Cell, SerdeMessage>;
+    type SerializeMap = Impossible<Cell, SerdeMessage>;
+    type SerializeStruct = StructFields;
+    type SerializeStructVariant = Impossible<Cell, SerdeMessage>;
+
+    as_text! {
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_f32(f32),
+        serialize_f64(f64),
+        serialize_char(char),
+        serialize_str(&str),
+    }
+
+    fn serialize_bytes(self, _value: &[u8]) -> Result<Cell, SerdeMessage> {
+        Err(unsupported("raw bytes"))
+    }
+
+    fn serialize_none(self) -> Result<Cell, SerdeMessage> {
+        Ok(Cell::Text(String::new()))
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Cell, SerdeMessage> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Cell, SerdeMessage> {
+        Ok(Cell::Text(String::new()))
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Cell, SerdeMessage> {
+        Ok(Cell::Text(String::new()))
+    }
+
+    fn serialize_unit_variant(self, _name: &'static str, _index: u32, variant: &'static str) -> Result<Cell, SerdeMessage> {
+        Ok(Cell::Text(variant.to_string()))
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _name: &'static str, value: &T) -> Result<Cell, SerdeMessage> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _value: &T,
+    ) -> Result<Cell, SerdeMessage> {
+        Err(unsupported(&format!("enum variant `{}` with data", variant)))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, SerdeMessage> {
+        Err(unsupported("a sequence"))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, SerdeMessage> {
+        Err(unsupported("a tuple"))
+    }
+
+    fn serialize_tuple_struct(self, name: &'static str, _len: usize) -> Result<Self::SerializeTupleStruct, SerdeMessage> {
+        Err(unsupported(&format!("tuple struct `{}`", name)))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, SerdeMessage> {
+        Err(unsupported(&format!("enum variant `{}` with data", variant)))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, SerdeMessage> {
+        Err(unsupported("a map"))
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<StructFields, SerdeMessage> {
+        Ok(StructFields(Vec::with_capacity(len)))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, SerdeMessage> {
+        Err(unsupported(&format!("enum variant `{}` with data", variant)))
+    }
+}
+
+pub(super) struct StructFields(Vec<(&'static str, String)>);
+
+impl SerializeStruct for StructFields {
+    type Ok = Cell;
+    type Error = SerdeMessage;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), SerdeMessage> {
+        match value.serialize(CellSerializer)? {
+            Cell::Text(text) => {
+                self.0.push((key, text));
+                Ok(())
+            }
+            Cell::Row(_) => Err(unsupported(&format!("field `{}`, a nested struct,", key))),
+        }
+    }
+
+    fn end(self) -> Result<Cell, SerdeMessage> {
+        Ok(Cell::Row(self.0))
+    }
+}
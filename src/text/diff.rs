@@ -0,0 +1,230 @@
+//! Line-based diffs built on the longest common subsequence (LCS).
+
+use std::fmt::Write;
+
+/// One line of a diff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffOp<'a> {
+    /// The line appears in both texts.
+    Equal(&'a str),
+    /// The line only appears in the new text.
+    Insert(&'a str),
+    /// The line only appears in the old text.
+    Delete(&'a str),
+}
+
+impl<'a> DiffOp<'a> {
+    /// The line this operation refers to, without its trailing newline.
+    pub fn line(&self) -> &'a str {
+        match *self {
+            DiffOp::Equal(line) | DiffOp::Insert(line) | DiffOp::Delete(line) => line,
+        }
+    }
+
+    fn prefix(&self) -> char {
+        match self {
+            DiffOp::Equal(_) => ' ',
+            DiffOp::Insert(_) => '+',
+            DiffOp::Delete(_) => '-',
+        }
+    }
+}
+
+/// Compares two texts line by line.
+///
+/// Lines kept in both texts come out as `Equal`; within each changed region
+/// the deletions are listed before the insertions.
+pub fn diff<'a>(old: &'a str, new: &'a str) -> Vec<DiffOp<'a>> {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let (n, m) = (old_lines.len(), new_lines.len());
+
+    // lcs[i][j] is the LCS length of old_lines[i..] and new_lines[j..]
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old_lines[i] == new_lines[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut ops = Vec::with_capacity(n.max(m));
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if old_lines[i] == new_lines[j] {
+            ops.push(DiffOp::Equal(old_lines[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            ops.push(DiffOp::Delete(old_lines[i]));
+            i += 1;
+        } else {
+            ops.push(DiffOp::Insert(new_lines[j]));
+            j += 1;
+        }
+    }
+    ops.extend(old_lines[i..].iter().map(|line| DiffOp::Delete(line)));
+    ops.extend(new_lines[j..].iter().map(|line| DiffOp::Insert(line)));
+    ops
+}
+
+/// Renders the difference between two texts in unified diff format.
+///
+/// `context` is the number of unchanged lines shown around each change,
+/// like `diff -U`. Identical texts produce an empty string.
+pub fn unified_diff(old_name: &str, new_name: &str, old: &str, new: &str, context: usize) -> String {
+    let ops = diff(old, new);
+    let mut out = String::new();
+    let changes: Vec<usize> = ops
+        .iter()
+        .enumerate()
+        .filter(|(_, op)| !matches!(op, DiffOp::Equal(_)))
+        .map(|(index, _)| index)
+        .collect();
+    if changes.is_empty() {
+        return out;
+    }
+
+    // Line numbers (0-based) in the old and new text before each op
+    let mut positions = Vec::with_capacity(ops.len() + 1);
+    let (mut old_line, mut new_line) = (0, 0);
+    for op in &ops {
+        positions.push((old_line, new_line));
+        match op {
+            DiffOp::Equal(_) => {
+                old_line += 1;
+                new_line += 1;
+            }
+            DiffOp::Delete(_) => old_line += 1,
+            DiffOp::Insert(_) => new_line += 1,
+        }
+    }
+    positions.push((old_line, new_line));
+
+    let _ = writeln!(out, "--- {}", old_name);
+    let _ = writeln!(out, "+++ {}", new_name);
+
+    // Group changes whose surrounding context would overlap into one hunk
+    let mut hunk_start = 0;
+    while hunk_start < changes.len() {
+        let mut hunk_end = hunk_start;
+        while hunk_end + 1 < changes.len() && changes[hunk_end + 1] - changes[hunk_end] <= 2 * context + 1 {
+            hunk_end += 1;
+        }
+
+        let first = changes[hunk_start].saturating_sub(context);
+        let last = (changes[hunk_end] + context + 1).min(ops.len());
+        let (old_from, new_from) = positions[first];
+        let (old_to, new_to) = positions[last];
+        let _ = writeln!(
+            out,
+            "@@ -{} +{} @@",
+            hunk_range(old_from, old_to - old_from),
+            hunk_range(new_from, new_to - new_from)
+        );
+        for op in &ops[first..last] {
+            let _ = writeln!(out, "{}{}", op.prefix(), op.line());
+        }
+
+        hunk_start = hunk_end + 1;
+    }
+    out
+}
+
+/// Formats a hunk range the way GNU diff does: an empty range is anchored
+/// on the line before it, and a count of one is left out.
+fn hunk_range(start: usize, count: usize) -> String {
+    match count {
+        0 => format!("{},0", start),
+        1 => format!("{}", start + 1),
+        _ => format!("{},{}", start + 1, count),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identical_texts() {
+        let ops = diff("a\nb\n", "a\nb\n");
+        assert_eq!(ops, vec![DiffOp::Equal("a"), DiffOp::Equal("b")]);
+        assert_eq!(unified_diff("old", "new", "a\nb\n", "a\nb\n", 3), "");
+    }
+
+    #[test]
+    fn test_insert_and_delete() {
+        let ops = diff("one\ntwo\nthree", "one\nthree\nfour");
+        assert_eq!(
+            ops,
+            vec![
+                DiffOp::Equal("one"),
+                DiffOp::Delete("two"),
+                DiffOp::Equal("three"),
+                DiffOp::Insert("four"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_replaced_line_deletes_before_inserting() {
+        let ops = diff("a\nb\nc", "a\nx\nc");
+        assert_eq!(
+            ops,
+            vec![
+                DiffOp::Equal("a"),
+                DiffOp::Delete("b"),
+                DiffOp::Insert("x"),
+                DiffOp::Equal("c"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_empty_inputs() {
+        assert!(diff("", "").is_empty());
+        assert_eq!(diff("", "a\nb"), vec![DiffOp::Insert("a"), DiffOp::Insert("b")]);
+        assert_eq!(diff("a", ""), vec![DiffOp::Delete("a")]);
+    }
+
+    #[test]
+    fn test_unified_diff_single_hunk() {
+        let old = "line 1\nline 2\nline 3\n";
+        let new = "line 1\nline two\nline 3\n";
+        let expected = "\
+--- old.txt
++++ new.txt
+@@ -1,3 +1,3 @@
+ line 1
+-line 2
++line two
+ line 3
+";
+        assert_eq!(unified_diff("old.txt", "new.txt", old, new, 3), expected);
+    }
+
+    #[test]
+    fn test_unified_diff_splits_distant_changes() {
+        let old: String = (1..=12).map(|i| format!("{}\n", i)).collect();
+        let new: String = (1..=12)
+            .map(|i| match i {
+                2 => "two\n".to_string(),
+                11 => "eleven\n".to_string(),
+                _ => format!("{}\n", i),
+            })
+            .collect();
+        let rendered = unified_diff("a", "b", &old, &new, 1);
+        let hunks: Vec<&str> = rendered.lines().filter(|l| l.starts_with("@@")).collect();
+        assert_eq!(hunks, vec!["@@ -1,3 +1,3 @@", "@@ -10,3 +10,3 @@"]);
+    }
+
+    #[test]
+    fn test_unified_diff_pure_insertion_range() {
+        let rendered = unified_diff("a", "b", "", "new line\n", 3);
+        assert!(rendered.contains("@@ -0,0 +1 @@"));
+        assert!(rendered.ends_with("+new line\n"));
+    }
+}
@@ -0,0 +1,219 @@
+//! Shell-style wildcard matching.
+//!
+//! Supported syntax:
+//!
+//! - `*` matches any run of characters, including none
+//! - `?` matches exactly one character
+//! - `[abc]`, `[a-z]` match one character from a class; `[!a-z]` or
+//!   `[^a-z]` match one character outside it
+//! - `\` makes the next character literal, so `\*` matches a star
+//!
+//! The matcher is iterative: when a later part of the pattern fails it
+//! backtracks to the most recent `*` instead of recursing, so even patterns
+//! like `*a*a*a*b` run in O(pattern * input) time.
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Literal(char),
+    AnyChar,
+    AnyRun,
+    Class { negated: bool, ranges: Vec<(char, char)> },
+}
+
+impl Token {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            Token::Literal(expected) => *expected == c,
+            Token::AnyChar => true,
+            Token::AnyRun => unreachable!("stars are handled by the matcher"),
+            Token::Class { negated, ranges } => {
+                ranges.iter().any(|&(lo, hi)| lo <= c && c <= hi) != *negated
+            }
+        }
+    }
+}
+
+/// A compiled glob pattern, for matching many inputs against one pattern.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pattern {
+    tokens: Vec<Token>,
+}
+
+impl Pattern {
+    /// Compiles a pattern. An unclosed `[` is treated as a literal bracket.
+    pub fn new(pattern: &str) -> Self {
+        let chars: Vec<char> = pattern.chars().collect();
+        let mut tokens = Vec::new();
+        let mut i = 0;
+        while i < chars.len() {
+            let token = match chars[i] {
+                '*' => {
+                    // Consecutive stars behave like a single one
+                    if tokens.last() != Some(&Token::AnyRun) {
+                        tokens.push(Token::AnyRun);
+                    }
+                    i += 1;
+                    continue;
+                }
+                '?' => Token::AnyChar,
+                '\\' if i + 1 < chars.len() => {
+                    i += 1;
+                    Token::Literal(chars[i])
+                }
+                '[' => match parse_class(&chars[i + 1..]) {
+                    Some((token, consumed)) => {
+                        i += consumed;
+                        token
+                    }
+                    None => Token::Literal('['),
+                },
+                c => Token::Literal(c),
+            };
+            tokens.push(token);
+            i += 1;
+        }
+        Pattern { tokens }
+    }
+
+    /// Returns true if the whole of `input` matches the pattern.
+    pub fn matches(&self, input: &str) -> bool {
+        let input: Vec<char> = input.chars().collect();
+        let (mut t, mut i) = (0, 0);
+        // Where to resume after the last star: (token after the star, input position)
+        let mut backtrack: Option<(usize, usize)> = None;
+
+        while i < input.len() {
+            match self.tokens.get(t) {
+                Some(Token::AnyRun) => {
+                    backtrack = Some((t + 1, i));
+                    t += 1;
+                }
+                Some(token) if token.matches(input[i]) => {
+                    t += 1;
+                    i += 1;
+                }
+                _ => match backtrack {
+                    // Let the star swallow one more character and retry
+                    Some((after_star, star_input)) => {
+                        t = after_star;
+                        i = star_input + 1;
+                        backtrack = Some((after_star, star_input + 1));
+                    }
+                    None => return false,
+                },
+            }
+        }
+
+        self.tokens[t..].iter().all(|token| *token == Token::AnyRun)
+    }
+}
+
+/// Parses the body of a `[...]` class. Returns the token and how many chars
+/// were consumed after the opening bracket, or `None` if it never closes.
+fn parse_class(chars: &[char]) -> Option<(Token, usize)> {
+    let mut i = 0;
+    let negated = matches!(chars.first(), Some('!' | '^'));
+    if negated {
+        i += 1;
+    }
+
+    let mut ranges = Vec::new();
+    let mut first = true;
+    while i < chars.len() {
+        let c = chars[i];
+        // A `]` right after the opening bracket is a literal member
+        if c == ']' && !first {
+            return Some((Token::Class { negated, ranges }, i + 1));
+        }
+        first = false;
+        if i + 2 < chars.len() && chars[i + 1] == '-' && chars[i + 2] != ']' {
+            ranges.push((c, chars[i + 2]));
+            i += 3;
+        } else {
+            ranges.push((c, c));
+            i += 1;
+        }
+    }
+    None
+}
+
+/// Returns true if `input` matches the glob `pattern`.
+///
+/// ```
+/// use rustler::text::glob::matches;
+///
+/// assert!(matches("*.rs", "main.rs"));
+/// assert!(matches("file?.[ct]xt", "file1.txt"));
+/// assert!(!matches("*.rs", "main.rs.bak"));
+/// ```
+pub fn matches(pattern: &str, input: &str) -> bool {
+    Pattern::new(pattern).matches(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_literals_and_question_mark() {
+        assert!(matches("hello", "hello"));
+        assert!(!matches("hello", "hell"));
+        assert!(matches("h?llo", "hallo"));
+        assert!(!matches("h?llo", "hllo"));
+        assert!(matches("", ""));
+        assert!(!matches("", "a"));
+    }
+
+    #[test]
+    fn test_star() {
+        assert!(matches("*", ""));
+        assert!(matches("*", "anything"));
+        assert!(matches("*.rs", "lib.rs"));
+        assert!(!matches("*.rs", "lib.rs.orig"));
+        assert!(matches("a*b*c", "aXXbYYc"));
+        assert!(matches("a*b*c", "abc"));
+        assert!(!matches("a*b*c", "acb"));
+        assert!(matches("**x", "x"));
+    }
+
+    #[test]
+    fn test_backtracking_after_star() {
+        assert!(matches("*ab", "aaab"));
+        assert!(matches("*aab", "aaaab"));
+        assert!(matches("*a*a*a*b", "aaaaaaaaab"));
+        assert!(!matches("*a*a*a*b", &"a".repeat(50)));
+    }
+
+    #[test]
+    fn test_character_classes() {
+        assert!(matches("[abc]", "b"));
+        assert!(!matches("[abc]", "d"));
+        assert!(matches("file[0-9].txt", "file7.txt"));
+        assert!(!matches("file[0-9].txt", "fileA.txt"));
+        assert!(matches("[!0-9]*", "report"));
+        assert!(!matches("[^0-9]*", "2024-report"));
+        assert!(matches("[]x]", "]"));
+        assert!(matches("[a-]", "-"));
+    }
+
+    #[test]
+    fn test_escapes_and_unclosed_class() {
+        assert!(matches("\\*", "*"));
+        assert!(!matches("\\*", "x"));
+        assert!(matches("[abc", "[abc"));
+    }
+
+    #[test]
+    fn test_unicode_input() {
+        assert!(matches("caf?", "café"));
+        assert!(matches("*🦀*", "I love 🦀 Rust"));
+    }
+
+    #[test]
+    fn test_compiled_pattern_reuse() {
+        let pattern = Pattern::new("*.[ch]");
+        let files = ["main.c", "util.h", "notes.txt", "lib.rs"];
+        let matched: Vec<&str> = files.iter().copied().filter(|f| pattern.matches(f)).collect();
+        assert_eq!(matched, vec!["main.c", "util.h"]);
+    }
+}
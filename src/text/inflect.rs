@@ -0,0 +1,212 @@
+//! English pluralization and singularization.
+//!
+//! The rules cover the common suffix patterns plus a table of irregular
+//! nouns. English has plenty of exceptions, so this is meant for generating
+//! friendly messages ("3 items", "1 child"), not for linguistics.
+
+/// Irregular singular/plural pairs.
+const IRREGULARS: &[(&str, &str)] = &[
+    ("child", "children"),
+    ("person", "people"),
+    ("man", "men"),
+    ("woman", "women"),
+    ("mouse", "mice"),
+    ("goose", "geese"),
+    ("tooth", "teeth"),
+    ("foot", "feet"),
+    ("ox", "oxen"),
+    ("hero", "heroes"),
+    ("potato", "potatoes"),
+    ("tomato", "tomatoes"),
+    ("index", "indices"),
+    ("datum", "data"),
+];
+
+/// Words that are spelled the same in the singular and the plural.
+const UNCOUNTABLE: &[&str] = &[
+    "sheep", "fish", "deer", "series", "species", "money", "rice", "information", "equipment", "news",
+];
+
+/// Suffix rules for singular -> plural, checked in order.
+const PLURAL_RULES: &[(&str, &str)] = &[
+    ("ay", "ays"),
+    ("ey", "eys"),
+    ("oy", "oys"),
+    ("uy", "uys"),
+    ("y", "ies"),
+    ("fe", "ves"),
+    ("lf", "lves"),
+    ("eaf", "eaves"),
+    ("ss", "sses"),
+    ("sh", "shes"),
+    ("ch", "ches"),
+    ("x", "xes"),
+    ("z", "zes"),
+    ("us", "uses"),
+    ("s", "ses"),
+];
+
+/// Suffix rules for plural -> singular, checked in order.
+const SINGULAR_RULES: &[(&str, &str)] = &[
+    ("ies", "y"),
+    ("ives", "ife"),
+    ("lves", "lf"),
+    ("eaves", "eaf"),
+    ("sses", "ss"),
+    ("shes", "sh"),
+    ("ches", "ch"),
+    ("xes", "x"),
+    ("zes", "z"),
+    ("ouses", "ouse"),
+    ("uses", "us"),
+    ("ss", "ss"),
+    ("us", "us"),
+    ("s", ""),
+];
+
+/// Returns the form of `word` that agrees with `count`.
+///
+/// ```
+/// use rustler::text::pluralize;
+///
+/// assert_eq!(pluralize("item", 1), "item");
+/// assert_eq!(pluralize("item", 3), "items");
+/// assert_eq!(pluralize("child", 0), "children");
+/// ```
+pub fn pluralize(word: &str, count: usize) -> String {
+    if count == 1 {
+        return word.to_string();
+    }
+    let lower = word.to_lowercase();
+    if lower.is_empty() || UNCOUNTABLE.contains(&lower.as_str()) {
+        return word.to_string();
+    }
+    if let Some(&(_, plural)) = IRREGULARS.iter().find(|&&(singular, _)| singular == lower) {
+        return match_case(word, plural);
+    }
+    if IRREGULARS.iter().any(|&(_, plural)| plural == lower) {
+        return word.to_string();
+    }
+    match_case(word, &apply_rules(&lower, PLURAL_RULES).unwrap_or_else(|| lower.clone() + "s"))
+}
+
+/// Returns the singular form of `word`.
+///
+/// ```
+/// use rustler::text::singularize;
+///
+/// assert_eq!(singularize("boxes"), "box");
+/// assert_eq!(singularize("people"), "person");
+/// ```
+pub fn singularize(word: &str) -> String {
+    let lower = word.to_lowercase();
+    if lower.is_empty() || UNCOUNTABLE.contains(&lower.as_str()) {
+        return word.to_string();
+    }
+    if let Some(&(singular, _)) = IRREGULARS.iter().find(|&&(_, plural)| plural == lower) {
+        return match_case(word, singular);
+    }
+    if IRREGULARS.iter().any(|&(singular, _)| singular == lower) {
+        return word.to_string();
+    }
+    match apply_rules(&lower, SINGULAR_RULES) {
+        Some(singular) => match_case(word, &singular),
+        None => word.to_string(),
+    }
+}
+
+/// Replaces the first matching suffix, leaving at least one letter of stem.
+fn apply_rules(word: &str, rules: &[(&str, &str)]) -> Option<String> {
+    rules
+        .iter()
+        .find(|&&(suffix, _)| word.len() > suffix.len() && word.ends_with(suffix))
+        .map(|&(suffix, replacement)| format!("{}{}", &word[..word.len() - suffix.len()], replacement))
+}
+
+/// Copies the capitalization style of `original` onto `word`.
+fn match_case(original: &str, word: &str) -> String {
+    let letters: Vec<char> = original.chars().filter(|c| c.is_alphabetic()).collect();
+    if letters.len() > 1 && letters.iter().all(|c| c.is_uppercase()) {
+        return word.to_uppercase();
+    }
+    let mut chars = word.chars();
+    match (original.chars().next(), chars.next()) {
+        (Some(first), Some(c)) if first.is_uppercase() => c.to_uppercase().chain(chars).collect(),
+        _ => word.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_regular_plurals() {
+        let cases = [
+            ("item", "items"),
+            ("box", "boxes"),
+            ("class", "classes"),
+            ("church", "churches"),
+            ("dish", "dishes"),
+            ("city", "cities"),
+            ("day", "days"),
+            ("knife", "knives"),
+            ("wolf", "wolves"),
+            ("bus", "buses"),
+        ];
+        for (singular, plural) in cases {
+            assert_eq!(pluralize(singular, 2), plural, "pluralize {}", singular);
+        }
+    }
+
+    #[test]
+    fn test_count_of_one_keeps_singular() {
+        assert_eq!(pluralize("item", 1), "item");
+        assert_eq!(pluralize("item", 0), "items");
+        assert_eq!(pluralize("child", 1), "child");
+    }
+
+    #[test]
+    fn test_irregulars_and_uncountables() {
+        assert_eq!(pluralize("child", 3), "children");
+        assert_eq!(pluralize("person", 2), "people");
+        assert_eq!(pluralize("sheep", 5), "sheep");
+        assert_eq!(pluralize("people", 2), "people");
+        assert_eq!(singularize("children"), "child");
+        assert_eq!(singularize("mice"), "mouse");
+        assert_eq!(singularize("fish"), "fish");
+    }
+
+    #[test]
+    fn test_regular_singulars() {
+        let cases = [
+            ("items", "item"),
+            ("boxes", "box"),
+            ("classes", "class"),
+            ("cities", "city"),
+            ("knives", "knife"),
+            ("leaves", "leaf"),
+            ("houses", "house"),
+            ("buses", "bus"),
+            ("class", "class"),
+            ("status", "status"),
+        ];
+        for (plural, singular) in cases {
+            assert_eq!(singularize(plural), singular, "singularize {}", plural);
+        }
+    }
+
+    #[test]
+    fn test_preserves_capitalization() {
+        assert_eq!(pluralize("Child", 2), "Children");
+        assert_eq!(pluralize("CITY", 2), "CITIES");
+        assert_eq!(singularize("People"), "Person");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for word in ["item", "box", "city", "knife", "person", "church", "day"] {
+            assert_eq!(singularize(&pluralize(word, 2)), word);
+        }
+    }
+}
@@ -0,0 +1,609 @@
+//! A JSON parser and printer written from scratch.
+//!
+//! `serde_json` does this job in real programs; this module shows what such
+//! a library does underneath. [`Value::parse`] is a recursive-descent parser:
+//! one function per grammar rule, each consuming input and calling the
+//! others for nested values. Errors carry the line and column where parsing
+//! went wrong.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// Nesting deeper than this is rejected instead of overflowing the stack.
+const MAX_DEPTH: usize = 128;
+
+/// Any JSON value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(BTreeMap<String, Value>),
+}
+
+impl Value {
+    /// Parses a complete JSON document.
+    pub fn parse(input: &str) -> Result<Value, ParseError> {
+        let mut parser = Parser { input, pos: 0 };
+        parser.skip_whitespace();
+        let value = parser.parse_value(0)?;
+        parser.skip_whitespace();
+        if parser.pos < input.len() {
+            return Err(parser.error(ErrorKind::TrailingCharacters));
+        }
+        Ok(value)
+    }
+
+    /// Looks up a key if this value is an object.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Object(map) => map.get(key),
+            _ => None,
+        }
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, Value::Null)
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// Returns the number if it is a whole number that fits in an `i64`.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Value::Number(n) if n.fract() == 0.0 && n.abs() < 9.2e18 => Some(*n as i64),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&Vec<Value>> {
+        match self {
+            Value::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&BTreeMap<String, Value>> {
+        match self {
+            Value::Object(map) => Some(map),
+            _ => None,
+        }
+    }
+
+    /// Renders the value across multiple lines with two-space indentation.
+    pub fn to_pretty_string(&self) -> String {
+        let mut out = String::new();
+        self.write_pretty(&mut out, 0);
+        out
+    }
+
+    fn write_pretty(&self, out: &mut String, indent: usize) {
+        let pad = |out: &mut String, level: usize| out.extend(std::iter::repeat_n("  ", level));
+        match self {
+            Value::Array(items) if !items.is_empty() => {
+                out.push_str("[\n");
+                for (i, item) in items.iter().enumerate() {
+                    pad(out, indent + 1);
+                    item.write_pretty(out, indent + 1);
+                    out.push_str(if i + 1 < items.len() { ",\n" } else { "\n" });
+                }
+                pad(out, indent);
+                out.push(']');
+            }
+            Value::Object(map) if !map.is_empty() => {
+                out.push_str("{\n");
+                for (i, (key, value)) in map.iter().enumerate() {
+                    pad(out, indent + 1);
+                    write_string(out, key);
+                    out.push_str(": ");
+                    value.write_pretty(out, indent + 1);
+                    out.push_str(if i + 1 < map.len() { ",\n" } else { "\n" });
+                }
+                pad(out, indent);
+                out.push('}');
+            }
+            other => out.push_str(&other.to_string()),
+        }
+    }
+}
+
+/// Compact single-line JSON.
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Null => write!(f, "null"),
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Number(n) => write!(f, "{}", format_number(*n)),
+            Value::String(s) => {
+                let mut out = String::new();
+                write_string(&mut out, s);
+                write!(f, "{}", out)
+            }
+            Value::Array(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+            Value::Object(map) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in map.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    let mut quoted = String::new();
+                    write_string(&mut quoted, key);
+                    write!(f, "{}:{}", quoted, value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+impl FromStr for Value {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Value::parse(s)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Self {
+        Value::Bool(b)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(n: f64) -> Self {
+        Value::Number(n)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(n: i64) -> Self {
+        Value::Number(n as f64)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Value::String(s.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Value::String(s)
+    }
+}
+
+impl From<Vec<Value>> for Value {
+    fn from(items: Vec<Value>) -> Self {
+        Value::Array(items)
+    }
+}
+
+impl From<BTreeMap<String, Value>> for Value {
+    fn from(map: BTreeMap<String, Value>) -> Self {
+        Value::Object(map)
+    }
+}
+
+/// Whole numbers print without a fraction; JSON has no NaN or infinity.
+fn format_number(n: f64) -> String {
+    if !n.is_finite() {
+        "null".to_string()
+    } else if n.fract() == 0.0 && n.abs() < 1e15 {
+        format!("{}", n as i64)
+    } else {
+        format!("{}", n)
+    }
+}
+
+fn write_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{08}' => out.push_str("\\b"),
+            '\u{0c}' => out.push_str("\\f"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// What went wrong while parsing.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ErrorKind {
+    UnexpectedEnd,
+    UnexpectedChar(char),
+    InvalidNumber,
+    InvalidEscape,
+    InvalidUnicode,
+    /// A control character appeared unescaped inside a string.
+    ControlCharacter,
+    TrailingCharacters,
+    TooDeep,
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ErrorKind::UnexpectedEnd => write!(f, "unexpected end of input"),
+            ErrorKind::UnexpectedChar(c) => write!(f, "unexpected character '{}'", c.escape_debug()),
+            ErrorKind::InvalidNumber => write!(f, "invalid number"),
+            ErrorKind::InvalidEscape => write!(f, "invalid escape sequence"),
+            ErrorKind::InvalidUnicode => write!(f, "invalid unicode escape"),
+            ErrorKind::ControlCharacter => write!(f, "control character in string"),
+            ErrorKind::TrailingCharacters => write!(f, "trailing characters after JSON value"),
+            ErrorKind::TooDeep => write!(f, "nesting deeper than {} levels", MAX_DEPTH),
+        }
+    }
+}
+
+/// A parse failure and where it happened (1-based line and column).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub kind: ErrorKind,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at line {}, column {}", self.kind, self.line, self.column)
+    }
+}
+
+impl Error for ParseError {}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn error(&self, kind: ErrorKind) -> ParseError {
+        let before = &self.input[..self.pos];
+        let line = before.matches('\n').count() + 1;
+        let column = before.rsplit('\n').next().map_or(0, |l| l.chars().count()) + 1;
+        ParseError { kind, line, column }
+    }
+
+    fn unexpected(&self) -> ParseError {
+        match self.peek() {
+            Some(c) => self.error(ErrorKind::UnexpectedChar(c)),
+            None => self.error(ErrorKind::UnexpectedEnd),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t' | '\n' | '\r')) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ParseError> {
+        if self.peek() == Some(expected) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.unexpected())
+        }
+    }
+
+    fn parse_value(&mut self, depth: usize) -> Result<Value, ParseError> {
+        if depth > MAX_DEPTH {
+            return Err(self.error(ErrorKind::TooDeep));
+        }
+        match self.peek() {
+            Some('n') => self.parse_literal("null", Value::Null),
+            Some('t') => self.parse_literal("true", Value::Bool(true)),
+            Some('f') => self.parse_literal("false", Value::Bool(false)),
+            Some('"') => self.parse_string().map(Value::String),
+            Some('[') => self.parse_array(depth),
+            Some('{') => self.parse_object(depth),
+            Some('-' | '0'..='9') => self.parse_number(),
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    fn parse_literal(&mut self, word: &str, value: Value) -> Result<Value, ParseError> {
+        for expected in word.chars() {
+            self.expect(expected)?;
+        }
+        Ok(value)
+    }
+
+    fn parse_number(&mut self) -> Result<Value, ParseError> {
+        let start = self.pos;
+        let digits = |p: &mut Self| {
+            let from = p.pos;
+            while matches!(p.peek(), Some('0'..='9')) {
+                p.pos += 1;
+            }
+            p.pos - from
+        };
+
+        if self.peek() == Some('-') {
+            self.pos += 1;
+        }
+        match self.peek() {
+            Some('0') => self.pos += 1,
+            Some('1'..='9') => {
+                digits(self);
+            }
+            _ => return Err(self.error(ErrorKind::InvalidNumber)),
+        }
+        if self.peek() == Some('.') {
+            self.pos += 1;
+            if digits(self) == 0 {
+                return Err(self.error(ErrorKind::InvalidNumber));
+            }
+        }
+        if matches!(self.peek(), Some('e' | 'E')) {
+            self.pos += 1;
+            if matches!(self.peek(), Some('+' | '-')) {
+                self.pos += 1;
+            }
+            if digits(self) == 0 {
+                return Err(self.error(ErrorKind::InvalidNumber));
+            }
+        }
+
+        self.input[start..self.pos]
+            .parse()
+            .map(Value::Number)
+            .map_err(|_| self.error(ErrorKind::InvalidNumber))
+    }
+
+    fn parse_string(&mut self) -> Result<String, ParseError> {
+        self.expect('"')?;
+        let mut out = String::new();
+        loop {
+            match self.peek() {
+                None => return Err(self.error(ErrorKind::UnexpectedEnd)),
+                Some('"') => {
+                    self.pos += 1;
+                    return Ok(out);
+                }
+                Some('\\') => {
+                    self.pos += 1;
+                    let escaped = match self.bump() {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{08}',
+                        Some('f') => '\u{0c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => self.parse_unicode_escape()?,
+                        Some(_) => {
+                            self.pos -= 1;
+                            return Err(self.error(ErrorKind::InvalidEscape));
+                        }
+                        None => return Err(self.error(ErrorKind::UnexpectedEnd)),
+                    };
+                    out.push(escaped);
+                }
+                Some(c) if (c as u32) < 0x20 => return Err(self.error(ErrorKind::ControlCharacter)),
+                Some(c) => {
+                    self.pos += c.len_utf8();
+                    out.push(c);
+                }
+            }
+        }
+    }
+
+    /// Parses the hex digits after `\u`, combining UTF-16 surrogate pairs.
+    fn parse_unicode_escape(&mut self) -> Result<char, ParseError> {
+        let high = self.parse_hex4()?;
+        let code = if (0xD800..0xDC00).contains(&high) {
+            if !self.input[self.pos..].starts_with("\\u") {
+                return Err(self.error(ErrorKind::InvalidUnicode));
+            }
+            self.pos += 2;
+            let low = self.parse_hex4()?;
+            if !(0xDC00..0xE000).contains(&low) {
+                return Err(self.error(ErrorKind::InvalidUnicode));
+            }
+            0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+        } else {
+            high
+        };
+        char::from_u32(code).ok_or_else(|| self.error(ErrorKind::InvalidUnicode))
+    }
+
+    fn parse_hex4(&mut self) -> Result<u32, ParseError> {
+        let hex = self
+            .input
+            .get(self.pos..self.pos + 4)
+            .filter(|h| h.chars().all(|c| c.is_ascii_hexdigit()))
+            .ok_or_else(|| self.error(ErrorKind::InvalidUnicode))?;
+        self.pos += 4;
+        Ok(u32::from_str_radix(hex, 16).expect("validated hex digits"))
+    }
+
+    fn parse_array(&mut self, depth: usize) -> Result<Value, ParseError> {
+        self.expect('[')?;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.pos += 1;
+            return Ok(Value::Array(items));
+        }
+        loop {
+            self.skip_whitespace();
+            items.push(self.parse_value(depth + 1)?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some(']') => {
+                    self.pos += 1;
+                    return Ok(Value::Array(items));
+                }
+                _ => return Err(self.unexpected()),
+            }
+        }
+    }
+
+    fn parse_object(&mut self, depth: usize) -> Result<Value, ParseError> {
+        self.expect('{')?;
+        let mut map = BTreeMap::new();
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.pos += 1;
+            return Ok(Value::Object(map));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.parse_string()?;
+            self.skip_whitespace();
+            self.expect(':')?;
+            self.skip_whitespace();
+            let value = self.parse_value(depth + 1)?;
+            map.insert(key, value);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some('}') => {
+                    self.pos += 1;
+                    return Ok(Value::Object(map));
+                }
+                _ => return Err(self.unexpected()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_scalars() {
+        assert_eq!(Value::parse("null"), Ok(Value::Null));
+        assert_eq!(Value::parse(" true "), Ok(Value::Bool(true)));
+        assert_eq!(Value::parse("false"), Ok(Value::Bool(false)));
+        assert_eq!(Value::parse("-12.5e2"), Ok(Value::Number(-1250.0)));
+        assert_eq!(Value::parse("0"), Ok(Value::Number(0.0)));
+        assert_eq!(Value::parse("\"hi\""), Ok(Value::String("hi".to_string())));
+    }
+
+    #[test]
+    fn test_parse_nested_document() {
+        let doc = r#"{
+            "name": "Ferris",
+            "age": 8,
+            "languages": ["Rust", "C"],
+            "address": {"city": "Crabtown", "zip": null}
+        }"#;
+        let value = Value::parse(doc).unwrap();
+        assert_eq!(value.get("name").and_then(Value::as_str), Some("Ferris"));
+        assert_eq!(value.get("age").and_then(Value::as_i64), Some(8));
+        assert_eq!(value.get("languages").and_then(Value::as_array).map(Vec::len), Some(2));
+        let address = value.get("address").unwrap();
+        assert!(address.get("zip").unwrap().is_null());
+        assert_eq!(value.get("missing"), None);
+    }
+
+    #[test]
+    fn test_string_escapes() {
+        let value = Value::parse(r#""a\"b\\c\/d\n\té🦀""#).unwrap();
+        assert_eq!(value.as_str(), Some("a\"b\\c/d\n\té🦀"));
+    }
+
+    #[test]
+    fn test_error_positions() {
+        let err = Value::parse("{\n  \"a\": 1,\n  \"b\": tru\n}").unwrap_err();
+        assert_eq!(err.kind, ErrorKind::UnexpectedChar('\n'));
+        assert_eq!((err.line, err.column), (3, 11));
+
+        let err = Value::parse("[1, 2").unwrap_err();
+        assert_eq!(err.kind, ErrorKind::UnexpectedEnd);
+        assert_eq!(err.to_string(), "unexpected end of input at line 1, column 6");
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        let kind = |s: &str| Value::parse(s).unwrap_err().kind;
+        assert_eq!(kind("[1,]"), ErrorKind::UnexpectedChar(']'));
+        assert_eq!(kind("01"), ErrorKind::TrailingCharacters);
+        assert_eq!(kind("1."), ErrorKind::InvalidNumber);
+        assert_eq!(kind("-"), ErrorKind::InvalidNumber);
+        assert_eq!(kind(r#""\x""#), ErrorKind::InvalidEscape);
+        assert_eq!(kind(r#""\ud83e""#), ErrorKind::InvalidUnicode);
+        assert_eq!(kind("\"line\nbreak\""), ErrorKind::ControlCharacter);
+        assert_eq!(kind("{\"a\" 1}"), ErrorKind::UnexpectedChar('1'));
+        assert_eq!(kind("{1: 2}"), ErrorKind::UnexpectedChar('1'));
+        assert_eq!(kind(""), ErrorKind::UnexpectedEnd);
+        assert_eq!(kind(&"[".repeat(200)), ErrorKind::TooDeep);
+    }
+
+    #[test]
+    fn test_compact_output() {
+        let value = Value::parse(r#"{"b": [1, 2.5, "x\"y"], "a": {}, "c": []}"#).unwrap();
+        assert_eq!(value.to_string(), r#"{"a":{},"b":[1,2.5,"x\"y"],"c":[]}"#);
+    }
+
+    #[test]
+    fn test_pretty_output() {
+        let value = Value::parse(r#"{"name": "Ferris", "tags": ["crab", "mascot"], "empty": []}"#).unwrap();
+        let expected = "{\n  \"empty\": [],\n  \"name\": \"Ferris\",\n  \"tags\": [\n    \"crab\",\n    \"mascot\"\n  ]\n}";
+        assert_eq!(value.to_pretty_string(), expected);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let doc = r#"{"list":[true,false,null,-3,0.125],"nested":{"s":"tab\there"}}"#;
+        let value: Value = doc.parse().unwrap();
+        assert_eq!(value.to_string(), doc);
+        assert_eq!(Value::parse(&value.to_pretty_string()), Ok(value));
+    }
+}
@@ -0,0 +1,254 @@
+//! Truncating and padding strings to a fixed width.
+//!
+//! Widths are measured in `char`s, never bytes. Slicing with `&s[..n]`
+//! counts bytes and panics when `n` lands inside a multibyte character
+//! such as `é` or `🦀`; these helpers cannot.
+//!
+//! For lining text up in a terminal, count columns instead:
+//! [`display_width`] counts CJK characters and most emoji as two columns
+//! and combining accents as none, and [`truncate_to_width`] cuts by it.
+
+/// The character appended by [`truncate_with_ellipsis`].
+pub const ELLIPSIS: char = '…';
+
+/// Shortens `text` to at most `max` characters, ending with `…` when
+/// anything was cut off.
+///
+/// ```
+/// use rustler::text::truncate_with_ellipsis;
+///
+/// assert_eq!(truncate_with_ellipsis("Hello, world", 8), "Hello, …");
+/// assert_eq!(truncate_with_ellipsis("héllo", 10), "héllo");
+/// ```
+pub fn truncate_with_ellipsis(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    if max == 0 {
+        return String::new();
+    }
+    let mut out: String = text.chars().take(max - 1).collect();
+    out.push(ELLIPSIS);
+    out
+}
+
+/// Right-aligns `text` by adding `fill` on the left up to `width` characters.
+pub fn pad_left(text: &str, width: usize, fill: char) -> String {
+    let padding = width.saturating_sub(text.chars().count());
+    let mut out: String = std::iter::repeat_n(fill, padding).collect();
+    out.push_str(text);
+    out
+}
+
+/// Left-aligns `text` by adding `fill` on the right up to `width` characters.
+pub fn pad_right(text: &str, width: usize, fill: char) -> String {
+    let padding = width.saturating_sub(text.chars().count());
+    let mut out = text.to_string();
+    out.extend(std::iter::repeat_n(fill, padding));
+    out
+}
+
+/// Centers `text` within `width` characters. When the padding is odd the
+/// extra `fill` goes on the right.
+pub fn pad_center(text: &str, width: usize, fill: char) -> String {
+    let padding = width.saturating_sub(text.chars().count());
+    let left = padding / 2;
+    let mut out: String = std::iter::repeat_n(fill, left).collect();
+    out.push_str(text);
+    out.extend(std::iter::repeat_n(fill, padding - left));
+    out
+}
+
+/// Characters that take two terminal columns: East Asian wide and
+/// fullwidth forms and the emoji blocks. Ranges are inclusive and sorted.
+const WIDE: &[(u32, u32)] = &[
+    (0x1100, 0x115F),
+    (0x231A, 0x231B),
+    (0x23E9, 0x23EC),
+    (0x2614, 0x2615),
+    (0x2648, 0x2653),
+    (0x26A1, 0x26A1),
+    (0x26AA, 0x26AB),
+    (0x26BD, 0x26BE),
+    (0x26C4, 0x26C5),
+    (0x26D4, 0x26D4),
+    (0x26EA, 0x26EA),
+    (0x26F2, 0x26F5),
+    (0x26FA, 0x26FD),
+    (0x2705, 0x2705),
+    (0x270A, 0x270B),
+    (0x2728, 0x2728),
+    (0x274C, 0x274C),
+    (0x2753, 0x2755),
+    (0x2795, 0x2797),
+    (0x2B1B, 0x2B1C),
+    (0x2B50, 0x2B50),
+    (0x2E80, 0x303E),
+    (0x3041, 0x33FF),
+    (0x3400, 0x4DBF),
+    (0x4E00, 0x9FFF),
+    (0xA000, 0xA4CF),
+    (0xAC00, 0xD7A3),
+    (0xF900, 0xFAFF),
+    (0xFE30, 0xFE4F),
+    (0xFF00, 0xFF60),
+    (0xFFE0, 0xFFE6),
+    (0x1F300, 0x1F64F),
+    (0x1F680, 0x1F6FF),
+    (0x1F900, 0x1F9FF),
+    (0x1FA70, 0x1FAFF),
+    (0x20000, 0x3FFFD),
+];
+
+/// Characters that take no columns: combining marks drawn over the
+/// character before, zero-width spaces and joiners, and variation
+/// selectors.
+const ZERO_WIDTH: &[(u32, u32)] = &[
+    (0x0300, 0x036F),
+    (0x0483, 0x0489),
+    (0x0591, 0x05BD),
+    (0x0610, 0x061A),
+    (0x064B, 0x065F),
+    (0x1AB0, 0x1AFF),
+    (0x1DC0, 0x1DFF),
+    (0x200B, 0x200F),
+    (0x20D0, 0x20FF),
+    (0xFE00, 0xFE0F),
+    (0xFE20, 0xFE2F),
+    (0xE0100, 0xE01EF),
+];
+
+fn in_ranges(c: char, ranges: &[(u32, u32)]) -> bool {
+    let c = c as u32;
+    ranges
+        .binary_search_by(|&(start, end)| {
+            if end < c {
+                std::cmp::Ordering::Less
+            } else if start > c {
+                std::cmp::Ordering::Greater
+            } else {
+                std::cmp::Ordering::Equal
+            }
+        })
+        .is_ok()
+}
+
+/// The number of terminal columns `c` takes: 0, 1 or 2. Control
+/// characters count as 0. This covers the common scripts and emoji, not
+/// every rule in Unicode's East Asian Width annex.
+pub fn char_width(c: char) -> usize {
+    if c.is_control() || in_ranges(c, ZERO_WIDTH) {
+        0
+    } else if in_ranges(c, WIDE) {
+        2
+    } else {
+        1
+    }
+}
+
+/// The number of terminal columns `text` takes.
+///
+/// ```
+/// use rustler::text::display_width;
+///
+/// assert_eq!(display_width("Rust"), 4);
+/// assert_eq!(display_width("日本"), 4);
+/// assert_eq!(display_width("e\u{301}"), 1); // e + combining acute
+/// ```
+pub fn display_width(text: &str) -> usize {
+    text.chars().map(char_width).sum()
+}
+
+/// Shortens `text` to at most `max` columns, ending with `…` when
+/// anything was cut off. A wide character that would straddle the limit
+/// is left out whole.
+pub fn truncate_to_width(text: &str, max: usize) -> String {
+    if display_width(text) <= max {
+        return text.to_string();
+    }
+    if max == 0 {
+        return String::new();
+    }
+    let mut out = String::new();
+    let mut width = 0;
+    for c in text.chars() {
+        let w = char_width(c);
+        if width + w > max - 1 {
+            break;
+        }
+        width += w;
+        out.push(c);
+    }
+    out.push(ELLIPSIS);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate_with_ellipsis("short", 10), "short");
+        assert_eq!(truncate_with_ellipsis("exactly", 7), "exactly");
+        assert_eq!(truncate_with_ellipsis("too long", 7), "too lo…");
+        assert_eq!(truncate_with_ellipsis("abc", 1), "…");
+        assert_eq!(truncate_with_ellipsis("abc", 0), "");
+    }
+
+    #[test]
+    fn test_truncate_multibyte() {
+        // Byte slicing would panic on these
+        assert_eq!(truncate_with_ellipsis("crème brûlée", 6), "crème…");
+        assert_eq!(truncate_with_ellipsis("🦀🦀🦀🦀", 3), "🦀🦀…");
+        assert_eq!(truncate_with_ellipsis("日本語のテキスト", 4), "日本語…");
+    }
+
+    #[test]
+    fn test_padding() {
+        assert_eq!(pad_left("42", 5, ' '), "   42");
+        assert_eq!(pad_right("42", 5, '.'), "42...");
+        assert_eq!(pad_center("hi", 6, '*'), "**hi**");
+        assert_eq!(pad_center("hi", 5, '-'), "-hi--");
+    }
+
+    #[test]
+    fn test_padding_never_truncates() {
+        assert_eq!(pad_left("toolong", 3, ' '), "toolong");
+        assert_eq!(pad_right("toolong", 3, ' '), "toolong");
+        assert_eq!(pad_center("toolong", 3, ' '), "toolong");
+    }
+
+    #[test]
+    fn test_display_width() {
+        assert_eq!(display_width(""), 0);
+        assert_eq!(display_width("héllo"), 5);
+        assert_eq!(display_width("日本語"), 6);
+        assert_eq!(display_width("🦀 ok"), 5);
+        assert_eq!(display_width("n\u{303}o"), 2);
+        assert_eq!(display_width("a\u{200D}b"), 2);
+        assert_eq!(char_width('\t'), 0);
+        assert_eq!(char_width('한'), 2);
+        assert_eq!(char_width('Ａ'), 2);
+        assert_eq!(char_width('✅'), 2);
+        assert_eq!(char_width('✓'), 1);
+    }
+
+    #[test]
+    fn test_truncate_to_width() {
+        assert_eq!(truncate_to_width("short", 10), "short");
+        assert_eq!(truncate_to_width("too long", 7), "too lo…");
+        // 日本 is four columns and the ellipsis one more
+        assert_eq!(truncate_to_width("日本語のテキスト", 5), "日本…");
+        // A wide character never gets cut in half
+        assert_eq!(truncate_to_width("日本語のテキスト", 6), "日本…");
+        assert_eq!(truncate_to_width("abc", 0), "");
+    }
+
+    #[test]
+    fn test_padding_multibyte() {
+        assert_eq!(pad_left("é", 3, ' '), "  é");
+        assert_eq!(pad_right("🦀", 3, '·'), "🦀··");
+        assert_eq!(pad_center("ü", 3, '═'), "═ü═");
+    }
+}
@@ -0,0 +1,387 @@
+//! Text processing utilities.
+
+use std::collections::HashSet;
+use std::fmt;
+
+pub mod csv;
+pub mod diff;
+pub mod glob;
+pub mod inflect;
+pub mod json;
+pub mod layout;
+pub mod numbers;
+pub mod search;
+pub mod sentences;
+pub mod template;
+
+pub use diff::{diff, unified_diff, DiffOp};
+pub use inflect::{pluralize, singularize};
+pub use layout::{display_width, pad_center, pad_left, pad_right, truncate_to_width, truncate_with_ellipsis};
+pub use sentences::split_sentences;
+pub use template::{Template, TemplateError};
+
+/// Text processing utilities
+#[derive(Debug, Default)]
+pub struct TextProcessor;
+
+impl TextProcessor {
+    pub fn new() -> Self {
+        TextProcessor
+    }
+
+    pub fn count_words(&self, text: &str) -> usize {
+        text.split_whitespace().count()
+    }
+
+    pub fn is_palindrome(&self, text: &str) -> bool {
+        let cleaned: String = text
+            .chars()
+            .filter(|c| c.is_alphanumeric())
+            .map(|c| c.to_lowercase().to_string())
+            .collect();
+        cleaned == cleaned.chars().rev().collect::<String>()
+    }
+
+    pub fn reverse(&self, text: &str) -> String {
+        text.chars().rev().collect()
+    }
+
+    pub fn capitalize_words(&self, text: &str) -> String {
+        text.split_whitespace()
+            .map(|word| {
+                let mut chars = word.chars();
+                match chars.next() {
+                    None => String::new(),
+                    Some(first) => {
+                        first.to_uppercase().collect::<String>()
+                            + &chars.collect::<String>().to_lowercase()
+                    }
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Turns text into a lowercase, hyphen-separated slug for URLs and file
+    /// names: "Hello, World!" becomes `hello-world`. Apostrophes are
+    /// dropped rather than splitting a word, so "Don't" becomes `dont`.
+    pub fn slugify(&self, text: &str) -> String {
+        let mut slug = String::new();
+        let mut pending_hyphen = false;
+        for c in text.chars().filter(|c| !matches!(c, '\'' | '’')) {
+            if c.is_alphanumeric() {
+                if pending_hyphen && !slug.is_empty() {
+                    slug.push('-');
+                }
+                pending_hyphen = false;
+                slug.extend(c.to_lowercase());
+            } else {
+                pending_hyphen = true;
+            }
+        }
+        slug
+    }
+
+    /// Estimates the number of syllables in a single word.
+    ///
+    /// Counts groups of consecutive vowels and drops a silent trailing "e",
+    /// which is the usual heuristic behind Flesch-Kincaid calculators.
+    /// Every word with at least one letter counts as one syllable or more.
+    pub fn count_syllables(&self, word: &str) -> usize {
+        let letters: Vec<char> = word
+            .chars()
+            .filter(|c| c.is_alphabetic())
+            .flat_map(|c| c.to_lowercase())
+            .collect();
+        if letters.is_empty() {
+            return 0;
+        }
+
+        let is_vowel = |c: char| matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'y');
+        let mut count = 0;
+        let mut previous_was_vowel = false;
+        for &c in &letters {
+            let vowel = is_vowel(c);
+            if vowel && !previous_was_vowel {
+                count += 1;
+            }
+            previous_was_vowel = vowel;
+        }
+
+        // "make" and "code" end in a silent e, but "the" and "table" do not
+        let n = letters.len();
+        if n > 2 && letters[n - 1] == 'e' && letters[n - 2] != 'l' && !is_vowel(letters[n - 2]) {
+            count -= 1;
+        }
+
+        count.max(1)
+    }
+
+    /// Counts sentences using [`split_sentences`], so abbreviations such
+    /// as "Dr." and decimals such as "3.14" do not end a sentence.
+    ///
+    /// Text that contains words but no terminator counts as one sentence.
+    pub fn count_sentences(&self, text: &str) -> usize {
+        split_sentences(text).len()
+    }
+
+    /// Counts paragraphs: blocks of non-blank lines separated by blank lines.
+    pub fn count_paragraphs(&self, text: &str) -> usize {
+        let mut count = 0;
+        let mut in_paragraph = false;
+        for line in text.lines() {
+            let blank = line.trim().is_empty();
+            if !blank && !in_paragraph {
+                count += 1;
+            }
+            in_paragraph = !blank;
+        }
+        count
+    }
+
+    /// Computes Flesch reading ease and Flesch-Kincaid grade level.
+    pub fn readability(&self, text: &str) -> ReadabilityScore {
+        let words: Vec<&str> = words(text).collect();
+        let syllables = words.iter().map(|w| self.count_syllables(w)).sum();
+        let sentences = self.count_sentences(text);
+        ReadabilityScore::from_counts(words.len(), sentences, syllables)
+    }
+
+    /// Gathers the usual document statistics in a single pass over the words.
+    pub fn analyze(&self, text: &str) -> TextStats {
+        let mut word_count = 0;
+        let mut total_word_chars = 0;
+        let mut longest_word = "";
+        let mut unique = HashSet::new();
+        for word in words(text) {
+            let length = word.chars().count();
+            word_count += 1;
+            total_word_chars += length;
+            if length > longest_word.chars().count() {
+                longest_word = word;
+            }
+            unique.insert(word.to_lowercase());
+        }
+
+        let (average_word_length, unique_word_ratio) = if word_count == 0 {
+            (0.0, 0.0)
+        } else {
+            (
+                total_word_chars as f64 / word_count as f64,
+                unique.len() as f64 / word_count as f64,
+            )
+        };
+
+        TextStats {
+            chars: text.chars().count(),
+            words: word_count,
+            sentences: self.count_sentences(text),
+            paragraphs: self.count_paragraphs(text),
+            average_word_length,
+            longest_word: longest_word.to_string(),
+            unique_word_ratio,
+        }
+    }
+}
+
+/// Splits text into words, trimming surrounding punctuation such as quotes
+/// and commas. Tokens with no letters or digits are skipped.
+fn words(text: &str) -> impl Iterator<Item = &str> {
+    text.split_whitespace()
+        .map(|token| token.trim_matches(|c: char| !c.is_alphanumeric()))
+        .filter(|word| !word.is_empty())
+}
+
+/// The result of [`TextProcessor::analyze`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextStats {
+    pub chars: usize,
+    pub words: usize,
+    pub sentences: usize,
+    pub paragraphs: usize,
+    /// Average word length in characters, ignoring surrounding punctuation.
+    pub average_word_length: f64,
+    /// The first of the longest words, or an empty string for empty text.
+    pub longest_word: String,
+    /// Distinct words (case-insensitive) divided by total words, from 0 to 1.
+    pub unique_word_ratio: f64,
+}
+
+impl fmt::Display for TextStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Characters:          {}", self.chars)?;
+        writeln!(f, "Words:               {}", self.words)?;
+        writeln!(f, "Sentences:           {}", self.sentences)?;
+        writeln!(f, "Paragraphs:          {}", self.paragraphs)?;
+        writeln!(f, "Average word length: {:.2}", self.average_word_length)?;
+        writeln!(f, "Longest word:        {}", self.longest_word)?;
+        write!(f, "Unique word ratio:   {:.2}", self.unique_word_ratio)
+    }
+}
+
+/// The result of [`TextProcessor::readability`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReadabilityScore {
+    pub words: usize,
+    pub sentences: usize,
+    pub syllables: usize,
+    /// Flesch reading ease: higher is easier, 60-70 is plain English.
+    pub reading_ease: f64,
+    /// Flesch-Kincaid grade level: the US school grade needed to follow the text.
+    pub grade_level: f64,
+}
+
+impl ReadabilityScore {
+    fn from_counts(words: usize, sentences: usize, syllables: usize) -> Self {
+        let (reading_ease, grade_level) = if words == 0 || sentences == 0 {
+            (0.0, 0.0)
+        } else {
+            let words_per_sentence = words as f64 / sentences as f64;
+            let syllables_per_word = syllables as f64 / words as f64;
+            (
+                206.835 - 1.015 * words_per_sentence - 84.6 * syllables_per_word,
+                0.39 * words_per_sentence + 11.8 * syllables_per_word - 15.59,
+            )
+        };
+        ReadabilityScore {
+            words,
+            sentences,
+            syllables,
+            reading_ease,
+            grade_level,
+        }
+    }
+
+    /// A plain-language label for the reading ease score.
+    pub fn difficulty(&self) -> &'static str {
+        match self.reading_ease {
+            s if s >= 90.0 => "very easy",
+            s if s >= 70.0 => "easy",
+            s if s >= 60.0 => "standard",
+            s if s >= 50.0 => "fairly difficult",
+            s if s >= 30.0 => "difficult",
+            _ => "very difficult",
+        }
+    }
+}
+
+impl fmt::Display for ReadabilityScore {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "reading ease {:.1} ({}), grade level {:.1} [{} words, {} sentences, {} syllables]",
+            self.reading_ease,
+            self.difficulty(),
+            self.grade_level,
+            self.words,
+            self.sentences,
+            self.syllables
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slugify() {
+        let processor = TextProcessor::new();
+        assert_eq!(processor.slugify("Hello, World!"), "hello-world");
+        assert_eq!(processor.slugify("  Don't   Panic -- 42 "), "dont-panic-42");
+        assert_eq!(processor.slugify("Crème brûlée"), "crème-brûlée");
+        assert_eq!(processor.slugify("?!"), "");
+    }
+
+    #[test]
+    fn test_count_syllables() {
+        let processor = TextProcessor::new();
+        assert_eq!(processor.count_syllables("cat"), 1);
+        assert_eq!(processor.count_syllables("make"), 1);
+        assert_eq!(processor.count_syllables("table"), 2);
+        assert_eq!(processor.count_syllables("reading"), 2);
+        assert_eq!(processor.count_syllables("programming"), 3);
+        assert_eq!(processor.count_syllables("the"), 1);
+        assert_eq!(processor.count_syllables("Hello,"), 2);
+        assert_eq!(processor.count_syllables("42"), 0);
+    }
+
+    #[test]
+    fn test_count_sentences() {
+        let processor = TextProcessor::new();
+        assert_eq!(processor.count_sentences(""), 0);
+        assert_eq!(processor.count_sentences("No terminator"), 1);
+        assert_eq!(processor.count_sentences("One. Two! Three?"), 3);
+        assert_eq!(processor.count_sentences("Wait... what?!"), 2);
+        assert_eq!(processor.count_sentences("Dr. Who costs $3.50. Really."), 2);
+    }
+
+    #[test]
+    fn test_readability_simple_text() {
+        let processor = TextProcessor::new();
+        let score = processor.readability("The cat sat on the mat. The dog ran.");
+        assert_eq!(score.words, 9);
+        assert_eq!(score.sentences, 2);
+        assert_eq!(score.syllables, 9);
+        assert!(score.reading_ease > 90.0);
+        assert!(score.grade_level < 2.0);
+        assert_eq!(score.difficulty(), "very easy");
+    }
+
+    #[test]
+    fn test_readability_harder_text_scores_lower() {
+        let processor = TextProcessor::new();
+        let easy = processor.readability("I like to run. It is fun.");
+        let hard = processor.readability(
+            "Comprehensive documentation facilitates understanding of sophisticated programming abstractions.",
+        );
+        assert!(hard.reading_ease < easy.reading_ease);
+        assert!(hard.grade_level > easy.grade_level);
+    }
+
+    #[test]
+    fn test_readability_empty_text() {
+        let score = TextProcessor::new().readability("   ");
+        assert_eq!(score.words, 0);
+        assert_eq!(score.reading_ease, 0.0);
+        assert_eq!(score.grade_level, 0.0);
+    }
+
+    #[test]
+    fn test_count_paragraphs() {
+        let processor = TextProcessor::new();
+        assert_eq!(processor.count_paragraphs(""), 0);
+        assert_eq!(processor.count_paragraphs("one line"), 1);
+        assert_eq!(processor.count_paragraphs("first\nstill first\n\n  \nsecond\n"), 2);
+    }
+
+    #[test]
+    fn test_analyze() {
+        let text = "The quick fox jumps. The lazy dog sleeps!\n\nA \"wonderful\" day.";
+        let stats = TextProcessor::new().analyze(text);
+        assert_eq!(stats.chars, text.chars().count());
+        assert_eq!(stats.words, 11);
+        assert_eq!(stats.sentences, 3);
+        assert_eq!(stats.paragraphs, 2);
+        assert_eq!(stats.longest_word, "wonderful");
+        assert!((stats.average_word_length - 45.0 / 11.0).abs() < 1e-9);
+        assert!((stats.unique_word_ratio - 10.0 / 11.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_analyze_empty_text() {
+        let stats = TextProcessor::new().analyze("");
+        assert_eq!(stats.words, 0);
+        assert_eq!(stats.longest_word, "");
+        assert_eq!(stats.average_word_length, 0.0);
+        assert_eq!(stats.unique_word_ratio, 0.0);
+    }
+
+    #[test]
+    fn test_readability_display() {
+        let score = TextProcessor::new().readability("The cat sat.");
+        let shown = score.to_string();
+        assert!(shown.contains("reading ease"));
+        assert!(shown.contains("3 words, 1 sentences, 3 syllables"));
+    }
+}
@@ -0,0 +1,235 @@
+//! Converting numbers to English words and Roman numerals.
+
+use std::error::Error;
+use std::fmt;
+
+const ONES: [&str; 20] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten", "eleven",
+    "twelve", "thirteen", "fourteen", "fifteen", "sixteen", "seventeen", "eighteen", "nineteen",
+];
+
+const TENS: [&str; 10] = [
+    "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+
+const SCALES: [&str; 7] = [
+    "", "thousand", "million", "billion", "trillion", "quadrillion", "quintillion",
+];
+
+/// Spells out a number in English words.
+///
+/// ```
+/// use rustler::text::numbers::to_words;
+///
+/// assert_eq!(to_words(1234), "one thousand two hundred thirty-four");
+/// assert_eq!(to_words(0), "zero");
+/// ```
+pub fn to_words(n: u64) -> String {
+    if n == 0 {
+        return ONES[0].to_string();
+    }
+
+    // Split into groups of three digits, least significant first
+    let mut groups = Vec::new();
+    let mut rest = n;
+    while rest > 0 {
+        groups.push((rest % 1000) as usize);
+        rest /= 1000;
+    }
+
+    let mut parts = Vec::new();
+    for (scale, &group) in groups.iter().enumerate().rev() {
+        if group == 0 {
+            continue;
+        }
+        parts.push(hundreds_to_words(group));
+        if scale > 0 {
+            parts.push(SCALES[scale].to_string());
+        }
+    }
+    parts.join(" ")
+}
+
+/// Words for 1..=999.
+fn hundreds_to_words(n: usize) -> String {
+    let mut parts = Vec::new();
+    if n >= 100 {
+        parts.push(format!("{} hundred", ONES[n / 100]));
+    }
+    let rest = n % 100;
+    if rest >= 20 {
+        match rest % 10 {
+            0 => parts.push(TENS[rest / 10].to_string()),
+            ones => parts.push(format!("{}-{}", TENS[rest / 10], ONES[ones])),
+        }
+    } else if rest > 0 {
+        parts.push(ONES[rest].to_string());
+    }
+    parts.join(" ")
+}
+
+/// Errors from converting to or from Roman numerals.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RomanError {
+    /// Roman numerals only cover 1 to 3999.
+    OutOfRange(u32),
+    Empty,
+    InvalidCharacter(char),
+    /// Only uses valid letters but is not written the standard way,
+    /// such as "IIII" or "IC".
+    NotCanonical(String),
+}
+
+impl fmt::Display for RomanError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RomanError::OutOfRange(n) => write!(f, "{} cannot be written as a Roman numeral (1-3999 only)", n),
+            RomanError::Empty => write!(f, "empty Roman numeral"),
+            RomanError::InvalidCharacter(c) => write!(f, "'{}' is not a Roman numeral letter", c),
+            RomanError::NotCanonical(s) => write!(f, "'{}' is not a standard Roman numeral", s),
+        }
+    }
+}
+
+impl Error for RomanError {}
+
+const ROMAN: [(u32, &str); 13] = [
+    (1000, "M"),
+    (900, "CM"),
+    (500, "D"),
+    (400, "CD"),
+    (100, "C"),
+    (90, "XC"),
+    (50, "L"),
+    (40, "XL"),
+    (10, "X"),
+    (9, "IX"),
+    (5, "V"),
+    (4, "IV"),
+    (1, "I"),
+];
+
+/// Converts 1..=3999 to a Roman numeral.
+///
+/// ```
+/// use rustler::text::numbers::to_roman;
+///
+/// assert_eq!(to_roman(2024).unwrap(), "MMXXIV");
+/// assert!(to_roman(0).is_err());
+/// ```
+pub fn to_roman(n: u32) -> Result<String, RomanError> {
+    if !(1..=3999).contains(&n) {
+        return Err(RomanError::OutOfRange(n));
+    }
+    let mut out = String::new();
+    let mut rest = n;
+    for &(value, symbol) in &ROMAN {
+        while rest >= value {
+            out.push_str(symbol);
+            rest -= value;
+        }
+    }
+    Ok(out)
+}
+
+/// Parses a Roman numeral (case-insensitive).
+///
+/// Numerals written in a non-standard way, like "IIII" or "VX", are
+/// rejected rather than guessed at.
+pub fn from_roman(s: &str) -> Result<u32, RomanError> {
+    let upper = s.trim().to_uppercase();
+    if upper.is_empty() {
+        return Err(RomanError::Empty);
+    }
+
+    let values = upper
+        .chars()
+        .map(|c| match c {
+            'I' => Ok(1),
+            'V' => Ok(5),
+            'X' => Ok(10),
+            'L' => Ok(50),
+            'C' => Ok(100),
+            'D' => Ok(500),
+            'M' => Ok(1000),
+            other => Err(RomanError::InvalidCharacter(other)),
+        })
+        .collect::<Result<Vec<u32>, _>>()?;
+
+    // A smaller value before a larger one is subtracted, as in IV = 4
+    let mut total = 0;
+    for (i, &value) in values.iter().enumerate() {
+        match values.get(i + 1) {
+            Some(&next) if next > value => total -= value as i64,
+            _ => total += value as i64,
+        }
+    }
+
+    let total = u32::try_from(total).map_err(|_| RomanError::NotCanonical(s.to_string()))?;
+    match to_roman(total) {
+        Ok(canonical) if canonical == upper => Ok(total),
+        _ => Err(RomanError::NotCanonical(s.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_words_small_numbers() {
+        assert_eq!(to_words(0), "zero");
+        assert_eq!(to_words(7), "seven");
+        assert_eq!(to_words(13), "thirteen");
+        assert_eq!(to_words(20), "twenty");
+        assert_eq!(to_words(42), "forty-two");
+        assert_eq!(to_words(100), "one hundred");
+        assert_eq!(to_words(305), "three hundred five");
+        assert_eq!(to_words(999), "nine hundred ninety-nine");
+    }
+
+    #[test]
+    fn test_to_words_large_numbers() {
+        assert_eq!(to_words(1234), "one thousand two hundred thirty-four");
+        assert_eq!(to_words(1_000_000), "one million");
+        assert_eq!(to_words(2_000_017), "two million seventeen");
+        assert_eq!(to_words(1_001_000), "one million one thousand");
+        assert_eq!(
+            to_words(u64::MAX),
+            "eighteen quintillion four hundred forty-six quadrillion seven hundred forty-four \
+             trillion seventy-three billion seven hundred nine million five hundred fifty-one \
+             thousand six hundred fifteen"
+        );
+    }
+
+    #[test]
+    fn test_to_roman() {
+        assert_eq!(to_roman(1).unwrap(), "I");
+        assert_eq!(to_roman(4).unwrap(), "IV");
+        assert_eq!(to_roman(9).unwrap(), "IX");
+        assert_eq!(to_roman(14).unwrap(), "XIV");
+        assert_eq!(to_roman(1994).unwrap(), "MCMXCIV");
+        assert_eq!(to_roman(3999).unwrap(), "MMMCMXCIX");
+        assert_eq!(to_roman(0), Err(RomanError::OutOfRange(0)));
+        assert_eq!(to_roman(4000), Err(RomanError::OutOfRange(4000)));
+    }
+
+    #[test]
+    fn test_from_roman() {
+        assert_eq!(from_roman("XIV"), Ok(14));
+        assert_eq!(from_roman("mcmxciv"), Ok(1994));
+        assert_eq!(from_roman("MMMCMXCIX"), Ok(3999));
+        assert_eq!(from_roman(""), Err(RomanError::Empty));
+        assert_eq!(from_roman("XIZ"), Err(RomanError::InvalidCharacter('Z')));
+        assert_eq!(from_roman("IIII"), Err(RomanError::NotCanonical("IIII".to_string())));
+        assert_eq!(from_roman("IC"), Err(RomanError::NotCanonical("IC".to_string())));
+        assert_eq!(from_roman("VX"), Err(RomanError::NotCanonical("VX".to_string())));
+    }
+
+    #[test]
+    fn test_roman_round_trip() {
+        for n in 1..=3999 {
+            assert_eq!(from_roman(&to_roman(n).unwrap()), Ok(n));
+        }
+    }
+}
@@ -0,0 +1,203 @@
+//! Substring search algorithms.
+//!
+//! Both functions return the byte offsets of every occurrence of `needle`
+//! in `haystack`, including overlapping ones, in increasing order. Because
+//! UTF-8 is self-synchronizing, every offset is a valid char boundary.
+//! An empty needle never matches.
+
+/// Finds all matches with the Knuth-Morris-Pratt algorithm.
+///
+/// A prefix table lets the scan skip re-examining haystack bytes after a
+/// mismatch, so the search runs in O(n + m) time.
+pub fn find_all_kmp(haystack: &str, needle: &str) -> Vec<usize> {
+    let text = haystack.as_bytes();
+    let pattern = needle.as_bytes();
+    let mut matches = Vec::new();
+    if pattern.is_empty() || pattern.len() > text.len() {
+        return matches;
+    }
+
+    let prefix = prefix_table(pattern);
+    let mut matched = 0;
+    for (i, &byte) in text.iter().enumerate() {
+        while matched > 0 && pattern[matched] != byte {
+            matched = prefix[matched - 1];
+        }
+        if pattern[matched] == byte {
+            matched += 1;
+        }
+        if matched == pattern.len() {
+            matches.push(i + 1 - pattern.len());
+            matched = prefix[matched - 1];
+        }
+    }
+    matches
+}
+
+/// Finds all matches with the Boyer-Moore algorithm.
+///
+/// The pattern is compared right to left, and the bad-character and
+/// good-suffix rules decide how far it can slide after a mismatch. On
+/// natural-language text this usually skips most of the haystack.
+pub fn find_all_bm(haystack: &str, needle: &str) -> Vec<usize> {
+    let text = haystack.as_bytes();
+    let pattern = needle.as_bytes();
+    let mut matches = Vec::new();
+    let m = pattern.len();
+    if m == 0 || m > text.len() {
+        return matches;
+    }
+
+    let last = last_occurrence(pattern);
+    let good_suffix = good_suffix_shifts(pattern);
+
+    let mut shift = 0;
+    while shift <= text.len() - m {
+        let mut j = m;
+        while j > 0 && pattern[j - 1] == text[shift + j - 1] {
+            j -= 1;
+        }
+        if j == 0 {
+            matches.push(shift);
+            shift += good_suffix[0];
+        } else {
+            let bad_char = match last[text[shift + j - 1] as usize] {
+                Some(pos) if pos < j - 1 => j - 1 - pos,
+                Some(_) => 1,
+                None => j,
+            };
+            shift += bad_char.max(good_suffix[j]);
+        }
+    }
+    matches
+}
+
+/// `prefix[i]` is the length of the longest proper prefix of
+/// `pattern[..=i]` that is also a suffix of it.
+fn prefix_table(pattern: &[u8]) -> Vec<usize> {
+    let mut prefix = vec![0; pattern.len()];
+    let mut len = 0;
+    for i in 1..pattern.len() {
+        while len > 0 && pattern[i] != pattern[len] {
+            len = prefix[len - 1];
+        }
+        if pattern[i] == pattern[len] {
+            len += 1;
+        }
+        prefix[i] = len;
+    }
+    prefix
+}
+
+/// The last index at which each byte value appears in the pattern.
+fn last_occurrence(pattern: &[u8]) -> [Option<usize>; 256] {
+    let mut last = [None; 256];
+    for (i, &byte) in pattern.iter().enumerate() {
+        last[byte as usize] = Some(i);
+    }
+    last
+}
+
+/// `shifts[j]` is how far the pattern may slide when `pattern[j..]` matched
+/// but `pattern[j - 1]` did not; `shifts[0]` is the shift after a full match.
+fn good_suffix_shifts(pattern: &[u8]) -> Vec<usize> {
+    let m = pattern.len();
+    let mut shifts = vec![0; m + 1];
+    let mut border = vec![0; m + 1];
+
+    let mut i = m;
+    let mut j = m + 1;
+    border[i] = j;
+    while i > 0 {
+        while j <= m && pattern[i - 1] != pattern[j - 1] {
+            if shifts[j] == 0 {
+                shifts[j] = j - i;
+            }
+            j = border[j];
+        }
+        i -= 1;
+        j -= 1;
+        border[i] = j;
+    }
+
+    j = border[0];
+    for (i, shift) in shifts.iter_mut().enumerate() {
+        if *shift == 0 {
+            *shift = j;
+        }
+        if i == j {
+            j = border[j];
+        }
+    }
+    shifts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn naive(haystack: &str, needle: &str) -> Vec<usize> {
+        if needle.is_empty() {
+            return Vec::new();
+        }
+        (0..haystack.len())
+            .filter(|&i| haystack.as_bytes()[i..].starts_with(needle.as_bytes()))
+            .collect()
+    }
+
+    #[test]
+    fn test_simple_matches() {
+        let text = "the cat sat on the mat with the hat";
+        assert_eq!(find_all_kmp(text, "the"), vec![0, 15, 28]);
+        assert_eq!(find_all_bm(text, "the"), vec![0, 15, 28]);
+        assert_eq!(find_all_kmp(text, "dog"), Vec::<usize>::new());
+        assert_eq!(find_all_bm(text, "dog"), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_overlapping_matches() {
+        assert_eq!(find_all_kmp("aaaaa", "aa"), vec![0, 1, 2, 3]);
+        assert_eq!(find_all_bm("aaaaa", "aa"), vec![0, 1, 2, 3]);
+        assert_eq!(find_all_kmp("abababa", "aba"), vec![0, 2, 4]);
+        assert_eq!(find_all_bm("abababa", "aba"), vec![0, 2, 4]);
+    }
+
+    #[test]
+    fn test_edge_cases() {
+        assert!(find_all_kmp("abc", "").is_empty());
+        assert!(find_all_bm("abc", "").is_empty());
+        assert!(find_all_kmp("ab", "abc").is_empty());
+        assert!(find_all_bm("ab", "abc").is_empty());
+        assert_eq!(find_all_kmp("abc", "abc"), vec![0]);
+        assert_eq!(find_all_bm("abc", "abc"), vec![0]);
+    }
+
+    #[test]
+    fn test_multibyte_text() {
+        let text = "héllo wörld, héllo again";
+        let expected = vec![0, 15];
+        assert_eq!(find_all_kmp(text, "héllo"), expected);
+        assert_eq!(find_all_bm(text, "héllo"), expected);
+        for offset in find_all_bm(text, "ö") {
+            assert!(text.is_char_boundary(offset));
+        }
+    }
+
+    #[test]
+    fn test_agrees_with_naive_search() {
+        let haystacks = [
+            "abracadabra abracadabra",
+            "aabaabaaabaabaaab",
+            "GCATCGCAGAGAGTATACAGTACG",
+            "mississippi",
+        ];
+        let needles = ["a", "abra", "aab", "aabaa", "GCAGAGAG", "issi", "ssi", "b", "zz"];
+        for haystack in haystacks {
+            for needle in needles {
+                let expected = naive(haystack, needle);
+                assert_eq!(find_all_kmp(haystack, needle), expected, "kmp {needle} in {haystack}");
+                assert_eq!(find_all_bm(haystack, needle), expected, "bm {needle} in {haystack}");
+            }
+        }
+    }
+}
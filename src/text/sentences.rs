@@ -0,0 +1,171 @@
+//! Sentence splitting.
+//!
+//! Splitting on `.` miscounts constantly: "Dr. Smith", "e.g. this",
+//! "3.14" and `"Stop." he said` all contain periods that do not end a
+//! sentence. [`split_sentences`] looks at the context around each
+//! terminator before deciding.
+
+/// Abbreviations that are normally followed by more of the same sentence.
+/// Compared without their final period and in lowercase.
+const ABBREVIATIONS: &[&str] = &[
+    "mr", "mrs", "ms", "dr", "prof", "sr", "jr", "st", "vs", "e.g", "i.e", "etc", "inc", "ltd", "co",
+    "no", "fig", "approx", "dept", "est", "jan", "feb", "mar", "apr", "jun", "jul", "aug", "sep",
+    "sept", "oct", "nov", "dec", "mt", "u.s", "a.m", "p.m",
+];
+
+/// Characters that may follow a terminator and still belong to the sentence.
+const CLOSERS: &[char] = &['"', '\'', '”', '’', ')', ']'];
+
+/// Splits text into sentences, trimming the whitespace between them.
+///
+/// A sentence ends at a run of `.`, `!` or `?` (plus any closing quotes or
+/// brackets) followed by whitespace or the end of the text, except when:
+///
+/// - the period ends a known abbreviation like "Dr." or "e.g." or a single
+///   initial like "J.";
+/// - a single period, or a terminator inside quotes, is followed by a
+///   lowercase word, as in `"Why?" she asked.`
+///
+/// Decimal numbers never split because the period is followed by a digit.
+/// Text after the last terminator counts as a final sentence.
+///
+/// ```
+/// use rustler::text::split_sentences;
+///
+/// let text = "Dr. Smith paid $3.50 for it, e.g. a bargain. \"Really?\" she asked. Yes!";
+/// assert_eq!(
+///     split_sentences(text),
+///     vec!["Dr. Smith paid $3.50 for it, e.g. a bargain.", "\"Really?\" she asked.", "Yes!"]
+/// );
+/// ```
+pub fn split_sentences(text: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let mut start = 0;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let (pos, c) = chars[i];
+        if !matches!(c, '.' | '!' | '?') {
+            i += 1;
+            continue;
+        }
+
+        // Consume the whole run of terminators and any closing quotes
+        let run_start = i;
+        while i < chars.len() && matches!(chars[i].1, '.' | '!' | '?') {
+            i += 1;
+        }
+        let single_period = i - run_start == 1 && c == '.';
+        let mut quoted = false;
+        while i < chars.len() && CLOSERS.contains(&chars[i].1) {
+            quoted = true;
+            i += 1;
+        }
+        let end = chars.get(i).map_or(text.len(), |&(p, _)| p);
+
+        let at_break = i == chars.len() || chars[i].1.is_whitespace();
+        if !at_break {
+            continue;
+        }
+        if single_period && !quoted && is_abbreviation(&text[start..pos]) {
+            continue;
+        }
+        if (single_period || quoted) && next_word_is_lowercase(&text[end..]) {
+            continue;
+        }
+
+        push_sentence(&mut sentences, &text[start..end]);
+        start = end;
+    }
+
+    push_sentence(&mut sentences, &text[start..]);
+    sentences
+}
+
+fn push_sentence<'a>(sentences: &mut Vec<&'a str>, candidate: &'a str) {
+    let trimmed = candidate.trim();
+    if trimmed.chars().any(char::is_alphanumeric) {
+        sentences.push(trimmed);
+    }
+}
+
+/// Checks whether the word right before a period is an abbreviation.
+fn is_abbreviation(before_period: &str) -> bool {
+    let word = before_period
+        .rsplit(char::is_whitespace)
+        .next()
+        .unwrap_or("")
+        .trim_start_matches(|c: char| !c.is_alphanumeric());
+    let mut letters = word.chars();
+    let single_initial = matches!((letters.next(), letters.next()), (Some(c), None) if c.is_uppercase());
+    single_initial || ABBREVIATIONS.contains(&word.to_lowercase().as_str())
+}
+
+fn next_word_is_lowercase(after: &str) -> bool {
+    after
+        .trim_start()
+        .chars()
+        .find(|c| !CLOSERS.contains(c) && *c != '“')
+        .is_some_and(char::is_lowercase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simple_sentences() {
+        assert_eq!(split_sentences("One. Two! Three?"), vec!["One.", "Two!", "Three?"]);
+        assert_eq!(split_sentences("No terminator"), vec!["No terminator"]);
+        assert!(split_sentences("").is_empty());
+        assert!(split_sentences("  ...  ").is_empty());
+    }
+
+    #[test]
+    fn test_abbreviations() {
+        assert_eq!(
+            split_sentences("Dr. Jones met Mrs. Lee at 5 p.m. on Main St. yesterday. They talked."),
+            vec!["Dr. Jones met Mrs. Lee at 5 p.m. on Main St. yesterday.", "They talked."]
+        );
+        assert_eq!(
+            split_sentences("Bring fruit, e.g. apples. Or i.e. Pears."),
+            vec!["Bring fruit, e.g. apples.", "Or i.e. Pears."]
+        );
+        assert_eq!(split_sentences("J. R. R. Tolkien wrote it."), vec!["J. R. R. Tolkien wrote it."]);
+    }
+
+    #[test]
+    fn test_decimal_numbers() {
+        assert_eq!(
+            split_sentences("Pi is about 3.14159. E is 2.718."),
+            vec!["Pi is about 3.14159.", "E is 2.718."]
+        );
+    }
+
+    #[test]
+    fn test_quoted_sentences() {
+        assert_eq!(
+            split_sentences("He said \"Stop.\" Then he left."),
+            vec!["He said \"Stop.\"", "Then he left."]
+        );
+        assert_eq!(
+            split_sentences("\"Is it done?\" she asked. \"Yes!\""),
+            vec!["\"Is it done?\" she asked.", "\"Yes!\""]
+        );
+    }
+
+    #[test]
+    fn test_multiple_terminators_and_whitespace() {
+        assert_eq!(split_sentences("Wait... what?! OK."), vec!["Wait...", "what?!", "OK."]);
+        assert_eq!(split_sentences("Line one.\n\nLine two."), vec!["Line one.", "Line two."]);
+    }
+
+    #[test]
+    fn test_unknown_abbreviation_followed_by_lowercase() {
+        assert_eq!(
+            split_sentences("See the approx. value and the Misc. notes."),
+            vec!["See the approx. value and the Misc. notes."]
+        );
+    }
+}
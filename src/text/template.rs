@@ -0,0 +1,176 @@
+//! A tiny `{placeholder}` template engine.
+//!
+//! Placeholders are written as `{name}`. Literal braces are escaped by
+//! doubling them, the same way `format!` does: `{{` and `}}`.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+/// Errors from parsing or rendering a [`Template`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum TemplateError {
+    /// A `{` was never closed. Holds the byte offset of the `{`.
+    UnclosedPlaceholder(usize),
+    /// A single `}` appeared outside a placeholder. Holds its byte offset.
+    UnmatchedBrace(usize),
+    /// A placeholder had no valid name, like `{}` or `{a b}`.
+    InvalidPlaceholder(usize),
+    /// Rendering found a placeholder with no value supplied.
+    UnknownPlaceholder(String),
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TemplateError::UnclosedPlaceholder(pos) => write!(f, "unclosed '{{' at byte {}", pos),
+            TemplateError::UnmatchedBrace(pos) => {
+                write!(f, "unmatched '}}' at byte {} (use '}}}}' for a literal brace)", pos)
+            }
+            TemplateError::InvalidPlaceholder(pos) => write!(f, "invalid placeholder name at byte {}", pos),
+            TemplateError::UnknownPlaceholder(name) => write!(f, "no value for placeholder '{}'", name),
+        }
+    }
+}
+
+impl Error for TemplateError {}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Part {
+    Literal(String),
+    Placeholder(String),
+}
+
+/// A parsed template that can be rendered many times.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+impl Template {
+    /// Parses a template such as `"Hello {name}, you are {age}"`.
+    pub fn parse(source: &str) -> Result<Template, TemplateError> {
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut chars = source.char_indices().peekable();
+
+        while let Some((pos, c)) = chars.next() {
+            match c {
+                '{' if chars.peek().map(|&(_, next)| next) == Some('{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek().map(|&(_, next)| next) == Some('}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some((_, '}')) => break,
+                            Some((_, c)) => name.push(c),
+                            None => return Err(TemplateError::UnclosedPlaceholder(pos)),
+                        }
+                    }
+                    let name = name.trim();
+                    if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                        return Err(TemplateError::InvalidPlaceholder(pos));
+                    }
+                    if !literal.is_empty() {
+                        parts.push(Part::Literal(std::mem::take(&mut literal)));
+                    }
+                    parts.push(Part::Placeholder(name.to_string()));
+                }
+                '}' => return Err(TemplateError::UnmatchedBrace(pos)),
+                _ => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            parts.push(Part::Literal(literal));
+        }
+
+        Ok(Template { parts })
+    }
+
+    /// The placeholder names in the order they appear (duplicates included).
+    pub fn placeholders(&self) -> impl Iterator<Item = &str> {
+        self.parts.iter().filter_map(|part| match part {
+            Part::Placeholder(name) => Some(name.as_str()),
+            Part::Literal(_) => None,
+        })
+    }
+
+    /// Fills in every placeholder from `values`.
+    ///
+    /// Fails with [`TemplateError::UnknownPlaceholder`] if any placeholder has
+    /// no entry; extra entries in `values` are ignored.
+    pub fn render(&self, values: &HashMap<&str, String>) -> Result<String, TemplateError> {
+        let mut out = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Literal(text) => out.push_str(text),
+                Part::Placeholder(name) => match values.get(name.as_str()) {
+                    Some(value) => out.push_str(value),
+                    None => return Err(TemplateError::UnknownPlaceholder(name.clone())),
+                },
+            }
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(pairs: &[(&'static str, &str)]) -> HashMap<&'static str, String> {
+        pairs.iter().map(|&(k, v)| (k, v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_render_placeholders() {
+        let template = Template::parse("Hello {name}, you are {age}").unwrap();
+        let rendered = template.render(&values(&[("name", "Ferris"), ("age", "8")])).unwrap();
+        assert_eq!(rendered, "Hello Ferris, you are 8");
+        assert_eq!(template.placeholders().collect::<Vec<_>>(), vec!["name", "age"]);
+    }
+
+    #[test]
+    fn test_repeated_placeholder_and_whitespace() {
+        let template = Template::parse("{ x } + {x} = {sum}").unwrap();
+        let rendered = template.render(&values(&[("x", "2"), ("sum", "4")])).unwrap();
+        assert_eq!(rendered, "2 + 2 = 4");
+    }
+
+    #[test]
+    fn test_escaped_braces() {
+        let template = Template::parse("{{literal}} and {value}}}").unwrap();
+        assert_eq!(template.render(&values(&[("value", "v")])).unwrap(), "{literal} and v}");
+    }
+
+    #[test]
+    fn test_unknown_placeholder() {
+        let template = Template::parse("Hi {name}").unwrap();
+        assert_eq!(
+            template.render(&HashMap::new()),
+            Err(TemplateError::UnknownPlaceholder("name".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(Template::parse("Hi {name"), Err(TemplateError::UnclosedPlaceholder(3)));
+        assert_eq!(Template::parse("oops }"), Err(TemplateError::UnmatchedBrace(5)));
+        assert_eq!(Template::parse("empty {}"), Err(TemplateError::InvalidPlaceholder(6)));
+        assert_eq!(Template::parse("{two words}"), Err(TemplateError::InvalidPlaceholder(0)));
+    }
+
+    #[test]
+    fn test_error_messages() {
+        let err = Template::parse("x }").unwrap_err();
+        assert_eq!(err.to_string(), "unmatched '}' at byte 2 (use '}}' for a literal brace)");
+        let err = TemplateError::UnknownPlaceholder("age".to_string());
+        assert_eq!(err.to_string(), "no value for placeholder 'age'");
+    }
+}
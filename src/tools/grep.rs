@@ -0,0 +1,325 @@
+//! A grep clone, after the Book's minigrep chapter.
+//!
+//! The Book's version reads a whole file and filters its lines with
+//! `str::contains`. This one reads a line at a time with
+//! [`ChunkedLines`], so files of any size work, finds matches with the
+//! Boyer-Moore search from [`text::search`](crate::text::search), and
+//! searches directory trees with [`Walk`]. With no paths it reads standard
+//! input.
+//!
+//! ```
+//! use rustler::tools::grep::{self, Config};
+//!
+//! let args: Vec<String> = ["-n", "-i", "rust"].iter().map(|s| s.to_string()).collect();
+//! let config = Config::parse(&args).unwrap();
+//! let mut out = Vec::new();
+//! let report = grep::run(&config, &b"Rust:\nsafe, fast, productive.\nTrust me."[..], &mut out).unwrap();
+//! assert_eq!(report.matches, 2);
+//! assert_eq!(String::from_utf8(out).unwrap(), "1:Rust:\n3:Trust me.\n");
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::io::{ChunkedLines, Walk};
+use crate::text::search::find_all_bm;
+
+/// How much of each input is checked for NUL bytes.
+const BINARY_SNIFF_LEN: usize = 8 * 1024;
+
+/// The options and flags, for `--help`.
+pub const USAGE: &str = "\
+Usage: minigrep [options] <pattern> [path...]
+
+Prints the lines that contain <pattern>. Reads standard input when no
+path is given.
+
+Options:
+  -i   ignore case
+  -n   show line numbers
+  -c   only print how many lines match
+  -r   search directories recursively, skipping hidden and binary files
+
+Flags can be combined, as in -in.";
+
+/// What to search for and where, from the command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    pub pattern: String,
+    /// Files, or directories with `recursive`. Empty means standard input.
+    pub paths: Vec<PathBuf>,
+    pub ignore_case: bool,
+    pub line_numbers: bool,
+    pub count: bool,
+    pub recursive: bool,
+}
+
+impl Config {
+    /// Parses the arguments after the program name. Flags may come
+    /// anywhere and be combined; `--` ends them.
+    pub fn parse(args: &[String]) -> Result<Config, UsageError> {
+        let mut config = Config {
+            pattern: String::new(),
+            paths: Vec::new(),
+            ignore_case: false,
+            line_numbers: false,
+            count: false,
+            recursive: false,
+        };
+        let mut operands = Vec::new();
+        let mut flags_done = false;
+        for arg in args {
+            if flags_done || !arg.starts_with('-') || arg == "-" {
+                operands.push(arg.as_str());
+            } else if arg == "--" {
+                flags_done = true;
+            } else {
+                for flag in arg.chars().skip(1) {
+                    match flag {
+                        'i' => config.ignore_case = true,
+                        'n' => config.line_numbers = true,
+                        'c' => config.count = true,
+                        'r' => config.recursive = true,
+                        other => return Err(UsageError(format!("unknown option `-{}`", other))),
+                    }
+                }
+            }
+        }
+        let (pattern, paths) = operands.split_first().ok_or_else(|| UsageError("missing pattern".to_string()))?;
+        if pattern.is_empty() {
+            return Err(UsageError("the pattern is empty".to_string()));
+        }
+        config.pattern = pattern.to_string();
+        config.paths = paths.iter().map(PathBuf::from).collect();
+        Ok(config)
+    }
+
+    /// Whether `line` contains the pattern.
+    pub fn matches(&self, line: &str) -> bool {
+        if self.ignore_case {
+            !find_all_bm(&line.to_lowercase(), &self.pattern.to_lowercase()).is_empty()
+        } else {
+            !find_all_bm(line, &self.pattern).is_empty()
+        }
+    }
+}
+
+/// The lines of `contents` that contain `query`, as in the Book.
+pub fn search<'a>(query: &str, contents: &'a str) -> Vec<&'a str> {
+    contents.lines().filter(|line| !find_all_bm(line, query).is_empty()).collect()
+}
+
+/// [`search`] ignoring case.
+pub fn search_case_insensitive<'a>(query: &str, contents: &'a str) -> Vec<&'a str> {
+    let query = query.to_lowercase();
+    contents
+        .lines()
+        .filter(|line| !find_all_bm(&line.to_lowercase(), &query).is_empty())
+        .collect()
+}
+
+/// What a [`run`] found.
+#[derive(Debug, Default)]
+pub struct Report {
+    /// Matching lines over every input.
+    pub matches: usize,
+    /// Inputs that could not be read, with why. The search carries on
+    /// past them.
+    pub errors: Vec<io::Error>,
+}
+
+/// Searches every input in `config`, or `stdin` when there are none, and
+/// writes the results to `out`. Only errors writing to `out` end the run;
+/// unreadable inputs are collected in the [`Report`].
+///
+/// Lines are prefixed with their file name when more than one file may be
+/// searched, and with their line number for `-n`. With `-c` each input
+/// gets one line with its count instead.
+pub fn run(config: &Config, stdin: impl Read, out: &mut impl Write) -> io::Result<Report> {
+    let mut report = Report::default();
+    if config.paths.is_empty() {
+        search_reader(config, stdin, None, out, &mut report)?;
+        return Ok(report);
+    }
+    let show_names = config.paths.len() > 1 || config.recursive;
+    for path in &config.paths {
+        if !path.is_dir() {
+            search_file(config, path, show_names, out, &mut report)?;
+        } else if !config.recursive {
+            report.errors.push(io::Error::other(format!("{}: is a directory (use -r to search it)", path.display())));
+        } else {
+            for entry in Walk::new(path).skip_hidden().files_only() {
+                match entry {
+                    Ok(entry) => search_file(config, entry.path(), show_names, out, &mut report)?,
+                    Err(e) => report.errors.push(e),
+                }
+            }
+        }
+    }
+    Ok(report)
+}
+
+fn search_file(config: &Config, path: &Path, show_name: bool, out: &mut impl Write, report: &mut Report) -> io::Result<()> {
+    match File::open(path) {
+        Ok(file) => {
+            let name = path.display().to_string();
+            search_reader(config, file, show_name.then_some(name.as_str()), out, report)
+        }
+        Err(e) => {
+            report.errors.push(io::Error::new(e.kind(), format!("{}: {}", path.display(), e)));
+            Ok(())
+        }
+    }
+}
+
+/// Searches one input. Like grep, an input with a NUL byte in its first
+/// block is taken to be binary, and skipped.
+fn search_reader(config: &Config, mut reader: impl Read, name: Option<&str>, out: &mut impl Write, report: &mut Report) -> io::Result<()> {
+    let prefix = name.map(|name| format!("{}:", name)).unwrap_or_default();
+    let mut head = Vec::with_capacity(BINARY_SNIFF_LEN);
+    if let Err(e) = reader.by_ref().take(BINARY_SNIFF_LEN as u64).read_to_end(&mut head) {
+        report.errors.push(io::Error::new(e.kind(), format!("{}{}", prefix, e)));
+        return Ok(());
+    }
+    if head.contains(&0) {
+        return Ok(());
+    }
+    let mut count = 0;
+    // The sniffed block is put back in front of the rest
+    for (index, line) in ChunkedLines::new(head.as_slice().chain(reader)).enumerate() {
+        let line = match line {
+            Ok(line) => line,
+            Err(e) => {
+                report.errors.push(io::Error::new(e.kind(), format!("{}{}", prefix, e)));
+                break;
+            }
+        };
+        if !config.matches(&line) {
+            continue;
+        }
+        count += 1;
+        if config.count {
+            continue;
+        }
+        if config.line_numbers {
+            writeln!(out, "{}{}:{}", prefix, index + 1, line)?;
+        } else {
+            writeln!(out, "{}{}", prefix, line)?;
+        }
+    }
+    if config.count {
+        writeln!(out, "{}{}", prefix, count)?;
+    }
+    report.matches += count;
+    Ok(())
+}
+
+/// A command line that [`Config::parse`] could not make sense of; the
+/// message says why.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UsageError(pub String);
+
+impl fmt::Display for UsageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Error for UsageError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::TempDir;
+    use std::fs;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn grep(config: &[&str], stdin: &str) -> (String, Report) {
+        let config = Config::parse(&args(config)).unwrap();
+        let mut out = Vec::new();
+        let report = run(&config, stdin.as_bytes(), &mut out).unwrap();
+        (String::from_utf8(out).unwrap(), report)
+    }
+
+    #[test]
+    fn test_book_search() {
+        let contents = "Rust:\nsafe, fast, productive.\nPick three.\nDuct tape.\nTrust me.";
+        assert_eq!(search("duct", contents), vec!["safe, fast, productive."]);
+        assert_eq!(search_case_insensitive("rUsT", contents), vec!["Rust:", "Trust me."]);
+    }
+
+    #[test]
+    fn test_parse() {
+        let config = Config::parse(&args(&["-in", "needle", "a.txt", "-c", "b.txt"])).unwrap();
+        assert_eq!(config.pattern, "needle");
+        assert_eq!(config.paths, vec![PathBuf::from("a.txt"), PathBuf::from("b.txt")]);
+        assert!(config.ignore_case && config.line_numbers && config.count && !config.recursive);
+
+        let config = Config::parse(&args(&["--", "-n"])).unwrap();
+        assert_eq!(config.pattern, "-n");
+        assert!(!config.line_numbers);
+
+        assert_eq!(Config::parse(&args(&["-x", "a"])).unwrap_err(), UsageError("unknown option `-x`".to_string()));
+        assert_eq!(Config::parse(&args(&["-i"])).unwrap_err(), UsageError("missing pattern".to_string()));
+        assert!(Config::parse(&args(&[""])).is_err());
+    }
+
+    #[test]
+    fn test_stdin_flags() {
+        let text = "one fish\ntwo fish\nred Fish\nblue";
+        assert_eq!(grep(&["fish"], text).0, "one fish\ntwo fish\n");
+        assert_eq!(grep(&["-i", "fish"], text).0, "one fish\ntwo fish\nred Fish\n");
+        assert_eq!(grep(&["-n", "Fish"], text).0, "3:red Fish\n");
+        let (out, report) = grep(&["-c", "-i", "FISH"], text);
+        assert_eq!(out, "3\n");
+        assert_eq!(report.matches, 3);
+        assert_eq!(grep(&["whale"], text).1.matches, 0);
+    }
+
+    #[test]
+    fn test_files_and_directories() {
+        let dir = TempDir::new("grep").unwrap();
+        fs::create_dir_all(dir.join("src/.git")).unwrap();
+        fs::write(dir.join("notes.txt"), "todo: tests\ndone").unwrap();
+        fs::write(dir.join("src/lib.rs"), "// todo: docs\nfn f() {}\n// TODO: more").unwrap();
+        fs::write(dir.join("src/.git/HEAD"), "todo").unwrap();
+        fs::write(dir.join("src/blob.bin"), b"todo\0todo").unwrap();
+        let root = dir.path().display().to_string();
+        let notes = dir.join("notes.txt").display().to_string();
+
+        let (out, report) = grep(&["todo", &notes], "");
+        assert_eq!(out, "todo: tests\n");
+        assert_eq!(report.matches, 1);
+
+        let (out, report) = grep(&["-r", "-n", "todo", &root], "");
+        let out = out.replace(&root, "ROOT").replace('\\', "/");
+        assert_eq!(out, "ROOT/notes.txt:1:todo: tests\nROOT/src/lib.rs:1:// todo: docs\n");
+        assert!(report.errors.is_empty());
+
+        let (out, _) = grep(&["-rc", "-i", "todo", &root], "");
+        let out = out.replace(&root, "ROOT").replace('\\', "/");
+        assert_eq!(out, "ROOT/notes.txt:1\nROOT/src/lib.rs:2\n");
+    }
+
+    #[test]
+    fn test_unreadable_inputs_are_reported() {
+        let dir = TempDir::new("grep").unwrap();
+        fs::write(dir.join("a.txt"), "hit").unwrap();
+        let a = dir.join("a.txt").display().to_string();
+        let missing = dir.join("missing.txt").display().to_string();
+        let root = dir.path().display().to_string();
+
+        let (out, report) = grep(&["hit", &missing, &root, &a], "");
+        assert!(out.ends_with("a.txt:hit\n"));
+        assert_eq!(report.matches, 1);
+        assert_eq!(report.errors.len(), 2);
+        assert_eq!(report.errors[0].kind(), io::ErrorKind::NotFound);
+        assert!(report.errors[1].to_string().contains("is a directory"));
+    }
+}
@@ -0,0 +1,9 @@
+//! Small versions of everyday command-line tools, built from the rest of
+//! the library.
+
+pub mod grep;
+pub mod portscan;
+pub mod shell;
+pub mod textfiles;
+
+pub use portscan::portscan;
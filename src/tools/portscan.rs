@@ -0,0 +1,243 @@
+//! A TCP port scanner: which ports on a host accept connections.
+//!
+//! Probing a port means trying to connect. A port that accepts is open; a
+//! refusal comes back at once and means closed; silence means a firewall
+//! is dropping the attempt, so each probe gives up after a timeout.
+//! Waiting out timeouts one port at a time would take hours, so
+//! [`Scanner::scan`] hands the ports to a [`ThreadPool`] and the workers
+//! send their findings back over a channel as they finish.
+//!
+//! Only scan hosts you own or have permission to scan.
+//!
+//! ```
+//! use std::net::TcpListener;
+//! use std::time::Duration;
+//! use rustler::tools::portscan::{PortState, Scanner};
+//!
+//! let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+//! let port = listener.local_addr().unwrap().port();
+//!
+//! let scanner = Scanner::new().workers(4).timeout(Duration::from_secs(1));
+//! let probes: Vec<_> = scanner.scan("127.0.0.1", port..=port).unwrap().collect();
+//! assert_eq!(probes.len(), 1);
+//! assert!(matches!(probes[0].state, PortState::Open(_)));
+//! ```
+
+use std::io::{self, ErrorKind};
+use std::net::{IpAddr, SocketAddr, TcpStream, ToSocketAddrs};
+use std::ops::RangeInclusive;
+use std::sync::mpsc::{self, Receiver};
+use std::time::{Duration, Instant};
+
+use crate::concurrent::{CancellationToken, ThreadPool};
+use crate::term::ProgressBar;
+
+/// What a probe found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortState {
+    /// Accepted a connection, after this long.
+    Open(Duration),
+    /// Refused the connection, or the host said it can't be reached.
+    Closed,
+    /// No answer before the timeout, which usually means a firewall.
+    Filtered,
+}
+
+/// One port's result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Probe {
+    pub port: u16,
+    pub state: PortState,
+}
+
+/// How to scan: how many probes at once and how long each may wait.
+#[derive(Debug, Clone)]
+pub struct Scanner {
+    workers: usize,
+    timeout: Duration,
+}
+
+impl Default for Scanner {
+    fn default() -> Self {
+        Scanner::new()
+    }
+}
+
+impl Scanner {
+    /// 64 probes at once, each waiting up to half a second.
+    pub fn new() -> Self {
+        Scanner {
+            workers: 64,
+            timeout: Duration::from_millis(500),
+        }
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `workers` is zero.
+    pub fn workers(mut self, workers: usize) -> Self {
+        assert!(workers > 0, "a scan needs at least one worker");
+        self.workers = workers;
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Starts probing `ports` on `host`, a name or an IP address, and
+    /// returns the results in the order the probes finish. Fails only if
+    /// the host doesn't resolve. Dropping the [`Scan`] early stops it.
+    pub fn scan(&self, host: &str, ports: RangeInclusive<u16>) -> io::Result<Scan> {
+        let ip = resolve(host)?;
+        let token = CancellationToken::new();
+        let pool = ThreadPool::with_token(self.workers, token.clone());
+        let (sender, receiver) = mpsc::channel();
+        let total = ports.clone().count();
+        for port in ports {
+            let (sender, timeout) = (sender.clone(), self.timeout);
+            pool.execute(move || {
+                let state = probe(SocketAddr::new(ip, port), timeout);
+                // The receiver is gone if the scan was dropped; nobody
+                // wants the result then
+                let _ = sender.send(Probe { port, state });
+            });
+        }
+        // Once every job has sent (or been discarded), the channel closes
+        // and iteration ends
+        drop(sender);
+        Ok(Scan {
+            receiver,
+            total,
+            token,
+            _pool: pool,
+        })
+    }
+}
+
+/// Probes in progress, from [`Scanner::scan`]. Iterating waits for each
+/// next result.
+pub struct Scan {
+    receiver: Receiver<Probe>,
+    total: usize,
+    token: CancellationToken,
+    // Dropped after the token is cancelled, so its workers stop taking
+    // jobs and this waits only for the probes already under way
+    _pool: ThreadPool,
+}
+
+impl Scan {
+    /// How many ports the scan covers.
+    pub fn total(&self) -> usize {
+        self.total
+    }
+}
+
+impl Iterator for Scan {
+    type Item = Probe;
+
+    fn next(&mut self) -> Option<Probe> {
+        self.receiver.recv().ok()
+    }
+}
+
+impl Drop for Scan {
+    fn drop(&mut self) {
+        self.token.cancel();
+    }
+}
+
+/// Scans `ports` on `host` with `workers` threads, showing a progress bar,
+/// and returns the open ports in order.
+pub fn portscan(host: &str, ports: RangeInclusive<u16>, workers: usize) -> io::Result<Vec<u16>> {
+    let scan = Scanner::new().workers(workers).scan(host, ports)?;
+    let bar = ProgressBar::new(scan.total() as u64).label(format!("Scanning {}", host));
+    let mut open = Vec::new();
+    for probe in scan {
+        bar.inc(1);
+        if let PortState::Open(_) = probe.state {
+            open.push(probe.port);
+        }
+    }
+    bar.finish();
+    open.sort_unstable();
+    Ok(open)
+}
+
+/// Tries to connect to `addr` once.
+pub fn probe(addr: SocketAddr, timeout: Duration) -> PortState {
+    let start = Instant::now();
+    match TcpStream::connect_timeout(&addr, timeout) {
+        // Dropping the stream closes the connection straight away
+        Ok(_) => PortState::Open(start.elapsed()),
+        Err(e) if matches!(e.kind(), ErrorKind::TimedOut | ErrorKind::WouldBlock) => PortState::Filtered,
+        Err(_) => PortState::Closed,
+    }
+}
+
+/// The host's first address, looked up once rather than once per port.
+fn resolve(host: &str) -> io::Result<IpAddr> {
+    let mut addrs = (host, 0).to_socket_addrs()?;
+    addrs
+        .next()
+        .map(|addr| addr.ip())
+        .ok_or_else(|| io::Error::new(ErrorKind::NotFound, format!("{} has no addresses", host)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::net::TcpListener;
+
+    /// A port with nothing listening on it, found by binding and letting go.
+    fn closed_port() -> u16 {
+        TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port()
+    }
+
+    #[test]
+    fn test_scan_finds_open_and_closed_ports() {
+        let listeners: Vec<TcpListener> = (0..3).map(|_| TcpListener::bind("127.0.0.1:0").unwrap()).collect();
+        let open: Vec<u16> = listeners.iter().map(|l| l.local_addr().unwrap().port()).collect();
+        let closed = closed_port();
+
+        let scanner = Scanner::new().workers(4).timeout(Duration::from_secs(2));
+        for &port in open.iter().chain([&closed]) {
+            // Neighbouring ports may belong to anything, so only the
+            // scanned port itself is checked
+            let low = port.saturating_sub(2);
+            let scan = scanner.scan("127.0.0.1", low..=port).unwrap();
+            assert_eq!(scan.total(), usize::from(port - low) + 1);
+            let found: HashMap<u16, PortState> = scan.map(|probe| (probe.port, probe.state)).collect();
+            assert_eq!(found.len(), usize::from(port - low) + 1);
+            let expect_open = port != closed;
+            assert_eq!(matches!(found[&port], PortState::Open(_)), expect_open, "port {}", port);
+        }
+    }
+
+    #[test]
+    fn test_probe_and_portscan() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let addr = SocketAddr::from(([127, 0, 0, 1], port));
+        assert!(matches!(probe(addr, Duration::from_secs(2)), PortState::Open(_)));
+        assert_eq!(portscan("127.0.0.1", port..=port, 1).unwrap(), vec![port]);
+
+        drop(listener);
+        assert_eq!(probe(addr, Duration::from_secs(2)), PortState::Closed);
+    }
+
+    #[test]
+    fn test_dropping_a_scan_stops_it() {
+        let scan = Scanner::new().workers(2).scan("127.0.0.1", 1..=u16::MAX).unwrap();
+        assert_eq!(scan.total(), 65535);
+        let first: Vec<Probe> = scan.take(3).collect();
+        assert_eq!(first.len(), 3);
+    }
+
+    #[test]
+    fn test_unknown_host() {
+        assert!(Scanner::new().scan("no-such-host.invalid", 1..=2).is_err());
+    }
+}
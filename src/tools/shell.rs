@@ -0,0 +1,248 @@
+//! Parsing command lines the way a shell does, for the minishell example.
+//!
+//! [`parse`] splits a line into the commands of a pipeline, each a program
+//! and its arguments, following a small part of the POSIX shell rules:
+//!
+//! - whitespace separates words and `|` separates commands
+//! - `'single quotes'` keep everything inside as it is
+//! - `"double quotes"` keep spaces and `|` but still expand variables
+//! - a backslash outside single quotes takes the next character literally
+//! - `$NAME` and `${NAME}` expand to a variable's value, `$?` to the last
+//!   exit status, and `~` at the start of a word to `$HOME`
+//!
+//! Variables come from a lookup function rather than the process
+//! environment, so callers decide what `$?` means and tests don't depend
+//! on the environment they run in. Expanded values are never split into
+//! several words.
+//!
+//! ```
+//! use rustler::tools::shell::{parse, Command};
+//!
+//! let vars = |name: &str| (name == "USER").then(|| "ferris".to_string());
+//! let pipeline = parse(r#"echo "hi $USER" | tr a-z 'A-Z'"#, vars).unwrap();
+//! assert_eq!(pipeline, vec![
+//!     Command { program: "echo".into(), args: vec!["hi ferris".into()] },
+//!     Command { program: "tr".into(), args: vec!["a-z".into(), "A-Z".into()] },
+//! ]);
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// One command of a pipeline.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Command {
+    pub program: String,
+    pub args: Vec<String>,
+}
+
+/// Splits `line` into the commands of a pipeline, expanding variables with
+/// `var`. A blank line is an empty pipeline; unset variables expand to
+/// nothing.
+pub fn parse(line: &str, var: impl Fn(&str) -> Option<String>) -> Result<Vec<Command>, ParseError> {
+    let mut commands = Vec::new();
+    let mut words: Vec<String> = Vec::new();
+    let mut word = String::new();
+    // Set by quotes too, so that "" is an empty word rather than none
+    let mut in_word = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            '|' => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+                commands.push(command(std::mem::take(&mut words))?);
+            }
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => return Err(ParseError::UnclosedQuote('\'')),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        // Inside double quotes a backslash only escapes
+                        // the characters that are special there
+                        Some('\\') if matches!(chars.peek(), Some('"' | '\\' | '$')) => word.extend(chars.next()),
+                        Some('$') => word.push_str(&expand(&mut chars, &var)?),
+                        Some(c) => word.push(c),
+                        None => return Err(ParseError::UnclosedQuote('"')),
+                    }
+                }
+            }
+            '\\' => {
+                in_word = true;
+                word.push(chars.next().ok_or(ParseError::TrailingBackslash)?);
+            }
+            '$' => {
+                in_word = true;
+                word.push_str(&expand(&mut chars, &var)?);
+            }
+            '~' if !in_word && chars.peek().is_none_or(|&c| c == '/' || c == '|' || c.is_whitespace()) => {
+                in_word = true;
+                word.push_str(&var("HOME").unwrap_or_else(|| "~".to_string()));
+            }
+            c => {
+                in_word = true;
+                word.push(c);
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    if words.is_empty() && commands.is_empty() {
+        return Ok(commands);
+    }
+    commands.push(command(words)?);
+    Ok(commands)
+}
+
+fn command(mut words: Vec<String>) -> Result<Command, ParseError> {
+    if words.is_empty() {
+        return Err(ParseError::EmptyCommand);
+    }
+    let program = words.remove(0);
+    Ok(Command { program, args: words })
+}
+
+/// The value of the variable after a `$`. A `$` not followed by a name
+/// stays a `$`.
+fn expand(chars: &mut Peekable<Chars>, var: impl Fn(&str) -> Option<String>) -> Result<String, ParseError> {
+    let name = match chars.peek() {
+        Some('?') => {
+            chars.next();
+            "?".to_string()
+        }
+        Some('{') => {
+            chars.next();
+            let mut name = String::new();
+            loop {
+                match chars.next() {
+                    Some('}') => break,
+                    Some(c) => name.push(c),
+                    None => return Err(ParseError::UnclosedBrace),
+                }
+            }
+            name
+        }
+        _ => {
+            let mut name = String::new();
+            while let Some(&c) = chars.peek().filter(|c| c.is_ascii_alphanumeric() || **c == '_') {
+                name.push(c);
+                chars.next();
+            }
+            if name.is_empty() {
+                return Ok("$".to_string());
+            }
+            name
+        }
+    };
+    Ok(var(&name).unwrap_or_default())
+}
+
+/// Why a line could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// A quote with no closing partner; holds the quote character.
+    UnclosedQuote(char),
+    /// `${` with no `}`.
+    UnclosedBrace,
+    /// A backslash at the very end of the line.
+    TrailingBackslash,
+    /// Nothing before or after a `|`.
+    EmptyCommand,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::UnclosedQuote(quote) => write!(f, "unclosed {} quote", quote),
+            ParseError::UnclosedBrace => write!(f, "unclosed ${{"),
+            ParseError::TrailingBackslash => write!(f, "backslash at the end of the line"),
+            ParseError::EmptyCommand => write!(f, "empty command in pipeline"),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(name: &str) -> Option<String> {
+        match name {
+            "HOME" => Some("/home/ferris".to_string()),
+            "GREETING" => Some("hello world".to_string()),
+            "?" => Some("1".to_string()),
+            _ => None,
+        }
+    }
+
+    /// Each command as its words, program first.
+    fn words(line: &str) -> Vec<Vec<String>> {
+        parse(line, vars)
+            .unwrap()
+            .into_iter()
+            .map(|command| std::iter::once(command.program).chain(command.args).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_words_and_pipes() {
+        assert!(words("").is_empty());
+        assert!(words("   \t ").is_empty());
+        assert_eq!(words("  ls   -l src "), vec![vec!["ls", "-l", "src"]]);
+        assert_eq!(words("cat a.txt|sort | uniq -c"), vec![vec!["cat", "a.txt"], vec!["sort"], vec!["uniq", "-c"]]);
+    }
+
+    #[test]
+    fn test_quotes_and_escapes() {
+        assert_eq!(words(r#"echo 'a  b' "c | d" e\ f"#), vec![vec!["echo", "a  b", "c | d", "e f"]]);
+        assert_eq!(words(r#"echo '' "" x"#), vec![vec!["echo", "", "", "x"]]);
+        assert_eq!(words(r"echo it\'s"), vec![vec!["echo", "it's"]]);
+        assert_eq!(words(r#"echo "say \"hi\" \n""#), vec![vec!["echo", "say \"hi\" \\n"]]);
+        assert_eq!(words("echo pre'fix'\"ed\""), vec![vec!["echo", "prefixed"]]);
+    }
+
+    #[test]
+    fn test_expansion() {
+        assert_eq!(words("echo $GREETING"), vec![vec!["echo", "hello world"]]);
+        assert_eq!(words("echo ${GREETING}!"), vec![vec!["echo", "hello world!"]]);
+        assert_eq!(words("echo \"$GREETING, $NOBODY.\""), vec![vec!["echo", "hello world, ."]]);
+        assert_eq!(words("echo '$GREETING' \\$GREETING"), vec![vec!["echo", "$GREETING", "$GREETING"]]);
+        assert_eq!(words("echo $? $ 5$"), vec![vec!["echo", "1", "$", "5$"]]);
+        assert_eq!(words("cd ~ ~/src a~ '~'"), vec![vec!["cd", "/home/ferris", "/home/ferris/src", "a~", "~"]]);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(parse("echo 'oops", vars), Err(ParseError::UnclosedQuote('\'')));
+        assert_eq!(parse("echo \"oops", vars), Err(ParseError::UnclosedQuote('"')));
+        assert_eq!(parse("echo ${HOME", vars), Err(ParseError::UnclosedBrace));
+        assert_eq!(parse("echo \\", vars), Err(ParseError::TrailingBackslash));
+        assert_eq!(parse("| wc", vars), Err(ParseError::EmptyCommand));
+        assert_eq!(parse("ls |", vars), Err(ParseError::EmptyCommand));
+        assert_eq!(parse("ls || wc", vars), Err(ParseError::EmptyCommand));
+        assert_eq!(ParseError::UnclosedBrace.to_string(), "unclosed ${");
+    }
+}
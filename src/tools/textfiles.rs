@@ -0,0 +1,182 @@
+//! `wc`, `head` and `tail` for text files.
+//!
+//! All three hold a block at a time however big the file is. [`count`]
+//! streams the file through [`Chunks`], [`head`] stops reading after the
+//! lines it needs, and [`tail`] seeks to the end and reads backwards a
+//! block at a time until it has seen enough newlines, so the last lines of
+//! a multi-gigabyte log come back as fast as those of a small file.
+//!
+//! ```
+//! use rustler::io::TempDir;
+//! use rustler::tools::textfiles::{self, Counts};
+//!
+//! let dir = TempDir::new("textfiles-doc").unwrap();
+//! let path = dir.join("poem.txt");
+//! std::fs::write(&path, "roses are red\nviolets are blue\n").unwrap();
+//!
+//! assert_eq!(textfiles::count(&path).unwrap(), Counts { lines: 2, words: 6, bytes: 31 });
+//! assert_eq!(textfiles::head(&path, 1).unwrap(), vec!["roses are red"]);
+//! assert_eq!(textfiles::tail(&path, 1).unwrap(), vec!["violets are blue"]);
+//! ```
+
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::ops::AddAssign;
+use std::path::Path;
+
+use crate::io::chunked::DEFAULT_CAPACITY;
+use crate::io::{ChunkedLines, Chunks};
+
+/// How far [`tail`] steps back from the end of the file per read.
+const TAIL_BLOCK: usize = 4096;
+
+/// Line, word and byte counts, as `wc` prints them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Counts {
+    /// Newline characters, so a last line without one isn't counted.
+    pub lines: u64,
+    /// Runs of bytes between ASCII whitespace.
+    pub words: u64,
+    pub bytes: u64,
+}
+
+impl Counts {
+    /// Counts everything `reader` produces.
+    pub fn from_reader(reader: impl Read) -> io::Result<Counts> {
+        let mut counts = Counts::default();
+        // A word can span two chunks, so whether we are in one carries over
+        let mut in_word = false;
+        for chunk in Chunks::new(reader, DEFAULT_CAPACITY) {
+            let chunk = chunk?;
+            counts.bytes += chunk.len() as u64;
+            for &byte in &chunk {
+                if byte == b'\n' {
+                    counts.lines += 1;
+                }
+                if byte.is_ascii_whitespace() {
+                    in_word = false;
+                } else if !in_word {
+                    in_word = true;
+                    counts.words += 1;
+                }
+            }
+        }
+        Ok(counts)
+    }
+}
+
+/// Adds up the counts of several files, for a total.
+impl AddAssign for Counts {
+    fn add_assign(&mut self, other: Counts) {
+        self.lines += other.lines;
+        self.words += other.words;
+        self.bytes += other.bytes;
+    }
+}
+
+/// The lines, words and bytes in the file at `path`.
+pub fn count(path: impl AsRef<Path>) -> io::Result<Counts> {
+    Counts::from_reader(File::open(path)?)
+}
+
+/// The first `n` lines of the file, without their line endings.
+pub fn head(path: impl AsRef<Path>, n: usize) -> io::Result<Vec<String>> {
+    ChunkedLines::new(File::open(path)?).take(n).collect()
+}
+
+/// The last `n` lines of the file, without their line endings.
+pub fn tail(path: impl AsRef<Path>, n: usize) -> io::Result<Vec<String>> {
+    tail_reader(File::open(path)?, n)
+}
+
+/// [`tail`] for anything that can seek, such as an in-memory `Cursor`.
+pub fn tail_reader<R: Read + Seek>(mut reader: R, n: usize) -> io::Result<Vec<String>> {
+    let len = reader.seek(SeekFrom::End(0))?;
+    if n == 0 || len == 0 {
+        return Ok(Vec::new());
+    }
+    // The last n lines start just after the nth newline from the end,
+    // not counting one that ends the file
+    let mut start = 0;
+    let mut newlines = 0;
+    let mut block = vec![0; TAIL_BLOCK];
+    let mut end = len;
+    'scan: while end > 0 {
+        let size = TAIL_BLOCK.min(end as usize);
+        let block_start = end - size as u64;
+        reader.seek(SeekFrom::Start(block_start))?;
+        reader.read_exact(&mut block[..size])?;
+        for (i, &byte) in block[..size].iter().enumerate().rev() {
+            let offset = block_start + i as u64;
+            if byte == b'\n' && offset != len - 1 {
+                newlines += 1;
+                if newlines == n {
+                    start = offset + 1;
+                    break 'scan;
+                }
+            }
+        }
+        end = block_start;
+    }
+    reader.seek(SeekFrom::Start(start))?;
+    ChunkedLines::new(reader).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::TempDir;
+    use std::fs;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_counts() {
+        let counts = |text: &str| Counts::from_reader(text.as_bytes()).unwrap();
+        assert_eq!(counts(""), Counts::default());
+        assert_eq!(counts("one"), Counts { lines: 0, words: 1, bytes: 3 });
+        assert_eq!(counts("  two  words\n\n\tand\r\n"), Counts { lines: 3, words: 3, bytes: 20 });
+        assert_eq!(counts("héllo wörld\n"), Counts { lines: 1, words: 2, bytes: 14 });
+
+        // Words that straddle a chunk boundary count once
+        let long = "word ".repeat(DEFAULT_CAPACITY / 3);
+        assert_eq!(counts(&long).words, (DEFAULT_CAPACITY / 3) as u64);
+
+        let mut total = counts("a b\n");
+        total += counts("c\n");
+        assert_eq!(total, Counts { lines: 2, words: 3, bytes: 6 });
+    }
+
+    #[test]
+    fn test_head_and_tail_of_a_file() {
+        let dir = TempDir::new("textfiles").unwrap();
+        let path = dir.join("lines.txt");
+        fs::write(&path, "1\n2\n3\n4\n5\n").unwrap();
+        assert_eq!(head(&path, 2).unwrap(), vec!["1", "2"]);
+        assert_eq!(head(&path, 10).unwrap().len(), 5);
+        assert_eq!(tail(&path, 2).unwrap(), vec!["4", "5"]);
+        assert_eq!(tail(&path, 10).unwrap().len(), 5);
+        assert!(tail(&path, 0).unwrap().is_empty());
+        assert_eq!(count(&path).unwrap().lines, 5);
+        assert!(head(dir.join("missing"), 1).is_err());
+    }
+
+    #[test]
+    fn test_tail_edge_cases() {
+        let tail = |text: &str, n| tail_reader(Cursor::new(text.as_bytes()), n).unwrap();
+        assert!(tail("", 3).is_empty());
+        assert_eq!(tail("no newline", 1), vec!["no newline"]);
+        assert_eq!(tail("a\nb", 1), vec!["b"]);
+        assert_eq!(tail("a\r\nb\r\n", 2), vec!["a", "b"]);
+        assert_eq!(tail("a\n\n\n", 2), vec!["", ""]);
+    }
+
+    #[test]
+    fn test_tail_across_blocks() {
+        let text: String = (0..5000).map(|i| format!("line {}\n", i)).collect();
+        assert!(text.len() > 3 * TAIL_BLOCK);
+        let last = tail_reader(Cursor::new(text.as_bytes()), 1200).unwrap();
+        assert_eq!(last.len(), 1200);
+        assert_eq!(last[0], "line 3800");
+        assert_eq!(last[1199], "line 4999");
+    }
+}
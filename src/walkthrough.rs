@@ -0,0 +1,298 @@
+//! Step-by-step runs of an example, for `rustler explain`.
+//!
+//! An example marks its sections as named steps with [`step!`]:
+//!
+//! ```
+//! use rustler::step;
+//!
+//! let s2 = step!("Ownership move", || {
+//!     let s1 = String::from("hello");
+//!     s1
+//! });
+//! assert_eq!(s2, "hello");
+//! ```
+//!
+//! Normally a step just runs its closure and returns what it returns, so
+//! the example behaves as if the steps weren't there. When the
+//! `RUSTLER_EXPLAIN` environment variable is set, as `rustler explain`
+//! does, each step first prints its number, its name and the code inside
+//! it, taken from the example's source, and waits for Enter. End of input
+//! runs the remaining steps without stopping.
+//!
+//! [`step!`]: crate::step
+
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+use std::sync::{Mutex, OnceLock, PoisonError};
+
+use crate::catalog;
+use crate::cli::prompt::Prompter;
+use crate::term::color::{DIM, HEADING};
+
+/// The environment variable that turns explaining on.
+pub const EXPLAIN_VAR: &str = "RUSTLER_EXPLAIN";
+
+/// Runs the closure as a named step of a walkthrough; see the
+/// [module documentation](crate::walkthrough).
+#[macro_export]
+macro_rules! step {
+    ($name:expr, $body:expr $(,)?) => {
+        $crate::walkthrough::run_step($name, file!(), line!(), $body)
+    };
+}
+
+/// Whether steps should be explained, read once from [`EXPLAIN_VAR`].
+/// Set to anything but empty or `0` to turn it on.
+pub fn is_explaining() -> bool {
+    static EXPLAINING: OnceLock<bool> = OnceLock::new();
+    *EXPLAINING.get_or_init(|| std::env::var_os(EXPLAIN_VAR).is_some_and(|v| !v.is_empty() && v != "0"))
+}
+
+/// What [`step!`](crate::step) expands to: explains the step if
+/// explaining is on, then runs `body`. `file` and `line` are where the
+/// step is written.
+pub fn run_step<T>(name: &str, file: &str, line: u32, body: impl FnOnce() -> T) -> T {
+    static TOUR: Mutex<Walkthrough> = Mutex::new(Walkthrough::new());
+    if is_explaining() {
+        let mut tour = TOUR.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut prompter = Prompter::new(io::stdin().lock(), io::stdout());
+        // Explaining is a nicety; the step runs even if the terminal is gone
+        let _ = tour.introduce(&mut prompter, name, file, line);
+        // The locks are released before the body runs, so it can read
+        // stdin or contain steps of its own
+    }
+    body()
+}
+
+/// Counts the steps and remembers whether to keep pausing.
+#[derive(Debug)]
+pub struct Walkthrough {
+    steps: usize,
+    pausing: bool,
+}
+
+impl Default for Walkthrough {
+    fn default() -> Self {
+        Walkthrough::new()
+    }
+}
+
+impl Walkthrough {
+    pub const fn new() -> Self {
+        Walkthrough { steps: 0, pausing: true }
+    }
+
+    /// Prints the next step's heading and code, then waits for Enter
+    /// unless an earlier step reached end of input.
+    pub fn introduce<R: BufRead, W: Write>(
+        &mut self,
+        prompter: &mut Prompter<R, W>,
+        name: &str,
+        file: &str,
+        line: u32,
+    ) -> io::Result<()> {
+        self.steps += 1;
+        let code = source_of(file).and_then(|source| Snippet::find(source, line as usize));
+        let mut text = format!("\n{}\n", HEADING.paint(format_args!("Step {}: {}", self.steps, name)));
+        if let Some(code) = code {
+            text.push_str(&code.to_string());
+        }
+        if !self.pausing {
+            return prompter.say(&text);
+        }
+        let prompt = format!("{}{} ", text, DIM.paint("(Enter to run this step)"));
+        if prompter.read_line(&prompt)?.is_none() {
+            self.pausing = false;
+            // The Enter key didn't end the prompt's line
+            prompter.say("\n")?;
+        }
+        Ok(())
+    }
+
+    /// How many steps have been introduced.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+}
+
+/// The source of the example at `file`, such as
+/// `examples/05_ownership_borrowing.rs`, from the copies compiled into the
+/// library.
+fn source_of(file: &str) -> Option<&'static str> {
+    let name = Path::new(file).file_stem()?.to_str()?;
+    catalog::source(name)
+}
+
+/// The code inside a step, with its line numbers in the file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snippet {
+    /// The line number of `lines[0]`, counting from 1.
+    pub first_line: usize,
+    /// The lines, with their common indentation removed.
+    pub lines: Vec<String>,
+}
+
+impl Snippet {
+    /// The body of the macro call that starts on line `line` of `source`:
+    /// everything up to where its brackets close, without the first and
+    /// last line when the body is a block. `None` if the line is past the
+    /// end or the brackets never close.
+    ///
+    /// ```
+    /// use rustler::walkthrough::Snippet;
+    ///
+    /// let source = "fn main() {\n    step!(\"Add\", || {\n        1 + 1\n    });\n}\n";
+    /// let snippet = Snippet::find(source, 2).unwrap();
+    /// assert_eq!(snippet.first_line, 3);
+    /// assert_eq!(snippet.lines, ["1 + 1"]);
+    /// ```
+    pub fn find(source: &str, line: usize) -> Option<Snippet> {
+        let lines: Vec<&str> = source.lines().skip(line.checked_sub(1)?).collect();
+        let mut depth = 0;
+        let mut end = None;
+        for (i, text) in lines.iter().enumerate() {
+            depth += bracket_balance(text);
+            if depth <= 0 {
+                end = Some(i);
+                break;
+            }
+        }
+        let end = end?;
+        if end == 0 {
+            return Some(Snippet {
+                first_line: line,
+                lines: vec![lines[0].trim().to_string()],
+            });
+        }
+        let body = &lines[1..end];
+        let indent = body
+            .iter()
+            .filter(|l| !l.trim().is_empty())
+            .map(|l| l.len() - l.trim_start().len())
+            .min()
+            .unwrap_or(0);
+        let lines = body.iter().map(|l| l.get(indent..).unwrap_or("").trim_end().to_string()).collect();
+        Some(Snippet { first_line: line + 1, lines })
+    }
+}
+
+impl fmt::Display for Snippet {
+    /// Each line after its number, like a listing.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (number, line) in (self.first_line..).zip(&self.lines) {
+            writeln!(f, "{} {}", DIM.paint(format_args!("{:>4} |", number)), line)?;
+        }
+        Ok(())
+    }
+}
+
+/// Opening minus closing brackets on a line of Rust, ignoring those in
+/// string and char literals and after a `//` comment.
+fn bracket_balance(line: &str) -> i32 {
+    let chars: Vec<char> = line.chars().collect();
+    let mut balance = 0;
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '/' if chars.get(i + 1) == Some(&'/') => break,
+            '"' => {
+                i += 1;
+                while i < chars.len() && chars[i] != '"' {
+                    if chars[i] == '\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+            }
+            // A char literal like '(' or '\n'; a lifetime like 'a has no
+            // closing quote and is left alone
+            '\'' if chars.get(i + 2) == Some(&'\'') => i += 2,
+            '\'' if chars.get(i + 1) == Some(&'\\') => {
+                while i + 1 < chars.len() && chars[i + 1] != '\'' {
+                    i += 1;
+                }
+                i += 1;
+            }
+            '(' | '[' | '{' => balance += 1,
+            ')' | ']' | '}' => balance -= 1,
+            _ => {}
+        }
+        i += 1;
+    }
+    balance
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"fn main() {
+    let total = step!("Counting", || {
+        let brackets = "(("; // a comment with ((
+        let open = '(';
+        if brackets.len() > 1 {
+            println!("{}", open);
+        }
+
+        3
+    });
+    step!("One line", || println!("{}", total));
+}
+"#;
+
+    #[test]
+    fn test_snippet_takes_the_block_body() {
+        let snippet = Snippet::find(SOURCE, 2).unwrap();
+        assert_eq!(snippet.first_line, 3);
+        assert_eq!(
+            snippet.lines,
+            [
+                r#"let brackets = "(("; // a comment with (("#,
+                "let open = '(';",
+                "if brackets.len() > 1 {",
+                r#"    println!("{}", open);"#,
+                "}",
+                "",
+                "3",
+            ]
+        );
+        assert!(snippet.to_string().starts_with("   3 | let brackets"));
+    }
+
+    #[test]
+    fn test_snippet_of_one_line_and_bad_lines() {
+        let snippet = Snippet::find(SOURCE, 11).unwrap();
+        assert_eq!(snippet.lines, [r#"step!("One line", || println!("{}", total));"#]);
+        assert_eq!(Snippet::find(SOURCE, 0), None);
+        assert_eq!(Snippet::find(SOURCE, 100), None);
+        assert_eq!(Snippet::find("step!(\"Open\", || {\n", 1), None);
+    }
+
+    #[test]
+    fn test_bracket_balance() {
+        assert_eq!(bracket_balance("step!(\"x\", || {"), 2);
+        assert_eq!(bracket_balance("});"), -2);
+        assert_eq!(bracket_balance(r#"let s = "\"{";"#), 0);
+        assert_eq!(bracket_balance("fn f<'a>(x: &'a str) {"), 1);
+        assert_eq!(bracket_balance("let c = '\\u{7b}';"), 0);
+    }
+
+    #[test]
+    fn test_introduce_pauses_until_end_of_input() {
+        let file = "examples/05_ownership_borrowing.rs";
+        let mut tour = Walkthrough::new();
+        let mut prompter = Prompter::new(&b"\n"[..], Vec::new());
+        tour.introduce(&mut prompter, "First", file, 1).unwrap();
+        tour.introduce(&mut prompter, "Second", file, 1).unwrap();
+        tour.introduce(&mut prompter, "Third", "src/lib.rs", 1).unwrap();
+        assert_eq!(tour.steps(), 3);
+
+        let out = String::from_utf8(prompter.into_output()).unwrap();
+        assert!(out.starts_with("\nStep 1: First\n"));
+        assert!(out.contains("\nStep 2: Second\n"));
+        assert!(out.ends_with("\nStep 3: Third\n"), "{}", out);
+        // Asked before the first two steps, then ran on after end of input
+        assert_eq!(out.matches("(Enter to run this step)").count(), 2);
+    }
+}